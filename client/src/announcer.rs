@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::game::Mk48Game;
+use crate::translation::Mk48Translation;
+use client_util::context::Context;
+use client_util::rate_limiter::RateLimiter;
+use client_util::screen_reader::Verbosity;
+use common::angle::Angle;
+use common::contact::{Contact, ContactTrait};
+use common::entity::EntitySubKind;
+use glam::Vec2;
+
+/// Announces changes in the state of the player's boat to screen readers.
+pub struct Announcer {
+    /// Number of [`Self::HEALTH_THRESHOLDS`] that health was below, as of the last update.
+    health_thresholds: usize,
+    /// Whether an upgrade was available, as of the last update.
+    upgrade_available: bool,
+    /// Several torpedoes are often launched at once, but one announcement is enough.
+    torpedo_rate_limiter: RateLimiter,
+}
+
+impl Default for Announcer {
+    fn default() -> Self {
+        Self {
+            health_thresholds: 0,
+            upgrade_available: false,
+            torpedo_rate_limiter: RateLimiter::new(5.0),
+        }
+    }
+}
+
+impl Announcer {
+    /// Percentages of health, in descending order, to announce when health falls below.
+    const HEALTH_THRESHOLDS: [u8; 3] = [75, 50, 25];
+
+    /// Call every frame, before borrowing the player's contact mutably.
+    pub fn update(&mut self, context: &Context<Mk48Game>, elapsed_seconds: f32) {
+        self.torpedo_rate_limiter.update(elapsed_seconds);
+        let t = context.common_settings.language;
+
+        let boat = if let Some(boat) = context.state.game.player_contact() {
+            boat
+        } else {
+            // Announce again after respawning.
+            self.health_thresholds = 0;
+            self.upgrade_available = false;
+            return;
+        };
+        let entity_type = if let Some(entity_type) = boat.entity_type() {
+            entity_type
+        } else {
+            return;
+        };
+
+        let max_health = entity_type.data().max_health().to_secs();
+        let health = 100.0 * (1.0 - boat.damage().to_secs() / max_health);
+        let health_thresholds = Self::HEALTH_THRESHOLDS
+            .iter()
+            .take_while(|&&threshold| health < threshold as f32)
+            .count();
+        // Repairing re-arms the thresholds, without announcing.
+        if health_thresholds > self.health_thresholds {
+            let percent = Self::HEALTH_THRESHOLDS[health_thresholds - 1];
+            context.announce(t.announce_health(percent), Verbosity::Essential);
+        }
+        self.health_thresholds = health_thresholds;
+
+        let moderator = context
+            .state
+            .core
+            .player()
+            .map_or(false, |player| player.moderator);
+        let upgrade_available = entity_type
+            .upgrade_options(context.state.game.score, false, moderator)
+            .next()
+            .is_some();
+        if upgrade_available && !self.upgrade_available {
+            context.announce(t.announce_upgrade(), Verbosity::Essential);
+        }
+        self.upgrade_available = upgrade_available;
+    }
+
+    /// Call when a previously-unseen contact appears.
+    pub fn new_contact(
+        &mut self,
+        contact: &Contact,
+        player_position: Vec2,
+        context: &Context<Mk48Game>,
+    ) {
+        let torpedo = contact
+            .entity_type()
+            .map_or(false, |t| t.data().sub_kind == EntitySubKind::Torpedo);
+        if !torpedo
+            || context.state.game.entity_id.is_none()
+            || context.state.core.is_friendly(contact.player_id())
+        {
+            return;
+        }
+
+        let direction = Angle::from(contact.transform().position - player_position);
+        let inbound = (contact.transform().direction - direction + Angle::PI).abs() < Angle::PI_2;
+        if inbound && self.torpedo_rate_limiter.ready() {
+            let t = context.common_settings.language;
+            context.announce(t.announce_torpedo(), Verbosity::Essential);
+        }
+    }
+}
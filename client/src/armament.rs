@@ -9,6 +9,7 @@ use client_util::rate_limiter::RateLimiter;
 use common::angle::Angle;
 use common::contact::{Contact, ContactTrait};
 use common::entity::{Armament, EntityData, EntityId, EntityKind, EntitySubKind, EntityType};
+use common::protocol::TurretLock;
 use common_util::range::gen_radius;
 use glam::Vec2;
 use rand::{thread_rng, Rng};
@@ -17,13 +18,15 @@ use std::collections::HashMap;
 
 impl Mk48Game {
     /// Finds the best armament (i.e. the one that will be fired if the mouse is clicked).
-    /// Armaments are scored by a combination of distance and angle to target.
+    /// Armaments are scored by a combination of distance and angle to target, or to the locked
+    /// bearing of their turret, if any.
     pub fn find_best_armament(
         fire_rate_limiter: &FireRateLimiter,
         player_contact: &Contact,
         angle_limit: bool,
         mouse_position: Vec2,
-        armament_selection: Option<EntityType>,
+        armament_selection: Option<u8>,
+        turret_locks: &[Option<TurretLock>],
     ) -> Option<usize> {
         // The f32 represents how good the shot is, lower is better.
         let mut best_armament: Option<(usize, f32)> = None;
@@ -32,8 +35,8 @@ impl Mk48Game {
             for i in 0..player_contact.data().armaments.len() {
                 let armament = &player_contact.data().armaments[i];
 
-                if armament.entity_type != armament_selection {
-                    // Wrong type; cannot fire.
+                if armament.group != armament_selection {
+                    // Wrong group; cannot fire.
                     continue;
                 }
 
@@ -66,7 +69,16 @@ impl Mk48Game {
                         .data()
                         .armament_transform(player_contact.turrets(), i);
 
-                let armament_direction_target = Angle::from(mouse_position - transform.position);
+                // Locked turrets don't follow the mouse, so fire along their bearing.
+                let armament_direction_target = if let Some(lock) = armament
+                    .turret
+                    .and_then(|turret_index| turret_locks.get(turret_index).copied().flatten())
+                {
+                    let boat_direction = player_contact.transform().direction;
+                    lock.direction_target(boat_direction) + boat_direction
+                } else {
+                    Angle::from(mouse_position - transform.position)
+                };
 
                 let mut angle_diff = (armament_direction_target - transform.direction).abs();
                 if armament.vertical
@@ -84,6 +96,8 @@ impl Mk48Game {
 
                 max_angle_diff += match armament_entity_data.sub_kind {
                     EntitySubKind::Shell => Angle::from_degrees(30.0),
+                    // Beams may only be steered a little off their direction.
+                    EntitySubKind::Beam => Angle::from_degrees(30.0),
                     EntitySubKind::Rocket => Angle::from_degrees(45.0),
                     EntitySubKind::RocketTorpedo => Angle::from_degrees(75.0),
                     EntitySubKind::Torpedo if armament_entity_data.sensors.sonar.range > 0.0 => {
@@ -232,49 +246,125 @@ impl FireRateLimiter {
     }
 }
 
+/// An armament group (see [`EntityData::armament_groups`]), as shown in the HUD.
 pub struct Group {
+    /// Index of the group, relative to [`EntityData::armament_groups`].
+    pub index: u8,
+    /// Type of the group's first armament, which represents the group.
     pub entity_type: EntityType,
     pub total: u8,
     pub ready: u8,
 }
 
-pub fn group_armaments(armaments: &[Armament], armament_consumption: &[bool]) -> Vec<Group> {
-    let mut groups = Vec::<Group>::with_capacity(armaments.len().min(5));
-    for (i, armament) in armaments.iter().enumerate() {
-        let ready = armament_consumption.get(i).cloned().unwrap_or(true) as u8;
-        if let Some(group) = groups
-            .iter_mut()
-            .find(|g| g.entity_type == armament.entity_type)
-        {
-            group.total += 1;
-            group.ready += ready;
-        } else {
-            groups.push(Group {
-                entity_type: armament.entity_type,
-                total: 1,
-                ready,
-            });
-        }
-    }
-    groups
+pub fn group_armaments(data: &EntityData, armament_consumption: &[bool]) -> Vec<Group> {
+    data.armament_groups
+        .iter()
+        .enumerate()
+        .filter_map(|(index, group)| {
+            let first = *group.armaments.first()?;
+            Some(Group {
+                index: index as u8,
+                entity_type: data.armaments[first as usize].entity_type,
+                total: group.armaments.len() as u8,
+                ready: group
+                    .armaments
+                    .iter()
+                    .filter(|&&i| {
+                        armament_consumption
+                            .get(i as usize)
+                            .cloned()
+                            .unwrap_or(true)
+                    })
+                    .count() as u8,
+            })
+        })
+        .collect()
+}
+
+/// Returns the type of the first armament of a group, or of any armament if [`None`].
+pub fn group_type(data: &EntityData, group: Option<u8>) -> Option<EntityType> {
+    data.armaments
+        .iter()
+        .find(|a| group.map_or(true, |group| a.group == group))
+        .map(|a| a.entity_type)
 }
 
-pub fn update(entity_type: Option<EntityType>, armament: &mut Option<EntityType>) {
+/// Keeps the selected armament group, and the type of boat it was selected on, valid for the
+/// player's boat. After upgrading, a group with the same name or kind of weapon is preferred.
+pub fn update(entity_type: Option<EntityType>, armament: &mut Option<(EntityType, u8)>) {
     if let Some(entity_type) = entity_type {
-        let armaments = &entity_type.data().armaments;
-        if !armaments.iter().any(|a| Some(a.entity_type) == *armament) {
+        let data = entity_type.data();
+        if armament.map_or(true, |(hull, _)| hull != entity_type) {
             let best = (*armament)
-                .and_then(|selection| {
-                    armaments
+                .and_then(|(hull, group)| {
+                    let old = hull.data();
+                    let name = old.armament_groups.get(group as usize)?.name;
+                    let sub_kind = group_type(old, Some(group))?.data().sub_kind;
+                    data.armament_groups
                         .iter()
-                        .find(|&a| a.entity_type.data().sub_kind == selection.data().sub_kind)
+                        .position(|g| g.name == name)
+                        .or_else(|| {
+                            group_armaments(data, &[])
+                                .iter()
+                                .position(|g| g.entity_type.data().sub_kind == sub_kind)
+                        })
                 })
-                .map(|a| a.entity_type)
-                .or_else(|| armaments.get(0).map(|a| a.entity_type));
-            *armament = best;
+                .or_else(|| (!data.armament_groups.is_empty()).then_some(0));
+            *armament = best.map(|group| (entity_type, group as u8));
         }
     } else {
         // Not alive.
         *armament = None;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::armament::{group_armaments, group_type, update};
+    use common::entity::EntityType;
+
+    #[test]
+    fn groups() {
+        for entity_type in EntityType::iter() {
+            let data = entity_type.data();
+            let groups = group_armaments(data, &[]);
+            assert_eq!(groups.len(), data.armament_groups.len());
+            let total: usize = groups.iter().map(|g| g.total as usize).sum();
+            assert_eq!(total, data.armaments.len(), "{:?}", entity_type);
+        }
+
+        // Consumed armaments aren't ready.
+        let data = EntityType::Yamato.data();
+        let consumption = vec![false; data.armaments.len()];
+        assert!(group_armaments(data, &consumption)
+            .iter()
+            .all(|g| g.ready == 0));
+    }
+
+    #[test]
+    fn update_selection() {
+        let mut armament = None;
+        update(Some(EntityType::Yamato), &mut armament);
+        assert_eq!(armament, Some((EntityType::Yamato, 0)));
+
+        // The same group is kept after upgrading, even if its index changed.
+        let igla = |entity_type: EntityType| {
+            entity_type
+                .data()
+                .armament_groups
+                .iter()
+                .position(|g| g.name == "Igla")
+                .unwrap() as u8
+        };
+        armament = Some((EntityType::Akula, igla(EntityType::Akula)));
+        update(Some(EntityType::Yasen), &mut armament);
+        assert_eq!(armament, Some((EntityType::Yasen, igla(EntityType::Yasen))));
+        assert_eq!(
+            group_type(EntityType::Yasen.data(), armament.map(|(_, g)| g)),
+            Some(EntityType::Igla)
+        );
+
+        update(None, &mut armament);
+        assert_eq!(armament, None);
+    }
+}
@@ -3,6 +3,8 @@
 
 use crate::game::Mk48Game;
 use client_util::audio::AudioPlayer;
+use common::audio_event::{AudioEvent, AudioEventKind};
+use glam::Vec2;
 
 engine_macros::include_audio!("/sprites_audio.mp3" "./sprites_audio.json");
 
@@ -13,6 +15,29 @@ impl Mk48Game {
         1.0 / (1.0 + 0.05 * distance)
     }
 
+    /// Plays a sound made off screen, from its direction relative to the center of the screen,
+    /// fading out towards the edge of its hearing range.
+    pub fn play_audio_event(event: &AudioEvent, center: Vec2, audio_player: &AudioPlayer<Audio>) {
+        let delta = event.position - center;
+        let distance = delta.length();
+        let fade = 1.0 - distance / event.hearing_range().max(1.0);
+        if fade <= 0.0 {
+            return;
+        }
+        // Quieter than on-screen sounds, so as not to distract from them.
+        let volume = 0.5 * event.intensity * fade;
+        let pan = delta.x / distance.max(1.0);
+
+        let audio = match event.kind {
+            AudioEventKind::Explosion if event.intensity >= 0.5 => Audio::ExplosionLong,
+            AudioEventKind::Explosion => Audio::ExplosionShort,
+            AudioEventKind::Gunfire => Audio::Shell,
+            AudioEventKind::SonarPing => Audio::Sonar2,
+            AudioEventKind::TorpedoLaunch => Audio::TorpedoLaunch,
+        };
+        audio_player.play_with_volume_and_pan(audio, volume, pan);
+    }
+
     /// Plays music if it is not already playing, automatically preempting lower priority music.
     pub fn play_music(audio: Audio, audio_player: &AudioPlayer<Audio>) {
         // Highest to lowest.
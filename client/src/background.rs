@@ -329,8 +329,10 @@ impl RenderLayer<&ShadowResult<&Mk48Params>> for Mk48BackgroundLayer {
             if !self.cache_frame {
                 weather = params.weather;
                 shader.uniform("uTime", renderer.time);
-                shader.uniform("uWind", weather.wind);
+                shader.uniform("uWaveOffset", weather.wave_offset);
+                shader.uniform("uWaveDrift", weather.wave_drift);
             }
+            shader.uniform("uWaveScale", weather.wave_scale);
             shader.uniform("uSun", weather.sun);
             shader.uniform("uWaterSun", weather.water_sun());
 
@@ -0,0 +1,141 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use client_util::joystick::Joystick;
+use client_util::keyboard::{KeyboardEvent, KeyboardState};
+use client_util::rate_limiter::RateLimiter;
+use common::protocol::{Cast, Command};
+use glam::Vec2;
+use std::num::NonZeroU8;
+
+/// Camera of a caster (see [`Cast`]), which pans freely and cuts between bookmarked positions.
+pub struct Caster {
+    /// Where the camera is, in world space.
+    pub position: Vec2,
+    /// Positions saved with Shift and a digit, and recalled with the digit alone.
+    bookmarks: [Option<Vec2>; 10],
+    /// Last position sent to the server, if casting.
+    sent: Option<Vec2>,
+    /// Rate limit cast websocket messages.
+    rate_limiter: RateLimiter,
+}
+
+impl Default for Caster {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            bookmarks: [None; 10],
+            sent: None,
+            rate_limiter: RateLimiter::new(0.25),
+        }
+    }
+}
+
+impl Caster {
+    /// Meters the camera must move before the server is told.
+    const RESEND_DISTANCE: f32 = 100.0;
+
+    /// Returns the command to start casting from `position`.
+    pub fn start(&mut self, position: Vec2) -> Command {
+        self.position = position;
+        self.sent = Some(position);
+        Command::Cast(Cast {
+            camera: Some(position),
+        })
+    }
+
+    /// Returns the command to stop casting.
+    pub fn stop(&mut self) -> Command {
+        self.sent = None;
+        Command::Cast(Cast { camera: None })
+    }
+
+    /// Saves (with Shift) or cuts to a bookmark. Returns whether the event was handled.
+    pub fn peek_keyboard(&mut self, event: &KeyboardEvent) -> bool {
+        let digit = if let Some(digit) = event.key.digit_with_ten().filter(|_| event.down) {
+            digit
+        } else {
+            return false;
+        };
+        if event.shift {
+            self.bookmarks[digit.get() as usize - 1] = Some(self.position);
+        } else {
+            self.cut_to(digit);
+        }
+        true
+    }
+
+    /// Moves the camera to a bookmark, if it was saved.
+    pub fn cut_to(&mut self, digit: NonZeroU8) {
+        if let Some(&Some(position)) = self.bookmarks.get(digit.get() as usize - 1) {
+            self.position = position;
+        }
+    }
+
+    /// Digits (1 to 10) of the saved bookmarks.
+    pub fn bookmarks(&self) -> Vec<NonZeroU8> {
+        (1..=10)
+            .filter_map(NonZeroU8::new)
+            .filter(|digit| self.bookmarks[digit.get() as usize - 1].is_some())
+            .collect()
+    }
+
+    /// Pans the camera with WASD or the arrow keys, at a speed proportional to `zoom`. Returns a
+    /// command to send, if the server should be told where the camera is.
+    pub fn update(
+        &mut self,
+        elapsed_seconds: f32,
+        time_seconds: f32,
+        keyboard: &KeyboardState,
+        zoom: f32,
+        world_radius: f32,
+    ) -> Option<Command> {
+        self.rate_limiter.update(elapsed_seconds);
+        if let Some(joystick) = Joystick::try_from_keyboard_state(time_seconds, keyboard) {
+            self.position += joystick.translation_2d * (zoom * elapsed_seconds);
+        }
+        self.position = self.position.clamp_length_max(world_radius);
+
+        let moved = self.sent.map_or(true, |sent| {
+            sent.distance_squared(self.position) > Self::RESEND_DISTANCE.powi(2)
+        });
+        if moved && self.rate_limiter.ready() {
+            Some(self.start(self.position))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::caster::Caster;
+    use client_util::keyboard::{Key, KeyboardEvent};
+    use glam::Vec2;
+    use std::num::NonZeroU8;
+
+    #[test]
+    fn bookmarks() {
+        let event = |key: Key, shift: bool| KeyboardEvent {
+            key,
+            ctrl: false,
+            down: true,
+            shift,
+            time: 0.0,
+        };
+
+        let mut caster = Caster::default();
+        caster.position = Vec2::new(100.0, 200.0);
+        assert!(caster.peek_keyboard(&event(Key::Two, true)));
+        assert_eq!(caster.bookmarks(), vec![NonZeroU8::new(2).unwrap()]);
+
+        caster.position = Vec2::ZERO;
+        // Not saved, so stays put.
+        assert!(caster.peek_keyboard(&event(Key::Three, false)));
+        assert_eq!(caster.position, Vec2::ZERO);
+        assert!(caster.peek_keyboard(&event(Key::Two, false)));
+        assert_eq!(caster.position, Vec2::new(100.0, 200.0));
+
+        assert!(!caster.peek_keyboard(&event(Key::W, false)));
+    }
+}
@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::cosmetic::{Flag, HullPaint};
+use glam::Vec3;
+use renderer::rgb;
+
+/// Multiplied with the color of a boat's hull sprite.
+pub fn hull_tint(paint: HullPaint) -> Vec3 {
+    match paint {
+        HullPaint::Standard => Vec3::ONE,
+        HullPaint::Arctic => rgb(225, 235, 255),
+        HullPaint::Desert => rgb(240, 215, 160),
+        HullPaint::Jungle => rgb(160, 200, 140),
+        HullPaint::Crimson => rgb(235, 130, 130),
+        HullPaint::Gold => rgb(255, 215, 90),
+    }
+}
+
+/// Top and bottom stripes of a flag, or [`None`] if no flag should be drawn.
+pub fn flag_colors(flag: Flag) -> Option<[Vec3; 2]> {
+    Some(match flag {
+        Flag::None => return None,
+        Flag::Naval => [rgb(30, 60, 150), rgb(255, 255, 255)],
+        Flag::Signal => [rgb(255, 210, 0), rgb(220, 30, 30)],
+        Flag::Checkered => [rgb(255, 255, 255), rgb(20, 20, 20)],
+        Flag::Pirate => [rgb(20, 20, 20), rgb(20, 20, 20)],
+        Flag::Gold => [rgb(255, 200, 40), rgb(200, 150, 20)],
+    })
+}
@@ -1,41 +1,58 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::armament::{group_armaments, FireRateLimiter, Group};
+use crate::announcer::Announcer;
+use crate::armament::{group_armaments, group_type, FireRateLimiter, Group};
 use crate::audio::Audio;
 use crate::background::{Mk48BackgroundLayer, Mk48OverlayLayer};
 use crate::camera::Mk48Camera;
+use crate::caster::Caster;
+use crate::cosmetic::{flag_colors, hull_tint};
 use crate::interpolated::Interpolated;
 use crate::interpolated_contact::InterpolatedContact;
+use crate::key_bindings::Action;
 use crate::particle::{Mk48Particle, Mk48ParticleLayer};
 use crate::settings::{Mk48Settings, ShadowSetting};
 use crate::sortable_sprite::SortableSprite;
 use crate::sprite::SpriteLayer;
 use crate::state::Mk48State;
 use crate::trail::TrailLayer;
+use crate::translation::Mk48Translation;
 use crate::ui::{
-    InstructionStatus, UiEvent, UiProps, UiState, UiStatus, UiStatusPlaying, UiStatusRespawning,
+    InstructionStatus, UiEvent, UiProps, UiState, UiStatus, UiStatusCasting, UiStatusPlaying,
+    UiStatusRespawning,
 };
 use crate::weather::Weather;
 use client_util::context::Context;
 use client_util::fps_monitor::FpsMonitor;
 use client_util::game_client::GameClient;
 use client_util::joystick::Joystick;
+use client_util::js_util::{focus, set_rich_presence};
 use client_util::keyboard::{Key, KeyboardEvent};
 use client_util::mouse::{MouseButton, MouseEvent, MouseState};
 use client_util::rate_limiter::RateLimiter;
 use common::altitude::Altitude;
 use common::angle::Angle;
+use common::area_effect::AreaEffectKind;
+use common::bounty::BountyMarker;
 use common::contact::{Contact, ContactTrait};
+use common::drawing::Drawing;
 use common::entity::{EntityData, EntityId, EntityKind, EntitySubKind, EntityType};
-use common::guidance::Guidance;
-use common::protocol::{Command, Control, Fire, Hint, Pay, Spawn, Update, Upgrade};
+use common::ghost::Ghost;
+use common::guidance::{Guidance, Waypoint};
+use common::protocol::{
+    Cast, Command, Control, DamageControl, Fire, Hint, LockTarget, Pay, ReturnToPort, Route,
+    Scuttle, Signal, Spawn, Throttle, Transfer, TurretLock, Update, Upgrade,
+};
+use common::signal::SignalKind;
+use common::terrain_objective::TerrainObjective;
+use common::threat_warning::ThreatKind;
 use common::ticks::Ticks;
 use common::transform::Transform;
 use common::velocity::Velocity;
 use common::world::strict_area_border;
 use common_util::range::{gen_radius, lerp, map_ranges};
-use core_protocol::id::{GameId, TeamId};
+use core_protocol::id::{GameId, PlayerId, TeamId};
 use glam::{Mat2, UVec2, Vec2, Vec3, Vec4Swizzles};
 use rand::{thread_rng, Rng};
 use renderer::{gray_a, rgb_array, rgba, DefaultRender, Layer, RenderChain};
@@ -72,15 +89,35 @@ pub struct Mk48Game {
     pub ui_props_rate_limiter: RateLimiter,
     /// Playing the alarm fast sound too often is annoying.
     pub alarm_fast_rate_limiter: RateLimiter,
+    /// Announces changes to screen readers.
+    announcer: Announcer,
     /// Peek update sound rate limiter (prevent backlog of sounds from nuking ears).
     /// It is reset to 0 every frame, and incremented in every peeked update. When it reaches
     /// a certain threshold, remaining audio and animations are dropped.
     pub peek_update_sound_counter: u8,
+    /// Time when several updates last arrived within one frame, meaning updates are arriving
+    /// faster than they can be processed.
+    pub congested_time: Option<f32>,
     /// If a given index is present and non-zero, should avoid firing weapon (was fired recently,
     /// and is probably consumed).
     pub fire_rate_limiter: FireRateLimiter,
     /// FPS counter
     pub fps_counter: FpsMonitor,
+    /// Where the signal wheel is open, in view space, and where the signal would be placed, in
+    /// world space.
+    signal_target: Option<(Vec2, Vec2)>,
+    /// Last rich presence, for diffing.
+    rich_presence: Option<String>,
+    /// Whether the repair crew was called since the last control.
+    repair_crew: bool,
+    /// Speed preset chosen since the last control, if any.
+    throttle: Option<Throttle>,
+    /// When the scuttle button started being held, if it is held.
+    scuttle_hold: Option<f32>,
+    /// Waypoints the ship is following, as sent to the server, minus those reached.
+    route: Vec<Waypoint>,
+    /// Camera and bookmarks while casting.
+    caster: Caster,
     ui_state: UiState,
 }
 
@@ -117,8 +154,8 @@ impl std::ops::Deref for Mk48Params {
 
 /// Back 75 degrees is reverse angle.
 const REVERSE_ANGLE: f32 = PI * 3.0 / 8.0;
-pub const SURFACE_KEY: Key = Key::R;
-pub const ACTIVE_KEY: Key = Key::Z;
+/// Seconds to flash a warning of a threat to the player's boat.
+const THREAT_WARNING_LIFESPAN: f32 = 3.0;
 
 impl Mk48Game {
     // Don't reverse early on, when the player doesn't have a great idea of their orientation.
@@ -203,9 +240,18 @@ impl GameClient for Mk48Game {
             control_rate_limiter: RateLimiter::new(0.1),
             ui_props_rate_limiter,
             alarm_fast_rate_limiter: RateLimiter::new(10.0),
+            announcer: Announcer::default(),
             peek_update_sound_counter: 0,
+            congested_time: None,
             fire_rate_limiter: FireRateLimiter::new(),
             fps_counter: FpsMonitor::new(1.0),
+            signal_target: None,
+            rich_presence: None,
+            repair_crew: false,
+            throttle: None,
+            scuttle_hold: None,
+            route: Vec::new(),
+            caster: Caster::default(),
             ui_state: UiState::default(),
         })
     }
@@ -216,9 +262,92 @@ impl GameClient for Mk48Game {
         // Only play sounds for 10 peeked updates between frames.
         let play_sounds = self.peek_update_sound_counter < 10;
 
+        // Only show the most recent signal from each teammate.
+        for &signal in &update.signals {
+            let signals = &mut context.state.game.signals;
+            signals.retain(|(s, _)| s.player_id != signal.player_id);
+            signals.push((signal, context.client.time_seconds));
+        }
+
+        context.state.game.beams.extend(
+            update
+                .beams
+                .iter()
+                .map(|&beam| (beam, context.client.time_seconds)),
+        );
+
+        context.state.game.intercepts.extend(
+            update
+                .intercepts
+                .iter()
+                .map(|&intercept| (intercept, context.client.time_seconds)),
+        );
+
+        let now = context.client.time_seconds;
+        let ghosts = &mut context.state.game.ghosts;
+        // Boats that are detected again are no longer ghosts.
+        ghosts.retain(|(ghost, time)| {
+            now - time < Ghost::LIFESPAN && !update.contacts.iter().any(|c| c.id() == ghost.id)
+        });
+        for &ghost in &update.ghosts {
+            let age = update.tick.saturating_sub(ghost.tick).to_secs();
+            ghosts.retain(|(g, _)| g.id != ghost.id);
+            ghosts.push((ghost, now - age));
+        }
+
+        context.state.game.sea_state_time = now;
+
+        let threat_warnings = &mut context.state.game.threat_warnings;
+        threat_warnings.retain(|(_, time)| now - time < THREAT_WARNING_LIFESPAN);
+        threat_warnings.extend(
+            update
+                .threat_warnings
+                .iter()
+                .map(|&warning| (warning, now)),
+        );
+
+        if play_sounds {
+            // Alarms for threats that may not be on screen yet.
+            for kind in [ThreatKind::Missile, ThreatKind::Torpedo] {
+                if update.threat_warnings.iter().any(|w| w.kind == kind) {
+                    context.audio.play(match kind {
+                        ThreatKind::Missile => Audio::AlarmFast,
+                        ThreatKind::Torpedo => Audio::AlarmSlow,
+                    });
+                }
+            }
+
+            for event in &update.audio_events {
+                Self::play_audio_event(event, self.camera.center, &context.audio);
+            }
+        }
+
+        let drawings = &mut context.state.game.drawings;
+        drawings.extend(
+            update
+                .drawings
+                .iter()
+                .map(|&drawing| (drawing, context.client.time_seconds)),
+        );
+
         let updated: HashMap<EntityId, &Contact> =
             update.contacts.iter().map(|c| (c.id(), c)).collect();
 
+        // Compensate for the fact that the data is a little old, more so if the update arrived
+        // late.
+        let game = &mut context.state.game;
+        let lateness = game.update_clock.lateness(
+            update.tick,
+            context.client.time_seconds,
+            game.interpolation.tick_period,
+        );
+        let delay = context
+            .settings
+            .interpolation_delay
+            .filter(|delay| delay.is_finite())
+            .map_or(game.interpolation.delay, |delay| delay.clamp(0.0, 0.5));
+        let extrapolation = delay + lateness;
+
         for (id, &contact) in updated.iter() {
             if let Some(InterpolatedContact { model, .. }) = context.state.game.contacts.get(id) {
                 if Some(*id) == context.state.game.entity_id {
@@ -242,10 +371,9 @@ impl GameClient for Mk48Game {
                 let network_contact = context.state.game.contacts.get_mut(id).unwrap();
                 network_contact.model = contact.clone();
 
-                // Compensate for the fact that the data is a little old (second parameter is rough
-                // estimate of latency)
-                network_contact.model.simulate(0.1);
+                network_contact.model.simulate(extrapolation);
             } else {
+                self.announcer.new_contact(contact, self.camera.center, context);
                 if play_sounds {
                     self.play_new_contact_audio(
                         contact,
@@ -409,46 +537,120 @@ impl GameClient for Mk48Game {
     }
 
     fn peek_keyboard(&mut self, event: &KeyboardEvent, context: &mut Context<Self>) {
+        // Sent once the player's contact is no longer borrowed.
+        let mut lock_target = None;
+        let mut route = None;
         if event.down {
             if let Some(contact) = context.state.game.player_contact() {
                 let entity_type = contact.entity_type().unwrap();
                 let consumptions: Vec<bool> = contact.reloads().iter().map(|b| *b).collect();
-                let groups = group_armaments(&entity_type.data().armaments, &consumptions);
-                match event.key {
-                    SURFACE_KEY => {
+                let groups = group_armaments(entity_type.data(), &consumptions);
+                match context.settings.key_bindings.action(event.key) {
+                    Some(Action::Surface) => {
                         self.set_submerge(!self.ui_state.submerge, &*context);
                     }
-                    ACTIVE_KEY => {
+                    Some(Action::Active) => {
                         self.set_active(!self.ui_state.active, &*context);
                     }
-                    Key::Tab => {
+                    Some(Action::RepairCrew) => {
+                        self.repair_crew = true;
+                    }
+                    Some(Action::Searchlight) => {
+                        self.ui_state.searchlight = !self.ui_state.searchlight;
+                    }
+                    Some(Action::Emcon) => {
+                        self.ui_state.emcon = !self.ui_state.emcon;
+                    }
+                    Some(Action::Periscope) => {
+                        self.ui_state.periscope = !self.ui_state.periscope;
+                    }
+                    Some(Action::Cruise) => {
+                        self.ui_state.cruise = !self.ui_state.cruise;
+                    }
+                    Some(Action::ThrottleReverse) => {
+                        self.throttle = Some(Throttle::Reverse);
+                    }
+                    Some(Action::ThrottleOneThird) => {
+                        self.throttle = Some(Throttle::OneThird);
+                    }
+                    Some(Action::ThrottleTwoThirds) => {
+                        self.throttle = Some(Throttle::TwoThirds);
+                    }
+                    Some(Action::ThrottleFlank) => {
+                        self.throttle = Some(Throttle::Flank);
+                    }
+                    Some(action @ (Action::LockTurrets | Action::LockTurretsRelative)) => {
+                        let view_position = context.mouse.view_position.unwrap_or_default();
+                        self.toggle_turret_locks(
+                            contact,
+                            self.camera.to_world_position(view_position),
+                            action == Action::LockTurretsRelative,
+                        );
+                    }
+                    Some(Action::LockTarget) => {
+                        lock_target = Some(self.designate_target(&*context));
+                    }
+                    Some(Action::Waypoint) => {
+                        let view_position = context.mouse.view_position.unwrap_or_default();
+                        route = self
+                            .add_waypoint(contact, self.camera.to_world_position(view_position));
+                    }
+                    Some(Action::Signal) => {
+                        self.signal_target = if self.signal_target.is_none()
+                            && context.state.core.team_id().is_some()
+                        {
+                            let view_position = context.mouse.view_position.unwrap_or_default();
+                            Some((
+                                view_position,
+                                self.camera.to_world_position(view_position),
+                            ))
+                        } else {
+                            None
+                        };
+                    }
+                    Some(Action::CycleArmament) => {
                         self.ui_state.armament = groups
                             .get(
                                 self.ui_state
                                     .armament
-                                    .and_then(|current| {
-                                        groups.iter().position(|Group { entity_type, .. }| {
-                                            *entity_type == current
-                                        })
+                                    .and_then(|(_, current)| {
+                                        groups
+                                            .iter()
+                                            .position(|Group { index, .. }| *index == current)
                                     })
                                     .map(|idx| (groups.len() + idx + 1) % groups.len())
                                     .unwrap_or(0),
                             )
-                            .map(|Group { entity_type, .. }| *entity_type);
+                            .map(|Group { index, .. }| (entity_type, *index));
                     }
-                    _ => {
+                    Some(action @ (Action::Controls | Action::Upgrade | Action::Team)) => {
+                        if let Some(selector) = action.focus_selector() {
+                            focus(selector);
+                        }
+                    }
+                    // Held, instead of pressed.
+                    Some(Action::Horn | Action::Pay) => {}
+                    None => {
                         if let Some(digit) = event.key.digit_with_ten() {
-                            if let Some(armament) = groups
+                            if let Some(group) = groups
                                 .get((digit.get() - 1) as usize)
-                                .map(|Group { entity_type, .. }| *entity_type)
+                                .map(|Group { index, .. }| *index)
                             {
-                                self.ui_state.armament = Some(armament);
+                                self.ui_state.armament = Some((entity_type, group));
                             }
                         }
                     }
                 }
+            } else if context.state.game.casting {
+                self.caster.peek_keyboard(event);
             }
         }
+        if let Some(lock_target) = lock_target {
+            context.send_to_game(Command::LockTarget(lock_target));
+        }
+        if let Some(route) = route {
+            context.send_to_game(Command::Route(route));
+        }
     }
 
     fn peek_mouse(&mut self, event: &MouseEvent, _context: &mut Context<Self>) {
@@ -463,12 +665,41 @@ impl GameClient for Mk48Game {
         let (renderer, shadow_layer) = frame.draw();
         let layer = &mut shadow_layer.inner;
 
+        if self.peek_update_sound_counter > 2 {
+            self.congested_time = Some(context.client.time_seconds);
+        }
+
         // Allow more sounds to be played in peek.
         self.peek_update_sound_counter = 0;
 
         // The distance from player's boat to the closest visible member of each team, for the purpose of sorting and
         // filtering.
         let mut team_proximity: HashMap<TeamId, f32> = HashMap::new();
+        // Enemy players that may be challenged to duel, and their distance squared.
+        let mut duel_candidates: Vec<(PlayerId, f32)> = Vec::new();
+        // Players that may be hired to escort cargo, and their distance squared.
+        let mut escort_candidates: Vec<(PlayerId, f32)> = Vec::new();
+        // Fleet members close enough to transfer score to, and their distance squared.
+        let mut transfer_candidates: Vec<(PlayerId, f32)> = Vec::new();
+        let boat_position = context
+            .state
+            .game
+            .player_contact()
+            .map(|contact| contact.transform().position);
+
+        // Casters have no boat, so look wherever they pan to.
+        if context.state.game.casting {
+            if let Some(command) = self.caster.update(
+                elapsed_seconds,
+                context.client.time_seconds,
+                &context.keyboard,
+                self.mk48_camera.interpolated_zoom,
+                context.state.game.world_radius,
+            ) {
+                context.send_to_game(command);
+            }
+            self.mk48_camera.saved_camera = Some((self.caster.position, Cast::VIEW));
+        }
 
         // Temporary (will be recalculated after moving ships).
         self.mk48_camera.update(
@@ -487,6 +718,7 @@ impl GameClient for Mk48Game {
         )
         .is_some()
             || context.state.game.death_reason.is_some()
+            || context.state.game.casting
         {
             context.audio.set_muted_by_game(false);
             if !context.audio.is_playing(Audio::Ocean) {
@@ -510,9 +742,32 @@ impl GameClient for Mk48Game {
                 .map(|e| e.data().kind == EntityKind::Boat)
                 .unwrap_or(false)
             {
-                // Update team_proximity.
+                // Update team_proximity, and duel, escort, and transfer candidates.
                 if let Some(player_id) = interp.model.player_id() {
                     if let Some(player) = context.state.core.only_players().get(&player_id) {
+                        if context.state.game.cargo.is_some()
+                            && Some(player_id) != context.state.core.player_id
+                        {
+                            escort_candidates.push((
+                                player_id,
+                                camera.distance_squared(interp.model.transform().position),
+                            ));
+                        }
+                        if !context.state.core.is_friendly(Some(player_id)) {
+                            duel_candidates.push((
+                                player_id,
+                                camera.distance_squared(interp.model.transform().position),
+                            ));
+                        } else if Some(player_id) != context.state.core.player_id {
+                            if let Some(distance_squared) = boat_position.map(|position| {
+                                position.distance_squared(interp.model.transform().position)
+                            }) {
+                                if distance_squared <= Transfer::RANGE.powi(2) {
+                                    transfer_candidates.push((player_id, distance_squared));
+                                }
+                            }
+                        }
+
                         if let Some(team_id) = player.team_id {
                             let mut distance =
                                 camera.distance_squared(interp.model.transform().position);
@@ -545,7 +800,11 @@ impl GameClient for Mk48Game {
         // Set camera before update layers so they don't get last frame's camera.
         // TODO decouple update and render.
         self.camera.update(camera, zoom, renderer.canvas_size());
-        let weather = Weather::new(renderer.time);
+        let weather = Weather::new(
+            renderer.time,
+            &context.state.game.sea_state,
+            context.state.game.sea_state_time,
+        );
 
         let (visual_range, visual_restriction, area) =
             if let Some(c) = context.state.game.player_interpolated_contact() {
@@ -562,10 +821,17 @@ impl GameClient for Mk48Game {
                 let visual_restriction = map_ranges(altitude, 0.0..-1.0, 0.0..0.8, true).powf(0.33);
                 let area = strict_area_border(entity_type);
                 (visual_range, visual_restriction, area)
+            } else if context.state.game.casting {
+                (Cast::VIEW, 0.0, None)
             } else {
                 (500.0, 0.0, None)
             };
 
+        // Darken beyond the shorter visual range at night.
+        let time_of_day = context.state.game.time_of_day;
+        let visual_range = visual_range * time_of_day.visual_multiplier();
+        let visual_restriction = visual_restriction.max(0.6 * (1.0 - time_of_day.daylight()));
+
         // Prepare to sort sprites.
         let mut sortable_sprites = Vec::with_capacity(context.state.game.contacts.len() * 5);
 
@@ -613,6 +879,11 @@ impl GameClient for Mk48Game {
             }
         }
 
+        // Apply quality knobs.
+        let quality = context.common_settings.graphics_quality;
+        layer.sea_level_particles.set_density(quality.wake_density());
+        layer.airborne_particles.set_density(quality.particle_density());
+
         // Update trails.
         layer.trails.set_time(context.client.time_seconds);
 
@@ -656,10 +927,9 @@ impl GameClient for Mk48Game {
                         && settings.dynamic_waves
                         && data.kind == EntityKind::Collectible
                     {
-                        let t = context.client.time_seconds;
-
                         // Moves waves with the wind.
-                        let mut input = (transform.position + weather.wind * t) * 0.1;
+                        let mut input =
+                            (transform.position + weather.wave_offset + weather.wave_drift) * 0.1;
 
                         // Offset waves from regular grid.
                         input.x += input.y * 0.3;
@@ -667,6 +937,9 @@ impl GameClient for Mk48Game {
                         // Don't apply waves when collectibles are moving (aka attracted to boat).
                         let f = map_ranges(transform.velocity.to_mps(), 0.0..4.0, 1.0..0.0, true);
 
+                        // Higher waves bob more.
+                        let f = f * weather.wave_scale;
+
                         // Waves modify rendered position and direction.
                         transform.position += Vec2::new(input.x.sin() * 3.0, input.y.sin()) * (f * 0.9);
                         transform.direction += Angle::from_radians(
@@ -674,13 +947,17 @@ impl GameClient for Mk48Game {
                         );
                     }
 
-                    sortable_sprites.push(SortableSprite::new_entity(
+                    let mut sprite = SortableSprite::new_entity(
                         entity_id,
                         entity_type,
                         transform,
                         altitude,
                         alpha,
-                    ));
+                    );
+                    if contact.is_boat() {
+                        sprite = sprite.with_tint(hull_tint(contact.cosmetics().paint));
+                    }
+                    sortable_sprites.push(sprite);
                 }
 
                 if contact.is_boat()
@@ -819,6 +1096,60 @@ impl GameClient for Mk48Game {
                                     );
                                 }
 
+                                // Radar warnings, as ticks just beyond the outer ring.
+                                let warning_color = rgba(255, 210, 0, 150);
+                                for &bearing in &context.state.game.radar_warnings {
+                                    let dir_mat = Mat2::from_angle(bearing.to_radians());
+                                    let radius = data.radii().end;
+                                    layer.graphics.draw_line(
+                                        contact.transform().position
+                                            + dir_mat * Vec2::new(radius * 1.05, 0.0),
+                                        contact.transform().position
+                                            + dir_mat * Vec2::new(radius * 1.2, 0.0),
+                                        hud_thickness * 2.0,
+                                        warning_color,
+                                    );
+                                }
+
+                                // Threat warnings, as flashing ticks further beyond the outer
+                                // ring.
+                                let now = context.client.time_seconds;
+                                let flash = (now * 8.0).sin() * 0.5 + 0.5;
+                                for (warning, _) in
+                                    context.state.game.threat_warnings.iter().filter(|(_, time)| {
+                                        now - time < THREAT_WARNING_LIFESPAN
+                                    })
+                                {
+                                    let dir_mat = Mat2::from_angle(warning.bearing.to_radians());
+                                    let radius = data.radii().end;
+                                    layer.graphics.draw_line(
+                                        contact.transform().position
+                                            + dir_mat * Vec2::new(radius * 1.05, 0.0),
+                                        contact.transform().position
+                                            + dir_mat * Vec2::new(radius * 1.4, 0.0),
+                                        hud_thickness * 3.0,
+                                        rgba(255, 40, 40, (55.0 + flash * 200.0) as u8),
+                                    );
+                                }
+
+                                // Route, as a line through the remaining waypoints.
+                                let mut leg_start = contact.transform().position;
+                                for waypoint in &self.route {
+                                    layer.graphics.draw_line(
+                                        leg_start,
+                                        waypoint.position,
+                                        hud_thickness,
+                                        hud_color,
+                                    );
+                                    layer.graphics.draw_circle(
+                                        waypoint.position,
+                                        waypoint.arrival_radius,
+                                        hud_thickness,
+                                        hud_color,
+                                    );
+                                    leg_start = waypoint.position;
+                                }
+
                                 // Target bearing line.
                                 if context.settings.circle_hud
                                     || Self::is_holding_control(
@@ -932,7 +1263,7 @@ impl GameClient for Mk48Game {
 
                                 // Turret azimuths.
                                 // Pre-borrow to not borrow all of context (will be fixed eventually).
-                                let ui_armament = self.ui_state.armament;
+                                let ui_armament = self.ui_state.armament.map(|(_, group)| group);
                                 if let Some((i, mouse_pos)) =
                                     context.mouse.view_position.and_then(|view_pos| {
                                         let mouse_pos = self.camera.to_world_position(view_pos);
@@ -942,6 +1273,7 @@ impl GameClient for Mk48Game {
                                             false,
                                             mouse_pos,
                                             ui_armament,
+                                            &self.ui_state.turret_locks,
                                         )
                                         .zip(Some(mouse_pos))
                                     })
@@ -1096,8 +1428,8 @@ impl GameClient for Mk48Game {
                                 }
                             }
 
-                            // Health bar
-                            if contact.damage() > Ticks::ZERO {
+                            // Health bar, always shown to casters.
+                            if contact.damage() > Ticks::ZERO || context.state.game.casting {
                                 let length = 0.12 * zoom;
                                 let health =
                                     1.0 - contact.damage().to_secs() / data.max_health().to_secs();
@@ -1128,6 +1460,17 @@ impl GameClient for Mk48Game {
                                 );
                             }
 
+                            // Shield, which flares up when absorbing a hit.
+                            if let Some(shield) = contact.shield().filter(|s| !s.is_down()) {
+                                let alpha = if shield.impact { 200.0 } else { 60.0 };
+                                layer.graphics.draw_circle(
+                                    contact.transform().position,
+                                    data.radius * 1.1,
+                                    0.004 * zoom,
+                                    rgba(90, 170, 255, (alpha * shield.fraction()) as u8),
+                                );
+                            }
+
                             // Name
                             let text = if let Some(player) = context
                                 .state
@@ -1147,6 +1490,36 @@ impl GameClient for Mk48Game {
                                 format!("{}", contact.player_id().unwrap().0.get())
                             };
 
+                            // Flag
+                            if let Some([top, bottom]) = flag_colors(contact.cosmetics().flag) {
+                                let size = Vec2::new(0.03, 0.01) * zoom;
+                                let center = contact.transform().position
+                                    + Vec2::new(0.0, overlay_vertical_position + 0.075 * zoom);
+                                layer.graphics.draw_rectangle(
+                                    center + Vec2::new(0.0, size.y * 0.5),
+                                    size,
+                                    0.0,
+                                    top.extend(1.0),
+                                );
+                                layer.graphics.draw_rectangle(
+                                    center - Vec2::new(0.0, size.y * 0.5),
+                                    size,
+                                    0.0,
+                                    bottom.extend(1.0),
+                                );
+                            }
+
+                            // Bounty, collected by whoever sinks them.
+                            if contact.bounty() {
+                                layer.text.draw(
+                                    "☠",
+                                    contact.transform().position
+                                        + Vec2::new(0.0, overlay_vertical_position + 0.11 * zoom),
+                                    0.035 * zoom,
+                                    [255, 190, 0, 255],
+                                );
+                            }
+
                             let c = color_bytes;
                             if data.sub_kind != EntitySubKind::Drone && !(context.state.core.player_id.is_some() && contact.player_id() == context.state.core.player_id) { 
                                 layer.text.draw(
@@ -1157,6 +1530,20 @@ impl GameClient for Mk48Game {
                                 [c[0], c[1], c[2], 255],
                             );
                             }
+
+                            // Score, only known to casters.
+                            if let Some(score) = contact
+                                .player_id()
+                                .and_then(|player_id| context.state.game.scores.get(&player_id))
+                            {
+                                layer.text.draw(
+                                    &score.to_string(),
+                                    contact.transform().position
+                                        + Vec2::new(0.0, overlay_vertical_position - 0.025 * zoom),
+                                    0.025 * zoom,
+                                    [c[0], c[1], c[2], 255],
+                                );
+                            }
                         }
                         EntityKind::Weapon | EntityKind::Decoy | EntityKind::Aircraft => {
                             let triangle_position = contact.transform().position
@@ -1180,7 +1567,7 @@ impl GameClient for Mk48Game {
 
                 // Integer amount of particles from fractional per_second
                 let amount = {
-                    let per_second = data.width * 6.0 + speed * 2.0;
+                    let per_second = (data.width * 6.0 + speed * 2.0) * data.fx.wake_scale;
                     let t = context.client.time_seconds;
                     let time_delta = elapsed_seconds;
 
@@ -1372,6 +1759,367 @@ impl GameClient for Mk48Game {
                     0.0,
                     0.0,
                 );
+
+                // Classified, but not yet identified.
+                if let Some(sub_kind) = contact.sub_kind() {
+                    let t = context.common_settings.language;
+                    layer.text.draw(
+                        &format!("{}?", t.entity_kind_name(EntityKind::Boat, sub_kind)),
+                        contact.transform().position + Vec2::new(0.0, 0.03 * zoom),
+                        0.025 * zoom,
+                        [color_bytes[0], color_bytes[1], color_bytes[2], 200],
+                    );
+                }
+            }
+        }
+
+        // Drawings from the fleet leader are only relevant while in their fleet.
+        let now = context.client.time_seconds;
+        let in_team = context.state.core.team_id().is_some();
+        let drawing_lifespan = Drawing::LIFESPAN.as_secs_f32();
+        context
+            .state
+            .game
+            .drawings
+            .retain(|(_, time)| in_team && now - time < drawing_lifespan);
+
+        // Beam weapons, only lasting until the next update.
+        const BEAM_LIFESPAN: f32 = 0.15;
+        let beams = &mut context.state.game.beams;
+        beams.retain(|(_, time)| now - time < BEAM_LIFESPAN);
+        for (beam, _) in beams.iter() {
+            layer.graphics.draw_line(
+                beam.source,
+                beam.target,
+                0.003 * zoom,
+                rgba(255, 60, 60, 220),
+            );
+        }
+
+        // Bursts of point defense fire, fading quickly.
+        const INTERCEPT_LIFESPAN: f32 = 0.5;
+        let intercepts = &mut context.state.game.intercepts;
+        intercepts.retain(|(_, time)| now - time < INTERCEPT_LIFESPAN);
+        for (intercept, time) in intercepts.iter() {
+            let alpha = 1.0 - (now - time) / INTERCEPT_LIFESPAN;
+            layer.graphics.draw_line(
+                intercept.source,
+                intercept.target,
+                0.002 * zoom,
+                rgba(255, 220, 120, (alpha * 200.0) as u8),
+            );
+        }
+
+        // Signals from teammates.
+        let lifespan = SignalKind::LIFESPAN.as_secs_f32();
+        let signals = &mut context.state.game.signals;
+        signals.retain(|(_, time)| now - time < lifespan);
+        if !context.settings.cinematic {
+            for (signal, time) in signals.iter() {
+                let age = now - time;
+                let alpha = map_ranges(age, lifespan * 0.75..lifespan, 1.0..0.0, true);
+                let c = match signal.kind {
+                    SignalKind::Attack => [255, 75, 75],
+                    SignalKind::Help => [255, 210, 0],
+                    SignalKind::Retreat => [100, 175, 255],
+                };
+                let color = rgb_array(c);
+                let pulse = 1.0 + (age * 4.0).sin().abs() * 0.25;
+
+                layer.graphics.draw_circle(
+                    signal.position,
+                    0.02 * zoom * pulse,
+                    0.003 * zoom,
+                    color.extend(alpha),
+                );
+
+                layer.text.draw(
+                    &format!(
+                        "{} {}",
+                        signal.kind.icon(),
+                        context.common_settings.language.signal(signal.kind)
+                    ),
+                    signal.position + Vec2::new(0.0, 0.04 * zoom),
+                    0.03 * zoom,
+                    [c[0], c[1], c[2], (alpha * 255.0) as u8],
+                );
+            }
+        }
+
+        // Bounties on the top players, roughly where they were.
+        if !context.settings.cinematic {
+            let c = [255, 190, 0];
+            for marker in &context.state.game.bounties {
+                layer.graphics.draw_circle(
+                    marker.position,
+                    BountyMarker::ERROR,
+                    0.003 * zoom,
+                    rgba(c[0], c[1], c[2], 150),
+                );
+
+                let alias = context
+                    .state
+                    .core
+                    .player_or_bot(marker.player_id)
+                    .map(|player| player.alias.as_str().to_owned())
+                    .unwrap_or_default();
+                layer.text.draw(
+                    &format!("☠ {} {}", alias, marker.bounty),
+                    marker.position + Vec2::new(0.0, BountyMarker::ERROR + 0.02 * zoom),
+                    0.03 * zoom,
+                    [c[0], c[1], c[2], 255],
+                );
+            }
+        }
+
+        // Route to the port that the player's cargo is bound for.
+        if let Some((cargo, contact)) = context
+            .state
+            .game
+            .cargo
+            .zip(context.state.game.player_contact())
+        {
+            let c = [120, 230, 160];
+            let position = contact.transform().position;
+            let destination = cargo.destination;
+            // Just a pointer, so as not to clutter the screen.
+            let pointer = (destination - position).clamp_length_max(0.15 * zoom);
+            layer.graphics.draw_line(
+                position + pointer * 0.5,
+                position + pointer,
+                0.003 * zoom,
+                rgba(c[0], c[1], c[2], 200),
+            );
+            layer.graphics.draw_circle(
+                destination,
+                0.03 * zoom,
+                0.003 * zoom,
+                rgba(c[0], c[1], c[2], 200),
+            );
+            layer.text.draw(
+                &format!("⚓ {}", cargo.payout(0.0)),
+                destination + Vec2::new(0.0, 0.05 * zoom),
+                0.03 * zoom,
+                [c[0], c[1], c[2], 255],
+            );
+        }
+
+        // Boats that were lost, where they were last seen and where they may be now.
+        if !context.settings.cinematic {
+            for (ghost, time) in &context.state.game.ghosts {
+                let age = now - time;
+                let alpha = map_ranges(age, 0.0..Ghost::LIFESPAN, 0.4..0.0, true);
+                let position = ghost.transform.position;
+                let color = rgba(255, 255, 255, (alpha * 255.0) as u8);
+                if let Some(entity_type) = ghost.entity_type {
+                    layer.sprites.draw(
+                        entity_type,
+                        None,
+                        position,
+                        entity_type.data().dimensions(),
+                        ghost.transform.direction.to_radians(),
+                        alpha,
+                        0.0,
+                        0.0,
+                    );
+                } else {
+                    layer.sprites.draw(
+                        "contact",
+                        None,
+                        position,
+                        Vec2::splat(10.0),
+                        ghost.transform.direction.to_radians(),
+                        alpha,
+                        0.0,
+                        0.0,
+                    );
+                }
+                layer.graphics.draw_line(
+                    position,
+                    ghost.dead_reckoning(age),
+                    0.002 * zoom,
+                    color,
+                );
+                layer.text.draw(
+                    &format!("{:.0}s", age),
+                    position + Vec2::new(0.0, 0.03 * zoom),
+                    0.025 * zoom,
+                    [255, 255, 255, (alpha * 255.0) as u8],
+                );
+            }
+        }
+
+        // Target lock, with where to aim the selected weapon to lead the target.
+        if let Some((lock, contact)) = context
+            .state
+            .game
+            .target_lock
+            .zip(context.state.game.player_contact())
+        {
+            let c = [255, 90, 60];
+            let color = rgba(c[0], c[1], c[2], 200);
+            let origin = contact.transform().position;
+            // The contact, if any, moves smoothly between updates.
+            let position = context
+                .state
+                .game
+                .contacts
+                .get(&lock.id)
+                .map_or(lock.position, |target| target.view.transform().position);
+            layer
+                .graphics
+                .draw_circle(position, 0.03 * zoom, 0.003 * zoom, color);
+
+            let speed = group_type(
+                contact.data(),
+                self.ui_state.armament.map(|(_, group)| group),
+            )
+            .map(|entity_type| entity_type.data().speed.to_mps());
+            if let Some(lead) = speed.and_then(|speed| lock.lead(origin, speed)) {
+                layer.graphics.draw_line(position, lead, 0.002 * zoom, color);
+                layer
+                    .graphics
+                    .draw_circle(lead, 0.01 * zoom, 0.003 * zoom, color);
+            }
+
+            layer.text.draw(
+                &format!(
+                    "⌖ {:03}° {:.0}m",
+                    lock.bearing(origin).to_bearing(),
+                    lock.range(origin)
+                ),
+                position + Vec2::new(0.0, 0.05 * zoom),
+                0.03 * zoom,
+                [c[0], c[1], c[2], 255],
+            );
+        }
+
+        // Range rings of the selected weapon and, towards the locked target, where its seeker can
+        // acquire targets once armed.
+        if let Some((contact, envelope)) = context
+            .state
+            .game
+            .player_contact()
+            .filter(|_| !context.settings.cinematic)
+            .and_then(|contact| {
+                let envelopes = context.state.game.envelopes.get(&contact.entity_type()?)?;
+                let weapon = self
+                    .ui_state
+                    .armament
+                    .and_then(|(_, group)| group_type(contact.data(), Some(group)));
+                Some(contact).zip(envelopes.get(weapon))
+            })
+        {
+            let origin = contact.transform().position;
+            layer.graphics.draw_circle(
+                origin,
+                envelope.range,
+                0.002 * zoom,
+                rgba(255, 255, 255, 60),
+            );
+            if envelope.arming > 0.0 {
+                layer.graphics.draw_circle(
+                    origin,
+                    envelope.arming,
+                    0.002 * zoom,
+                    rgba(255, 255, 255, 30),
+                );
+            }
+
+            if let Some((lock, seeker)) = context.state.game.target_lock.zip(envelope.seeker) {
+                let color = if envelope.in_range(lock.range(origin)) {
+                    rgba(90, 220, 120, 120)
+                } else {
+                    rgba(255, 255, 255, 60)
+                };
+                let bearing = lock.bearing(origin);
+                let apex = origin + bearing.to_vec() * envelope.arming;
+                for side in [-1.0, 1.0] {
+                    let edge = (bearing + seeker * side).to_vec();
+                    layer.graphics.draw_line(
+                        apex,
+                        apex + edge * (envelope.range - envelope.arming),
+                        0.002 * zoom,
+                        color,
+                    );
+                }
+            }
+        }
+
+        // Objective to reshape the terrain.
+        if let Some(objective) = context.state.game.terrain_objective {
+            let c = [210, 170, 110];
+            let color = rgba(c[0], c[1], c[2], 200);
+            match objective {
+                TerrainObjective::Channel { start, end } => {
+                    layer.graphics.draw_line(start, end, 0.003 * zoom, color);
+                    for point in [start, end] {
+                        layer
+                            .graphics
+                            .draw_circle(point, 0.01 * zoom, 0.003 * zoom, color);
+                    }
+                }
+                TerrainObjective::Island { center } => {
+                    layer.graphics.draw_circle(
+                        center,
+                        TerrainObjective::ISLAND_RADIUS,
+                        0.003 * zoom,
+                        color,
+                    );
+                }
+            }
+            layer.text.draw(
+                &format!(
+                    "{} ({})",
+                    context
+                        .common_settings
+                        .language
+                        .terrain_objective(&objective),
+                    objective.reward()
+                ),
+                objective.position() + Vec2::new(0.0, 0.04 * zoom),
+                0.03 * zoom,
+                [c[0], c[1], c[2], 255],
+            );
+        }
+
+        // Oil slicks, fires on them, and star shells.
+        let mut rng = thread_rng();
+        for effect in &context.state.game.area_effects {
+            // Proportional to area (except the flare of a star shell, which is small), fading out
+            // towards the end of the effect's lifespan.
+            let (per_second, spread) = if effect.kind == AreaEffectKind::Light {
+                (40.0, 8.0)
+            } else {
+                ((effect.radius.powi(2) * 0.02).min(2000.0), effect.radius)
+            };
+            let per_second = per_second * effect.remaining.sqrt();
+            let amount = ((elapsed_seconds * per_second + rng.gen::<f32>()) as usize).min(100);
+            for _ in 0..amount {
+                let position = effect.position + gen_radius(&mut rng, spread);
+                match effect.kind {
+                    AreaEffectKind::Oil => layer.sea_level_particles.add(Mk48Particle {
+                        position,
+                        velocity: Vec2::ZERO,
+                        radius: 6.0,
+                        color: 0.0,
+                        smoothness: 1.0,
+                    }),
+                    AreaEffectKind::Fire => layer.airborne_particles.add(Mk48Particle {
+                        position,
+                        velocity: gen_radius(&mut rng, 3.0),
+                        radius: 4.0,
+                        color: -1.0,
+                        smoothness: 1.0,
+                    }),
+                    AreaEffectKind::Light => layer.airborne_particles.add(Mk48Particle {
+                        position,
+                        velocity: gen_radius(&mut rng, 6.0),
+                        radius: 3.0,
+                        color: 1.0,
+                        smoothness: 0.5,
+                    }),
+                }
             }
         }
 
@@ -1392,6 +2140,7 @@ impl GameClient for Mk48Game {
             height,
             shadow_height,
             sprite,
+            tint,
             transform:
                 Transform {
                     direction,
@@ -1430,8 +2179,8 @@ impl GameClient for Mk48Game {
             }
 
             let center = position;
-            layer.sprites.draw(
-                sprite, frame, center, dimensions, angle, alpha, altitude, height,
+            layer.sprites.draw_tinted(
+                sprite, frame, center, dimensions, angle, alpha, altitude, height, tint,
             );
         }
 
@@ -1450,6 +2199,31 @@ impl GameClient for Mk48Game {
 
         // Send command later, when lifetimes allow.
         let mut control: Option<Command> = None;
+        let mut route: Option<Route> = None;
+
+        // Before the player's contact is borrowed mutably.
+        self.announcer.update(context, elapsed_seconds);
+
+        // Scuttling starts once the scuttle button has been held for long enough.
+        if context.state.game.entity_id.is_none() {
+            self.scuttle_hold = None;
+            self.ui_state.turret_locks.clear();
+            self.route.clear();
+        }
+        let scuttle_hold = self
+            .scuttle_hold
+            .map(|since| ((context.client.time_seconds - since) / Scuttle::HOLD).min(1.0));
+        if scuttle_hold == Some(1.0) {
+            self.scuttle_hold = None;
+            context.send_to_game(Command::Scuttle(Scuttle { cancel: false }));
+        }
+
+        // Middle clicking locks onto targets, like the key.
+        if context.mouse.take_click(MouseButton::Middle) && context.state.game.entity_id.is_some()
+        {
+            let lock_target = self.designate_target(&*context);
+            context.send_to_game(Command::LockTarget(lock_target));
+        }
 
         let player_contact = Self::maybe_contact_mut(
             &mut context.state.game.contacts,
@@ -1468,6 +2242,16 @@ impl GameClient for Mk48Game {
                 let player_contact = &player_contact.view;
                 let max_speed = player_contact.data().speed.to_mps();
 
+                // Forget waypoints the server will have reached, in the same order.
+                let position = player_contact.transform().position;
+                while self
+                    .route
+                    .first()
+                    .map_or(false, |waypoint| waypoint.is_reached(position))
+                {
+                    self.route.remove(0);
+                }
+
                 let joystick = Joystick::try_from_keyboard_state(
                     context.client.time_seconds,
                     &context.keyboard,
@@ -1475,6 +2259,10 @@ impl GameClient for Mk48Game {
                 let stop = joystick.as_ref().map(|j| j.stop).unwrap_or(false);
 
                 if let Some(joystick) = joystick {
+                    // Changing speed with the keyboard disengages cruise control.
+                    if joystick.stop || joystick.position.y.abs() > 0.05 {
+                        self.ui_state.cruise = false;
+                    }
                     guidance = Some(Guidance {
                         direction_target: player_contact.transform().direction
                             + Angle::from_radians(0.5 * joystick.position.x),
@@ -1545,6 +2333,25 @@ impl GameClient for Mk48Game {
                     self.holding = false;
                     self.reversing = false;
                 }
+
+                // Steering by hand abandons the route, which would otherwise take precedence.
+                if guidance.is_some() && !self.route.is_empty() {
+                    self.route.clear();
+                    route = Some(Route {
+                        waypoints: Vec::new(),
+                    });
+                }
+
+                // Speed presets, and cruise control, override the speed of steering.
+                if let Some(throttle) = self.throttle {
+                    guidance
+                        .get_or_insert(*player_contact.guidance())
+                        .velocity_target = throttle.velocity_target(player_contact.data().speed);
+                } else if self.ui_state.cruise {
+                    if let Some(guidance) = guidance.as_mut() {
+                        guidance.velocity_target = player_contact.guidance().velocity_target;
+                    }
+                }
             }
 
             if let Some(guidance) = guidance.as_ref() {
@@ -1562,19 +2369,76 @@ impl GameClient for Mk48Game {
                 velocity: player_contact.transform().velocity,
                 altitude: player_contact.altitude(),
                 submerge: self.ui_state.submerge,
+                periscope: self.ui_state.periscope,
                 active: self.ui_state.active,
+                searchlight: self.ui_state.searchlight,
+                emcon: self.ui_state.emcon,
+                cruise: self.ui_state.cruise,
+                turrets_locked: self.ui_state.turret_locks.iter().any(Option::is_some),
+                target_locked: context.state.game.target_lock.is_some(),
                 instruction_status: if player_contact.data().level <= 3 {
                     InstructionStatus {
                         touch: context.mouse.touch_screen,
                         basics: self.first_control,
                         zoom: self.first_zoom,
+                        afk: context.state.game.afk,
+                        collision: context.state.game.collision_warning,
                     }
                 } else {
-                    InstructionStatus::default()
+                    InstructionStatus {
+                        afk: context.state.game.afk,
+                        collision: context.state.game.collision_warning,
+                        ..InstructionStatus::default()
+                    }
                 },
-                armament: self.ui_state.armament,
+                armament: self.ui_state.armament.map(|(_, group)| group),
                 armament_consumption: player_contact.reloads().iter().map(|b| *b).collect(),
                 team_proximity,
+                signal_wheel: self.signal_target.map(|(view_position, _)| view_position),
+                drawings: context.state.core.team_id().map(|_| {
+                    context
+                        .state
+                        .game
+                        .drawings
+                        .iter()
+                        .map(|&(drawing, _)| drawing)
+                        .collect()
+                }),
+                team_captain: context
+                    .state
+                    .core
+                    .player()
+                    .map_or(false, |player| player.team_captain),
+                world_radius: context.state.game.world_radius,
+                port_progress: context.state.game.port_progress,
+                scuttle_hold,
+                scuttle_progress: context.state.game.scuttle_progress,
+                banked_coins: context.state.game.banked_coins,
+                status_effects: context.state.game.status_effects,
+                ammo: context.state.game.ammo.clone(),
+                duels: context.state.game.duels.clone(),
+                duel_candidates: {
+                    duel_candidates.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+                    duel_candidates
+                        .into_iter()
+                        .map(|(player_id, _)| player_id)
+                        .collect()
+                },
+                escorts: context.state.game.escorts.clone(),
+                escort_candidates: {
+                    escort_candidates.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+                    escort_candidates
+                        .into_iter()
+                        .map(|(player_id, _)| player_id)
+                        .collect()
+                },
+                transfer_candidates: {
+                    transfer_candidates.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+                    transfer_candidates
+                        .into_iter()
+                        .map(|(player_id, _)| player_id)
+                        .collect()
+                },
             });
 
             if self.control_rate_limiter.update_ready(elapsed_seconds) {
@@ -1583,14 +2447,19 @@ impl GameClient for Mk48Game {
                 // Get hint before borrow of player_contact().
                 let hint = Some(Hint {
                     aspect: aspect_ratio,
+                    congested: self
+                        .congested_time
+                        .map_or(false, |time| context.client.time_seconds - time < 5.0),
                 });
 
+                let key_bindings = &context.settings.key_bindings;
                 let current_control = Control {
                     guidance: Some(*player_contact.guidance()), // TODO don't send if hasn't changed.
                     submerge: self.ui_state.submerge,
+                    periscope: self.ui_state.periscope,
                     aim_target,
                     active: self.ui_state.active,
-                    pay: context.keyboard.is_down(Key::C).then_some(Pay),
+                    pay: context.keyboard.is_down(key_bindings.key(Action::Pay)).then_some(Pay),
                     fire: if left_click
                         || context
                             .keyboard
@@ -1603,7 +2472,8 @@ impl GameClient for Mk48Game {
                             player_contact,
                             true,
                             aim_target.unwrap_or_default(),
-                            self.ui_state.armament,
+                            self.ui_state.armament.map(|(_, group)| group),
+                            &self.ui_state.turret_locks,
                         )
                         .map(|i| {
                             self.fire_rate_limiter.fired(i as u8);
@@ -1616,12 +2486,22 @@ impl GameClient for Mk48Game {
                         None
                     },
                     hint,
-                    horn: context.keyboard.is_down(Key::H),
+                    horn: context.keyboard.is_down(key_bindings.key(Action::Horn)),
+                    repair_crew: std::mem::take(&mut self.repair_crew),
+                    searchlight: self.ui_state.searchlight,
+                    emcon: self.ui_state.emcon,
+                    throttle: self.throttle.take(),
+                    cruise: self.ui_state.cruise,
+                    collision_assist: context.settings.collision_assist,
+                    turret_locks: self.ui_state.turret_locks.clone(),
                 };
 
                 // Some things are not idempotent.
                 fn is_significant(control: &Control) -> bool {
-                    control.fire.is_some() || control.pay.is_some()
+                    control.fire.is_some()
+                        || control.pay.is_some()
+                        || control.repair_crew
+                        || control.throttle.is_some()
                 }
 
                 if Some(&current_control) != self.last_control.as_ref()
@@ -1641,6 +2521,10 @@ impl GameClient for Mk48Game {
             self.respawn_overridden = false;
 
             status
+        } else if context.state.game.casting {
+            UiStatus::Casting(UiStatusCasting {
+                bookmarks: self.caster.bookmarks(),
+            })
         } else if let Some(death_reason) = context
             .state
             .game
@@ -1654,6 +2538,10 @@ impl GameClient for Mk48Game {
             UiStatus::Spawning
         };
 
+        // Before the control, so that its steering isn't ignored.
+        if let Some(route) = route {
+            context.send_to_game(Command::Route(route));
+        }
         if let Some(control) = control {
             context.send_to_game(control);
         }
@@ -1663,6 +2551,12 @@ impl GameClient for Mk48Game {
 
         if self.ui_props_rate_limiter.update_ready(elapsed_seconds) {
             self.update_ui_props(context, status);
+
+            let rich_presence = context.state.game.rich_presence();
+            if rich_presence != self.rich_presence {
+                set_rich_presence(rich_presence.as_deref());
+                self.rich_presence = rich_presence;
+            }
         }
     }
 
@@ -1672,7 +2566,19 @@ impl GameClient for Mk48Game {
                 self.set_active(active, &*context);
             }
             UiEvent::Armament(armament) => {
-                self.ui_state.armament = armament;
+                self.ui_state.armament = context.state.game.entity_type.zip(armament);
+            }
+            UiEvent::Cast(true) => {
+                let command = self.caster.start(self.camera.center);
+                context.send_to_game(command);
+            }
+            UiEvent::Cast(false) => {
+                let command = self.caster.stop();
+                context.send_to_game(command);
+                self.mk48_camera.saved_camera = None;
+            }
+            UiEvent::CastBookmark(digit) => {
+                self.caster.cut_to(digit);
             }
             UiEvent::GraphicsSettingsChanged => {
                 self.render_chain = Self::create_render_chain(context).unwrap();
@@ -1680,17 +2586,81 @@ impl GameClient for Mk48Game {
             UiEvent::OverrideRespawn => {
                 self.respawn_overridden = true;
             }
+            UiEvent::Periscope(periscope) => {
+                self.ui_state.periscope = periscope;
+            }
+            UiEvent::Draw(drawing) => {
+                context.send_to_game(Command::Draw(drawing));
+            }
+            UiEvent::Duel(duel) => {
+                context.send_to_game(Command::Duel(duel));
+            }
+            UiEvent::Escort(escort) => {
+                context.send_to_game(Command::Escort(escort));
+            }
+            UiEvent::Transfer { player_id, amount } => {
+                context.send_to_game(Command::Transfer(Transfer { player_id, amount }));
+            }
+            UiEvent::Signal(kind) => {
+                if let Some(((_, position), kind)) = self.signal_target.take().zip(kind) {
+                    context.send_to_game(Command::Signal(Signal { kind, position }));
+                }
+            }
             UiEvent::Respawn(entity_type) => {
-                context.send_to_game(Command::Spawn(Spawn { entity_type }));
+                context.send_to_game(Command::Spawn(Spawn {
+                    entity_type,
+                    cosmetics: context.settings.cosmetics(),
+                    region: context.settings.spawn_region,
+                }));
+            }
+            UiEvent::Emcon(emcon) => {
+                self.ui_state.emcon = emcon;
+            }
+            UiEvent::Cruise(cruise) => {
+                self.ui_state.cruise = cruise;
+            }
+            UiEvent::DamageControl(active) => {
+                context.send_to_game(Command::DamageControl(DamageControl { active }));
+            }
+            UiEvent::RepairCrew => {
+                self.repair_crew = true;
+            }
+            UiEvent::Searchlight(searchlight) => {
+                self.ui_state.searchlight = searchlight;
+            }
+            UiEvent::ReturnToPort(start) => {
+                context.send_to_game(Command::ReturnToPort(ReturnToPort { cancel: !start }));
+            }
+            UiEvent::HoldScuttle(hold) => {
+                if !hold {
+                    self.scuttle_hold = None;
+                } else if self.scuttle_hold.is_none() {
+                    self.scuttle_hold = Some(context.client.time_seconds);
+                }
+            }
+            UiEvent::CancelScuttle => {
+                context.send_to_game(Command::Scuttle(Scuttle { cancel: true }));
             }
             UiEvent::Spawn { alias, entity_type } => {
                 context.send_set_alias(alias);
-                context.send_to_game(Command::Spawn(Spawn { entity_type }));
+                context.send_to_game(Command::Spawn(Spawn {
+                    entity_type,
+                    cosmetics: context.settings.cosmetics(),
+                    region: context.settings.spawn_region,
+                }));
             }
             UiEvent::Submerge(submerge) => {
                 self.set_submerge(submerge, &*context);
             }
+            UiEvent::ReleaseTarget => {
+                context.send_to_game(Command::LockTarget(LockTarget { target: None }));
+            }
+            UiEvent::UnlockTurrets => {
+                self.ui_state.turret_locks.clear();
+            }
             UiEvent::Upgrade(entity_type) => {
+                // Turrets of the new boat start unlocked.
+                self.ui_state.turret_locks.clear();
                 context.audio.play(Audio::Upgrade);
                 context.send_to_game(Command::Upgrade(Upgrade { entity_type }));
             }
@@ -1720,4 +2690,94 @@ impl Mk48Game { //sounds edited
         }
         self.ui_state.submerge = submerge;
     }
+
+    /// Returns a command to lock onto the identified enemy boat nearest the mouse cursor, or to
+    /// release the lock if there is none or it is already locked.
+    fn designate_target(&self, context: &Context<Self>) -> LockTarget {
+        /// Meters beyond a boat's radius within which the cursor designates it.
+        const MARGIN: f32 = 50.0;
+
+        let view_position = context.mouse.view_position.unwrap_or_default();
+        let mouse_position = self.camera.to_world_position(view_position);
+        let nearest = context
+            .state
+            .game
+            .contacts
+            .values()
+            .map(|contact| &contact.view)
+            .filter(|contact| {
+                contact.is_boat()
+                    && Some(contact.id()) != context.state.game.entity_id
+                    && !context.state.core.is_friendly(contact.player_id())
+            })
+            .map(|contact| {
+                let distance = contact.transform().position.distance(mouse_position);
+                (contact.id(), distance - contact.data().radius)
+            })
+            .filter(|&(_, distance)| distance < MARGIN)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id);
+
+        let locked = context.state.game.target_lock.map(|lock| lock.id);
+        LockTarget {
+            target: nearest.filter(|&id| Some(id) != locked),
+        }
+    }
+
+    /// Appends a waypoint at `position` to the route, to be reached at the current speed (or two
+    /// thirds of full speed, if stopped). Returns the route to send, unless it is full.
+    fn add_waypoint(&mut self, contact: &Contact, position: Vec2) -> Option<Route> {
+        if self.route.len() >= Waypoint::MAX_ROUTE_LEN {
+            return None;
+        }
+        let data = contact.data();
+        let velocity_target = Some(contact.guidance().velocity_target)
+            .filter(|&velocity| velocity > Velocity::ZERO)
+            .unwrap_or_else(|| Throttle::TwoThirds.velocity_target(data.speed));
+        self.route.push(Waypoint {
+            position,
+            arrival_radius: data.radius.max(Waypoint::MIN_ARRIVAL_RADIUS),
+            velocity_target,
+        });
+        Some(Route {
+            waypoints: self.route.clone(),
+        })
+    }
+
+    /// Locks the turrets of the selected armament (or all armed turrets, if none is selected) at
+    /// the bearing of `aim_target`, or unlocks them if they are all locked already.
+    fn toggle_turret_locks(&mut self, contact: &Contact, aim_target: Vec2, relative: bool) {
+        let data = contact.data();
+        let mut turrets: Vec<usize> = data
+            .armaments
+            .iter()
+            .filter(|armament| {
+                self.ui_state
+                    .armament
+                    .map_or(true, |(_, selected)| armament.group == selected)
+            })
+            .filter_map(|armament| armament.turret)
+            .collect();
+        turrets.dedup();
+
+        let locks = &mut self.ui_state.turret_locks;
+        locks.resize(data.turrets.len(), None);
+        if turrets.iter().all(|&i| locks[i].is_some()) {
+            for i in turrets {
+                locks[i] = None;
+            }
+        } else {
+            for i in turrets {
+                let position = (*contact.transform()
+                    + Transform::from_position(data.turrets[i].position()))
+                .position;
+                let bearing = Angle::from(aim_target - position);
+                locks[i] = Some(if relative {
+                    TurretLock::Relative(bearing - contact.transform().direction)
+                } else {
+                    TurretLock::True(bearing)
+                });
+            }
+        }
+    }
 }
@@ -226,22 +226,13 @@ impl Mk48Game {
                 Mk48Game::volume_at(player_position.distance(contact.transform().position))
                     .min(0.25);
             if entity_type == EntityType::Uap {return};
-            let name = match entity_type.data().kind {
-                EntityKind::Boat | EntityKind::Aircraft => "splash",
-                EntityKind::Weapon => match entity_type.data().sub_kind {
-                    EntitySubKind::Missile
-                    | EntitySubKind::GlideBomb
-                    | EntitySubKind::Sam
-                    | EntitySubKind::Rocket
-                    | EntitySubKind::RocketTorpedo
-                    | EntitySubKind::Shell 
-                    | EntitySubKind::TankShell => "explosion",
-                    _ => "splash",
-                },
-                _ => return,
+            let data = entity_type.data();
+            let name = if let Some(name) = data.fx.explosion {
+                name
+            } else {
+                return;
             };
 
-            let data = entity_type.data();
             if data.kind == EntityKind::Boat {
                 audio_layer.play_with_volume(Audio::ExplosionLong, volume);
             } else {
@@ -288,6 +279,12 @@ impl Mk48Game {
 
         if let Some(entity_type) = contact.entity_type() {
             let data: &EntityData = entity_type.data();
+            let fire_sound = data.fx.fire_sound.and_then(Audio::from_name);
+            let play_fire_sound = |volume: f32| {
+                if let Some(fire_sound) = fire_sound {
+                    audio_layer.play_with_volume(fire_sound, volume);
+                }
+            };
 
             match data.kind {
                 EntityKind::Boat => {
@@ -298,7 +295,7 @@ impl Mk48Game {
                 EntityKind::Weapon => match data.sub_kind {
                     EntitySubKind::Torpedo => {
                         if friendly {
-                            audio_layer.play_with_volume(Audio::TorpedoLaunch, volume.min(0.5));
+                            play_fire_sound(volume.min(0.5));
                             audio_layer.play_with_volume_and_delay(Audio::Splash, volume, 0.1);
                         }
                         if data.sensors.sonar.range > 0.0 {
@@ -317,30 +314,23 @@ impl Mk48Game {
                         {
                             audio_layer.play_with_volume(Audio::AlarmFast, volume.max(0.5));
                         }
-                        audio_layer.play_with_volume(Audio::Rocket, volume);
+                        play_fire_sound(volume);
                     }
                     EntitySubKind::Sam | EntitySubKind::RocketTorpedo => {
-                        audio_layer.play_with_volume(Audio::Rocket, volume);
+                        play_fire_sound(volume);
                     }
                     EntitySubKind::DepthCharge | EntitySubKind::Mine => {
-                        audio_layer.play_with_volume(Audio::Splash, volume);
+                        play_fire_sound(volume);
                         if !friendly && context.state.game.entity_id.is_some() {
                             audio_layer.play_with_volume(Audio::AlarmSlow, volume.max(0.5));
                         }
                     }
-                    EntitySubKind::Shell => {
-                        audio_layer.play_with_volume(
-                            Audio::Shell,
-                            volume * map_ranges(data.length, 0.5..1.5, 0.5..1.0, true),
-                        );
-                    }
-                    EntitySubKind::TankShell => {
-                        audio_layer.play_with_volume(
-                            Audio::Shell,
+                    EntitySubKind::Shell | EntitySubKind::TankShell => {
+                        play_fire_sound(
                             volume * map_ranges(data.length, 0.5..1.5, 0.5..1.0, true),
                         );
                     }
-                    _ => {}
+                    _ => play_fire_sound(volume),
                 },
                 EntityKind::Aircraft => {
                     if !friendly && inbound {
@@ -357,3 +347,36 @@ impl Mk48Game {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::audio::Audio;
+    use common::entity::EntityType;
+    use sprite_sheet::UvSpriteSheet;
+
+    #[test]
+    fn fx_names() {
+        let sheet: UvSpriteSheet =
+            serde_json::from_str(include_str!("./sprites_webgl.json")).unwrap();
+
+        for entity_type in EntityType::iter() {
+            let fx = &entity_type.data().fx;
+            if let Some(fire_sound) = fx.fire_sound {
+                assert!(
+                    Audio::from_name(fire_sound).is_some(),
+                    "{:?} has unknown fire_sound {}",
+                    entity_type,
+                    fire_sound
+                );
+            }
+            if let Some(explosion) = fx.explosion {
+                assert!(
+                    sheet.animations.contains_key(explosion),
+                    "{:?} has unknown explosion {}",
+                    entity_type,
+                    explosion
+                );
+            }
+        }
+    }
+}
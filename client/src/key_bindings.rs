@@ -0,0 +1,304 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use client_util::keyboard::Key;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Something the player may do by pressing a key.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Action {
+    Surface,
+    Active,
+    Signal,
+    RepairCrew,
+    Searchlight,
+    Emcon,
+    Periscope,
+    /// Select the next group of armaments.
+    CycleArmament,
+    Horn,
+    Pay,
+    /// Toggle cruise control, which holds the current speed through turns.
+    Cruise,
+    /// Set speed to a [`Throttle`][common::protocol::Throttle] preset.
+    ThrottleReverse,
+    ThrottleOneThird,
+    ThrottleTwoThirds,
+    ThrottleFlank,
+    /// Lock (or unlock) the selected turrets at the true bearing of the aim point.
+    LockTurrets,
+    /// Lock (or unlock) the selected turrets at the bearing of the aim point, relative to the bow.
+    LockTurretsRelative,
+    /// Lock onto the enemy boat nearest the mouse cursor, or release the lock.
+    LockTarget,
+    /// Add a waypoint at the mouse cursor to the route that the ship follows.
+    Waypoint,
+    /// Focus the ship controls, from which every other control is reachable by tabbing.
+    Controls,
+    /// Focus the first upgrade option.
+    Upgrade,
+    /// Focus team management.
+    Team,
+}
+
+impl Action {
+    pub const ALL: [Self; 22] = [
+        Self::Surface,
+        Self::Active,
+        Self::Signal,
+        Self::RepairCrew,
+        Self::Searchlight,
+        Self::Emcon,
+        Self::Periscope,
+        Self::CycleArmament,
+        Self::Horn,
+        Self::Pay,
+        Self::Cruise,
+        Self::ThrottleReverse,
+        Self::ThrottleOneThird,
+        Self::ThrottleTwoThirds,
+        Self::ThrottleFlank,
+        Self::LockTurrets,
+        Self::LockTurretsRelative,
+        Self::LockTarget,
+        Self::Waypoint,
+        Self::Controls,
+        Self::Upgrade,
+        Self::Team,
+    ];
+
+    pub fn default_key(self) -> Key {
+        match self {
+            Self::Surface => Key::R,
+            Self::Active => Key::Z,
+            Self::Signal => Key::G,
+            Self::RepairCrew => Key::F,
+            Self::Searchlight => Key::L,
+            Self::Emcon => Key::V,
+            Self::Periscope => Key::P,
+            Self::CycleArmament => Key::Tab,
+            Self::Horn => Key::H,
+            Self::Pay => Key::C,
+            Self::Cruise => Key::Y,
+            Self::ThrottleReverse => Key::B,
+            Self::ThrottleOneThird => Key::N,
+            Self::ThrottleTwoThirds => Key::M,
+            Self::ThrottleFlank => Key::J,
+            Self::LockTurrets => Key::O,
+            Self::LockTurretsRelative => Key::I,
+            Self::LockTarget => Key::Home,
+            Self::Waypoint => Key::Q,
+            Self::Controls => Key::K,
+            Self::Upgrade => Key::U,
+            Self::Team => Key::T,
+        }
+    }
+
+    /// Name shown in the settings.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Surface => "Surface/Dive",
+            Self::Active => "Active Sensors",
+            Self::Signal => "Signal Team",
+            Self::RepairCrew => "Repair Crew",
+            Self::Searchlight => "Searchlight",
+            Self::Emcon => "Emission Control",
+            Self::Periscope => "Periscope Depth",
+            Self::CycleArmament => "Next Weapon",
+            Self::Horn => "Horn",
+            Self::Pay => "Pay",
+            Self::Cruise => "Cruise Control",
+            Self::ThrottleReverse => "Reverse",
+            Self::ThrottleOneThird => "1/3 Speed",
+            Self::ThrottleTwoThirds => "2/3 Speed",
+            Self::ThrottleFlank => "Flank Speed",
+            Self::LockTurrets => "Lock Turrets",
+            Self::LockTurretsRelative => "Lock Turrets (Relative)",
+            Self::LockTarget => "Lock Target",
+            Self::Waypoint => "Add Waypoint",
+            Self::Controls => "Focus Ship Controls",
+            Self::Upgrade => "Focus Upgrades",
+            Self::Team => "Focus Team",
+        }
+    }
+
+    /// CSS selector of the control to focus, if the action focuses one.
+    pub fn focus_selector(self) -> Option<&'static str> {
+        Some(match self {
+            Self::Controls => "#controls > [tabindex]",
+            // Skip the level arrows in the heading.
+            Self::Upgrade => "#upgrade > div [tabindex]",
+            Self::Team => "#team button, #team input",
+            _ => return None,
+        })
+    }
+
+    /// Stable name, for storage.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Surface => "surface",
+            Self::Active => "active",
+            Self::Signal => "signal",
+            Self::RepairCrew => "repairCrew",
+            Self::Searchlight => "searchlight",
+            Self::Emcon => "emcon",
+            Self::Periscope => "periscope",
+            Self::CycleArmament => "cycleArmament",
+            Self::Horn => "horn",
+            Self::Pay => "pay",
+            Self::Cruise => "cruise",
+            Self::ThrottleReverse => "throttleReverse",
+            Self::ThrottleOneThird => "throttleOneThird",
+            Self::ThrottleTwoThirds => "throttleTwoThirds",
+            Self::ThrottleFlank => "throttleFlank",
+            Self::LockTurrets => "lockTurrets",
+            Self::LockTurretsRelative => "lockTurretsRelative",
+            Self::LockTarget => "lockTarget",
+            Self::Waypoint => "waypoint",
+            Self::Controls => "controls",
+            Self::Upgrade => "upgrade",
+            Self::Team => "team",
+        }
+    }
+}
+
+/// Which key performs each [`Action`]. Each key performs at most one action.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    keys: [Key; Action::ALL.len()],
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            keys: Action::ALL.map(Action::default_key),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Whether a key is reserved for hardcoded controls (steering, firing, selecting armaments
+    /// by number, and operating the UI), so may not be bound to an action.
+    pub fn is_reserved(key: Key) -> bool {
+        key.digit().is_some()
+            || matches!(
+                key,
+                Key::W
+                    | Key::A
+                    | Key::S
+                    | Key::D
+                    | Key::E
+                    | Key::X
+                    | Key::Up
+                    | Key::Down
+                    | Key::Left
+                    | Key::Right
+                    | Key::Enter
+                    | Key::Escape
+                    | Key::Space
+            )
+    }
+
+    pub fn key(&self, action: Action) -> Key {
+        self.keys[action as usize]
+    }
+
+    /// The action, if any, that a key performs.
+    pub fn action(&self, key: Key) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|&action| self.key(action) == key)
+    }
+
+    /// Binds an action to a key. If the key was bound to another action, that action gets this
+    /// action's old key instead. Reserved keys are ignored.
+    pub fn set(&mut self, action: Action, key: Key) {
+        if Self::is_reserved(key) {
+            return;
+        }
+        let old = self.key(action);
+        if let Some(other) = self.action(key) {
+            self.keys[other as usize] = old;
+        }
+        self.keys[action as usize] = key;
+    }
+}
+
+/// e.g. `surface=R,active=Z`
+impl Display for KeyBindings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, action) in Action::ALL.into_iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}={}", action.name(), self.key(action))?;
+        }
+        Ok(())
+    }
+}
+
+/// Actions that are missing, or unknown, keep their default keys.
+impl FromStr for KeyBindings {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut key_bindings = Self::default();
+        for binding in s.split(',').filter(|b| !b.is_empty()) {
+            let (name, key) = binding.split_once('=').ok_or(())?;
+            let key = Key::from_str(key).map_err(|_| ())?;
+            if let Some(action) = Action::ALL.into_iter().find(|a| a.name() == name) {
+                key_bindings.set(action, key);
+            }
+        }
+        Ok(key_bindings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::key_bindings::{Action, KeyBindings};
+    use client_util::keyboard::Key;
+    use std::str::FromStr;
+
+    #[test]
+    fn from_str() {
+        let mut key_bindings = KeyBindings::default();
+        key_bindings.set(Action::Horn, Key::B);
+        assert_eq!(
+            KeyBindings::from_str(&key_bindings.to_string()),
+            Ok(key_bindings)
+        );
+        assert_eq!(KeyBindings::from_str(""), Ok(KeyBindings::default()));
+        assert_eq!(
+            KeyBindings::from_str("unknown=Q").map(|k| k.key(Action::Horn)),
+            Ok(Key::H)
+        );
+        assert!(KeyBindings::from_str("horn=NotAKey").is_err());
+    }
+
+    #[test]
+    fn set() {
+        let mut key_bindings = KeyBindings::default();
+        key_bindings.set(Action::Surface, Key::Z);
+        assert_eq!(key_bindings.key(Action::Surface), Key::Z);
+        assert_eq!(key_bindings.key(Action::Active), Key::R);
+        assert_eq!(key_bindings.action(Key::Z), Some(Action::Surface));
+        assert_eq!(key_bindings.action(Key::PageUp), None);
+    }
+
+    #[test]
+    fn reserved() {
+        for action in Action::ALL {
+            assert!(!KeyBindings::is_reserved(action.default_key()));
+        }
+        let mut key_bindings = KeyBindings::default();
+        key_bindings.set(Action::Horn, Key::Space);
+        assert_eq!(key_bindings.key(Action::Horn), Key::H);
+        assert_eq!(key_bindings.action(Key::Space), None);
+        assert_eq!(
+            KeyBindings::from_str("horn=W").map(|k| k.key(Action::Horn)),
+            Ok(Key::H)
+        );
+    }
+}
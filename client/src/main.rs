@@ -15,13 +15,17 @@ use crate::game::Mk48Game;
 use crate::ui::{Mk48Route, Mk48Ui};
 
 mod animation;
+mod announcer;
 mod armament;
 mod audio;
 mod background;
 mod camera;
+mod caster;
+mod cosmetic;
 mod game;
 mod interpolated;
 mod interpolated_contact;
+mod key_bindings;
 mod licenses;
 mod particle;
 mod settings;
@@ -32,6 +36,7 @@ mod tessellation;
 mod trail;
 mod translation;
 mod ui;
+mod update_clock;
 mod weather;
 
 fn main() {
@@ -1,9 +1,12 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::key_bindings::KeyBindings;
 use client_util::browser_storage::BrowserStorages;
 use client_util::js_util::is_mobile;
 use client_util::setting::Settings;
+use common::cosmetic::{Cosmetics, Flag, HullPaint};
+use common::spawn_region::SpawnRegion;
 use std::str::FromStr;
 
 /// Settings can be set via Javascript (see util/settings.js and page/Settings.svelte).
@@ -13,9 +16,34 @@ pub struct Mk48Settings {
     #[setting(no_store)]
     pub cinematic: bool,
     pub circle_hud: bool,
+    /// Slow down and steer automatically to avoid running aground.
+    pub collision_assist: bool,
     pub dynamic_waves: bool,
+    /// Flag to fly when spawning.
+    pub flag: Flag,
     pub fps_shown: bool,
+    /// Seconds to extrapolate contacts by, overriding the server's suggestion.
+    #[setting(optional)]
+    pub interpolation_delay: Option<f32>,
+    /// Keys that perform actions, as chosen by the player.
+    pub key_bindings: KeyBindings,
+    /// Hull paint to use when spawning.
+    pub paint: HullPaint,
+    /// Depends on the device.
+    #[setting(no_sync)]
     pub shadows: ShadowSetting,
+    /// Region to request when spawning.
+    pub spawn_region: SpawnRegion,
+}
+
+impl Mk48Settings {
+    /// Cosmetics to request when spawning (the server ignores any that aren't unlocked).
+    pub fn cosmetics(&self) -> Cosmetics {
+        Cosmetics {
+            flag: self.flag,
+            paint: self.paint,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -16,7 +16,9 @@ uniform float uDerivative;
 uniform float uTime;
 uniform vec3 uSun;
 uniform vec3 uWaterSun;
-uniform vec2 uWind;
+uniform vec2 uWaveOffset;
+uniform vec2 uWaveDrift;
+uniform float uWaveScale;
 
 #ifdef SHADOWS
     uniform mat4 uShadowMatrix;
@@ -238,9 +240,9 @@ void main() {
         if (height >= sandHeight + WAVE_HEIGHT * 0.3) {
             fragColor = vec4(s, 1.0);
         } else {
-            vec2 wavePos = vPosition + uWind * (uTime * -0.42);
+            vec2 wavePos = vPosition + uWaveOffset + uWaveDrift * -0.42;
             #ifdef WAVES
-                vec2 wn = waveNoise(vec3(wavePos, uTime) * 0.07) * 0.8 * vec2(WAVE_HEIGHT, 2.5);
+                vec2 wn = waveNoise(vec3(wavePos, uTime) * 0.07) * 0.8 * vec2(WAVE_HEIGHT, 2.5 * uWaveScale);
             #else
                 #ifdef ANIMATIONS
                     float v = texture(uDetail, wavePos * 0.005).w;
@@ -248,7 +250,7 @@ void main() {
                     float v = detail.w;
                 #endif
                 // Multiply by factor to account for mipmapping a value that is used non-linearly.
-                vec2 wn = v * vec2(WAVE_HEIGHT, (uDerivative * 0.35 + 0.9) * 2.5);
+                vec2 wn = v * vec2(WAVE_HEIGHT, (uDerivative * 0.35 + 0.9) * 2.5 * uWaveScale);
             #endif
             sandHeight += wn.x - 1.25 * WAVE_HEIGHT * float(ocean);
 
@@ -4,6 +4,7 @@ in highp vec4 vPosition;
 in highp vec2 vUv;
 in vec2 vColor;
 in vec3 vTangent;
+in vec3 vTint;
 
 uniform sampler2D uColor;
 uniform sampler2D uNormal;
@@ -46,6 +47,7 @@ void main() {
     // Use mipmap bias + multiple samples to make sprites appear sharper.
     // Makes a big difference on ships with high frequency detail like Yamato.
     vec4 color = textureSharp(uColor, vUv, bias) * vColor.xxxy;
+    color.rgb *= vTint;
     vec4 normalBump = textureSharp(uNormal, vUv, 0.0);
     vec3 tsn = normalize(normalBump.xyz * 2.0 - 1.0);
 
@@ -4,6 +4,7 @@ in vec4 position;
 in vec2 uv;
 in float alpha;
 in vec2 tangent;
+in vec3 tint;
 
 uniform mat3 uView;
 
@@ -11,6 +12,7 @@ out vec4 vPosition;
 out vec2 vUv;
 out vec2 vColor;
 out vec3 vTangent;
+out vec3 vTint;
 
 void main() {
     vPosition = position;
@@ -18,4 +20,5 @@ void main() {
     vUv = uv;
     vColor = alpha == 0.0 ? vec2(0.0, 0.48) : vec2(alpha); // TODO fix drop shadows blending towards 0
     vTangent = vec3(tangent, 0.0);
+    vTint = tint;
 }
@@ -2,10 +2,11 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::animation::Animation;
+use crate::sprite::SpriteId;
 use common::altitude::Altitude;
 use common::entity::{EntityId, EntityKind, EntitySubKind, EntityType};
 use common::transform::Transform;
-use glam::Vec2;
+use glam::{Vec2, Vec3};
 use std::cmp::Ordering;
 
 /// Rendering information of a sprite that may be sorted relative to other sprites.
@@ -18,7 +19,9 @@ pub struct SortableSprite {
     pub frame: Option<usize>,
     pub height: f32,
     pub shadow_height: f32,
-    pub sprite: &'static str,
+    pub sprite: SpriteId,
+    /// Multiplied with the sprite's color.
+    pub tint: Vec3,
     pub transform: Transform,
 }
 
@@ -42,7 +45,8 @@ impl SortableSprite {
             frame: None,
             height,
             shadow_height: altitude,
-            sprite: entity_type.as_str(),
+            sprite: SpriteId::Entity(entity_type),
+            tint: Vec3::ONE,
             transform,
         }
     }
@@ -69,7 +73,8 @@ impl SortableSprite {
             frame: None,
             height,
             shadow_height,
-            sprite: entity_type.as_str(),
+            sprite: SpriteId::Entity(entity_type),
+            tint: Vec3::ONE,
             transform,
         }
     }
@@ -84,11 +89,18 @@ impl SortableSprite {
             frame: Some(animation.frame(time_seconds)),
             height: 0.0,
             shadow_height: 0.0, // Animations don't have height so they don't have shadows.
-            sprite: animation.name,
+            sprite: SpriteId::Named(animation.name),
+            tint: Vec3::ONE,
             transform: Transform::from_position(animation.position),
         }
     }
 
+    /// Multiplies the sprite's color by `tint`.
+    pub fn with_tint(mut self, tint: Vec3) -> Self {
+        self.tint = tint;
+        self
+    }
+
     fn deck_height(entity_type: EntityType) -> f32 {
         let data = entity_type.data();
         if data.sub_kind == EntitySubKind::Submarine {
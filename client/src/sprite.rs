@@ -3,7 +3,8 @@
 
 use crate::game::Mk48Params;
 use crate::settings::ShadowSetting;
-use glam::{Mat3, Vec2, Vec4};
+use common::entity::EntityType;
+use glam::{Mat3, Vec2, Vec3, Vec4};
 use renderer::{
     derive_vertex, Layer, MeshBuilder, RenderLayer, Renderer, Shader, Texture, TextureFormat,
     TriangleBuffer,
@@ -17,9 +18,33 @@ derive_vertex!(
         uv: Vec2,
         alpha: f32,
         tangent: Vec2,
+        tint: Vec3,
     }
 );
 
+common::entity_sprites!("src/sprites_webgl.json");
+
+/// Identifies a sprite in the [`UvSpriteSheet`].
+#[derive(Copy, Clone)]
+pub enum SpriteId {
+    /// Texture coordinates were generated at compile time, see [`entity_sprite`].
+    Entity(EntityType),
+    /// Looked up by name, optionally as an animation.
+    Named(&'static str),
+}
+
+impl From<EntityType> for SpriteId {
+    fn from(entity_type: EntityType) -> Self {
+        Self::Entity(entity_type)
+    }
+}
+
+impl From<&'static str> for SpriteId {
+    fn from(name: &'static str) -> Self {
+        Self::Named(name)
+    }
+}
+
 /// Draws sprites from a [`UvSpriteSheet`].
 pub struct SpriteLayer {
     atlas_color: Texture,
@@ -81,7 +106,32 @@ impl SpriteLayer {
     /// Draws a sprite. `angle` is in radians.
     pub fn draw(
         &mut self,
-        sprite: &str,
+        sprite: impl Into<SpriteId>,
+        frame: Option<usize>,
+        center: Vec2,
+        dimensions: Vec2,
+        angle: f32,
+        alpha: f32,
+        altitude: f32,
+        height: f32,
+    ) {
+        self.draw_tinted(
+            sprite,
+            frame,
+            center,
+            dimensions,
+            angle,
+            alpha,
+            altitude,
+            height,
+            Vec3::ONE,
+        );
+    }
+
+    /// Draws a sprite, multiplying its color by `tint`. `angle` is in radians.
+    pub fn draw_tinted(
+        &mut self,
+        sprite: impl Into<SpriteId>,
         frame: Option<usize>,
         center: Vec2,
         dimensions: Vec2,
@@ -89,31 +139,50 @@ impl SpriteLayer {
         alpha: f32,
         altitude: f32,
         height: f32,
+        tint: Vec3,
     ) {
         if alpha == 0.0 {
             return; // Reserved for shadows.
         }
         self.draw_inner(
-            sprite, frame, center, dimensions, angle, alpha, altitude, height,
+            sprite.into(),
+            frame,
+            center,
+            dimensions,
+            angle,
+            alpha,
+            altitude,
+            height,
+            tint,
         );
     }
 
     /// Draws a sprite shadow. `angle` is in radians.
     pub fn draw_shadow(
         &mut self,
-        sprite: &str,
+        sprite: impl Into<SpriteId>,
         frame: Option<usize>,
         center: Vec2,
         dimensions: Vec2,
         angle: f32,
     ) {
-        self.draw_inner(sprite, frame, center, dimensions, angle, 0.0, 0.0, 0.0);
+        self.draw_inner(
+            sprite.into(),
+            frame,
+            center,
+            dimensions,
+            angle,
+            0.0,
+            0.0,
+            0.0,
+            Vec3::ONE,
+        );
     }
 
     /// Draws a sprite or a shadow depending on if the alpha > 0.0.
     fn draw_inner(
         &mut self,
-        sprite: &str,
+        sprite: SpriteId,
         frame: Option<usize>,
         center: Vec2,
         dimensions: Vec2,
@@ -121,12 +190,23 @@ impl SpriteLayer {
         alpha: f32,
         altitude: f32,
         height: f32,
+        tint: Vec3,
     ) {
-        let sprite = if let Some(frame) = frame {
-            let animation = &self.sheet.animations.get(sprite).unwrap();
-            &animation[frame]
-        } else {
-            self.sheet.sprites.get(sprite).expect(sprite)
+        let (uvs, aspect) = match sprite {
+            SpriteId::Entity(entity_type) => {
+                let sprite = entity_sprite(entity_type)
+                    .unwrap_or_else(|| panic!("{entity_type} has no sprite"));
+                (sprite.uvs.map(Vec2::from), sprite.aspect)
+            }
+            SpriteId::Named(name) => {
+                let sprite = if let Some(frame) = frame {
+                    let animation = &self.sheet.animations.get(name).unwrap();
+                    &animation[frame]
+                } else {
+                    self.sheet.sprites.get(name).expect(name)
+                };
+                (sprite.uvs, sprite.aspect)
+            }
         };
 
         // TODO make sprites and entities have same aspect ratio.
@@ -144,7 +224,7 @@ impl SpriteLayer {
         //     )
         // }
         let matrix = Mat3::from_scale_angle_translation(
-            Vec2::new(dimensions.x, dimensions.x / sprite.aspect),
+            Vec2::new(dimensions.x, dimensions.x / aspect),
             angle,
             center,
         );
@@ -171,12 +251,13 @@ impl SpriteLayer {
 
         self.mesh.vertices.extend(
             IntoIterator::into_iter(positions)
-                .zip(sprite.uvs.iter())
-                .map(|(pos, &uv)| SpriteVertex {
+                .zip(uvs)
+                .map(|(pos, uv)| SpriteVertex {
                     pos: matrix.transform_point2(pos).extend(altitude).extend(height),
                     uv,
                     alpha,
                     tangent,
+                    tint,
                 }),
         );
     }
@@ -211,3 +292,19 @@ impl RenderLayer<&ShadowResult<&Mk48Params>> for SpriteLayer {
         self.mesh.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::sprite::entity_sprite;
+    use common::entity::{EntityKind, EntityType};
+
+    #[test]
+    fn boat_sprites() {
+        for typ in EntityType::iter() {
+            if typ.data().kind == EntityKind::Boat {
+                let sprite = entity_sprite(typ).unwrap();
+                assert!(sprite.aspect > 0.0, "{:?}", typ);
+            }
+        }
+    }
+}
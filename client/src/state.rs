@@ -3,22 +3,112 @@
 
 use crate::animation::Animation;
 use crate::interpolated_contact::InterpolatedContact;
+use crate::update_clock::UpdateClock;
 use client_util::apply::Apply;
+use common::ammo::Ammo;
+use common::angle::Angle;
+use common::area_effect::AreaEffect;
+use common::beam::Beam;
+use common::bounty::BountyMarker;
+use common::cargo::Cargo;
 use common::contact::Contact;
+use common::content_pack::ContentPackInfo;
+use common::day_night::TimeOfDay;
 use common::death_reason::DeathReason;
-use common::entity::EntityId;
-use common::protocol::Update;
+use common::drawing::Drawing;
+use common::duel::DuelDto;
+use common::entity::{EntityId, EntityType};
+use common::envelope::HullEnvelopes;
+use common::escort::EscortDto;
+use common::ghost::Ghost;
+use common::intercept::Intercept;
+use common::protocol::{Interpolation, Update};
+use common::sea_state::SeaState;
+use common::signal::SignalMarker;
+use common::status_effects::StatusEffects;
+use common::summary::Summary;
+use common::target_lock::TargetLock;
 use common::terrain::Terrain;
+use common::terrain_objective::TerrainObjective;
+use common::threat_warning::ThreatWarning;
+use core_protocol::id::PlayerId;
 use std::collections::HashMap;
 
 /// State associated with game server connection. Reset when connection is reset.
 pub struct Mk48State {
+    /// Whether the player's boat will soon be scuttled for lack of input.
+    pub afk: bool,
+    /// Ammunition in reserve on the player's boat.
+    pub ammo: Ammo,
     pub animations: Vec<Animation>,
+    /// Oil slicks and fires that are on screen.
+    pub area_effects: Vec<AreaEffect>,
+    /// Coins banked by returning to port.
+    pub banked_coins: u32,
+    /// Beam weapons being fired, and when they were received.
+    pub beams: Vec<(Beam, f32)>,
+    /// Cargo being delivered by the player's boat.
+    pub cargo: Option<Cargo>,
+    /// Whether the player is casting, seeing every boat.
+    pub casting: bool,
+    /// Approximate positions of players with bounties on them.
+    pub bounties: Vec<BountyMarker>,
+    /// Whether collision assist is slowing or steering the player's boat, because of land or an
+    /// obstacle ahead.
+    pub collision_warning: bool,
     pub contacts: HashMap<EntityId, InterpolatedContact>,
+    /// Additional content the server loaded, whose assets are fetched from the server.
+    pub content_pack: Option<Box<ContentPackInfo>>,
     pub death_reason: Option<DeathReason>,
+    /// Drawings from the fleet leader, and when they were received.
+    pub drawings: Vec<(Drawing, f32)>,
+    /// Duels, and challenges to duel, involving the player.
+    pub duels: Vec<DuelDto>,
     pub entity_id: Option<EntityId>,
+    /// Type of the player's boat, if alive.
+    pub entity_type: Option<EntityType>,
+    /// Engagement envelopes of the armaments of each type of boat the player had, which the
+    /// server sends once per hull.
+    pub envelopes: HashMap<EntityType, HullEnvelopes>,
+    /// Escort contracts, and offers of them, involving the player.
+    pub escorts: Vec<EscortDto>,
+    /// Last known states of boats that the player's sensors lost track of, and when they were
+    /// lost.
+    pub ghosts: Vec<(Ghost, f32)>,
+    /// Weapons shot down by point defenses, and when they were received.
+    pub intercepts: Vec<(Intercept, f32)>,
+    /// How the server suggests interpolating contacts.
+    pub interpolation: Interpolation,
+    /// How much of returning to port is complete, if returning to port.
+    pub port_progress: Option<f32>,
+    /// Bearings of active radars that illuminate the player's boat.
+    pub radar_warnings: Vec<Angle>,
     pub score: u32,
+    /// Scores of every player with a boat, while casting.
+    pub scores: HashMap<PlayerId, u32>,
+    /// How much of scuttling is complete, if scuttling.
+    pub scuttle_progress: Option<f32>,
+    /// Signals from teammates, and when they were received.
+    pub signals: Vec<(SignalMarker, f32)>,
+    /// Fires and flooding on the player's boat.
+    pub status_effects: StatusEffects,
+    /// Summary of the previous life, kept until respawning.
+    pub summary: Option<Box<Summary>>,
+    /// The enemy boat the player locked onto, if their sensors still track it.
+    pub target_lock: Option<TargetLock>,
     pub terrain: Terrain,
+    /// Objective for the player's boat to reshape the terrain, if it can.
+    pub terrain_objective: Option<TerrainObjective>,
+    /// Weapons that threatened the player's boat, and when they were received.
+    pub threat_warnings: Vec<(ThreatWarning, f32)>,
+    /// Position in the day/night cycle.
+    pub time_of_day: TimeOfDay,
+    /// Wind and waves.
+    pub sea_state: SeaState,
+    /// Client time the sea state was received, for extrapolating it.
+    pub sea_state_time: f32,
+    /// Measures how late updates arrive.
+    pub update_clock: UpdateClock,
     pub world_radius: f32,
     terrain_reset: bool,
 }
@@ -26,12 +116,44 @@ pub struct Mk48State {
 impl Default for Mk48State {
     fn default() -> Self {
         Self {
+            afk: false,
+            ammo: Ammo::default(),
             animations: Vec::new(),
+            area_effects: Vec::new(),
+            banked_coins: 0,
+            beams: Vec::new(),
+            cargo: None,
+            casting: false,
+            bounties: Vec::new(),
+            collision_warning: false,
             contacts: HashMap::new(),
+            content_pack: None,
             death_reason: None,
+            drawings: Vec::new(),
+            duels: Vec::new(),
             entity_id: None,
+            entity_type: None,
+            envelopes: HashMap::new(),
+            escorts: Vec::new(),
+            ghosts: Vec::new(),
+            intercepts: Vec::new(),
+            interpolation: Interpolation::default(),
+            port_progress: None,
+            radar_warnings: Vec::new(),
             score: 0,
+            scores: HashMap::new(),
+            scuttle_progress: None,
+            signals: Vec::new(),
+            status_effects: StatusEffects::default(),
+            summary: None,
+            target_lock: None,
             terrain: Terrain::default(),
+            terrain_objective: None,
+            threat_warnings: Vec::new(),
+            time_of_day: TimeOfDay::default(),
+            sea_state: SeaState::default(),
+            sea_state_time: 0.0,
+            update_clock: UpdateClock::default(),
             // Keep border off splash screen by assuming radius.
             world_radius: 10000.0,
             terrain_reset: false,
@@ -50,6 +172,14 @@ impl Mk48State {
         self.entity_id.map(|id| self.contacts.get(&id).unwrap())
     }
 
+    /// Describes what the player is doing, for rich presence, e.g. "Playing mk48+: Level 7 Ohio".
+    pub(crate) fn rich_presence(&self) -> Option<String> {
+        self.entity_type.map(|entity_type| {
+            let data = entity_type.data();
+            format!("Playing mk48+: Level {} {}", data.level, data.label)
+        })
+    }
+
     // Reset terrain cache when switching servers and state resets.
     // TODO find a better way to do this.
     pub fn take_terrain_reset(&mut self) -> bool {
@@ -64,7 +194,22 @@ impl Mk48State {
 
 impl Apply<Update> for Mk48State {
     fn apply(&mut self, update: Update) {
+        if update.content_pack.is_some() {
+            self.content_pack = update.content_pack;
+        }
+        if let Some(interpolation) = update.interpolation {
+            self.interpolation = interpolation;
+        }
+        if let Some(envelopes) = update.envelopes {
+            self.envelopes.insert(envelopes.entity_type, *envelopes);
+        }
         self.death_reason = update.death_reason;
+        if update.summary.is_some() {
+            self.summary = update.summary;
+        } else if update.entity_type.is_some() {
+            // Respawned.
+            self.summary = None;
+        }
 
         // Didn't consume previous update (tabbed out) and now terrain updated state is invalid.
         self.terrain_reset = !self.terrain.updated.is_empty();
@@ -72,6 +217,26 @@ impl Apply<Update> for Mk48State {
 
         self.world_radius = update.world_radius;
         self.score = update.score;
+        self.entity_type = update.entity_type;
+        self.afk = update.afk;
+        self.port_progress = update.port_progress;
+        self.scuttle_progress = update.scuttle_progress;
+        self.banked_coins = update.banked_coins;
+        self.duels = update.duels;
+        self.escorts = update.escorts;
+        self.bounties = update.bounties;
+        self.area_effects = update.area_effects;
+        self.status_effects = update.status_effects;
+        self.ammo = update.ammo;
+        self.cargo = update.cargo;
+        self.terrain_objective = update.terrain_objective;
+        self.time_of_day = update.time_of_day;
+        self.sea_state = update.sea_state;
+        self.radar_warnings = update.radar_warnings;
+        self.collision_warning = update.collision_warning;
+        self.target_lock = update.target_lock;
+        self.casting = update.casting;
+        self.scores = update.scores.into_iter().collect();
     }
 
     fn reset(&mut self) {
@@ -1,18 +1,36 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::game::{ACTIVE_KEY, SURFACE_KEY};
+use client_util::keyboard::Key;
+use common::cosmetic::{Flag, HullPaint};
 use common::death_reason::DeathReason;
 use common::entity::{EntityKind, EntitySubKind, EntityType};
+use common::protocol::{ReturnToPort, Scuttle};
+use common::signal::SignalKind;
+use common::spawn_region::SpawnRegion;
+use common::terrain_objective::TerrainObjective;
 use core_protocol::id::LanguageId;
 use core_protocol::id::LanguageId::*;
 use core_protocol::name::PlayerAlias;
 use std::fmt::Display;
 use yew_frontend::frontend::RewardedAd;
-use yew_frontend::s;
+use yew_frontend::{s, sd};
 
 pub trait Mk48Translation: Sized {
+    fn announce_health(self, percent: u8) -> String;
+    s!(announce_torpedo);
+    s!(announce_upgrade);
+
+    s!(caster_label);
+    s!(caster_hint);
+    s!(caster_start_label);
+    s!(caster_start_hint);
+    s!(caster_stop_label);
+
     fn death_reason(self, death_reason: &DeathReason) -> String;
+    s!(death_reason_afk);
+    s!(death_reason_fire);
+    fn death_reason_port(self, coins: u32) -> String;
     fn death_reason_boat(self, alias: PlayerAlias) -> String {
         self.death_reason_collision(&alias)
     }
@@ -24,9 +42,23 @@ pub trait Mk48Translation: Sized {
     fn death_reason_ram(self, alias: PlayerAlias) -> String;
     fn death_reason_aa(self, alias: PlayerAlias) -> String;
     s!(death_reason_terrain);
+    s!(death_reason_scuttled);
     s!(death_reason_sunk);
     fn death_reason_weapon(self, alias: PlayerAlias, entity_type: EntityType) -> String;
 
+    s!(duel_label);
+    fn duel_challenge_hint(self, stake: u32) -> String;
+    fn duel_accept_hint(self, stake: u32) -> String;
+    s!(duel_decline_hint);
+    s!(duel_withdraw_hint);
+    fn duel_active_hint(self, pot: u32) -> String;
+
+    s!(escort_label);
+    fn escort_offer_hint(self, share: u8) -> String;
+    fn escort_accept_hint(self, share: u8) -> String;
+    s!(escort_cancel_hint);
+    fn escort_active_hint(self, share: u8, attackers_sunk: u8) -> String;
+
     fn entity_kind_name(self, kind: EntityKind, sub_kind: EntitySubKind) -> &'static str {
         match (kind, sub_kind) {
             (EntityKind::Aircraft, EntitySubKind::Heli) => self.entity_aircraft_heli_name(),
@@ -75,6 +107,7 @@ pub trait Mk48Translation: Sized {
             (EntityKind::Weapon, EntitySubKind::Shell) => self.entity_weapon_shell_name(),
             (EntityKind::Weapon, EntitySubKind::TankShell) => self.entity_weapon_tankshell_name(),
             (EntityKind::Weapon, EntitySubKind::Laser) => self.entity_weapon_laser_name(),
+            (EntityKind::Weapon, EntitySubKind::Beam) => self.entity_weapon_beam_name(),
             (EntityKind::Weapon, EntitySubKind::Torpedo) => self.entity_weapon_torpedo_name(),
             _ => {
                 debug_assert!(false, "missing name for {:?}/{:?}", kind, sub_kind);
@@ -178,24 +211,141 @@ pub trait Mk48Translation: Sized {
     s!(entity_weapon_shell_name);
     s!(entity_weapon_tankshell_name);
     s!(entity_weapon_laser_name);
+    s!(entity_weapon_beam_name);
     s!(entity_weapon_torpedo_name);
 
+    s!(instruction_afk);
+    s!(instruction_collision);
     s!(instruction_basics_mouse);
     s!(instruction_basics_touch);
     s!(instruction_zoom_mouse);
     s!(instruction_zoom_touch);
 
     s!(sensor_active_label);
-    fn sensor_active_hint(self, sensors: &str) -> String;
+    fn sensor_active_hint(self, sensors: &str, key: Key) -> String;
     s!(sensor_radar_label);
     s!(sensor_sonar_label);
 
     s!(ship_surface_label);
-    fn ship_surface_hint(self) -> String;
+    fn ship_surface_hint(self, key: Key) -> String;
     s!(ship_dive_label);
+    s!(ship_periscope_label);
+    fn ship_periscope_hint(self, key: Key) -> String;
+    s!(ship_return_to_port_label);
+    fn ship_return_to_port_hint(self, banked_coins: u32) -> String;
+    s!(ship_scuttle_label);
+    fn ship_scuttle_hint(self) -> String;
+    s!(ship_damage_control_label);
+    s!(ship_damage_control_hint);
+    s!(ship_fire_label);
+    s!(ship_flooding_label);
+    s!(ship_repair_crew_label);
+    fn ship_repair_crew_hint(self, key: Key) -> String;
+    s!(ship_searchlight_label);
+    fn ship_searchlight_hint(self, key: Key) -> String;
+    s!(ship_emcon_label);
+    fn ship_emcon_hint(self, key: Key) -> String;
+    s!(ship_cruise_label);
+    fn ship_cruise_hint(self, key: Key) -> String;
+    s!(ship_unlock_turrets_label);
+    fn ship_unlock_turrets_hint(self, key: Key) -> String;
+    s!(ship_release_target_label);
+    fn ship_release_target_hint(self, key: Key) -> String;
+
+    fn cosmetic_flag(self, flag: Flag) -> &'static str {
+        match flag {
+            Flag::None => self.cosmetic_flag_none(),
+            Flag::Naval => self.cosmetic_flag_naval(),
+            Flag::Signal => self.cosmetic_flag_signal(),
+            Flag::Checkered => self.cosmetic_flag_checkered(),
+            Flag::Pirate => self.cosmetic_flag_pirate(),
+            Flag::Gold => self.cosmetic_flag_gold(),
+        }
+    }
+    s!(cosmetic_flag_label);
+    s!(cosmetic_flag_none);
+    s!(cosmetic_flag_naval);
+    s!(cosmetic_flag_signal);
+    s!(cosmetic_flag_checkered);
+    s!(cosmetic_flag_pirate);
+    s!(cosmetic_flag_gold);
+    fn cosmetic_paint(self, paint: HullPaint) -> &'static str {
+        match paint {
+            HullPaint::Standard => self.cosmetic_paint_standard(),
+            HullPaint::Arctic => self.cosmetic_paint_arctic(),
+            HullPaint::Desert => self.cosmetic_paint_desert(),
+            HullPaint::Jungle => self.cosmetic_paint_jungle(),
+            HullPaint::Crimson => self.cosmetic_paint_crimson(),
+            HullPaint::Gold => self.cosmetic_paint_gold(),
+        }
+    }
+    s!(cosmetic_paint_label);
+    s!(cosmetic_paint_standard);
+    s!(cosmetic_paint_arctic);
+    s!(cosmetic_paint_desert);
+    s!(cosmetic_paint_jungle);
+    s!(cosmetic_paint_crimson);
+    s!(cosmetic_paint_gold);
+    /// A cosmetic that isn't unlocked until reaching a boat level.
+    fn cosmetic_locked(self, name: &str, level: u8) -> String;
+
+    fn spawn_region(self, region: SpawnRegion) -> &'static str {
+        match region {
+            SpawnRegion::Any => self.spawn_region_any(),
+            SpawnRegion::Fleet => self.spawn_region_fleet(),
+            SpawnRegion::North => self.spawn_region_north(),
+            SpawnRegion::East => self.spawn_region_east(),
+            SpawnRegion::South => self.spawn_region_south(),
+            SpawnRegion::West => self.spawn_region_west(),
+        }
+    }
+    s!(spawn_region_label);
+    s!(spawn_region_any);
+    s!(spawn_region_fleet);
+    s!(spawn_region_north);
+    s!(spawn_region_east);
+    s!(spawn_region_south);
+    s!(spawn_region_west);
+    /// Speed and health of a hull that may be spawned as.
+    fn spawn_hull_stats(self, knots: f32, health: f32) -> String;
+
+    fn terrain_objective(self, objective: &TerrainObjective) -> &'static str {
+        match objective {
+            TerrainObjective::Channel { .. } => self.terrain_objective_channel(),
+            TerrainObjective::Island { .. } => self.terrain_objective_island(),
+        }
+    }
+    s!(terrain_objective_channel);
+    s!(terrain_objective_island);
+
+    fn signal(self, kind: SignalKind) -> &'static str {
+        match kind {
+            SignalKind::Attack => self.signal_attack(),
+            SignalKind::Help => self.signal_help(),
+            SignalKind::Retreat => self.signal_retreat(),
+        }
+    }
+    s!(signal_attack);
+    s!(signal_help);
+    s!(signal_retreat);
+
+    s!(summary_time_alive);
+    s!(summary_highest_level);
+    s!(summary_kill_score);
+    s!(summary_loot_score);
+    /// Loot score, with the number of coins that contributed to it.
+    fn summary_loot(self, loot_score: u32, coins: u32) -> String;
+    /// Kills made with a particular weapon.
+    fn summary_kills(self, weapon: &str) -> String;
+    sd!(
+        summary_kills_other,
+        "Kills made by ramming, collision, or anti-aircraft."
+    );
 
     s!(team_fleet_label);
     s!(team_fleet_name_placeholder);
+    s!(transfer_label);
+    fn transfer_hint(self, amount: u32, received: u32) -> String;
 
     fn rewarded_ad(self, rewarded_ad: &RewardedAd) -> &'static str {
         match rewarded_ad {
@@ -248,6 +398,142 @@ impl Mk48Translation for LanguageId {
     }
     */
 
+    fn announce_health(self, percent: u8) -> String {
+        match self {
+            Arabic => format!("الصحة {percent}٪"),
+            Bork => format!("Bork health {percent}%"),
+            English => format!("Health {percent}%"),
+            French => format!("Santé {percent} %"),
+            German => format!("Gesundheit {percent} %"),
+            Hindi => format!("स्वास्थ्य {percent}%"),
+            Italian => format!("Salute {percent}%"),
+            Japanese => format!("耐久力 {percent}%"),
+            Russian => format!("Прочность {percent}%"),
+            SimplifiedChinese => format!("生命值 {percent}%"),
+            Spanish => format!("Salud {percent} %"),
+            Vietnamese => format!("Máu {percent}%"),
+        }
+    }
+
+    fn announce_torpedo(self) -> &'static str {
+        match self {
+            Arabic => "طوربيد قادم",
+            Bork => "Bork torpedo incoming",
+            English => "Incoming torpedo",
+            French => "Torpille en approche",
+            German => "Torpedo im Anmarsch",
+            Hindi => "टारपीडो आ रहा है",
+            Italian => "Siluro in arrivo",
+            Japanese => "魚雷接近中",
+            Russian => "Приближается торпеда",
+            SimplifiedChinese => "鱼雷来袭",
+            Spanish => "Torpedo entrante",
+            Vietnamese => "Ngư lôi đang đến",
+        }
+    }
+
+    fn announce_upgrade(self) -> &'static str {
+        match self {
+            Arabic => "الترقية متاحة",
+            Bork => "Bork upgrade ready",
+            English => "Upgrade available",
+            French => "Amélioration disponible",
+            German => "Upgrade verfügbar",
+            Hindi => "अपग्रेड उपलब्ध है",
+            Italian => "Potenziamento disponibile",
+            Japanese => "アップグレード可能",
+            Russian => "Доступно улучшение",
+            SimplifiedChinese => "可以升级",
+            Spanish => "Mejora disponible",
+            Vietnamese => "Có thể nâng cấp",
+        }
+    }
+
+    fn caster_label(self) -> &'static str {
+        match self {
+            Arabic => "بث مباشر",
+            Bork => "Borkcasting",
+            English => "Casting",
+            French => "Diffusion",
+            German => "Übertragung",
+            Hindi => "प्रसारण",
+            Italian => "Telecronaca",
+            Japanese => "実況中",
+            Russian => "Трансляция",
+            SimplifiedChinese => "解说中",
+            Spanish => "Retransmitiendo",
+            Vietnamese => "Đang bình luận",
+        }
+    }
+
+    fn caster_hint(self) -> &'static str {
+        match self {
+            Arabic => "WASD أو مفاتيح الأسهم للتحريك، Shift+1-0 لحفظ موضع، 1-0 للانتقال إليه.",
+            Bork => "WASD or arrows to bork around, Shift+1-0 to bork a spot, 1-0 to bork there.",
+            English => "WASD or arrow keys to pan, Shift+1-0 to bookmark, 1-0 to cut.",
+            French => "WASD ou flèches pour déplacer la vue, Maj+1-0 pour marquer, 1-0 pour changer de plan.",
+            German => "WASD oder Pfeiltasten zum Schwenken, Umschalt+1-0 zum Merken, 1-0 zum Umschalten.",
+            Hindi => "पैन करने के लिए WASD या तीर कुंजियाँ, बुकमार्क के लिए Shift+1-0, कट के लिए 1-0।",
+            Italian => "WASD o frecce per spostare la visuale, Maiusc+1-0 per salvare, 1-0 per cambiare inquadratura.",
+            Japanese => "WASDまたは矢印キーで移動、Shift+1-0でブックマーク、1-0で切り替え。",
+            Russian => "WASD или стрелки для перемещения, Shift+1-0 для закладки, 1-0 для переключения.",
+            SimplifiedChinese => "WASD 或方向键平移，Shift+1-0 添加书签，1-0 切换镜头。",
+            Spanish => "WASD o flechas para mover la cámara, Mayús+1-0 para marcar, 1-0 para cambiar de plano.",
+            Vietnamese => "WASD hoặc phím mũi tên để di chuyển, Shift+1-0 để đánh dấu, 1-0 để chuyển cảnh.",
+        }
+    }
+
+    fn caster_start_label(self) -> &'static str {
+        match self {
+            Arabic => "بث",
+            Bork => "Borkcast",
+            English => "Cast",
+            French => "Diffuser",
+            German => "Übertragen",
+            Hindi => "प्रसारित करें",
+            Italian => "Telecronaca",
+            Japanese => "実況",
+            Russian => "Трансляция",
+            SimplifiedChinese => "解说",
+            Spanish => "Retransmitir",
+            Vietnamese => "Bình luận",
+        }
+    }
+
+    fn caster_start_hint(self) -> &'static str {
+        match self {
+            Arabic => "شاهد كل القوارب مع نقاطها للتعليق على بطولة",
+            Bork => "See every bork, with scores, to bork about a tournament",
+            English => "See every boat, with scores, to commentate a tournament",
+            French => "Voir tous les bateaux, avec leurs scores, pour commenter un tournoi",
+            German => "Alle Boote mit Punkten sehen, um ein Turnier zu kommentieren",
+            Hindi => "टूर्नामेंट की कमेंट्री के लिए हर नाव को स्कोर सहित देखें",
+            Italian => "Vedi tutte le navi, con i punteggi, per commentare un torneo",
+            Japanese => "大会を実況するため、全ての艦とスコアを表示",
+            Russian => "Видеть все корабли и их очки, чтобы комментировать турнир",
+            SimplifiedChinese => "查看所有船只及其分数，以解说比赛",
+            Spanish => "Ver todos los barcos, con sus puntuaciones, para comentar un torneo",
+            Vietnamese => "Xem mọi con tàu cùng điểm số để bình luận giải đấu",
+        }
+    }
+
+    fn caster_stop_label(self) -> &'static str {
+        match self {
+            Arabic => "إيقاف البث",
+            Bork => "Stop borkcasting",
+            English => "Stop Casting",
+            French => "Arrêter la diffusion",
+            German => "Übertragung beenden",
+            Hindi => "प्रसारण बंद करें",
+            Italian => "Termina telecronaca",
+            Japanese => "実況を終了",
+            Russian => "Остановить трансляцию",
+            SimplifiedChinese => "停止解说",
+            Spanish => "Dejar de retransmitir",
+            Vietnamese => "Dừng bình luận",
+        }
+    }
+
     fn death_reason(self, death_reason: &DeathReason) -> String {
         match death_reason {
             &DeathReason::Boat(alias) => self.death_reason_boat(alias),
@@ -257,6 +543,10 @@ impl Mk48Translation for LanguageId {
             &DeathReason::AntiAir(alias) => self.death_reason_aa(alias),
             DeathReason::Terrain => self.death_reason_terrain().to_owned(),
             DeathReason::Sunk => self.death_reason_sunk().to_owned(),
+            DeathReason::Afk => self.death_reason_afk().to_owned(),
+            DeathReason::Fire => self.death_reason_fire().to_owned(),
+            &DeathReason::ReturnedToPort(coins) => self.death_reason_port(coins),
+            DeathReason::Scuttled => self.death_reason_scuttled().to_owned(),
             &DeathReason::Weapon(alias, entity_type) => {
                 self.death_reason_weapon(alias, entity_type)
             }
@@ -267,6 +557,74 @@ impl Mk48Translation for LanguageId {
         }
     }
 
+    fn death_reason_fire(self) -> &'static str {
+        match self {
+            Arabic => "احترقت في حريق نفطي!",
+            Bork => "Borked by burning oil!",
+            English => "Burned in an oil fire!",
+            French => "Brûlé dans un incendie de pétrole!",
+            German => "In einem Ölbrand verbrannt!",
+            Hindi => "तेल की आग में जल गया!",
+            Italian => "Bruciato in un incendio di petrolio!",
+            Japanese => "油火災で炎上!",
+            Russian => "Сгорел в горящей нефти!",
+            SimplifiedChinese => "在石油大火中烧毁!",
+            Spanish => "¡Quemado en un incendio de petróleo!",
+            Vietnamese => "Bị thiêu rụi trong đám cháy dầu!",
+        }
+    }
+
+    fn death_reason_afk(self) -> &'static str {
+        match self {
+            Arabic => "تم إغراق سفينتك بسبب عدم النشاط!",
+            Bork => "Bork fell asleep at the wheel!",
+            English => "Scuttled for being inactive!",
+            French => "Sabordé pour inactivité!",
+            German => "Wegen Inaktivität versenkt!",
+            Hindi => "निष्क्रिय रहने के कारण डुबो दिया गया!",
+            Italian => "Affondato per inattività!",
+            Japanese => "操作がなかったため自沈しました！",
+            Russian => "Затоплен из-за бездействия!",
+            SimplifiedChinese => "因长时间未操作而被凿沉！",
+            Spanish => "¡Hundido por inactividad!",
+            Vietnamese => "Bị đánh chìm vì không hoạt động!",
+        }
+    }
+
+    fn death_reason_port(self, coins: u32) -> String {
+        match self {
+            Arabic => format!("عدت إلى الميناء وادخرت {coins} عملة!"),
+            Bork => format!("Bork went home with {coins} borkcoins!"),
+            English => format!("Returned to port, banking {coins} coins!"),
+            French => format!("Retour au port, {coins} pièces mises en banque!"),
+            German => format!("Zurück im Hafen, {coins} Münzen gespart!"),
+            Hindi => format!("बंदरगाह लौटे, {coins} सिक्के जमा किए!"),
+            Italian => format!("Tornato in porto, {coins} monete messe da parte!"),
+            Japanese => format!("帰港し、{coins}コインを貯めました！"),
+            Russian => format!("Вернулись в порт и сохранили {coins} монет!"),
+            SimplifiedChinese => format!("已返回港口，存入 {coins} 枚硬币！"),
+            Spanish => format!("¡Regresaste a puerto y guardaste {coins} monedas!"),
+            Vietnamese => format!("Đã về cảng, gửi {coins} đồng xu!"),
+        }
+    }
+
+    fn death_reason_scuttled(self) -> &'static str {
+        match self {
+            Arabic => "أغرقت سفينتك بنفسك!",
+            Bork => "Bork borked own boat!",
+            English => "Scuttled your own boat!",
+            French => "Vous avez sabordé votre navire!",
+            German => "Eigenes Schiff selbst versenkt!",
+            Hindi => "अपना जहाज़ खुद डुबो दिया!",
+            Italian => "Hai autoaffondato la tua nave!",
+            Japanese => "自船を自沈させました！",
+            Russian => "Вы затопили свой корабль!",
+            SimplifiedChinese => "你凿沉了自己的船！",
+            Spanish => "¡Hundiste tu propio barco!",
+            Vietnamese => "Bạn đã tự đánh chìm tàu của mình!",
+        }
+    }
+
     fn death_reason_border(self) -> &'static str {
         match self {
             Arabic => "تحطمت في الحدود!",
@@ -387,6 +745,193 @@ impl Mk48Translation for LanguageId {
         }
     }
 
+    fn duel_label(self) -> &'static str {
+        match self {
+            Arabic => "مبارزات",
+            Bork => "Bork-offs",
+            English => "Duels",
+            French => "Duels",
+            German => "Duelle",
+            Hindi => "द्वंद्व",
+            Italian => "Duelli",
+            Japanese => "決闘",
+            Russian => "Дуэли",
+            SimplifiedChinese => "决斗",
+            Spanish => "Duelos",
+            Vietnamese => "Đấu tay đôi",
+        }
+    }
+
+    fn duel_challenge_hint(self, stake: u32) -> String {
+        match self {
+            Arabic => format!("تحدَّ إلى مبارزة برهان {stake} نقطة. من يُغرق الآخر أولًا يربح الرهانين"),
+            Bork => format!("Bork-off for {stake} bork points. First to bork the other takes both"),
+            English => format!("Challenge to a duel, staking {stake} points. Whoever sinks the other first takes both stakes"),
+            French => format!("Défier en duel, en misant {stake} points. Le premier à couler l'autre remporte les deux mises"),
+            German => format!("Zum Duell herausfordern, mit einem Einsatz von {stake} Punkten. Wer den anderen zuerst versenkt, gewinnt beide Einsätze"),
+            Hindi => format!("{stake} अंक दांव पर लगाकर द्वंद्व की चुनौती दें। जो पहले दूसरे को डुबोएगा, वह दोनों दांव जीतेगा"),
+            Italian => format!("Sfida a duello, puntando {stake} punti. Chi affonda per primo l'altro vince entrambe le puntate"),
+            Japanese => format!("{stake}ポイントを賭けて決闘を挑む。先に相手を沈めた方が両方の賭け金を得る"),
+            Russian => format!("Вызвать на дуэль со ставкой {stake} очков. Кто первым потопит другого, забирает обе ставки"),
+            SimplifiedChinese => format!("以 {stake} 分为赌注发起决斗。先击沉对方者赢得双方赌注"),
+            Spanish => format!("Retar a un duelo, apostando {stake} puntos. Quien hunda primero al otro se lleva ambas apuestas"),
+            Vietnamese => format!("Thách đấu tay đôi, đặt cược {stake} điểm. Ai đánh chìm đối phương trước sẽ thắng cả hai khoản cược"),
+        }
+    }
+
+    fn duel_accept_hint(self, stake: u32) -> String {
+        match self {
+            Arabic => format!("اقبل، براهن {stake} نقطة"),
+            Bork => format!("Bork yes, for {stake} bork points"),
+            English => format!("Accept, staking {stake} points"),
+            French => format!("Accepter, en misant {stake} points"),
+            German => format!("Annehmen, mit einem Einsatz von {stake} Punkten"),
+            Hindi => format!("{stake} अंक दांव पर लगाकर स्वीकार करें"),
+            Italian => format!("Accetta, puntando {stake} punti"),
+            Japanese => format!("{stake}ポイントを賭けて受ける"),
+            Russian => format!("Принять, поставив {stake} очков"),
+            SimplifiedChinese => format!("接受，押上 {stake} 分"),
+            Spanish => format!("Aceptar, apostando {stake} puntos"),
+            Vietnamese => format!("Chấp nhận, đặt cược {stake} điểm"),
+        }
+    }
+
+    fn duel_decline_hint(self) -> &'static str {
+        match self {
+            Arabic => "ارفض",
+            Bork => "Bork no",
+            English => "Decline",
+            French => "Refuser",
+            German => "Ablehnen",
+            Hindi => "अस्वीकार करें",
+            Italian => "Rifiuta",
+            Japanese => "断る",
+            Russian => "Отклонить",
+            SimplifiedChinese => "拒绝",
+            Spanish => "Rechazar",
+            Vietnamese => "Từ chối",
+        }
+    }
+
+    fn duel_withdraw_hint(self) -> &'static str {
+        match self {
+            Arabic => "اسحب التحدي",
+            Bork => "Unbork",
+            English => "Withdraw challenge",
+            French => "Retirer le défi",
+            German => "Herausforderung zurückziehen",
+            Hindi => "चुनौती वापस लें",
+            Italian => "Ritira la sfida",
+            Japanese => "挑戦を取り下げる",
+            Russian => "Отозвать вызов",
+            SimplifiedChinese => "撤回挑战",
+            Spanish => "Retirar el reto",
+            Vietnamese => "Rút lại lời thách đấu",
+        }
+    }
+
+    fn duel_active_hint(self, pot: u32) -> String {
+        match self {
+            Arabic => format!("أغرقهم لتربح {pot} نقطة"),
+            Bork => format!("Bork them to win {pot} bork points"),
+            English => format!("Sink them to win {pot} points"),
+            French => format!("Coulez-les pour gagner {pot} points"),
+            German => format!("Versenke sie, um {pot} Punkte zu gewinnen"),
+            Hindi => format!("{pot} अंक जीतने के लिए उन्हें डुबोएं"),
+            Italian => format!("Affondali per vincere {pot} punti"),
+            Japanese => format!("相手を沈めて{pot}ポイントを獲得"),
+            Russian => format!("Потопите их, чтобы выиграть {pot} очков"),
+            SimplifiedChinese => format!("击沉对方即可赢得 {pot} 分"),
+            Spanish => format!("Húndelos para ganar {pot} puntos"),
+            Vietnamese => format!("Đánh chìm họ để thắng {pot} điểm"),
+        }
+    }
+
+    fn escort_label(self) -> &'static str {
+        match self {
+            Arabic => "مرافقة",
+            Bork => "Bork guards",
+            English => "Escorts",
+            French => "Escortes",
+            German => "Geleitschutz",
+            Hindi => "अनुरक्षण",
+            Italian => "Scorte",
+            Japanese => "護衛",
+            Russian => "Конвой",
+            SimplifiedChinese => "护航",
+            Spanish => "Escoltas",
+            Vietnamese => "Hộ tống",
+        }
+    }
+
+    fn escort_offer_hint(self, share: u8) -> String {
+        match self {
+            Arabic => format!("اعرض {share}% من أجر الشحنة مقابل مرافقتها"),
+            Bork => format!("Give {share}% of the bork cargo for guarding it"),
+            English => format!("Offer {share}% of the cargo's payout to escort it"),
+            French => format!("Offrir {share}% de la rémunération de la cargaison pour l'escorter"),
+            German => format!("{share}% der Frachtvergütung für Geleitschutz anbieten"),
+            Hindi => format!("माल की रखवाली के लिए उसके भुगतान का {share}% प्रस्तावित करें"),
+            Italian => format!("Offri il {share}% del compenso del carico per scortarlo"),
+            Japanese => format!("積荷の報酬の{share}%で護衛を依頼する"),
+            Russian => format!("Предложить {share}% оплаты за груз за его сопровождение"),
+            SimplifiedChinese => format!("以货物报酬的 {share}% 雇佣护航"),
+            Spanish => format!("Ofrecer el {share}% del pago de la carga por escoltarla"),
+            Vietnamese => format!("Trả {share}% tiền công hàng hóa để được hộ tống"),
+        }
+    }
+
+    fn escort_accept_hint(self, share: u8) -> String {
+        match self {
+            Arabic => format!("اقبل مرافقة شحنتهم مقابل {share}% من أجرها"),
+            Bork => format!("Guard their bork cargo for {share}%"),
+            English => format!("Accept escorting their cargo for {share}% of its payout"),
+            French => format!("Accepter d'escorter leur cargaison pour {share}% de sa rémunération"),
+            German => format!("Geleitschutz für ihre Fracht für {share}% der Vergütung annehmen"),
+            Hindi => format!("उनके माल के भुगतान के {share}% के लिए रखवाली स्वीकार करें"),
+            Italian => format!("Accetta di scortare il loro carico per il {share}% del compenso"),
+            Japanese => format!("報酬の{share}%で積荷の護衛を引き受ける"),
+            Russian => format!("Согласиться сопровождать их груз за {share}% оплаты"),
+            SimplifiedChinese => format!("接受以报酬的 {share}% 为其货物护航"),
+            Spanish => format!("Aceptar escoltar su carga por el {share}% del pago"),
+            Vietnamese => format!("Nhận hộ tống hàng hóa của họ với {share}% tiền công"),
+        }
+    }
+
+    fn escort_cancel_hint(self) -> &'static str {
+        match self {
+            Arabic => "ألغِ",
+            Bork => "Unbork",
+            English => "Cancel",
+            French => "Annuler",
+            German => "Abbrechen",
+            Hindi => "रद्द करें",
+            Italian => "Annulla",
+            Japanese => "取り消す",
+            Russian => "Отменить",
+            SimplifiedChinese => "取消",
+            Spanish => "Cancelar",
+            Vietnamese => "Hủy",
+        }
+    }
+
+    fn escort_active_hint(self, share: u8, attackers_sunk: u8) -> String {
+        match self {
+            Arabic => format!("تذهب {share}% من الأجر إلى المرافق عند التسليم. المهاجمون المُغرَقون: {attackers_sunk}"),
+            Bork => format!("{share}% goes to the guard on delivery. Attackers borked: {attackers_sunk}"),
+            English => format!("{share}% of the payout goes to the escort on delivery. Attackers sunk: {attackers_sunk}"),
+            French => format!("{share}% de la rémunération revient à l'escorte à la livraison. Attaquants coulés : {attackers_sunk}"),
+            German => format!("{share}% der Vergütung gehen bei Lieferung an den Geleitschutz. Versenkte Angreifer: {attackers_sunk}"),
+            Hindi => format!("डिलीवरी पर भुगतान का {share}% अनुरक्षक को मिलता है। डुबोए गए हमलावर: {attackers_sunk}"),
+            Italian => format!("Il {share}% del compenso va alla scorta alla consegna. Attaccanti affondati: {attackers_sunk}"),
+            Japanese => format!("配達時に報酬の{share}%が護衛に支払われる。撃沈した襲撃者: {attackers_sunk}"),
+            Russian => format!("{share}% оплаты получает сопровождающий при доставке. Потоплено нападающих: {attackers_sunk}"),
+            SimplifiedChinese => format!("交付时报酬的 {share}% 归护航者。已击沉袭击者：{attackers_sunk}"),
+            Spanish => format!("El {share}% del pago es para la escolta al entregar. Atacantes hundidos: {attackers_sunk}"),
+            Vietnamese => format!("{share}% tiền công thuộc về người hộ tống khi giao hàng. Kẻ tấn công bị đánh chìm: {attackers_sunk}"),
+        }
+    }
+
     fn entity_aircraft_heli_name(self) -> &'static str {
         match self {
             Arabic => "هليكوبتر",
@@ -1470,6 +2015,23 @@ impl Mk48Translation for LanguageId {
         }
     }
 
+    fn entity_weapon_beam_name(self) -> &'static str {
+        match self {
+            Arabic => "شعاع",
+            Bork => "Beam",
+            English => "Beam",
+            French => "Rayon",
+            German => "Strahl",
+            Hindi => "किरण",
+            Italian => "Raggio",
+            Japanese => "ビーム",
+            Russian => "Луч",
+            SimplifiedChinese => "光束",
+            Spanish => "Rayo",
+            Vietnamese => "Tia",
+        }
+    }
+
     fn entity_weapon_torpedo_name(self) -> &'static str {
         match self {
             Arabic => "نسف",
@@ -1487,6 +2049,23 @@ impl Mk48Translation for LanguageId {
         }
     }
 
+    fn instruction_collision(self) -> &'static str {
+        match self {
+            Arabic => "يابسة أمامك! جارٍ الإبطاء والانعطاف لتجنب الجنوح",
+            Bork => "Land ahead! Slowing and turning bork to not get stuck",
+            English => "Land ahead! Slowing down and steering to avoid running aground",
+            French => "Terre droit devant ! Ralentissement et virage pour éviter l'échouage",
+            German => "Land voraus! Abbremsen und Ausweichen, um nicht aufzulaufen",
+            Hindi => "आगे ज़मीन है! किनारे से टकराने से बचने के लिए धीमा और मोड़ा जा रहा है",
+            Italian => "Terra in vista! Rallento e viro per evitare di incagliarmi",
+            Japanese => "前方に陸地！座礁を避けるため減速・転舵中",
+            Russian => "Впереди земля! Замедляемся и отворачиваем, чтобы не сесть на мель",
+            SimplifiedChinese => "前方有陆地！正在减速并转向以免搁浅",
+            Spanish => "¡Tierra a la vista! Frenando y virando para no encallar",
+            Vietnamese => "Phía trước có đất liền! Đang giảm tốc và chuyển hướng để tránh mắc cạn",
+        }
+    }
+
     fn instruction_basics_mouse(self) -> &'static str {
         match self {
             Arabic => "انقر مع الاستمرار للتحرك ، انقر لإطلاق طوربيدات",
@@ -1539,6 +2118,23 @@ impl Mk48Translation for LanguageId {
         }
     }
 
+    fn instruction_afk(self) -> &'static str {
+        match self {
+            Arabic => "تحرك أو سيتم إغراق سفينتك بسبب عدم النشاط",
+            Bork => "Bork or be borked for sleeping",
+            English => "Move or your boat will be scuttled for inactivity",
+            French => "Bougez ou votre bateau sera sabordé pour inactivité",
+            German => "Bewege dich oder dein Boot wird wegen Inaktivität versenkt",
+            Hindi => "हिलें, नहीं तो निष्क्रियता के कारण आपकी नाव डुबो दी जाएगी",
+            Italian => "Muoviti o la tua barca verrà affondata per inattività",
+            Japanese => "操作しないと、船は自沈します",
+            Russian => "Двигайтесь, иначе ваш корабль будет затоплен из-за бездействия",
+            SimplifiedChinese => "请移动，否则你的船将因长时间未操作而被凿沉",
+            Spanish => "Muévete o tu barco será hundido por inactividad",
+            Vietnamese => "Hãy di chuyển nếu không thuyền sẽ bị đánh chìm vì không hoạt động",
+        }
+    }
+
     fn instruction_zoom_touch(self) -> &'static str {
         match self {
             Arabic => "قرصة للتصغير للحصول على عرض أفضل",
@@ -1572,8 +2168,7 @@ impl Mk48Translation for LanguageId {
         }
     }
 
-    fn sensor_active_hint(self, sensors: &str) -> String {
-        let key = ACTIVE_KEY;
+    fn sensor_active_hint(self, sensors: &str, key: Key) -> String {
         match self {
             English => format!("({key}) Active {sensors} helps you see more, but may also give away your position"),
             Spanish => format!("({key}) Los {sensors} activos te ayudan a ver más, pero también pueden revelar tu posición"),
@@ -1658,8 +2253,7 @@ impl Mk48Translation for LanguageId {
         }
     }
 
-    fn ship_surface_hint(self) -> String {
-        let key = SURFACE_KEY;
+    fn ship_surface_hint(self, key: Key) -> String {
         match self {
             Arabic => format!("({key}) يمكنك سطح سفينتك وقتما تشاء ، لكن الغوص أحيانًا يكون مقيدًا بعمق الماء"),
             Bork => format!("({key}) Bork can surface wherever bork wants, but diving is sometimes limited by the depth of the water"),
@@ -1676,6 +2270,980 @@ impl Mk48Translation for LanguageId {
         }
     }
 
+    fn ship_periscope_label(self) -> &'static str {
+        match self {
+            Arabic => "عمق المنظار",
+            Bork => "Peek bork",
+            English => "Periscope depth",
+            French => "Immersion périscopique",
+            German => "Sehrohrtiefe",
+            Hindi => "पेरिस्कोप गहराई",
+            Italian => "Quota periscopica",
+            Japanese => "潜望鏡深度",
+            Russian => "Перископная глубина",
+            SimplifiedChinese => "潜望镜深度",
+            Spanish => "Cota periscópica",
+            Vietnamese => "Độ sâu kính tiềm vọng",
+        }
+    }
+
+    fn ship_periscope_hint(self, key: Key) -> String {
+        match self {
+            Arabic => format!("({key}) ابقَ قرب السطح عند الغوص لترى وتطلق الصواريخ، مع كشف الصاري فقط"),
+            Bork => format!("({key}) Bork stays just under the water, so bork can see and launch missiles"),
+            English => format!("({key}) Stay just below the surface when diving, exposing only your mast, so you can see and launch missiles"),
+            French => format!("({key}) Restez juste sous la surface en plongée, en n'exposant que votre mât, pour voir et lancer des missiles"),
+            German => format!("({key}) Beim Tauchen dicht unter der Oberfläche bleiben, nur mit dem Mast sichtbar, um zu sehen und Raketen zu starten"),
+            Hindi => format!("({key}) गोता लगाते समय सतह के ठीक नीचे रहें, केवल मस्तूल दिखे, ताकि आप देख सकें और मिसाइलें दाग सकें"),
+            Italian => format!("({key}) Resta appena sotto la superficie in immersione, esponendo solo l'albero, per vedere e lanciare missili"),
+            Japanese => format!("({key}) 潜航時に水面直下に留まり、マストだけを出して視認やミサイル発射ができる"),
+            Russian => format!("({key}) Держаться у самой поверхности при погружении, выставив лишь мачту, чтобы видеть и запускать ракеты"),
+            SimplifiedChinese => format!("({key}) 潜航时停留在水面下方，仅露出桅杆，从而可以观察并发射导弹"),
+            Spanish => format!("({key}) Quédate justo bajo la superficie al sumergirte, exponiendo solo el mástil, para ver y lanzar misiles"),
+            Vietnamese => format!("({key}) Ở ngay dưới mặt nước khi lặn, chỉ để lộ cột buồm, để có thể quan sát và phóng tên lửa"),
+        }
+    }
+
+    fn ship_return_to_port_label(self) -> &'static str {
+        match self {
+            Arabic => "العودة إلى الميناء",
+            Bork => "Go home",
+            English => "Return to port",
+            French => "Retour au port",
+            German => "Zum Hafen",
+            Hindi => "बंदरगाह लौटें",
+            Italian => "Torna in porto",
+            Japanese => "帰港",
+            Russian => "В порт",
+            SimplifiedChinese => "返回港口",
+            Spanish => "Volver a puerto",
+            Vietnamese => "Về cảng",
+        }
+    }
+
+    fn ship_return_to_port_hint(self, banked_coins: u32) -> String {
+        let percent = (ReturnToPort::BANK_FRACTION * 100.0) as u32;
+        let seconds = ReturnToPort::CHANNEL.as_secs();
+        match self {
+            Arabic => format!("ابقَ بعيدًا عن الأعداء لمدة {seconds} ثانية لادخار {percent}% من نقاطك كعملات (المدخر: {banked_coins})"),
+            Bork => format!("Stay away from borks for {seconds} seconds to save {percent}% of bork points (saved: {banked_coins})"),
+            English => format!("Stay away from enemies for {seconds} seconds to bank {percent}% of your score as coins (banked: {banked_coins})"),
+            French => format!("Restez loin des ennemis pendant {seconds} secondes pour mettre en banque {percent}% de votre score en pièces (en banque : {banked_coins})"),
+            German => format!("Halte dich {seconds} Sekunden von Gegnern fern, um {percent}% deiner Punkte als Münzen zu sparen (gespart: {banked_coins})"),
+            Hindi => format!("अपने स्कोर का {percent}% सिक्कों के रूप में जमा करने के लिए {seconds} सेकंड तक दुश्मनों से दूर रहें (जमा: {banked_coins})"),
+            Italian => format!("Stai lontano dai nemici per {seconds} secondi per mettere da parte il {percent}% del punteggio in monete (da parte: {banked_coins})"),
+            Japanese => format!("{seconds}秒間敵から離れると、スコアの{percent}%をコインとして貯められます（貯金：{banked_coins}）"),
+            Russian => format!("Держитесь подальше от врагов {seconds} секунд, чтобы сохранить {percent}% очков в виде монет (сохранено: {banked_coins})"),
+            SimplifiedChinese => format!("远离敌人 {seconds} 秒，即可将 {percent}% 的分数存为硬币（已存：{banked_coins}）"),
+            Spanish => format!("Aléjate de los enemigos durante {seconds} segundos para guardar el {percent}% de tu puntuación en monedas (guardadas: {banked_coins})"),
+            Vietnamese => format!("Tránh xa kẻ thù trong {seconds} giây để gửi {percent}% điểm thành xu (đã gửi: {banked_coins})"),
+        }
+    }
+
+    fn ship_scuttle_label(self) -> &'static str {
+        match self {
+            Arabic => "إغراق السفينة",
+            Bork => "Bork boat",
+            English => "Scuttle",
+            French => "Saborder",
+            German => "Selbst versenken",
+            Hindi => "जहाज़ डुबोएँ",
+            Italian => "Autoaffonda",
+            Japanese => "自沈",
+            Russian => "Затопить",
+            SimplifiedChinese => "凿沉",
+            Spanish => "Hundir",
+            Vietnamese => "Tự đánh chìm",
+        }
+    }
+
+    fn ship_scuttle_hint(self) -> String {
+        let hold = Scuttle::HOLD;
+        let seconds = Scuttle::DELAY.as_secs();
+        let cooldown = Scuttle::COMBAT_COOLDOWN.to_secs() as u32;
+        match self {
+            Arabic => format!("اضغط مطولًا لمدة {hold} ثانية لإغراق سفينتك بعد {seconds} ثانية، مع ترك القليل من الغنائم. غير ممكن خلال {cooldown} ثانية من تلقي الضرر"),
+            Bork => format!("Hold {hold} seconds to bork own boat in {seconds} seconds, leaving few treats. No borking within {cooldown} seconds of getting borked"),
+            English => format!("Hold for {hold} seconds to sink your own boat after {seconds} seconds, leaving little loot. Not possible within {cooldown} seconds of taking damage"),
+            French => format!("Maintenez {hold} secondes pour saborder votre navire après {seconds} secondes, en laissant peu de butin. Impossible dans les {cooldown} secondes suivant des dégâts"),
+            German => format!("{hold} Sekunden halten, um dein Schiff nach {seconds} Sekunden selbst zu versenken, mit wenig Beute. Nicht möglich innerhalb von {cooldown} Sekunden nach erlittenem Schaden"),
+            Hindi => format!("{seconds} सेकंड बाद अपना जहाज़ डुबोने के लिए {hold} सेकंड तक दबाए रखें, थोड़ी ही लूट बचेगी। नुकसान होने के {cooldown} सेकंड के भीतर संभव नहीं"),
+            Italian => format!("Tieni premuto per {hold} secondi per autoaffondare la nave dopo {seconds} secondi, lasciando poco bottino. Non possibile entro {cooldown} secondi dall'aver subito danni"),
+            Japanese => format!("{hold}秒間長押しすると、{seconds}秒後に自船を自沈させます（戦利品は少なくなります）。ダメージを受けてから{cooldown}秒間は使えません"),
+            Russian => format!("Удерживайте {hold} секунды, чтобы затопить свой корабль через {seconds} секунд, оставив мало добычи. Недоступно в течение {cooldown} секунд после получения урона"),
+            SimplifiedChinese => format!("按住 {hold} 秒，{seconds} 秒后凿沉自己的船，只留下少量战利品。受到伤害后 {cooldown} 秒内无法使用"),
+            Spanish => format!("Mantén pulsado {hold} segundos para hundir tu barco tras {seconds} segundos, dejando poco botín. No es posible en los {cooldown} segundos tras recibir daño"),
+            Vietnamese => format!("Giữ {hold} giây để tự đánh chìm tàu sau {seconds} giây, để lại ít chiến lợi phẩm. Không thể dùng trong {cooldown} giây sau khi chịu sát thương"),
+        }
+    }
+
+    fn ship_damage_control_label(self) -> &'static str {
+        match self {
+            Arabic => "التحكم في الأضرار",
+            Bork => "Fix bork",
+            English => "Damage control",
+            French => "Contrôle des avaries",
+            German => "Schadensbegrenzung",
+            Hindi => "क्षति नियंत्रण",
+            Italian => "Controllo danni",
+            Japanese => "ダメージコントロール",
+            Russian => "Борьба за живучесть",
+            SimplifiedChinese => "损管",
+            Spanish => "Control de daños",
+            Vietnamese => "Kiểm soát thiệt hại",
+        }
+    }
+
+    fn ship_damage_control_hint(self) -> &'static str {
+        match self {
+            Arabic => "أخمد الحرائق وأوقف الغمر أسرع، لكن أعد التعبئة أبطأ",
+            Bork => "Fix fires and leaks faster, but bork slower",
+            English => "Put out fires and stop flooding faster, but reload slower",
+            French => "Éteignez les incendies et arrêtez l'inondation plus vite, mais rechargez plus lentement",
+            German => "Brände löschen und Wassereinbruch schneller stoppen, aber langsamer nachladen",
+            Hindi => "आग जल्दी बुझाएं और बाढ़ जल्दी रोकें, लेकिन धीरे रीलोड करें",
+            Italian => "Spegni gli incendi e ferma gli allagamenti più in fretta, ma ricarica più lentamente",
+            Japanese => "消火と浸水対策が速くなるが、装填が遅くなる",
+            Russian => "Быстрее тушить пожары и откачивать воду, но медленнее перезаряжаться",
+            SimplifiedChinese => "更快灭火和止住进水，但装填更慢",
+            Spanish => "Apaga incendios y detén inundaciones más rápido, pero recarga más lento",
+            Vietnamese => "Dập lửa và ngăn ngập nước nhanh hơn, nhưng nạp đạn chậm hơn",
+        }
+    }
+
+    fn ship_fire_label(self) -> &'static str {
+        match self {
+            Arabic => "حريق",
+            Bork => "Hot bork",
+            English => "On fire",
+            French => "En feu",
+            German => "In Brand",
+            Hindi => "आग लगी है",
+            Italian => "In fiamme",
+            Japanese => "火災",
+            Russian => "Пожар",
+            SimplifiedChinese => "起火",
+            Spanish => "En llamas",
+            Vietnamese => "Đang cháy",
+        }
+    }
+
+    fn ship_flooding_label(self) -> &'static str {
+        match self {
+            Arabic => "غمر",
+            Bork => "Wet bork",
+            English => "Flooding",
+            French => "Inondation",
+            German => "Wassereinbruch",
+            Hindi => "बाढ़",
+            Italian => "Allagamento",
+            Japanese => "浸水",
+            Russian => "Затопление",
+            SimplifiedChinese => "进水",
+            Spanish => "Inundación",
+            Vietnamese => "Ngập nước",
+        }
+    }
+
+    fn ship_repair_crew_label(self) -> &'static str {
+        match self {
+            Arabic => "طاقم الإصلاح",
+            Bork => "Bork crew",
+            English => "Repair crew",
+            French => "Équipe de réparation",
+            German => "Reparaturtrupp",
+            Hindi => "मरम्मत दल",
+            Italian => "Squadra di riparazione",
+            Japanese => "修理班",
+            Russian => "Ремонтная бригада",
+            SimplifiedChinese => "维修队",
+            Spanish => "Equipo de reparación",
+            Vietnamese => "Đội sửa chữa",
+        }
+    }
+
+    fn ship_repair_crew_hint(self, key: Key) -> String {
+        match self {
+            Arabic => format!("({key}) أخمد الحرائق وأوقف التسربات فورًا، ثم أصلح أسرع لفترة"),
+            Bork => format!("({key}) Fix fires and leaks right now, then bork faster for a while"),
+            English => format!("({key}) Put out fires and stop leaks immediately, then repair faster for a while"),
+            French => format!("({key}) Éteignez les incendies et colmatez les fuites immédiatement, puis réparez plus vite pendant un moment"),
+            German => format!("({key}) Brände sofort löschen und Lecks abdichten, dann eine Weile schneller reparieren"),
+            Hindi => format!("({key}) आग तुरंत बुझाएं और रिसाव रोकें, फिर कुछ समय तक तेज़ी से मरम्मत करें"),
+            Italian => format!("({key}) Spegni subito gli incendi e tappa le falle, poi ripara più in fretta per un po'"),
+            Japanese => format!("({key}) 火災と浸水を即座に止め、しばらく修理が速くなる"),
+            Russian => format!("({key}) Немедленно потушить пожары и заделать пробоины, затем некоторое время чиниться быстрее"),
+            SimplifiedChinese => format!("({key}) 立即灭火并堵住漏洞，随后一段时间内维修更快"),
+            Spanish => format!("({key}) Apaga incendios y tapa vías de agua al instante, y luego repara más rápido durante un tiempo"),
+            Vietnamese => format!("({key}) Dập lửa và bịt chỗ rò ngay lập tức, sau đó sửa chữa nhanh hơn trong một lúc"),
+        }
+    }
+
+    fn ship_searchlight_label(self) -> &'static str {
+        match self {
+            Arabic => "كشاف",
+            Bork => "Bork light",
+            English => "Searchlight",
+            French => "Projecteur",
+            German => "Suchscheinwerfer",
+            Hindi => "सर्चलाइट",
+            Italian => "Proiettore",
+            Japanese => "探照灯",
+            Russian => "Прожектор",
+            SimplifiedChinese => "探照灯",
+            Spanish => "Reflector",
+            Vietnamese => "Đèn pha",
+        }
+    }
+
+    fn ship_searchlight_hint(self, key: Key) -> String {
+        match self {
+            Arabic => format!("({key}) أضئ ما تصوب نحوه ليلًا، لكنك تكشف موقعك"),
+            Bork => format!("({key}) See what bork aims at in the dark, but everyone sees bork"),
+            English => format!("({key}) Light up where you aim at night, but give away your position"),
+            French => format!("({key}) Éclairez la nuit là où vous visez, mais révélez votre position"),
+            German => format!("({key}) Nachts das Ziel beleuchten, aber die eigene Position verraten"),
+            Hindi => format!("({key}) रात में जहां निशाना लगाएं वहां रोशनी करें, लेकिन अपनी स्थिति उजागर करें"),
+            Italian => format!("({key}) Illumina di notte dove miri, ma rivela la tua posizione"),
+            Japanese => format!("({key}) 夜間に照準先を照らすが、自分の位置も知られる"),
+            Russian => format!("({key}) Освещать ночью место прицеливания, но выдать свою позицию"),
+            SimplifiedChinese => format!("({key}) 夜间照亮瞄准的方向，但会暴露自己的位置"),
+            Spanish => format!("({key}) Ilumina de noche hacia donde apuntas, pero delata tu posición"),
+            Vietnamese => format!("({key}) Chiếu sáng nơi bạn nhắm vào ban đêm, nhưng để lộ vị trí của bạn"),
+        }
+    }
+
+    fn ship_emcon_label(self) -> &'static str {
+        match self {
+            Arabic => "صمت الرادار",
+            Bork => "Quiet bork",
+            English => "Radar silence",
+            French => "Silence radar",
+            German => "Radarstille",
+            Hindi => "रडार मौन",
+            Italian => "Silenzio radar",
+            Japanese => "レーダー封止",
+            Russian => "Радиомолчание",
+            SimplifiedChinese => "雷达静默",
+            Spanish => "Silencio de radar",
+            Vietnamese => "Tắt radar",
+        }
+    }
+
+    fn ship_emcon_hint(self, key: Key) -> String {
+        match self {
+            Arabic => format!("({key}) أطفئ الرادار النشط حتى لا يكشف موقعك، مع استمرار التحذير من الرادارات المعادية"),
+            Bork => format!("({key}) Stop borking radar so nobody hears bork, but still hear their radar"),
+            English => format!("({key}) Turn off active radar so it doesn't give away your position, while still warning of enemy radar"),
+            French => format!("({key}) Coupez le radar actif pour ne pas révéler votre position, tout en restant averti des radars ennemis"),
+            German => format!("({key}) Aktives Radar abschalten, um die eigene Position nicht zu verraten, aber weiterhin vor feindlichem Radar warnen"),
+            Hindi => format!("({key}) सक्रिय रडार बंद करें ताकि आपकी स्थिति उजागर न हो, फिर भी दुश्मन रडार की चेतावनी मिलती रहे"),
+            Italian => format!("({key}) Spegni il radar attivo per non rivelare la tua posizione, continuando a ricevere avvisi dai radar nemici"),
+            Japanese => format!("({key}) アクティブレーダーを止めて位置を隠す。敵レーダーの警告は引き続き受け取る"),
+            Russian => format!("({key}) Выключить активный радар, чтобы не выдать свою позицию, сохраняя предупреждения о вражеских радарах"),
+            SimplifiedChinese => format!("({key}) 关闭主动雷达以免暴露位置，同时仍能收到敌方雷达告警"),
+            Spanish => format!("({key}) Apaga el radar activo para no delatar tu posición, sin dejar de recibir avisos de radares enemigos"),
+            Vietnamese => format!("({key}) Tắt radar chủ động để không lộ vị trí, nhưng vẫn được cảnh báo radar địch"),
+        }
+    }
+
+    fn ship_cruise_label(self) -> &'static str {
+        match self {
+            Arabic => "مثبت السرعة",
+            Bork => "Lazy bork",
+            English => "Cruise control",
+            French => "Régulateur de vitesse",
+            German => "Tempomat",
+            Hindi => "क्रूज़ कंट्रोल",
+            Italian => "Controllo velocità",
+            Japanese => "定速航行",
+            Russian => "Круиз-контроль",
+            SimplifiedChinese => "定速巡航",
+            Spanish => "Control de crucero",
+            Vietnamese => "Giữ tốc độ",
+        }
+    }
+
+    fn ship_cruise_hint(self, key: Key) -> String {
+        match self {
+            Arabic => format!("({key}) حافظ على سرعتك الحالية أثناء الانعطاف، حتى تغيرها بلوحة المفاتيح"),
+            Bork => format!("({key}) Keep borking same speed around corners, until keyboard says otherwise"),
+            English => format!("({key}) Hold your current speed through turns, until you change it with the keyboard"),
+            French => format!("({key}) Maintenez votre vitesse actuelle dans les virages, jusqu'à la changer au clavier"),
+            German => format!("({key}) Aktuelle Geschwindigkeit in Kurven halten, bis du sie mit der Tastatur änderst"),
+            Hindi => format!("({key}) मोड़ों पर अपनी मौजूदा गति बनाए रखें, जब तक आप कीबोर्ड से उसे न बदलें"),
+            Italian => format!("({key}) Mantieni la velocità attuale in virata, finché non la cambi con la tastiera"),
+            Japanese => format!("({key}) キーボードで変えるまで、旋回中も現在の速度を保つ"),
+            Russian => format!("({key}) Сохранять текущую скорость на поворотах, пока вы не измените её с клавиатуры"),
+            SimplifiedChinese => format!("({key}) 转向时保持当前速度，直到用键盘改变速度"),
+            Spanish => format!("({key}) Mantén tu velocidad actual en los giros, hasta que la cambies con el teclado"),
+            Vietnamese => format!("({key}) Giữ tốc độ hiện tại khi rẽ, cho đến khi bạn đổi bằng bàn phím"),
+        }
+    }
+
+    fn ship_release_target_label(self) -> &'static str {
+        match self {
+            Arabic => "إلغاء تثبيت الهدف",
+            Bork => "Let go of bork",
+            English => "Release target",
+            French => "Relâcher la cible",
+            German => "Ziel freigeben",
+            Hindi => "लक्ष्य छोड़ें",
+            Italian => "Rilascia bersaglio",
+            Japanese => "目標ロック解除",
+            Russian => "Сбросить цель",
+            SimplifiedChinese => "解除目标锁定",
+            Spanish => "Soltar objetivo",
+            Vietnamese => "Bỏ khóa mục tiêu",
+        }
+    }
+
+    fn ship_release_target_hint(self, key: Key) -> String {
+        match self {
+            Arabic => format!("({key}) صواريخك الموجهة تُطلق نحو الهدف المثبت. اضغط على زر الفأرة الأوسط أو ({key}) قرب عدو لتثبيته"),
+            Bork => format!("({key}) Smart booms bork at locked target. Middle click, or ({key}), near a baddie to lock it"),
+            English => format!("({key}) Guided weapons are launched at the locked target. Middle click, or press ({key}), near an enemy to lock onto it"),
+            French => format!("({key}) Les armes guidées sont lancées vers la cible verrouillée. Clic du milieu, ou ({key}), près d'un ennemi pour le verrouiller"),
+            German => format!("({key}) Lenkwaffen werden auf das erfasste Ziel abgefeuert. Mittelklick oder ({key}) nahe einem Gegner, um ihn zu erfassen"),
+            Hindi => format!("({key}) निर्देशित हथियार लॉक किए गए लक्ष्य पर दागे जाते हैं। किसी दुश्मन को लॉक करने के लिए उसके पास मिडिल क्लिक करें, या ({key}) दबाएँ"),
+            Italian => format!("({key}) Le armi guidate vengono lanciate verso il bersaglio agganciato. Clic centrale, o ({key}), vicino a un nemico per agganciarlo"),
+            Japanese => format!("({key}) 誘導兵器はロックした目標に向けて発射されます。敵の近くで中クリック、または({key})でロック"),
+            Russian => format!("({key}) Управляемое оружие запускается по захваченной цели. Щёлкните средней кнопкой или нажмите ({key}) рядом с врагом, чтобы захватить его"),
+            SimplifiedChinese => format!("({key}) 制导武器会朝锁定的目标发射。在敌人附近点击鼠标中键或按 ({key}) 以锁定"),
+            Spanish => format!("({key}) Las armas guiadas se lanzan hacia el objetivo fijado. Clic central, o ({key}), cerca de un enemigo para fijarlo"),
+            Vietnamese => format!("({key}) Vũ khí dẫn đường được phóng về mục tiêu đã khóa. Nhấp chuột giữa, hoặc nhấn ({key}), gần kẻ địch để khóa"),
+        }
+    }
+
+    fn ship_unlock_turrets_label(self) -> &'static str {
+        match self {
+            Arabic => "فك قفل الأبراج",
+            Bork => "Unstuck turrets",
+            English => "Unlock turrets",
+            French => "Déverrouiller les tourelles",
+            German => "Türme entsperren",
+            Hindi => "बुर्ज अनलॉक करें",
+            Italian => "Sblocca torrette",
+            Japanese => "砲塔のロック解除",
+            Russian => "Разблокировать башни",
+            SimplifiedChinese => "解锁炮塔",
+            Spanish => "Desbloquear torretas",
+            Vietnamese => "Mở khóa tháp pháo",
+        }
+    }
+
+    fn ship_unlock_turrets_hint(self, key: Key) -> String {
+        match self {
+            Arabic => format!("({key}) بعض الأبراج مقفلة على اتجاه ثابت. فك قفلها لتتبع تصويبك مجددًا"),
+            Bork => format!("({key}) Some turrets stare at one spot. Unstuck them to follow your bork again"),
+            English => format!("({key}) Some turrets are locked at a fixed bearing. Unlock them to follow your aim again"),
+            French => format!("({key}) Certaines tourelles sont verrouillées sur un cap fixe. Déverrouillez-les pour qu'elles suivent à nouveau votre visée"),
+            German => format!("({key}) Einige Türme sind auf eine feste Peilung gesperrt. Entsperre sie, damit sie wieder deinem Ziel folgen"),
+            Hindi => format!("({key}) कुछ बुर्ज एक तय दिशा पर लॉक हैं। उन्हें फिर से अपने निशाने के साथ घुमाने के लिए अनलॉक करें"),
+            Italian => format!("({key}) Alcune torrette sono bloccate su un rilevamento fisso. Sbloccale per farle seguire di nuovo la tua mira"),
+            Japanese => format!("({key}) 一部の砲塔が固定方位にロックされています。ロックを解除すると再び照準に追従します"),
+            Russian => format!("({key}) Некоторые башни зафиксированы на постоянном пеленге. Разблокируйте их, чтобы они снова следовали за прицелом"),
+            SimplifiedChinese => format!("({key}) 部分炮塔已锁定在固定方位。解锁后它们会重新跟随你的瞄准"),
+            Spanish => format!("({key}) Algunas torretas están bloqueadas en un rumbo fijo. Desbloquéalas para que vuelvan a seguir tu puntería"),
+            Vietnamese => format!("({key}) Một số tháp pháo đang khóa ở hướng cố định. Mở khóa để chúng lại bám theo điểm ngắm"),
+        }
+    }
+
+    fn cosmetic_flag_label(self) -> &'static str {
+        match self {
+            Arabic => "العلم",
+            Bork => "Bork flag",
+            English => "Flag",
+            French => "Drapeau",
+            German => "Flagge",
+            Hindi => "ध्वज",
+            Italian => "Bandiera",
+            Japanese => "旗",
+            Russian => "Флаг",
+            SimplifiedChinese => "旗帜",
+            Spanish => "Bandera",
+            Vietnamese => "Cờ",
+        }
+    }
+
+    fn cosmetic_flag_none(self) -> &'static str {
+        match self {
+            Arabic => "بلا علم",
+            Bork => "No flag",
+            English => "No flag",
+            French => "Aucun drapeau",
+            German => "Keine Flagge",
+            Hindi => "कोई ध्वज नहीं",
+            Italian => "Nessuna bandiera",
+            Japanese => "旗なし",
+            Russian => "Без флага",
+            SimplifiedChinese => "无旗帜",
+            Spanish => "Sin bandera",
+            Vietnamese => "Không cờ",
+        }
+    }
+
+    fn cosmetic_flag_naval(self) -> &'static str {
+        match self {
+            Arabic => "علم بحري",
+            Bork => "Navy bork flag",
+            English => "Naval flag",
+            French => "Pavillon naval",
+            German => "Marineflagge",
+            Hindi => "नौसेना ध्वज",
+            Italian => "Bandiera navale",
+            Japanese => "軍艦旗",
+            Russian => "Военно-морской флаг",
+            SimplifiedChinese => "海军旗",
+            Spanish => "Bandera naval",
+            Vietnamese => "Cờ hải quân",
+        }
+    }
+
+    fn cosmetic_flag_signal(self) -> &'static str {
+        match self {
+            Arabic => "علم إشارات",
+            Bork => "Bork signal flag",
+            English => "Signal flag",
+            French => "Pavillon de signalisation",
+            German => "Signalflagge",
+            Hindi => "संकेत ध्वज",
+            Italian => "Bandiera di segnalazione",
+            Japanese => "信号旗",
+            Russian => "Сигнальный флаг",
+            SimplifiedChinese => "信号旗",
+            Spanish => "Bandera de señales",
+            Vietnamese => "Cờ hiệu",
+        }
+    }
+
+    fn cosmetic_flag_checkered(self) -> &'static str {
+        match self {
+            Arabic => "علم مربعات",
+            Bork => "Checkered bork flag",
+            English => "Checkered flag",
+            French => "Drapeau à damier",
+            German => "Zielflagge",
+            Hindi => "चेकर्ड ध्वज",
+            Italian => "Bandiera a scacchi",
+            Japanese => "チェッカーフラッグ",
+            Russian => "Клетчатый флаг",
+            SimplifiedChinese => "方格旗",
+            Spanish => "Bandera a cuadros",
+            Vietnamese => "Cờ ca-rô",
+        }
+    }
+
+    fn cosmetic_flag_pirate(self) -> &'static str {
+        match self {
+            Arabic => "علم قراصنة",
+            Bork => "Pirate bork flag",
+            English => "Pirate flag",
+            French => "Drapeau pirate",
+            German => "Piratenflagge",
+            Hindi => "समुद्री डाकू ध्वज",
+            Italian => "Bandiera pirata",
+            Japanese => "海賊旗",
+            Russian => "Пиратский флаг",
+            SimplifiedChinese => "海盗旗",
+            Spanish => "Bandera pirata",
+            Vietnamese => "Cờ cướp biển",
+        }
+    }
+
+    fn cosmetic_flag_gold(self) -> &'static str {
+        match self {
+            Arabic => "علم ذهبي",
+            Bork => "Shiny bork flag",
+            English => "Gold flag",
+            French => "Drapeau doré",
+            German => "Goldene Flagge",
+            Hindi => "स्वर्ण ध्वज",
+            Italian => "Bandiera d'oro",
+            Japanese => "金の旗",
+            Russian => "Золотой флаг",
+            SimplifiedChinese => "金色旗帜",
+            Spanish => "Bandera dorada",
+            Vietnamese => "Cờ vàng",
+        }
+    }
+
+    fn cosmetic_paint_label(self) -> &'static str {
+        match self {
+            Arabic => "طلاء الهيكل",
+            Bork => "Bork paint",
+            English => "Hull paint",
+            French => "Peinture de coque",
+            German => "Rumpflackierung",
+            Hindi => "पतवार का रंग",
+            Italian => "Vernice dello scafo",
+            Japanese => "船体塗装",
+            Russian => "Окраска корпуса",
+            SimplifiedChinese => "船体涂装",
+            Spanish => "Pintura del casco",
+            Vietnamese => "Sơn thân tàu",
+        }
+    }
+
+    fn cosmetic_paint_standard(self) -> &'static str {
+        match self {
+            Arabic => "طلاء قياسي",
+            Bork => "Normal bork paint",
+            English => "Standard paint",
+            French => "Peinture standard",
+            German => "Standardlackierung",
+            Hindi => "मानक रंग",
+            Italian => "Vernice standard",
+            Japanese => "標準塗装",
+            Russian => "Стандартная окраска",
+            SimplifiedChinese => "标准涂装",
+            Spanish => "Pintura estándar",
+            Vietnamese => "Sơn tiêu chuẩn",
+        }
+    }
+
+    fn cosmetic_paint_arctic(self) -> &'static str {
+        match self {
+            Arabic => "طلاء قطبي",
+            Bork => "Snowy bork paint",
+            English => "Arctic paint",
+            French => "Peinture arctique",
+            German => "Arktische Lackierung",
+            Hindi => "आर्कटिक रंग",
+            Italian => "Vernice artica",
+            Japanese => "北極塗装",
+            Russian => "Арктическая окраска",
+            SimplifiedChinese => "极地涂装",
+            Spanish => "Pintura ártica",
+            Vietnamese => "Sơn bắc cực",
+        }
+    }
+
+    fn cosmetic_paint_desert(self) -> &'static str {
+        match self {
+            Arabic => "طلاء صحراوي",
+            Bork => "Sandy bork paint",
+            English => "Desert paint",
+            French => "Peinture désert",
+            German => "Wüstenlackierung",
+            Hindi => "रेगिस्तानी रंग",
+            Italian => "Vernice desertica",
+            Japanese => "砂漠塗装",
+            Russian => "Пустынная окраска",
+            SimplifiedChinese => "沙漠涂装",
+            Spanish => "Pintura desértica",
+            Vietnamese => "Sơn sa mạc",
+        }
+    }
+
+    fn cosmetic_paint_jungle(self) -> &'static str {
+        match self {
+            Arabic => "طلاء الأدغال",
+            Bork => "Leafy bork paint",
+            English => "Jungle paint",
+            French => "Peinture jungle",
+            German => "Dschungellackierung",
+            Hindi => "जंगल रंग",
+            Italian => "Vernice giungla",
+            Japanese => "ジャングル塗装",
+            Russian => "Окраска джунглей",
+            SimplifiedChinese => "丛林涂装",
+            Spanish => "Pintura de jungla",
+            Vietnamese => "Sơn rừng rậm",
+        }
+    }
+
+    fn cosmetic_paint_crimson(self) -> &'static str {
+        match self {
+            Arabic => "طلاء قرمزي",
+            Bork => "Red bork paint",
+            English => "Crimson paint",
+            French => "Peinture cramoisie",
+            German => "Purpurrote Lackierung",
+            Hindi => "गहरा लाल रंग",
+            Italian => "Vernice cremisi",
+            Japanese => "深紅塗装",
+            Russian => "Багровая окраска",
+            SimplifiedChinese => "深红涂装",
+            Spanish => "Pintura carmesí",
+            Vietnamese => "Sơn đỏ thẫm",
+        }
+    }
+
+    fn cosmetic_paint_gold(self) -> &'static str {
+        match self {
+            Arabic => "طلاء ذهبي",
+            Bork => "Shiny bork paint",
+            English => "Gold paint",
+            French => "Peinture dorée",
+            German => "Goldlackierung",
+            Hindi => "स्वर्ण रंग",
+            Italian => "Vernice d'oro",
+            Japanese => "金色塗装",
+            Russian => "Золотая окраска",
+            SimplifiedChinese => "金色涂装",
+            Spanish => "Pintura dorada",
+            Vietnamese => "Sơn vàng",
+        }
+    }
+
+    fn cosmetic_locked(self, name: &str, level: u8) -> String {
+        match self {
+            Arabic => format!("{name} (المستوى {level})"),
+            Bork => format!("{name} (bork level {level})"),
+            English => format!("{name} (level {level})"),
+            French => format!("{name} (niveau {level})"),
+            German => format!("{name} (Stufe {level})"),
+            Hindi => format!("{name} (स्तर {level})"),
+            Italian => format!("{name} (livello {level})"),
+            Japanese => format!("{name}（レベル{level}）"),
+            Russian => format!("{name} (уровень {level})"),
+            SimplifiedChinese => format!("{name}（等级 {level}）"),
+            Spanish => format!("{name} (nivel {level})"),
+            Vietnamese => format!("{name} (cấp {level})"),
+        }
+    }
+
+    fn spawn_region_label(self) -> &'static str {
+        match self {
+            Arabic => "منطقة الظهور",
+            Bork => "Bork spot",
+            English => "Spawn region",
+            French => "Zone d'apparition",
+            German => "Startgebiet",
+            Hindi => "स्पॉन क्षेत्र",
+            Italian => "Zona di partenza",
+            Japanese => "出現地域",
+            Russian => "Район появления",
+            SimplifiedChinese => "出生区域",
+            Spanish => "Zona de aparición",
+            Vietnamese => "Khu vực xuất hiện",
+        }
+    }
+
+    fn spawn_region_any(self) -> &'static str {
+        match self {
+            Arabic => "الظهور في أي مكان",
+            Bork => "Bork anywhere",
+            English => "Spawn anywhere",
+            French => "Apparaître n'importe où",
+            German => "Irgendwo starten",
+            Hindi => "कहीं भी स्पॉन करें",
+            Italian => "Parti ovunque",
+            Japanese => "どこでも出現",
+            Russian => "Появиться где угодно",
+            SimplifiedChinese => "任意位置出生",
+            Spanish => "Aparecer en cualquier lugar",
+            Vietnamese => "Xuất hiện ở bất kỳ đâu",
+        }
+    }
+
+    fn spawn_region_fleet(self) -> &'static str {
+        match self {
+            Arabic => "الظهور قرب الأسطول",
+            Bork => "Bork near borks",
+            English => "Spawn near fleet",
+            French => "Apparaître près de la flotte",
+            German => "Bei der Flotte starten",
+            Hindi => "बेड़े के पास स्पॉन करें",
+            Italian => "Parti vicino alla flotta",
+            Japanese => "艦隊の近くに出現",
+            Russian => "Появиться у флота",
+            SimplifiedChinese => "在舰队附近出生",
+            Spanish => "Aparecer cerca de la flota",
+            Vietnamese => "Xuất hiện gần hạm đội",
+        }
+    }
+
+    fn spawn_region_north(self) -> &'static str {
+        match self {
+            Arabic => "الظهور في الشمال",
+            Bork => "Bork in north",
+            English => "Spawn in north",
+            French => "Apparaître au nord",
+            German => "Im Norden starten",
+            Hindi => "उत्तर में स्पॉन करें",
+            Italian => "Parti a nord",
+            Japanese => "北に出現",
+            Russian => "Появиться на севере",
+            SimplifiedChinese => "在北方出生",
+            Spanish => "Aparecer en el norte",
+            Vietnamese => "Xuất hiện ở phía bắc",
+        }
+    }
+
+    fn spawn_region_east(self) -> &'static str {
+        match self {
+            Arabic => "الظهور في الشرق",
+            Bork => "Bork in east",
+            English => "Spawn in east",
+            French => "Apparaître à l'est",
+            German => "Im Osten starten",
+            Hindi => "पूर्व में स्पॉन करें",
+            Italian => "Parti a est",
+            Japanese => "東に出現",
+            Russian => "Появиться на востоке",
+            SimplifiedChinese => "在东方出生",
+            Spanish => "Aparecer en el este",
+            Vietnamese => "Xuất hiện ở phía đông",
+        }
+    }
+
+    fn spawn_region_south(self) -> &'static str {
+        match self {
+            Arabic => "الظهور في الجنوب",
+            Bork => "Bork in south",
+            English => "Spawn in south",
+            French => "Apparaître au sud",
+            German => "Im Süden starten",
+            Hindi => "दक्षिण में स्पॉन करें",
+            Italian => "Parti a sud",
+            Japanese => "南に出現",
+            Russian => "Появиться на юге",
+            SimplifiedChinese => "在南方出生",
+            Spanish => "Aparecer en el sur",
+            Vietnamese => "Xuất hiện ở phía nam",
+        }
+    }
+
+    fn spawn_region_west(self) -> &'static str {
+        match self {
+            Arabic => "الظهور في الغرب",
+            Bork => "Bork in west",
+            English => "Spawn in west",
+            French => "Apparaître à l'ouest",
+            German => "Im Westen starten",
+            Hindi => "पश्चिम में स्पॉन करें",
+            Italian => "Parti a ovest",
+            Japanese => "西に出現",
+            Russian => "Появиться на западе",
+            SimplifiedChinese => "在西方出生",
+            Spanish => "Aparecer en el oeste",
+            Vietnamese => "Xuất hiện ở phía tây",
+        }
+    }
+
+    fn spawn_hull_stats(self, knots: f32, health: f32) -> String {
+        match self {
+            Arabic => format!("السرعة: {knots:.1} عقدة · الصحة: {health:.2}"),
+            Bork => format!("Zoomies: {knots:.1}kn · Borkiness: {health:.2}"),
+            English => format!("Speed: {knots:.1}kn · Health: {health:.2}"),
+            French => format!("Vitesse : {knots:.1} nd · Santé : {health:.2}"),
+            German => format!("Geschwindigkeit: {knots:.1} kn · Gesundheit: {health:.2}"),
+            Hindi => format!("गति: {knots:.1} नॉट · स्वास्थ्य: {health:.2}"),
+            Italian => format!("Velocità: {knots:.1} nodi · Salute: {health:.2}"),
+            Japanese => format!("速度：{knots:.1}ノット・耐久力：{health:.2}"),
+            Russian => format!("Скорость: {knots:.1} уз · Прочность: {health:.2}"),
+            SimplifiedChinese => format!("速度：{knots:.1} 节 · 生命值：{health:.2}"),
+            Spanish => format!("Velocidad: {knots:.1} nudos · Salud: {health:.2}"),
+            Vietnamese => format!("Tốc độ: {knots:.1} hải lý · Máu: {health:.2}"),
+        }
+    }
+
+    fn terrain_objective_channel(self) -> &'static str {
+        match self {
+            Arabic => "افتح قناة",
+            Bork => "Dig a bork ditch",
+            English => "Open a channel",
+            French => "Ouvrir un chenal",
+            German => "Einen Kanal öffnen",
+            Hindi => "एक नहर खोलें",
+            Italian => "Apri un canale",
+            Japanese => "水路を開く",
+            Russian => "Проложить канал",
+            SimplifiedChinese => "开辟一条水道",
+            Spanish => "Abrir un canal",
+            Vietnamese => "Mở một kênh đào",
+        }
+    }
+
+    fn terrain_objective_island(self) -> &'static str {
+        match self {
+            Arabic => "ارفع جزيرة",
+            Bork => "Make a bork island",
+            English => "Raise an island",
+            French => "Faire émerger une île",
+            German => "Eine Insel aufschütten",
+            Hindi => "एक द्वीप बनाएं",
+            Italian => "Fai emergere un'isola",
+            Japanese => "島を造る",
+            Russian => "Насыпать остров",
+            SimplifiedChinese => "堆起一座岛屿",
+            Spanish => "Levantar una isla",
+            Vietnamese => "Bồi đắp một hòn đảo",
+        }
+    }
+
+    fn signal_attack(self) -> &'static str {
+        match self {
+            Arabic => "هاجموا هنا",
+            Bork => "Bork here",
+            English => "Attack here",
+            French => "Attaquez ici",
+            German => "Hier angreifen",
+            Hindi => "यहाँ हमला करें",
+            Italian => "Attacca qui",
+            Japanese => "ここを攻撃",
+            Russian => "Атакуйте здесь",
+            SimplifiedChinese => "攻击这里",
+            Spanish => "Ataquen aquí",
+            Vietnamese => "Tấn công ở đây",
+        }
+    }
+
+    fn signal_help(self) -> &'static str {
+        match self {
+            Arabic => "أحتاج مساعدة",
+            Bork => "Need borks",
+            English => "Need help",
+            French => "Besoin d'aide",
+            German => "Brauche Hilfe",
+            Hindi => "मदद चाहिए",
+            Italian => "Serve aiuto",
+            Japanese => "救援求む",
+            Russian => "Нужна помощь",
+            SimplifiedChinese => "需要支援",
+            Spanish => "Necesito ayuda",
+            Vietnamese => "Cần giúp đỡ",
+        }
+    }
+
+    fn signal_retreat(self) -> &'static str {
+        match self {
+            Arabic => "أنسحب",
+            Bork => "Running away",
+            English => "Retreating",
+            French => "Repli",
+            German => "Rückzug",
+            Hindi => "पीछे हट रहा हूँ",
+            Italian => "In ritirata",
+            Japanese => "撤退中",
+            Russian => "Отступаю",
+            SimplifiedChinese => "正在撤退",
+            Spanish => "Retirándome",
+            Vietnamese => "Đang rút lui",
+        }
+    }
+
+    fn summary_time_alive(self) -> &'static str {
+        match self {
+            Arabic => "مدة البقاء",
+            Bork => "Time borking",
+            English => "Time alive",
+            French => "Temps de survie",
+            German => "Überlebenszeit",
+            Hindi => "जीवित समय",
+            Italian => "Tempo di sopravvivenza",
+            Japanese => "生存時間",
+            Russian => "Время жизни",
+            SimplifiedChinese => "存活时间",
+            Spanish => "Tiempo vivo",
+            Vietnamese => "Thời gian sống",
+        }
+    }
+
+    fn summary_highest_level(self) -> &'static str {
+        match self {
+            Arabic => "أعلى مستوى",
+            Bork => "Highest bork",
+            English => "Highest level",
+            French => "Niveau maximal",
+            German => "Höchste Stufe",
+            Hindi => "उच्चतम स्तर",
+            Italian => "Livello massimo",
+            Japanese => "最高レベル",
+            Russian => "Высший уровень",
+            SimplifiedChinese => "最高等级",
+            Spanish => "Nivel más alto",
+            Vietnamese => "Cấp cao nhất",
+        }
+    }
+
+    fn summary_kill_score(self) -> &'static str {
+        match self {
+            Arabic => "نقاط القتل",
+            Bork => "Bork score",
+            English => "Kill score",
+            French => "Score d'éliminations",
+            German => "Abschusspunkte",
+            Hindi => "किल स्कोर",
+            Italian => "Punteggio uccisioni",
+            Japanese => "撃沈スコア",
+            Russian => "Очки за потопления",
+            SimplifiedChinese => "击沉得分",
+            Spanish => "Puntuación de bajas",
+            Vietnamese => "Điểm hạ gục",
+        }
+    }
+
+    fn summary_loot_score(self) -> &'static str {
+        match self {
+            Arabic => "نقاط الغنائم",
+            Bork => "Treat score",
+            English => "Loot score",
+            French => "Score de butin",
+            German => "Beutepunkte",
+            Hindi => "लूट स्कोर",
+            Italian => "Punteggio bottino",
+            Japanese => "戦利品スコア",
+            Russian => "Очки за добычу",
+            SimplifiedChinese => "战利品得分",
+            Spanish => "Puntuación de botín",
+            Vietnamese => "Điểm chiến lợi phẩm",
+        }
+    }
+
+    fn summary_loot(self, loot_score: u32, coins: u32) -> String {
+        match self {
+            Arabic => format!("{loot_score} ({coins} عملة)"),
+            Bork => format!("{loot_score} ({coins} borkcoins)"),
+            English => format!("{loot_score} ({coins} coins)"),
+            French => format!("{loot_score} ({coins} pièces)"),
+            German => format!("{loot_score} ({coins} Münzen)"),
+            Hindi => format!("{loot_score} ({coins} सिक्के)"),
+            Italian => format!("{loot_score} ({coins} monete)"),
+            Japanese => format!("{loot_score}（コイン{coins}枚）"),
+            Russian => format!("{loot_score} (монет: {coins})"),
+            SimplifiedChinese => format!("{loot_score}（{coins} 枚金币）"),
+            Spanish => format!("{loot_score} ({coins} monedas)"),
+            Vietnamese => format!("{loot_score} ({coins} xu)"),
+        }
+    }
+
+    fn summary_kills(self, weapon: &str) -> String {
+        match self {
+            Arabic => format!("القتلى ({weapon})"),
+            Bork => format!("Borks ({weapon})"),
+            English => format!("Kills ({weapon})"),
+            French => format!("Éliminations ({weapon})"),
+            German => format!("Abschüsse ({weapon})"),
+            Hindi => format!("किल ({weapon})"),
+            Italian => format!("Uccisioni ({weapon})"),
+            Japanese => format!("撃沈（{weapon}）"),
+            Russian => format!("Потопления ({weapon})"),
+            SimplifiedChinese => format!("击沉（{weapon}）"),
+            Spanish => format!("Bajas ({weapon})"),
+            Vietnamese => format!("Hạ gục ({weapon})"),
+        }
+    }
+
+    fn summary_kills_other(self) -> &'static str {
+        match self {
+            Arabic => "أخرى",
+            Bork => "Other bork",
+            English => "Other",
+            French => "Autre",
+            German => "Sonstige",
+            Hindi => "अन्य",
+            Italian => "Altro",
+            Japanese => "その他",
+            Russian => "Другое",
+            SimplifiedChinese => "其他",
+            Spanish => "Otro",
+            Vietnamese => "Khác",
+        }
+    }
+
     fn team_fleet_label(self) -> &'static str {
         match self {
             Arabic => "أسطول",
@@ -1710,6 +3278,40 @@ impl Mk48Translation for LanguageId {
         }
     }
 
+    fn transfer_label(self) -> &'static str {
+        match self {
+            Arabic => "تحويلات",
+            Bork => "Bork sharing",
+            English => "Transfers",
+            French => "Transferts",
+            German => "Überweisungen",
+            Hindi => "स्थानांतरण",
+            Italian => "Trasferimenti",
+            Japanese => "送金",
+            Russian => "Переводы",
+            SimplifiedChinese => "转账",
+            Spanish => "Transferencias",
+            Vietnamese => "Chuyển điểm",
+        }
+    }
+
+    fn transfer_hint(self, amount: u32, received: u32) -> String {
+        match self {
+            Arabic => format!("أرسل {amount} نقطة، يصل منها {received} بعد الضريبة"),
+            Bork => format!("Share {amount} bork points, {received} of which get there"),
+            English => format!("Send {amount} points, of which {received} arrive after tax"),
+            French => format!("Envoyer {amount} points, dont {received} arrivent après la taxe"),
+            German => format!("{amount} Punkte senden, von denen nach Steuern {received} ankommen"),
+            Hindi => format!("{amount} अंक भेजें, जिनमें से कर के बाद {received} पहुंचेंगे"),
+            Italian => format!("Invia {amount} punti, di cui {received} arrivano dopo la tassa"),
+            Japanese => format!("{amount}ポイントを送る（税引き後{received}ポイントが届く）"),
+            Russian => format!("Отправить {amount} очков, из которых после налога дойдёт {received}"),
+            SimplifiedChinese => format!("发送 {amount} 分，扣税后到账 {received} 分"),
+            Spanish => format!("Enviar {amount} puntos, de los cuales llegan {received} tras el impuesto"),
+            Vietnamese => format!("Gửi {amount} điểm, sau thuế còn {received} điểm"),
+        }
+    }
+
     fn rewarded_ad_available(self) -> &'static str {
         match self {
             Arabic => "فتح محتوى المكافأة",
@@ -4,29 +4,48 @@
 use crate::game::Mk48Game;
 use crate::translation::Mk48Translation;
 use crate::ui::about_dialog::AboutDialog;
+use crate::ui::caster_overlay::{CastButton, CasterOverlay};
 use crate::ui::changelog_dialog::ChangelogDialog;
+use crate::ui::cosmetic_picker::CosmeticPicker;
+use crate::ui::duel_overlay::DuelOverlay;
+use crate::ui::escort_overlay::EscortOverlay;
 use crate::ui::help_dialog::HelpDialog;
 use crate::ui::hint::Hint;
 pub use crate::ui::instructions::InstructionStatus;
 use crate::ui::levels_dialog::LevelsDialog;
 use crate::ui::logo::logo;
+use crate::ui::minimap_overlay::MinimapOverlay;
+use crate::ui::region_picker::RegionPicker;
 use crate::ui::respawn_overlay::RespawnOverlay;
 use crate::ui::settings_dialog::SettingsDialog;
 use crate::ui::ship_controls::ShipControls;
 use crate::ui::ships_dialog::ShipsDialog;
+use crate::ui::signal_wheel::SignalWheel;
+use crate::ui::spawn_carousel::SpawnCarousel;
 use crate::ui::status_overlay::StatusOverlay;
+use crate::ui::summary_overlay::SummaryOverlay;
+use crate::ui::transfer_overlay::TransferOverlay;
 use crate::ui::upgrade_overlay::UpgradeOverlay;
 use client_util::context::Context;
 use common::altitude::Altitude;
+use common::ammo::Ammo;
 use common::angle::Angle;
 use common::death_reason::DeathReason;
+use common::drawing::Drawing;
+use common::duel::DuelDto;
 use common::entity::EntityType;
+use common::escort::EscortDto;
+use common::protocol::{Duel, Escort, TurretLock};
+use common::signal::SignalKind;
+use common::status_effects::StatusEffects;
+use common::summary::Summary;
 use common::velocity::Velocity;
-use core_protocol::id::{LanguageId, TeamId};
+use core_protocol::id::{LanguageId, PlayerId, TeamId};
 use core_protocol::name::PlayerAlias;
 use engine_macros::SmolRoutable;
 use glam::Vec2;
 use std::collections::HashMap;
+use std::num::NonZeroU8;
 use stylist::yew::styled_component;
 use yew::prelude::*;
 use yew_frontend::component::discord_icon::DiscordIcon;
@@ -40,26 +59,39 @@ use yew_frontend::component::x_button::XButton;
 use yew_frontend::frontend::{use_gctw, use_outbound_enabled};
 use yew_frontend::frontend::{use_rewarded_ad, PropertiesWrapper};
 use yew_frontend::overlay::chat::ChatOverlay;
+use yew_frontend::overlay::friends::FriendsOverlay;
 use yew_frontend::overlay::leaderboard::LeaderboardOverlay;
+use yew_frontend::overlay::news::NewsOverlay;
+use yew_frontend::overlay::server_browser::ServerBrowserOverlay;
 use yew_frontend::overlay::spawn::SpawnOverlay;
 use yew_frontend::overlay::team::TeamOverlay;
 use yew_frontend::translation::{use_translation, Translation};
 use yew_router::{Routable, Switch};
 
 mod about_dialog;
+mod caster_overlay;
 mod changelog_dialog;
+mod cosmetic_picker;
+mod duel_overlay;
+mod escort_overlay;
 mod help_dialog;
 mod hint;
 mod instructions;
 mod levels_dialog;
 mod logo;
+mod minimap_overlay;
+mod region_picker;
 mod respawn_overlay;
 mod settings_dialog;
 mod ship_controls;
 mod ship_menu;
 mod ships_dialog;
+mod signal_wheel;
+mod spawn_carousel;
 mod sprite;
 mod status_overlay;
+mod summary_overlay;
+mod transfer_overlay;
 mod upgrade_overlay;
 
 #[styled_component(Mk48Ui)]
@@ -76,10 +108,16 @@ pub fn mk48_ui(props: &PropertiesWrapper<UiProps>) -> Html {
 
     let gctw = use_gctw::<Mk48Game>();
     let t = use_translation();
-    let on_play = gctw.send_ui_event_callback.reform(|alias| UiEvent::Spawn {
-        alias,
-        entity_type: EntityType::G5,
-    });
+    let spawn_entity_type = use_state_eq(|| EntityType::G5);
+    let on_play = {
+        let entity_type = *spawn_entity_type;
+        gctw.send_ui_event_callback
+            .reform(move |alias| UiEvent::Spawn { alias, entity_type })
+    };
+    let on_spawn_entity_type_change = {
+        let spawn_entity_type = spawn_entity_type.clone();
+        Callback::from(move |entity_type| spawn_entity_type.set(entity_type))
+    };
 
     let margin = "0.75rem";
     let status = props.status.clone();
@@ -168,6 +206,25 @@ pub fn mk48_ui(props: &PropertiesWrapper<UiProps>) -> Html {
                         position={Position::TopRight{margin}}
                         style="max-width:25%;"
                     />
+                    <Positioner id="minimap" position={Position::CenterLeft{margin}} flex={Flex::Column}>
+                        if let Some(drawings) = playing.drawings.clone() {
+                            <MinimapOverlay
+                                position={playing.position}
+                                world_radius={playing.world_radius}
+                                {drawings}
+                                editable={playing.team_captain}
+                            />
+                        }
+                        <DuelOverlay
+                            duels={playing.duels.clone()}
+                            candidates={playing.duel_candidates.clone()}
+                        />
+                        <EscortOverlay
+                            escorts={playing.escorts.clone()}
+                            candidates={playing.escort_candidates.clone()}
+                        />
+                        <TransferOverlay candidates={playing.transfer_candidates.clone()}/>
+                    </Positioner>
                     <ChatOverlay
                         position={Position::BottomRight{margin}}
                         style="max-width:25%;"
@@ -178,20 +235,53 @@ pub fn mk48_ui(props: &PropertiesWrapper<UiProps>) -> Html {
                 if !gctw.settings_cache.cinematic {
                     <Hint entity_type={playing.entity_type}/>
                 }
+                if let Some(position) = playing.signal_wheel {
+                    <SignalWheel {position}/>
+                }
+            } else if let UiStatus::Casting(casting) = status {
+                <CasterOverlay position={Position::TopMiddle{margin}} status={casting}/>
+                <Positioner id="sidebar" position={Position::CenterRight{margin}} flex={Flex::Column}>
+                    <VolumeIcon/>
+                    <SettingsIcon<Mk48Route> route={Mk48Route::Settings}/>
+                    <LanguageMenu/>
+                </Positioner>
+                <LeaderboardOverlay
+                    position={Position::TopRight{margin}}
+                    style="max-width:25%;"
+                />
             } else if let UiStatus::Respawning(respawning) = status {
-                <RespawnOverlay status={respawning} score={props.score}/>
+                <RespawnOverlay status={respawning} score={props.score} summary={props.summary.clone()}/>
                 <Positioner position={Position::TopRight{margin}} max_width="25%">
                     <XButton onclick={gctw.send_ui_event_callback.reform(|_| UiEvent::OverrideRespawn)}/>
                 </Positioner>
             } else {
                 <SpawnOverlay {on_play}>
                     {logo()}
+                    if let Some(summary) = props.summary.clone() {
+                        <SummaryOverlay {summary}/>
+                    }
+                    <SpawnCarousel selected={*spawn_entity_type} onchange={on_spawn_entity_type_change}/>
+                    <CosmeticPicker/>
+                    <RegionPicker/>
                 </SpawnOverlay>
                 <Positioner id="back" position={Position::TopRight{margin}} flex={Flex::Row}>
+                    <CastButton/>
                     <LanguageMenu/>
                 </Positioner>
+                <FriendsOverlay
+                    position={Position::CenterLeft{margin}}
+                    style="max-width:25%;"
+                />
+                <NewsOverlay
+                    position={Position::CenterRight{margin}}
+                    style="max-width:25%;"
+                />
+                <ServerBrowserOverlay
+                    position={Position::BottomLeft{margin}}
+                    style="max-width:25%;"
+                />
             }
-            if !matches!(props.status, UiStatus::Playing(_)) {
+            if !matches!(props.status, UiStatus::Playing(_) | UiStatus::Casting(_)) {
                 if outbound_enabled {
                     <Positioner id="social" position={Position::BottomRight{margin}} flex={Flex::Row}>
                         <DiscordIcon/>
@@ -239,7 +329,18 @@ pub enum Mk48Route {
 pub struct UiState {
     pub active: bool,
     pub submerge: bool,
-    pub armament: Option<EntityType>,
+    /// Hold at periscope depth, while submerged.
+    pub periscope: bool,
+    pub searchlight: bool,
+    /// Emission control, which keeps radar silent.
+    pub emcon: bool,
+    /// Cruise control, which holds speed through turns.
+    pub cruise: bool,
+    /// Selected armament group, and the type of boat it was selected on (see
+    /// [`crate::armament::update`]).
+    pub armament: Option<(EntityType, u8)>,
+    /// Turrets held at fixed bearings, indexed like the turrets of the player's boat.
+    pub turret_locks: Vec<Option<TurretLock>>,
 }
 
 impl Default for UiState {
@@ -247,7 +348,12 @@ impl Default for UiState {
         Self {
             active: true,
             submerge: false,
+            periscope: false,
+            searchlight: false,
+            emcon: false,
+            cruise: false,
             armament: None,
+            turret_locks: Vec::new(),
         }
     }
 }
@@ -255,17 +361,58 @@ impl Default for UiState {
 pub enum UiEvent {
     /// Sensors active.
     Active(bool),
-    Armament(Option<EntityType>),
+    /// Select an armament group, relative to `EntityData::armament_groups`.
+    Armament(Option<u8>),
+    /// Start (true) or stop (false) casting (moderator only).
+    Cast(bool),
+    /// Move the caster's camera to a bookmark.
+    CastBookmark(NonZeroU8),
+    /// Start (true) or stop (false) damage control.
+    DamageControl(bool),
+    /// Start (true) or stop (false) emission control.
+    Emcon(bool),
+    /// Cruise control.
+    Cruise(bool),
     GraphicsSettingsChanged,
     /// Go from respawning to spawning.
     #[allow(unused)]
     OverrideRespawn,
+    /// Hold at periscope depth (true) or dive deep (false), while submerged.
+    Periscope(bool),
+    /// Call the repair crew.
+    RepairCrew,
     Respawn(EntityType),
+    /// Start (true) or cancel (false) returning to port.
+    ReturnToPort(bool),
+    /// Start (true) or stop (false) holding the scuttle button, which starts scuttling once held
+    /// for [`Scuttle::HOLD`][common::protocol::Scuttle::HOLD] seconds.
+    HoldScuttle(bool),
+    /// Cancel scuttling.
+    CancelScuttle,
+    /// Turn the searchlight on (true) or off (false).
+    Searchlight(bool),
+    /// Draw on teammates' minimaps (fleet leader only).
+    Draw(Drawing),
+    /// Challenge another player to a duel, or answer a challenge.
+    Duel(Duel),
+    /// Offer another player a contract to escort one's cargo, or answer or end a contract.
+    Escort(Escort),
+    /// Send a signal to teammates, or close the signal wheel if [`None`].
+    Signal(Option<SignalKind>),
     Spawn {
         alias: PlayerAlias,
         entity_type: EntityType,
     },
     Submerge(bool),
+    /// Send score to a fleet member.
+    Transfer {
+        player_id: PlayerId,
+        amount: u32,
+    },
+    /// Release the target lock.
+    ReleaseTarget,
+    /// Let all turrets follow the aim point again.
+    UnlockTurrets,
     Upgrade(EntityType),
 }
 
@@ -274,6 +421,8 @@ pub struct UiProps {
     pub fps: f32,
     pub score: u32,
     pub status: UiStatus,
+    /// Summary of the previous life, if it ended by dying or leaving, until respawning.
+    pub summary: Option<Box<Summary>>,
 }
 
 /// Mutually exclusive statuses.
@@ -283,6 +432,8 @@ pub enum UiStatus {
     Spawning,
     Playing(UiStatusPlaying),
     Respawning(UiStatusRespawning),
+    /// Spectating as a caster, instead of playing.
+    Casting(UiStatusCasting),
 }
 
 #[derive(PartialEq, Clone)]
@@ -293,12 +444,54 @@ pub struct UiStatusPlaying {
     pub position: Vec2,
     pub altitude: Altitude,
     pub submerge: bool,
+    /// Hold at periscope depth, while submerged.
+    pub periscope: bool,
     /// Active sensors.
     pub active: bool,
+    /// Shining a searchlight.
+    pub searchlight: bool,
+    /// Emission control.
+    pub emcon: bool,
+    /// Cruise control.
+    pub cruise: bool,
+    /// Whether any turrets are locked at fixed bearings.
+    pub turrets_locked: bool,
+    /// Whether the player locked onto a target.
+    pub target_locked: bool,
     pub instruction_status: InstructionStatus,
-    pub armament: Option<EntityType>,
+    /// Selected armament group, relative to `EntityData::armament_groups`.
+    pub armament: Option<u8>,
     pub armament_consumption: Box<[bool]>,
     pub team_proximity: HashMap<TeamId, f32>,
+    /// Where to show the signal wheel, in view space, if it is open.
+    pub signal_wheel: Option<Vec2>,
+    /// Drawings from the fleet leader, or [`None`] if not in a fleet.
+    pub drawings: Option<Vec<Drawing>>,
+    /// Whether the player is the fleet leader, and may draw.
+    pub team_captain: bool,
+    pub world_radius: f32,
+    /// How much of returning to port is complete, if returning to port.
+    pub port_progress: Option<f32>,
+    /// How long the scuttle button has been held, from 0 to 1, if it is held.
+    pub scuttle_hold: Option<f32>,
+    /// How much of scuttling is complete, if scuttling.
+    pub scuttle_progress: Option<f32>,
+    /// Coins banked by returning to port.
+    pub banked_coins: u32,
+    /// Fires and flooding on the player's boat.
+    pub status_effects: StatusEffects,
+    /// Ammunition in reserve on the player's boat.
+    pub ammo: Ammo,
+    /// Duels, and challenges to duel, involving the player.
+    pub duels: Vec<DuelDto>,
+    /// Nearby enemy players that may be challenged to duel, closest first.
+    pub duel_candidates: Vec<PlayerId>,
+    /// Escort contracts, and offers of them, involving the player.
+    pub escorts: Vec<EscortDto>,
+    /// Nearby players that may be hired to escort one's cargo, closest first, if carrying any.
+    pub escort_candidates: Vec<PlayerId>,
+    /// Fleet members close enough to transfer score to, closest first.
+    pub transfer_candidates: Vec<PlayerId>,
 }
 
 #[derive(PartialEq, Clone)]
@@ -306,12 +499,19 @@ pub struct UiStatusRespawning {
     pub death_reason: DeathReason,
 }
 
+#[derive(PartialEq, Clone)]
+pub struct UiStatusCasting {
+    /// Digits of the saved camera bookmarks.
+    pub bookmarks: Vec<NonZeroU8>,
+}
+
 impl Mk48Game {
     pub(crate) fn update_ui_props(&self, context: &mut Context<Self>, status: UiStatus) {
         let props = UiProps {
             fps: self.fps_counter.last_sample().unwrap_or(0.0),
             score: context.state.game.score,
             status,
+            summary: context.state.game.summary.clone(),
         };
 
         context.set_ui_props(props);
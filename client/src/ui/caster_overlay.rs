@@ -0,0 +1,77 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::translation::Mk48Translation;
+use crate::ui::{UiEvent, UiStatusCasting};
+use crate::Mk48Game;
+use stylist::yew::styled_component;
+use yew::{function_component, html, html_nested, Html, Properties};
+use yew_frontend::component::positioner::Position;
+use yew_frontend::frontend::{use_core_state, use_ui_event_callback};
+use yew_frontend::translation::use_translation;
+
+#[derive(Properties, PartialEq)]
+pub struct CasterOverlayProps {
+    pub position: Position,
+    pub status: UiStatusCasting,
+}
+
+/// Shown while casting, to cut between bookmarks and to stop casting.
+#[styled_component(CasterOverlay)]
+pub fn caster_overlay(props: &CasterOverlayProps) -> Html {
+    let container_style = css!(
+        r#"
+        color: white;
+        text-align: center;
+        user-select: none;
+        "#
+    );
+
+    let hint_style = css!(
+        r#"
+        font-size: 0.9rem;
+        margin: 0.25rem 0;
+        opacity: 0.8;
+        "#
+    );
+
+    let t = use_translation();
+    let ui_event_callback = use_ui_event_callback::<Mk48Game>();
+
+    html! {
+        <div id="caster" class={container_style} style={props.position.to_string()}>
+            <h2>{t.caster_label()}</h2>
+            <p class={hint_style}>{t.caster_hint()}</p>
+            <div>
+                {props.status.bookmarks.iter().map(|&digit| {
+                    let onclick = ui_event_callback.reform(move |_| UiEvent::CastBookmark(digit));
+                    html_nested!{
+                        <button {onclick}>{digit.get() % 10}</button>
+                    }
+                }).collect::<Html>()}
+                <button onclick={ui_event_callback.reform(|_| UiEvent::Cast(false))}>{t.caster_stop_label()}</button>
+            </div>
+        </div>
+    }
+}
+
+/// Lets moderators start casting, such as to spectate a tournament.
+#[function_component(CastButton)]
+pub fn cast_button() -> Html {
+    let core_state = use_core_state();
+    let t = use_translation();
+    let ui_event_callback = use_ui_event_callback::<Mk48Game>();
+
+    if !core_state.player().map(|p| p.moderator).unwrap_or(false) {
+        return Html::default();
+    }
+
+    html! {
+        <button
+            onclick={ui_event_callback.reform(|_| UiEvent::Cast(true))}
+            title={t.caster_start_hint()}
+        >
+            {t.caster_start_label()}
+        </button>
+    }
+}
@@ -0,0 +1,100 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::settings::Mk48Settings;
+use crate::translation::Mk48Translation;
+use crate::Mk48Game;
+use client_util::browser_storage::BrowserStorages;
+use common::cosmetic::{Flag, HullPaint};
+use std::str::FromStr;
+use stylist::yew::styled_component;
+use web_sys::{HtmlSelectElement, InputEvent};
+use yew::{html, html_nested, Html, TargetCast};
+use yew_frontend::frontend::use_gctw;
+use yew_frontend::translation::use_translation;
+
+/// Lets the player choose a flag and hull paint to spawn with. Locked choices may still be
+/// selected, but the server will replace them with the defaults until they are unlocked.
+#[styled_component(CosmeticPicker)]
+pub fn cosmetic_picker() -> Html {
+    let container_style = css!(
+        r#"
+        display: flex;
+        flex-direction: row;
+        gap: 0.5rem;
+        justify-content: center;
+        margin: 0.5rem auto;
+        "#
+    );
+
+    let select_style = css!(
+        r#"
+        border-radius: 0.25em;
+        box-sizing: border-box;
+        cursor: pointer;
+        font-size: 1em;
+        font-weight: bold;
+        outline: 0;
+        padding: 0.5em;
+        pointer-events: all;
+        white-space: nowrap;
+        border: 0;
+        color: white;
+        background-color: #00000025;
+        "#
+    );
+
+    let gctw = use_gctw::<Mk48Game>();
+    let t = use_translation();
+
+    let flag = gctw.settings_cache.flag;
+    let on_set_flag = gctw
+        .change_settings_callback
+        .reform(move |event: InputEvent| {
+            let value = event.target_unchecked_into::<HtmlSelectElement>().value();
+            Box::new(
+                move |settings: &mut Mk48Settings, browser_storages: &mut BrowserStorages| {
+                    if let Ok(flag) = Flag::from_str(&value) {
+                        settings.set_flag(flag, browser_storages);
+                    }
+                },
+            )
+        });
+
+    let paint = gctw.settings_cache.paint;
+    let on_set_paint = gctw
+        .change_settings_callback
+        .reform(move |event: InputEvent| {
+            let value = event.target_unchecked_into::<HtmlSelectElement>().value();
+            Box::new(
+                move |settings: &mut Mk48Settings, browser_storages: &mut BrowserStorages| {
+                    if let Ok(paint) = HullPaint::from_str(&value) {
+                        settings.set_paint(paint, browser_storages);
+                    }
+                },
+            )
+        });
+
+    let label = |name: &str, unlock_level: u8| -> String {
+        if unlock_level > 1 {
+            t.cosmetic_locked(name, unlock_level)
+        } else {
+            name.to_owned()
+        }
+    };
+
+    html! {
+        <div id="cosmetic_picker" class={container_style}>
+            <select oninput={on_set_flag} class={select_style.clone()} title={t.cosmetic_flag_label()}>
+                {Flag::iter().map(|v| html_nested!{
+                    <option value={v.to_string()} selected={flag == v}>{label(t.cosmetic_flag(v), v.unlock_level())}</option>
+                }).collect::<Html>()}
+            </select>
+            <select oninput={on_set_paint} class={select_style} title={t.cosmetic_paint_label()}>
+                {HullPaint::iter().map(|v| html_nested!{
+                    <option value={v.to_string()} selected={paint == v}>{label(t.cosmetic_paint(v), v.unlock_level())}</option>
+                }).collect::<Html>()}
+            </select>
+        </div>
+    }
+}
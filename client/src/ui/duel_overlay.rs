@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::translation::Mk48Translation;
+use crate::ui::UiEvent;
+use crate::Mk48Game;
+use common::duel::{DuelDto, DuelStatus};
+use common::protocol::Duel;
+use core_protocol::id::PlayerId;
+use stylist::yew::styled_component;
+use yew::{classes, html, html_nested, Html, Properties};
+use yew_frontend::component::section::Section;
+use yew_frontend::frontend::{use_core_state, use_ui_event_callback};
+use yew_frontend::translation::use_translation;
+
+#[derive(Properties, PartialEq)]
+pub struct DuelOverlayProps {
+    pub duels: Vec<DuelDto>,
+    /// Nearby enemy players that may be challenged, closest first.
+    pub candidates: Vec<PlayerId>,
+}
+
+/// Lists duels and challenges involving the player, and nearby players they may challenge.
+#[styled_component(DuelOverlay)]
+pub fn duel_overlay(props: &DuelOverlayProps) -> Html {
+    let table_style = css!(
+        r#"
+        color: white;
+        width: 100%;
+        border-spacing: 0;
+        border-collapse: collapse;
+        "#
+    );
+
+    let name_style = css!(
+        r#"
+        white-space: nowrap;
+        "#
+    );
+
+    let pending_style = css!(
+        r#"
+        filter: brightness(0.7);
+        "#
+    );
+
+    let button_style = css!(
+        r#"
+        background-color: transparent;
+        border: 0;
+        border-radius: 0.25em;
+        color: white;
+        cursor: pointer;
+        font-size: 1em;
+        padding: 0.1em 0.5em;
+        white-space: nowrap;
+
+        :hover {
+            background-color: #00000025;
+        }
+        "#
+    );
+
+    const CHECK_MARK: &'static str = "✔";
+    const CROSSED_SWORDS: &'static str = "⚔";
+    const X_MARK: &'static str = "✘";
+
+    let t = use_translation();
+    let core_state = use_core_state();
+    let ui_event_callback = use_ui_event_callback::<Mk48Game>();
+
+    let alias = |player_id: PlayerId| {
+        core_state
+            .player_or_bot(player_id)
+            .map(|player| player.alias.as_str().to_owned())
+            .unwrap_or_default()
+    };
+    let dueling = props
+        .duels
+        .iter()
+        .any(|duel| duel.status == DuelStatus::Active);
+    let challenging = props
+        .duels
+        .iter()
+        .any(|duel| duel.status == DuelStatus::Challenging);
+
+    if props.duels.is_empty() && props.candidates.is_empty() {
+        return Html::default();
+    }
+
+    html! {
+        <Section id="duels" name={t.duel_label()}>
+            <table class={table_style}>
+                {props.duels.iter().map(|&DuelDto{opponent, stake, status, seconds_remaining}| {
+                    let remaining = format!("{}:{:02}", seconds_remaining / 60, seconds_remaining % 60);
+                    let decline = ui_event_callback.reform(move |_| UiEvent::Duel(Duel::Decline(opponent)));
+                    html_nested!{
+                        <tr>
+                            <td class={classes!(name_style.clone(), (status != DuelStatus::Active).then(|| pending_style.clone()))}>{alias(opponent)}</td>
+                            <td>{stake}</td>
+                            <td>{remaining}</td>
+                            {match status {
+                                DuelStatus::Active => html!{
+                                    <td title={t.duel_active_hint(stake * 2)}>{CROSSED_SWORDS}</td>
+                                },
+                                DuelStatus::Challenging => html!{
+                                    <td><button class={button_style.clone()} onclick={decline} title={t.duel_withdraw_hint()}>{X_MARK}</button></td>
+                                },
+                                DuelStatus::Challenged => {
+                                    let accept = ui_event_callback.reform(move |_| UiEvent::Duel(Duel::Accept(opponent)));
+                                    html!{
+                                        <td>
+                                            if !dueling {
+                                                <button class={button_style.clone()} onclick={accept} title={t.duel_accept_hint(stake)}>{CHECK_MARK}</button>
+                                            }
+                                            <button class={button_style.clone()} onclick={decline} title={t.duel_decline_hint()}>{X_MARK}</button>
+                                        </td>
+                                    }
+                                }
+                            }}
+                        </tr>
+                    }
+                }).collect::<Html>()}
+                if !dueling && !challenging {
+                    {props.candidates.iter().filter(|&&player_id| !props.duels.iter().any(|duel| duel.opponent == player_id)).take(3).map(|&player_id| {
+                        html_nested!{
+                            <tr>
+                                <td class={name_style.clone()}>{alias(player_id)}</td>
+                                <td colspan="3">
+                                    {Duel::STAKES.into_iter().map(|stake| {
+                                        let onclick = ui_event_callback.reform(move |_| UiEvent::Duel(Duel::Challenge{player_id, stake}));
+                                        html_nested!{
+                                            <button class={button_style.clone()} {onclick} title={t.duel_challenge_hint(stake)}>{CROSSED_SWORDS}{stake}</button>
+                                        }
+                                    }).collect::<Html>()}
+                                </td>
+                            </tr>
+                        }
+                    }).collect::<Html>()}
+                }
+            </table>
+        </Section>
+    }
+}
@@ -0,0 +1,135 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::translation::Mk48Translation;
+use crate::ui::UiEvent;
+use crate::Mk48Game;
+use common::escort::{EscortDto, EscortStatus};
+use common::protocol::Escort;
+use core_protocol::id::PlayerId;
+use stylist::yew::styled_component;
+use yew::{classes, html, html_nested, Html, Properties};
+use yew_frontend::component::section::Section;
+use yew_frontend::frontend::{use_core_state, use_ui_event_callback};
+use yew_frontend::translation::use_translation;
+
+#[derive(Properties, PartialEq)]
+pub struct EscortOverlayProps {
+    pub escorts: Vec<EscortDto>,
+    /// Nearby players that may be hired to escort the player's cargo, closest first.
+    pub candidates: Vec<PlayerId>,
+}
+
+/// Lists escort contracts and offers involving the player, and nearby players they may hire.
+#[styled_component(EscortOverlay)]
+pub fn escort_overlay(props: &EscortOverlayProps) -> Html {
+    let table_style = css!(
+        r#"
+        color: white;
+        width: 100%;
+        border-spacing: 0;
+        border-collapse: collapse;
+        "#
+    );
+
+    let name_style = css!(
+        r#"
+        white-space: nowrap;
+        "#
+    );
+
+    let pending_style = css!(
+        r#"
+        filter: brightness(0.7);
+        "#
+    );
+
+    let button_style = css!(
+        r#"
+        background-color: transparent;
+        border: 0;
+        border-radius: 0.25em;
+        color: white;
+        cursor: pointer;
+        font-size: 1em;
+        padding: 0.1em 0.5em;
+        white-space: nowrap;
+
+        :hover {
+            background-color: #00000025;
+        }
+        "#
+    );
+
+    const ANCHOR: &str = "⚓";
+    const CHECK_MARK: &str = "✔";
+    const SHIELD: &str = "⛨";
+    const X_MARK: &str = "✘";
+
+    let t = use_translation();
+    let core_state = use_core_state();
+    let ui_event_callback = use_ui_event_callback::<Mk48Game>();
+
+    let alias = |player_id: PlayerId| {
+        core_state
+            .player_or_bot(player_id)
+            .map(|player| player.alias.as_str().to_owned())
+            .unwrap_or_default()
+    };
+    let contracted = props.escorts.iter().any(|escort| {
+        matches!(
+            escort.status,
+            EscortStatus::Escorting | EscortStatus::Escorted
+        )
+    });
+    let offering = props
+        .escorts
+        .iter()
+        .any(|escort| escort.status == EscortStatus::Offering);
+
+    if props.escorts.is_empty() && props.candidates.is_empty() {
+        return Html::default();
+    }
+
+    html! {
+        <Section id="escorts" name={t.escort_label()}>
+            <table class={table_style}>
+                {props.escorts.iter().map(|&EscortDto{other, share, status, attackers_sunk}| {
+                    let cancel = ui_event_callback.reform(move |_| UiEvent::Escort(Escort::Cancel(other)));
+                    let active = matches!(status, EscortStatus::Escorting | EscortStatus::Escorted);
+                    html_nested!{
+                        <tr>
+                            <td class={classes!(name_style.clone(), (!active).then(|| pending_style.clone()))}>{alias(other)}</td>
+                            <td>{format!("{share}%")}</td>
+                            <td>
+                                if active {
+                                    <span title={t.escort_active_hint(share, attackers_sunk)}>{if status == EscortStatus::Escorting { SHIELD } else { ANCHOR }}{attackers_sunk}</span>
+                                } else if status == EscortStatus::Offered && !contracted {
+                                    <button class={button_style.clone()} onclick={ui_event_callback.reform(move |_| UiEvent::Escort(Escort::Accept(other)))} title={t.escort_accept_hint(share)}>{CHECK_MARK}</button>
+                                }
+                                <button class={button_style.clone()} onclick={cancel} title={t.escort_cancel_hint()}>{X_MARK}</button>
+                            </td>
+                        </tr>
+                    }
+                }).collect::<Html>()}
+                if !contracted && !offering {
+                    {props.candidates.iter().filter(|&&player_id| !props.escorts.iter().any(|escort| escort.other == player_id)).take(3).map(|&player_id| {
+                        html_nested!{
+                            <tr>
+                                <td class={name_style.clone()}>{alias(player_id)}</td>
+                                <td colspan="2">
+                                    {Escort::SHARES.into_iter().map(|share| {
+                                        let onclick = ui_event_callback.reform(move |_| UiEvent::Escort(Escort::Offer{player_id, share}));
+                                        html_nested!{
+                                            <button class={button_style.clone()} {onclick} title={t.escort_offer_hint(share)}>{SHIELD}{format!("{share}%")}</button>
+                                        }
+                                    }).collect::<Html>()}
+                                </td>
+                            </tr>
+                        }
+                    }).collect::<Html>()}
+                }
+            </table>
+        </Section>
+    }
+}
@@ -1,11 +1,13 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::key_bindings::Action;
 use crate::ui::Mk48Route;
+use crate::Mk48Game;
 use yew::{function_component, html, Html};
 use yew_frontend::component::route_link::RouteLink;
 use yew_frontend::dialog::dialog::Dialog;
-use yew_frontend::frontend::use_game_id;
+use yew_frontend::frontend::{use_game_id, use_gctw};
 use yew_frontend::translation::{use_translation, Translation};
 
 #[function_component(HelpDialog)]
@@ -13,6 +15,8 @@ pub fn help_dialog() -> Html {
     let t = use_translation();
     let game_id = use_game_id();
     let game_name = game_id.name();
+    let key_bindings = use_gctw::<Mk48Game>().settings_cache.key_bindings;
+    let key = |action: Action| key_bindings.key(action).to_string();
     html! {
         <Dialog title={t.help_title(game_id)}>
 
@@ -58,7 +62,9 @@ pub fn help_dialog() -> Html {
                 <li><b>{"Battleships"}</b>{" and "}<b>{"Dreadnoughts"}</b>{" are very formidable ships, having extremely
                 powerful main cannons. They may carry a minimal complement of aircraft for submarine defense."}</li>
                 <li><b>{"Submarines"}</b>{" travel underwater, making them immune to certain
-                types of weapons, but must surface to fire certain types of weapons."}</li>
+                types of weapons, but must surface to fire certain types of weapons. At periscope depth, only
+                their mast is exposed, letting them see the surface and launch missiles, whereas deeper they
+                must rely on sonar."}</li>
                 <li><b>{"Hovercraft"}</b>{" can travel on land and water."}</li>
                 <li><b>{"Rams"}</b>{" are specially designed to ram other ships."}</li>
                 <li><b>{"Dredgers"}</b>{" have the ability to modify the land. New land
@@ -139,12 +145,19 @@ pub fn help_dialog() -> Html {
                 {". Passive mode listens for emissions (e.g. sound in the case of sonar) from other entities. "}
                 {"Active mode emits a signal and resolves contacts based on the signals that bounce back. "}
                 {"In general, active mode allows you to see more, but has the potential to give away your position. "}
-                {"You can toggle between the modes with the 'z' key."}
+                {"You can toggle between the modes with the "}<b>{key(Action::Active)}</b>{" key."}
             </p>
 
             <p>
                 {"If a contact is on the border of your sensor range, it will appear as an arrow. "}
-                {"In this case, you know something is there, but not what it is."}
+                {"In this case, you know something is there, but not what it is. "}
+                {"Tracking a ship for longer, or getting closer to it, reveals what kind of ship it is, and then exactly which ship. "}
+                {"If you lose track of a ship, a faded marker shows where it was last seen, with a line to where it may be now."}
+            </p>
+
+            <p>
+                {"An alarm sounds, and a red tick flashes around your ship in its direction, when a torpedo's seeker "}
+                {"locks onto you or a missile is seconds from hitting you, even if you can't see it."}
             </p>
 
             <h2>{"Fleets"}</h2>
@@ -156,6 +169,44 @@ pub fn help_dialog() -> Html {
                 {"Importantly, you cannot request to join a fleet until you are close enough to see one of its members, and the fleet has slots remaining."}
             </p>
 
+            <h2>{"Keyboard"}</h2>
+
+            <p>
+                {"Everything can be done without a mouse. Press "}<b>{key(Action::Controls)}</b>{" to focus the ship controls, "}
+                <b>{key(Action::Upgrade)}</b>{" to focus the upgrades, "}<b>{key(Action::Team)}</b>{" to focus the fleet panel, or "}
+                <b>{"Enter"}</b>{" to chat. Then, "}<b>{"Tab"}</b>{" moves between controls, "}<b>{"Enter"}</b>{" or "}<b>{"Space"}</b>
+                {" activates the focused control, and "}<b>{"Escape"}</b>{" returns to steering. "}
+                {"Keys can be changed in the settings."}
+            </p>
+
+            <p>
+                {"Besides steering, speed can be set to a preset: "}<b>{key(Action::ThrottleOneThird)}</b>{" for one third, "}
+                <b>{key(Action::ThrottleTwoThirds)}</b>{" for two thirds, "}<b>{key(Action::ThrottleFlank)}</b>{" for flank (full) speed, or "}
+                <b>{key(Action::ThrottleReverse)}</b>{" to reverse. "}<b>{key(Action::Cruise)}</b>{" toggles cruise control, which holds "}
+                {"your speed through turns until you speed up or slow down with the keyboard. Slower boats are quieter on sonar."}
+            </p>
+
+            <p>
+                {"Turrets can be kept trained on one bearing while you aim elsewhere. "}<b>{key(Action::LockTurrets)}</b>
+                {" locks the turrets of the selected weapon at the true bearing of your mouse cursor, and "}
+                <b>{key(Action::LockTurretsRelative)}</b>{" locks them at that bearing relative to your bow, so that they turn with your boat. "}
+                {"Pressing either key again, or the unlock button, lets them follow your aim again."}
+            </p>
+
+            <p>
+                {"To lock onto an enemy boat that your sensors identify, middle click near it or press "}<b>{key(Action::LockTarget)}</b>
+                {". Its bearing and range are shown, along with where to aim your selected weapon to hit it. "}
+                {"Guided weapons are launched towards where it will be, instead of your mouse cursor. "}
+                {"The lock is lost if your sensors lose track of it."}
+                {" Rings around your ship show the range of your selected weapon and, for guided weapons, how far it travels before its seeker activates. "}
+                {"Lines towards a locked target outline where the seeker can acquire it, and turn green when it is within range."}
+            </p>
+
+            <p>
+                {"To plot a route, press "}<b>{key(Action::Waypoint)}</b>{" at each point your boat should steer through, in order. "}
+                {"It follows the route at its current speed, and stops at the last waypoint. Steering by hand abandons the route."}
+            </p>
+
             <h2>{"The Arctic"}</h2>
 
             <p>
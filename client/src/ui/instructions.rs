@@ -15,6 +15,11 @@ pub struct InstructionStatus {
     pub touch: bool,
     pub basics: bool,
     pub zoom: bool,
+    /// Warn that the boat will soon be scuttled for lack of input.
+    pub afk: bool,
+    /// Warn that collision assist is slowing or steering the boat, because of land or an obstacle
+    /// ahead.
+    pub collision: bool,
 }
 
 #[styled_component(Instructions)]
@@ -37,6 +42,12 @@ pub fn instructions(props: &InstructionsProps) -> Html {
 
     html! {
         <div id="instructions" class={div_style} style={props.position.to_string()}>
+            if props.status.afk {
+                <h2>{t.instruction_afk()}</h2>
+            }
+            if props.status.collision {
+                <h2>{t.instruction_collision()}</h2>
+            }
             if props.status.basics {
                 <h2>{if props.status.touch { t.instruction_basics_touch() } else { t.instruction_basics_mouse() }}</h2>
             }
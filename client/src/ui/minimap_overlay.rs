@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::ui::UiEvent;
+use crate::Mk48Game;
+use common::drawing::Drawing;
+use glam::Vec2;
+use stylist::yew::styled_component;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, MouseEvent};
+use yew::{classes, html, html_nested, use_state, Callback, Html, Properties};
+use yew_frontend::frontend::use_ui_event_callback;
+
+#[derive(Properties, PartialEq)]
+pub struct MinimapOverlayProps {
+    /// Position of the player's boat.
+    pub position: Vec2,
+    pub world_radius: f32,
+    /// Drawings from the fleet leader.
+    pub drawings: Vec<Drawing>,
+    /// Whether the player may draw (is the fleet leader).
+    pub editable: bool,
+}
+
+/// Shows the world, and drawings from the fleet leader. The fleet leader can click to place a
+/// marker, or drag to draw an arrow.
+#[styled_component(MinimapOverlay)]
+pub fn minimap_overlay(props: &MinimapOverlayProps) -> Html {
+    let minimap_style = css!(
+        r#"
+        background-color: #00000040;
+        border-radius: 50%;
+        height: 12rem;
+        width: 12rem;
+
+        * {
+            pointer-events: none;
+        }
+        "#
+    );
+
+    let editable_style = css!(
+        r#"
+        cursor: crosshair;
+        pointer-events: all;
+        "#
+    );
+
+    let ui_event_callback = use_ui_event_callback::<Mk48Game>();
+    let drag_start = use_state(|| None::<Vec2>);
+
+    let world_radius = props.world_radius;
+
+    // Converts the mouse position to a world position.
+    let to_world = move |event: &MouseEvent| -> Option<Vec2> {
+        let element = event.current_target()?;
+        let rect = element
+            .unchecked_into::<Element>()
+            .get_bounding_client_rect();
+        let fraction = Vec2::new(
+            (event.client_x() as f32 - rect.x() as f32) / rect.width() as f32,
+            (event.client_y() as f32 - rect.y() as f32) / rect.height() as f32,
+        );
+        let position = Vec2::new(fraction.x * 2.0 - 1.0, 1.0 - fraction.y * 2.0) * world_radius;
+        (position.length() <= world_radius).then_some(position)
+    };
+
+    let onmousedown = props.editable.then(|| {
+        let drag_start = drag_start.clone();
+        Callback::from(move |event: MouseEvent| drag_start.set(to_world(&event)))
+    });
+
+    let onmouseup = props.editable.then(|| {
+        let drag_start = drag_start.clone();
+        ui_event_callback.filter_reform(move |event: MouseEvent| {
+            let start = (*drag_start)?;
+            drag_start.set(None);
+            let end = to_world(&event)?;
+            Some(UiEvent::Draw(
+                if start.distance(end) < world_radius * 0.05 {
+                    Drawing::Marker(start)
+                } else {
+                    Drawing::Arrow(start, end)
+                },
+            ))
+        })
+    });
+
+    let onmouseleave = props.editable.then(|| {
+        let drag_start = drag_start.clone();
+        Callback::from(move |_: MouseEvent| drag_start.set(None))
+    });
+
+    // SVG has y pointing down.
+    let flip = |v: Vec2| Vec2::new(v.x, -v.y);
+    let position = flip(props.position);
+
+    html! {
+        <svg
+            id="minimap"
+            class={classes!(minimap_style, props.editable.then_some(editable_style))}
+            viewBox={format!("{} {} {} {}", -world_radius, -world_radius, world_radius * 2.0, world_radius * 2.0)}
+            {onmousedown}
+            {onmouseup}
+            {onmouseleave}
+        >
+            <defs>
+                <marker id="minimap_arrow" viewBox="0 0 10 10" refX="5" refY="5" markerWidth="4" markerHeight="4" orient="auto-start-reverse">
+                    <path d="M 0 0 L 10 5 L 0 10 z" fill="#f39c12"/>
+                </marker>
+            </defs>
+            <circle r={world_radius.to_string()} fill="none" stroke="#ffffff80" stroke-width={(world_radius * 0.02).to_string()}/>
+            {props.drawings.iter().map(|drawing| match *drawing {
+                Drawing::Marker(marker) => {
+                    let marker = flip(marker);
+                    html_nested!{
+                        <circle
+                            cx={marker.x.to_string()}
+                            cy={marker.y.to_string()}
+                            r={(world_radius * 0.06).to_string()}
+                            fill="none"
+                            stroke="#f39c12"
+                            stroke-width={(world_radius * 0.025).to_string()}
+                        />
+                    }
+                }
+                Drawing::Arrow(start, end) => {
+                    let (start, end) = (flip(start), flip(end));
+                    html_nested!{
+                        <line
+                            x1={start.x.to_string()}
+                            y1={start.y.to_string()}
+                            x2={end.x.to_string()}
+                            y2={end.y.to_string()}
+                            stroke="#f39c12"
+                            stroke-width={(world_radius * 0.025).to_string()}
+                            marker-end="url(#minimap_arrow)"
+                        />
+                    }
+                }
+            }).collect::<Html>()}
+            <circle cx={position.x.to_string()} cy={position.y.to_string()} r={(world_radius * 0.03).to_string()} fill="white"/>
+        </svg>
+    }
+}
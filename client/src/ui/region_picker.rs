@@ -0,0 +1,62 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::settings::Mk48Settings;
+use crate::translation::Mk48Translation;
+use crate::Mk48Game;
+use client_util::browser_storage::BrowserStorages;
+use common::spawn_region::SpawnRegion;
+use std::str::FromStr;
+use stylist::yew::styled_component;
+use web_sys::{HtmlSelectElement, InputEvent};
+use yew::{html, html_nested, Html, TargetCast};
+use yew_frontend::frontend::use_gctw;
+use yew_frontend::translation::use_translation;
+
+/// Lets the player choose a region of the map to spawn in, e.g. to regroup with their fleet.
+#[styled_component(RegionPicker)]
+pub fn region_picker() -> Html {
+    let select_style = css!(
+        r#"
+        border-radius: 0.25em;
+        box-sizing: border-box;
+        cursor: pointer;
+        display: block;
+        font-size: 1em;
+        font-weight: bold;
+        margin: 0.5rem auto;
+        outline: 0;
+        padding: 0.5em;
+        pointer-events: all;
+        white-space: nowrap;
+        border: 0;
+        color: white;
+        background-color: #00000025;
+        "#
+    );
+
+    let gctw = use_gctw::<Mk48Game>();
+    let t = use_translation();
+
+    let region = gctw.settings_cache.spawn_region;
+    let on_set_region = gctw
+        .change_settings_callback
+        .reform(move |event: InputEvent| {
+            let value = event.target_unchecked_into::<HtmlSelectElement>().value();
+            Box::new(
+                move |settings: &mut Mk48Settings, browser_storages: &mut BrowserStorages| {
+                    if let Ok(region) = SpawnRegion::from_str(&value) {
+                        settings.set_spawn_region(region, browser_storages);
+                    }
+                },
+            )
+        });
+
+    html! {
+        <select id="region_picker" oninput={on_set_region} class={select_style} title={t.spawn_region_label()}>
+            {SpawnRegion::iter().map(|v| html_nested!{
+                <option value={v.to_string()} selected={region == v}>{t.spawn_region(v)}</option>
+            }).collect::<Html>()}
+        </select>
+    }
+}
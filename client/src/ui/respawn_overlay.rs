@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::translation::Mk48Translation;
+use crate::ui::region_picker::RegionPicker;
 use crate::ui::ship_menu::ShipMenu;
+use crate::ui::summary_overlay::SummaryOverlay;
 use crate::ui::{UiEvent, UiStatusRespawning};
 use crate::Mk48Game;
+use common::summary::Summary;
 use stylist::yew::styled_component;
 use yew::{html, Html, Properties};
 use yew_frontend::frontend::use_ui_event_callback;
@@ -15,6 +18,7 @@ use yew_frontend::translation::use_translation;
 pub struct RespawnOverlayProps {
     pub score: u32,
     pub status: UiStatusRespawning,
+    pub summary: Option<Box<Summary>>,
 }
 
 #[styled_component(RespawnOverlay)]
@@ -55,6 +59,10 @@ pub fn respawn_overlay(props: &RespawnOverlayProps) -> Html {
     html! {
         <div id="death" class={container_style} {onanimationend}>
             <h2 class={reason_style}>{t.death_reason(&props.status.death_reason)}</h2>
+            if let Some(summary) = props.summary.clone() {
+                <SummaryOverlay {summary}/>
+            }
+            <RegionPicker/>
             <ShipMenu
                 score={props.score}
                 {onclick}
@@ -1,10 +1,14 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::key_bindings::{Action, KeyBindings};
 use crate::settings::{Mk48Settings, ShadowSetting};
 use crate::ui::UiEvent;
 use crate::Mk48Game;
 use client_util::browser_storage::BrowserStorages;
+use client_util::keyboard::Key;
+use client_util::quality::GraphicsQuality;
+use client_util::screen_reader::Verbosity;
 use client_util::setting::CommonSettings;
 //use core_protocol::dto::ServerDto;
 use core_protocol::id::ServerId;
@@ -45,6 +49,19 @@ pub fn settings_dialog() -> Html {
         "#
     };
 
+    let key_select_style = css! {
+        r#"
+        border-radius: 0.25em;
+        border: 0;
+        color: white;
+        background-color: #0075ff;
+        cursor: pointer;
+        font-weight: bold;
+        margin-right: 0.5em;
+        min-width: 8em;
+        "#
+    };
+
     let t = use_translation();
     let ctw = use_ctw();
     //let core_state = use_core_state();
@@ -134,6 +151,15 @@ pub fn settings_dialog() -> Html {
         )
     });
 
+    let collision_assist = gctw.settings_cache.collision_assist;
+    let on_toggle_collision_assist = gctw.change_settings_callback.reform(move |_| {
+        Box::new(
+            move |settings: &mut Mk48Settings, browser_storages: &mut BrowserStorages| {
+                settings.set_collision_assist(!collision_assist, browser_storages);
+            },
+        )
+    });
+
     let high_contrast = ctw.setting_cache.high_contrast;
     let on_toggle_high_contrast = ctw.change_common_settings_callback.reform(move |_| {
         Box::new(
@@ -143,6 +169,20 @@ pub fn settings_dialog() -> Html {
         )
     });
 
+    let screen_reader = ctw.setting_cache.screen_reader;
+    let on_set_screen_reader =
+        ctw.change_common_settings_callback
+            .reform(move |event: InputEvent| {
+                let value = event.target_unchecked_into::<HtmlSelectElement>().value();
+                Box::new(
+                    move |settings: &mut CommonSettings, browser_storages: &mut BrowserStorages| {
+                        if let Ok(verbosity) = Verbosity::from_str(&value) {
+                            settings.set_screen_reader(verbosity, browser_storages);
+                        }
+                    },
+                )
+            });
+
     let antialias = ctw.setting_cache.antialias;
     let on_toggle_antialias = {
         let graphics_callback = graphics_callback.clone();
@@ -157,6 +197,40 @@ pub fn settings_dialog() -> Html {
         })
     };
 
+    let graphics_quality = ctw.setting_cache.graphics_quality;
+    let auto_graphics_quality = ctw.setting_cache.auto_graphics_quality;
+    let on_set_graphics_quality =
+        ctw.change_common_settings_callback
+            .reform(move |event: InputEvent| {
+                let value = event.target_unchecked_into::<HtmlSelectElement>().value();
+                Box::new(
+                    move |settings: &mut CommonSettings, browser_storages: &mut BrowserStorages| {
+                        // Automatic starts from the default, and lowers it if necessary.
+                        let quality = GraphicsQuality::from_str(&value).ok();
+                        settings
+                            .set_graphics_quality(quality.unwrap_or_default(), browser_storages);
+                        settings.set_auto_graphics_quality(quality.is_none(), browser_storages);
+                    },
+                )
+            });
+
+    let key_bindings = gctw.settings_cache.key_bindings.clone();
+    let on_set_key_factory = |action: Action| {
+        gctw.change_settings_callback
+            .reform(move |event: InputEvent| {
+                let value = event.target_unchecked_into::<HtmlSelectElement>().value();
+                Box::new(
+                    move |settings: &mut Mk48Settings, browser_storages: &mut BrowserStorages| {
+                        if let Ok(key) = Key::from_str(&value) {
+                            let mut key_bindings = settings.key_bindings.clone();
+                            key_bindings.set(action, key);
+                            settings.set_key_bindings(key_bindings, browser_storages);
+                        }
+                    },
+                )
+            })
+    };
+
     //let selected_server_id = ctw.setting_cache.server_id;
     let on_select_server_id = {
         ctw.set_server_id_callback.reform(move |event: InputEvent| {
@@ -179,6 +253,11 @@ pub fn settings_dialog() -> Html {
                 {"Circle HUD"}
             </label>
 
+            <label class={label_style.clone()}>
+                <input type="checkbox" checked={collision_assist} oninput={on_toggle_collision_assist}/>
+                {"Collision Assist"}
+            </label>
+
             <label class={label_style.clone()}>
                 <input type="checkbox" checked={high_contrast} oninput={on_toggle_high_contrast}/>
                 {"High Contrast"}
@@ -194,6 +273,15 @@ pub fn settings_dialog() -> Html {
                 {"Radio"}
             </label>
 
+            <select
+                oninput={on_set_screen_reader}
+                class={select_style.clone()}
+            >
+                {[(Verbosity::Off, "No Screen Reader Announcements"), (Verbosity::Essential, "Essential Announcements"), (Verbosity::Verbose, "Verbose Announcements")].into_iter().map(|(v, d)| html_nested!{
+                    <option value={v.to_string()} selected={screen_reader == v}>{d}</option>
+                }).collect::<Html>()}
+            </select>
+
             <select
                 oninput={on_select_server_id}
                 class={select_style.clone()}
@@ -226,6 +314,32 @@ pub fn settings_dialog() -> Html {
                     <option value={v.to_string()} selected={shadows == v}>{d}</option>
                 }).collect::<Html>()}
             </select>
+
+            <select
+                oninput={on_set_graphics_quality}
+                class={select_style.clone()}
+            >
+                <option value="auto" selected={auto_graphics_quality}>{"Automatic Quality"}</option>
+                {[(GraphicsQuality::High, "High Quality"), (GraphicsQuality::Medium, "Medium Quality"), (GraphicsQuality::Low, "Low Quality")].into_iter().map(|(v, d)| html_nested!{
+                    <option value={v.to_string()} selected={!auto_graphics_quality && graphics_quality == v}>{d}</option>
+                }).collect::<Html>()}
+            </select>
+
+            <h3>{"Controls"}</h3>
+
+            {Action::ALL.into_iter().map(|action| {
+                let bound = key_bindings.key(action);
+                html_nested!{
+                    <label class={label_style.clone()}>
+                        <select oninput={on_set_key_factory(action)} class={key_select_style.clone()}>
+                            {Key::iter().filter(|&key| !KeyBindings::is_reserved(key)).map(|key| html_nested!{
+                                <option value={key.to_string()} selected={key == bound}>{key.to_string()}</option>
+                            }).collect::<Html>()}
+                        </select>
+                        {action.label()}
+                    </label>
+                }
+            }).collect::<Html>()}
         </Dialog>
     }
 }
@@ -2,20 +2,24 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::armament::{group_armaments, Group};
+use crate::key_bindings::Action;
 use crate::translation::Mk48Translation;
 use crate::ui::sprite::Sprite;
 use crate::ui::{UiEvent, UiStatusPlaying};
 use crate::Mk48Game;
+use client_util::keyboard::Key;
 use common::altitude::Altitude;
 use common::entity::{EntityData, EntitySubKind, EntityType};
+use common::status_effects::StatusEffects;
+use common::ticks::Ticks;
 use core_protocol::id::LanguageId;
 use stylist::yew::styled_component;
 use stylist::{css, StyleSource};
-use web_sys::MouseEvent;
+use web_sys::{MouseEvent, PointerEvent};
 use yew::{classes, html, html_nested, AttrValue, Callback, Html, Properties};
 use yew_frontend::component::positioner::Position;
 use yew_frontend::component::section::Section;
-use yew_frontend::frontend::use_ui_event_callback;
+use yew_frontend::frontend::{use_gctw, use_ui_event_callback};
 use yew_frontend::translation::use_translation;
 
 #[derive(Properties, PartialEq)]
@@ -71,32 +75,49 @@ pub fn ship_controls(props: &ShipControlsProps) -> Html {
     let ui_event_callback = use_ui_event_callback::<Mk48Game>();
     let select_factory = {
         let ui_event_callback = ui_event_callback.clone();
-        move |entity_type: EntityType| {
-            (!props.status.armament.contains(&entity_type)).then(move || {
-                ui_event_callback.reform(move |_: MouseEvent| UiEvent::Armament(Some(entity_type)))
+        move |group: u8| {
+            (!props.status.armament.contains(&group)).then(move || {
+                ui_event_callback.reform(move |_: MouseEvent| UiEvent::Armament(Some(group)))
             })
         }
     };
 
     let t = use_translation();
+    let key_bindings = use_gctw::<Mk48Game>().settings_cache.key_bindings;
     let status = &props.status;
     html! {
         <Section id="controls" name={data.label.clone()} position={props.position} style={props.style.clone()} closable={false}>
             if status.entity_type.data().armaments.is_empty() {
                 <small>{t.entity_kind_hint(status.entity_type.data().kind, status.entity_type.data().sub_kind)}</small>
             } else {
-                {group_armaments(&status.entity_type.data().armaments, &*status.armament_consumption).into_iter().map(|Group{entity_type, total, ready}| {
-                    let onclick = select_factory.clone()(entity_type);
+                {group_armaments(status.entity_type.data(), &*status.armament_consumption).into_iter().map(|Group{index, entity_type, total, ready}| {
+                    let onclick = select_factory.clone()(index);
+                    // Ammunition in reserve, if limited.
+                    let consumption = if let Some(reserve) = status.ammo.reserve(entity_type) {
+                        format!("{ready}/{total} +{reserve}")
+                    } else {
+                        format!("{ready}/{total}")
+                    };
                     html_nested!{
-                        <div class={classes!(button_style.clone(), onclick.is_none().then(|| button_selected_style.clone()))} {onclick}>
+                        <div class={classes!(button_style.clone(), onclick.is_none().then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick}>
                             <Sprite {entity_type} class={classes!((ready == 0).then(|| consumed_style.clone()))}/>
-                            <span class={consumption_style.clone()}>{format!("{ready}/{total}")}</span>
+                            <span class={consumption_style.clone()}>{consumption}</span>
                         </div>
                     }
                 }).collect::<Html>()}
             }
-            {surface_button(t, props.status.entity_type, props.status.submerge, &button_style, &button_selected_style, &ui_event_callback)}
-            {active_sensor_button(t, props.status.entity_type, props.status.active, props.status.altitude, &button_style, &button_selected_style, &ui_event_callback)}
+            {surface_button(t, props.status.entity_type, props.status.submerge, key_bindings.key(Action::Surface), &button_style, &button_selected_style, &ui_event_callback)}
+            {periscope_button(t, props.status.entity_type, props.status.periscope, key_bindings.key(Action::Periscope), &button_style, &button_selected_style, &ui_event_callback)}
+            {active_sensor_button(t, props.status.entity_type, props.status.active, props.status.altitude, key_bindings.key(Action::Active), &button_style, &button_selected_style, &ui_event_callback)}
+            {emcon_button(t, props.status.entity_type, props.status.emcon, key_bindings.key(Action::Emcon), &button_style, &button_selected_style, &ui_event_callback)}
+            {searchlight_button(t, props.status.entity_type, props.status.searchlight, key_bindings.key(Action::Searchlight), &button_style, &button_selected_style, &ui_event_callback)}
+            {cruise_button(t, props.status.cruise, key_bindings.key(Action::Cruise), &button_style, &button_selected_style, &ui_event_callback)}
+            {unlock_turrets_button(t, props.status.turrets_locked, key_bindings.key(Action::LockTurrets), &button_style, &button_selected_style, &ui_event_callback)}
+            {release_target_button(t, props.status.target_locked, key_bindings.key(Action::LockTarget), &button_style, &button_selected_style, &ui_event_callback)}
+            {return_to_port_button(t, props.status.port_progress, props.status.banked_coins, &button_style, &button_selected_style, &ui_event_callback)}
+            {scuttle_button(t, props.status.scuttle_hold, props.status.scuttle_progress, &button_style, &button_selected_style, &ui_event_callback)}
+            {damage_control_button(t, props.status.status_effects, &button_style, &button_selected_style, &ui_event_callback)}
+            {repair_crew_button(t, props.status.status_effects, key_bindings.key(Action::RepairCrew), &button_style, &button_selected_style, &ui_event_callback)}
         </Section>
     }
 }
@@ -105,6 +126,7 @@ fn surface_button(
     t: LanguageId,
     entity_type: EntityType,
     submerge: bool,
+    key: Key,
     button_style: &StyleSource,
     button_selected_style: &StyleSource,
     ui_event_callback: &Callback<UiEvent>,
@@ -116,18 +138,42 @@ fn surface_button(
         let surface_or_dive = if submerge {t.ship_surface_label()} else {t.ship_dive_label()};
 
         html! {
-            <div class={classes!(button_style.clone(), (submerge).then(|| button_selected_style.clone()))} {onclick} title={t.ship_surface_hint()}>
+            <div class={classes!(button_style.clone(), (submerge).then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick} title={t.ship_surface_hint(key)}>
                 {surface_or_dive}
             </div>
         }
     }
 }
 
+/// Toggles holding at periscope depth, where submarines may see the surface and launch missiles.
+fn periscope_button(
+    t: LanguageId,
+    entity_type: EntityType,
+    periscope: bool,
+    key: Key,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    if entity_type.data().sub_kind != EntitySubKind::Submarine {
+        Html::default()
+    } else {
+        let onclick = ui_event_callback.reform(move |_: MouseEvent| UiEvent::Periscope(!periscope));
+
+        html! {
+            <div class={classes!(button_style.clone(), periscope.then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick} title={t.ship_periscope_hint(key)}>
+                {t.ship_periscope_label()}
+            </div>
+        }
+    }
+}
+
 fn active_sensor_button(
     t: LanguageId,
     entity_type: EntityType,
     active: bool,
     altitude: Altitude,
+    key: Key,
     button_style: &StyleSource,
     button_selected_style: &StyleSource,
     ui_event_callback: &Callback<UiEvent>,
@@ -146,13 +192,246 @@ fn active_sensor_button(
             )
             .intersperse(" / ")
             .collect::<String>();
-        let title = t.sensor_active_hint(&sensors);
+        let title = t.sensor_active_hint(&sensors, key);
         let onclick = ui_event_callback.reform(move |_: MouseEvent| UiEvent::Active(!active));
 
         html! {
-            <div class={classes!(button_style.clone(), active.then(|| button_selected_style.clone()))} {onclick} {title}>
+            <div class={classes!(button_style.clone(), active.then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick} {title}>
                 {t.sensor_active_label()}
             </div>
         }
     }
 }
+
+/// Toggles emission control, which keeps radar silent so that it doesn't give away the boat.
+fn emcon_button(
+    t: LanguageId,
+    entity_type: EntityType,
+    emcon: bool,
+    key: Key,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    if entity_type.data().sensors.radar.range <= 0.0 {
+        Html::default()
+    } else {
+        let onclick = ui_event_callback.reform(move |_: MouseEvent| UiEvent::Emcon(!emcon));
+
+        html! {
+            <div class={classes!(button_style.clone(), emcon.then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick} title={t.ship_emcon_hint(key)}>
+                {t.ship_emcon_label()}
+            </div>
+        }
+    }
+}
+
+/// Toggles cruise control, which holds speed through turns.
+fn cruise_button(
+    t: LanguageId,
+    cruise: bool,
+    key: Key,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    let onclick = ui_event_callback.reform(move |_: MouseEvent| UiEvent::Cruise(!cruise));
+
+    html! {
+        <div class={classes!(button_style.clone(), cruise.then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick} title={t.ship_cruise_hint(key)}>
+            {t.ship_cruise_label()}
+        </div>
+    }
+}
+
+/// Lets locked turrets follow the aim point again. Only shown while any turrets are locked, since
+/// they are locked with the keyboard.
+fn unlock_turrets_button(
+    t: LanguageId,
+    turrets_locked: bool,
+    key: Key,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    if !turrets_locked {
+        Html::default()
+    } else {
+        let onclick = ui_event_callback.reform(|_: MouseEvent| UiEvent::UnlockTurrets);
+
+        html! {
+            <div class={classes!(button_style.clone(), button_selected_style.clone())} tabindex="0" role="button" {onclick} title={t.ship_unlock_turrets_hint(key)}>
+                {t.ship_unlock_turrets_label()}
+            </div>
+        }
+    }
+}
+
+/// Releases the target lock. Only shown while locked onto a target, since targets are designated
+/// on the map.
+fn release_target_button(
+    t: LanguageId,
+    target_locked: bool,
+    key: Key,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    if !target_locked {
+        Html::default()
+    } else {
+        let onclick = ui_event_callback.reform(|_: MouseEvent| UiEvent::ReleaseTarget);
+
+        html! {
+            <div class={classes!(button_style.clone(), button_selected_style.clone())} tabindex="0" role="button" {onclick} title={t.ship_release_target_hint(key)}>
+                {t.ship_release_target_label()}
+            </div>
+        }
+    }
+}
+
+/// Toggles the searchlight, which boats with turrets may shine towards where they aim.
+fn searchlight_button(
+    t: LanguageId,
+    entity_type: EntityType,
+    searchlight: bool,
+    key: Key,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    if entity_type.data().turrets.is_empty() {
+        Html::default()
+    } else {
+        let onclick = ui_event_callback.reform(move |_: MouseEvent| UiEvent::Searchlight(!searchlight));
+
+        html! {
+            <div class={classes!(button_style.clone(), searchlight.then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick} title={t.ship_searchlight_hint(key)}>
+                {t.ship_searchlight_label()}
+            </div>
+        }
+    }
+}
+
+fn return_to_port_button(
+    t: LanguageId,
+    port_progress: Option<f32>,
+    banked_coins: u32,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    let returning = port_progress.is_some();
+    let onclick = ui_event_callback.reform(move |_: MouseEvent| UiEvent::ReturnToPort(!returning));
+    let label = if let Some(progress) = port_progress {
+        format!("{} {:.0}%", t.ship_return_to_port_label(), progress * 100.0)
+    } else {
+        t.ship_return_to_port_label().to_owned()
+    };
+
+    html! {
+        <div class={classes!(button_style.clone(), returning.then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick} title={t.ship_return_to_port_hint(banked_coins)}>
+            {label}
+        </div>
+    }
+}
+
+/// Must be held for [`Scuttle::HOLD`][common::protocol::Scuttle::HOLD] seconds to start scuttling, so that it isn't started by
+/// accident. Activating it with the keyboard starts (or stops) holding it, since keys can't hold
+/// controls. Clicking it while scuttling cancels.
+fn scuttle_button(
+    t: LanguageId,
+    scuttle_hold: Option<f32>,
+    scuttle_progress: Option<f32>,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    let scuttling = scuttle_progress.is_some();
+    let holding = scuttle_hold.is_some();
+    let onpointerdown = (!scuttling)
+        .then(|| ui_event_callback.reform(|_: PointerEvent| UiEvent::HoldScuttle(true)));
+    let onpointerup = ui_event_callback.reform(|_: PointerEvent| UiEvent::HoldScuttle(false));
+    let onpointerleave = onpointerup.clone();
+    let onclick = ui_event_callback.reform(move |e: MouseEvent| {
+        if scuttling {
+            UiEvent::CancelScuttle
+        } else if e.detail() == 0 {
+            // Not a click of a pointing device, so from the keyboard.
+            UiEvent::HoldScuttle(!holding)
+        } else {
+            UiEvent::HoldScuttle(false)
+        }
+    });
+    let label = if let Some(progress) = scuttle_progress.or(scuttle_hold) {
+        format!("{} {:.0}%", t.ship_scuttle_label(), progress * 100.0)
+    } else {
+        t.ship_scuttle_label().to_owned()
+    };
+
+    html! {
+        <div class={classes!(button_style.clone(), (scuttling || holding).then(|| button_selected_style.clone()))} tabindex="0" role="button" {onpointerdown} {onpointerup} {onpointerleave} {onclick} title={t.ship_scuttle_hint()}>
+            {label}
+        </div>
+    }
+}
+
+/// Shows fires and flooding, and toggles damage control. Hidden when there is nothing to fix.
+fn damage_control_button(
+    t: LanguageId,
+    status_effects: StatusEffects,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    let active = status_effects.damage_control;
+    if !(active || status_effects.is_on_fire() || status_effects.is_flooding()) {
+        Html::default()
+    } else {
+        let onclick = ui_event_callback.reform(move |_: MouseEvent| UiEvent::DamageControl(!active));
+        let fire = status_effects
+            .is_on_fire()
+            .then(|| format!("🔥 {}", t.ship_fire_label()));
+        let flooding = status_effects.is_flooding().then(|| {
+            format!("🌊 {} {:.0}%", t.ship_flooding_label(), status_effects.flooding * 100.0)
+        });
+
+        html! {
+            <div class={classes!(button_style.clone(), active.then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick} title={t.ship_damage_control_hint()}>
+                {t.ship_damage_control_label()}
+                if let Some(fire) = fire {
+                    <br/><small>{fire}</small>
+                }
+                if let Some(flooding) = flooding {
+                    <br/><small>{flooding}</small>
+                }
+            </div>
+        }
+    }
+}
+
+/// Calls the repair crew, showing the time until it may be called again.
+fn repair_crew_button(
+    t: LanguageId,
+    status_effects: StatusEffects,
+    key: Key,
+    button_style: &StyleSource,
+    button_selected_style: &StyleSource,
+    ui_event_callback: &Callback<UiEvent>,
+) -> Html {
+    let working = status_effects.repair_crew != Ticks::ZERO;
+    let cooldown = status_effects.repair_crew_cooldown;
+    let onclick = (cooldown == Ticks::ZERO)
+        .then(|| ui_event_callback.reform(|_: MouseEvent| UiEvent::RepairCrew));
+    let label = if cooldown == Ticks::ZERO {
+        t.ship_repair_crew_label().to_owned()
+    } else {
+        format!("{} {:.0}s", t.ship_repair_crew_label(), cooldown.to_secs().ceil())
+    };
+
+    html! {
+        <div class={classes!(button_style.clone(), working.then(|| button_selected_style.clone()))} tabindex="0" role="button" {onclick} title={t.ship_repair_crew_hint(key)}>
+            {label}
+        </div>
+    }
+}
@@ -124,7 +124,7 @@ fn entity_card(
                     <Sprite {entity_type}/>
                 </td>
             </tr>
-            {group_armaments(&data.armaments, &[]).into_iter().map(|Group{entity_type, total, ..}| html_nested!{
+            {group_armaments(data, &[]).into_iter().map(|Group{entity_type, total, ..}| html_nested!{
                 <tr>
                     <td colspan="2">
                         {entity_card(t, table_style, entity_type, Some(total))}
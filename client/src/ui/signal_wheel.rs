@@ -0,0 +1,90 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::translation::Mk48Translation;
+use crate::ui::UiEvent;
+use crate::Mk48Game;
+use common::signal::SignalKind;
+use glam::Vec2;
+use std::f32::consts::PI;
+use stylist::yew::styled_component;
+use yew::{html, html_nested, Html, Properties};
+use yew_frontend::frontend::use_ui_event_callback;
+use yew_frontend::translation::use_translation;
+
+#[derive(Properties, PartialEq)]
+pub struct SignalWheelProps {
+    /// Center of the wheel, in view space (-1 to 1).
+    pub position: Vec2,
+}
+
+/// Radial menu for sending a signal to teammates.
+#[styled_component(SignalWheel)]
+pub fn signal_wheel(props: &SignalWheelProps) -> Html {
+    let wheel_style = css!(
+        r#"
+        height: 0;
+        pointer-events: none;
+        position: absolute;
+        width: 0;
+        "#
+    );
+
+    let button_style = css!(
+        r#"
+        background-color: #00000080;
+        border-radius: 50%;
+        border: 0;
+        color: white;
+        cursor: pointer;
+        font-size: 1rem;
+        height: 5rem;
+        pointer-events: all;
+        position: absolute;
+        transform: translate(-50%, -50%);
+        width: 5rem;
+
+        :hover {
+            background-color: #000000c0;
+        }
+        "#
+    );
+
+    let t = use_translation();
+    let ui_event_callback = use_ui_event_callback::<Mk48Game>();
+
+    // Convert from view space to CSS.
+    let left = (props.position.x + 1.0) * 50.0;
+    let top = (1.0 - props.position.y) * 50.0;
+
+    let count = SignalKind::iter().count();
+    let radius = 4.5;
+
+    html! {
+        <div id="signal_wheel" class={wheel_style} style={format!("left: {}%; top: {}%;", left, top)}>
+            {SignalKind::iter().enumerate().map(|(i, kind)| {
+                // Start at the top, and go clockwise.
+                let angle = PI * 0.5 - 2.0 * PI * i as f32 / count as f32;
+                let offset = Vec2::new(angle.cos(), -angle.sin()) * radius;
+                let onclick = ui_event_callback.reform(move |_| UiEvent::Signal(Some(kind)));
+                html_nested!{
+                    <button
+                        class={button_style.clone()}
+                        style={format!("left: {}rem; top: {}rem;", offset.x, offset.y)}
+                        title={t.signal(kind)}
+                        {onclick}
+                    >
+                        {kind.icon()}<br/>{t.signal(kind)}
+                    </button>
+                }
+            }).collect::<Html>()}
+            <button
+                class={button_style.clone()}
+                style="left: 0; top: 0; height: 2.5rem; width: 2.5rem;"
+                onclick={ui_event_callback.reform(|_| UiEvent::Signal(None))}
+            >
+                {"✘"}
+            </button>
+        </div>
+    }
+}
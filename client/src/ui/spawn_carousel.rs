@@ -0,0 +1,122 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::translation::Mk48Translation;
+use crate::ui::sprite::Sprite;
+use common::entity::{EntityData, EntityType};
+use stylist::yew::styled_component;
+use web_sys::MouseEvent;
+use yew::{html, use_effect_with_deps, Callback, Html, Properties};
+use yew_frontend::frontend::use_core_state;
+use yew_frontend::translation::use_translation;
+
+#[derive(Properties, PartialEq)]
+pub struct SpawnCarouselProps {
+    /// Currently selected hull, which will be sent in the spawn command.
+    pub selected: EntityType,
+    pub onchange: Callback<EntityType>,
+}
+
+/// Lets the player choose which hull to spawn as, from the hulls they are currently allowed to
+/// spawn as.
+#[styled_component(SpawnCarousel)]
+pub fn spawn_carousel(props: &SpawnCarouselProps) -> Html {
+    let container_style = css!(
+        r#"
+        color: white;
+        display: flex;
+        flex-direction: row;
+        align-items: center;
+        justify-content: center;
+        gap: 1rem;
+        margin: 1rem auto;
+        user-select: none;
+        "#
+    );
+
+    let arrow_style = css!(
+        r#"
+        background-color: transparent;
+        border: 0;
+        color: white;
+        cursor: pointer;
+        font-size: 2rem;
+        padding: 0 0.5rem;
+
+        :disabled {
+            visibility: hidden;
+        }
+        "#
+    );
+
+    let blurb_style = css!(
+        r#"
+        font-size: 0.9rem;
+        margin: 0.25rem 0 0 0;
+        opacity: 0.8;
+        "#
+    );
+
+    let t = use_translation();
+    let core_state = use_core_state();
+    let moderator = core_state.player().map(|p| p.moderator).unwrap_or(false);
+    // Players always spawn with no score.
+    let options: Vec<EntityType> = EntityType::spawn_options(0, false, moderator).collect();
+
+    // The selection may have become invalid (e.g. moderator status changed).
+    let index = options
+        .iter()
+        .position(|&option| option == props.selected)
+        .unwrap_or(0);
+    let selected = options.get(index).copied();
+
+    // Replace an invalid selection with the one shown, so that it is what gets spawned as.
+    {
+        let onchange = props.onchange.clone();
+        use_effect_with_deps(
+            move |&(selected, shown)| {
+                if let Some(shown) = shown.filter(|&shown| shown != selected) {
+                    onchange.emit(shown);
+                }
+                || ()
+            },
+            (props.selected, selected),
+        );
+    }
+
+    let selected = if let Some(selected) = selected {
+        selected
+    } else {
+        debug_assert!(false, "no choices to spawn");
+        return Html::default();
+    };
+    let data: &'static EntityData = selected.data();
+
+    let arrow_factory = |increment: isize| -> Option<Callback<MouseEvent>> {
+        let new = index as isize + increment;
+        (0..options.len() as isize).contains(&new).then(|| {
+            let entity_type = options[new as usize];
+            props.onchange.reform(move |e: MouseEvent| {
+                e.prevent_default();
+                entity_type
+            })
+        })
+    };
+    let left = arrow_factory(-1);
+    let right = arrow_factory(1);
+
+    html! {
+        <div id="spawn_carousel" class={container_style}>
+            <button type="button" class={arrow_style.clone()} disabled={left.is_none()} onclick={left}>{"◀"}</button>
+            <div>
+                <Sprite entity_type={selected}/>
+                <h3 style="margin: 0.5rem 0 0 0;">{format!("{} ({})", data.label, t.entity_kind_name(data.kind, data.sub_kind))}</h3>
+                <p class={blurb_style.clone()}>{t.entity_kind_hint(data.kind, data.sub_kind)}</p>
+                <p class={blurb_style}>
+                    {t.spawn_hull_stats(data.speed.to_knots(), data.damage)}
+                </p>
+            </div>
+            <button type="button" class={arrow_style} disabled={right.is_none()} onclick={right}>{"▶"}</button>
+        </div>
+    }
+}
@@ -68,7 +68,14 @@ pub fn sprite(props: &SpriteProps) -> Html {
     });
 
     html! {
-        <div onclick={props.onclick.clone()} class={classes!(container_style, props.class.clone())} style={format!("width: {}px; height: {}px;", sprite.width, sprite.height)}>
+        <div
+            onclick={props.onclick.clone()}
+            tabindex={props.onclick.is_some().then_some("0")}
+            role={props.onclick.is_some().then_some("button")}
+            aria-label={props.onclick.is_some().then(|| title.clone())}
+            class={classes!(container_style, props.class.clone())}
+            style={format!("width: {}px; height: {}px;", sprite.width, sprite.height)}
+        >
             <div {title} class={classes!(image_style, props.image_class.clone())} style={format!("background-position: -{}px -{}px; width: {}px; height: {}px;", sprite.x, sprite.y, sprite.width, sprite.height)}></div>
             if let Some(children) = props.children.clone() {
                 <div class={children_style}>
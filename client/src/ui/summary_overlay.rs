@@ -0,0 +1,92 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::translation::Mk48Translation;
+use common::summary::Summary;
+use std::fmt::Write;
+use stylist::yew::styled_component;
+use yew::{html, html_nested, Html, Properties};
+use yew_frontend::translation::use_translation;
+
+#[derive(Properties, PartialEq)]
+pub struct SummaryOverlayProps {
+    pub summary: Box<Summary>,
+}
+
+/// Shows statistics about the player's previous life.
+#[styled_component(SummaryOverlay)]
+pub fn summary_overlay(props: &SummaryOverlayProps) -> Html {
+    let container_style = css!(
+        r#"
+        color: white;
+        margin: 0.5rem auto;
+        user-select: none;
+        "#
+    );
+
+    let table_style = css!(
+        r#"
+        border-collapse: collapse;
+        margin: 0 auto;
+        white-space: nowrap;
+
+        td {
+            padding: 0.1rem 0.5rem;
+        }
+
+        td:first-child {
+            opacity: 0.8;
+            text-align: right;
+        }
+
+        td:last-child {
+            text-align: left;
+        }
+        "#
+    );
+
+    let t = use_translation();
+    let summary = &props.summary;
+    let minutes = summary.time_alive / 60;
+    let seconds = summary.time_alive % 60;
+
+    html! {
+        <div id="summary" class={container_style}>
+            <table class={table_style}>
+                <tr><td>{t.summary_time_alive()}</td><td>{format!("{}:{:02}", minutes, seconds)}</td></tr>
+                <tr><td>{t.summary_highest_level()}</td><td>{summary.highest_level}</td></tr>
+                <tr><td>{t.summary_kill_score()}</td><td>{summary.kill_score}</td></tr>
+                <tr><td>{t.summary_loot_score()}</td><td>{t.summary_loot(summary.loot_score, summary.coins_collected)}</td></tr>
+                {summary.kills.iter().map(|&(weapon, count)| {
+                    let weapon = weapon.map(|w| w.data().label).unwrap_or(t.summary_kills_other());
+                    html_nested!{
+                        <tr><td>{t.summary_kills(weapon)}</td><td>{count}</td></tr>
+                    }
+                }).collect::<Html>()}
+            </table>
+            if let Some(points) = sparkline_points(&summary.score_timeline, 200.0, 40.0) {
+                <svg width="200" height="40" viewBox="0 0 200 40" style="display: block; margin: 0.5rem auto;">
+                    <polyline {points} fill="none" stroke="white" stroke-width="2"/>
+                </svg>
+            }
+        </div>
+    }
+}
+
+/// Formats the score timeline as SVG polyline points, scaled to fit the given dimensions. Returns
+/// [`None`] if there are too few samples to draw a line.
+fn sparkline_points(timeline: &[u32], width: f32, height: f32) -> Option<String> {
+    if timeline.len() < 2 {
+        return None;
+    }
+    let max = timeline.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let step = width / (timeline.len() - 1) as f32;
+
+    let mut points = String::new();
+    for (i, &score) in timeline.iter().enumerate() {
+        // Leave room for the stroke at the top and bottom.
+        let y = 1.0 + (height - 2.0) * (1.0 - score as f32 / max);
+        let _ = write!(points, "{:.1},{:.1} ", i as f32 * step, y);
+    }
+    Some(points)
+}
@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::translation::Mk48Translation;
+use crate::ui::UiEvent;
+use crate::Mk48Game;
+use common::protocol::Transfer;
+use core_protocol::id::PlayerId;
+use stylist::yew::styled_component;
+use yew::{html, html_nested, Html, Properties};
+use yew_frontend::component::section::Section;
+use yew_frontend::frontend::{use_core_state, use_ui_event_callback};
+use yew_frontend::translation::use_translation;
+
+#[derive(Properties, PartialEq)]
+pub struct TransferOverlayProps {
+    /// Fleet members close enough to transfer score to, closest first.
+    pub candidates: Vec<PlayerId>,
+}
+
+/// Lists nearby fleet members that the player may send score to.
+#[styled_component(TransferOverlay)]
+pub fn transfer_overlay(props: &TransferOverlayProps) -> Html {
+    let table_style = css!(
+        r#"
+        color: white;
+        width: 100%;
+        border-spacing: 0;
+        border-collapse: collapse;
+        "#
+    );
+
+    let name_style = css!(
+        r#"
+        white-space: nowrap;
+        "#
+    );
+
+    let button_style = css!(
+        r#"
+        background-color: transparent;
+        border: 0;
+        border-radius: 0.25em;
+        color: white;
+        cursor: pointer;
+        font-size: 1em;
+        padding: 0.1em 0.5em;
+        white-space: nowrap;
+
+        :hover {
+            background-color: #00000025;
+        }
+        "#
+    );
+
+    let t = use_translation();
+    let core_state = use_core_state();
+    let ui_event_callback = use_ui_event_callback::<Mk48Game>();
+
+    if props.candidates.is_empty() {
+        return Html::default();
+    }
+
+    html! {
+        <Section id="transfers" name={t.transfer_label()}>
+            <table class={table_style}>
+                {props.candidates.iter().take(3).map(|&player_id| {
+                    let alias = core_state
+                        .player_or_bot(player_id)
+                        .map(|player| player.alias.as_str().to_owned())
+                        .unwrap_or_default();
+                    html_nested!{
+                        <tr>
+                            <td class={name_style.clone()}>{alias}</td>
+                            <td>
+                                {Transfer::AMOUNTS.into_iter().map(|amount| {
+                                    let onclick = ui_event_callback.reform(move |_| UiEvent::Transfer{player_id, amount});
+                                    html_nested!{
+                                        <button class={button_style.clone()} {onclick} title={t.transfer_hint(amount, Transfer::received(amount))}>{"+"}{amount}</button>
+                                    }
+                                }).collect::<Html>()}
+                            </td>
+                        </tr>
+                    }
+                }).collect::<Html>()}
+            </table>
+        </Section>
+    }
+}
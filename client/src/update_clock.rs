@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::ticks::Ticks;
+
+/// Estimates how late each update arrived, based on the server tick it was generated at, so that
+/// contacts can be extrapolated consistently despite network jitter.
+#[derive(Default)]
+pub struct UpdateClock {
+    /// Server tick of the last update.
+    last_tick: Option<Ticks>,
+    /// Client time that the last update arrived.
+    last_time: f32,
+    /// Ticks elapsed since the first update (doesn't wrap like [`Ticks`]).
+    elapsed_ticks: u32,
+    /// Difference between arrival time and server time of the least delayed update.
+    baseline: f32,
+}
+
+impl UpdateClock {
+    /// Seconds per second that the baseline creeps upward, so that the clock adapts if latency
+    /// increases.
+    const DRIFT: f32 = 0.01;
+    /// Updates that are later than this are not extrapolated any further.
+    const MAX_LATENESS: f32 = 0.5;
+
+    /// Forgets all previous updates (e.g. after reconnecting).
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Records an update generated at a server tick, arriving at a client time (both in seconds),
+    /// and returns how many seconds later than expected it arrived.
+    pub fn lateness(&mut self, tick: Ticks, time: f32, tick_period: f32) -> f32 {
+        let first = if let Some(last_tick) = self.last_tick {
+            self.elapsed_ticks += tick.0.wrapping_sub(last_tick.0) as u32;
+            false
+        } else {
+            true
+        };
+
+        let offset = time - self.elapsed_ticks as f32 * tick_period;
+        self.baseline = if first {
+            offset
+        } else {
+            (self.baseline + (time - self.last_time) * Self::DRIFT).min(offset)
+        };
+        self.last_tick = Some(tick);
+        self.last_time = time;
+
+        (offset - self.baseline).clamp(0.0, Self::MAX_LATENESS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::update_clock::UpdateClock;
+    use common::ticks::Ticks;
+
+    #[test]
+    fn lateness() {
+        let mut clock = UpdateClock::default();
+        let period = Ticks::PERIOD_SECS;
+        let mut tick = Ticks::from_repr(u16::MAX - 5);
+        let mut time = 100.0;
+
+        // On time, including when the tick wraps.
+        for _ in 0..10 {
+            assert!(clock.lateness(tick, time, period) < 0.001);
+            tick = tick.wrapping_add(Ticks::ONE);
+            time += period;
+        }
+
+        // Late.
+        let lateness = clock.lateness(tick, time + 0.05, period);
+        assert!((lateness - 0.05).abs() < 0.01, "{}", lateness);
+
+        // Skipped ticks aren't late.
+        tick = tick.wrapping_add(Ticks::from_repr(3));
+        time += period * 3.0;
+        assert!(clock.lateness(tick, time, period) < 0.01);
+    }
+}
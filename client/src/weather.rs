@@ -1,3 +1,4 @@
+use common::sea_state::SeaState;
 use glam::{vec2, vec3, Vec2, Vec3};
 use std::f32::consts::TAU;
 
@@ -8,7 +9,12 @@ pub struct Weather {
     pub sun: Vec3,
     /// Points in the direction of the wind.
     pub wind: Vec2,
-    // TODO make wind change wave height
+    /// Offset of the wave field, so that waves match between clients.
+    pub wave_offset: Vec2,
+    /// Meters the wave field has drifted with the wind.
+    pub wave_drift: Vec2,
+    /// Wave height relative to a typical sea.
+    pub wave_scale: f32,
 }
 
 impl Default for Weather {
@@ -16,6 +22,9 @@ impl Default for Weather {
         Self {
             sun: vec3(0.5, 0.5, 0.8).normalize(),
             wind: vec2(7.0, 1.5),
+            wave_offset: Vec2::ZERO,
+            wave_drift: Vec2::ZERO,
+            wave_scale: 1.0,
         }
     }
 }
@@ -24,7 +33,9 @@ impl Weather {
     // Test [`Weather`] with unrealistic conditions.
     const TEST: bool = false;
 
-    pub fn new(time: f32) -> Self {
+    /// Weather matching the server's [`SeaState`], which was received at `received` (both in
+    /// seconds).
+    pub fn new(time: f32, sea_state: &SeaState, received: f32) -> Self {
         if Self::TEST {
             // Make sun sin fast for testing.
             let (x, y) = (time * (3.0 / TAU)).sin_cos();
@@ -32,9 +43,23 @@ impl Weather {
 
             // Increase wind for testing.
             let wind = Self::default().wind * 1.0;
-            Self { sun, wind }
+            Self {
+                sun,
+                wind,
+                wave_drift: wind * time,
+                ..Self::default()
+            }
         } else {
-            Self::default()
+            // Extrapolate drift between updates.
+            let elapsed = (time - received).clamp(0.0, 1.0);
+            let wind = sea_state.wind();
+            Self {
+                wind,
+                wave_offset: sea_state.wave_offset(),
+                wave_drift: sea_state.wave_drift + wind * elapsed,
+                wave_scale: sea_state.wave_scale(),
+                ..Self::default()
+            }
         }
     }
 
@@ -122,6 +122,26 @@ impl Altitude {
         self > Self::ZERO
     }
 
+    /// Returns the band of the sea, if submerged.
+    pub fn depth_band(self) -> Option<DepthBand> {
+        if !self.is_submerged() {
+            None
+        } else if self >= DepthBand::PERISCOPE_FLOOR {
+            Some(DepthBand::Periscope)
+        } else {
+            Some(DepthBand::Deep)
+        }
+    }
+
+    /// Returns the band of airspace, if airborne.
+    pub fn air_band(self) -> Option<AirBand> {
+        AirBand::ALL
+            .iter()
+            .rev()
+            .copied()
+            .find(|band| self >= band.floor())
+    }
+
     /// Returns positive difference between two altitudes.
     pub fn difference(self, other: Self) -> Self {
         if self < other {
@@ -132,6 +152,66 @@ impl Altitude {
     }
 }
 
+/// Discrete layers of airspace. Airborne entities only intercept others in the same band, surface
+/// to air missiles can only reach so high, and bombs must be released from low enough.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum AirBand {
+    Low,
+    Medium,
+    High,
+}
+
+impl AirBand {
+    pub const ALL: [Self; 3] = [Self::Low, Self::Medium, Self::High];
+    /// Highest band surface to air missiles can reach.
+    pub const SAM_CEILING: Self = Self::Medium;
+    /// Highest band unguided bombs may be released from.
+    pub const BOMB_RELEASE_CEILING: Self = Self::Low;
+
+    /// Lowest altitude within the band.
+    pub const fn floor(self) -> Altitude {
+        match self {
+            Self::Low => Altitude::UNIT,
+            Self::Medium => Altitude(25),
+            Self::High => Altitude(97),
+        }
+    }
+
+    /// Multiplier of radar and visual range, due to a more distant horizon.
+    pub fn sensor_multiplier(self) -> f32 {
+        match self {
+            Self::Low => 1.0,
+            Self::Medium => 1.1,
+            Self::High => 1.25,
+        }
+    }
+}
+
+/// Layers of the sea that submarines may be in. At periscope depth, only a submarine's mast is
+/// exposed, so it may use visual and radar sensors and launch missiles. Deeper, it must rely on
+/// sonar.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DepthBand {
+    Periscope,
+    Deep,
+}
+
+impl DepthBand {
+    /// Lowest altitude of periscope depth.
+    pub const PERISCOPE_FLOOR: Altitude = Altitude(-4);
+    /// Altitude that submarines hold at when ordered to periscope depth.
+    pub const PERISCOPE_DEPTH: Altitude = Altitude(-2);
+
+    /// Fraction of a submarine's mast that is exposed at `altitude`, from 0 to 1.
+    pub fn mast_exposure(altitude: Altitude, mast: Altitude) -> f32 {
+        if mast <= Altitude::ZERO {
+            0.0
+        } else {
+            ((mast + altitude).0 as f32 / mast.0 as f32).clamp(0.0, 1.0)
+        }
+    }
+}
+
 impl Add for Altitude {
     type Output = Self;
 
@@ -218,3 +298,51 @@ impl<'de> Deserialize<'de> for Altitude {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::altitude::{AirBand, Altitude, DepthBand};
+
+    #[test]
+    fn air_band() {
+        assert_eq!(Altitude::MIN.air_band(), None);
+        assert_eq!(Altitude::ZERO.air_band(), None);
+        assert_eq!(Altitude::UNIT.air_band(), Some(AirBand::Low));
+        assert_eq!(Altitude::MAX.air_band(), Some(AirBand::High));
+
+        for pair in AirBand::ALL.windows(2) {
+            assert!(pair[0].floor() < pair[1].floor());
+            assert_eq!(pair[1].floor().air_band(), Some(pair[1]));
+            assert_eq!((pair[1].floor() - Altitude::UNIT).air_band(), Some(pair[0]));
+        }
+    }
+
+    #[test]
+    fn depth_band() {
+        assert_eq!(Altitude::ZERO.depth_band(), None);
+        assert_eq!(Altitude::UNIT.depth_band(), None);
+        assert_eq!(
+            DepthBand::PERISCOPE_DEPTH.depth_band(),
+            Some(DepthBand::Periscope)
+        );
+        assert_eq!(
+            DepthBand::PERISCOPE_FLOOR.depth_band(),
+            Some(DepthBand::Periscope)
+        );
+        assert_eq!(
+            (DepthBand::PERISCOPE_FLOOR - Altitude::UNIT).depth_band(),
+            Some(DepthBand::Deep)
+        );
+        assert_eq!(Altitude::MIN.depth_band(), Some(DepthBand::Deep));
+
+        let mast = Altitude::from_whole_meters(10);
+        assert_eq!(DepthBand::mast_exposure(Altitude::ZERO, mast), 1.0);
+        assert_eq!(DepthBand::mast_exposure(Altitude::MIN, mast), 0.0);
+        let exposure = DepthBand::mast_exposure(DepthBand::PERISCOPE_DEPTH, mast);
+        assert!(exposure > 0.0 && exposure < 1.0);
+        assert_eq!(
+            DepthBand::mast_exposure(Altitude::ZERO, Altitude::ZERO),
+            0.0
+        );
+    }
+}
@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::{EntityKind, EntitySubKind, EntityType};
+use serde::{Deserialize, Serialize};
+
+/// Ammunition a boat carries in reserve, beyond what its armaments are loaded with, per type of
+/// armament. Armaments only reload while there is ammunition left for them. Sent to the boat's
+/// player.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ammo {
+    /// Reloads remaining, per armament type that needs ammunition.
+    reserves: Vec<(EntityType, u16)>,
+}
+
+impl Ammo {
+    /// Fraction of capacity replenished by collecting an ammo crate.
+    pub const CRATE_RESUPPLY: f32 = 0.25;
+    /// Fraction of capacity replenished each time a boat docked at a base is resupplied.
+    pub const DOCK_RESUPPLY: f32 = 0.1;
+
+    /// Returns how many reloads each armament of a type carries in reserve, or [`None`] if
+    /// unlimited.
+    pub fn per_armament(armament_type: EntityType) -> Option<u16> {
+        let data = armament_type.data();
+        if data.kind != EntityKind::Weapon || data.limited {
+            return None;
+        }
+        match data.sub_kind {
+            EntitySubKind::Shell | EntitySubKind::TankShell => Some(20),
+            EntitySubKind::Rocket => Some(8),
+            EntitySubKind::DepthCharge | EntitySubKind::Mine => Some(6),
+            EntitySubKind::Missile | EntitySubKind::RocketTorpedo | EntitySubKind::Sam => Some(4),
+            EntitySubKind::Torpedo => Some(3),
+            EntitySubKind::GlideBomb => Some(2),
+            // Lasers, beams, and terrain editing tools run on power.
+            _ => None,
+        }
+    }
+
+    /// Returns the reloads a boat type carries in reserve for an armament type, when fully
+    /// supplied, or [`None`] if unlimited.
+    pub fn capacity(boat_type: EntityType, armament_type: EntityType) -> Option<u16> {
+        let per_armament = Self::per_armament(armament_type)?;
+        let count = boat_type
+            .data()
+            .armaments
+            .iter()
+            .filter(|a| a.entity_type == armament_type)
+            .count();
+        Some((count as u16).saturating_mul(per_armament))
+    }
+
+    /// Returns full reserves for a boat type.
+    pub fn new(boat_type: EntityType) -> Self {
+        let mut reserves = Vec::<(EntityType, u16)>::new();
+        for armament in boat_type.data().armaments.iter() {
+            if reserves.iter().any(|&(t, _)| t == armament.entity_type) {
+                continue;
+            }
+            if let Some(capacity) = Self::capacity(boat_type, armament.entity_type) {
+                reserves.push((armament.entity_type, capacity));
+            }
+        }
+        Self { reserves }
+    }
+
+    /// Returns the reloads remaining for an armament type, or [`None`] if unlimited.
+    pub fn reserve(&self, armament_type: EntityType) -> Option<u16> {
+        self.reserves
+            .iter()
+            .find(|&&(t, _)| t == armament_type)
+            .map(|&(_, reserve)| reserve)
+    }
+
+    /// Takes one reload for an armament type, returning false if there were none left.
+    pub fn take(&mut self, armament_type: EntityType) -> bool {
+        match self.reserves.iter_mut().find(|(t, _)| *t == armament_type) {
+            Some((_, 0)) => false,
+            Some((_, reserve)) => {
+                *reserve -= 1;
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Replenishes each armament type by a fraction of its capacity, rounded up, without
+    /// exceeding it.
+    pub fn resupply(&mut self, boat_type: EntityType, fraction: f32) {
+        for (armament_type, reserve) in self.reserves.iter_mut() {
+            let capacity = Self::capacity(boat_type, *armament_type).unwrap_or(0);
+            let amount = (capacity as f32 * fraction).ceil() as u16;
+            *reserve = reserve.saturating_add(amount).min(capacity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ammo::Ammo;
+    use crate::entity::EntityType;
+
+    #[test]
+    fn take_and_resupply() {
+        let boat_type = EntityType::Zubr;
+        let armament_type = boat_type
+            .data()
+            .armaments
+            .iter()
+            .map(|a| a.entity_type)
+            .find(|&t| Ammo::per_armament(t).is_some())
+            .unwrap();
+        let capacity = Ammo::capacity(boat_type, armament_type).unwrap();
+
+        let mut ammo = Ammo::new(boat_type);
+        assert_eq!(ammo.reserve(armament_type), Some(capacity));
+        for _ in 0..capacity {
+            assert!(ammo.take(armament_type));
+        }
+        assert!(!ammo.take(armament_type));
+        assert_eq!(ammo.reserve(armament_type), Some(0));
+
+        ammo.resupply(boat_type, 0.01);
+        assert_eq!(ammo.reserve(armament_type), Some(1));
+        ammo.resupply(boat_type, 2.0);
+        assert_eq!(ammo.reserve(armament_type), Some(capacity));
+
+        // Unlimited.
+        assert_eq!(ammo.reserve(EntityType::Crate), None);
+        assert!(ammo.take(EntityType::Crate));
+    }
+}
@@ -0,0 +1,34 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// What an [`AreaEffect`] does to entities within it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum AreaEffectKind {
+    /// Slows surface boats, and may be set alight by shells.
+    Oil,
+    /// Burning oil, which also damages surface boats.
+    Fire,
+    /// Lit by a star shell, which restores full visual range within it at night.
+    Light,
+}
+
+/// A circular area of the world that affects entities within it, such as an oil slick or the
+/// light of a star shell. Sent to clients for rendering.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AreaEffect {
+    pub kind: AreaEffectKind,
+    pub position: Vec2,
+    pub radius: f32,
+    /// Fraction of the effect's lifespan that remains, from 1 to 0, for fading out.
+    pub remaining: f32,
+}
+
+impl AreaEffect {
+    /// Whether `position` is within the effect.
+    pub fn contains(&self, position: Vec2) -> bool {
+        self.position.distance_squared(position) <= self.radius.powi(2)
+    }
+}
@@ -0,0 +1,120 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::{EntityData, EntityKind, EntitySubKind, EntityType};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// What made an [`AudioEvent`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum AudioEventKind {
+    /// A boat sank or a weapon detonated.
+    Explosion,
+    /// A gun fired a shell.
+    Gunfire,
+    /// An active sonar pinged.
+    SonarPing,
+    /// A torpedo was launched.
+    TorpedoLaunch,
+}
+
+impl AudioEventKind {
+    /// Meters within which an event of full intensity can be heard.
+    pub fn hearing_range(self) -> f32 {
+        match self {
+            Self::Explosion => 3000.0,
+            Self::Gunfire => 2000.0,
+            Self::SonarPing => 2500.0,
+            Self::TorpedoLaunch => 1500.0,
+        }
+    }
+}
+
+/// A sound made out of sight. Sent to clients that can hear but not see it, so they can play
+/// a positional cue.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AudioEvent {
+    pub kind: AudioEventKind,
+    /// Where the sound was made.
+    pub position: Vec2,
+    /// How loud the sound is, from 0 to 1.
+    pub intensity: f32,
+}
+
+impl AudioEvent {
+    /// Maximum events sent per update, to bound bandwidth and cacophony.
+    pub const MAX_PER_UPDATE: usize = 8;
+
+    /// The sound of launching a weapon, if it makes one worth hearing from afar.
+    pub fn launch(weapon_type: EntityType, position: Vec2) -> Option<Self> {
+        let data: &EntityData = weapon_type.data();
+        let kind = match data.sub_kind {
+            EntitySubKind::Shell | EntitySubKind::TankShell => AudioEventKind::Gunfire,
+            EntitySubKind::Torpedo => AudioEventKind::TorpedoLaunch,
+            _ => return None,
+        };
+        Some(Self {
+            kind,
+            position,
+            // Bigger guns are louder.
+            intensity: (data.length * 0.5).clamp(0.25, 1.0),
+        })
+    }
+
+    /// The sound of a boat sinking, or of a weapon detonating.
+    pub fn explosion(entity_type: EntityType, position: Vec2) -> Option<Self> {
+        let data: &EntityData = entity_type.data();
+        let intensity = match data.kind {
+            EntityKind::Boat => (data.length * (1.0 / 100.0)).clamp(0.5, 1.0),
+            // Beams damage continuously, without detonating.
+            EntityKind::Weapon if data.sub_kind != EntitySubKind::Beam && data.damage > 0.0 => {
+                (data.damage * 0.5).sqrt().clamp(0.1, 1.0)
+            }
+            _ => return None,
+        };
+        Some(Self {
+            kind: AudioEventKind::Explosion,
+            position,
+            intensity,
+        })
+    }
+
+    /// Meters within which this event can be heard.
+    pub fn hearing_range(&self) -> f32 {
+        self.kind.hearing_range() * self.intensity.clamp(0.0, 1.0).sqrt()
+    }
+
+    /// Whether the event can be heard, but not seen, by a camera at `camera_pos` that sees
+    /// `camera_dims` meters.
+    pub fn is_heard_offscreen(&self, camera_pos: Vec2, camera_dims: Vec2) -> bool {
+        let delta = (self.position - camera_pos).abs();
+        let half_dims = camera_dims * 0.5;
+        let onscreen = delta.x <= half_dims.x && delta.y <= half_dims.y;
+        !onscreen && delta.length_squared() <= self.hearing_range().powi(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audio_event::{AudioEvent, AudioEventKind};
+    use glam::Vec2;
+
+    #[test]
+    fn is_heard_offscreen() {
+        let event = |x: f32, intensity: f32| AudioEvent {
+            kind: AudioEventKind::Explosion,
+            position: Vec2::new(x, 0.0),
+            intensity,
+        };
+        let camera_dims = Vec2::splat(1000.0);
+
+        // On screen.
+        assert!(!event(100.0, 1.0).is_heard_offscreen(Vec2::ZERO, camera_dims));
+        // Off screen, within hearing range.
+        assert!(event(2000.0, 1.0).is_heard_offscreen(Vec2::ZERO, camera_dims));
+        // Off screen, too quiet to hear.
+        assert!(!event(2000.0, 0.1).is_heard_offscreen(Vec2::ZERO, camera_dims));
+        // Beyond hearing range.
+        assert!(!event(5000.0, 1.0).is_heard_offscreen(Vec2::ZERO, camera_dims));
+    }
+}
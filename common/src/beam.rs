@@ -0,0 +1,14 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// A beam weapon being fired. Sent to clients for rendering the beam.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Beam {
+    /// Where the beam weapon is firing from.
+    pub source: Vec2,
+    /// Where the beam ends, either at its maximum range or where something blocks it.
+    pub target: Vec2,
+}
@@ -0,0 +1,59 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::ticks::Ticks;
+use core_protocol::id::PlayerId;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// A bounty on one of the top players of the liveboard, which is collected by whoever sinks them.
+/// Sent to all clients, with an approximate position that is only updated periodically.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BountyMarker {
+    pub player_id: PlayerId,
+    /// Within [`Self::ERROR`] of where the player's boat was, as of the last update.
+    pub position: Vec2,
+    /// Score awarded for sinking the player.
+    pub bounty: u32,
+}
+
+impl BountyMarker {
+    /// How many of the top players of the liveboard have bounties on them.
+    pub const TOP_PLAYERS: usize = 3;
+    /// How often bounties grow and markers move.
+    pub const PERIOD: Ticks = Ticks::from_whole_secs(10);
+    /// Bounty when first placed on a player.
+    pub const INITIAL: u32 = 100;
+    /// How much a bounty grows each period that the player stays on top.
+    pub const GROWTH: u32 = 25;
+    /// Largest a bounty may grow.
+    pub const MAX: u32 = 2000;
+    /// Meters by which a marker may be off from the boat.
+    pub const ERROR: f32 = 400.0;
+
+    /// Returns a player's bounty after another period on top, given their current bounty (zero if
+    /// they had none).
+    pub fn grow(bounty: u32) -> u32 {
+        if bounty == 0 {
+            Self::INITIAL
+        } else {
+            bounty.saturating_add(Self::GROWTH).min(Self::MAX)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bounty::BountyMarker;
+
+    #[test]
+    fn grow() {
+        assert_eq!(BountyMarker::grow(0), BountyMarker::INITIAL);
+        assert_eq!(
+            BountyMarker::grow(BountyMarker::INITIAL),
+            BountyMarker::INITIAL + BountyMarker::GROWTH
+        );
+        assert_eq!(BountyMarker::grow(BountyMarker::MAX), BountyMarker::MAX);
+        assert_eq!(BountyMarker::grow(u32::MAX), BountyMarker::MAX);
+    }
+}
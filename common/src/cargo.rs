@@ -0,0 +1,81 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::{EntitySubKind, EntityType};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Cargo carried by a civilian boat from one port (HQ or oil platform) to another, which pays out
+/// score on delivery, or is lost as loot if the boat is sunk on the way.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cargo {
+    /// Where the port that the cargo was picked up at is.
+    pub origin: Vec2,
+    /// Where the port that the cargo must be delivered to is.
+    pub destination: Vec2,
+}
+
+impl Cargo {
+    /// Ports closer together than this don't trade with each other.
+    pub const MIN_DISTANCE: f32 = 2000.0;
+    /// Score paid per kilometer between the ports.
+    pub const SCORE_PER_KM: f32 = 60.0;
+    /// Fraction by which the payout grows per level of difficulty at the destination.
+    pub const DANGER_BONUS: f32 = 0.1;
+    /// Crates dropped per kilometer between the ports, if the boat carrying it is sunk.
+    pub const LOOT_PER_KM: f32 = 4.0;
+    /// Most crates that may be dropped.
+    pub const MAX_LOOT: u32 = 40;
+
+    /// Whether boats of a type may carry cargo.
+    pub fn can_carry(entity_type: EntityType) -> bool {
+        matches!(
+            entity_type.data().sub_kind,
+            EntitySubKind::Dredger | EntitySubKind::Passenger | EntitySubKind::Tanker
+        )
+    }
+
+    /// Kilometers between the ports.
+    fn km(&self) -> f32 {
+        self.origin.distance(self.destination) * 0.001
+    }
+
+    /// Returns the score paid on delivery, given the difficulty of the destination's defenses.
+    pub fn payout(&self, danger: f32) -> u32 {
+        (self.km() * Self::SCORE_PER_KM * (1.0 + danger.max(0.0) * Self::DANGER_BONUS)) as u32
+    }
+
+    /// Returns how many crates are dropped if the boat carrying the cargo is sunk.
+    pub fn loot(&self) -> u32 {
+        ((self.km() * Self::LOOT_PER_KM) as u32).min(Self::MAX_LOOT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cargo::Cargo;
+    use crate::entity::EntityType;
+    use glam::Vec2;
+
+    #[test]
+    fn payout() {
+        let near = Cargo {
+            origin: Vec2::ZERO,
+            destination: Vec2::new(Cargo::MIN_DISTANCE, 0.0),
+        };
+        let far = Cargo {
+            origin: Vec2::ZERO,
+            destination: Vec2::new(0.0, Cargo::MIN_DISTANCE * 4.0),
+        };
+        assert!(far.payout(0.0) > near.payout(0.0));
+        assert!(near.payout(10.0) > near.payout(0.0));
+        assert!(near.loot() > 0);
+        assert!(far.loot() <= Cargo::MAX_LOOT);
+    }
+
+    #[test]
+    fn can_carry() {
+        assert!(Cargo::can_carry(EntityType::Dredger));
+        assert!(!Cargo::can_carry(EntityType::Zumwalt));
+    }
+}
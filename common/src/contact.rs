@@ -3,8 +3,10 @@
 
 use crate::altitude::Altitude;
 use crate::angle::Angle;
+use crate::cosmetic::Cosmetics;
 use crate::entity::*;
 use crate::guidance::Guidance;
+use crate::shield::{Shield, ShieldState};
 use crate::ticks::Ticks;
 use crate::transform::Transform;
 use crate::util::make_mut_slice;
@@ -24,10 +26,17 @@ pub type ReloadsStorage = u32;
 pub trait ContactTrait {
     fn altitude(&self) -> Altitude;
 
+    /// Only meaningful for boats.
+    fn cosmetics(&self) -> Cosmetics;
+
     fn damage(&self) -> Ticks;
 
     fn entity_type(&self) -> Option<EntityType>;
 
+    /// Known for boats that were classified, but not yet identified, as well as whenever the
+    /// entity type is known.
+    fn sub_kind(&self) -> Option<EntitySubKind>;
+
     fn guidance(&self) -> &Guidance;
 
     fn id(&self) -> EntityId;
@@ -58,12 +67,19 @@ pub trait ContactTrait {
     }
 
     fn horn(&self) -> bool;
+
+    /// Only present for boats that have a shield.
+    fn shield(&self) -> Option<ShieldState>;
+
+    /// Whether there is a bounty on the boat's player. Only meaningful for boats.
+    fn bounty(&self) -> bool;
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Contact {
     transform: Transform,
     altitude: Altitude,
+    cosmetics: Cosmetics,
     guidance: Guidance,
     damage: Ticks,
     entity_type: Option<EntityType>,
@@ -72,12 +88,17 @@ pub struct Contact {
     reloads: Option<BitArray<ReloadsStorage>>,
     turrets: Option<Arc<[Angle]>>,
     horn: bool,
+    shield: Option<ShieldState>,
+    bounty: bool,
+    /// Sub-kind of a boat that was classified, but not yet identified.
+    sub_kind: Option<EntitySubKind>,
 }
 
 impl Default for Contact {
     fn default() -> Self {
         Self {
             altitude: Altitude::default(),
+            cosmetics: Cosmetics::default(),
             damage: Ticks::default(),
             entity_type: None,
             guidance: Guidance::default(),
@@ -87,6 +108,9 @@ impl Default for Contact {
             transform: Transform::default(),
             turrets: None,
             horn: false,
+            shield: None,
+            bounty: false,
+            sub_kind: None,
         }
     }
 }
@@ -96,6 +120,7 @@ impl Contact {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         altitude: Altitude,
+        cosmetics: Cosmetics,
         damage: Ticks,
         entity_type: Option<EntityType>,
         guidance: Guidance,
@@ -105,9 +130,13 @@ impl Contact {
         transform: Transform,
         turrets: Option<Arc<[Angle]>>,
         horn: bool,
+        shield: Option<ShieldState>,
+        bounty: bool,
+        sub_kind: Option<EntitySubKind>,
     ) -> Self {
         Self {
             altitude,
+            cosmetics,
             damage,
             entity_type,
             guidance,
@@ -117,6 +146,9 @@ impl Contact {
             transform,
             turrets,
             horn,
+            shield,
+            bounty,
+            sub_kind: sub_kind.filter(|_| entity_type.is_none()),
         }
     }
 
@@ -166,8 +198,12 @@ impl Contact {
         // Upgraded.
         let changed_type = self.entity_type != model.entity_type;
         self.entity_type = model.entity_type;
+        self.sub_kind = model.sub_kind;
 
         self.horn = model.horn;
+        self.shield = model.shield;
+        self.bounty = model.bounty;
+        self.cosmetics = model.cosmetics;
         self.altitude = self.altitude.lerp(model.altitude, lerp);
         self.damage = model.damage;
         self.player_id = model.player_id;
@@ -233,6 +269,11 @@ impl ContactTrait for Contact {
         self.altitude
     }
 
+    #[inline]
+    fn cosmetics(&self) -> Cosmetics {
+        self.cosmetics
+    }
+
     #[inline]
     fn damage(&self) -> Ticks {
         self.damage
@@ -243,6 +284,13 @@ impl ContactTrait for Contact {
         self.entity_type
     }
 
+    #[inline]
+    fn sub_kind(&self) -> Option<EntitySubKind> {
+        self.entity_type
+            .map(|entity_type| entity_type.data().sub_kind)
+            .or(self.sub_kind)
+    }
+
     #[inline]
     fn guidance(&self) -> &Guidance {
         &self.guidance
@@ -291,6 +339,16 @@ impl ContactTrait for Contact {
     fn horn(&self) -> bool {
         self.horn
     }
+
+    #[inline]
+    fn shield(&self) -> Option<ShieldState> {
+        self.shield
+    }
+
+    #[inline]
+    fn bounty(&self) -> bool {
+        self.bounty
+    }
 }
 
 /// Useful for efficiently serializing contact.
@@ -375,6 +433,12 @@ impl<'a> ContactSerializer<'a> {
         // Assert that all boats have turrets.
         assert_eq!(c.turrets.is_some(), c.is_boat());
 
+        // Assert that exactly the boats with shields have shield state.
+        debug_assert_eq!(
+            c.shield.is_some(),
+            c.is_boat() && Shield::of(c.entity_type.unwrap()).is_some()
+        );
+
         // Assert that, if reloads are known, so is entity type.
         debug_assert!(!(c.reloads.is_some() && c.entity_type.is_none()), "{:?}", c);
 
@@ -446,14 +510,16 @@ impl<'a> Serialize for ContactSerializer<'a> {
             }
         }
 
-        // 1 option or unit element.
+        // 1 boat, sub-kind, or unit element.
         if self.c.is_boat() {
-            let turrets = self.c.turrets.as_ref().unwrap();
-            if turrets.is_empty() {
-                tup.serialize_element(&())?;
-            } else {
-                tup.serialize_element(&KnownSizeSerializer::new(turrets))?;
-            }
+            tup.serialize_element(&BoatSerializer {
+                cosmetics: self.c.cosmetics,
+                turrets: self.c.turrets.as_ref().unwrap(),
+                shield: self.c.shield,
+                bounty: self.c.bounty,
+            })?;
+        } else if !self.h.has_type {
+            tup.serialize_element(&self.c.sub_kind)?;
         } else {
             tup.serialize_element(&())?;
         }
@@ -462,6 +528,37 @@ impl<'a> Serialize for ContactSerializer<'a> {
     }
 }
 
+/// Serializes the elements only boats have, namely cosmetics, turrets, shield, and bounty.
+struct BoatSerializer<'a> {
+    cosmetics: Cosmetics,
+    turrets: &'a [Angle],
+    shield: Option<ShieldState>,
+    bounty: bool,
+}
+
+impl<'a> Serialize for BoatSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tup = serializer.serialize_tuple(4)?;
+        tup.serialize_element(&self.cosmetics)?;
+        if self.turrets.is_empty() {
+            tup.serialize_element(&())?;
+        } else {
+            tup.serialize_element(&KnownSizeSerializer::new(self.turrets))?;
+        }
+        // Whether there is a shield is known from the entity type.
+        if let Some(shield) = self.shield {
+            tup.serialize_element(&shield)?;
+        } else {
+            tup.serialize_element(&())?;
+        }
+        tup.serialize_element(&self.bounty)?;
+        tup.end()
+    }
+}
+
 /// Serializes a slice of bytes without length (known size).
 struct ByteSerializer<'a> {
     items: &'a [u8],
@@ -620,19 +717,22 @@ impl<'de, 'a> Visitor<'de> for ContactDeserializer<'a> {
             }
         }
 
-        // 1 option or unit element.
+        // 1 boat, sub-kind, or unit element.
         if self.c.is_boat() {
             // Must be after type is assigend.
-            let size = self.c.entity_type.unwrap().data().turrets.len();
-            if size == 0 {
-                let _: () = seq.next_element()?.unwrap();
-                self.c.turrets = Some(Arc::new([]))
-            } else {
-                self.c.turrets = Some(
-                    seq.next_element_seed(KnownSizeDeserializer::new(size))?
-                        .unwrap(),
-                );
-            }
+            let entity_type = self.c.entity_type.unwrap();
+            let (cosmetics, turrets, shield, bounty) = seq
+                .next_element_seed(BoatDeserializer {
+                    turrets: entity_type.data().turrets.len(),
+                    shield: Shield::of(entity_type).is_some(),
+                })?
+                .unwrap();
+            self.c.cosmetics = cosmetics;
+            self.c.turrets = Some(turrets);
+            self.c.shield = shield;
+            self.c.bounty = bounty;
+        } else if !self.h.has_type {
+            self.c.sub_kind = seq.next_element()?.unwrap();
         } else {
             let _: () = seq.next_element()?.unwrap();
         }
@@ -641,6 +741,55 @@ impl<'de, 'a> Visitor<'de> for ContactDeserializer<'a> {
     }
 }
 
+/// Deserializes the elements only boats have, namely cosmetics, turrets, shield, and bounty.
+struct BoatDeserializer {
+    /// Number of turrets.
+    turrets: usize,
+    /// Whether the boat has a shield.
+    shield: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for BoatDeserializer {
+    type Value = (Cosmetics, Arc<[Angle]>, Option<ShieldState>, bool);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(4, self)
+    }
+}
+
+impl<'de> Visitor<'de> for BoatDeserializer {
+    type Value = (Cosmetics, Arc<[Angle]>, Option<ShieldState>, bool);
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("a boat tuple")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let cosmetics = seq.next_element()?.unwrap();
+        let turrets = if self.turrets == 0 {
+            let _: () = seq.next_element()?.unwrap();
+            Arc::new([]) as Arc<[Angle]>
+        } else {
+            seq.next_element_seed(KnownSizeDeserializer::new(self.turrets))?
+                .unwrap()
+        };
+        let shield = if self.shield {
+            Some(seq.next_element()?.unwrap())
+        } else {
+            let _: () = seq.next_element()?.unwrap();
+            None
+        };
+        let bounty = seq.next_element()?.unwrap();
+        Ok((cosmetics, turrets, shield, bounty))
+    }
+}
+
 struct ByteDeserializer<const MAX: usize> {
     items: [u8; MAX],
     size: usize,
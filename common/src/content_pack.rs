@@ -0,0 +1,55 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::EntityType;
+use serde::{Deserialize, Serialize};
+
+/// Advertises the content pack a server loaded at startup, so clients can fetch matching assets.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ContentPackInfo {
+    /// Hash of every file in the pack, which changes whenever the assets do.
+    pub hash: u64,
+    /// Wire id and name of each additional entity, in order of wire id.
+    pub entities: Vec<(u8, String)>,
+}
+
+impl ContentPackInfo {
+    /// Name of the sprite sheet that must accompany additional boats.
+    pub const ATLAS: &'static str = "sprites_webgl.json";
+
+    /// The first wire id available to additional entities, after those of [`EntityType`].
+    pub fn first_wire_id() -> usize {
+        EntityType::iter()
+            .map(|entity_type| entity_type.to_wire_id() as usize + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Path, relative to the server, of one of the pack's files. Includes the hash so that
+    /// browsers don't cache files across different packs.
+    pub fn asset_path(&self, file: &str) -> String {
+        format!("/content/{:016x}/{file}", self.hash)
+    }
+
+    /// Looks up an additional entity by wire id.
+    pub fn entity_name(&self, wire_id: u8) -> Option<&str> {
+        self.entities
+            .iter()
+            .find(|(id, _)| *id == wire_id)
+            .map(|(_, name)| name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::content_pack::ContentPackInfo;
+    use crate::entity::EntityType;
+
+    #[test]
+    fn first_wire_id() {
+        let first = ContentPackInfo::first_wire_id();
+        for entity_type in EntityType::iter() {
+            assert!((entity_type.to_wire_id() as usize) < first);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use enum_iterator::IntoEnumIterator;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A flag flown above a boat.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+    enum_iterator::IntoEnumIterator,
+)]
+pub enum Flag {
+    #[default]
+    None,
+    Naval,
+    Signal,
+    Checkered,
+    Pirate,
+    Gold,
+}
+
+impl Flag {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::into_enum_iter()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Naval => "naval",
+            Self::Signal => "signal",
+            Self::Checkered => "checkered",
+            Self::Pirate => "pirate",
+            Self::Gold => "gold",
+        }
+    }
+
+    /// Boat level that must be reached (in any life, which is remembered by the player's account
+    /// if they sign in) to fly this flag.
+    pub fn unlock_level(self) -> u8 {
+        match self {
+            Self::None => 1,
+            Self::Naval => 2,
+            Self::Signal => 3,
+            Self::Checkered => 4,
+            Self::Pirate => 6,
+            Self::Gold => 8,
+        }
+    }
+}
+
+/// A paint scheme applied to a boat's hull.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+    enum_iterator::IntoEnumIterator,
+)]
+pub enum HullPaint {
+    #[default]
+    Standard,
+    Arctic,
+    Desert,
+    Jungle,
+    Crimson,
+    Gold,
+}
+
+impl HullPaint {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::into_enum_iter()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::Arctic => "arctic",
+            Self::Desert => "desert",
+            Self::Jungle => "jungle",
+            Self::Crimson => "crimson",
+            Self::Gold => "gold",
+        }
+    }
+
+    /// Boat level that must be reached (in any life, see [`Flag::unlock_level`]) to use this paint.
+    pub fn unlock_level(self) -> u8 {
+        match self {
+            Self::Standard => 1,
+            Self::Arctic => 2,
+            Self::Desert => 3,
+            Self::Jungle => 4,
+            Self::Crimson => 6,
+            Self::Gold => 8,
+        }
+    }
+}
+
+macro_rules! impl_display_from_str {
+    ($typ: ty) => {
+        impl fmt::Display for $typ {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $typ {
+            type Err = ();
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::iter().find(|c| c.as_str() == s).ok_or(())
+            }
+        }
+    };
+}
+
+impl_display_from_str!(Flag);
+impl_display_from_str!(HullPaint);
+
+/// Purely visual customization of a boat, chosen when spawning.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Cosmetics {
+    pub flag: Flag,
+    pub paint: HullPaint,
+}
+
+impl Cosmetics {
+    /// Replaces any cosmetic that isn't unlocked at `highest_level` with the default.
+    pub fn unlocked(self, highest_level: u8) -> Self {
+        Self {
+            flag: if self.flag.unlock_level() <= highest_level {
+                self.flag
+            } else {
+                Flag::default()
+            },
+            paint: if self.paint.unlock_level() <= highest_level {
+                self.paint
+            } else {
+                HullPaint::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cosmetic::{Cosmetics, Flag, HullPaint};
+    use std::str::FromStr;
+
+    #[test]
+    fn from_str() {
+        for flag in Flag::iter() {
+            assert_eq!(Flag::from_str(&flag.to_string()), Ok(flag));
+        }
+        for paint in HullPaint::iter() {
+            assert_eq!(HullPaint::from_str(&paint.to_string()), Ok(paint));
+        }
+        assert!(Flag::from_str("nonexistent").is_err());
+    }
+
+    #[test]
+    fn unlocked() {
+        let cosmetics = Cosmetics {
+            flag: Flag::Gold,
+            paint: HullPaint::Arctic,
+        };
+        assert_eq!(
+            cosmetics.unlocked(2),
+            Cosmetics {
+                flag: Flag::None,
+                paint: HullPaint::Arctic
+            }
+        );
+        assert_eq!(cosmetics.unlocked(8), cosmetics);
+        assert_eq!(Cosmetics::default().unlocked(1), Cosmetics::default());
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// Position in the day/night cycle, from 0 to 1, where 0 is midnight and 0.5 is noon. Advanced by
+/// the server, which uses it for visual sensors, and sent to clients for rendering.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct TimeOfDay(pub f32);
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        Self::NOON
+    }
+}
+
+impl TimeOfDay {
+    pub const MIDNIGHT: Self = Self(0.0);
+    pub const NOON: Self = Self(0.5);
+    /// Fraction of visual range that remains at night.
+    const NIGHT_VISUAL: f32 = 0.35;
+    /// Height of the sun, from -1 to 1, over which dusk and dawn happen.
+    const TWILIGHT: f32 = 0.3;
+
+    /// Returns the time of day after `secs`, given the length of a whole cycle in seconds.
+    pub fn advance(self, secs: f32, day_length: f32) -> Self {
+        Self((self.0 + secs / day_length).rem_euclid(1.0))
+    }
+
+    /// How light it is, from 0 (night) to 1 (day).
+    pub fn daylight(self) -> f32 {
+        let sun = -(self.0 * TAU).cos();
+        (sun / Self::TWILIGHT + 0.5).clamp(0.0, 1.0)
+    }
+
+    /// Multiplier of visual range, which is reduced at night.
+    pub fn visual_multiplier(self) -> f32 {
+        Self::NIGHT_VISUAL + (1.0 - Self::NIGHT_VISUAL) * self.daylight()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day_night::TimeOfDay;
+
+    #[test]
+    fn cycle() {
+        assert!(TimeOfDay::NOON.daylight() >= 1.0);
+        assert!(TimeOfDay::MIDNIGHT.daylight() <= 0.0);
+        assert!(TimeOfDay::MIDNIGHT.visual_multiplier() < TimeOfDay::NOON.visual_multiplier());
+
+        // Dusk.
+        let dusk = TimeOfDay(0.75).daylight();
+        assert!(dusk > 0.0 && dusk < 1.0);
+
+        let mut time = TimeOfDay::NOON;
+        for _ in 0..10 {
+            time = time.advance(60.0, 1200.0);
+        }
+        assert!((time.0 - 1.0).abs() < 0.001 || time.0 < 0.001);
+        assert!(time.daylight() <= 0.0);
+    }
+}
@@ -23,6 +23,10 @@ pub enum DeathReason {
     Ram(PlayerAlias),
     Weapon(PlayerAlias, EntityType),
     AntiAir(PlayerAlias),
+    Afk, // Scuttled for lack of input.
+    Fire, // Burned in an oil fire.
+    ReturnedToPort(u32), // Contains number of coins banked.
+    Scuttled, // Scuttled on purpose.
     // Allows code to convey a reason for killing an entity that is not necessarily a player's boat.
     // In release mode, Unknown is used instead.
     #[cfg(debug_assertions)]
@@ -36,6 +40,10 @@ impl DeathReason {
         match self {
             Self::AntiAir(_) => true,
             Self::Unknown => false,
+            Self::Afk => false,
+            Self::Fire => false,
+            Self::ReturnedToPort(_) => false,
+            Self::Scuttled => false,
             Self::Border => false,
             Self::Landing(_) => false,
             Self::Terrain => false,
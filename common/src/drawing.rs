@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A simple drawing, placed on the map by a fleet leader for their teammates to see.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Drawing {
+    /// Marks a position.
+    Marker(Vec2),
+    /// Points from the first position to the second.
+    Arrow(Vec2, Vec2),
+}
+
+impl Drawing {
+    /// How long a drawing is shown to teammates.
+    pub const LIFESPAN: Duration = Duration::from_secs(30);
+    /// Minimum time between drawings from the same player.
+    pub const COOLDOWN: Duration = Duration::from_millis(500);
+    /// Maximum number of drawings per player, at any given time.
+    pub const MAX: usize = 8;
+
+    /// Returns true iff all points are finite and within `world_radius` of the origin.
+    pub fn is_valid(&self, world_radius: f32) -> bool {
+        let valid = |point: Vec2| point.is_finite() && point.length() <= world_radius;
+        match *self {
+            Self::Marker(position) => valid(position),
+            Self::Arrow(start, end) => valid(start) && valid(end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::drawing::Drawing;
+    use glam::Vec2;
+
+    #[test]
+    fn is_valid() {
+        assert!(Drawing::Marker(Vec2::new(100.0, 0.0)).is_valid(500.0));
+        assert!(!Drawing::Marker(Vec2::new(1000.0, 0.0)).is_valid(500.0));
+        assert!(!Drawing::Arrow(Vec2::ZERO, Vec2::new(f32::NAN, 0.0)).is_valid(500.0));
+        assert!(Drawing::Arrow(Vec2::ZERO, Vec2::new(0.0, -400.0)).is_valid(500.0));
+    }
+}
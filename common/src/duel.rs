@@ -0,0 +1,28 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::id::PlayerId;
+use serde::{Deserialize, Serialize};
+
+/// How a duel involving the player stands.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DuelStatus {
+    /// The player challenged the opponent, who has yet to accept.
+    Challenging,
+    /// The opponent challenged the player, who has yet to accept.
+    Challenged,
+    /// Both stakes are held in escrow, until one sinks the other.
+    Active,
+}
+
+/// A duel involving the player, as sent to their client.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DuelDto {
+    pub opponent: PlayerId,
+    /// Score staked by each player. The winner takes both stakes.
+    pub stake: u32,
+    pub status: DuelStatus,
+    /// Whole seconds until the challenge expires, or the duel is called off and the stakes are
+    /// refunded.
+    pub seconds_remaining: u16,
+}
@@ -7,24 +7,35 @@ mod _type;
 mod armament;
 mod data;
 mod exhaust;
+mod export;
+mod fx;
 mod kind;
 mod sensor;
+mod sprite;
 mod sub_kind;
 mod turret;
 
 pub type EntityId = NonZeroU32;
 pub use _type::EntityType;
-pub use armament::Armament;
+pub use armament::{Armament, ArmamentGroup};
 pub use data::EntityData;
 pub use exhaust::Exhaust;
+pub use fx::EntityFx;
 pub use kind::EntityKind;
 pub use sensor::{Sensor, Sensors};
+pub use sprite::EntitySprite;
 pub use sub_kind::EntitySubKind;
 pub use turret::Turret;
 
+/// Used by `entity_sprites!`, which is generated alongside [`EntityType`].
+#[doc(hidden)]
+pub use macros::entity_sprites as __entity_sprites;
+
 #[cfg(test)]
 mod tests {
-    use crate::entity::{EntityKind, EntityType};
+    use crate::entity::{EntityData, EntityKind, EntityType};
+    use std::convert::TryFrom;
+    use std::str::FromStr;
 
     #[test]
     fn weapon_sensors() {
@@ -39,4 +50,38 @@ mod tests {
             println!("{:?} sensor range is {}", typ, range);
         }
     }
+
+    #[test]
+    fn from_str_and_wire_id() {
+        for typ in EntityType::iter() {
+            assert_eq!(EntityType::from_str(typ.as_str()), Ok(typ));
+            assert_eq!(EntityType::try_from(typ.to_wire_id()), Ok(typ));
+        }
+        assert!(EntityType::from_str("NotAnEntityType").is_err());
+        assert!(EntityType::from_str("").is_err());
+    }
+
+    /// Keeps the committed `balance.md` in sync with the final entity data, so that balance
+    /// changes show up in review.
+    #[test]
+    fn balance_table() {
+        assert!(
+            include_str!("../balance.md") == EntityData::BALANCE_TABLE,
+            "balance changed, replace balance.md with:\n{}",
+            EntityData::BALANCE_TABLE
+        );
+    }
+
+    #[test]
+    fn armament_groups() {
+        for typ in EntityType::iter() {
+            let data = typ.data();
+            for (i, armament) in data.armaments.iter().enumerate() {
+                let group = &data.armament_groups[armament.group as usize];
+                assert!(group.armaments.contains(&(i as u8)), "{:?}", typ);
+            }
+            let total: usize = data.armament_groups.iter().map(|g| g.armaments.len()).sum();
+            assert_eq!(total, data.armaments.len(), "{:?}", typ);
+        }
+    }
 }
@@ -13,6 +13,8 @@ pub struct Armament {
     pub position_side: f32,
     pub angle: Angle,
     pub turret: Option<usize>,
+    /// The index of the armament's group, relative to `EntityData.armament_groups`.
+    pub group: u8,
 }
 
 impl Armament {
@@ -30,3 +32,12 @@ impl Armament {
         self.entity_type == other.entity_type && self.turret == other.turret
     }
 }
+
+/// A logical group of armaments, e.g. "torpedoes", that can be addressed as a whole. Armaments
+/// without an explicit group are grouped by entity type.
+#[derive(Clone, Debug)]
+pub struct ArmamentGroup {
+    pub name: &'static str,
+    /// The indices of the group's armaments, relative to `EntityData.armaments`.
+    pub armaments: &'static [u8],
+}
@@ -1,5 +1,8 @@
 use crate::altitude::Altitude;
-use crate::entity::{Armament, EntityKind, EntitySubKind, Exhaust, Sensors, Turret};
+use crate::entity::{
+    Armament, ArmamentGroup, EntityFx, EntityKind, EntitySubKind, Exhaust, Sensors, Turret,
+};
+use crate::protocol::TurretLock;
 use crate::ticks;
 use crate::ticks::Ticks;
 use crate::transform::Transform;
@@ -34,8 +37,10 @@ pub struct EntityData {
     pub stealth: f32,
     pub sensors: Sensors,
     pub armaments: &'static [Armament],
+    pub armament_groups: &'static [ArmamentGroup],
     pub turrets: &'static [Turret],
     pub exhausts: &'static [Exhaust],
+    pub fx: EntityFx,
     pub label: &'static str,
     pub link: Option<&'static str>,
     pub range: f32,
@@ -51,6 +56,9 @@ impl EntityData {
     /// Constant used for checking whether a depth charge should explode.
     pub const DEPTH_CHARGE_PROXIMITY: f32 = 30.0;
 
+    /// How long after launch weapon seekers activate.
+    pub const SEEKER_DELAY: Ticks = Ticks::from_whole_secs(1);
+
     /// radii range of throttle (0-100%) and limit of collecting things.
     pub fn radii(&self) -> Range<f32> {
         self.length * 0.55..self.length
@@ -82,6 +90,46 @@ impl EntityData {
         self.radii().end
     }
 
+    /// Whether this is an aircraft that carries air-to-air armaments, and therefore intercepts
+    /// enemy aircraft.
+    pub fn is_fighter(&self) -> bool {
+        self.kind == EntityKind::Aircraft
+            && self
+                .armaments
+                .iter()
+                .any(|a| a.entity_type.data().sub_kind == EntitySubKind::Sam)
+    }
+
+    /// Whether this is a weapon (or aircraft) that homes in on targets with a seeker. Rockets are
+    /// unguided, despite any sensors.
+    pub fn has_seeker(&self) -> bool {
+        self.sensors.any() && self.sub_kind != EntitySubKind::Rocket
+    }
+
+    /// Maximum angle between the heading of a weapon with a seeker and a target that the seeker
+    /// can sense.
+    pub fn seeker_angle(&self) -> Angle {
+        Angle::from_degrees(match self.sub_kind {
+            EntitySubKind::Missile => 40.0,
+            EntitySubKind::GlideBomb => 45.0,
+            _ => 80.0,
+        })
+    }
+
+    /// Maximum angle between the course of a weapon with a seeker and a target that the seeker
+    /// will steer towards, so it doesn't go off target.
+    pub fn seeker_course_angle(&self) -> Angle {
+        Angle::from_degrees(match self.sub_kind {
+            EntitySubKind::Missile | EntitySubKind::GlideBomb => 30.0,
+            _ => 60.0,
+        })
+    }
+
+    /// Distance that a weapon travels in its lifespan, which for beams is how far they reach.
+    pub fn range(&self) -> f32 {
+        self.speed.to_mps() * self.lifespan.to_secs()
+    }
+
     /// max_health returns the the minimum damage to kill a boat, panicking if the corresponding
     /// entity does not have health.
     pub fn max_health(&self) -> Ticks {
@@ -114,6 +162,11 @@ impl EntityData {
         )
     }
 
+    /// Returns the armament group with the given name, if any.
+    pub fn armament_group(&self, name: &str) -> Option<&'static ArmamentGroup> {
+        self.armament_groups.iter().find(|g| g.name == name)
+    }
+
     /// armament_transform returns the entity-relative transform of a given armament.
     pub fn armament_transform(&self, turret_angles: &[Angle], index: usize) -> Transform {
         let armament = &self.armaments[index];
@@ -152,11 +205,13 @@ impl EntityData {
         transform
     }
 
-    /// update_turret_aim brings turret_angles delta_seconds closer to position_target.
+    /// update_turret_aim brings turret_angles delta_seconds closer to position_target, except
+    /// for turrets that are locked to a bearing, which are brought closer to it instead.
     pub fn update_turret_aim(
         &self,
         boat_transform: Transform,
         turret_angles: &mut [Angle],
+        turret_locks: &[Option<TurretLock>],
         position_target: Option<Vec2>,
         delta_seconds: f32,
     ) {
@@ -166,7 +221,9 @@ impl EntityData {
                 (delta_seconds * turret.speed.to_radians()).clamp(0.0, std::f32::consts::PI),
             );
             let mut direction_target = turret.angle;
-            if let Some(target) = position_target {
+            if let Some(lock) = turret_locks.get(i).copied().flatten() {
+                direction_target = lock.direction_target(boat_transform.direction);
+            } else if let Some(target) = position_target {
                 let turret_global_transform = boat_transform
                     + Transform {
                         position: turret.position(),
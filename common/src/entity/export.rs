@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::{Armament, EntityData, EntityType, Exhaust, Sensor, Turret};
+use serde_json::{json, Map, Value};
+
+impl EntityType {
+    /// Exports the compiled data of all entity types as JSON, keyed by entity type, for external
+    /// tools (wiki generators, balance spreadsheets, etc.)
+    ///
+    /// Distances are in meters, speeds in meters per second, durations in seconds, and angles in
+    /// degrees.
+    pub fn export_json() -> String {
+        let entities: Map<String, Value> = Self::iter()
+            .map(|entity_type| {
+                (
+                    entity_type.as_str().to_owned(),
+                    entity_type.data().to_json(),
+                )
+            })
+            .collect();
+        serde_json::to_string_pretty(&entities).unwrap()
+    }
+}
+
+impl EntityData {
+    fn to_json(&self) -> Value {
+        let sensor = |sensor: &Sensor| (sensor.range != 0.0).then_some(sensor.range);
+
+        json!({
+            "label": self.label,
+            "link": self.link,
+            "kind": format!("{:?}", self.kind),
+            "sub_kind": format!("{:?}", self.sub_kind),
+            "level": self.level,
+            "limited": self.limited,
+            "npc": self.npc,
+            "length": self.length,
+            "width": self.width,
+            "draft": self.draft.to_meters(),
+            "mast": self.mast.to_meters(),
+            "depth": self.depth.to_meters(),
+            "radius": self.radius,
+            "speed": self.speed.to_mps(),
+            "range": self.range,
+            "lifespan": self.lifespan.to_secs(),
+            "reload": self.reload.to_secs(),
+            "damage": self.damage,
+            "anti_aircraft": self.anti_aircraft,
+            "ram_damage": self.ram_damage,
+            "torpedo_resistance": self.torpedo_resistance,
+            "stealth": self.stealth,
+            "sensors": {
+                "visual": sensor(&self.sensors.visual),
+                "radar": sensor(&self.sensors.radar),
+                "sonar": sensor(&self.sensors.sonar),
+            },
+            "armaments": self.armaments.iter().map(Armament::to_json).collect::<Vec<_>>(),
+            "armament_groups": self
+                .armament_groups
+                .iter()
+                .map(|group| json!({"name": group.name, "armaments": group.armaments}))
+                .collect::<Vec<_>>(),
+            "turrets": self.turrets.iter().map(Turret::to_json).collect::<Vec<_>>(),
+            "exhausts": self.exhausts.iter().map(Exhaust::to_json).collect::<Vec<_>>(),
+            "fx": {
+                "fire_sound": self.fx.fire_sound,
+                "explosion": self.fx.explosion,
+                "wake_scale": self.fx.wake_scale,
+            },
+        })
+    }
+}
+
+impl Armament {
+    fn to_json(&self) -> Value {
+        json!({
+            "type": self.entity_type.as_str(),
+            "forward": self.position_forward,
+            "side": self.position_side,
+            "angle": self.angle.to_degrees(),
+            "turret": self.turret,
+            "hidden": self.hidden,
+            "external": self.external,
+            "vertical": self.vertical,
+        })
+    }
+}
+
+impl Turret {
+    fn to_json(&self) -> Value {
+        json!({
+            "type": self.entity_type.as_ref().map(EntityType::as_str),
+            "forward": self.position_forward,
+            "side": self.position_side,
+            "angle": self.angle.to_degrees(),
+            "speed": self.speed.to_degrees(),
+            "azimuth_fl": self.azimuth_fl.to_degrees(),
+            "azimuth_fr": self.azimuth_fr.to_degrees(),
+            "azimuth_bl": self.azimuth_bl.to_degrees(),
+            "azimuth_br": self.azimuth_br.to_degrees(),
+            "hidden": self.hidden,
+        })
+    }
+}
+
+impl Exhaust {
+    fn to_json(&self) -> Value {
+        json!({
+            "forward": self.position_forward,
+            "side": self.position_side,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::EntityType;
+    use serde_json::Value;
+
+    #[test]
+    fn export_json() {
+        let exported: Value = serde_json::from_str(&EntityType::export_json()).unwrap();
+        let entities = exported.as_object().unwrap();
+        assert_eq!(entities.len(), EntityType::iter().count());
+
+        let yamato = &entities[EntityType::Yamato.as_str()];
+        assert_eq!(yamato["label"], "Yamato");
+        assert_eq!(yamato["kind"], "Boat");
+        assert_eq!(
+            yamato["turrets"].as_array().unwrap().len(),
+            EntityType::Yamato.data().turrets.len()
+        );
+        assert!(yamato["sensors"]["sonar"].is_null());
+    }
+}
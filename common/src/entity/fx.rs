@@ -0,0 +1,13 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+/// Audio and visual effects of an entity, for the client.
+#[derive(Clone, Debug)]
+pub struct EntityFx {
+    /// Name of the sound played when the entity is fired (or launched).
+    pub fire_sound: Option<&'static str>,
+    /// Name of the animation played when the entity is destroyed.
+    pub explosion: Option<&'static str>,
+    /// Multiplier of the amount of wake particles.
+    pub wake_scale: f32,
+}
@@ -0,0 +1,11 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+/// Location of an entity's sprite in the sprite sheet, generated from the atlas at compile time.
+#[derive(Clone, Debug)]
+pub struct EntitySprite {
+    /// Texture coordinates in counter-clockwise order starting at bottom left.
+    pub uvs: [[f32; 2]; 4],
+    /// Aspect ratio aka width / height.
+    pub aspect: f32,
+}
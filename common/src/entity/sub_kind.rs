@@ -1,7 +1,10 @@
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum EntitySubKind {
     Aeroplane,
     Battleship,
+    Beam,
     Carrier,
     Corvette,
     Cruiser,
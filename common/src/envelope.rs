@@ -0,0 +1,115 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use crate::entity::{EntityData, EntityKind, EntityType};
+use serde::{Deserialize, Serialize};
+
+/// Where one of a boat's armaments can engage targets, for drawing range rings and launch
+/// envelopes.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    /// Meters the weapon travels before it expires.
+    pub range: f32,
+    /// Meters the weapon travels before its seeker activates, or zero if it has none.
+    pub arming: f32,
+    /// Maximum angle between the weapon's heading and a target that its seeker can acquire, if
+    /// it has a seeker.
+    pub seeker: Option<Angle>,
+}
+
+impl Envelope {
+    /// Returns the envelope of a weapon of type `entity_type`, or [`None`] if it isn't a weapon
+    /// that travels to its target (e.g. a mine).
+    pub fn new(entity_type: EntityType) -> Option<Self> {
+        let data = entity_type.data();
+        if data.kind != EntityKind::Weapon || data.range() <= 0.0 {
+            return None;
+        }
+        let seeker = data.has_seeker().then(|| data.seeker_angle());
+        let arming = if seeker.is_some() {
+            (data.speed.to_mps() * EntityData::SEEKER_DELAY.to_secs()).min(data.range())
+        } else {
+            0.0
+        };
+        Some(Self {
+            range: data.range(),
+            arming,
+            seeker,
+        })
+    }
+
+    /// Whether a target `distance` meters away can be engaged.
+    pub fn in_range(&self, distance: f32) -> bool {
+        distance <= self.range
+    }
+}
+
+/// Envelopes of every armament of a boat. Computed by the server, and sent once per hull, so the
+/// client can cache them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HullEnvelopes {
+    pub entity_type: EntityType,
+    /// Envelope of each armament, in the same order as `EntityData.armaments`.
+    pub armaments: Vec<Option<Envelope>>,
+}
+
+impl HullEnvelopes {
+    /// Returns the envelopes of the armaments of a boat of type `entity_type`.
+    pub fn new(entity_type: EntityType) -> Self {
+        Self {
+            entity_type,
+            armaments: entity_type
+                .data()
+                .armaments
+                .iter()
+                .map(|armament| Envelope::new(armament.entity_type))
+                .collect(),
+        }
+    }
+
+    /// Returns the envelope of the first armament of type `weapon`, or of any armament if
+    /// [`None`].
+    pub fn get(&self, weapon: Option<EntityType>) -> Option<Envelope> {
+        self.entity_type
+            .data()
+            .armaments
+            .iter()
+            .zip(&self.armaments)
+            .filter(|(armament, _)| weapon.map_or(true, |weapon| armament.entity_type == weapon))
+            .find_map(|(_, envelope)| *envelope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::{EntityKind, EntitySubKind, EntityType};
+    use crate::envelope::{Envelope, HullEnvelopes};
+
+    #[test]
+    fn envelope() {
+        for entity_type in EntityType::iter() {
+            let data = entity_type.data();
+            if let Some(envelope) = Envelope::new(entity_type) {
+                assert_eq!(data.kind, EntityKind::Weapon);
+                assert!(envelope.range > 0.0);
+                assert!(envelope.arming <= envelope.range);
+                assert_eq!(envelope.seeker.is_some(), envelope.arming > 0.0);
+                if data.sub_kind == EntitySubKind::Rocket {
+                    assert_eq!(envelope.seeker, None);
+                }
+            }
+
+            if data.kind == EntityKind::Boat {
+                let envelopes = HullEnvelopes::new(entity_type);
+                assert_eq!(envelopes.armaments.len(), data.armaments.len());
+                for armament in data.armaments.iter() {
+                    assert_eq!(
+                        envelopes.get(Some(armament.entity_type)),
+                        Envelope::new(armament.entity_type)
+                    );
+                }
+            }
+        }
+    }
+}
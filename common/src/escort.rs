@@ -0,0 +1,31 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::id::PlayerId;
+use serde::{Deserialize, Serialize};
+
+/// How an escort contract involving the player stands.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EscortStatus {
+    /// The player offered the other player a contract to escort their cargo, which has yet to be
+    /// accepted.
+    Offering,
+    /// The other player offered the player a contract to escort their cargo, which has yet to be
+    /// accepted.
+    Offered,
+    /// The player is escorting the other player's cargo.
+    Escorting,
+    /// The other player is escorting the player's cargo.
+    Escorted,
+}
+
+/// An escort contract involving the player, as sent to their client.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EscortDto {
+    pub other: PlayerId,
+    /// Percentage of the cargo's payout that goes to the escort.
+    pub share: u8,
+    pub status: EscortStatus,
+    /// Attackers sunk by either player since the contract was accepted.
+    pub attackers_sunk: u8,
+}
@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::{EntityId, EntityType};
+use crate::ticks::Ticks;
+use crate::transform::Transform;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Last known state of a boat that the player's sensors lost track of. Shown as a faded contact
+/// until it is detected again.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ghost {
+    pub id: EntityId,
+    /// Type of the boat, if it was identified before being lost.
+    pub entity_type: Option<EntityType>,
+    /// Last known position, course, and speed.
+    pub transform: Transform,
+    /// Server tick at which the boat was last detected (wraps around).
+    pub tick: Ticks,
+}
+
+impl Ghost {
+    /// Seconds that ghosts are shown for, unless the boat is detected again sooner.
+    pub const LIFESPAN: f32 = 30.0;
+
+    /// Returns where the boat would be `seconds` after it was lost, had it held its course and
+    /// speed.
+    pub fn dead_reckoning(&self, seconds: f32) -> Vec2 {
+        self.transform.position
+            + self.transform.direction.to_vec() * (self.transform.velocity.to_mps() * seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::angle::Angle;
+    use crate::entity::EntityId;
+    use crate::ghost::Ghost;
+    use crate::ticks::Ticks;
+    use crate::transform::Transform;
+    use crate::velocity::Velocity;
+    use glam::vec2;
+
+    #[test]
+    fn dead_reckoning() {
+        let ghost = Ghost {
+            id: EntityId::new(1).unwrap(),
+            entity_type: None,
+            transform: Transform {
+                position: vec2(100.0, 0.0),
+                direction: Angle::from_degrees(90.0),
+                velocity: Velocity::from_mps(10.0),
+            },
+            tick: Ticks::ZERO,
+        };
+        assert_eq!(ghost.dead_reckoning(0.0), vec2(100.0, 0.0));
+        assert!(ghost.dead_reckoning(5.0).distance(vec2(100.0, 50.0)) < 0.5);
+    }
+}
@@ -4,6 +4,7 @@
 use crate::angle::Angle;
 
 use crate::velocity::Velocity;
+use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
@@ -18,3 +19,58 @@ impl Guidance {
         Self::default()
     }
 }
+
+/// One leg of a route, which a ship follows in order without further steering commands.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Waypoint {
+    /// Where, in world space.
+    pub position: Vec2,
+    /// Distance from `position` within which the waypoint is considered reached.
+    pub arrival_radius: f32,
+    /// Speed while heading towards `position`.
+    pub velocity_target: Velocity,
+}
+
+impl Waypoint {
+    /// Maximum number of waypoints in a route.
+    pub const MAX_ROUTE_LEN: usize = 16;
+    /// Minimum arrival radius, so that waypoints can't be missed by overshooting.
+    pub const MIN_ARRIVAL_RADIUS: f32 = 10.0;
+
+    /// Returns whether a ship at `position` has reached the waypoint.
+    pub fn is_reached(&self, position: Vec2) -> bool {
+        position.distance_squared(self.position) <= self.arrival_radius.powi(2)
+    }
+
+    /// Returns guidance towards the waypoint, from `position`.
+    pub fn guidance(&self, position: Vec2) -> Guidance {
+        Guidance {
+            direction_target: Angle::from(self.position - position),
+            velocity_target: self.velocity_target,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::angle::Angle;
+    use crate::guidance::Waypoint;
+    use crate::velocity::Velocity;
+    use glam::Vec2;
+
+    #[test]
+    fn waypoint() {
+        let waypoint = Waypoint {
+            position: Vec2::new(100.0, 0.0),
+            arrival_radius: 10.0,
+            velocity_target: Velocity::from_mps(5.0),
+        };
+
+        assert!(!waypoint.is_reached(Vec2::ZERO));
+        assert!(waypoint.is_reached(Vec2::new(95.0, 5.0)));
+
+        let guidance = waypoint.guidance(Vec2::ZERO);
+        assert_eq!(guidance.direction_target, Angle::ZERO);
+        assert_eq!(guidance.velocity_target, waypoint.velocity_target);
+    }
+}
@@ -0,0 +1,14 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// A weapon shot down by point defenses. Sent to clients for rendering the burst of fire.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Intercept {
+    /// Where the point defense fired from.
+    pub source: Vec2,
+    /// Where the weapon was shot down.
+    pub target: Vec2,
+}
@@ -14,14 +14,41 @@ extern crate core;
 extern crate test;
 
 pub mod altitude;
+pub mod ammo;
 pub mod angle;
+pub mod area_effect;
+pub mod audio_event;
+pub mod beam;
+pub mod bounty;
+pub mod cargo;
 pub mod complete;
 pub mod contact;
+pub mod content_pack;
+pub mod cosmetic;
+pub mod day_night;
 pub mod death_reason;
+pub mod drawing;
+pub mod duel;
 pub mod entity;
+pub mod envelope;
+pub mod escort;
+pub mod ghost;
 pub mod guidance;
+pub mod intercept;
 pub mod protocol;
+pub mod scheduler;
+pub mod sea_state;
+pub mod shield;
+pub mod signal;
+pub mod spawn_region;
+pub mod status_effects;
+pub mod summary;
+pub mod target_lock;
 pub mod terrain;
+pub mod terrain_objective;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+pub mod threat_warning;
 pub mod ticks;
 pub mod transform;
 pub mod util;
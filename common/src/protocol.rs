@@ -1,28 +1,142 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::ammo::Ammo;
+use crate::angle::Angle;
+use crate::area_effect::AreaEffect;
+use crate::audio_event::AudioEvent;
+use crate::beam::Beam;
+use crate::bounty::BountyMarker;
+use crate::cargo::Cargo;
 use crate::contact::Contact;
+use crate::content_pack::ContentPackInfo;
+use crate::cosmetic::Cosmetics;
+use crate::day_night::TimeOfDay;
 use crate::death_reason::DeathReason;
+use crate::drawing::Drawing;
+use crate::duel::DuelDto;
 use crate::entity::*;
-use crate::guidance::Guidance;
+use crate::envelope::HullEnvelopes;
+use crate::escort::EscortDto;
+use crate::ghost::Ghost;
+use crate::guidance::{Guidance, Waypoint};
+use crate::intercept::Intercept;
+use crate::sea_state::SeaState;
+use crate::signal::{SignalKind, SignalMarker};
+use crate::spawn_region::SpawnRegion;
+use crate::status_effects::StatusEffects;
+use crate::summary::Summary;
+use crate::target_lock::TargetLock;
 use crate::terrain::{ChunkId, SerializedChunk};
+use crate::terrain_objective::TerrainObjective;
+use crate::threat_warning::ThreatWarning;
+use crate::ticks::Ticks;
+use crate::velocity::Velocity;
+use core_protocol::id::PlayerId;
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Server to client update.
 #[cfg_attr(feature = "server", derive(actix::Message))]
 #[cfg_attr(feature = "server", rtype(result = "()"))]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Update {
+    /// Server tick at which the update was generated (wraps around).
+    pub tick: Ticks,
     /// All currently visible contacts.
     pub contacts: Vec<Contact>,
     /// Why the player died, if they died, otherwise None.
     pub death_reason: Option<DeathReason>,
+    /// Summary of the player's last life, sent once after they die.
+    pub summary: Option<Box<Summary>>,
     /// Player's current score.
     pub score: u32,
+    /// Player's current boat, if alive. Also visible among the contacts, but this is simpler for
+    /// things like rich presence.
+    pub entity_type: Option<EntityType>,
+    /// Signals placed by teammates since the last update.
+    pub signals: Vec<SignalMarker>,
+    /// Drawings placed by the fleet leader since the last update.
+    pub drawings: Vec<Drawing>,
     /// Current world border radius.
     pub world_radius: f32,
     pub terrain: Box<TerrainUpdate>,
+    /// The server's content pack, if any, sent once per connection.
+    pub content_pack: Option<Box<ContentPackInfo>>,
+    /// How to interpolate contacts, sent once per connection.
+    pub interpolation: Option<Interpolation>,
+    /// Engagement envelopes of the armaments of the player's boat, sent once per hull.
+    pub envelopes: Option<Box<HullEnvelopes>>,
+    /// Whether the player's boat will soon be scuttled for lack of input.
+    pub afk: bool,
+    /// How much of returning to port is complete, from 0 to 1, if returning to port.
+    pub port_progress: Option<f32>,
+    /// How much of scuttling is complete, from 0 to 1, if scuttling.
+    pub scuttle_progress: Option<f32>,
+    /// Coins banked by returning to port, which persist with the player's account.
+    pub banked_coins: u32,
+    /// Duels, and challenges to duel, involving the player.
+    pub duels: Vec<DuelDto>,
+    /// Escort contracts, and offers of them, involving the player.
+    pub escorts: Vec<EscortDto>,
+    /// Oil slicks and fires that are on screen.
+    pub area_effects: Vec<AreaEffect>,
+    /// Fires and flooding on the player's boat, if alive.
+    pub status_effects: StatusEffects,
+    /// Ammunition in reserve on the player's boat, if alive.
+    pub ammo: Ammo,
+    /// Cargo being delivered by the player's boat, if any.
+    pub cargo: Option<Cargo>,
+    /// Objective for the player's boat to reshape the terrain, if it can.
+    pub terrain_objective: Option<TerrainObjective>,
+    /// Position in the day/night cycle.
+    pub time_of_day: TimeOfDay,
+    /// Wind and waves.
+    pub sea_state: SeaState,
+    /// Bearings of active radars that illuminate the player's boat, if it has radar.
+    pub radar_warnings: Vec<Angle>,
+    /// Whether collision assist is slowing or steering the player's boat, because of land or an
+    /// obstacle ahead.
+    pub collision_warning: bool,
+    /// The enemy boat the player locked onto, if their sensors still track it.
+    pub target_lock: Option<TargetLock>,
+    /// Last known states of boats that the player's sensors lost track of, since the last
+    /// update.
+    pub ghosts: Vec<Ghost>,
+    /// Weapons that newly threaten the player's boat, since the last update.
+    pub threat_warnings: Vec<ThreatWarning>,
+    /// Weapons shot down by point defenses on screen, since the last update.
+    pub intercepts: Vec<Intercept>,
+    /// Beam weapons being fired on screen.
+    pub beams: Vec<Beam>,
+    /// Sounds made off screen, within hearing range, since the last update.
+    pub audio_events: Vec<AudioEvent>,
+    /// Bounties on the top players, wherever they are.
+    pub bounties: Vec<BountyMarker>,
+    /// Whether the player is casting (see [`Cast`]).
+    pub casting: bool,
+    /// Scores of every player with a boat, only sent while casting.
+    pub scores: Vec<(PlayerId, u32)>,
+}
+
+/// Timing of updates, for client-side interpolation and extrapolation of contacts.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Interpolation {
+    /// Seconds between server ticks (and therefore updates).
+    pub tick_period: f32,
+    /// Seconds that contacts should be extrapolated by, to compensate for latency. Clients may
+    /// override this.
+    pub delay: f32,
+}
+
+impl Default for Interpolation {
+    fn default() -> Self {
+        Self {
+            tick_period: Ticks::PERIOD_SECS,
+            delay: 0.1,
+        }
+    }
 }
 
 /// Updates for terrain chunks.
@@ -33,11 +147,40 @@ pub type TerrainUpdate = [(ChunkId, SerializedChunk)];
 #[cfg_attr(feature = "server", derive(actix::Message))]
 #[cfg_attr(feature = "server", rtype(result = "()"))]
 pub enum Command {
+    /// Only allowed for moderators.
+    Cast(Cast),
     Control(Control),
+    DamageControl(DamageControl),
+    /// Only allowed for fleet leaders.
+    Draw(Drawing),
+    Duel(Duel),
+    Escort(Escort),
+    LockTarget(LockTarget),
+    ReturnToPort(ReturnToPort),
+    Route(Route),
+    Scuttle(Scuttle),
+    Signal(Signal),
     Spawn(Spawn),
+    Transfer(Transfer),
     Upgrade(Upgrade),
 }
 
+/// Starts, moves, or stops casting, in which a spectator (e.g. of a tournament) sees every boat,
+/// wherever it is, and everything near the camera, regardless of sensors. Casters are sent
+/// updates less often, to limit the cost of the larger view.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Cast {
+    /// Where the caster is looking, or [`None`] to stop casting.
+    pub camera: Option<Vec2>,
+}
+
+impl Cast {
+    /// Ticks between updates sent to casters.
+    pub const UPDATE_PERIOD: Ticks = Ticks::from_repr(3);
+    /// Meters from the camera within which casters see all entities, not only boats.
+    pub const VIEW: f32 = 1500.0;
+}
+
 /// Generic command to control one's ship.
 #[derive(Clone, Serialize, PartialEq, Deserialize, Debug)]
 pub struct Control {
@@ -45,6 +188,8 @@ pub struct Control {
     pub guidance: Option<Guidance>,
     /// Submerge submarine.
     pub submerge: bool,
+    /// Hold at periscope depth, rather than diving deep, while submerged.
+    pub periscope: bool,
     /// Turret/aircraft/pay target.
     pub aim_target: Option<Vec2>,
     /// Active sensors.
@@ -57,6 +202,69 @@ pub struct Control {
     pub hint: Option<Hint>,
     /// Horn Volume
     pub horn: bool,
+    /// Call the repair crew, if off cooldown.
+    pub repair_crew: bool,
+    /// Shine a searchlight towards the aim target.
+    pub searchlight: bool,
+    /// Emission control, which keeps radar silent even if sensors are active.
+    pub emcon: bool,
+    /// Set speed to a preset, after any steering commands.
+    pub throttle: Option<Throttle>,
+    /// Cruise control, which holds the current speed, ignoring the speed of steering commands.
+    pub cruise: bool,
+    /// Slow down and steer automatically to avoid running aground, and warn of land or obstacles
+    /// ahead.
+    pub collision_assist: bool,
+    /// Bearings at which to hold turrets, indexed like `EntityData.turrets`, instead of aiming
+    /// them at the aim target. Missing or [`None`] turrets follow the aim target.
+    pub turret_locks: Vec<Option<TurretLock>>,
+}
+
+/// Holds a turret at a fixed bearing, regardless of the aim target.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TurretLock {
+    /// Bearing relative to the boat's heading, which turns with the boat.
+    Relative(Angle),
+    /// True bearing, which is held as the boat turns.
+    True(Angle),
+}
+
+impl TurretLock {
+    /// Direction in which to point the turret, relative to the boat, given its direction.
+    pub fn direction_target(self, boat_direction: Angle) -> Angle {
+        match self {
+            Self::Relative(bearing) => bearing,
+            Self::True(bearing) => bearing - boat_direction,
+        }
+    }
+}
+
+/// Preset speeds, in addition to the analog speed of steering commands.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Throttle {
+    /// Full speed astern.
+    Reverse,
+    OneThird,
+    TwoThirds,
+    /// Full speed ahead.
+    Flank,
+}
+
+impl Throttle {
+    /// Fraction of a boat's maximum speed.
+    pub fn fraction(self) -> f32 {
+        match self {
+            Self::Reverse => Velocity::MAX_REVERSE_SCALE,
+            Self::OneThird => 1.0 / 3.0,
+            Self::TwoThirds => 2.0 / 3.0,
+            Self::Flank => 1.0,
+        }
+    }
+
+    /// Velocity target of the preset, given a boat's maximum speed.
+    pub fn velocity_target(self, max_speed: Velocity) -> Velocity {
+        max_speed * self.fraction()
+    }
 }
 
 /// Fire/use a single weapon.
@@ -72,11 +280,17 @@ pub struct Hint {
     /// aspect ratio of screen (width / height).
     /// Allows the server to send the correct amount of terrain.
     pub aspect: f32,
+    /// Whether updates have recently been arriving faster than they could be processed, so the
+    /// server should reduce their detail.
+    pub congested: bool,
 }
 
 impl Default for Hint {
     fn default() -> Self {
-        Self { aspect: 1.0 }
+        Self {
+            aspect: 1.0,
+            congested: false,
+        }
     }
 }
 
@@ -89,6 +303,150 @@ pub struct Pay;
 pub struct Spawn {
     /// What to spawn as. Must be an affordable boat.
     pub entity_type: EntityType,
+    /// How to look. Any that aren't unlocked are ignored.
+    pub cosmetics: Cosmetics,
+    /// Where to spawn, if possible.
+    pub region: SpawnRegion,
+}
+
+/// Starts or stops damage control, which puts out fires and stops flooding sooner, but slows
+/// reloading.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DamageControl {
+    pub active: bool,
+}
+
+/// Starts or cancels returning to port. Once one's boat has stayed safe for
+/// [`ReturnToPort::CHANNEL`], it is removed and part of one's score is banked as coins, instead
+/// of being lost when eventually sunk.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ReturnToPort {
+    pub cancel: bool,
+}
+
+impl ReturnToPort {
+    /// How long one's boat must stay safe to complete returning to port.
+    pub const CHANNEL: Duration = Duration::from_secs(30);
+    /// Fraction of score that is banked.
+    pub const BANK_FRACTION: f32 = 0.5;
+    /// Returning to port is only possible, and is cancelled, with enemy boats this close.
+    pub const SAFE_RANGE: f32 = 1500.0;
+}
+
+/// Locks onto an enemy boat, which must be identified by one's sensors, or releases the lock if
+/// [`None`]. Guided weapons are launched towards where the locked target will be, instead of the
+/// aim target.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LockTarget {
+    pub target: Option<EntityId>,
+}
+
+/// Starts or cancels scuttling. Once [`Scuttle::DELAY`] has passed, one's boat is removed, leaving
+/// less loot than if sunk, so that scuttling can't be used to hand loot to others. Scuttling isn't
+/// possible, and is cancelled, within [`Scuttle::COMBAT_COOLDOWN`] of taking damage.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Scuttle {
+    pub cancel: bool,
+}
+
+impl Scuttle {
+    /// How long the scuttle button must be held to confirm starting to scuttle.
+    pub const HOLD: f32 = 1.5;
+    /// How long scuttling takes.
+    pub const DELAY: Duration = Duration::from_secs(20);
+    /// How long after taking damage scuttling is unavailable.
+    pub const COMBAT_COOLDOWN: Ticks = Ticks::from_whole_secs(10);
+    /// Fraction of the usual loot left by a scuttled boat.
+    pub const LOOT_FRACTION: f32 = 0.25;
+}
+
+/// Challenges another player to a duel, or answers a challenge. Both players stake score, which
+/// is held in escrow, and whichever sinks the other within [`Duel::TIME_LIMIT`] takes both stakes.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum Duel {
+    /// Challenge a player, staking one of [`Duel::STAKES`].
+    Challenge { player_id: PlayerId, stake: u32 },
+    /// Accept a player's challenge, staking the same score.
+    Accept(PlayerId),
+    /// Decline a player's challenge, or withdraw one's challenge to them.
+    Decline(PlayerId),
+}
+
+impl Duel {
+    /// Score that may be staked. Stakes count towards [`Transfer::CAP`], so none may exceed it.
+    pub const STAKES: [u32; 3] = [100, 500, Transfer::CAP];
+    /// How long a challenge may go unanswered before it expires.
+    pub const CHALLENGE_LIFESPAN: Duration = Duration::from_secs(30);
+    /// How long a duel may go undecided before it is called off.
+    pub const TIME_LIMIT: Duration = Duration::from_secs(5 * 60);
+}
+
+/// Offers another player a contract to escort one's [`Cargo`], or answers or ends a contract. On
+/// delivery, the escort is paid a share of the payout, and both players are paid a bonus for each
+/// attacker that either sank during the run.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum Escort {
+    /// Offer a player one of [`Escort::SHARES`] to escort one's cargo.
+    Offer { player_id: PlayerId, share: u8 },
+    /// Accept a player's offer to escort their cargo.
+    Accept(PlayerId),
+    /// Decline a player's offer, withdraw one's offer to them, or end a contract with them.
+    Cancel(PlayerId),
+}
+
+impl Escort {
+    /// Percentages of the payout that may be offered.
+    pub const SHARES: [u8; 3] = [10, 25, 50];
+    /// How long an offer may go unanswered before it expires.
+    pub const OFFER_LIFESPAN: Duration = Duration::from_secs(30);
+    /// Fraction of the payout paid to both players, per attacker sunk during the run.
+    pub const ATTACKER_BONUS: f32 = 0.1;
+    /// Most attackers that count towards the bonus.
+    pub const MAX_ATTACKERS: u8 = 5;
+}
+
+/// Replaces the route of one's ship, which it follows instead of [`Control::guidance`] until
+/// the last waypoint is reached. An empty route clears it.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Route {
+    pub waypoints: Vec<Waypoint>,
+}
+
+/// Place a signal for teammates to see.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Signal {
+    pub kind: SignalKind,
+    /// Where, in world space.
+    pub position: Vec2,
+}
+
+/// Sends score to a nearby member of one's fleet, such as to fund a support boat. Part of it is
+/// lost to [`Transfer::TAX`], and senders are limited to [`Transfer::CAP`] per
+/// [`Transfer::CAP_PERIOD`], to discourage farming score with alternate accounts.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Transfer {
+    pub player_id: PlayerId,
+    /// One of [`Transfer::AMOUNTS`].
+    pub amount: u32,
+}
+
+impl Transfer {
+    /// Score that may be sent at once.
+    pub const AMOUNTS: [u32; 2] = [100, 500];
+    /// Fraction of each transfer that is lost.
+    pub const TAX: f32 = 0.2;
+    /// Most score a player may send per [`Transfer::CAP_PERIOD`].
+    pub const CAP: u32 = 1000;
+    pub const CAP_PERIOD: Duration = Duration::from_secs(60);
+    /// Meters within which fleet members may transfer score.
+    pub const RANGE: f32 = 1000.0;
+    /// Highest level a player must have ever reached, so that brand-new accounts can't send score.
+    pub const MIN_HIGHEST_LEVEL: u8 = 3;
+
+    /// Returns how much of an amount the recipient receives, after tax.
+    pub fn received(amount: u32) -> u32 {
+        (amount as f32 * (1.0 - Self::TAX)) as u32
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -102,8 +460,10 @@ mod tests {
     use super::*;
     use crate::altitude::Altitude;
     use crate::contact::ReloadsStorage;
+    use crate::cosmetic::{Flag, HullPaint};
     use crate::entity::EntityId;
     use crate::guidance::Guidance;
+    use crate::shield::{Shield, ShieldState};
     use crate::ticks::Ticks;
     use crate::transform::Transform;
     use crate::velocity::Velocity;
@@ -113,6 +473,42 @@ mod tests {
     use glam::vec2;
     use rand::prelude::*;
     use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn transfer_tax() {
+        assert_eq!(Transfer::received(0), 0);
+        for amount in Transfer::AMOUNTS {
+            assert!(Transfer::received(amount) < amount);
+            assert!(Transfer::received(amount) > 0);
+        }
+        assert_eq!(Transfer::received(500), 400);
+    }
+
+    #[test]
+    fn throttle() {
+        let max_speed = Velocity::from_mps(30.0);
+        assert_eq!(Throttle::Flank.velocity_target(max_speed), max_speed);
+        assert!(Throttle::Reverse.velocity_target(max_speed) < Velocity::ZERO);
+        assert!(
+            Throttle::OneThird.velocity_target(max_speed)
+                < Throttle::TwoThirds.velocity_target(max_speed)
+        );
+    }
+
+    #[test]
+    fn turret_lock() {
+        let boat_direction = Angle::from_degrees(30.0);
+        let bearing = Angle::from_degrees(90.0);
+        assert_eq!(
+            TurretLock::Relative(bearing).direction_target(boat_direction),
+            bearing
+        );
+        assert_eq!(
+            TurretLock::True(bearing).direction_target(boat_direction) + boat_direction,
+            bearing
+        );
+    }
 
     #[test]
     fn serialize() {
@@ -127,6 +523,14 @@ mod tests {
 
             let c = Contact::new(
                 Altitude::from_u8(rng.gen()),
+                if is_boat {
+                    Cosmetics {
+                        flag: Flag::iter().choose(&mut rng).unwrap(),
+                        paint: HullPaint::iter().choose(&mut rng).unwrap(),
+                    }
+                } else {
+                    Cosmetics::default()
+                },
                 Ticks::from_secs(rng.gen::<f32>() * 10.0),
                 entity_type,
                 Guidance {
@@ -166,6 +570,20 @@ mod tests {
                         .map(|_| rng.gen())
                         .collect()
                 }),
+                is_boat && rng.gen(),
+                entity_type
+                    .filter(|_| is_boat)
+                    .and_then(Shield::of)
+                    .map(|_| ShieldState::new(rng.gen(), rng.gen())),
+                is_boat && rng.gen(),
+                (entity_type.is_none() && rng.gen_bool(0.5)).then(|| {
+                    EntityType::iter()
+                        .filter(|t| t.data().kind == EntityKind::Boat)
+                        .choose(&mut rng)
+                        .unwrap()
+                        .data()
+                        .sub_kind
+                }),
             );
 
             let options = DefaultOptions::new()
@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::ticks::Ticks;
+use rand::{thread_rng, Rng};
+
+/// Schedules repeating and one-shot tasks, instead of each subsystem checking `counter.every(..)`
+/// ad hoc. Tasks are plain values (e.g. an enum), returned from [`Scheduler::update`] when due.
+pub struct Scheduler<T> {
+    /// Ticks since creation. Unlike [`Ticks`], this doesn't wrap.
+    now: u64,
+    tasks: Vec<ScheduledTask<T>>,
+}
+
+struct ScheduledTask<T> {
+    task: T,
+    /// When the task is next due, not including jitter.
+    base: u64,
+    /// When the task is next due, including jitter.
+    due: u64,
+    /// None if the task is one-shot.
+    period: Option<Ticks>,
+    jitter: Ticks,
+}
+
+impl<T> Default for Scheduler<T> {
+    fn default() -> Self {
+        Self {
+            now: 0,
+            tasks: Vec::new(),
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> Scheduler<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedules `task` to run once, after `delay`.
+    pub fn once(&mut self, task: T, delay: Ticks) {
+        self.schedule(task, delay, None, Ticks::ZERO);
+    }
+
+    /// Schedules `task` to run every `period`, starting one `period` from now.
+    pub fn repeat(&mut self, task: T, period: Ticks) {
+        self.repeat_with_jitter(task, period, Ticks::ZERO);
+    }
+
+    /// Like [`Scheduler::repeat`], but delays each run by a random amount up to `jitter`, so that
+    /// tasks with the same period don't all run on the same tick. The average period is unchanged.
+    pub fn repeat_with_jitter(&mut self, task: T, period: Ticks, jitter: Ticks) {
+        debug_assert!(period > Ticks::ZERO, "period must be positive");
+        self.schedule(task, period, Some(period), jitter);
+    }
+
+    /// Cancels all occurrences of `task`.
+    pub fn cancel(&mut self, task: T) {
+        self.tasks.retain(|scheduled| scheduled.task != task);
+    }
+
+    /// Returns whether `task` is scheduled.
+    pub fn contains(&self, task: T) -> bool {
+        self.tasks.iter().any(|scheduled| scheduled.task == task)
+    }
+
+    /// Advances time by `delta`, returning the tasks that became due, in the order they were
+    /// scheduled. Repeating tasks that were due multiple times are only returned once.
+    pub fn update(&mut self, delta: Ticks) -> Vec<T> {
+        self.now += delta.0 as u64;
+        let now = self.now;
+
+        let mut due = Vec::new();
+        self.tasks.retain_mut(|scheduled| {
+            if scheduled.due > now {
+                return true;
+            }
+            due.push(scheduled.task);
+
+            if let Some(period) = scheduled.period {
+                while scheduled.base <= now {
+                    scheduled.base += period.0 as u64;
+                }
+                scheduled.due = scheduled.base + Self::jitter(scheduled.jitter);
+                true
+            } else {
+                false
+            }
+        });
+        due
+    }
+
+    fn schedule(&mut self, task: T, delay: Ticks, period: Option<Ticks>, jitter: Ticks) {
+        let base = self.now + delay.0 as u64;
+        self.tasks.push(ScheduledTask {
+            task,
+            base,
+            due: base + Self::jitter(jitter),
+            period,
+            jitter,
+        });
+    }
+
+    fn jitter(jitter: Ticks) -> u64 {
+        if jitter == Ticks::ZERO {
+            0
+        } else {
+            thread_rng().gen_range(0..=jitter.0) as u64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scheduler::Scheduler;
+    use crate::ticks::Ticks;
+
+    #[test]
+    fn once_and_repeat() {
+        let mut scheduler = Scheduler::new();
+        scheduler.once("once", Ticks::from_repr(2));
+        scheduler.repeat("repeat", Ticks::from_repr(3));
+
+        let runs: Vec<_> = (0..9).map(|_| scheduler.update(Ticks::ONE)).collect();
+        assert_eq!(
+            runs,
+            vec![
+                vec![],
+                vec!["once"],
+                vec!["repeat"],
+                vec![],
+                vec![],
+                vec!["repeat"],
+                vec![],
+                vec![],
+                vec!["repeat"],
+            ]
+        );
+        assert!(!scheduler.contains("once"));
+
+        scheduler.cancel("repeat");
+        assert!(scheduler.update(Ticks::from_repr(10)).is_empty());
+    }
+
+    #[test]
+    fn jitter() {
+        let mut scheduler = Scheduler::new();
+        scheduler.repeat_with_jitter((), Ticks::from_repr(10), Ticks::from_repr(4));
+
+        let mut runs = Vec::new();
+        for tick in 1..=1000 {
+            if !scheduler.update(Ticks::ONE).is_empty() {
+                runs.push(tick);
+            }
+        }
+        // Each run is within its window, so the average period is unchanged. Jitter may push the
+        // last run past the end.
+        assert!((99..=100).contains(&runs.len()), "{} runs", runs.len());
+        for (i, tick) in runs.into_iter().enumerate() {
+            let base = (i as u32 + 1) * 10;
+            assert!((base..=base + 4).contains(&tick), "{} {}", tick, base);
+        }
+    }
+}
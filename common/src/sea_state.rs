@@ -0,0 +1,147 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use common_util::range::map_ranges;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// Wind and waves. Advanced by the server, which uses it for boat physics, and sent to clients so
+/// that the water and bobbing entities they render match it.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SeaState {
+    /// Seeds the procedural wave field (and how the weather changes), so that every client sees
+    /// the same waves in the same places.
+    pub seed: u32,
+    /// Direction the wind blows towards.
+    pub wind_direction: Angle,
+    /// Wind speed in meters per second.
+    pub wind_speed: f32,
+    /// Significant wave height in meters.
+    pub wave_height: f32,
+    /// Meters the wave field has drifted with the wind. Integrated as the wind changes, so waves
+    /// don't jump when it does.
+    pub wave_drift: Vec2,
+}
+
+impl Default for SeaState {
+    fn default() -> Self {
+        Self::at(0, 0.0)
+    }
+}
+
+impl SeaState {
+    /// Slowest and fastest wind speed, in meters per second.
+    const WIND_SPEED: std::ops::Range<f32> = 3.0..12.0;
+    /// Seconds per period of the (slowest) weather change.
+    const PERIOD: f32 = 30.0 * 60.0;
+    /// Boats shorter than this many wave heights are slowed.
+    const SMALL_BOAT_WAVES: f32 = 12.0;
+    /// Fraction of speed that the smallest boats lose.
+    const MAX_SPEED_PENALTY: f32 = 0.3;
+
+    /// The sea state at `secs` seconds since the weather, with the given seed, started.
+    pub fn at(seed: u32, secs: f32) -> Self {
+        // Phases in 0..TAU, derived from the seed.
+        let phase = |i: u32| {
+            (seed.wrapping_mul(2654435761).rotate_left(i * 8) as f32 / u32::MAX as f32) * TAU
+        };
+        let t = secs * (TAU / Self::PERIOD);
+
+        // Sum of incommensurate sines, so the weather doesn't obviously repeat.
+        let gust = 0.6 * (t + phase(0)).sin() + 0.4 * (t * 2.7 + phase(1)).sin();
+        let wind_speed = map_ranges(gust, -1.0..1.0, Self::WIND_SPEED, true);
+        let wind_direction = Angle::from_radians(phase(2) + 0.8 * (t * 0.6 + phase(3)).sin());
+
+        // Approximates a fully developed sea.
+        let wave_height = 0.0246 * wind_speed.powi(2);
+
+        Self {
+            seed,
+            wind_direction,
+            wind_speed,
+            wave_height,
+            wave_drift: Vec2::ZERO,
+        }
+    }
+
+    /// Advances the sea state to `secs` seconds since the weather started, `delta` seconds after
+    /// it was last advanced.
+    pub fn advance(&self, secs: f32, delta: f32) -> Self {
+        Self {
+            wave_drift: self.wave_drift + self.wind() * delta,
+            ..Self::at(self.seed, secs)
+        }
+    }
+
+    /// Wind velocity in meters per second.
+    pub fn wind(&self) -> Vec2 {
+        self.wind_direction.to_vec() * self.wind_speed
+    }
+
+    /// Offset of the procedural wave field, in meters, which depends only on the seed.
+    pub fn wave_offset(&self) -> Vec2 {
+        let x = self.seed & 0xffff;
+        let y = self.seed >> 16;
+        Vec2::new(x as f32, y as f32) * (1000.0 / u16::MAX as f32)
+    }
+
+    /// Wave height relative to a typical sea, for scaling visual waves and bobbing.
+    pub fn wave_scale(&self) -> f32 {
+        (self.wave_height * (1.0 / 1.2)).clamp(0.25, 2.5)
+    }
+
+    /// Multiplier of the maximum speed of a surface boat of a given length, because small boats
+    /// are slowed by high seas.
+    pub fn speed_multiplier(&self, length: f32) -> f32 {
+        let small = 1.0 - length / (self.wave_height * Self::SMALL_BOAT_WAVES);
+        1.0 - Self::MAX_SPEED_PENALTY * small.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sea_state::SeaState;
+
+    #[test]
+    fn sea_state() {
+        let mut calm = f32::INFINITY;
+        let mut rough = 0f32;
+        for minute in 0..24 * 60 {
+            let sea_state = SeaState::at(12345, minute as f32 * 60.0);
+            assert_eq!(sea_state, SeaState::at(12345, minute as f32 * 60.0));
+            assert!(sea_state.wind_speed >= 3.0 && sea_state.wind_speed <= 12.0);
+            calm = calm.min(sea_state.wave_height);
+            rough = rough.max(sea_state.wave_height);
+
+            // Big boats are never slowed, small boats are slowed at most 30%.
+            assert_eq!(sea_state.speed_multiplier(200.0), 1.0);
+            let small = sea_state.speed_multiplier(10.0);
+            assert!(small >= 0.7 && small <= 1.0);
+        }
+        assert!(calm < 0.5);
+        assert!(rough > 3.0);
+        assert_ne!(
+            SeaState::at(1, 0.0).wave_offset(),
+            SeaState::at(2 << 16, 0.0).wave_offset()
+        );
+    }
+
+    #[test]
+    fn advance() {
+        let mut sea_state = SeaState::at(12345, 0.0);
+        let mut secs = 0.0;
+        for _ in 0..10 * 60 * 10 {
+            let previous = sea_state;
+            secs += 0.1;
+            sea_state = sea_state.advance(secs, 0.1);
+            assert_eq!(sea_state.wind_speed, SeaState::at(12345, secs).wind_speed);
+
+            // Waves move smoothly with the wind, even as it changes.
+            let drift = sea_state.wave_drift - previous.wave_drift;
+            assert!((drift - previous.wind() * 0.1).length() < 0.001);
+        }
+        assert!(sea_state.wave_drift.length() > 1000.0);
+    }
+}
@@ -0,0 +1,86 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::EntityType;
+use crate::ticks::Ticks;
+use serde::{Deserialize, Serialize};
+
+/// A regenerating pool that absorbs damage before it reaches the hull.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Shield {
+    /// Damage absorbed by a full shield, as a fraction of the boat's max health.
+    pub capacity: f32,
+    /// Fraction of capacity recharged per second, once the recharge delay is over.
+    pub recharge: f32,
+}
+
+impl Shield {
+    /// How long after absorbing a hit a shield waits before recharging.
+    pub const RECHARGE_DELAY: Ticks = Ticks::from_whole_secs(4);
+    /// How long after absorbing a hit a shield shows an impact.
+    pub const IMPACT: Ticks = Ticks::from_repr(5);
+
+    /// Returns the shield of a boat type, if it has one.
+    pub fn of(entity_type: EntityType) -> Option<Self> {
+        match entity_type {
+            EntityType::StarDestroyer => Some(Self {
+                capacity: 0.5,
+                recharge: 0.1,
+            }),
+            EntityType::Vindicator => Some(Self {
+                capacity: 0.4,
+                recharge: 0.125,
+            }),
+            EntityType::Xwing => Some(Self {
+                capacity: 0.25,
+                recharge: 0.2,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// State of a boat's shield, as seen by clients.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShieldState {
+    /// Remaining fraction of capacity, out of `u8::MAX`.
+    pub strength: u8,
+    /// Whether the shield recently absorbed a hit.
+    pub impact: bool,
+}
+
+impl ShieldState {
+    /// Creates a shield state from the remaining fraction of capacity.
+    pub fn new(fraction: f32, impact: bool) -> Self {
+        Self {
+            // Rounded up, so a shield is never shown as down while it has any strength left.
+            strength: (fraction.clamp(0.0, 1.0) * u8::MAX as f32).ceil() as u8,
+            impact,
+        }
+    }
+
+    /// Returns the remaining fraction of capacity.
+    pub fn fraction(self) -> f32 {
+        self.strength as f32 / u8::MAX as f32
+    }
+
+    /// Returns true if the shield is down, and won't absorb damage.
+    pub fn is_down(self) -> bool {
+        self.strength == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shield::ShieldState;
+
+    #[test]
+    fn fraction() {
+        assert!(ShieldState::new(0.0, false).is_down());
+        assert!(ShieldState::new(-1.0, false).is_down());
+        assert_eq!(ShieldState::new(1.0, false).fraction(), 1.0);
+        assert_eq!(ShieldState::new(2.0, false).fraction(), 1.0);
+        assert!((ShieldState::new(0.3, true).fraction() - 0.3).abs() < 0.01);
+        assert!(!ShieldState::new(0.001, false).is_down());
+    }
+}
@@ -0,0 +1,46 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::id::PlayerId;
+use enum_iterator::IntoEnumIterator;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Quick, non-chat communication with teammates, placed at a position in the world.
+#[derive(
+    Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, enum_iterator::IntoEnumIterator,
+)]
+pub enum SignalKind {
+    Attack,
+    Help,
+    Retreat,
+}
+
+impl SignalKind {
+    /// Minimum time between signals from the same player.
+    pub const COOLDOWN: Duration = Duration::from_secs(3);
+    /// How long a signal is shown to teammates.
+    pub const LIFESPAN: Duration = Duration::from_secs(10);
+
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::into_enum_iter()
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::Attack => "⚔",
+            Self::Help => "✚",
+            Self::Retreat => "↩",
+        }
+    }
+}
+
+/// A signal, as seen by a teammate.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SignalMarker {
+    /// Who placed the signal.
+    pub player_id: PlayerId,
+    pub kind: SignalKind,
+    pub position: Vec2,
+}
@@ -0,0 +1,123 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use enum_iterator::IntoEnumIterator;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A coarse region of the world that a player would like to spawn in. The server may still move
+/// them, e.g. to keep their boat in the right area or away from where they were just sunk.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+    enum_iterator::IntoEnumIterator,
+)]
+pub enum SpawnRegion {
+    /// Wherever the server decides, based on score, even if the player has a fleet.
+    Any,
+    /// Near a boat of the player's fleet, falling back to [`Self::Any`] if there are none.
+    #[default]
+    Fleet,
+    North,
+    East,
+    South,
+    West,
+}
+
+impl SpawnRegion {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::into_enum_iter()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Fleet => "fleet",
+            Self::North => "north",
+            Self::East => "east",
+            Self::South => "south",
+            Self::West => "west",
+        }
+    }
+
+    /// Whether to spawn near a boat of the player's fleet (team or inviter), if possible.
+    pub fn follows_fleet(self) -> bool {
+        self == Self::Fleet
+    }
+
+    /// Returns the center and radius of a quadrant of a world of radius `world_radius`, or
+    /// [`None`] if this region isn't a quadrant.
+    pub fn quadrant(self, world_radius: f32) -> Option<(Vec2, f32)> {
+        let direction = match self {
+            Self::Any | Self::Fleet => return None,
+            Self::North => Vec2::Y,
+            Self::East => Vec2::X,
+            Self::South => -Vec2::Y,
+            Self::West => -Vec2::X,
+        };
+        Some((direction * (0.5 * world_radius), 0.35 * world_radius))
+    }
+}
+
+impl fmt::Display for SpawnRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SpawnRegion {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::iter().find(|r| r.as_str() == s).ok_or(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spawn_region::SpawnRegion;
+    use std::str::FromStr;
+
+    #[test]
+    fn from_str() {
+        for region in SpawnRegion::iter() {
+            assert_eq!(SpawnRegion::from_str(&region.to_string()), Ok(region));
+        }
+        assert!(SpawnRegion::from_str("up").is_err());
+    }
+
+    #[test]
+    fn quadrant() {
+        assert_eq!(SpawnRegion::Any.quadrant(1000.0), None);
+        assert_eq!(SpawnRegion::Fleet.quadrant(1000.0), None);
+        for region in SpawnRegion::iter() {
+            if let Some((center, radius)) = region.quadrant(1000.0) {
+                // Quadrants stay within the world.
+                assert!(center.length() + radius <= 1000.0, "{:?}", region);
+            }
+        }
+        let (north, _) = SpawnRegion::North.quadrant(1000.0).unwrap();
+        assert!(north.y > 0.0);
+    }
+
+    #[test]
+    fn follows_fleet() {
+        // Players keep regrouping with their fleet unless they opt out.
+        assert!(SpawnRegion::default().follows_fleet());
+        assert_eq!(
+            SpawnRegion::iter()
+                .filter(|r| r.follows_fleet())
+                .collect::<Vec<_>>(),
+            vec![SpawnRegion::Fleet]
+        );
+    }
+}
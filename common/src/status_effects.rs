@@ -0,0 +1,200 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::ticks::Ticks;
+use serde::{Deserialize, Serialize};
+
+/// Fires and flooding that a boat suffers from, and what its crew is doing about them. Sent to
+/// the boat's player.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffects {
+    /// Time until the fire burns out, or zero if not on fire.
+    pub fire: Ticks,
+    /// Time until the leak is patched, or zero if not leaking.
+    pub leak: Ticks,
+    /// How much water the boat has taken on, from 0 to 1.
+    pub flooding: f32,
+    /// Whether the crew is doing damage control, at the expense of reloading.
+    pub damage_control: bool,
+    /// Time until the repair crew is done, or zero if not called.
+    pub repair_crew: Ticks,
+    /// Time until the repair crew may be called again.
+    pub repair_crew_cooldown: Ticks,
+}
+
+impl StatusEffects {
+    /// Chance of a shell that doesn't sink a boat setting it on fire.
+    pub const IGNITE_CHANCE: f64 = 0.2;
+    const FIRE_DURATION: Ticks = Ticks::from_whole_secs(20);
+    /// Fraction of its health that a boat loses per second, while on fire.
+    const FIRE_DAMAGE: f32 = 0.01;
+    /// Time each torpedo hit leaks for.
+    const LEAK_DURATION: Ticks = Ticks::from_whole_secs(15);
+    const MAX_LEAK: Ticks = Ticks::from_whole_secs(45);
+    /// Flooding per second, while leaking.
+    const FLOOD_RATE: f32 = 0.04;
+    /// Flooding drained per second, once the leak is patched.
+    const DRAIN_RATE: f32 = 0.02;
+    /// Most that flooding may reduce speed and turning by.
+    const MAX_FLOODING_PENALTY: f32 = 0.6;
+    /// How much faster fires burn out, leaks are patched, and water is drained during damage
+    /// control.
+    const DAMAGE_CONTROL_RECOVERY: f32 = 3.0;
+    /// Fraction of the usual reload speed during damage control.
+    pub const DAMAGE_CONTROL_RELOAD: f32 = 0.5;
+    const REPAIR_CREW_DURATION: Ticks = Ticks::from_whole_secs(10);
+    const REPAIR_CREW_COOLDOWN: Ticks = Ticks::from_whole_secs(60);
+    /// How much faster boats repair while the repair crew is working.
+    const REPAIR_CREW_REPAIR: f32 = 4.0;
+
+    pub fn is_on_fire(&self) -> bool {
+        self.fire != Ticks::ZERO
+    }
+
+    pub fn is_flooding(&self) -> bool {
+        self.leak != Ticks::ZERO || self.flooding > 0.0
+    }
+
+    /// Sets the boat on fire, e.g. when hit by a shell.
+    pub fn ignite(&mut self) {
+        self.fire = self.fire.max(Self::FIRE_DURATION);
+    }
+
+    /// Makes the boat leak for longer, e.g. when hit by a torpedo.
+    pub fn spring_leak(&mut self) {
+        self.leak = self
+            .leak
+            .saturating_add(Self::LEAK_DURATION)
+            .min(Self::MAX_LEAK);
+    }
+
+    /// Calls the repair crew, which puts out fires and patches leaks immediately, then drains
+    /// water and repairs faster for a while. Returns false if still on cooldown.
+    pub fn call_repair_crew(&mut self) -> bool {
+        if self.repair_crew_cooldown != Ticks::ZERO {
+            return false;
+        }
+        self.fire = Ticks::ZERO;
+        self.leak = Ticks::ZERO;
+        self.repair_crew = Self::REPAIR_CREW_DURATION;
+        self.repair_crew_cooldown = Self::REPAIR_CREW_COOLDOWN;
+        true
+    }
+
+    /// Multiplier for repairing health, due to the repair crew.
+    pub fn repair_multiplier(&self) -> f32 {
+        if self.repair_crew != Ticks::ZERO {
+            Self::REPAIR_CREW_REPAIR
+        } else {
+            1.0
+        }
+    }
+
+    /// Multiplier for speed and turning, due to flooding.
+    pub fn flooding_penalty(&self) -> f32 {
+        1.0 - Self::MAX_FLOODING_PENALTY * self.flooding
+    }
+
+    /// Burns, floods, and recovers over `delta`, returning the fraction of the boat's health that
+    /// was lost to fire.
+    pub fn update(&mut self, delta: Ticks) -> f32 {
+        let secs = delta.to_secs();
+        let recovery = if self.damage_control || self.repair_crew != Ticks::ZERO {
+            Self::DAMAGE_CONTROL_RECOVERY
+        } else {
+            1.0
+        };
+        let recovered = delta * recovery;
+
+        let damage = if self.is_on_fire() {
+            Self::FIRE_DAMAGE * secs
+        } else {
+            0.0
+        };
+        self.fire = self.fire.saturating_sub(recovered);
+
+        self.flooding = if self.leak != Ticks::ZERO {
+            self.flooding + Self::FLOOD_RATE * secs
+        } else {
+            self.flooding - Self::DRAIN_RATE * recovery * secs
+        }
+        .clamp(0.0, 1.0);
+        self.leak = self.leak.saturating_sub(recovered);
+
+        self.repair_crew = self.repair_crew.saturating_sub(delta);
+        self.repair_crew_cooldown = self.repair_crew_cooldown.saturating_sub(delta);
+
+        damage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::status_effects::StatusEffects;
+    use crate::ticks::Ticks;
+
+    fn recover(status_effects: &mut StatusEffects) -> usize {
+        let mut secs = 0;
+        while status_effects.is_on_fire() || status_effects.is_flooding() {
+            status_effects.update(Ticks::from_whole_secs(1));
+            secs += 1;
+        }
+        secs
+    }
+
+    #[test]
+    fn fire() {
+        let mut status_effects = StatusEffects::default();
+        assert!(status_effects.update(Ticks::from_whole_secs(1)) <= 0.0);
+
+        status_effects.ignite();
+        assert!(status_effects.is_on_fire());
+        assert!(status_effects.update(Ticks::from_whole_secs(1)) > 0.0);
+
+        let slow = recover(&mut status_effects);
+        status_effects.ignite();
+        status_effects.damage_control = true;
+        assert!(recover(&mut status_effects) < slow);
+    }
+
+    #[test]
+    fn flooding() {
+        let mut status_effects = StatusEffects::default();
+        status_effects.spring_leak();
+        status_effects.update(Ticks::from_whole_secs(5));
+        let penalty = status_effects.flooding_penalty();
+        assert!(penalty < 1.0);
+
+        // Progressive.
+        status_effects.update(Ticks::from_whole_secs(5));
+        assert!(status_effects.flooding_penalty() < penalty);
+
+        let slow = recover(&mut status_effects);
+        status_effects.spring_leak();
+        status_effects.damage_control = true;
+        assert!(recover(&mut status_effects) < slow);
+        assert!(status_effects.flooding_penalty() >= 1.0);
+    }
+
+    #[test]
+    fn repair_crew() {
+        let mut status_effects = StatusEffects::default();
+        status_effects.ignite();
+        status_effects.spring_leak();
+        status_effects.update(Ticks::from_whole_secs(5));
+
+        assert!(status_effects.call_repair_crew());
+        assert!(!status_effects.is_on_fire());
+        assert!(status_effects.repair_multiplier() > 1.0);
+        recover(&mut status_effects);
+
+        // Cooldown.
+        status_effects.ignite();
+        assert!(!status_effects.call_repair_crew());
+        assert!(status_effects.is_on_fire());
+        status_effects.update(StatusEffects::REPAIR_CREW_COOLDOWN);
+        assert!(status_effects.repair_multiplier() <= 1.0);
+        status_effects.ignite();
+        assert!(status_effects.call_repair_crew());
+    }
+}
@@ -0,0 +1,110 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::EntityType;
+use serde::{Deserialize, Serialize};
+
+/// Summary of a single life, computed by the server and shown to the player once their boat is
+/// lost.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    /// Seconds between spawning and dying.
+    pub time_alive: u32,
+    /// Highest level of boat reached.
+    pub highest_level: u8,
+    /// Score earned by sinking other boats.
+    pub kill_score: u32,
+    /// Score earned by collecting loot (including coins).
+    pub loot_score: u32,
+    /// Number of coins collected.
+    pub coins_collected: u32,
+    /// Boats sunk by each weapon. [`None`] means by ramming, collision, or anti-aircraft.
+    pub kills: Vec<(Option<EntityType>, u16)>,
+    /// Score, sampled at regular intervals.
+    pub score_timeline: Vec<u32>,
+    /// Only take one sample per this many calls to [`Self::sample_score`].
+    #[serde(skip)]
+    timeline_stride: u32,
+    /// Calls to [`Self::sample_score`] since the last sample.
+    #[serde(skip)]
+    timeline_skipped: u32,
+}
+
+impl Summary {
+    /// Maximum length of [`Self::score_timeline`].
+    pub const MAX_TIMELINE: usize = 64;
+
+    /// Records a kill with a particular weapon.
+    pub fn add_kill(&mut self, weapon: Option<EntityType>, score: u32) {
+        self.kill_score = self.kill_score.saturating_add(score);
+        if let Some((_, count)) = self.kills.iter_mut().find(|(w, _)| *w == weapon) {
+            *count = count.saturating_add(1);
+        } else {
+            self.kills.push((weapon, 1));
+        }
+    }
+
+    /// Records the collection of a piece of loot.
+    pub fn add_loot(&mut self, entity_type: EntityType, score: u32) {
+        self.loot_score = self.loot_score.saturating_add(score);
+        if entity_type == EntityType::Coin {
+            self.coins_collected += 1;
+        }
+    }
+
+    /// Records the level of the player's boat, in case it is the highest so far.
+    pub fn reached_level(&mut self, level: u8) {
+        self.highest_level = self.highest_level.max(level);
+    }
+
+    /// Call at a regular interval to record score. Resolution is halved whenever the timeline
+    /// would otherwise exceed [`Self::MAX_TIMELINE`].
+    pub fn sample_score(&mut self, score: u32) {
+        self.timeline_skipped += 1;
+        if self.timeline_skipped < self.timeline_stride {
+            return;
+        }
+        self.timeline_skipped = 0;
+
+        if self.score_timeline.len() >= Self::MAX_TIMELINE {
+            let mut i = 0;
+            self.score_timeline.retain(|_| {
+                i += 1;
+                i % 2 == 1
+            });
+            self.timeline_stride = self.timeline_stride.max(1) * 2;
+        }
+        self.score_timeline.push(score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::EntityType;
+    use crate::summary::Summary;
+
+    #[test]
+    fn kills() {
+        let mut summary = Summary::default();
+        summary.add_kill(Some(EntityType::Mark18), 10);
+        summary.add_kill(None, 5);
+        summary.add_kill(Some(EntityType::Mark18), 10);
+        assert_eq!(summary.kill_score, 25);
+        assert_eq!(
+            summary.kills,
+            vec![(Some(EntityType::Mark18), 2), (None, 1)]
+        );
+    }
+
+    #[test]
+    fn score_timeline() {
+        let mut summary = Summary::default();
+        for score in 0..1000 {
+            summary.sample_score(score);
+            assert!(summary.score_timeline.len() <= Summary::MAX_TIMELINE);
+        }
+        assert_eq!(summary.score_timeline[0], 0);
+        assert!(summary.score_timeline.windows(2).all(|w| w[0] < w[1]));
+        assert!(summary.score_timeline.len() > Summary::MAX_TIMELINE / 2);
+    }
+}
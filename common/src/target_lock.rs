@@ -0,0 +1,92 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use crate::entity::EntityId;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// An enemy boat that a player designated (see
+/// [`LockTarget`][crate::protocol::LockTarget]), as tracked by their sensors. The lock is lost
+/// once their sensors can no longer identify it.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TargetLock {
+    pub id: EntityId,
+    /// Where the target is.
+    pub position: Vec2,
+    /// Velocity of the target, in meters per second.
+    pub velocity: Vec2,
+}
+
+impl TargetLock {
+    /// Bearing of the target from `origin`.
+    pub fn bearing(&self, origin: Vec2) -> Angle {
+        Angle::from(self.position - origin)
+    }
+
+    /// Meters from `origin` to the target.
+    pub fn range(&self, origin: Vec2) -> f32 {
+        self.position.distance(origin)
+    }
+
+    /// Returns where to aim a weapon launched from `origin` at `speed` meters per second, to
+    /// intercept the target if it holds its course and speed, or [`None`] if it is too fast to
+    /// intercept.
+    pub fn lead(&self, origin: Vec2, speed: f32) -> Option<Vec2> {
+        let offset = self.position - origin;
+        // Solve |offset + velocity * t| = speed * t for the soonest t > 0.
+        let a = self.velocity.length_squared() - speed.powi(2);
+        let b = 2.0 * offset.dot(self.velocity);
+        let c = offset.length_squared();
+        let t = if a.abs() < f32::EPSILON {
+            (b < 0.0).then(|| -c / b)?
+        } else {
+            let discriminant = b.powi(2) - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return None;
+            }
+            let root = discriminant.sqrt();
+            [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+                .iter()
+                .copied()
+                .filter(|&t| t > 0.0)
+                .reduce(f32::min)?
+        };
+        Some(self.position + self.velocity * t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::target_lock::TargetLock;
+    use glam::{vec2, Vec2};
+    use std::num::NonZeroU32;
+
+    fn target(position: Vec2, velocity: Vec2) -> TargetLock {
+        TargetLock {
+            id: NonZeroU32::new(1).unwrap(),
+            position,
+            velocity,
+        }
+    }
+
+    #[test]
+    fn lead() {
+        // Stationary targets need no lead.
+        let stationary = target(vec2(100.0, 0.0), Vec2::ZERO);
+        assert_eq!(stationary.lead(Vec2::ZERO, 10.0), Some(vec2(100.0, 0.0)));
+        assert_eq!(stationary.range(Vec2::ZERO), 100.0);
+
+        // Crossing targets are led, such that the weapon and target arrive together.
+        let crossing = target(vec2(100.0, 0.0), vec2(0.0, 10.0));
+        let aim = crossing.lead(Vec2::ZERO, 20.0).unwrap();
+        assert!(aim.y > 0.0);
+        let t = (aim.y - crossing.position.y) / crossing.velocity.y;
+        assert!((aim.length() - 20.0 * t).abs() < 0.01);
+
+        // Targets that are too fast, and moving away, can't be intercepted.
+        let fleeing = target(vec2(100.0, 0.0), vec2(30.0, 0.0));
+        assert_eq!(fleeing.lead(Vec2::ZERO, 20.0), None);
+        assert!(fleeing.lead(Vec2::ZERO, 40.0).is_some());
+    }
+}
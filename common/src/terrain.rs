@@ -335,6 +335,55 @@ impl TerrainMutation {
     }
 }
 
+/// Limits the rate at which something (e.g. a ship) may edit terrain, in units of
+/// [`TerrainMutation`] amount.
+#[derive(Clone, Debug)]
+pub struct TerrainEditBudget {
+    available: f32,
+    /// Amount regained per second.
+    rate: f32,
+    /// Maximum amount available at once.
+    burst: f32,
+}
+
+impl TerrainEditBudget {
+    /// Starts with a full budget.
+    pub fn new(rate: f32, burst: f32) -> Self {
+        Self {
+            available: burst,
+            rate,
+            burst,
+        }
+    }
+
+    /// Regains budget over `delta_seconds`.
+    pub fn update(&mut self, delta_seconds: f32) {
+        self.available = (self.available + self.rate * delta_seconds).min(self.burst);
+    }
+}
+
+/// Record of the terrain changed by [`Terrain::edit`], so that a rejected edit can be rolled back.
+#[must_use]
+pub struct TerrainEdit {
+    /// Previous value of each changed pixel, in order of change.
+    previous: Vec<(Coord, u8)>,
+}
+
+impl TerrainEdit {
+    /// Returns whether any terrain was actually changed.
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty()
+    }
+
+    /// Restores the terrain to how it was before the edit. Clients are notified like any other
+    /// modification.
+    pub fn undo(self, terrain: &mut Terrain) {
+        for (coord, value) in self.previous.into_iter().rev() {
+            terrain.set(coord, value);
+        }
+    }
+}
+
 pub struct TerrainCollision {
     /// Maximum altitude that a collision occurred at.
     pub max_altitude: Altitude,
@@ -644,7 +693,37 @@ impl Terrain {
 
     /// Modifies a small radius around a pos by adding or subtracting an amount of land. Returns
     /// if actually modified terrain, or None if unsuccessful.
-    pub fn modify(&mut self, mut mutation: TerrainMutation) -> Option<bool> {
+    pub fn modify(&mut self, mutation: TerrainMutation) -> Option<bool> {
+        self.modify_inner(mutation, None)
+    }
+
+    /// Applies mutations, provided `budget` allows their total amount, returning a record that
+    /// can undo them. Mutations outside the terrain are skipped.
+    pub fn edit(
+        &mut self,
+        mutations: impl IntoIterator<Item = TerrainMutation>,
+        budget: &mut TerrainEditBudget,
+    ) -> Result<TerrainEdit, &'static str> {
+        let mutations: Vec<_> = mutations.into_iter().collect();
+        let cost = mutations.iter().map(|m| m.amount.abs()).sum::<f32>();
+        if cost > budget.available {
+            return Err("terrain edit rate exceeded");
+        }
+        budget.available -= cost;
+
+        let mut previous = Vec::new();
+        for mutation in mutations {
+            self.modify_inner(mutation, Some(&mut previous));
+        }
+        Ok(TerrainEdit { previous })
+    }
+
+    /// Like [`Terrain::modify`], but optionally records the previous value of changed pixels.
+    fn modify_inner(
+        &mut self,
+        mut mutation: TerrainMutation,
+        mut previous: Option<&mut Vec<(Coord, u8)>>,
+    ) -> Option<bool> {
         let pos = mutation.position.mul(1.0 / SCALE);
 
         let c_pos = pos.ceil();
@@ -662,16 +741,24 @@ impl Terrain {
             y: usize,
             factor: f32,
             mutation: &TerrainMutation,
+            previous: &mut Option<&mut Vec<(Coord, u8)>>,
         ) -> bool {
             let coord = Coord(x, y);
-            let old = terrain.at(coord) + 0b0011;
+            let raw = terrain.at(coord);
+            let old = raw + 0b0011;
             let old_altitude = lookup_altitude(old);
             if mutation.condition.contains(&old_altitude) {
                 let to_add = (mutation.amount * factor) as i8;
                 let new = lookup_altitude(old.saturating_add_signed(to_add));
                 let clamped = new.clamp(*mutation.clamp.start(), *mutation.clamp.end());
                 //println!("old: {:?}, new: {:?}, clamped: {:?}, reverse: {}", old_altitude, new, clamped, reverse_lookup_altitude(clamped));
-                terrain.set(coord, reverse_lookup_altitude(clamped))
+                let changed = terrain.set(coord, reverse_lookup_altitude(clamped));
+                if changed {
+                    if let Some(previous) = previous {
+                        previous.push((coord, raw));
+                    }
+                }
+                changed
             } else {
                 false
             }
@@ -682,10 +769,10 @@ impl Terrain {
         // The following code (factor params) effectively doubles the amount, so correct for this.
         mutation.amount *= 0.5;
 
-        modified |= mutate(self, fx, fy, 2.0 - fract.x - fract.y, &mutation);
-        modified |= mutate(self, cx, fy, 1.0 + fract.x - fract.y, &mutation);
-        modified |= mutate(self, fx, cy, 1.0 - fract.x + fract.y, &mutation);
-        modified |= mutate(self, cx, cy, fract.x + fract.y, &mutation);
+        modified |= mutate(self, fx, fy, 2.0 - fract.x - fract.y, &mutation, &mut previous);
+        modified |= mutate(self, cx, fy, 1.0 + fract.x - fract.y, &mutation, &mut previous);
+        modified |= mutate(self, fx, cy, 1.0 - fract.x + fract.y, &mutation, &mut previous);
+        modified |= mutate(self, cx, cy, fract.x + fract.y, &mutation, &mut previous);
 
         Some(modified)
     }
@@ -837,9 +924,19 @@ impl Default for ChunkUpdate {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SerializedChunk {
     is_update: bool,
+    /// Level of detail of a whole chunk, where each sent pixel covers a square `1 << lod` pixels
+    /// wide. Always zero for updates.
+    lod: u8,
     bytes: Arc<[u8]>, // TODO: use serde_bytes.
 }
 
+impl SerializedChunk {
+    /// Returns the number of encoded bytes.
+    pub fn encoded_len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
 /// A single chunk in a Terrain.
 pub struct Chunk {
     data: [[u8; CHUNK_SIZE / 2]; CHUNK_SIZE],
@@ -848,6 +945,9 @@ pub struct Chunk {
 }
 
 impl Chunk {
+    /// Level of detail of chunks that are near, but not within, a client's view.
+    pub const COARSE_LOD: u8 = 2;
+
     /// Allocates a zero chunk.
     pub fn zero() -> Self {
         Self {
@@ -1015,6 +1115,45 @@ impl Chunk {
         chunk
     }
 
+    /// to_bytes_lod encodes a chunk at a level of detail, by averaging squares of `1 << lod`
+    /// pixels. Level zero is equivalent to to_bytes.
+    pub fn to_bytes_lod(&self, lod: u8) -> Vec<u8> {
+        if lod == 0 {
+            return self.to_bytes();
+        }
+        let step = 1 << lod;
+        let mut compressor = Compressor::new(1024 >> (2 * lod));
+        for y in (0..CHUNK_SIZE).step_by(step) {
+            for x in (0..CHUNK_SIZE).step_by(step) {
+                let sum: usize = (y..y + step)
+                    .flat_map(|y| (x..x + step).map(move |x| Coord(x, y)))
+                    .map(|coord| self.at(coord) as usize)
+                    .sum();
+                compressor.write_byte((sum / (step * step)) as u8);
+            }
+        }
+        compressor.into_vec()
+    }
+
+    /// from_bytes_lod decodes bytes encoded with to_bytes_lod into a chunk.
+    pub fn from_bytes_lod(bytes: &[u8], lod: u8) -> Self {
+        if lod == 0 {
+            return Self::from_bytes(bytes);
+        }
+        let mut chunk = Self::zero();
+        let step = 1 << lod;
+        let width = CHUNK_SIZE / step;
+        for (i, b) in Decompressor::new(bytes).enumerate() {
+            let (x, y) = ((i % width) * step, (i / width) * step);
+            for y in y..y + step {
+                for x in x..x + step {
+                    chunk.set(Coord(x, y), b);
+                }
+            }
+        }
+        chunk
+    }
+
     pub fn to_serialized_chunk(
         &self,
         should_update: bool,
@@ -1030,6 +1169,7 @@ impl Chunk {
                 ChunkUpdate::Mods(mods) => {
                     return SerializedChunk {
                         is_update: true,
+                        lod: 0,
                         bytes: Arc::clone(mods),
                     }
                 }
@@ -1040,10 +1180,20 @@ impl Chunk {
         // Send whole chunk.
         SerializedChunk {
             is_update: false,
+            lod: 0,
             bytes: self.to_bytes().into(), // TODO could save encoded chunk is lru cache but would require atomics.
         }
     }
 
+    /// Like to_serialized_chunk, but sends the whole chunk at Self::COARSE_LOD.
+    pub fn to_coarse_serialized_chunk(&self) -> SerializedChunk {
+        SerializedChunk {
+            is_update: false,
+            lod: Self::COARSE_LOD,
+            bytes: self.to_bytes_lod(Self::COARSE_LOD).into(),
+        }
+    }
+
     pub fn apply_serialized_chunk(&mut self, serialized: &SerializedChunk) {
         let bytes: &[u8] = &*serialized.bytes;
 
@@ -1062,7 +1212,7 @@ impl Chunk {
             )
         } else {
             // Overwrite chunk.
-            *self = Self::from_bytes(bytes);
+            *self = Self::from_bytes_lod(bytes, serialized.lod);
             ChunkUpdate::Complete
         }
     }
@@ -1377,6 +1527,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn edit_and_undo() {
+        let mut terrain = Terrain::new();
+        let pos = Vec2::splat(100.0);
+        let before = terrain.sample(pos);
+        let mut budget = TerrainEditBudget::new(10.0, 150.0);
+
+        let edit = terrain
+            .edit([TerrainMutation::simple(pos, 120.0)], &mut budget)
+            .unwrap();
+        assert!(!edit.is_empty());
+        assert_ne!(terrain.sample(pos), before);
+
+        assert!(terrain
+            .edit([TerrainMutation::simple(pos, 120.0)], &mut budget)
+            .is_err());
+        budget.update(10.0);
+        let second = terrain
+            .edit([TerrainMutation::simple(pos, 120.0)], &mut budget)
+            .unwrap();
+
+        second.undo(&mut terrain);
+        edit.undo(&mut terrain);
+        assert_eq!(terrain.sample(pos), before);
+    }
+
     #[test]
     fn test_lookup_altitude() {
         assert!(lookup_altitude_f32((0.5 - 0.000001) * 255.0) < Altitude::ZERO);
@@ -1396,4 +1572,28 @@ mod tests {
         let chunk2 = Chunk::from_bytes(&bytes);
         assert_eq!(chunk.data, chunk2.data);
     }
+
+    #[test]
+    fn compress_lod() {
+        let mut terrain = Terrain::with_generator(random_generator);
+        let chunk = terrain.mut_chunk(ChunkId(0, 0));
+        let coarse = chunk.to_bytes_lod(Chunk::COARSE_LOD);
+        assert!(coarse.len() < chunk.to_bytes().len());
+
+        let chunk2 = Chunk::from_bytes_lod(&coarse, Chunk::COARSE_LOD);
+        let step = 1 << Chunk::COARSE_LOD;
+        for y in (0..CHUNK_SIZE).step_by(step) {
+            for x in (0..CHUNK_SIZE).step_by(step) {
+                // Each square is uniform.
+                let value = chunk2.at(Coord(x, y));
+                assert_eq!(chunk2.at(Coord(x + step - 1, y + step - 1)), value);
+            }
+        }
+
+        // Coarsening uniform terrain is lossless.
+        let chunk = Chunk::new(ChunkId(1, 1), |_, _| 0b01010000);
+        let coarse = chunk.to_bytes_lod(Chunk::COARSE_LOD);
+        let chunk2 = Chunk::from_bytes_lod(&coarse, Chunk::COARSE_LOD);
+        assert_eq!(chunk.data, chunk2.data);
+    }
 }
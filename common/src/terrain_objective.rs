@@ -0,0 +1,172 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use crate::entity::{EntitySubKind, EntityType};
+use crate::terrain::{Terrain, SAND_LEVEL, SCALE};
+use glam::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::time::Duration;
+
+/// A contract for a terrain-editing boat to reshape the terrain near it, which pays out score once
+/// the terrain is verified to match.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TerrainObjective {
+    /// Dredge a navigable channel, across land, between two points.
+    Channel { start: Vec2, end: Vec2 },
+    /// Raise an island at a point that is under water.
+    Island { center: Vec2 },
+}
+
+impl TerrainObjective {
+    /// How far from the boat objectives are generated.
+    pub const RANGE: Range<f32> = 150.0..400.0;
+    /// Length of channels.
+    pub const CHANNEL_LENGTH: f32 = 200.0;
+    /// Radius that islands must cover.
+    pub const ISLAND_RADIUS: f32 = 20.0;
+    /// How long an objective may go unfinished before it is withdrawn.
+    pub const TIME_LIMIT: Duration = Duration::from_secs(5 * 60);
+    /// How many attempts are made to find a suitable channel before settling for an island.
+    const CHANNEL_ATTEMPTS: usize = 20;
+
+    /// Whether boats of a type can edit terrain, and therefore pursue objectives.
+    pub fn can_pursue(entity_type: EntityType) -> bool {
+        entity_type
+            .data()
+            .armaments
+            .iter()
+            .any(|a| a.entity_type.data().sub_kind == EntitySubKind::Depositor)
+    }
+
+    /// Score paid on completion.
+    pub fn reward(&self) -> u32 {
+        match self {
+            Self::Channel { .. } => 400,
+            Self::Island { .. } => 250,
+        }
+    }
+
+    /// Where to mark the objective.
+    pub fn position(&self) -> Vec2 {
+        match *self {
+            Self::Channel { start, end } => (start + end) * 0.5,
+            Self::Island { center } => center,
+        }
+    }
+
+    /// Generates an objective near `position`, within the world border, preferring a channel
+    /// across land, if there is any nearby.
+    pub fn generate(
+        terrain: &Terrain,
+        position: Vec2,
+        world_radius: f32,
+        rng: &mut impl Rng,
+    ) -> Option<Self> {
+        let is_water = |p: Vec2| {
+            p.length() < world_radius && terrain.sample(p).map_or(false, |a| a < SAND_LEVEL)
+        };
+        for _ in 0..Self::CHANNEL_ATTEMPTS {
+            let middle = Self::random_point(position, rng);
+            if terrain.sample(middle).map_or(true, |a| a < SAND_LEVEL) {
+                continue;
+            }
+            let offset = rng.gen::<Angle>().to_vec() * (Self::CHANNEL_LENGTH * 0.5);
+            let (start, end) = (middle - offset, middle + offset);
+            if is_water(start) && is_water(end) {
+                return Some(Self::Channel { start, end });
+            }
+        }
+
+        let center = Self::random_point(position, rng);
+        is_water(center).then_some(Self::Island { center })
+    }
+
+    fn random_point(position: Vec2, rng: &mut impl Rng) -> Vec2 {
+        position + rng.gen::<Angle>().to_vec() * rng.gen_range(Self::RANGE)
+    }
+
+    /// Whether the terrain has been reshaped to match the objective.
+    pub fn is_complete(&self, terrain: &Terrain) -> bool {
+        match *self {
+            Self::Channel { start, end } => {
+                let steps = (start.distance(end) / SCALE).ceil() as usize;
+                (0..=steps).all(|i| {
+                    let p = start.lerp(end, i as f32 / steps as f32);
+                    terrain.sample(p).map_or(false, |a| a < SAND_LEVEL)
+                })
+            }
+            Self::Island { center } => {
+                let r = Self::ISLAND_RADIUS;
+                [
+                    Vec2::ZERO,
+                    Vec2::new(r, 0.0),
+                    Vec2::new(-r, 0.0),
+                    Vec2::new(0.0, r),
+                    Vec2::new(0.0, -r),
+                ]
+                .iter()
+                .all(|&offset| {
+                    terrain
+                        .sample(center + offset)
+                        .map_or(false, |a| a >= SAND_LEVEL)
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::EntityType;
+    use crate::terrain::{Terrain, TerrainMutation, SCALE};
+    use crate::terrain_objective::TerrainObjective;
+    use glam::Vec2;
+    use rand::thread_rng;
+
+    #[test]
+    fn can_pursue() {
+        assert!(TerrainObjective::can_pursue(EntityType::Dredger));
+        assert!(TerrainObjective::can_pursue(EntityType::Zudredger));
+        assert!(!TerrainObjective::can_pursue(EntityType::Zumwalt));
+    }
+
+    #[test]
+    fn island() {
+        let mut terrain = Terrain::new();
+        let position = Vec2::splat(100.0);
+
+        // All water, so there is nowhere to dredge a channel.
+        let objective = TerrainObjective::generate(&terrain, position, 10000.0, &mut thread_rng())
+            .expect("water everywhere");
+        let center = if let TerrainObjective::Island { center } = objective {
+            center
+        } else {
+            panic!("expected island");
+        };
+        assert!(!objective.is_complete(&terrain));
+
+        // Each modification only adds so much.
+        for _ in 0..3 {
+            for x in -2..=2 {
+                for y in -2..=2 {
+                    let p = center + Vec2::new(x as f32, y as f32) * SCALE;
+                    terrain.modify(TerrainMutation::simple(p, 255.0));
+                }
+            }
+        }
+        assert!(objective.is_complete(&terrain));
+    }
+
+    #[test]
+    fn channel() {
+        let terrain = Terrain::new();
+        let objective = TerrainObjective::Channel {
+            start: Vec2::ZERO,
+            end: Vec2::new(TerrainObjective::CHANNEL_LENGTH, 0.0),
+        };
+        assert!(objective.is_complete(&terrain));
+    }
+}
@@ -0,0 +1,74 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Proptest strategies for generating arbitrary simulation state. Enabled by the `test-util`
+//! feature, so that the server's tests can share them.
+
+use crate::altitude::Altitude;
+use crate::angle::Angle;
+use crate::entity::{EntityKind, EntityType};
+use crate::guidance::Guidance;
+use crate::ticks::Ticks;
+use crate::transform::Transform;
+use crate::velocity::Velocity;
+use glam::Vec2;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+/// Any entity type.
+pub fn entity_type() -> impl Strategy<Value = EntityType> {
+    select(EntityType::iter().collect::<Vec<_>>())
+}
+
+/// Any entity type of the given kind.
+pub fn entity_type_of_kind(kind: EntityKind) -> impl Strategy<Value = EntityType> {
+    select(
+        EntityType::iter()
+            .filter(|t| t.data().kind == kind)
+            .collect::<Vec<_>>(),
+    )
+}
+
+pub fn angle() -> impl Strategy<Value = Angle> {
+    any::<i16>().prop_map(Angle)
+}
+
+pub fn altitude() -> impl Strategy<Value = Altitude> {
+    any::<i8>().prop_map(Altitude)
+}
+
+/// Any velocity, forward or reverse, of up to `max_mps`.
+pub fn velocity(max_mps: f32) -> impl Strategy<Value = Velocity> {
+    (-max_mps..=max_mps).prop_map(Velocity::from_mps)
+}
+
+pub fn guidance() -> impl Strategy<Value = Guidance> {
+    (angle(), velocity(1000.0)).prop_map(|(direction_target, velocity_target)| Guidance {
+        direction_target,
+        velocity_target,
+    })
+}
+
+/// Any position within `radius` of the origin.
+pub fn position(radius: f32) -> impl Strategy<Value = Vec2> {
+    (angle(), 0.0..=1.0f32).prop_map(move |(angle, fraction)| {
+        // Square root makes positions uniform over the area of the circle.
+        angle.to_vec() * fraction.sqrt() * radius
+    })
+}
+
+/// Any transform within `radius` of the origin, moving at up to `max_mps`.
+pub fn transform(radius: f32, max_mps: f32) -> impl Strategy<Value = Transform> {
+    (position(radius), angle(), velocity(max_mps)).prop_map(|(position, direction, velocity)| {
+        Transform {
+            position,
+            direction,
+            velocity,
+        }
+    })
+}
+
+/// Any amount of damage that an entity of type `entity_type` could have without dying.
+pub fn damage(entity_type: EntityType) -> impl Strategy<Value = Ticks> {
+    (0..entity_type.data().max_health().0).prop_map(Ticks::from_repr)
+}
@@ -0,0 +1,100 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use crate::entity::{EntityKind, EntitySubKind, EntityType};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// A kind of weapon that boats are warned of.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum ThreatKind {
+    /// A missile that will hit within [`ThreatWarning::MISSILE_SECONDS`], if nothing changes.
+    Missile,
+    /// A torpedo whose seeker acquired the boat.
+    Torpedo,
+}
+
+impl ThreatKind {
+    /// Returns the kind of threat that a weapon of type `entity_type` can pose, if any.
+    pub fn of(entity_type: EntityType) -> Option<Self> {
+        let data = entity_type.data();
+        if data.kind != EntityKind::Weapon {
+            return None;
+        }
+        match data.sub_kind {
+            EntitySubKind::Missile => Some(Self::Missile),
+            // Only torpedoes with seekers can acquire boats.
+            EntitySubKind::Torpedo if data.sensors.any() => Some(Self::Torpedo),
+            _ => None,
+        }
+    }
+}
+
+/// A weapon that newly threatens the player's boat. Sent once per weapon, so the client can
+/// sound an alarm without the player having to spot it.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThreatWarning {
+    pub kind: ThreatKind,
+    /// Bearing of the weapon from the boat.
+    pub bearing: Angle,
+}
+
+impl ThreatWarning {
+    /// Maximum warnings sent per update.
+    pub const MAX_PER_UPDATE: usize = 4;
+    /// Seconds before impact that inbound missiles are warned of.
+    pub const MISSILE_SECONDS: f32 = 6.0;
+
+    /// Returns the seconds until a weapon, `offset` meters from a boat and moving at
+    /// `relative_velocity` meters per second relative to it, comes within `radius` meters of it,
+    /// or [`None`] if it won't.
+    pub fn time_to_impact(offset: Vec2, relative_velocity: Vec2, radius: f32) -> Option<f32> {
+        let c = offset.length_squared() - radius.powi(2);
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        // Solve |offset + relative_velocity * t| = radius for the soonest t > 0.
+        let a = relative_velocity.length_squared();
+        let b = 2.0 * offset.dot(relative_velocity);
+        if a < f32::EPSILON || b >= 0.0 {
+            // Not closing in.
+            return None;
+        }
+        let discriminant = b.powi(2) - 4.0 * a * c;
+        (discriminant >= 0.0).then(|| (-b - discriminant.sqrt()) / (2.0 * a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::threat_warning::ThreatWarning;
+    use glam::{vec2, Vec2};
+
+    #[test]
+    fn time_to_impact() {
+        // Head on.
+        let t = ThreatWarning::time_to_impact(vec2(100.0, 0.0), vec2(-10.0, 0.0), 10.0);
+        assert!((t.unwrap() - 9.0).abs() < 0.001);
+
+        // Already hitting.
+        assert_eq!(
+            ThreatWarning::time_to_impact(vec2(5.0, 0.0), Vec2::ZERO, 10.0),
+            Some(0.0)
+        );
+
+        // Going to miss, or moving away.
+        assert_eq!(
+            ThreatWarning::time_to_impact(vec2(100.0, 50.0), vec2(-10.0, 0.0), 10.0),
+            None
+        );
+        assert_eq!(
+            ThreatWarning::time_to_impact(vec2(100.0, 0.0), vec2(10.0, 0.0), 10.0),
+            None
+        );
+        assert_eq!(
+            ThreatWarning::time_to_impact(vec2(100.0, 0.0), Vec2::ZERO, 10.0),
+            None
+        );
+    }
+}
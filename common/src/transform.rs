@@ -170,3 +170,53 @@ impl Add for Transform {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::{EntityKind, EntityType};
+    use crate::test_util;
+    use crate::ticks::Ticks;
+    use crate::transform::Transform;
+    use proptest::prelude::*;
+
+    /// Entity type, initial transform (at or below max speed), and damage.
+    fn within_speed() -> impl Strategy<Value = (EntityType, Transform, Ticks)> {
+        test_util::entity_type().prop_flat_map(|entity_type| {
+            let data = entity_type.data();
+            let damage = if data.kind == EntityKind::Boat {
+                test_util::damage(entity_type).boxed()
+            } else {
+                Just(Ticks::ZERO).boxed()
+            };
+            (
+                Just(entity_type),
+                test_util::transform(1000.0, data.speed.to_mps()),
+                damage,
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn never_exceeds_speed(
+            (entity_type, mut transform, damage) in within_speed(),
+            guidances in prop::collection::vec(test_util::guidance(), 1..20),
+            max_speed_fraction in 0.0..=1.5f32,
+            delta_seconds in 0.0..=1.0f32,
+        ) {
+            let data = entity_type.data();
+            let speed = data.speed.to_mps();
+            for guidance in guidances {
+                transform.apply_guidance(data, guidance, speed * max_speed_fraction, delta_seconds, damage);
+                transform.do_kinematics(delta_seconds);
+                prop_assert!(
+                    transform.velocity.abs() <= data.speed,
+                    "{:?} reached {:?} (max {:?})",
+                    entity_type,
+                    transform.velocity,
+                    data.speed
+                );
+            }
+        }
+    }
+}
@@ -2,12 +2,16 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::ticks::{Ticks, TicksRepr};
-use core_protocol::serde_util::{F32Visitor, I16Visitor};
+use arrayvec::ArrayVec;
+use core_protocol::serde_util::F32Visitor;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 
-type VelocityRepr = i16;
+/// Wide enough for exotic hulls and relative velocities, but see [`Velocity::serialize`].
+type VelocityRepr = i32;
 
 // Note: pub(crate) is intentional.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -32,6 +36,8 @@ impl Velocity {
     const KNOTS_SCALE: f32 = Self::SCALE * 1.94384;
     /// Max reverse velocity as a function of max forward velocity.
     pub const MAX_REVERSE_SCALE: f32 = -1.0 / 3.0;
+    /// Maximum length of the varint encoding.
+    const VARINT_MAX_LEN: usize = 5;
 
     /// new returns zero Velocity.
     pub fn new() -> Self {
@@ -172,6 +178,8 @@ impl fmt::Debug for Velocity {
 }
 
 impl Serialize for Velocity {
+    /// Binary formats get a zigzag varint, so that typical velocities take no more space than an
+    /// `i16`. It is a tuple of bytes, which bincode writes without a length prefix.
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -179,7 +187,23 @@ impl Serialize for Velocity {
         if serializer.is_human_readable() {
             serializer.serialize_f32(self.to_mps())
         } else {
-            serializer.serialize_i16(self.0)
+            let mut zigzag = ((self.0 << 1) ^ (self.0 >> (VelocityRepr::BITS - 1))) as u32;
+            let mut bytes = ArrayVec::<u8, { Self::VARINT_MAX_LEN }>::new();
+            loop {
+                let byte = (zigzag & 0x7f) as u8;
+                zigzag >>= 7;
+                if zigzag == 0 {
+                    bytes.push(byte);
+                    break;
+                }
+                bytes.push(byte | 0x80);
+            }
+
+            let mut tuple = serializer.serialize_tuple(bytes.len())?;
+            for byte in &bytes {
+                tuple.serialize_element(byte)?;
+            }
+            tuple.end()
         }
     }
 }
@@ -192,14 +216,83 @@ impl<'de> Deserialize<'de> for Velocity {
         if deserializer.is_human_readable() {
             deserializer.deserialize_f32(F32Visitor).map(Self::from_mps)
         } else {
-            deserializer.deserialize_i16(I16Visitor).map(Self)
+            deserializer.deserialize_tuple(Self::VARINT_MAX_LEN, VarintVisitor)
         }
     }
 }
 
+struct VarintVisitor;
+
+impl<'de> Visitor<'de> for VarintVisitor {
+    type Value = Velocity;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a varint velocity")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut zigzag = 0u32;
+        for i in 0..Velocity::VARINT_MAX_LEN {
+            let byte: u8 = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+            zigzag |= ((byte & 0x7f) as u32) << (i * 7);
+            if byte & 0x80 == 0 {
+                return Ok(Velocity(
+                    (zigzag >> 1) as VelocityRepr ^ -((zigzag & 1) as VelocityRepr),
+                ));
+            }
+        }
+        Err(de::Error::custom("varint velocity too long"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    // use crate::velocity::Velocity;
+    use crate::entity::EntityType;
+    use crate::velocity::Velocity;
+
+    fn round_trip(velocity: Velocity) -> usize {
+        let bytes = bincode::serialize(&velocity).unwrap();
+        assert_eq!(
+            bincode::deserialize::<Velocity>(&bytes).unwrap(),
+            velocity,
+            "{:?}",
+            velocity
+        );
+        bytes.len()
+    }
 
-    // TODO: Test velocity.
+    #[test]
+    fn varint() {
+        assert_eq!(round_trip(Velocity::ZERO), 1);
+        assert_eq!(round_trip(-Velocity::UNIT), 1);
+        assert_eq!(round_trip(Velocity::from_mps(20.0)), 2);
+        assert_eq!(round_trip(Velocity::from_mps(-20.0)), 2);
+        assert_eq!(round_trip(Velocity::MAX), Velocity::VARINT_MAX_LEN);
+        assert_eq!(round_trip(Velocity::MIN), Velocity::VARINT_MAX_LEN);
+        for i in (i16::MIN..=i16::MAX).step_by(7) {
+            assert!(round_trip(Velocity(i as i32)) <= 3);
+        }
+    }
+
+    #[test]
+    fn entity_speeds() {
+        for typ in EntityType::iter() {
+            let speed = typ.data().speed;
+            // Relative velocities of head on collisions, with some margin, must not saturate.
+            let closing = speed * 4.0;
+            assert!(closing < Velocity::MAX, "{:?}", typ);
+            assert!(-closing > Velocity::MIN, "{:?}", typ);
+            assert_eq!(closing - (-closing), closing * 2.0, "{:?}", typ);
+
+            let mps = speed.to_mps();
+            assert!(
+                (Velocity::from_mps(mps).to_mps() - mps).abs() <= Velocity::SCALE,
+                "{:?}",
+                typ
+            );
+            round_trip(speed);
+        }
+    }
 }
@@ -13,6 +13,7 @@ use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::{future_to_promise, JsFuture};
 use web_sys::{
     AudioBuffer, AudioBufferSourceNode, AudioContext, AudioContextState, Event, GainNode, Response,
+    StereoPannerNode,
 };
 
 /// A macro-generated enum representing all audio sprites.
@@ -130,17 +131,23 @@ impl<A: Audio> AudioPlayer<A> {
 
     /// Plays a particular sound once, with a specified volume.
     pub fn play_with_volume(&self, audio: A, volume: f32) {
-        Inner::play(&self.inner, audio, volume, false);
+        Inner::play(&self.inner, audio, volume, 0.0, false);
+    }
+
+    /// Plays a particular sound once, with a specified volume and stereo pan (-1 is left, 1 is
+    /// right).
+    pub fn play_with_volume_and_pan(&self, audio: A, volume: f32, pan: f32) {
+        Inner::play(&self.inner, audio, volume, pan, false);
     }
 
     /// Plays a particular sound once, with a specified volume and delay in seconds.
     pub fn play_with_volume_and_delay(&self, audio: A, volume: f32, _delay: f32) {
-        Inner::play(&self.inner, audio, volume, false);
+        Inner::play(&self.inner, audio, volume, 0.0, false);
     }
 
     /// Plays a particular sound in a loop.
     pub fn play_looping(&self, audio: A) {
-        Inner::play(&self.inner, audio, 1.0, true);
+        Inner::play(&self.inner, audio, 1.0, 0.0, true);
     }
 
     pub fn is_playing(&self, audio: A) -> bool {
@@ -217,7 +224,7 @@ impl<A: Audio> Inner<A> {
 
     /// Plays a particular sound, optionally in a loop. This is private, since looping is never
     /// determined at runtime.
-    fn play(rc: &Rc<RefCell<Option<Self>>>, audio: A, volume: f32, looping: bool) {
+    fn play(rc: &Rc<RefCell<Option<Self>>>, audio: A, volume: f32, pan: f32, looping: bool) {
         if let Some(inner) = rc.borrow_mut().as_mut() {
             if inner.recalculate_volume() == 0.0 {
                 return;
@@ -242,7 +249,17 @@ impl<A: Audio> Inner<A> {
                 gain.gain().set_value(volume);
                 let _ = source.connect_with_audio_node(&gain);
 
-                let _ = gain.connect_with_audio_node(&inner.sfx_gain);
+                // Only pan if necessary, to avoid creating an extra node.
+                let panner = (pan != 0.0)
+                    .then(|| StereoPannerNode::new(&inner.context).ok())
+                    .flatten();
+                if let Some(panner) = panner {
+                    panner.pan().set_value(pan.clamp(-1.0, 1.0));
+                    let _ = gain.connect_with_audio_node(&panner);
+                    let _ = panner.connect_with_audio_node(&inner.sfx_gain);
+                } else {
+                    let _ = gain.connect_with_audio_node(&inner.sfx_gain);
+                }
 
                 if looping {
                     source.set_loop(true);
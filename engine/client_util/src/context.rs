@@ -5,18 +5,26 @@ use crate::apply::Apply;
 use crate::browser_storage::BrowserStorages;
 use crate::frontend::Frontend;
 use crate::game_client::GameClient;
-use crate::js_util::{domain_name_of, host, invitation_id, is_https, ws_protocol};
+use crate::js_util::{
+    begin_google_login, domain_name_of, google_login_state, host, invitation_id, is_https,
+    ws_protocol,
+};
 use crate::keyboard::KeyboardState;
 use crate::mouse::MouseState;
 use crate::reconn_web_socket::ReconnWebSocket;
+use crate::screen_reader::Verbosity;
 use crate::setting::CommonSettings;
 use crate::visibility::VisibilityState;
-use core_protocol::dto::{LeaderboardDto, LiveboardDto, MessageDto, PlayerDto, ServerDto, TeamDto};
+use core_protocol::dto::{
+    AnnouncementDto, FriendDto, LeaderboardDto, LiveboardDto, MessageDto, PlayerDto, ReferralDto,
+    ServerDto, TeamDto, TeamMemberDto,
+};
 use core_protocol::id::{CohortId, InvitationId, LoginType, PeriodId, PlayerId, ServerId, TeamId};
 use core_protocol::name::PlayerAlias;
 use core_protocol::rpc::{
-    ChatUpdate, ClientRequest, ClientUpdate, InvitationUpdate, LeaderboardUpdate, LiveboardUpdate,
-    PlayerUpdate, Request, SystemUpdate, TeamUpdate, Update, WebSocketQuery,
+    AnnouncementUpdate, ChatUpdate, ClientRequest, ClientUpdate, FriendUpdate, InvitationUpdate,
+    LeaderboardUpdate, LiveboardUpdate, PlayerUpdate, Request, SystemUpdate, TeamUpdate, Update,
+    VoiceUpdate, WebSocketQuery,
 };
 use heapless::HistoryBuffer;
 use std::collections::HashMap;
@@ -69,12 +77,18 @@ pub struct ServerState<G: GameClient> {
 /// Server state specific to core functions
 #[derive(Default)]
 pub struct CoreState {
+    /// Announcements currently being shown, in the order they were scheduled.
+    pub announcements: Vec<AnnouncementDto>,
     pub cohort_id: Option<CohortId>,
     pub player_id: Option<PlayerId>,
     pub created_invitation_id: Option<InvitationId>,
+    /// Progress of the players invited by [`Self::created_invitation_id`].
+    pub referrals: ReferralDto,
+    /// Friends and their presence, in the order they were added.
+    pub friends: Box<[FriendDto]>,
     /// Ordered, i.e. first is captain.
-    pub members: Box<[PlayerId]>,
-    pub joiners: Box<[PlayerId]>,
+    pub members: Box<[TeamMemberDto]>,
+    pub joiners: Box<[TeamMemberDto]>,
     pub joins: Box<[TeamId]>,
     /// TODO: Deprecate `pub`
     pub leaderboards: [Box<[LeaderboardDto]>; std::mem::variant_count::<PeriodId>()],
@@ -84,6 +98,10 @@ pub struct CoreState {
     pub real_players: u32,
     pub teams: HashMap<TeamId, TeamDto>,
     pub servers: HashMap<ServerId, ServerDto>,
+    /// Whether the player joined their team's voice room.
+    pub voice_joined: bool,
+    /// Other members of the voice room, that the player should be connected to.
+    pub voice_members: Box<[PlayerId]>,
 }
 
 impl<G: GameClient> Default for ServerState<G> {
@@ -176,6 +194,19 @@ impl<G: GameClient> Apply<Update<G::GameUpdate>> for ServerState<G> {
         let mut core = Rc::borrow_mut(&mut self.core);
 
         match update {
+            Update::Announcement(update) => match update {
+                AnnouncementUpdate::Shown(shown) => {
+                    for announcement in shown.into_vec() {
+                        core.announcements
+                            .retain(|a| a.announcement_id != announcement.announcement_id);
+                        core.announcements.push(announcement);
+                    }
+                }
+                AnnouncementUpdate::Hidden(hidden) => {
+                    core.announcements
+                        .retain(|a| !hidden.contains(&a.announcement_id));
+                }
+            },
             Update::Chat(update) => {
                 match update {
                     ChatUpdate::Received(received) => {
@@ -197,6 +228,12 @@ impl<G: GameClient> Apply<Update<G::GameUpdate>> for ServerState<G> {
                 }
                 _ => {}
             },
+            Update::Friend(update) => match update {
+                FriendUpdate::Presence(friends) => {
+                    core.friends = friends;
+                }
+                _ => {}
+            },
             Update::Game(update) => {
                 self.game.apply(update);
             }
@@ -204,6 +241,9 @@ impl<G: GameClient> Apply<Update<G::GameUpdate>> for ServerState<G> {
                 InvitationUpdate::InvitationCreated(invitation_id) => {
                     core.created_invitation_id = Some(invitation_id);
                 }
+                InvitationUpdate::ReferralsUpdated(referrals) => {
+                    core.referrals = referrals;
+                }
             },
             Update::Leaderboard(update) => match update {
                 LeaderboardUpdate::Updated(period_id, leaderboard) => {
@@ -294,6 +334,22 @@ impl<G: GameClient> Apply<Update<G::GameUpdate>> for ServerState<G> {
                 }
                 _ => {}
             },
+            Update::Voice(update) => match update {
+                VoiceUpdate::Joined => {
+                    core.voice_joined = true;
+                }
+                VoiceUpdate::Left => {
+                    core.voice_joined = false;
+                    core.voice_members = Box::default();
+                }
+                VoiceUpdate::Updated {
+                    members: Some(members),
+                    ..
+                } => {
+                    core.voice_members = members;
+                }
+                _ => {}
+            },
         }
     }
 }
@@ -305,6 +361,8 @@ impl<G: GameClient> Context<G> {
         settings: G::GameSettings,
         frontend: Box<dyn Frontend<G::UiProps> + 'static>,
     ) -> Self {
+        // Only does anything if the page was opened to begin a Google login.
+        begin_google_login();
         let (host, server_id) = Self::compute_websocket_host(&common_settings, None, &*frontend);
         let socket = ReconnWebSocket::new(host, common_settings.protocol, None);
         common_settings.set_server_id(server_id, &mut browser_storages);
@@ -342,16 +400,39 @@ impl<G: GameClient> Context<G> {
         // crate::console_log!("override={:?} ideal server={:?}, host={:?}, ideal_host={:?}", override_server_id, ideal_server_id, host, ideal_host);
 
         let query = js_hooks::window().location().search().ok();
-        let params = query.and_then(|query| UrlSearchParams::new_with_str(&query).ok());
-        let oauth2_code = params.and_then(|params| params.get("code"));
+        let params = query
+            .as_ref()
+            .and_then(|query| UrlSearchParams::new_with_str(query).ok());
+        let login = params.and_then(|params| {
+            if let Some(code) = params.get("code") {
+                // Google logins are distinguished by their state, which must be the one this tab
+                // began the login with.
+                let login_type = match params
+                    .get("state")
+                    .and_then(|state| google_login_state(&state))
+                {
+                    Some(true) => LoginType::Google,
+                    Some(false) => return None,
+                    None => LoginType::Discord,
+                };
+                Some((login_type, code))
+            } else if params.has("openid.claimed_id") {
+                // The server verifies the whole Steam OpenID assertion.
+                query.map(|query| (LoginType::Steam, query))
+            } else {
+                None
+            }
+        });
+        let login_type = login.as_ref().map(|&(login_type, _)| login_type);
+        let login_id = login.map(|(_, login_id)| login_id);
 
         let web_socket_query = WebSocketQuery {
             protocol: Some(common_settings.protocol),
             arena_id: common_settings.arena_id,
             session_id: common_settings.session_id,
             invitation_id: invitation_id(),
-            login_type: oauth2_code.is_some().then_some(LoginType::Discord),
-            login_id: oauth2_code,
+            login_type,
+            login_id,
             referrer: frontend.get_real_referrer(),
         };
 
@@ -392,6 +473,14 @@ impl<G: GameClient> Context<G> {
     pub fn set_ui_props(&mut self, props: G::UiProps) {
         self.frontend.set_ui_props(props);
     }
+
+    /// Announce text to assistive technology, such as screen readers, if the player's
+    /// [`Verbosity`] setting includes announcements of this level.
+    pub fn announce(&self, text: impl Into<String>, level: Verbosity) {
+        if self.common_settings.screen_reader.announces(level) {
+            self.frontend.announce(text.into());
+        }
+    }
 }
 
 #[derive(Clone)]
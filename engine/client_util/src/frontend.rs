@@ -8,6 +8,8 @@ use core_protocol::name::Referrer;
 pub trait Frontend<P> {
     /// Set the props used to render the UI.
     fn set_ui_props(&self, props: P);
+    /// Announce text to assistive technology, such as screen readers.
+    fn announce(&self, _text: String) {}
     /// Gets the referer.
     fn get_real_referrer(&self) -> Option<Referrer> {
         referrer()
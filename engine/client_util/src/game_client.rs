@@ -8,7 +8,7 @@ use crate::mouse::MouseEvent;
 use crate::setting::Settings;
 use crate::visibility::VisibilityEvent;
 use core_protocol::id::GameId;
-use core_protocol::rpc::ClientUpdate;
+use core_protocol::rpc::{ClientUpdate, VoiceUpdate};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
@@ -52,6 +52,12 @@ pub trait GameClient: Sized + 'static {
         let _ = event;
     }
 
+    /// Peek at a voice update before it is applied to `CoreState`, such as to pass relayed
+    /// signals to WebRTC peer connections.
+    fn peek_voice(&mut self, inbound: &VoiceUpdate, _context: &mut Context<Self>) {
+        let _ = inbound;
+    }
+
     /// Peek at a visibility event before it is applied to `VisibilityState`.
     fn peek_visibility(&mut self, event: &VisibilityEvent, _context: &mut Context<Self>) {
         let _ = event;
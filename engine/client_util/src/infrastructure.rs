@@ -9,22 +9,29 @@ use crate::frontend::Frontend;
 use crate::game_client::GameClient;
 use crate::keyboard::{Key, KeyboardEvent as GameClientKeyboardEvent};
 use crate::mouse::{MouseButton, MouseEvent as GameClientMouseEvent};
+use crate::quality::QualityGovernor;
+use crate::rate_limiter::RateLimiter;
 use crate::reconn_web_socket::ReconnWebSocket;
-use crate::setting::CommonSettings;
+use crate::screen_reader::Verbosity;
+use crate::setting::{CommonSettings, NewsCache, Settings};
+use crate::telemetry::Telemetry;
 use crate::visibility::VisibilityEvent;
 use common_util::range::map_ranges;
-use core_protocol::id::{PlayerId, ServerId, TeamId};
+use core_protocol::dto::ClientFailure;
+use core_protocol::id::{ArenaId, PlayerId, ServerId, TeamId};
 use core_protocol::name::TeamName;
 use core_protocol::rpc::{
-    AdType, ChatRequest, ClientRequest, ClientUpdate, InvitationRequest, PlayerRequest, Request,
-    TeamRequest, Update,
+    AdType, ChatRequest, ChatUpdate, ClientRequest, ClientUpdate, FriendRequest, FriendUpdate,
+    InvitationRequest, PlayerRequest, Request, TeamRequest, Update,
 };
 use core_protocol::web_socket::WebSocketProtocol;
 use glam::{IVec2, Vec2};
 use js_sys::Function;
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{
-    Event, FocusEvent, HtmlInputElement, KeyboardEvent, MouseEvent, Touch, TouchEvent, WheelEvent,
+    Event, FocusEvent, HtmlAnchorElement, HtmlButtonElement, HtmlElement, HtmlInputElement,
+    HtmlSelectElement, HtmlTextAreaElement, KeyboardEvent, MouseEvent, Touch, TouchEvent,
+    WheelEvent,
 };
 
 pub struct Infrastructure<G: GameClient> {
@@ -37,6 +44,12 @@ pub struct Infrastructure<G: GameClient> {
     /// screen in a gesture, used to emulate right click.
     right_touch_id: Option<i32>,
     statistic_fps_monitor: FpsMonitor,
+    /// Synced settings as of the last time they were saved to (or loaded from) the player's
+    /// account, or [`None`] if the player has no account.
+    saved_settings: Option<Vec<(String, String)>>,
+    save_settings_rate_limiter: RateLimiter,
+    quality_governor: QualityGovernor,
+    telemetry: Telemetry,
 }
 
 impl<G: GameClient> Infrastructure<G> {
@@ -49,6 +62,8 @@ impl<G: GameClient> Infrastructure<G> {
         // Don't try to catch panics if aborting (because it's useless).
         #[cfg(panic = "unwind")]
         std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+        // Remember panics, so they may be reported after the page is reloaded.
+        Telemetry::set_panic_hook();
 
         #[cfg(feature = "joined")]
         crate::joined::init();
@@ -62,6 +77,10 @@ impl<G: GameClient> Infrastructure<G> {
                 left_touch_id: None,
                 right_touch_id: None,
                 statistic_fps_monitor: FpsMonitor::new(60.0),
+                saved_settings: None,
+                save_settings_rate_limiter: RateLimiter::new(10.0),
+                quality_governor: QualityGovernor::default(),
+                telemetry: Telemetry::default(),
             }),
             Err(e) => Err((
                 e,
@@ -78,7 +97,8 @@ impl<G: GameClient> Infrastructure<G> {
             .audio
             .set_volume_setting(self.context.common_settings.volume);
 
-        let elapsed_seconds = (time_seconds - self.context.client.time_seconds).clamp(0.001, 0.5);
+        let raw_elapsed_seconds = time_seconds - self.context.client.time_seconds;
+        let elapsed_seconds = raw_elapsed_seconds.clamp(0.001, 0.5);
         self.context.client.time_seconds = time_seconds;
 
         for inbound in self
@@ -98,6 +118,17 @@ impl<G: GameClient> Infrastructure<G> {
                     self.context
                         .send_to_server(Request::Invitation(InvitationRequest::CreateInvitation));
 
+                    // Report a panic from before the page was reloaded.
+                    if let Some(message) = Telemetry::take_panic(&mut self.context.browser_storages)
+                    {
+                        self.report_failure(ClientFailure::Panic, Some(message));
+                    }
+
+                    // Server doesn't remember friends between sessions, so remind it.
+                    let friends = self.context.common_settings.friends.0.clone();
+                    self.context
+                        .send_to_server(Request::Friend(FriendRequest::Set(friends.into())));
+
                     let (host, server_id) = Context::<G>::compute_websocket_host(
                         &self.context.common_settings,
                         server_id,
@@ -123,6 +154,61 @@ impl<G: GameClient> Infrastructure<G> {
                             .set_session_id(Some(session_id), &mut self.context.browser_storages);
                     }
                 }
+                &Update::Friend(FriendUpdate::Added(alias)) => {
+                    let mut friends = self.context.common_settings.friends();
+                    friends.0.push(alias);
+                    self.context
+                        .common_settings
+                        .set_friends(friends, &mut self.context.browser_storages);
+                }
+                &Update::Friend(FriendUpdate::Removed(alias)) => {
+                    let mut friends = self.context.common_settings.friends();
+                    friends.0.retain(|&a| a != alias);
+                    self.context
+                        .common_settings
+                        .set_friends(friends, &mut self.context.browser_storages);
+                }
+                Update::Client(ClientUpdate::SettingsLoaded(settings)) => {
+                    // Only apply the first time, so as not to revert changes made since.
+                    if self.saved_settings.is_none() && !settings.is_empty() {
+                        let keys = Self::synced_keys().collect::<Vec<_>>();
+                        for (key, value) in settings.iter() {
+                            if keys.contains(&key.as_str()) {
+                                let _ = self.context.browser_storages.local.set(key, Some(value));
+                            }
+                        }
+                        self.context.common_settings = CommonSettings::load(
+                            &self.context.browser_storages,
+                            CommonSettings::default(),
+                        );
+                        self.context.settings = G::GameSettings::load(
+                            &self.context.browser_storages,
+                            G::GameSettings::default(),
+                        );
+                    }
+                    // Any settings that differ will be saved.
+                    self.saved_settings = Some(settings.to_vec());
+                }
+                Update::Chat(ChatUpdate::Received(received)) => {
+                    for message in received.iter() {
+                        // Don't repeat the player's own messages back to them.
+                        if message.player_id.is_some()
+                            && message.player_id == self.context.state.core.player_id
+                        {
+                            continue;
+                        }
+                        self.context.announce(
+                            format!("{}: {}", message.alias, message.text),
+                            Verbosity::Verbose,
+                        );
+                    }
+                }
+                Update::Client(ClientUpdate::NewsUpdated(news)) => {
+                    self.context.common_settings.set_news(
+                        NewsCache(news.to_vec()),
+                        &mut self.context.browser_storages,
+                    );
+                }
                 Update::Client(ClientUpdate::EvalSnippet(snippet)) => {
                     // Do NOT use `eval`, since it runs in the local scope and therefore
                     // prevents minification.
@@ -135,20 +221,100 @@ impl<G: GameClient> Infrastructure<G> {
             if let Update::Game(update) = &inbound {
                 self.game.peek_game(update, &mut self.context);
             }
+            if let Update::Voice(update) = &inbound {
+                self.game.peek_voice(update, &mut self.context);
+            }
             self.context.state.apply(inbound);
         }
 
         self.game.tick(elapsed_seconds, &mut self.context);
 
+        if self
+            .telemetry
+            .frame(raw_elapsed_seconds, self.context.visibility.is_visible())
+        {
+            self.report_failure(
+                ClientFailure::SlowFrame,
+                Some(format!("{:.2}s", raw_elapsed_seconds)),
+            );
+        }
+
+        if self.context.common_settings.auto_graphics_quality {
+            if let Some(quality) = self.quality_governor.update(
+                elapsed_seconds,
+                self.context.visibility.is_visible(),
+                self.context.common_settings.graphics_quality,
+            ) {
+                self.context
+                    .common_settings
+                    .set_graphics_quality(quality, &mut self.context.browser_storages);
+                self.report_failure(
+                    ClientFailure::GraphicsQualityLowered,
+                    Some(quality.to_string()),
+                );
+            }
+        }
+
         if let Some(fps) = self.statistic_fps_monitor.update(elapsed_seconds) {
             self.context
                 .send_to_server(Request::Client(ClientRequest::TallyFps(fps)));
         }
+
+        if self
+            .save_settings_rate_limiter
+            .update_ready(elapsed_seconds)
+        {
+            self.save_settings();
+        }
+    }
+
+    /// Reports a client failure to the server, for telemetry.
+    fn report_failure(&mut self, failure: ClientFailure, message: Option<String>) {
+        self.context
+            .send_to_server(Request::Client(ClientRequest::ReportFailure {
+                failure,
+                message,
+            }));
+    }
+
+    /// Call when the browser loses the WebGL context.
+    pub fn webgl_context_lost(&mut self) {
+        self.report_failure(ClientFailure::WebGlContextLost, None);
+    }
+
+    /// Local storage keys of settings that are synced with the player's account.
+    fn synced_keys() -> impl Iterator<Item = &'static str> {
+        CommonSettings::synced_keys()
+            .iter()
+            .chain(G::GameSettings::synced_keys())
+            .copied()
+    }
+
+    /// Saves synced settings to the player's account, if they changed.
+    fn save_settings(&mut self) {
+        if let Some(saved) = self.saved_settings.as_mut() {
+            let local = &self.context.browser_storages.local;
+            let mut settings: Vec<(String, String)> = Self::synced_keys()
+                .filter_map(|key| Some((key.to_owned(), local.get::<String>(key)?)))
+                .collect();
+            settings.sort_unstable();
+
+            if settings != *saved {
+                self.context
+                    .send_to_server(Request::Client(ClientRequest::SaveSettings(
+                        settings.clone().into_boxed_slice(),
+                    )));
+                *saved = settings;
+            }
+        }
     }
 
     pub fn keyboard(&mut self, event: KeyboardEvent) {
         if let Some(target) = event.target() {
-            if target.is_instance_of::<HtmlInputElement>() {
+            if target.is_instance_of::<HtmlInputElement>()
+                || target.is_instance_of::<HtmlSelectElement>()
+                || target.is_instance_of::<HtmlTextAreaElement>()
+            {
                 return;
             }
         }
@@ -168,6 +334,35 @@ impl<G: GameClient> Infrastructure<G> {
                         time: self.context.client.time_seconds,
                     };
 
+                    // A focused control, such as a button, is navigated and activated by keyboard,
+                    // instead of the game receiving those keys.
+                    if let Some(control) = Self::focused_control(&event) {
+                        if matches!(key, Key::Enter | Key::Escape | Key::Space | Key::Tab) {
+                            if down {
+                                match key {
+                                    // Return the keyboard to the game.
+                                    Key::Escape => {
+                                        event.prevent_default();
+                                        let _ = control.blur();
+                                    }
+                                    // Buttons and links are activated natively.
+                                    Key::Enter | Key::Space
+                                        if !control.is_instance_of::<HtmlButtonElement>()
+                                            && !control.is_instance_of::<HtmlAnchorElement>() =>
+                                    {
+                                        event.prevent_default();
+                                        control.click();
+                                    }
+                                    _ => {}
+                                }
+                            } else {
+                                // Don't leave the key stuck down, if it was pressed before focusing.
+                                self.context.keyboard.apply(e);
+                            }
+                            return;
+                        }
+                    }
+
                     if down {
                         // Simulate zooming.
                         match key {
@@ -193,6 +388,18 @@ impl<G: GameClient> Infrastructure<G> {
         }
     }
 
+    /// The target of a keyboard event, if it is a focusable control (as opposed to the page) that
+    /// was focused by keyboard. Controls that were clicked keep focus, but the game's keys (e.g.
+    /// space to fire) should keep working.
+    fn focused_control(event: &KeyboardEvent) -> Option<HtmlElement> {
+        event
+            .target()?
+            .dyn_into::<HtmlElement>()
+            .ok()
+            .filter(|element| element.tab_index() >= 0)
+            .filter(|element| element.matches(":focus-visible").unwrap_or(false))
+    }
+
     pub fn keyboard_focus(&mut self, event: FocusEvent) {
         if event.type_() == "blur" {
             self.context.keyboard.reset();
@@ -374,6 +581,8 @@ impl<G: GameClient> Infrastructure<G> {
         // Written with the intention that errors bias towards visible=true.
         let visible = js_hooks::document().visibility_state() != web_sys::VisibilityState::Hidden;
         let e = VisibilityEvent::Visible(visible);
+        self.telemetry.visibility_change();
+        self.quality_governor.visibility_change();
         self.game.peek_visibility(&e, &mut self.context);
         #[cfg(feature = "audio")]
         self.context.audio.peek_visibility(&e);
@@ -528,11 +737,33 @@ impl<G: GameClient> Infrastructure<G> {
             .send_to_server(Request::Client(ClientRequest::TallyAd(ad_type)));
     }
 
+    /// Call when the splash screen was shown.
+    pub fn tally_splash(&mut self) {
+        self.context
+            .send_to_server(Request::Client(ClientRequest::TallySplash));
+    }
+
     /// Connects to a different server.
     pub fn choose_server_id(&mut self, server_id: Option<ServerId>) {
         if server_id == self.context.common_settings.server_id {
             return;
         }
+        self.reconnect(server_id);
+    }
+
+    /// Connects to a specific arena of a (possibly different) server.
+    pub fn choose_arena(&mut self, server_id: Option<ServerId>, arena_id: ArenaId) {
+        let settings = &mut self.context.common_settings;
+        if server_id == settings.server_id && Some(arena_id) == settings.arena_id {
+            return;
+        }
+        // The session belongs to the old arena.
+        settings.set_arena_id(Some(arena_id), &mut self.context.browser_storages);
+        settings.set_session_id(None, &mut self.context.browser_storages);
+        self.reconnect(server_id);
+    }
+
+    fn reconnect(&mut self, server_id: Option<ServerId>) {
         // Clear state from old server.
         self.context.state = ServerState::default();
 
@@ -4,8 +4,11 @@
 use core_protocol::id::InvitationId;
 use core_protocol::name::Referrer;
 use js_hooks::{document, window};
+use js_sys::{Object, Reflect};
 use std::num::NonZeroU32;
 use std::str::FromStr;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::HtmlElement;
 
 /// Gets the domain name component of a host string e.g. mk48.io
 pub fn domain_name_of(host: &str) -> String {
@@ -25,18 +28,61 @@ pub fn host() -> String {
     window().location().host().unwrap()
 }
 
-/// Reads the `InvitationId` present in the path, if any.
-/// Path should resemble /invite/INVITE_CODE_HERE
-pub fn invitation_id() -> Option<InvitationId> {
-    window()
-        .location()
-        .hash()
+/// Session storage key of the random state that a pending Google login must return with.
+const GOOGLE_LOGIN_STATE: &str = "googleLoginState";
+
+/// If the page was opened to begin a Google login (the server redirects to `#/login/google`),
+/// remembers a random state in session storage and navigates to the server's login route with it.
+/// Returns whether it navigated away.
+pub fn begin_google_login() -> bool {
+    if window().location().hash().ok().as_deref() != Some("#/login/google") {
+        return false;
+    }
+    let mut bytes = [0u8; 16];
+    let state = window()
+        .crypto()
+        .ok()
+        .and_then(|crypto| crypto.get_random_values_with_u8_array(&mut bytes).ok())
+        .map(|_| {
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        });
+    let storage = window().session_storage().ok().flatten();
+    if let Some((state, storage)) = state.zip(storage) {
+        if storage.set_item(GOOGLE_LOGIN_STATE, &state).is_ok() {
+            return window()
+                .location()
+                .set_href(&format!("/oauth2/google?state={}", state))
+                .is_ok();
+        }
+    }
+    false
+}
+
+/// Whether an OAuth2 `state` is that of a Google login, and returns whether this tab began it (see
+/// [`begin_google_login`]).
+pub fn google_login_state(state: &str) -> Option<bool> {
+    let state = state.strip_prefix("google.")?;
+    let expected: Option<String> = window()
+        .session_storage()
         .ok()
-        .filter(|h| h.contains("/invite/"))
-        .and_then(|h| {
-            h.split('/')
-                .last()
-                .and_then(|n| NonZeroU32::from_str(n).ok())
+        .flatten()
+        .and_then(|storage| storage.get_item(GOOGLE_LOGIN_STATE).ok().flatten());
+    Some(expected.as_deref() == Some(state))
+}
+
+/// Reads the `InvitationId` present in the path (or legacy hash), if any.
+/// Path should resemble /invite/INVITE_CODE_HERE/
+pub fn invitation_id() -> Option<InvitationId> {
+    let location = window().location();
+    [location.pathname(), location.hash()]
+        .into_iter()
+        .filter_map(Result::ok)
+        .find_map(|p| {
+            let (_, code) = p.split_once("/invite/")?;
+            NonZeroU32::from_str(code.trim_end_matches('/')).ok()
         })
         .map(InvitationId)
 }
@@ -58,6 +104,34 @@ pub fn is_mobile() -> bool {
         .unwrap_or(false)
 }
 
+/// Tells whatever is embedding the game, such as a desktop wrapper that shows Discord rich presence,
+/// what the player is doing (or [`None`] if they aren't playing).
+pub fn set_rich_presence(details: Option<&str>) {
+    let message = Object::new();
+    let _ = Reflect::set(&message, &"type".into(), &"richPresence".into());
+    let _ = Reflect::set(
+        &message,
+        &"details".into(),
+        &details.map(JsValue::from).unwrap_or(JsValue::NULL),
+    );
+    // The parent of a top-level window is itself.
+    if let Ok(Some(parent)) = window().parent() {
+        let _ = parent.post_message(&message, "*");
+    }
+}
+
+/// Moves keyboard focus to the first element matching a CSS selector, returning whether there was
+/// one.
+pub fn focus(selector: &str) -> bool {
+    document()
+        .query_selector(selector)
+        .ok()
+        .flatten()
+        .and_then(|element| element.dyn_into::<HtmlElement>().ok())
+        .map(|element| element.focus().is_ok())
+        .unwrap_or(false)
+}
+
 /// Gets the string, ws or wss, for the websocket protocol to use.
 /// This is a problematic API because it does not respect redirect schemes.
 pub fn is_https() -> bool {
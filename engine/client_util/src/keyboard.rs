@@ -3,10 +3,11 @@
 
 use crate::apply::Apply;
 use std::num::NonZeroU8;
-use strum_macros::Display;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
 
 /// Each variant is a possible key. Not guaranteed to support all keys.
-#[derive(Copy, Clone, Eq, PartialEq, Display)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Display, EnumIter, EnumString)]
 pub enum Key {
     A,
     B,
@@ -51,6 +52,7 @@ pub enum Key {
     Ctrl,
     Down,
     Enter,
+    Escape,
     Home,
     Left,
     PageDown,
@@ -65,6 +67,11 @@ pub enum Key {
 }
 
 impl Key {
+    /// Iterates every key, e.g. to offer a choice of key bindings.
+    pub fn iter() -> impl Iterator<Item = Self> {
+        <Self as IntoEnumIterator>::iter()
+    }
+
     /// Converts from a Javascript keycode.
     pub fn try_from_key_code(key_code: u32) -> Option<Self> {
         Some(match key_code {
@@ -74,6 +81,7 @@ impl Key {
             16 => Self::Shift,
             17 => Self::Ctrl,
             18 => Self::Alt,
+            27 => Self::Escape,
             32 => Self::Space,
             33 => Self::PageUp,
             34 => Self::PageDown,
@@ -233,3 +241,17 @@ pub struct KeyboardEvent {
     pub shift: bool,
     pub time: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::keyboard::Key;
+    use std::str::FromStr;
+
+    #[test]
+    fn from_str() {
+        for key in Key::iter() {
+            assert_eq!(Key::from_str(&key.to_string()), Ok(key));
+        }
+        assert!(Key::from_str("NotAKey").is_err());
+    }
+}
@@ -26,8 +26,11 @@ pub mod joystick;
 pub mod js_util;
 pub mod keyboard;
 pub mod mouse;
+pub mod quality;
 pub mod rate_limiter;
 pub mod reconn_web_socket;
+pub mod screen_reader;
 pub mod setting;
+pub mod telemetry;
 pub mod visibility;
 pub mod web_socket;
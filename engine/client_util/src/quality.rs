@@ -0,0 +1,190 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::js_util::is_mobile;
+use std::fmt::{self, Display, Formatter};
+use std::num::NonZeroU8;
+use std::str::FromStr;
+
+/// Graphics quality level, which determines the quality knobs exposed to the renderer.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum GraphicsQuality {
+    Low,
+    Medium,
+    High,
+}
+
+impl Default for GraphicsQuality {
+    fn default() -> Self {
+        if is_mobile() {
+            Self::Medium
+        } else {
+            Self::High
+        }
+    }
+}
+
+impl GraphicsQuality {
+    pub const ALL: [Self; 3] = [Self::High, Self::Medium, Self::Low];
+
+    /// The next lower quality level, if any.
+    pub fn lower(self) -> Option<Self> {
+        match self {
+            Self::Low => None,
+            Self::Medium => Some(Self::Low),
+            Self::High => Some(Self::Medium),
+        }
+    }
+
+    /// Canvas resolution = window dimension / resolution divisor.
+    pub fn resolution_divisor(self) -> NonZeroU8 {
+        NonZeroU8::new(match self {
+            Self::Low => 2,
+            Self::Medium | Self::High => 1,
+        })
+        .unwrap()
+    }
+
+    /// Fraction (0 to 1) of particles to draw, e.g. smoke and sparks.
+    pub fn particle_density(self) -> f32 {
+        match self {
+            Self::Low => 0.25,
+            Self::Medium => 0.5,
+            Self::High => 1.0,
+        }
+    }
+
+    /// Fraction (0 to 1) of wake particles to draw.
+    pub fn wake_density(self) -> f32 {
+        match self {
+            Self::Low => 0.35,
+            Self::Medium => 0.7,
+            Self::High => 1.0,
+        }
+    }
+}
+
+impl Display for GraphicsQuality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        })
+    }
+}
+
+impl FromStr for GraphicsQuality {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "low" => Self::Low,
+            "medium" => Self::Medium,
+            "high" => Self::High,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Monitors frame time, and decides when to lower the [`GraphicsQuality`] (never raises it, to
+/// avoid oscillating).
+pub struct QualityGovernor {
+    /// Seconds of frames measured since the last decision.
+    elapsed: f32,
+    frames: u32,
+    /// Whether the next frame should not be measured, because it may have been delayed for a
+    /// legitimate reason (e.g. first frame, or page was hidden).
+    skip_frame: bool,
+}
+
+impl Default for QualityGovernor {
+    fn default() -> Self {
+        Self {
+            elapsed: 0.0,
+            frames: 0,
+            skip_frame: true,
+        }
+    }
+}
+
+impl QualityGovernor {
+    /// Seconds of frames to measure before deciding.
+    const PERIOD: f32 = 5.0;
+    /// Lower quality if frames per second are below this.
+    const MIN_FPS: f32 = 30.0;
+
+    /// Call when page visibility changes.
+    pub fn visibility_change(&mut self) {
+        self.skip_frame = true;
+    }
+
+    /// Call every frame. Returns the quality to switch to, if it should be lowered.
+    pub fn update(
+        &mut self,
+        elapsed_seconds: f32,
+        visible: bool,
+        quality: GraphicsQuality,
+    ) -> Option<GraphicsQuality> {
+        if std::mem::take(&mut self.skip_frame) || !visible {
+            return None;
+        }
+        self.elapsed += elapsed_seconds;
+        self.frames += 1;
+        if self.elapsed < Self::PERIOD {
+            return None;
+        }
+
+        let fps = self.frames as f32 / self.elapsed;
+        self.elapsed = 0.0;
+        self.frames = 0;
+        if fps < Self::MIN_FPS {
+            // Changing quality may cause a slow frame.
+            self.skip_frame = true;
+            quality.lower()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::quality::{GraphicsQuality, QualityGovernor};
+    use std::str::FromStr;
+
+    #[test]
+    fn from_str() {
+        for quality in GraphicsQuality::ALL {
+            assert_eq!(GraphicsQuality::from_str(&quality.to_string()), Ok(quality));
+        }
+    }
+
+    #[test]
+    fn governor() {
+        let mut governor = QualityGovernor::default();
+        let mut quality = GraphicsQuality::High;
+
+        // Fast frames.
+        for _ in 0..1000 {
+            assert_eq!(governor.update(1.0 / 60.0, true, quality), None);
+        }
+
+        // Slow frames, but hidden.
+        for _ in 0..1000 {
+            assert_eq!(governor.update(0.1, false, quality), None);
+        }
+
+        // Slow frames.
+        let mut lowered = 0;
+        for _ in 0..1000 {
+            if let Some(lower) = governor.update(0.1, true, quality) {
+                assert!(lower < quality);
+                quality = lower;
+                lowered += 1;
+            }
+        }
+        assert_eq!(lowered, 2);
+        assert_eq!(quality, GraphicsQuality::Low);
+    }
+}
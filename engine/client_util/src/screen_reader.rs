@@ -0,0 +1,74 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// How much is announced to assistive technology, such as screen readers. Also the level of each
+/// announcement, which is only made if the setting is at least as verbose.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Verbosity {
+    Off,
+    /// Only announce what requires the player's attention, e.g. incoming weapons.
+    #[default]
+    Essential,
+    /// Also announce everything else, e.g. chat messages.
+    Verbose,
+}
+
+impl Verbosity {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Essential, Self::Verbose];
+
+    /// Whether an announcement of a given level should be made, if this is the setting.
+    pub fn announces(self, level: Self) -> bool {
+        level != Self::Off && level <= self
+    }
+}
+
+impl Display for Verbosity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Off => "off",
+            Self::Essential => "essential",
+            Self::Verbose => "verbose",
+        })
+    }
+}
+
+impl FromStr for Verbosity {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "off" => Self::Off,
+            "essential" => Self::Essential,
+            "verbose" => Self::Verbose,
+            _ => return Err(()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::screen_reader::Verbosity;
+    use std::str::FromStr;
+
+    #[test]
+    fn from_str() {
+        for verbosity in Verbosity::ALL {
+            assert_eq!(Verbosity::from_str(&verbosity.to_string()), Ok(verbosity));
+        }
+    }
+
+    #[test]
+    fn announces() {
+        for level in Verbosity::ALL {
+            assert!(!Verbosity::Off.announces(level));
+            assert!(!level.announces(Verbosity::Off));
+        }
+        assert!(Verbosity::Essential.announces(Verbosity::Essential));
+        assert!(!Verbosity::Essential.announces(Verbosity::Verbose));
+        assert!(Verbosity::Verbose.announces(Verbosity::Essential));
+        assert!(Verbosity::Verbose.announces(Verbosity::Verbose));
+    }
+}
@@ -3,20 +3,33 @@
 
 use crate::browser_storage::BrowserStorages;
 use crate::js_util::is_mobile;
+use crate::quality::GraphicsQuality;
+use crate::screen_reader::Verbosity;
+use core_protocol::dto::NewsItemDto;
 use core_protocol::id::{ArenaId, CohortId, LanguageId, ServerId, SessionId};
 use core_protocol::name::PlayerAlias;
 use core_protocol::web_socket::WebSocketProtocol;
 pub use engine_macros::Settings;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 /// Settings backed by local storage.
 pub trait Settings: Sized {
     /// Loads all settings from local storage.
     fn load(l: &BrowserStorages, default: Self) -> Self;
+
+    /// Local storage keys of settings that follow the player across devices, i.e. all except
+    /// those that are `volatile`, `no_store`, or `no_sync`.
+    fn synced_keys() -> &'static [&'static str];
 }
 
 // Useful if you don't want settings.
 impl Settings for () {
     fn load(_: &BrowserStorages, _: Self) -> Self {}
+
+    fn synced_keys() -> &'static [&'static str] {
+        &[]
+    }
 }
 
 /// Settings of the infrastructure, common to all games.
@@ -31,20 +44,27 @@ pub struct CommonSettings {
     #[setting(range = "0.0..1.0", finite)]
     pub volume: f32,
     /// Last [`CohortId`].
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub cohort_id: Option<CohortId>,
     /// Last-used/chosen [`ServerId`].
     #[setting(optional, volatile)]
     pub server_id: Option<ServerId>,
     /// Not manually set by the player.
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub arena_id: Option<ArenaId>,
     /// Not manually set by the player. Not accessible via arbitrary getter/setter as doing so would
     /// pull BigUint64Array into the JS shim, breaking compatibility with old devices.
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub session_id: Option<SessionId>,
-    /// Whether to set antialias rendering option.
+    /// Whether to set antialias rendering option (depends on the device).
+    #[setting(no_sync)]
     pub antialias: bool,
+    /// Graphics quality (depends on the device).
+    #[setting(no_sync)]
+    pub graphics_quality: GraphicsQuality,
+    /// Whether to lower [`Self::graphics_quality`] automatically, if frames are slow.
+    #[setting(no_sync)]
+    pub auto_graphics_quality: bool,
     /// Websocket protocol.
     #[setting(volatile)]
     pub protocol: WebSocketProtocol,
@@ -53,6 +73,8 @@ pub struct CommonSettings {
     pub chat_message: String,
     /// Whether to add a contrasting border behind UI elements.
     pub high_contrast: bool,
+    /// How much to announce to screen readers.
+    pub screen_reader: Verbosity,
     /// Whether team menu is open.
     #[setting(volatile)]
     pub team_dialog_shown: bool,
@@ -61,6 +83,12 @@ pub struct CommonSettings {
     /// Whether leaderboard menu is open.
     #[setting(volatile)]
     pub leaderboard_dialog_shown: bool,
+    /// Not manually set by the player, but rather mirrors the server's view of friends.
+    #[setting(no_sync)]
+    pub friends: FriendAliases,
+    /// Last news feed received from the server, so it can be shown before connecting.
+    #[setting(no_sync)]
+    pub news: NewsCache,
 }
 
 impl Default for CommonSettings {
@@ -74,12 +102,17 @@ impl Default for CommonSettings {
             arena_id: None,
             session_id: None,
             antialias: !is_mobile(),
+            graphics_quality: GraphicsQuality::default(),
+            auto_graphics_quality: true,
             protocol: WebSocketProtocol::default(),
             chat_message: String::new(),
             high_contrast: false,
+            screen_reader: Verbosity::default(),
             team_dialog_shown: true,
             chat_dialog_shown: true,
             leaderboard_dialog_shown: true,
+            friends: FriendAliases::default(),
+            news: NewsCache::default(),
         }
     }
 }
@@ -90,3 +123,43 @@ impl CommonSettings {
         self.arena_id.zip(self.session_id)
     }
 }
+
+/// Aliases of friends, stored as base64 encoded bincode, since aliases may contain commas.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FriendAliases(pub Vec<PlayerAlias>);
+
+impl Display for FriendAliases {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let bytes = bincode::serialize(&self.0).map_err(|_| std::fmt::Error)?;
+        f.write_str(&base64::encode(bytes))
+    }
+}
+
+impl FromStr for FriendAliases {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = base64::decode(s).map_err(|_| ())?;
+        bincode::deserialize(&bytes).map(Self).map_err(|_| ())
+    }
+}
+
+/// News feed items, stored as base64 encoded bincode.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NewsCache(pub Vec<NewsItemDto>);
+
+impl Display for NewsCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let bytes = bincode::serialize(&self.0).map_err(|_| std::fmt::Error)?;
+        f.write_str(&base64::encode(bytes))
+    }
+}
+
+impl FromStr for NewsCache {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = base64::decode(s).map_err(|_| ())?;
+        bincode::deserialize(&bytes).map(Self).map_err(|_| ())
+    }
+}
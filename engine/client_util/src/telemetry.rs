@@ -0,0 +1,80 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::browser_storage::BrowserStorages;
+
+/// Detects client failures, to be reported to the server (see
+/// [`core_protocol::rpc::ClientRequest::ReportFailure`]).
+pub struct Telemetry {
+    /// Number of slow frames reported, to avoid flooding the server.
+    slow_frames: u8,
+    /// Whether the next frame should not be checked, because it may have been delayed for a
+    /// legitimate reason (e.g. first frame, or page was hidden).
+    skip_frame: bool,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self {
+            slow_frames: 0,
+            skip_frame: true,
+        }
+    }
+}
+
+impl Telemetry {
+    /// Local storage key of the message of a panic, to be reported after the page is reloaded.
+    const PANIC_KEY: &'static str = "panic";
+    /// Maximum length of a reported panic message, in bytes.
+    const MAX_PANIC_BYTES: usize = 1024;
+    /// Frames that take at least this long are considered slow.
+    const SLOW_FRAME_SECONDS: f32 = 1.0;
+    /// Maximum number of slow frames to report, per page load.
+    const MAX_SLOW_FRAMES: u8 = 3;
+
+    /// Saves the message of any panic to local storage, after calling the previous hook. Has no
+    /// effect if panics abort immediately.
+    pub fn set_panic_hook() {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+            let mut message = info.to_string();
+            if message.len() > Self::MAX_PANIC_BYTES {
+                let mut end = Self::MAX_PANIC_BYTES;
+                while !message.is_char_boundary(end) {
+                    end -= 1;
+                }
+                message.truncate(end);
+            }
+            let _ = BrowserStorages::new()
+                .local
+                .set(Self::PANIC_KEY, Some(message));
+        }));
+    }
+
+    /// Takes the message of a panic that happened before the page was reloaded, if any.
+    pub fn take_panic(browser_storages: &mut BrowserStorages) -> Option<String> {
+        let message = browser_storages.local.get::<String>(Self::PANIC_KEY)?;
+        let _ = browser_storages.local.set::<String>(Self::PANIC_KEY, None);
+        Some(message)
+    }
+
+    /// Call when page visibility changes.
+    pub fn visibility_change(&mut self) {
+        self.skip_frame = true;
+    }
+
+    /// Call every frame with the unclamped elapsed time. Returns true if the frame was
+    /// abnormally slow, and should be reported.
+    pub fn frame(&mut self, elapsed_seconds: f32, visible: bool) -> bool {
+        if std::mem::take(&mut self.skip_frame) || !visible {
+            return false;
+        }
+        if elapsed_seconds >= Self::SLOW_FRAME_SECONDS && self.slow_frames < Self::MAX_SLOW_FRAMES {
+            self.slow_frames += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -8,12 +8,61 @@ use crate::UnixTime;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
+/// The Announcement Data Transfer Object (DTO) is a system message, shown to every client in an
+/// arena as a banner until it expires or is dismissed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AnnouncementDto {
+    pub announcement_id: AnnouncementId,
+    pub message: String,
+}
+
+/// What a [`NewsItemDto`] is about, which determines how it is displayed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum NewsKind {
+    /// An upcoming or ongoing event, highlighted as a banner.
+    Event,
+    /// Anything else, such as a maintenance notice.
+    Notice,
+    /// Changes made by an update.
+    PatchNotes,
+}
+
+/// The News Data Transfer Object (DTO) is one item of the news feed on the spawn screen.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NewsItemDto {
+    pub kind: NewsKind,
+    pub title: String,
+    pub body: String,
+    /// Where to read more, if anywhere. Always `https`.
+    pub link: Option<String>,
+    pub date: UnixTime,
+}
+
+/// The Friend Data Transfer Object (DTO) describes a friend and their online presence.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FriendDto {
+    pub alias: PlayerAlias,
+    /// [`None`] if the friend is offline (or online on a different server).
+    pub presence: Option<PresenceDto>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct InvitationDto {
     /// Who sent it.
     pub player_id: PlayerId,
 }
 
+/// How many players followed a player's invitation link, and how far they got.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReferralDto {
+    /// Visits via the invitation link.
+    pub clicks: u32,
+    /// Invited players that spawned.
+    pub spawns: u32,
+    /// Invited players that progressed far enough for the inviter to be rewarded.
+    pub rewards: u32,
+}
+
 /// The Leaderboard Data Transfer Object (DTO) is a single line on a leaderboard.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct LeaderboardDto {
@@ -109,6 +158,30 @@ pub struct MessageDto {
     /// Whether message is directed to team only.
     pub whisper: bool,
 }
+/// The Match Data Transfer Object (DTO) is the result of a match, such as a round of a ranked or
+/// tournament arena, as archived.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MatchDto {
+    pub match_id: MatchId,
+    pub arena_id: ArenaId,
+    pub server_id: Option<ServerId>,
+    pub game_mode: String,
+    pub date_started: UnixTime,
+    pub date_ended: UnixTime,
+    /// Highest score first.
+    pub participants: Vec<MatchParticipantDto>,
+}
+
+/// A player that took part in a match, and how they did.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MatchParticipantDto {
+    pub alias: PlayerAlias,
+    /// [`None`] if the player wasn't logged in, so the match won't appear in their history.
+    pub user_id: Option<UserId>,
+    pub score: u32,
+    pub kills: u32,
+}
+
 /// The Player Data Transfer Object (DTO) binds player ID to player data.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PlayerDto {
@@ -119,6 +192,16 @@ pub struct PlayerDto {
     pub team_id: Option<TeamId>,
 }
 
+/// The Presence Data Transfer Object (DTO) describes where an online player is playing.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PresenceDto {
+    pub arena_id: ArenaId,
+    pub server_id: Option<ServerId>,
+    /// Invitation that may be accepted in order to join the player's arena. Only shared by
+    /// friends who befriended the player in return.
+    pub invitation_id: Option<InvitationId>,
+}
+
 /// The Server Data Transfer Object (DTO) binds server ID to server data.
 /// It is assumed to be reachable, healthy, having an ip mapped to server_id via DNS, and having
 /// a compatible client version.
@@ -127,6 +210,23 @@ pub struct ServerDto {
     pub server_id: ServerId,
     pub region_id: RegionId,
     pub player_count: u32,
+    /// Game mode the server advertises, if it isn't the default.
+    pub game_mode: Option<String>,
+    /// Average round-trip time of probes to the server, in milliseconds, as measured by the
+    /// server that sent this (not the client).
+    pub ping: Option<u16>,
+    /// Arenas hosted by the server, if more than the default one.
+    pub arenas: Vec<ArenaDto>,
+}
+
+/// One of possibly several arenas hosted by a server.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ArenaDto {
+    pub arena_id: ArenaId,
+    /// Number of (real) players.
+    pub player_count: u32,
+    /// Game mode of the arena, if it isn't the default.
+    pub game_mode: Option<String>,
 }
 
 impl PartialOrd for ServerDto {
@@ -175,6 +275,14 @@ pub struct TeamDto {
     pub closed: bool,
 }
 
+/// The Team Member Data Transfer Object (DTO) describes a member of, or joiner to, the
+/// recipient's team, so that the team overlay need not cross-reference [`PlayerDto`]s.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TeamMemberDto {
+    pub player_id: PlayerId,
+    pub alias: PlayerAlias,
+}
+
 /// Filter daily metrics.
 // TODO: Not a DTO?
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
@@ -185,6 +293,58 @@ pub enum MetricFilter {
     UserAgentId(UserAgentId),
 }
 
+/// Actions that are rate limited at every [`RateLimitScope`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum RateLimitedAction {
+    Chat,
+    CreateInvitation,
+    /// A game command that fires a weapon.
+    Fire,
+    /// A game command that spawns the player.
+    Spawn,
+    /// A client failure report.
+    Telemetry,
+}
+
+impl RateLimitedAction {
+    pub const ALL: [Self; 5] = [
+        Self::Chat,
+        Self::CreateInvitation,
+        Self::Fire,
+        Self::Spawn,
+        Self::Telemetry,
+    ];
+}
+
+/// Kinds of client failures, reported for telemetry.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum ClientFailure {
+    /// Frames were slow enough that the graphics quality was lowered automatically (the new
+    /// level is reported as the message).
+    GraphicsQualityLowered,
+    /// The client panicked (reported after the page is reloaded).
+    Panic,
+    /// A frame took abnormally long, while the page was visible.
+    SlowFrame,
+    /// The browser lost the WebGL context (e.g. due to a GPU driver reset).
+    WebGlContextLost,
+}
+
+/// What a rate limit applies to. An action must be within the limits of every scope.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum RateLimitScope {
+    /// Each client separately.
+    Client,
+    /// All clients from each IP address together.
+    Ip,
+    /// All clients together.
+    Global,
+}
+
+impl RateLimitScope {
+    pub const ALL: [Self; 3] = [Self::Client, Self::Ip, Self::Global];
+}
+
 #[cfg(feature = "admin")]
 pub use admin::*;
 #[cfg(feature = "admin")]
@@ -217,6 +377,52 @@ mod admin {
         pub restriction: usize,
     }
 
+    /// Rules that aliases must follow, in addition to the built-in censor.
+    #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct AliasPolicyDto {
+        /// Aliases that match any of these patterns are rejected. Matching ignores case,
+        /// punctuation, and look-alike characters, and `*` matches anything, e.g. `*badword*`.
+        pub deny_patterns: Vec<String>,
+        /// Only moderators may use aliases that contain any of these, e.g. the game's name or a
+        /// clan tag. The aliases of online moderators are always reserved.
+        pub reserved_aliases: Vec<String>,
+    }
+
+    /// An announcement, and when and where it is shown.
+    #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+    pub struct AdminAnnouncementDto {
+        pub announcement_id: AnnouncementId,
+        pub message: String,
+        /// [`None`] means all arenas.
+        pub arena_ids: Option<Box<[ArenaId]>>,
+        pub start: crate::UnixTime,
+        pub stop: crate::UnixTime,
+    }
+
+    /// A score withheld from the persisted leaderboards, because it looked illegitimate, until an
+    /// admin reviews it.
+    #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+    pub struct QuarantinedScoreDto {
+        pub alias: PlayerAlias,
+        pub player_id: PlayerId,
+        /// Highest score since quarantined.
+        pub score: u32,
+        /// Why the score was quarantined, e.g. "score rate".
+        pub reason: String,
+        pub date_created: crate::UnixTime,
+    }
+
+    /// One scope of the rate limit of one action.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct RateLimitDto {
+        pub action: RateLimitedAction,
+        pub scope: RateLimitScope,
+        /// Average milliseconds between actions, or zero for no limit.
+        pub period_millis: u32,
+        /// Number of actions that may be performed in a burst, in addition to the average rate.
+        pub burst: u32,
+    }
+
     /// Like [`ServerDto`] but more details.
     #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
     pub struct AdminServerDto {
@@ -255,14 +461,20 @@ mod admin {
         pub bandwidth_tx: <ContinuousExtremaMetric as Metric>::Summary,
         pub banner_ads: <DiscreteMetric as Metric>::Summary,
         pub bounce: <RatioMetric as Metric>::Summary,
+        pub client_panics: <DiscreteMetric as Metric>::Summary,
         pub concurrent: <ContinuousExtremaMetric as Metric>::Summary,
         pub connections: <ContinuousExtremaMetric as Metric>::Summary,
         pub cpu: <ContinuousExtremaMetric as Metric>::Summary,
         pub cpu_steal: <ContinuousExtremaMetric as Metric>::Summary,
         pub flop: <RatioMetric as Metric>::Summary,
         pub fps: <ContinuousExtremaMetric as Metric>::Summary,
+        pub graphics_quality_lowered: <DiscreteMetric as Metric>::Summary,
+        pub funnel: <FunnelMetric as Metric>::Summary,
         pub invited: <RatioMetric as Metric>::Summary,
+        pub invitation_clicks: <DiscreteMetric as Metric>::Summary,
+        pub invitation_spawns: <DiscreteMetric as Metric>::Summary,
         pub invitations_cached: <DiscreteMetric as Metric>::Summary,
+        pub invitations_created: <DiscreteMetric as Metric>::Summary,
         pub low_fps: <RatioMetric as Metric>::Summary,
         pub minutes_per_play: <ContinuousExtremaMetric as Metric>::Summary,
         pub minutes_per_visit: <ContinuousExtremaMetric as Metric>::Summary,
@@ -273,6 +485,7 @@ mod admin {
         pub plays_per_visit: <ContinuousExtremaMetric as Metric>::Summary,
         pub plays_total: <DiscreteMetric as Metric>::Summary,
         pub ram: <ContinuousExtremaMetric as Metric>::Summary,
+        pub referral_rewards: <DiscreteMetric as Metric>::Summary,
         pub renews: <DiscreteMetric as Metric>::Summary,
         pub retention_days: <ContinuousExtremaMetric as Metric>::Summary,
         pub retention_histogram: <HistogramMetric as Metric>::Summary,
@@ -280,6 +493,7 @@ mod admin {
         pub rtt: <ContinuousExtremaMetric as Metric>::Summary,
         pub score: <ContinuousExtremaMetric as Metric>::Summary,
         pub sessions_cached: <DiscreteMetric as Metric>::Summary,
+        pub slow_frames: <DiscreteMetric as Metric>::Summary,
         pub spt: <ContinuousExtremaMetric as Metric>::Summary,
         pub teamed: <RatioMetric as Metric>::Summary,
         pub toxicity: <RatioMetric as Metric>::Summary,
@@ -287,6 +501,7 @@ mod admin {
         pub uptime: <ContinuousExtremaMetric as Metric>::Summary,
         pub video_ads: <DiscreteMetric as Metric>::Summary,
         pub visits: <DiscreteMetric as Metric>::Summary,
+        pub webgl_context_losses: <DiscreteMetric as Metric>::Summary,
     }
 
     #[derive(Clone, Copy, Debug, Serialize)]
@@ -297,14 +512,19 @@ mod admin {
         pub bandwidth_tx: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub banner_ads: <DiscreteMetric as Metric>::DataPoint,
         pub bounce: <RatioMetric as Metric>::DataPoint,
+        pub client_panics: <DiscreteMetric as Metric>::DataPoint,
         pub concurrent: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub connections: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub cpu: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub cpu_steal: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub flop: <RatioMetric as Metric>::DataPoint,
         pub fps: <ContinuousExtremaMetric as Metric>::DataPoint,
+        pub graphics_quality_lowered: <DiscreteMetric as Metric>::DataPoint,
         pub invited: <RatioMetric as Metric>::DataPoint,
+        pub invitation_clicks: <DiscreteMetric as Metric>::DataPoint,
+        pub invitation_spawns: <DiscreteMetric as Metric>::DataPoint,
         pub invitations_cached: <DiscreteMetric as Metric>::DataPoint,
+        pub invitations_created: <DiscreteMetric as Metric>::DataPoint,
         pub low_fps: <RatioMetric as Metric>::DataPoint,
         pub minutes_per_play: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub minutes_per_visit: <ContinuousExtremaMetric as Metric>::DataPoint,
@@ -315,12 +535,14 @@ mod admin {
         pub plays_per_visit: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub plays_total: <DiscreteMetric as Metric>::DataPoint,
         pub ram: <ContinuousExtremaMetric as Metric>::DataPoint,
+        pub referral_rewards: <DiscreteMetric as Metric>::DataPoint,
         pub renews: <DiscreteMetric as Metric>::DataPoint,
         pub retention_days: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub rewarded_ads: <DiscreteMetric as Metric>::DataPoint,
         pub rtt: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub score: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub sessions_cached: <DiscreteMetric as Metric>::DataPoint,
+        pub slow_frames: <DiscreteMetric as Metric>::DataPoint,
         pub spt: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub teamed: <RatioMetric as Metric>::DataPoint,
         pub toxicity: <RatioMetric as Metric>::DataPoint,
@@ -328,5 +550,6 @@ mod admin {
         pub uptime: <ContinuousExtremaMetric as Metric>::DataPoint,
         pub video_ads: <DiscreteMetric as Metric>::DataPoint,
         pub visits: <DiscreteMetric as Metric>::DataPoint,
+        pub webgl_context_losses: <DiscreteMetric as Metric>::DataPoint,
     }
 }
@@ -108,6 +108,11 @@ impl GameId {
     }
 }
 
+/// Identifies an announcement scheduled by an operator.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct AnnouncementId(pub NonZeroU32);
+impl_wrapper_from_str!(AnnouncementId, NonZeroU32);
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct InvitationId(pub NonZeroU32);
 
@@ -134,6 +139,11 @@ impl InvitationId {
 
 impl_wrapper_from_str!(InvitationId, NonZeroU32);
 
+/// Identifies a match in the archive (see [`MatchDto`][crate::dto::MatchDto]).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct MatchId(pub NonZeroU64);
+impl_wrapper_from_str!(MatchId, NonZeroU64);
+
 // The LanguageId enum may be extended with additional languages, such as:
 // Bengali,
 // Hindi,
@@ -210,6 +220,7 @@ impl PeriodId {
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct PlayerId(pub NonZeroU32);
+impl_wrapper_from_str!(PlayerId, NonZeroU32);
 
 impl PlayerId {
     pub const DAY_BITS: u32 = 10;
@@ -407,6 +418,10 @@ pub struct UserId(pub NonZeroU64);
 pub enum LoginType {
     /// Discord OAuth2.
     Discord,
+    /// Google OAuth2.
+    Google,
+    /// Steam OpenID.
+    Steam,
 }
 
 #[cfg(test)]
@@ -515,3 +515,114 @@ impl Add for HistogramMetric {
         }
     }
 }
+
+/// Stages of the funnel that visits progress through, in order.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FunnelStage {
+    /// Connected to the server.
+    Load,
+    /// Shown the splash screen.
+    Splash,
+    /// Started playing.
+    Spawn,
+    /// Sank another player for the first time.
+    FirstKill,
+    /// Stopped playing for the first time.
+    FirstDeath,
+    /// Started playing again.
+    Respawn,
+}
+
+impl FunnelStage {
+    pub const ALL: [Self; FUNNEL_STAGE_COUNT] = [
+        Self::Load,
+        Self::Splash,
+        Self::Spawn,
+        Self::FirstKill,
+        Self::FirstDeath,
+        Self::Respawn,
+    ];
+}
+
+const FUNNEL_STAGE_COUNT: usize = 6;
+const FUNNEL_BUCKET_COUNT: usize = 12;
+
+/// A metric tracking how many visits reached each [`FunnelStage`], and how many seconds after
+/// loading they did.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunnelMetric {
+    /// Visits that reached each stage.
+    #[serde(rename = "c")]
+    counts: [u32; FUNNEL_STAGE_COUNT],
+    /// How many visits reached each stage within 0-1, 1-3, 3-7, ... seconds (doubling each time)
+    /// of loading? The last bucket also holds all samples beyond it.
+    #[serde(rename = "b")]
+    buckets: [[u32; FUNNEL_BUCKET_COUNT]; FUNNEL_STAGE_COUNT],
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct FunnelStageSummary {
+    stage: FunnelStage,
+    total: u32,
+    /// Percent of loads that reached this stage.
+    percent: f32,
+    /// What percent of samples reached this stage within 0-1, 1-3, 3-7, ... seconds of loading?
+    buckets: [f32; FUNNEL_BUCKET_COUNT],
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct FunnelMetricSummary {
+    stages: [FunnelStageSummary; FUNNEL_STAGE_COUNT],
+}
+
+impl FunnelMetric {
+    pub fn push(&mut self, stage: FunnelStage, seconds: f32) {
+        let stage = stage as usize;
+        let bucket = ((seconds.max(0.0) + 1.0).log2() as usize).min(FUNNEL_BUCKET_COUNT - 1);
+        self.counts[stage] = self.counts[stage].saturating_add(1);
+        self.buckets[stage][bucket] = self.buckets[stage][bucket].saturating_add(1);
+    }
+}
+
+impl Metric for FunnelMetric {
+    type Summary = FunnelMetricSummary;
+    type DataPoint = ();
+
+    fn summarize(&self) -> Self::Summary {
+        let loads = self.counts[FunnelStage::Load as usize];
+        let stages = FunnelStage::ALL.map(|stage| {
+            let total = self.counts[stage as usize];
+            let to_percent = |count: u32| count as f32 * 100.0 / total.max(1) as f32;
+            FunnelStageSummary {
+                stage,
+                total,
+                percent: total as f32 * 100.0 / loads.max(1) as f32,
+                buckets: self.buckets[stage as usize].map(to_percent),
+            }
+        });
+        FunnelMetricSummary { stages }
+    }
+
+    fn data_point(&self) -> Self::DataPoint {
+        ()
+    }
+}
+
+impl Add for FunnelMetric {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        for (a, b) in self.counts.iter_mut().zip(rhs.counts) {
+            *a = a.saturating_add(b);
+        }
+        for (a, b) in self
+            .buckets
+            .iter_mut()
+            .flatten()
+            .zip(rhs.buckets.iter().flatten())
+        {
+            *a = a.saturating_add(*b);
+        }
+        self
+    }
+}
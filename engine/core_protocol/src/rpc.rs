@@ -90,6 +90,14 @@ pub struct StatusResponse {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub player_count: Option<u32>,
+    /// Game mode, if not the default.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_mode: Option<String>,
+    /// Every arena, if there are several.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub arenas: Vec<ArenaDto>,
     /// Dying servers, in need of DNS replacement, according to this server.
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -129,10 +137,12 @@ pub struct WebSocketQuery {
 pub enum Request<GR> {
     Chat(ChatRequest),
     Client(ClientRequest),
+    Friend(FriendRequest),
     Game(GR),
     Invitation(InvitationRequest),
     Player(PlayerRequest),
     Team(TeamRequest),
+    Voice(VoiceRequest),
 }
 
 #[cfg(feature = "server")]
@@ -148,8 +158,10 @@ where
 #[cfg_attr(feature = "server", derive(actix::Message))]
 #[cfg_attr(feature = "server", rtype(result = "()"))]
 pub enum Update<GU> {
+    Announcement(AnnouncementUpdate),
     Chat(ChatUpdate),
     Client(ClientUpdate),
+    Friend(FriendUpdate),
     Game(GU),
     Invitation(InvitationUpdate),
     Leaderboard(LeaderboardUpdate),
@@ -157,6 +169,16 @@ pub enum Update<GU> {
     Player(PlayerUpdate),
     System(SystemUpdate),
     Team(TeamUpdate),
+    Voice(VoiceUpdate),
+}
+
+/// Announcement related update from server to client.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum AnnouncementUpdate {
+    /// Announcements that started being shown in the client's arena.
+    Shown(Owned<[AnnouncementDto]>),
+    /// Announcements that expired or were cancelled.
+    Hidden(Owned<[AnnouncementId]>),
 }
 
 /// Team related requests from the client to the server.
@@ -178,14 +200,14 @@ pub enum TeamUpdate {
     AddedOrUpdated(Owned<[TeamDto]>),
     Created(TeamId, TeamName),
     /// A complete enumeration of joiners, for the team captain only.
-    Joiners(Box<[PlayerId]>),
+    Joiners(Box<[TeamMemberDto]>),
     Joining(TeamId),
     /// The following is for the joiner only, to indicate which teams they are joining.
     Joins(Box<[TeamId]>),
     Kicked(PlayerId),
     Left,
     /// A complete enumeration of team members, in order (first is captain).
-    Members(Owned<[PlayerId]>),
+    Members(Owned<[TeamMemberDto]>),
     Promoted(PlayerId),
     Rejected(PlayerId),
     Removed(Owned<[TeamId]>),
@@ -208,6 +230,9 @@ pub enum ChatRequest {
     SetSafeMode(u32),
     /// Chat will be in slow mode for this many more minutes. For moderators only.
     SetSlowMode(u32),
+    /// Deliver this player's messages only to themselves, for this many minutes (zero lifts it).
+    /// For moderators only.
+    ShadowBanPlayer { player_id: PlayerId, minutes: u32 },
     /// Resume seeing this player's messages.
     Unmute(PlayerId),
 }
@@ -217,6 +242,7 @@ pub enum ChatRequest {
 pub enum ChatUpdate {
     Muted(PlayerId),
     PlayerRestricted { player_id: PlayerId, minutes: u32 },
+    PlayerShadowBanned { player_id: PlayerId, minutes: u32 },
     Received(Box<[Dedup<MessageDto>]>),
     SafeModeSet(u32),
     SlowModeSet(u32),
@@ -268,15 +294,110 @@ pub enum InvitationRequest {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum InvitationUpdate {
     InvitationCreated(InvitationId),
+    /// Progress of the players invited by the client's invitation, sent whenever it changes.
+    ReferralsUpdated(ReferralDto),
+}
+
+/// Friend related request from client to server. Friends are identified by alias, since, unlike
+/// [`PlayerId`]s, aliases stay the same between sessions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum FriendRequest {
+    /// Add an online player by their id.
+    Add(PlayerId),
+    /// Add an online player by their alias.
+    AddAlias(PlayerAlias),
+    Remove(PlayerAlias),
+    /// Restore a previously persisted friends list, such as after reconnecting.
+    Set(Box<[PlayerAlias]>),
+}
+
+/// Friend related update from server to client.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum FriendUpdate {
+    Added(PlayerAlias),
+    /// A complete enumeration of friends and their presence.
+    Presence(Box<[FriendDto]>),
+    Removed(PlayerAlias),
+    Set,
+}
+
+/// Voice chat related request from client to server. The server only relays WebRTC signaling
+/// between members of the same team's voice room; media is peer-to-peer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum VoiceRequest {
+    /// Join the voice room of the player's team.
+    Join,
+    Leave,
+    /// Stop exchanging signals with this player.
+    Mute(PlayerId),
+    /// For team captains only. Allows a previously revoked team member to speak again.
+    Permit(PlayerId),
+    /// For team captains only. Removes a team member from the voice room until permitted again.
+    Revoke(PlayerId),
+    /// Relay a signal to another member of the voice room.
+    Signal {
+        player_id: PlayerId,
+        signal: VoiceSignal,
+    },
+    /// Resume exchanging signals with this player.
+    Unmute(PlayerId),
+}
+
+/// WebRTC signaling, relayed verbatim by the server.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum VoiceSignal {
+    /// Session description protocol (SDP) offer.
+    Offer(String),
+    /// Session description protocol (SDP) answer.
+    Answer(String),
+    /// Interactive connectivity establishment (ICE) candidate.
+    IceCandidate(String),
+}
+
+impl VoiceSignal {
+    pub fn payload(&self) -> &str {
+        match self {
+            Self::Offer(s) | Self::Answer(s) | Self::IceCandidate(s) => s,
+        }
+    }
+}
+
+/// Voice chat related update from server to client.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum VoiceUpdate {
+    Joined,
+    Left,
+    Muted(PlayerId),
+    Permitted(PlayerId),
+    Revoked(PlayerId),
+    Signaled,
+    Unmuted(PlayerId),
+    Updated {
+        /// A complete enumeration of other members of the voice room that the client should be
+        /// connected to, or [`None`] if unchanged.
+        members: Option<Box<[PlayerId]>>,
+        /// Signals relayed from other members, oldest first.
+        signals: Box<[(PlayerId, VoiceSignal)]>,
+    },
 }
 
 /// General request from client to server.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ClientRequest {
+    /// Report a client failure, for telemetry. Rate limited.
+    ReportFailure {
+        failure: ClientFailure,
+        /// Details, such as a panic message.
+        message: Option<String>,
+    },
+    /// Replace the settings stored with the player's account, by storage key.
+    SaveSettings(Box<[(String, String)]>),
     SetAlias(PlayerAlias),
     /// An advertisement was shown or played.
     TallyAd(AdType),
     TallyFps(f32),
+    /// The splash screen was shown.
+    TallySplash,
     Trace {
         message: String,
     },
@@ -295,7 +416,10 @@ pub enum ClientUpdate {
     AdTallied,
     AliasSet(PlayerAlias),
     EvalSnippet(Owned<str>),
+    FailureReported,
     FpsTallied,
+    /// The news feed, newest first. Sent on connection, and whenever it changes.
+    NewsUpdated(Owned<[NewsItemDto]>),
     SessionCreated {
         arena_id: ArenaId,
         cohort_id: CohortId,
@@ -303,6 +427,11 @@ pub enum ClientUpdate {
         session_id: SessionId,
         player_id: PlayerId,
     },
+    /// Settings stored with the player's account, by storage key (sorted). Only sent if the
+    /// player has an account, and empty if nothing was stored yet.
+    SettingsLoaded(Box<[(String, String)]>),
+    SettingsSaved,
+    SplashTallied,
     Traced,
 }
 
@@ -322,6 +451,7 @@ mod admin {
     /// Admin requests are from the admin interface to the core service.
     #[derive(Clone, Debug, Deserialize, Serialize)]
     pub enum AdminRequest {
+        CancelAnnouncement(AnnouncementId),
         ClearSnippet {
             cohort_id: Option<CohortId>,
             referrer: Option<Referrer>,
@@ -340,14 +470,26 @@ mod admin {
             player_id: PlayerId,
             moderator: bool,
         },
+        RequestAliasPolicy,
         RequestAllowWebSocketJson,
+        RequestAnnouncements,
+        RequestChecksum,
         RequestDay {
             filter: Option<MetricFilter>,
         },
         RequestDistributeLoad,
         RequestGames,
+        /// Where gameplay events happened in each arena, as game-specific JSON.
+        RequestHeatmaps,
+        /// Most recent archived matches that a logged-in player took part in, newest first.
+        RequestMatches {
+            user_id: UserId,
+        },
+        RequestNews,
         RequestPlayers,
         RequestProfile,
+        RequestQuarantinedScores,
+        RequestRateLimits,
         RequestRedirect,
         RequestReferrers,
         RequestRegions,
@@ -371,42 +513,89 @@ mod admin {
             player_id: PlayerId,
             minutes: usize,
         },
+        /// Approving lets the quarantined score onto the leaderboards, and exempts the player from
+        /// anomaly detection until the server restarts. Otherwise, the score is discarded.
+        ReviewQuarantinedScore {
+            player_id: PlayerId,
+            approve: bool,
+        },
+        /// Show a banner to every client in the given arenas, or all arenas if [`None`], from
+        /// `start` (or now, if [`None`]) for the given number of minutes.
+        ScheduleAnnouncement {
+            message: String,
+            arena_ids: Option<Box<[ArenaId]>>,
+            start: Option<crate::UnixTime>,
+            minutes: usize,
+        },
         SendChat {
             // If None, goes to all players.
             player_id: Option<PlayerId>,
             alias: PlayerAlias,
             message: String,
         },
+        /// Applies to aliases chosen from now on. Persisted in the admin config file.
+        SetAliasPolicy(AliasPolicyDto),
         SetAllowWebSocketJson(bool),
         SetDistributeLoad(bool),
         SetGameClient(minicdn::EmbeddedMiniCdn),
+        /// Replaces the news feed shown on the spawn screen. Persisted in the admin config file.
+        SetNews(Box<[NewsItemDto]>),
+        /// Takes effect immediately, but is not persisted.
+        SetRateLimit(RateLimitDto),
         SetRedirect(Option<ServerId>),
         SetSnippet {
             cohort_id: Option<CohortId>,
             referrer: Option<Referrer>,
             snippet: Owned<str>,
         },
+        /// Deliver a player's chat only to themselves for the given number of minutes (zero lifts
+        /// it), even if they reconnect.
+        ShadowBanPlayer {
+            player_id: PlayerId,
+            minutes: usize,
+        },
+        /// Record the player's inputs, and game events involving them, in the trace log.
+        TracePlayer {
+            player_id: PlayerId,
+            traced: bool,
+        },
     }
 
     /// Admin related responses from the server.
     #[derive(Clone, Debug, Serialize)]
     pub enum AdminUpdate {
+        AliasPolicyRequested(AliasPolicyDto),
+        AliasPolicySet,
         AllowWebSocketJsonRequested(bool),
         AllowWebSocketJsonSet(bool),
+        AnnouncementCancelled,
+        AnnouncementScheduled(AnnouncementId),
+        AnnouncementsRequested(Box<[AdminAnnouncementDto]>),
         ChatSent,
+        ChecksumRequested(Option<u32>),
         ClientHashOverridden(u64),
         DayRequested(Owned<[(crate::UnixTime, MetricsDataPointDto)]>),
         DistributeLoadRequested(bool),
         DistributeLoadSet(bool),
         GameClientSet(u64),
         GamesRequested(Box<[(GameId, f32)]>),
+        HeatmapsRequested(Box<[(ArenaId, String)]>),
         HttpServerRestarting,
+        MatchesRequested(Box<[MatchDto]>),
+        NewsRequested(Box<[NewsItemDto]>),
+        NewsSet,
         PlayerAliasOverridden(PlayerAlias),
         PlayerModeratorOverridden(bool),
         PlayerMuted(usize),
         PlayerRestricted(usize),
+        PlayerShadowBanned(usize),
+        PlayerTraced(bool),
         PlayersRequested(Box<[AdminPlayerDto]>),
         ProfileRequested(String),
+        QuarantinedScoreReviewed(bool),
+        QuarantinedScoresRequested(Box<[QuarantinedScoreDto]>),
+        RateLimitSet(RateLimitDto),
+        RateLimitsRequested(Box<[RateLimitDto]>),
         RedirectRequested(Option<ServerId>),
         RedirectSet(Option<ServerId>),
         ReferrersRequested(Box<[(Referrer, f32)]>),
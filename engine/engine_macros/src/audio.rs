@@ -45,9 +45,11 @@ pub fn include_audio(item: TokenStream) -> TokenStream {
     sorted.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
 
     let mut sprites: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
     let variants: Vec<proc_macro2::TokenStream> = sorted
         .into_iter()
         .map(|(name, sprite)| {
+            names.push(name.clone());
             let variant = name_to_ident(name);
             let start = sprite.start;
             let loop_start: proc_macro2::TokenStream = if let Some(loop_start) = sprite.loop_start {
@@ -81,6 +83,16 @@ pub fn include_audio(item: TokenStream) -> TokenStream {
             #(#variants,)*
         }
 
+        impl Audio {
+            /// Looks up audio by its (snake case) name in the sprite sheet.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    #(#names => Some(Self::#variants),)*
+                    _ => None,
+                }
+            }
+        }
+
         impl client_util::audio::Audio for Audio {
             fn index(self) -> usize {
                 self as usize
@@ -18,6 +18,7 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
             let mut getters = Vec::with_capacity(named.len());
             let mut setters = Vec::with_capacity(named.len());
             let mut validators = Vec::with_capacity(named.len());
+            let mut synced_keys = Vec::with_capacity(named.len());
 
             for Field {
                 ident, ty, attrs, ..
@@ -31,6 +32,7 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
 
                 let mut storage = quote! { local };
                 let mut optional = false;
+                let mut synced = true;
                 let mut validations = Vec::new();
 
                 for attribute in attrs.into_iter().filter(|a| a.path.is_ident("setting")) {
@@ -64,8 +66,12 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
                                         optional = true;
                                     } else if path.is_ident("volatile") {
                                         storage = quote! { session };
+                                        synced = false;
                                     } else if path.is_ident("no_store") {
                                         storage = quote! { no_op };
+                                        synced = false;
+                                    } else if path.is_ident("no_sync") {
+                                        synced = false;
                                     } else {
                                         panic!("Unexpected path: {}", path.get_ident().unwrap());
                                     }
@@ -129,6 +135,9 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
                     validators.push(validator);
                 }
 
+                if synced {
+                    synced_keys.push(ident_string);
+                }
                 loaders.push(loader);
                 getters.push(getter);
                 setters.push(setter);
@@ -141,6 +150,10 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
                             #(#loaders)*
                         }
                     }
+
+                    fn synced_keys() -> &'static [&'static str] {
+                        &[#(#synced_keys),*]
+                    }
                 }
 
                 impl #ident {
@@ -1,10 +1,13 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::alias_policy::AliasPolicy;
 use crate::client::ClientRepo;
 use crate::context::Context;
+use crate::context_service::ContextService;
 use crate::game_service::GameArenaService;
 use crate::infrastructure::Infrastructure;
+use crate::match_archive::MatchArchiveRepo;
 use crate::metric::{Bundle, MetricBundle, MetricRepo};
 use crate::player::PlayerRepo;
 use crate::static_files::static_size_and_hash;
@@ -12,9 +15,10 @@ use crate::status::StatusRepo;
 use crate::system::{ServerStatus, SystemRepo};
 use actix::{fut, ActorFutureExt, Handler, Message, ResponseActFuture, WrapFuture};
 use core_protocol::dto::{
-    AdminPlayerDto, AdminServerDto, MessageDto, MetricFilter, MetricsDataPointDto, SnippetDto,
+    AdminPlayerDto, AdminServerDto, AliasPolicyDto, MessageDto, MetricFilter, MetricsDataPointDto,
+    NewsItemDto, SnippetDto,
 };
-use core_protocol::id::{CohortId, PlayerId, RegionId, ServerId, UserAgentId};
+use core_protocol::id::{ArenaId, CohortId, PlayerId, RegionId, ServerId, UserAgentId};
 use core_protocol::name::{PlayerAlias, Referrer};
 use core_protocol::rpc::{AdminRequest, AdminUpdate};
 use core_protocol::{get_unix_time_now, UnixTime};
@@ -45,6 +49,10 @@ pub struct AdminRepo<G: GameArenaService> {
     pub(crate) redirect_server_id_preference: Option<ServerId>,
     /// Route players to other available servers (bias towards emptier servers).
     pub(crate) distribute_load: bool,
+    /// News feed shown on the spawn screen, newest first.
+    pub(crate) news: Arc<[NewsItemDto]>,
+    /// Rules that chosen aliases must follow.
+    pub(crate) alias_policy: AliasPolicy,
     #[cfg(unix)]
     profile: Option<pprof::ProfilerGuard<'static>>,
     _spooky: PhantomData<G>,
@@ -57,6 +65,10 @@ struct ConfigFile<'a> {
     /// 0 means None.
     redirect_server_id_preference: u8,
     distribute_load: bool,
+    #[serde(default)]
+    news: Cow<'a, [NewsItemDto]>,
+    #[serde(default)]
+    alias_policy: Cow<'a, AliasPolicyDto>,
 }
 
 impl ConfigFile<'static> {
@@ -76,6 +88,8 @@ impl Default for ConfigFile<'static> {
             password: Cow::Borrowed(include_str!("auth.txt")),
             redirect_server_id_preference: 0,
             distribute_load: false,
+            news: Cow::Borrowed(&[]),
+            alias_policy: Cow::Owned(AliasPolicyDto::default()),
         }
     }
 }
@@ -114,6 +128,10 @@ impl<G: GameArenaService> AdminRepo<G> {
             password: config.password,
             redirect_server_id_preference: ServerId::new(config.redirect_server_id_preference),
             distribute_load: config.distribute_load,
+            news: config.news.into_owned().into(),
+            alias_policy: AliasPolicy::new(config.alias_policy.into_owned())
+                .inspect_err(|e| error!("error loading alias policy: {}", e))
+                .unwrap_or_default(),
             #[cfg(unix)]
             profile: None,
             _spooky: PhantomData,
@@ -141,6 +159,8 @@ impl<G: GameArenaService> AdminRepo<G> {
                     .map(|n| n.0.get())
                     .unwrap_or(0),
                 distribute_load: self.distribute_load,
+                news: Cow::Borrowed(&self.news),
+                alias_policy: Cow::Borrowed(self.alias_policy.dto()),
             };
 
             info!("saving admin config: {:?}", config);
@@ -161,11 +181,22 @@ impl<G: GameArenaService> AdminRepo<G> {
         ))
     }
 
+    /// Get heatmaps of the arenas that support them.
+    fn request_heatmaps(arenas: &[ContextService<G>]) -> Result<AdminUpdate, &'static str> {
+        Ok(AdminUpdate::HeatmapsRequested(
+            arenas
+                .iter()
+                .filter_map(|arena| Some((arena.context.arena_id, arena.service.heatmap()?)))
+                .collect(),
+        ))
+    }
+
     /// Get admin view of real players in the game.
-    fn request_players(&self, players: &PlayerRepo<G>) -> Result<AdminUpdate, &'static str> {
+    fn request_players(&self, arenas: &[ContextService<G>]) -> Result<AdminUpdate, &'static str> {
         Ok(AdminUpdate::PlayersRequested(
-            players
-                .iter_borrow()
+            arenas
+                .iter()
+                .flat_map(|arena| arena.context.players.iter_borrow())
                 .filter_map(|player| {
                     if let Some(client) = player.client().filter(|_| !player.is_out_of_game()) {
                         Some(AdminPlayerDto {
@@ -226,6 +257,22 @@ impl<G: GameArenaService> AdminRepo<G> {
         Ok(AdminUpdate::PlayerModeratorOverridden(moderator))
     }
 
+    /// Starts or stops recording a given real player's inputs, and game events involving them,
+    /// in the trace log.
+    fn trace_player(
+        &self,
+        player_id: PlayerId,
+        traced: bool,
+        players: &PlayerRepo<G>,
+    ) -> Result<AdminUpdate, &'static str> {
+        let mut player = players
+            .borrow_player_mut(player_id)
+            .ok_or("nonexistent player")?;
+        let client = player.client_mut().ok_or("not a real player")?;
+        client.traced = traced;
+        Ok(AdminUpdate::PlayerTraced(traced))
+    }
+
     /// Mutes a given real player for a configurable amount of minutes (0 means disable mute).
     fn mute_player(
         &self,
@@ -450,7 +497,9 @@ impl<G: GameArenaService> AdminRepo<G> {
     ) -> Result<AdminUpdate, &'static str> {
         context.chat.log_chat(
             IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            None,
             alias,
+            None,
             &message,
             false,
             "ok",
@@ -482,6 +531,81 @@ impl<G: GameArenaService> AdminRepo<G> {
         Ok(AdminUpdate::ChatSent)
     }
 
+    /// Schedules an announcement for the given arenas (all arenas if [`None`]), starting at
+    /// `start` (now if [`None`]) and lasting a given number of minutes.
+    fn schedule_announcement(
+        infrastructure: &mut Infrastructure<G>,
+        message: String,
+        arena_ids: Option<Box<[ArenaId]>>,
+        start: Option<UnixTime>,
+        minutes: usize,
+    ) -> Result<AdminUpdate, &'static str> {
+        if let Some(arena_ids) = arena_ids.as_ref() {
+            if !arena_ids.iter().all(|&arena_id| {
+                infrastructure
+                    .arenas
+                    .iter()
+                    .any(|arena| arena.context.arena_id == arena_id)
+            }) {
+                return Err("nonexistent arena");
+            }
+        }
+        let start = start.unwrap_or_else(get_unix_time_now);
+        let stop = start.saturating_add(minutes as UnixTime * 60 * 1000);
+        infrastructure
+            .announcements
+            .schedule(message, arena_ids, start, stop)
+            .map(AdminUpdate::AnnouncementScheduled)
+    }
+
+    /// Replaces the news feed, and sends it to every connected client.
+    fn set_news(
+        &mut self,
+        news: Box<[NewsItemDto]>,
+        arenas: &[ContextService<G>],
+    ) -> Result<AdminUpdate, &'static str> {
+        if news.len() > 10 {
+            return Err("too many news items");
+        }
+        for item in news.iter() {
+            if item.title.is_empty() || item.title.len() > 100 {
+                return Err("invalid news title");
+            }
+            if item.body.len() > 1000 {
+                return Err("news body too long");
+            }
+            if let Some(link) = item.link.as_ref() {
+                if !link.starts_with("https://") || link.len() > 256 {
+                    return Err("invalid news link");
+                }
+            }
+        }
+
+        self.news = news.into();
+        self.log_save_config_file();
+        for arena in arenas {
+            ClientRepo::broadcast_news(&self.news, &arena.context.players);
+        }
+        Ok(AdminUpdate::NewsSet)
+    }
+
+    /// Responds with the current alias policy.
+    fn request_alias_policy(&self) -> Result<AdminUpdate, &'static str> {
+        Ok(AdminUpdate::AliasPolicyRequested(
+            self.alias_policy.dto().clone(),
+        ))
+    }
+
+    /// Replaces the alias policy. Aliases that were already chosen are unaffected.
+    fn set_alias_policy(
+        &mut self,
+        alias_policy: AliasPolicyDto,
+    ) -> Result<AdminUpdate, &'static str> {
+        self.alias_policy = AliasPolicy::new(alias_policy)?;
+        self.log_save_config_file();
+        Ok(AdminUpdate::AliasPolicySet)
+    }
+
     /// Responds with the current status of web socket json.
     fn request_allow_web_socket_json(&self) -> Result<AdminUpdate, &'static str> {
         Ok(AdminUpdate::AllowWebSocketJsonRequested(
@@ -628,26 +752,39 @@ impl<G: GameArenaService> Handler<ParameterizedAdminRequest> for Infrastructure<
         let database = self.database();
         match request {
             AdminRequest::RequestSnippets => Box::pin(fut::ready(AdminRepo::request_snippets(
-                &self.context_service.context.clients,
+                &self.arenas[0].context.clients,
             ))),
+            // Snippets and rate limits apply to all arenas.
             AdminRequest::ClearSnippet {
                 cohort_id,
                 referrer,
-            } => Box::pin(fut::ready(AdminRepo::clear_snippet(
-                &mut self.context_service.context.clients,
-                cohort_id,
-                referrer,
-            ))),
+            } => Box::pin(fut::ready(
+                self.arenas
+                    .iter_mut()
+                    .map(|arena| {
+                        AdminRepo::clear_snippet(&mut arena.context.clients, cohort_id, referrer)
+                    })
+                    .reduce(|a, b| a.or(b))
+                    .unwrap(),
+            )),
             AdminRequest::SetSnippet {
                 cohort_id,
                 referrer,
                 snippet,
-            } => Box::pin(fut::ready(AdminRepo::set_snippet(
-                &mut self.context_service.context.clients,
-                cohort_id,
-                referrer,
-                snippet,
-            ))),
+            } => Box::pin(fut::ready(
+                self.arenas
+                    .iter_mut()
+                    .map(|arena| {
+                        AdminRepo::set_snippet(
+                            &mut arena.context.clients,
+                            cohort_id,
+                            referrer,
+                            Arc::clone(&snippet),
+                        )
+                    })
+                    .reduce(Result::and)
+                    .unwrap(),
+            )),
             // Handle asynchronous requests (i.e. those that access database).
             AdminRequest::RequestSeries {
                 game_id,
@@ -688,36 +825,91 @@ impl<G: GameArenaService> Handler<ParameterizedAdminRequest> for Infrastructure<
                 Box::pin(fut::ready(AdminRepo::request_day(&self.metrics, filter)))
             }
             AdminRequest::RequestGames => Box::pin(fut::ready(self.admin.request_games())),
-            AdminRequest::RequestPlayers => Box::pin(fut::ready(
-                self.admin
-                    .request_players(&self.context_service.context.players),
-            )),
-            AdminRequest::OverridePlayerAlias { player_id, alias } => {
-                Box::pin(fut::ready(self.admin.override_player_alias(
+            AdminRequest::RequestHeatmaps => {
+                Box::pin(fut::ready(AdminRepo::request_heatmaps(&self.arenas)))
+            }
+            AdminRequest::RequestMatches { user_id } => {
+                MatchArchiveRepo::request_matches(self, user_id)
+            }
+            AdminRequest::RequestNews => Box::pin(fut::ready(Ok(AdminUpdate::NewsRequested(
+                self.admin.news.iter().cloned().collect(),
+            )))),
+            AdminRequest::SetNews(news) => {
+                Box::pin(fut::ready(self.admin.set_news(news, &self.arenas)))
+            }
+            AdminRequest::RequestPlayers => {
+                Box::pin(fut::ready(self.admin.request_players(&self.arenas)))
+            }
+            AdminRequest::OverridePlayerAlias { player_id, alias } => Box::pin(fut::ready(
+                self.admin.override_player_alias(
                     player_id,
                     alias,
-                    &self.context_service.context.players,
-                )))
-            }
+                    &self.arenas[self.player_arena_index(player_id).unwrap_or(0)]
+                        .context
+                        .players,
+                ),
+            )),
             AdminRequest::OverridePlayerModerator {
                 player_id,
                 moderator,
-            } => Box::pin(fut::ready(self.admin.override_player_moderator(
-                player_id,
-                moderator,
-                &self.context_service.context.players,
-            ))),
-            AdminRequest::RestrictPlayer { player_id, minutes } => {
-                Box::pin(fut::ready(self.admin.restrict_player(
+            } => Box::pin(fut::ready(
+                self.admin.override_player_moderator(
                     player_id,
-                    minutes,
-                    &self.context_service.context.players,
-                )))
+                    moderator,
+                    &self.arenas[self.player_arena_index(player_id).unwrap_or(0)]
+                        .context
+                        .players,
+                ),
+            )),
+            AdminRequest::RequestQuarantinedScores => {
+                Box::pin(fut::ready(self.leaderboard.request_quarantined_scores()))
             }
+            AdminRequest::ReviewQuarantinedScore { player_id, approve } => Box::pin(fut::ready(
+                self.leaderboard.review_quarantined_score(player_id, approve),
+            )),
+            AdminRequest::ShadowBanPlayer { player_id, minutes } => Box::pin(fut::ready(
+                self.player_arena_index(player_id)
+                    .ok_or("nonexistent player")
+                    .and_then(|index| {
+                        self.shadow_bans.shadow_ban_player(
+                            None,
+                            player_id,
+                            minutes.min(u32::MAX as usize) as u32,
+                            &self.arenas[index].context.players,
+                        )
+                    })
+                    .map(|minutes| AdminUpdate::PlayerShadowBanned(minutes as usize)),
+            )),
+            AdminRequest::RestrictPlayer { player_id, minutes } => Box::pin(fut::ready(
+                self.admin.restrict_player(
+                    player_id,
+                    minutes,
+                    &self.arenas[self.player_arena_index(player_id).unwrap_or(0)]
+                        .context
+                        .players,
+                ),
+            )),
+            AdminRequest::TracePlayer { player_id, traced } => Box::pin(fut::ready(
+                self.admin.trace_player(
+                    player_id,
+                    traced,
+                    &self.arenas[self.player_arena_index(player_id).unwrap_or(0)]
+                        .context
+                        .players,
+                ),
+            )),
             AdminRequest::MutePlayer { player_id, minutes } => Box::pin(fut::ready(
-                self.admin
-                    .mute_player(player_id, minutes, &self.context_service.context.players),
+                self.admin.mute_player(
+                    player_id,
+                    minutes,
+                    &self.arenas[self.player_arena_index(player_id).unwrap_or(0)]
+                        .context
+                        .players,
+                ),
             )),
+            AdminRequest::RequestChecksum => Box::pin(fut::ready(Ok(
+                AdminUpdate::ChecksumRequested(self.arenas[0].service.checksum()),
+            ))),
             AdminRequest::RequestServerId => Box::pin(fut::ready(Ok(
                 AdminUpdate::ServerIdRequested(self.server_id),
             ))),
@@ -740,12 +932,44 @@ impl<G: GameArenaService> Handler<ParameterizedAdminRequest> for Infrastructure<
                 player_id,
                 alias,
                 message,
-            } => Box::pin(fut::ready(self.admin.send_chat(
-                player_id,
-                alias,
+            } => Box::pin(fut::ready(if let Some(player_id) = player_id {
+                let index = self.player_arena_index(player_id).unwrap_or(0);
+                self.admin.send_chat(
+                    Some(player_id),
+                    alias,
+                    message,
+                    &mut self.arenas[index].context,
+                )
+            } else {
+                let admin = &self.admin;
+                self.arenas
+                    .iter_mut()
+                    .map(|arena| admin.send_chat(None, alias, message.clone(), &mut arena.context))
+                    .reduce(Result::and)
+                    .unwrap()
+            })),
+            AdminRequest::RequestAnnouncements => Box::pin(fut::ready(Ok(
+                AdminUpdate::AnnouncementsRequested(self.announcements.admin_dtos()),
+            ))),
+            AdminRequest::ScheduleAnnouncement {
                 message,
-                &mut self.context_service.context,
+                arena_ids,
+                start,
+                minutes,
+            } => Box::pin(fut::ready(AdminRepo::schedule_announcement(
+                self, message, arena_ids, start, minutes,
             ))),
+            AdminRequest::CancelAnnouncement(announcement_id) => Box::pin(fut::ready(
+                self.announcements
+                    .cancel(announcement_id)
+                    .map(|_| AdminUpdate::AnnouncementCancelled),
+            )),
+            AdminRequest::RequestAliasPolicy => {
+                Box::pin(fut::ready(self.admin.request_alias_policy()))
+            }
+            AdminRequest::SetAliasPolicy(alias_policy) => {
+                Box::pin(fut::ready(self.admin.set_alias_policy(alias_policy)))
+            }
             AdminRequest::RequestAllowWebSocketJson => {
                 Box::pin(fut::ready(self.admin.request_allow_web_socket_json()))
             }
@@ -758,6 +982,23 @@ impl<G: GameArenaService> Handler<ParameterizedAdminRequest> for Infrastructure<
             AdminRequest::SetDistributeLoad(distribute_load) => {
                 Box::pin(fut::ready(self.admin.set_distribute_load(distribute_load)))
             }
+            AdminRequest::RequestRateLimits => {
+                Box::pin(fut::ready(Ok(AdminUpdate::RateLimitsRequested(
+                    self.arenas[0]
+                        .context
+                        .clients
+                        .rate_limits
+                        .request_rate_limits(),
+                ))))
+            }
+            AdminRequest::SetRateLimit(rate_limit) => Box::pin(fut::ready(
+                self.arenas
+                    .iter_mut()
+                    .map(|arena| arena.context.clients.rate_limits.set_rate_limit(rate_limit))
+                    .reduce(|a, b| a.and(b))
+                    .unwrap()
+                    .map(AdminUpdate::RateLimitSet),
+            )),
             AdminRequest::OverrideClientHash(server_id) => Box::pin(fut::ready(
                 self.admin
                     .override_client_hash(server_id, &self.system, &mut self.status),
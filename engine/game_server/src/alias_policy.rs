@@ -0,0 +1,208 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::dto::AliasPolicyDto;
+
+/// Enforces an [`AliasPolicyDto`] on aliases that already passed
+/// [`PlayerAlias::new_sanitized`][core_protocol::name::PlayerAlias::new_sanitized].
+#[derive(Debug, Default)]
+pub struct AliasPolicy {
+    dto: AliasPolicyDto,
+    /// Normalized [`AliasPolicyDto::deny_patterns`].
+    deny_patterns: Vec<String>,
+    /// Normalized [`AliasPolicyDto::reserved_aliases`].
+    reserved_aliases: Vec<String>,
+}
+
+impl AliasPolicy {
+    const MAX_ENTRIES: usize = 200;
+    const MAX_ENTRY_LEN: usize = 32;
+
+    pub fn new(dto: AliasPolicyDto) -> Result<Self, &'static str> {
+        if dto.deny_patterns.len() + dto.reserved_aliases.len() > Self::MAX_ENTRIES {
+            return Err("too many entries");
+        }
+        let normalize_all = |entries: &[String]| -> Result<Vec<String>, &'static str> {
+            entries
+                .iter()
+                .map(|entry| {
+                    if entry.len() > Self::MAX_ENTRY_LEN {
+                        return Err("entry too long");
+                    }
+                    let normalized = normalize(entry);
+                    if normalized.chars().all(|c| c == '*') {
+                        Err("entry matches everything")
+                    } else {
+                        Ok(normalized)
+                    }
+                })
+                .collect()
+        };
+        Ok(Self {
+            deny_patterns: normalize_all(&dto.deny_patterns)?,
+            reserved_aliases: normalize_all(&dto.reserved_aliases)?,
+            dto,
+        })
+    }
+
+    pub fn dto(&self) -> &AliasPolicyDto {
+        &self.dto
+    }
+
+    /// Checks whether a player may use `alias`, given whether they are a moderator and the aliases
+    /// of (other) online moderators. The error completes the sentence "The alias ...".
+    pub fn check<'a>(
+        &self,
+        alias: &str,
+        moderator: bool,
+        moderator_aliases: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), &'static str> {
+        let normalized = normalize(alias);
+        if self
+            .deny_patterns
+            .iter()
+            .any(|pattern| glob_match(pattern, &normalized))
+        {
+            return Err("is not allowed");
+        }
+        if moderator {
+            return Ok(());
+        }
+        if self
+            .reserved_aliases
+            .iter()
+            .any(|reserved| normalized.contains(reserved.as_str()))
+        {
+            return Err("is reserved");
+        }
+        if moderator_aliases
+            .into_iter()
+            .any(|moderator_alias| normalize(moderator_alias) == normalized)
+        {
+            return Err("belongs to a moderator");
+        }
+        Ok(())
+    }
+}
+
+/// Lowercases, replaces look-alike characters (homoglyphs) with a canonical one, and removes
+/// everything but letters, digits, and `*`, so that e.g. "Ádm1n", "ADMIN" and "a.d.m.i.n" are
+/// all equivalent.
+fn normalize(s: &str) -> String {
+    let mapped: String = s
+        .chars()
+        .map(|c| match c {
+            // Fullwidth forms.
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .filter_map(|c| {
+            Some(match c {
+                '*' => '*',
+                '4' | '@' | 'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'а' | 'α' => 'a',
+                '8' | 'ß' | 'β' => 'b',
+                'ç' | 'с' | 'ϲ' => 'c',
+                'ԁ' => 'd',
+                '3' | 'è' | 'é' | 'ê' | 'ë' | 'е' | 'ё' | 'ε' => 'e',
+                '9' => 'g',
+                'н' => 'h',
+                // 'i' and 'l' are indistinguishable in many fonts.
+                'i' | 'l' | '1' | '!' | '|' | 'ì' | 'í' | 'î' | 'ï' | 'ı' | 'і' | 'ι' | 'ӏ' => {
+                    'l'
+                }
+                'к' | 'κ' => 'k',
+                'м' => 'm',
+                'ñ' | 'п' | 'η' => 'n',
+                '0' | 'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'о' | 'ο' => 'o',
+                'р' | 'ρ' => 'p',
+                '5' | '$' | 'ѕ' => 's',
+                '7' | '+' | 'т' | 'τ' => 't',
+                'ù' | 'ú' | 'û' | 'ü' | 'υ' => 'u',
+                'ν' | 'ѵ' => 'v',
+                'ω' | 'ш' => 'w',
+                'х' | 'χ' => 'x',
+                'ý' | 'ÿ' | 'у' | 'γ' => 'y',
+                c if c.is_alphanumeric() => c,
+                _ => return None,
+            })
+        })
+        .collect();
+    // Pairs of letters that look like one.
+    mapped.replace("rn", "m").replace("vv", "w")
+}
+
+/// Whether `s` matches `pattern`, in which `*` matches any (possibly empty) substring.
+fn glob_match(pattern: &str, s: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = if let Some(rest) = s.strip_prefix(first) {
+        rest
+    } else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // Last part must match the end.
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    // No `*`, so must match exactly.
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alias_policy::{glob_match, normalize, AliasPolicy};
+    use core_protocol::dto::AliasPolicyDto;
+
+    #[test]
+    fn normalize_homoglyphs() {
+        assert_eq!(normalize("Ádm1n"), normalize("admin"));
+        assert_eq!(normalize("a.d.m.i.n"), normalize("ADMIN"));
+        // Cyrillic.
+        assert_eq!(normalize("Ѕоftbеаr"), normalize("Softbear"));
+        assert_eq!(normalize("ｍｏｄ"), normalize("rnod"));
+        assert_ne!(normalize("admin"), normalize("odmin"));
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("abc", "abc"));
+        assert!(!glob_match("abc", "abcd"));
+        assert!(glob_match("ab*", "abcd"));
+        assert!(!glob_match("ab*", "xabcd"));
+        assert!(glob_match("*cd", "abcd"));
+        assert!(glob_match("*bc*", "abcd"));
+        assert!(glob_match("a*c*e", "abcde"));
+        assert!(!glob_match("a*c*e", "abcdef"));
+        assert!(glob_match("a**", "a"));
+    }
+
+    #[test]
+    fn check() {
+        let policy = AliasPolicy::new(AliasPolicyDto {
+            deny_patterns: vec!["*badword*".to_owned()],
+            reserved_aliases: vec!["Softbear".to_owned()],
+        })
+        .unwrap();
+
+        assert!(policy.check("Sailor", false, ["Captain"]).is_ok());
+        assert!(policy.check("my BadW0rd", false, []).is_err());
+        assert!(policy.check("my BadW0rd", true, []).is_err());
+        assert!(policy.check("S0ftbear fan", false, []).is_err());
+        assert!(policy.check("S0ftbear fan", true, []).is_ok());
+        assert!(policy.check("CAPTAlN", false, ["Captain"]).is_err());
+
+        assert!(AliasPolicy::new(AliasPolicyDto {
+            deny_patterns: vec!["**".to_owned()],
+            reserved_aliases: vec![],
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,230 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::dto::{AdminAnnouncementDto, AnnouncementDto};
+use core_protocol::id::{AnnouncementId, ArenaId};
+use core_protocol::rpc::AnnouncementUpdate;
+use core_protocol::UnixTime;
+use std::collections::{BTreeMap, HashMap};
+use std::num::NonZeroU32;
+use std::sync::Arc;
+
+/// A message scheduled by an operator.
+struct Announcement {
+    message: String,
+    /// [`None`] means all arenas.
+    arena_ids: Option<Box<[ArenaId]>>,
+    start: UnixTime,
+    stop: UnixTime,
+}
+
+impl Announcement {
+    fn is_shown(&self, arena_id: ArenaId, now: UnixTime) -> bool {
+        (self.start..self.stop).contains(&now)
+            && self
+                .arena_ids
+                .as_ref()
+                .map_or(true, |arena_ids| arena_ids.contains(&arena_id))
+    }
+}
+
+/// Announcements, such as maintenance warnings and event starts, which are shown to every client
+/// of the selected arenas as a banner, between the scheduled times. Not persisted.
+pub(crate) struct AnnouncementRepo {
+    announcements: BTreeMap<AnnouncementId, Announcement>,
+    next_id: NonZeroU32,
+    /// What the clients of each arena were last sent, in order.
+    shown: HashMap<ArenaId, Vec<AnnouncementId>>,
+}
+
+impl AnnouncementRepo {
+    /// Maximum length of a message, in bytes.
+    pub const MAX_MESSAGE_BYTES: usize = 500;
+    /// Maximum number of announcements scheduled at once.
+    pub const MAX_ANNOUNCEMENTS: usize = 32;
+
+    pub fn new() -> Self {
+        Self {
+            announcements: BTreeMap::new(),
+            next_id: NonZeroU32::new(1).unwrap(),
+            shown: HashMap::new(),
+        }
+    }
+
+    /// Schedules an announcement, to be shown in the given arenas (or all arenas if [`None`])
+    /// from `start` until `stop`.
+    pub fn schedule(
+        &mut self,
+        message: String,
+        arena_ids: Option<Box<[ArenaId]>>,
+        start: UnixTime,
+        stop: UnixTime,
+    ) -> Result<AnnouncementId, &'static str> {
+        let message = message.trim();
+        if message.is_empty() {
+            return Err("empty message");
+        }
+        if message.len() > Self::MAX_MESSAGE_BYTES {
+            return Err("message too long");
+        }
+        if stop <= start {
+            return Err("announcement would never be shown");
+        }
+        if arena_ids.as_ref().map_or(false, |ids| ids.is_empty()) {
+            return Err("no arenas");
+        }
+        if self.announcements.len() >= Self::MAX_ANNOUNCEMENTS {
+            return Err("too many announcements");
+        }
+
+        let announcement_id = AnnouncementId(self.next_id);
+        self.next_id = NonZeroU32::new(self.next_id.get().wrapping_add(1)).unwrap_or(self.next_id);
+        self.announcements.insert(
+            announcement_id,
+            Announcement {
+                message: message.to_owned(),
+                arena_ids,
+                start,
+                stop,
+            },
+        );
+        Ok(announcement_id)
+    }
+
+    /// Cancels an announcement, hiding it if it is being shown.
+    pub fn cancel(&mut self, announcement_id: AnnouncementId) -> Result<(), &'static str> {
+        self.announcements
+            .remove(&announcement_id)
+            .map(|_| ())
+            .ok_or("nonexistent announcement")
+    }
+
+    /// Lists the announcements that are scheduled or being shown, for the admin interface.
+    pub fn admin_dtos(&self) -> Box<[AdminAnnouncementDto]> {
+        self.announcements
+            .iter()
+            .map(|(&announcement_id, announcement)| AdminAnnouncementDto {
+                announcement_id,
+                message: announcement.message.clone(),
+                arena_ids: announcement.arena_ids.clone(),
+                start: announcement.start,
+                stop: announcement.stop,
+            })
+            .collect()
+    }
+
+    /// Forgets expired announcements, and arenas that no longer exist. Call once per tick,
+    /// before [`Self::delta`].
+    pub fn prune(&mut self, now: UnixTime, arena_ids: impl Iterator<Item = ArenaId>) {
+        self.announcements.retain(|_, a| a.stop > now);
+        let arena_ids: Vec<ArenaId> = arena_ids.collect();
+        self.shown
+            .retain(|arena_id, _| arena_ids.contains(arena_id));
+    }
+
+    fn dto(&self, announcement_id: AnnouncementId) -> Option<AnnouncementDto> {
+        self.announcements
+            .get(&announcement_id)
+            .map(|announcement| AnnouncementDto {
+                announcement_id,
+                message: announcement.message.clone(),
+            })
+    }
+
+    /// Returns the announcements to show to, and hide from, the clients of an arena, if any
+    /// changed since the last call for that arena.
+    pub fn delta(
+        &mut self,
+        arena_id: ArenaId,
+        now: UnixTime,
+    ) -> Option<(Arc<[AnnouncementDto]>, Arc<[AnnouncementId]>)> {
+        let current: Vec<AnnouncementId> = self
+            .announcements
+            .iter()
+            .filter(|(_, announcement)| announcement.is_shown(arena_id, now))
+            .map(|(&announcement_id, _)| announcement_id)
+            .collect();
+        let previous = self.shown.get(&arena_id).map(Vec::as_slice).unwrap_or(&[]);
+        if current == previous {
+            return None;
+        }
+
+        let shown: Arc<[AnnouncementDto]> = current
+            .iter()
+            .filter(|id| !previous.contains(id))
+            .filter_map(|&id| self.dto(id))
+            .collect();
+        let hidden: Arc<[AnnouncementId]> = previous
+            .iter()
+            .filter(|id| !current.contains(id))
+            .copied()
+            .collect();
+        self.shown.insert(arena_id, current);
+        Some((shown, hidden))
+    }
+
+    /// Announcements being shown in an arena, for a client that just connected.
+    pub fn initializer(&self, arena_id: ArenaId) -> Option<AnnouncementUpdate> {
+        let shown: Arc<[AnnouncementDto]> = self
+            .shown
+            .get(&arena_id)?
+            .iter()
+            .filter_map(|&id| self.dto(id))
+            .collect();
+        (!shown.is_empty()).then(|| AnnouncementUpdate::Shown(shown))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::announcement::AnnouncementRepo;
+    use core_protocol::id::ArenaId;
+    use core_protocol::rpc::AnnouncementUpdate;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn schedule() {
+        let mut repo = AnnouncementRepo::new();
+        let first = ArenaId(NonZeroU32::new(1).unwrap());
+        let second = ArenaId(NonZeroU32::new(2).unwrap());
+
+        assert!(repo.schedule(String::from(" "), None, 0, 10).is_err());
+        assert!(repo.schedule(String::from("a"), None, 10, 10).is_err());
+        let everywhere = repo
+            .schedule(String::from("maintenance"), None, 100, 200)
+            .unwrap();
+        let only_second = repo
+            .schedule(String::from("event"), Some(vec![second].into()), 150, 300)
+            .unwrap();
+
+        // Not started.
+        assert!(repo.delta(first, 50).is_none());
+        assert!(repo.initializer(first).is_none());
+
+        let (shown, hidden) = repo.delta(first, 100).unwrap();
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].announcement_id, everywhere);
+        assert!(hidden.is_empty());
+        assert!(repo.delta(first, 150).is_none());
+        assert!(matches!(
+            repo.initializer(first),
+            Some(AnnouncementUpdate::Shown(shown)) if shown.len() == 1
+        ));
+
+        let (shown, _) = repo.delta(second, 150).unwrap();
+        assert_eq!(shown.len(), 2);
+
+        // Cancelled.
+        repo.cancel(everywhere).unwrap();
+        assert!(repo.cancel(everywhere).is_err());
+        let (shown, hidden) = repo.delta(second, 160).unwrap();
+        assert!(shown.is_empty());
+        assert_eq!(&*hidden, &[everywhere]);
+
+        // Expired.
+        repo.prune(300, vec![first, second].into_iter());
+        assert!(repo.admin_dtos().is_empty());
+        let (_, hidden) = repo.delta(second, 300).unwrap();
+        assert_eq!(&*hidden, &[only_second]);
+    }
+}
@@ -0,0 +1,87 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::game_service::GameArenaService;
+use crate::infrastructure::Infrastructure;
+use actix::{Handler, Message, MessageResult};
+use core_protocol::id::{ArenaId, PlayerId};
+use core_protocol::name::{PlayerAlias, TeamName};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How often the broadcast feed sends the state of an arena.
+pub const BROADCAST_PERIOD: Duration = Duration::from_secs(1);
+
+/// Query of the broadcast feed, a read-only stream of [`Broadcast`]s for external overlays, e.g.
+/// of streams and tournament broadcasts, that shouldn't have to parse the game protocol.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastQuery {
+    /// Must match `--broadcast-token`.
+    pub token: String,
+    /// The arena new players join, if omitted.
+    pub arena_id: Option<ArenaId>,
+}
+
+/// Summary of an arena, sent every [`BROADCAST_PERIOD`] as JSON.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Broadcast {
+    pub arena_id: ArenaId,
+    pub game_mode: Option<String>,
+    /// Real players that are alive or recently alive.
+    pub player_count: u32,
+    /// Highest score first.
+    pub liveboard: Vec<BroadcastLiveboardDto>,
+    /// See [`GameArenaService::broadcast_state`].
+    pub game: Option<serde_json::Value>,
+}
+
+/// A line of the liveboard, with names resolved so that overlays don't need the game protocol.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastLiveboardDto {
+    pub player_id: PlayerId,
+    pub alias: PlayerAlias,
+    pub score: u32,
+    pub team_name: Option<TeamName>,
+}
+
+/// Asks for the [`Broadcast`] of an arena.
+#[derive(Message)]
+#[rtype(result = "Broadcast")]
+pub struct BroadcastRequest {
+    /// The arena new players join, if [`None`] or not found.
+    pub arena_id: Option<ArenaId>,
+}
+
+impl<G: GameArenaService> Handler<BroadcastRequest> for Infrastructure<G> {
+    type Result = MessageResult<BroadcastRequest>;
+
+    fn handle(&mut self, request: BroadcastRequest, _: &mut Self::Context) -> Self::Result {
+        let arena = &self.arenas[self.arena_index(request.arena_id)];
+        let context = &arena.context;
+        MessageResult(Broadcast {
+            arena_id: context.arena_id,
+            game_mode: arena.config.game_mode.clone(),
+            player_count: context.players.real_players_live as u32,
+            liveboard: context
+                .liveboard()
+                .iter()
+                .filter_map(|dto| {
+                    let player = context.players.borrow_player(dto.player_id)?;
+                    Some(BroadcastLiveboardDto {
+                        player_id: dto.player_id,
+                        alias: player.alias(),
+                        score: dto.score,
+                        team_name: dto
+                            .team_id
+                            .and_then(|team_id| context.teams.get(team_id))
+                            .map(|team| team.name),
+                    })
+                })
+                .collect(),
+            game: arena.service.broadcast_state(),
+        })
+    }
+}
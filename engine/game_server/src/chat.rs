@@ -1,23 +1,22 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::chat_log::{ChatLog, ChatLogConfig, ChatLogEntry};
 use crate::game_service::GameArenaService;
 use crate::metric::MetricRepo;
 use crate::player::PlayerRepo;
+use crate::shadow_ban::ShadowBanRepo;
 use crate::team::TeamRepo;
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use core_protocol::dto::MessageDto;
 use core_protocol::get_unix_time_now;
-use core_protocol::id::PlayerId;
+use core_protocol::id::{ArenaId, PlayerId, TeamId};
 use core_protocol::name::PlayerAlias;
 use core_protocol::rpc::{ChatRequest, ChatUpdate};
 use heapless::HistoryBuffer;
-use log::error;
 use rustrict::{BlockReason, ContextProcessingOptions, ContextRateLimitOptions};
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
-use std::fs::OpenOptions;
-use std::io::Write;
 use std::marker::PhantomData;
 use std::net::IpAddr;
 use std::num::NonZeroUsize;
@@ -33,8 +32,10 @@ pub struct ChatRepo<G> {
     /// Slow mode (more aggressive rate limits for all players) is on until this time.
     slow_mode_until: Option<Instant>,
     emoji_replacer: AhoCorasick<u32>,
+    /// Included in the chat log.
+    arena_id: ArenaId,
     /// Log all chats here.
-    log_path: Option<Arc<str>>,
+    log: Option<Arc<ChatLog>>,
     _spooky: PhantomData<G>,
 }
 
@@ -78,7 +79,7 @@ impl ClientChatData {
 engine_macros::include_emoji!();
 
 impl<G: GameArenaService> ChatRepo<G> {
-    pub fn new(log_path: Option<String>) -> Self {
+    pub fn new(arena_id: ArenaId, log: Option<ChatLogConfig>) -> Self {
         let emoji_replacer = AhoCorasickBuilder::new()
             .dfa(true)
             .build_with_size(EMOJI_FIND)
@@ -89,7 +90,8 @@ impl<G: GameArenaService> ChatRepo<G> {
             safe_mode_until: None,
             slow_mode_until: None,
             emoji_replacer,
-            log_path: log_path.map(Into::into),
+            arena_id,
+            log: log.map(ChatLog::new),
             _spooky: PhantomData,
         }
     }
@@ -216,12 +218,14 @@ impl<G: GameArenaService> ChatRepo<G> {
         Ok(ChatUpdate::SlowModeSet(clamped))
     }
 
-    /// Send a chat to all players, or one's team (whisper).
+    /// Send a chat to all players, or one's team (whisper). If shadow banned, the chat appears
+    /// sent, but is only delivered to the sender.
     fn send_chat(
         &mut self,
         req_player_id: PlayerId,
         message: String,
         whisper: bool,
+        shadow_banned: bool,
         service: &mut G,
         players: &mut PlayerRepo<G>,
         teams: &TeamRepo<G>,
@@ -230,8 +234,17 @@ impl<G: GameArenaService> ChatRepo<G> {
         if let Some(text) = self.try_execute_command(req_player_id, &message, service, players) {
             if let Some(mut req_player) = players.borrow_player_mut(req_player_id) {
                 let alias = req_player.alias();
+                let team_id = req_player.team_id();
                 if let Some(req_client) = req_player.client_mut() {
-                    self.log_chat(req_client.ip_address, alias, &message, whisper, "executed");
+                    self.log_chat(
+                        req_client.ip_address,
+                        Some(req_player_id),
+                        alias,
+                        team_id,
+                        &message,
+                        whisper,
+                        "executed",
+                    );
                     let message = MessageDto {
                         alias: G::authority_alias(),
                         date_sent: get_unix_time_now(),
@@ -309,6 +322,7 @@ impl<G: GameArenaService> ChatRepo<G> {
             metrics.mutate_with(|m| m.toxicity.push(was_toxic), &req_client.metrics);
 
             let verdict = match &result {
+                Ok(_) if shadow_banned => "shadow banned",
                 Ok(_) if was_toxic => "toxic",
                 Ok(_) => "ok",
                 Err(BlockReason::Inappropriate(_)) => "inappropriate",
@@ -322,7 +336,9 @@ impl<G: GameArenaService> ChatRepo<G> {
 
             self.log_chat(
                 req_client.ip_address,
+                Some(req_player_id),
                 req_player.alias(),
+                req_player.team_id(),
                 &message,
                 whisper,
                 verdict,
@@ -345,6 +361,14 @@ impl<G: GameArenaService> ChatRepo<G> {
                     whisper,
                 });
 
+                if shadow_banned {
+                    // Appears sent, but no one else receives it.
+                    if let Some(req_client) = req_player.client_mut() {
+                        req_client.chat.receive(&message);
+                    }
+                    return Ok(ChatUpdate::Sent);
+                }
+
                 // We are about to borrow the players to send to them.
                 drop(req_player);
 
@@ -406,6 +430,7 @@ impl<G: GameArenaService> ChatRepo<G> {
         service: &mut G,
         players: &mut PlayerRepo<G>,
         teams: &TeamRepo<G>,
+        shadow_bans: &mut ShadowBanRepo<G>,
         metrics: &mut MetricRepo<G>,
     ) -> Result<ChatUpdate, &'static str> {
         match request {
@@ -415,6 +440,7 @@ impl<G: GameArenaService> ChatRepo<G> {
                 req_player_id,
                 message,
                 whisper,
+                shadow_bans.is_shadow_banned(req_player_id, players),
                 service,
                 players,
                 teams,
@@ -429,6 +455,9 @@ impl<G: GameArenaService> ChatRepo<G> {
             ChatRequest::RestrictPlayer { player_id, minutes } => {
                 self.restrict_player(req_player_id, player_id, minutes, players)
             }
+            ChatRequest::ShadowBanPlayer { player_id, minutes } => shadow_bans
+                .shadow_ban_player(Some(req_player_id), player_id, minutes, players)
+                .map(|minutes| ChatUpdate::PlayerShadowBanned { player_id, minutes }),
         }
     }
 
@@ -466,42 +495,29 @@ impl<G: GameArenaService> ChatRepo<G> {
         }
     }
 
-    /// Logs a chat message to a file (provided that a logging path has been configured).
+    /// Logs a chat message to a file (provided that a chat log has been configured).
     pub(crate) fn log_chat(
         &self,
         ip: IpAddr,
+        player_id: Option<PlayerId>,
         alias: PlayerAlias,
+        team_id: Option<TeamId>,
         message: &str,
         whisper: bool,
         verdict: &str,
     ) {
-        if let Some(log_path) = &self.log_path {
-            let ctx = if whisper { "team" } else { "global" };
-            let log_path = Arc::clone(log_path);
-            let mut line = Vec::with_capacity(256);
-            let mut writer = csv::Writer::from_writer(&mut line);
-            if let Err(e) = writer.write_record(&[
-                &format!("{}", get_unix_time_now()),
-                &format!("{:?}", G::GAME_ID),
-                &ip.to_string(),
-                ctx,
+        if let Some(log) = &self.log {
+            log.log(&ChatLogEntry {
+                timestamp: get_unix_time_now(),
+                game_id: G::GAME_ID,
+                arena_id: self.arena_id,
+                player_id,
+                alias,
+                team_id,
+                ip,
+                whisper,
                 verdict,
-                alias.as_str(),
                 message,
-            ]) {
-                error!("error composing chat line: {:?}", e);
-            }
-            drop(writer);
-
-            tokio::task::spawn_blocking(move || {
-                if let Err(e) = OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(&*log_path)
-                    .and_then(move |mut file| file.write_all(&line))
-                {
-                    error!("error logging chat: {:?}", e);
-                }
             });
         }
     }
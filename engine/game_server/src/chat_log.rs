@@ -0,0 +1,268 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::get_unix_time_now;
+use core_protocol::id::{ArenaId, GameId, PlayerId, TeamId};
+use core_protocol::name::PlayerAlias;
+use core_protocol::UnixTime;
+use log::error;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// How each chat is written to the chat log.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ChatLogFormat {
+    /// Comma separated values: timestamp, game, ip, context (global or team), verdict, alias,
+    /// and message.
+    Csv,
+    /// One JSON object per line, with the fields of [`ChatLogEntry`].
+    Jsonl,
+}
+
+impl FromStr for ChatLogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "jsonl" => Ok(Self::Jsonl),
+            _ => Err(format!(
+                "unknown chat log format {} (expected csv or jsonl)",
+                s
+            )),
+        }
+    }
+}
+
+/// Where to log chats, and when to rotate and delete the files.
+#[derive(Clone, Debug)]
+pub struct ChatLogConfig {
+    pub path: String,
+    pub format: ChatLogFormat,
+    /// Rotate the file once it is this large.
+    pub max_bytes: Option<u64>,
+    /// Rotate the file once it is this old.
+    pub max_age: Option<Duration>,
+    /// Delete rotated files this long after they were rotated. Files are only deleted when
+    /// rotating, so this has no effect unless rotation is configured.
+    pub retention: Option<Duration>,
+}
+
+/// One chat, as it is logged.
+#[derive(Debug, Serialize)]
+pub struct ChatLogEntry<'a> {
+    pub timestamp: UnixTime,
+    pub game_id: GameId,
+    pub arena_id: ArenaId,
+    /// `None` for chats sent by admins.
+    pub player_id: Option<PlayerId>,
+    pub alias: PlayerAlias,
+    pub team_id: Option<TeamId>,
+    pub ip: IpAddr,
+    /// Whether the chat was only sent to the player's team.
+    pub whisper: bool,
+    /// Result of filtering the chat, e.g. `ok` or `spam`.
+    pub verdict: &'a str,
+    pub message: &'a str,
+}
+
+/// The file currently being appended to.
+struct CurrentFile {
+    file: File,
+    bytes: u64,
+    created: SystemTime,
+}
+
+/// Appends chats to a file, rotating it (renaming it to `{path}-{unix millis}`) and deleting old
+/// rotated files, according to a [`ChatLogConfig`].
+pub(crate) struct ChatLog {
+    config: ChatLogConfig,
+    current: Mutex<Option<CurrentFile>>,
+}
+
+impl ChatLog {
+    pub fn new(config: ChatLogConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            current: Mutex::new(None),
+        })
+    }
+
+    /// Writes a chat to the log, without blocking.
+    pub fn log(self: &Arc<Self>, entry: &ChatLogEntry) {
+        let line = match self.format(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                error!("error composing chat line: {:?}", e);
+                return;
+            }
+        };
+
+        let log = Arc::clone(self);
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = log.append(&line) {
+                error!("error logging chat: {:?}", e);
+            }
+        });
+    }
+
+    fn format(&self, entry: &ChatLogEntry) -> Result<Vec<u8>, String> {
+        let mut line = Vec::with_capacity(256);
+        match self.config.format {
+            ChatLogFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(&mut line);
+                writer
+                    .write_record(&[
+                        format!("{}", entry.timestamp).as_str(),
+                        format!("{:?}", entry.game_id).as_str(),
+                        entry.ip.to_string().as_str(),
+                        if entry.whisper { "team" } else { "global" },
+                        entry.verdict,
+                        entry.alias.as_str(),
+                        entry.message,
+                    ])
+                    .map_err(|e| e.to_string())?;
+            }
+            ChatLogFormat::Jsonl => {
+                serde_json::to_writer(&mut line, entry).map_err(|e| e.to_string())?;
+                line.push(b'\n');
+            }
+        }
+        Ok(line)
+    }
+
+    /// Appends a line to the current file, rotating it first if necessary.
+    fn append(&self, line: &[u8]) -> io::Result<()> {
+        let mut current = self.current.lock().unwrap();
+
+        if let Some(file) = current.as_ref() {
+            let too_large = self.config.max_bytes.map_or(false, |max| {
+                file.bytes > 0 && file.bytes + line.len() as u64 > max
+            });
+            let too_old = self.config.max_age.map_or(false, |max| {
+                file.created.elapsed().map_or(false, |age| age > max)
+            });
+            if too_large || too_old {
+                *current = None;
+                self.rotate()?;
+            }
+        }
+
+        if current.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.config.path)?;
+            let metadata = file.metadata()?;
+            *current = Some(CurrentFile {
+                file,
+                bytes: metadata.len(),
+                created: metadata.created().unwrap_or_else(|_| SystemTime::now()),
+            });
+        }
+
+        let current = current.as_mut().unwrap();
+        current.file.write_all(line)?;
+        current.bytes += line.len() as u64;
+        Ok(())
+    }
+
+    /// Renames the current file, so that a new one is started, and deletes expired ones.
+    fn rotate(&self) -> io::Result<()> {
+        let now = get_unix_time_now();
+        std::fs::rename(&self.config.path, format!("{}-{}", self.config.path, now))?;
+
+        if let Some(retention) = self.config.retention {
+            let path = Path::new(&self.config.path);
+            let directory = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+            let prefix = format!(
+                "{}-",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            );
+            let expiry = now.saturating_sub(retention.as_millis() as UnixTime);
+
+            for entry in std::fs::read_dir(directory)? {
+                let entry = entry?;
+                let rotated = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.strip_prefix(&prefix))
+                    .and_then(|timestamp| timestamp.parse::<UnixTime>().ok());
+                if rotated.map_or(false, |rotated| rotated < expiry) {
+                    std::fs::remove_file(entry.path())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chat_log::{ChatLog, ChatLogConfig, ChatLogEntry, ChatLogFormat};
+    use core_protocol::id::{ArenaId, GameId, PlayerId};
+    use core_protocol::name::PlayerAlias;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::num::NonZeroU32;
+    use std::time::Duration;
+
+    #[test]
+    fn rotation() {
+        let directory = std::env::temp_dir().join(format!("chat_log_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("chat.jsonl").to_string_lossy().into_owned();
+
+        // An expired file from an earlier rotation, and an unrelated file.
+        std::fs::write(format!("{}-1", path), "").unwrap();
+        std::fs::write(directory.join("other"), "").unwrap();
+
+        let log = ChatLog::new(ChatLogConfig {
+            path: path.clone(),
+            format: ChatLogFormat::Jsonl,
+            max_bytes: Some(1),
+            max_age: None,
+            retention: Some(Duration::from_secs(60)),
+        });
+        let entry = ChatLogEntry {
+            timestamp: 0,
+            game_id: GameId::Redacted,
+            arena_id: ArenaId(NonZeroU32::new(1).unwrap()),
+            player_id: Some(PlayerId(NonZeroU32::new(1).unwrap())),
+            alias: PlayerAlias::new_unsanitized("alias"),
+            team_id: None,
+            ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            whisper: false,
+            verdict: "ok",
+            message: "hello",
+        };
+        let line = log.format(&entry).unwrap();
+        assert!(std::str::from_utf8(&line)
+            .unwrap()
+            .contains("\"message\":\"hello\""));
+
+        // Every line after the first exceeds the size limit, so rotates the file.
+        log.append(&line).unwrap();
+        log.append(&line).unwrap();
+
+        let mut names: Vec<_> = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(names.len(), 3, "{:?}", names);
+        assert_eq!(names[0], "chat.jsonl");
+        assert!(names[1].starts_with("chat.jsonl-") && names[1] != "chat.jsonl-1");
+        assert_eq!(names[2], "other");
+    }
+}
@@ -1,50 +1,61 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::alias_policy::AliasPolicy;
+use crate::announcement::AnnouncementRepo;
 use crate::chat::{ChatRepo, ClientChatData};
+use crate::friend::{ClientFriendData, FriendRepo};
 use crate::game_service::GameArenaService;
 use crate::infrastructure::Infrastructure;
-use crate::invitation::{ClientInvitationData, InvitationRepo};
+use crate::invitation::{ClientInvitationData, Invitation, InvitationRepo};
 use crate::leaderboard::LeaderboardRepo;
 use crate::liveboard::LiveboardRepo;
 use crate::metric::{ClientMetricData, MetricRepo};
 use crate::player::{PlayerData, PlayerRepo, PlayerTuple};
+use crate::rate_limit::RateLimitRepo;
+use crate::shadow_ban::ShadowBanRepo;
 use crate::system::SystemRepo;
 use crate::team::{ClientTeamData, TeamRepo};
+use crate::trace_log::{TraceEvent, TraceLog};
+use crate::voice::{ClientVoiceData, VoiceRepo};
 use actix::WrapStream;
 use actix::{
     fut, ActorFutureExt, ActorStreamExt, Context as ActorContext, ContextFutureSpawner, Handler,
     Message, ResponseActFuture, WrapFuture,
 };
 use atomic_refcell::AtomicRefCell;
-use core_protocol::dto::{InvitationDto, ServerDto};
+use core_protocol::dto::{
+    AnnouncementDto, ClientFailure, MessageDto, NewsItemDto, RateLimitedAction, ServerDto,
+    TeamMemberDto,
+};
 use core_protocol::get_unix_time_now;
 use core_protocol::id::{
-    ArenaId, CohortId, InvitationId, PlayerId, ServerId, SessionId, UserAgentId,
+    AnnouncementId, ArenaId, CohortId, InvitationId, LoginType, PlayerId, ServerId, SessionId,
+    UserAgentId, UserId,
 };
+use core_protocol::metrics::FunnelStage;
 use core_protocol::name::{PlayerAlias, Referrer};
 use core_protocol::rpc::{
-    AdType, ClientRequest, ClientUpdate, LeaderboardUpdate, LiveboardUpdate, PlayerUpdate, Request,
-    SystemUpdate, TeamUpdate, Update,
+    AdType, AnnouncementUpdate, ChatRequest, ClientRequest, ClientUpdate, InvitationRequest,
+    LeaderboardUpdate, LiveboardUpdate, PlayerUpdate, Request, SystemUpdate, TeamUpdate, Update,
 };
 use futures::stream::FuturesUnordered;
 use log::{error, info, warn};
 use maybe_parallel_iterator::IntoMaybeParallelRefIterator;
 use rust_embed::RustEmbed;
+use serde::Serialize;
 use server_util::database_schema::SessionItem;
 use server_util::generate_id::{generate_id, generate_id_64};
 use server_util::ip_rate_limiter::IpRateLimiter;
 use server_util::observer::{ObserverMessage, ObserverUpdate};
 use server_util::rate_limiter::{RateLimiter, RateLimiterProps};
-use std::borrow::Cow;
+use server_util::storage::{AccountItem, Storage, StorageError};
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::net::IpAddr;
 use std::num::NonZeroU64;
-use std::ops::Deref;
 use std::str;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -59,10 +70,13 @@ pub struct ClientRepo<G: GameArenaService> {
     authenticate_rate_limiter: IpRateLimiter,
     prune_rate_limiter: RateLimiter,
     database_rate_limiter: RateLimiter,
+    /// Limits the rate of certain requests.
+    pub(crate) rate_limits: RateLimitRepo,
     pending_session_write: Vec<SessionItem>,
+    pending_account_write: Vec<AccountItem>,
     pub(crate) snippets: HashMap<(Option<CohortId>, Option<Referrer>), Arc<str>>,
     /// Where to log traces to.
-    trace_log: Option<Arc<str>>,
+    trace_log: Option<TraceLog>,
     _spooky: PhantomData<G>,
 }
 
@@ -76,9 +90,11 @@ impl<G: GameArenaService> ClientRepo<G> {
             authenticate_rate_limiter: authenticate.into(),
             prune_rate_limiter: RateLimiter::new(Duration::from_secs(1), 0),
             database_rate_limiter: RateLimiter::new(Duration::from_secs(30), 0),
+            rate_limits: RateLimitRepo::new(),
             pending_session_write: Vec::new(),
+            pending_account_write: Vec::new(),
             snippets: Self::load_default_snippets(),
-            trace_log: trace_log.map(Into::into),
+            trace_log: trace_log.map(TraceLog::new),
             _spooky: PhantomData,
         }
     }
@@ -125,15 +141,14 @@ impl<G: GameArenaService> ClientRepo<G> {
         hash_map
     }
 
-    /// Updates sessions to database (internally rate-limited).
+    /// Updates sessions to database, and accounts to storage (internally rate-limited).
     ///
-    /// Note: Sessions also get updated to database when they are being dropped.
+    /// Note: Sessions and accounts also get updated when they are being dropped.
     pub(crate) fn update_to_database(
         infrastructure: &mut Infrastructure<G>,
         ctx: &mut ActorContext<Infrastructure<G>>,
     ) {
-        if infrastructure
-            .context_service
+        if infrastructure.arenas[0]
             .context
             .clients
             .database_rate_limiter
@@ -142,6 +157,8 @@ impl<G: GameArenaService> ClientRepo<G> {
             return;
         }
 
+        Self::update_to_storage(infrastructure, ctx);
+
         // Mocker server id if read only, so we can still proceed.
         #[cfg(debug_assertions)]
         let server_id = infrastructure
@@ -149,33 +166,25 @@ impl<G: GameArenaService> ClientRepo<G> {
             .unwrap_or(ServerId::new(200).unwrap());
         #[cfg(not(debug_assertions))]
         let server_id = crate::unwrap_or_return!(infrastructure.server_id);
-        let arena_id = infrastructure.context_service.context.arena_id;
 
         let queue = FuturesUnordered::new();
 
-        // Backlog from leaving sessions.
-        for pending in infrastructure
-            .context_service
-            .context
-            .clients
-            .pending_session_write
-            .drain(..)
-        {
-            queue.push(infrastructure.database.put_session(pending));
-        }
+        for arena in &mut infrastructure.arenas {
+            let arena_id = arena.context.arena_id;
 
-        for mut player in infrastructure
-            .context_service
-            .context
-            .players
-            .iter_borrow_mut()
-        {
-            let player_id = player.player_id;
-            if let Some(client) = player.client_mut() {
-                if let Some(session_item) =
-                    Self::db_session_item(server_id, arena_id, player_id, client)
-                {
-                    queue.push(infrastructure.database.put_session(session_item))
+            // Backlog from leaving sessions.
+            for pending in arena.context.clients.pending_session_write.drain(..) {
+                queue.push(infrastructure.database.put_session(pending));
+            }
+
+            for mut player in arena.context.players.iter_borrow_mut() {
+                let player_id = player.player_id;
+                if let Some(client) = player.client_mut() {
+                    if let Some(session_item) =
+                        Self::db_session_item(server_id, arena_id, player_id, client)
+                    {
+                        queue.push(infrastructure.database.put_session(session_item))
+                    }
                 }
             }
         }
@@ -191,6 +200,99 @@ impl<G: GameArenaService> ClientRepo<G> {
             .spawn(ctx);
     }
 
+    /// Puts accounts that changed since they were last put.
+    fn update_to_storage(
+        infrastructure: &mut Infrastructure<G>,
+        ctx: &mut ActorContext<Infrastructure<G>>,
+    ) {
+        let storage = match infrastructure.storage {
+            Some(storage) => storage,
+            None => {
+                for arena in &mut infrastructure.arenas {
+                    arena.context.clients.pending_account_write.clear();
+                }
+                return;
+            }
+        };
+
+        let queue = FuturesUnordered::new();
+
+        for arena in &mut infrastructure.arenas {
+            // Backlog from leaving sessions.
+            for pending in arena.context.clients.pending_account_write.drain(..) {
+                queue.push(storage.put_account(pending));
+            }
+
+            for mut player in arena.context.players.iter_borrow_mut() {
+                let unlocks = G::account_unlocks(&player.data);
+                if let Some(client) = player.client_mut() {
+                    if let Some(account_item) = Self::db_account_item(client, unlocks) {
+                        queue.push(storage.put_account(account_item));
+                    }
+                }
+            }
+        }
+
+        queue
+            .into_actor(infrastructure)
+            .map(|result, _, _| {
+                if let Err(e) = result {
+                    error!("error putting account: {:?}", e);
+                }
+            })
+            .finish()
+            .spawn(ctx);
+    }
+
+    /// If the client has an account that is dirty with respect to storage, creates an account
+    /// item to overwrite the stored version.
+    fn db_account_item(
+        client: &mut PlayerClientData<G>,
+        unlocks: Option<String>,
+    ) -> Option<AccountItem> {
+        let account = client.account.as_mut()?;
+        account.alias = Some(client.alias);
+        if unlocks.is_some() {
+            account.unlocks = unlocks;
+        }
+
+        if client.account_item.as_ref() != Some(account) {
+            account.date_updated = get_unix_time_now();
+            client.account_item = Some(account.clone());
+            Some(account.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Applies an account loaded from storage, unless the client already has it.
+    fn restore_account(player: &mut PlayerData<G>, account: AccountItem) {
+        if player
+            .client()
+            .map(|client| {
+                client
+                    .account
+                    .as_ref()
+                    .map(|existing| existing.user_id == account.user_id)
+                    .unwrap_or(false)
+            })
+            .unwrap_or(true)
+        {
+            // Don't revert changes that weren't stored yet.
+            return;
+        }
+        if let Some(unlocks) = account.unlocks.as_deref() {
+            G::restore_account_unlocks(&mut player.data, unlocks);
+        }
+        if let Some(client) = player.client_mut() {
+            if let Some(alias) = account.alias {
+                client.alias = alias;
+            }
+            client.account_item = Some(account.clone());
+            client.account = Some(account);
+        }
+    }
+
     /// If the session is dirty with respect to the database, creates a session item to overwrite
     /// the database version.
     fn db_session_item(
@@ -238,6 +340,8 @@ impl<G: GameArenaService> ClientRepo<G> {
         liveboard: &LiveboardRepo<G>,
         metrics: &mut MetricRepo<G>,
         system: Option<&SystemRepo<G>>,
+        announcements: &AnnouncementRepo,
+        news: &Arc<[NewsItemDto]>,
         arena_id: ArenaId,
         server_id: Option<ServerId>,
         game: &mut G,
@@ -271,9 +375,23 @@ impl<G: GameArenaService> ClientRepo<G> {
             }),
         });
 
+        // Restore account settings, which also lets the client know to save changes to them.
+        if let Some(account) = client.account.as_ref() {
+            let settings = account
+                .settings
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            let _ = register_observer.send(ObserverUpdate::Send {
+                message: Update::Client(ClientUpdate::SettingsLoaded(settings)),
+            });
+        }
+
         // Don't assume client remembered anything, although it may/should have.
         *client.data.borrow_mut() = G::ClientData::default();
         client.chat.forget_state();
+        client.friend.forget_state();
+        client.voice.forget_state();
         client.team.forget_state();
 
         // If there is a JS snippet for the cohort and referrer, send it to client for eval.
@@ -361,6 +479,27 @@ impl<G: GameArenaService> ClientRepo<G> {
                 });
             }
         }
+
+        let _ = register_observer.send(ObserverUpdate::Send {
+            message: Update::Client(ClientUpdate::NewsUpdated(Arc::clone(news))),
+        });
+
+        if let Some(initializer) = announcements.initializer(arena_id) {
+            let _ = register_observer.send(ObserverUpdate::Send {
+                message: Update::Announcement(initializer),
+            });
+        }
+    }
+
+    /// Sends the news feed to every connected client.
+    pub(crate) fn broadcast_news(news: &Arc<[NewsItemDto]>, players: &PlayerRepo<G>) {
+        for player in players.iter_borrow() {
+            if let Some(ClientStatus::Connected { observer }) = player.client().map(|c| &c.status) {
+                let _ = observer.send(ObserverUpdate::Send {
+                    message: Update::Client(ClientUpdate::NewsUpdated(Arc::clone(news))),
+                });
+            }
+        }
     }
 
     /// Client websocket disconnected.
@@ -398,6 +537,99 @@ impl<G: GameArenaService> ClientRepo<G> {
         }
     }
 
+    /// Real players that are currently connected.
+    pub(crate) fn connected_player_ids(players: &PlayerRepo<G>) -> Vec<PlayerId> {
+        players
+            .iter_borrow()
+            .filter(|player| {
+                player
+                    .client()
+                    .map(|client| matches!(client.status, ClientStatus::Connected { .. }))
+                    .unwrap_or(false)
+            })
+            .map(|player| player.player_id)
+            .collect()
+    }
+
+    /// Moves a connected player from one arena to another, keeping their session, account, and
+    /// score. The player leaves the old arena's game and is welcomed into the new one over the
+    /// same connection.
+    pub(crate) fn hand_off(
+        infrastructure: &mut Infrastructure<G>,
+        player_id: PlayerId,
+        from: usize,
+        to: usize,
+    ) -> Result<(), &'static str> {
+        if from == to {
+            return Err("same arena");
+        }
+        let old = &mut infrastructure.arenas[from];
+        let player_tuple = Arc::clone(
+            old.context
+                .players
+                .get(player_id)
+                .ok_or("nonexistent player")?,
+        );
+
+        let player = player_tuple.borrow_player();
+        let observer = match &player.client().ok_or("not a real player")?.status {
+            ClientStatus::Connected { observer } => observer.clone(),
+            _ => return Err("not connected"),
+        };
+        let score = player.score;
+        let unlocks = G::account_unlocks(&player.data);
+        drop(player);
+
+        old.service.player_left(&player_tuple, &old.context.players);
+        old.context.players.forget(
+            player_id,
+            &mut old.context.teams,
+            &mut infrastructure.invitations,
+        );
+        // The old arena may still refer to the player until it cleans up after them.
+        let mut client = player_tuple
+            .borrow_player_mut()
+            .client
+            .take()
+            .ok_or("not a real player")?;
+        // Rejoin without starting a new visit, and without closing the connection.
+        client.status = ClientStatus::LeavingLimbo {
+            since: Instant::now(),
+        };
+
+        let mut player = PlayerData::new(player_id, Some(client));
+        player.score = score;
+        if let Some(unlocks) = unlocks.as_deref() {
+            G::restore_account_unlocks(&mut player.data, unlocks);
+        }
+
+        let new = &mut infrastructure.arenas[to];
+        new.context
+            .players
+            .insert(player_id, Arc::new(PlayerTuple::new(player)));
+        new.context.clients.register(
+            player_id,
+            observer,
+            &mut new.context.players,
+            &mut new.context.teams,
+            &new.context.chat,
+            &infrastructure.leaderboard,
+            &new.context.liveboard,
+            &mut infrastructure.metrics,
+            infrastructure.system.as_ref(),
+            &infrastructure.announcements,
+            &infrastructure.admin.news,
+            new.context.arena_id,
+            infrastructure.server_id,
+            &mut new.service,
+        );
+        info!(
+            "handed off player {:?} to arena {:?}",
+            player_id, new.context.arena_id
+        );
+        Ok(())
+    }
+
     /// Update all clients with game state.
     pub(crate) fn update(
         &mut self,
@@ -407,7 +639,14 @@ impl<G: GameArenaService> ClientRepo<G> {
         liveboard: &mut LiveboardRepo<G>,
         leaderboard: &LeaderboardRepo<G>,
         server_delta: Option<(Arc<[ServerDto]>, Arc<[ServerId]>)>,
+        announcement_delta: Option<(Arc<[AnnouncementDto]>, Arc<[AnnouncementId]>)>,
+        arena_id: ArenaId,
+        server_id: Option<ServerId>,
     ) {
+        if let Some(trace_log) = self.trace_log.as_mut() {
+            trace_log.update();
+        }
+
         let player_update = players.delta(&*teams);
         let team_update = teams.delta(&*players);
         let immut_players = &*players;
@@ -423,11 +662,28 @@ impl<G: GameArenaService> ClientRepo<G> {
                         .unwrap_or(false)
             })
             .map(|player_id| {
+                let (members, joiners, joins) =
+                    teams.player_delta(player_id, immut_players).unwrap();
                 (
                     player_id,
                     (
                         ChatRepo::<G>::player_delta(player_id, immut_players),
-                        teams.player_delta(player_id, immut_players).unwrap(),
+                        FriendRepo::<G>::player_delta(
+                            player_id,
+                            immut_players,
+                            arena_id,
+                            server_id,
+                        ),
+                        VoiceRepo::<G>::player_delta(player_id, immut_players),
+                        (
+                            members.map(|members| {
+                                Self::team_member_dtos::<Arc<[_]>>(members.iter(), immut_players)
+                            }),
+                            joiners.map(|joiners| {
+                                Self::team_member_dtos::<Box<[_]>>(joiners.iter(), immut_players)
+                            }),
+                            joins,
+                        ),
                     ),
                 )
             })
@@ -482,8 +738,12 @@ impl<G: GameArenaService> ClientRepo<G> {
                         }
                     }
 
-                    if let Some((chat_update, (members, joiners, joins))) =
-                        player_chat_team_updates.get(&player_id)
+                    if let Some((
+                        chat_update,
+                        friend_update,
+                        voice_update,
+                        (members, joiners, joins),
+                    )) = player_chat_team_updates.get(&player_id)
                     {
                         if let Some(chat_update) = chat_update {
                             let _ = observer.send(ObserverUpdate::Send {
@@ -491,20 +751,28 @@ impl<G: GameArenaService> ClientRepo<G> {
                             });
                         }
 
+                        if let Some(friend_update) = friend_update {
+                            let _ = observer.send(ObserverUpdate::Send {
+                                message: Update::Friend(friend_update.clone()),
+                            });
+                        }
+
+                        if let Some(voice_update) = voice_update {
+                            let _ = observer.send(ObserverUpdate::Send {
+                                message: Update::Voice(voice_update.clone()),
+                            });
+                        }
+
                         // TODO: We could get members on a per team basis.
                         if let Some(members) = members {
                             let _ = observer.send(ObserverUpdate::Send {
-                                message: Update::Team(TeamUpdate::Members(
-                                    members.deref().clone().into(),
-                                )),
+                                message: Update::Team(TeamUpdate::Members(Arc::clone(members))),
                             });
                         }
 
                         if let Some(joiners) = joiners {
                             let _ = observer.send(ObserverUpdate::Send {
-                                message: Update::Team(TeamUpdate::Joiners(
-                                    joiners.deref().clone().into(),
-                                )),
+                                message: Update::Team(TeamUpdate::Joiners(joiners.clone())),
                             });
                         }
 
@@ -552,11 +820,45 @@ impl<G: GameArenaService> ClientRepo<G> {
                             });
                         }
                     }
+
+                    if let Some((shown, hidden)) = announcement_delta.as_ref() {
+                        if !shown.is_empty() {
+                            let _ = observer.send(ObserverUpdate::Send {
+                                message: Update::Announcement(AnnouncementUpdate::Shown(
+                                    Arc::clone(shown),
+                                )),
+                            });
+                        }
+                        if !hidden.is_empty() {
+                            let _ = observer.send(ObserverUpdate::Send {
+                                message: Update::Announcement(AnnouncementUpdate::Hidden(
+                                    Arc::clone(hidden),
+                                )),
+                            });
+                        }
+                    }
                 }
             },
         );
     }
 
+    /// Resolves the aliases of team members or joiners, skipping any that no longer exist.
+    fn team_member_dtos<C: FromIterator<TeamMemberDto>>(
+        player_ids: impl Iterator<Item = PlayerId>,
+        players: &PlayerRepo<G>,
+    ) -> C {
+        player_ids
+            .filter_map(|player_id| {
+                players
+                    .borrow_player(player_id)
+                    .map(|player| TeamMemberDto {
+                        player_id,
+                        alias: player.alias(),
+                    })
+            })
+            .collect()
+    }
+
     /// Cleans up old clients. Rate limited internally.
     pub(crate) fn prune(
         &mut self,
@@ -601,9 +903,15 @@ impl<G: GameArenaService> ClientRepo<G> {
                                 false
                             } else {
                                 metrics.stop_visit(&mut *player);
+                                let unlocks = G::account_unlocks(&player.data);
                                 // Unfortunately, the above makes finishing touches to metrics, but
                                 // borrows the entire player. Must therefore re-borrow client.
                                 let client_data = player.client_mut().unwrap();
+                                if let Some(account_item) =
+                                    Self::db_account_item(client_data, unlocks)
+                                {
+                                    self.pending_account_write.push(account_item);
+                                }
                                 if let Some(server_id) = server_id {
                                     if let Some(session_item) = Self::db_session_item(
                                         server_id,
@@ -651,12 +959,71 @@ impl<G: GameArenaService> ClientRepo<G> {
         }
     }
 
-    /// Request a different alias (may not be done while alive).
+    /// Records a game request in the trace log, if the player is being traced.
+    fn trace_input(
+        &mut self,
+        player_id: PlayerId,
+        command: &G::GameRequest,
+        arena_id: ArenaId,
+        players: &PlayerRepo<G>,
+    ) {
+        if let Some(trace_log) = self.trace_log.as_mut() {
+            if players
+                .borrow_player(player_id)
+                .map_or(false, |player| player.is_traced())
+            {
+                match serde_json::to_value(command) {
+                    Ok(value) => {
+                        trace_log.record(G::GAME_ID, arena_id, player_id, TraceEvent::Input(value))
+                    }
+                    Err(e) => error!("error composing trace input: {:?}", e),
+                }
+            }
+        }
+    }
+
+    /// Records a game event in the trace log, if the player is being traced.
+    pub(crate) fn trace_event(
+        &mut self,
+        player_id: PlayerId,
+        event: &impl Serialize,
+        arena_id: ArenaId,
+        players: &PlayerRepo<G>,
+    ) {
+        if let Some(trace_log) = self.trace_log.as_mut() {
+            if players
+                .borrow_player(player_id)
+                .map_or(false, |player| player.is_traced())
+            {
+                match serde_json::to_value(event) {
+                    Ok(value) => {
+                        trace_log.record(G::GAME_ID, arena_id, player_id, TraceEvent::Event(value))
+                    }
+                    Err(e) => error!("error composing trace event: {:?}", e),
+                }
+            }
+        }
+    }
+
+    /// Request a different alias (may not be done while alive). If the alias violates the
+    /// [`AliasPolicy`], the player is given the default alias instead, and told why in chat.
     fn set_alias(
         player_id: PlayerId,
         alias: PlayerAlias,
         players: &PlayerRepo<G>,
+        alias_policy: &AliasPolicy,
     ) -> Result<ClientUpdate, &'static str> {
+        let default_alias = G::default_alias();
+        let moderator_aliases: Vec<PlayerAlias> = players
+            .iter_borrow()
+            .filter(|player| {
+                player.player_id != player_id
+                    && player.client().map_or(false, |client| client.moderator)
+            })
+            .map(|player| player.alias())
+            .filter(|&alias| alias != default_alias)
+            .collect();
+
         let mut player = players
             .borrow_player_mut(player_id)
             .ok_or("player doesn't exist")?;
@@ -671,8 +1038,48 @@ impl<G: GameArenaService> ClientRepo<G> {
 
         let client = player.client_mut().ok_or("only clients can set alias")?;
         let censored_alias = PlayerAlias::new_sanitized(alias.as_str());
-        client.alias = censored_alias;
-        Ok(ClientUpdate::AliasSet(censored_alias))
+        let policy_alias = match alias_policy.check(
+            censored_alias.as_str(),
+            client.moderator,
+            moderator_aliases.iter().map(|alias| alias.as_str()),
+        ) {
+            Ok(()) => censored_alias,
+            Err(reason) => {
+                let warning = MessageDto {
+                    alias: G::authority_alias(),
+                    date_sent: get_unix_time_now(),
+                    player_id: None,
+                    team_captain: false,
+                    team_name: None,
+                    text: format!(
+                        "The alias \"{}\" {}, so you will be known as \"{}\" instead.",
+                        censored_alias, reason, default_alias
+                    ),
+                    whisper: false,
+                };
+                client.chat.receive(&Arc::new(warning));
+                default_alias
+            }
+        };
+        client.alias = policy_alias;
+        Ok(ClientUpdate::AliasSet(policy_alias))
+    }
+
+    /// Replace the settings stored with the client's account.
+    fn save_settings(
+        player_id: PlayerId,
+        settings: Box<[(String, String)]>,
+        players: &PlayerRepo<G>,
+    ) -> Result<ClientUpdate, &'static str> {
+        let mut player = players
+            .borrow_player_mut(player_id)
+            .ok_or("player doesn't exist")?;
+        let client = player
+            .client_mut()
+            .ok_or("only clients can save settings")?;
+        let account = client.account.as_mut().ok_or("no account")?;
+        account.set_settings(settings.into_vec())?;
+        Ok(ClientUpdate::SettingsSaved)
     }
 
     /// Record client frames per second (FPS) for statistical purposes.
@@ -719,11 +1126,26 @@ impl<G: GameArenaService> ClientRepo<G> {
         }
     }
 
+    /// Record that the client was shown the splash screen, for the funnel.
+    fn tally_splash(
+        player_id: PlayerId,
+        players: &PlayerRepo<G>,
+        metrics: &mut MetricRepo<G>,
+    ) -> Result<ClientUpdate, &'static str> {
+        let mut player = players
+            .borrow_player_mut(player_id)
+            .ok_or("player doesn't exist")?;
+        let client = player.client_mut().ok_or("only clients can tally splash")?;
+        metrics.reach_funnel_stage(FunnelStage::Splash, Instant::now(), &mut client.metrics);
+        Ok(ClientUpdate::SplashTallied)
+    }
+
     /// Record a client-side error message for investigation.
     fn trace(
-        &self,
+        &mut self,
         player_id: PlayerId,
         message: String,
+        arena_id: ArenaId,
         players: &PlayerRepo<G>,
     ) -> Result<ClientUpdate, &'static str> {
         let mut player = players
@@ -742,46 +1164,19 @@ impl<G: GameArenaService> ClientRepo<G> {
             .map(|limit| client.traces < limit)
             .unwrap_or(true)
         {
-            if let Some(trace_log) = self.trace_log.as_ref() {
-                let trace_log = Arc::clone(trace_log);
-                let mut line = Vec::with_capacity(256);
-                let mut writer = csv::Writer::from_writer(&mut line);
-                if let Err(e) = writer.write_record(&[
-                    get_unix_time_now().to_string().as_str(),
-                    &format!("{:?}", G::GAME_ID),
-                    &client.ip_address.to_string(),
-                    &client
-                        .metrics
-                        .region_id
-                        .map(|r| Cow::Owned(format!("{:?}", r)))
-                        .unwrap_or(Cow::Borrowed("?")),
-                    client
-                        .metrics
-                        .referrer
-                        .as_ref()
-                        .map(|r| r.as_str())
-                        .unwrap_or("?"),
-                    &client
-                        .metrics
-                        .user_agent_id
-                        .map(|ua| Cow::Owned(format!("{:?}", ua)))
-                        .unwrap_or(Cow::Borrowed("?")),
-                    &message,
-                ]) {
-                    error!("error composing trace line: {:?}", e);
-                } else {
-                    drop(writer);
-                    tokio::task::spawn_blocking(move || {
-                        if let Err(e) = OpenOptions::new()
-                            .create(true)
-                            .append(true)
-                            .open(&*trace_log)
-                            .and_then(move |mut file| file.write_all(&line))
-                        {
-                            error!("error logging trace: {:?}", e);
-                        }
-                    });
-                }
+            if let Some(trace_log) = self.trace_log.as_mut() {
+                trace_log.record(
+                    G::GAME_ID,
+                    arena_id,
+                    player_id,
+                    TraceEvent::Error {
+                        ip: client.ip_address,
+                        region_id: client.metrics.region_id,
+                        referrer: client.metrics.referrer,
+                        user_agent_id: client.metrics.user_agent_id,
+                        message: &message,
+                    },
+                );
             } else {
                 info!("client_trace: {}", message);
             }
@@ -792,19 +1187,93 @@ impl<G: GameArenaService> ClientRepo<G> {
         }
     }
 
+    /// Records a client failure in metrics, and (with details) in the trace log.
+    fn report_failure(
+        &mut self,
+        player_id: PlayerId,
+        failure: ClientFailure,
+        message: Option<String>,
+        arena_id: ArenaId,
+        players: &PlayerRepo<G>,
+        metrics: &mut MetricRepo<G>,
+    ) -> Result<ClientUpdate, &'static str> {
+        let mut player = players
+            .borrow_player_mut(player_id)
+            .ok_or("player doesn't exist")?;
+        let client = player
+            .client_mut()
+            .ok_or("only clients can report failures")?;
+
+        if message.as_ref().map_or(false, |m| m.len() > 4096) {
+            return Err("failure message too long");
+        }
+        if client.failures >= 10 {
+            return Err("too many failures");
+        }
+        client.failures += 1;
+
+        metrics.mutate_with(
+            |metrics| {
+                let metric = match failure {
+                    ClientFailure::GraphicsQualityLowered => &mut metrics.graphics_quality_lowered,
+                    ClientFailure::Panic => &mut metrics.client_panics,
+                    ClientFailure::SlowFrame => &mut metrics.slow_frames,
+                    ClientFailure::WebGlContextLost => &mut metrics.webgl_context_losses,
+                };
+                metric.increment();
+            },
+            &mut client.metrics,
+        );
+
+        if let Some(trace_log) = self.trace_log.as_mut() {
+            trace_log.record(
+                G::GAME_ID,
+                arena_id,
+                player_id,
+                TraceEvent::Failure {
+                    ip: client.ip_address,
+                    user_agent_id: client.metrics.user_agent_id,
+                    client_hash: client.client_hash,
+                    failure,
+                    message: message.as_deref(),
+                },
+            );
+        } else {
+            info!(
+                "client_failure: {:?} (client {:x}, {:?}) {}",
+                failure,
+                client.client_hash,
+                client.metrics.user_agent_id,
+                message.as_deref().unwrap_or_default()
+            );
+        }
+        Ok(ClientUpdate::FailureReported)
+    }
+
     /// Handles an arbitrary [`ClientRequest`].
     fn handle_client_request(
         &mut self,
         player_id: PlayerId,
         request: ClientRequest,
+        arena_id: ArenaId,
         players: &PlayerRepo<G>,
         metrics: &mut MetricRepo<G>,
+        alias_policy: &AliasPolicy,
     ) -> Result<ClientUpdate, &'static str> {
         match request {
-            ClientRequest::SetAlias(alias) => Self::set_alias(player_id, alias, players),
+            ClientRequest::ReportFailure { failure, message } => {
+                self.report_failure(player_id, failure, message, arena_id, players, metrics)
+            }
+            ClientRequest::SaveSettings(settings) => {
+                Self::save_settings(player_id, settings, players)
+            }
+            ClientRequest::SetAlias(alias) => {
+                Self::set_alias(player_id, alias, players, alias_policy)
+            }
             ClientRequest::TallyAd(ad_type) => Self::tally_ad(player_id, ad_type, players, metrics),
             ClientRequest::TallyFps(fps) => Self::tally_fps(player_id, fps, players),
-            ClientRequest::Trace { message } => self.trace(player_id, message, players),
+            ClientRequest::TallySplash => Self::tally_splash(player_id, players, metrics),
+            ClientRequest::Trace { message } => self.trace(player_id, message, arena_id, players),
         }
     }
 
@@ -812,30 +1281,84 @@ impl<G: GameArenaService> ClientRepo<G> {
     fn handle_observer_request(
         &mut self,
         player_id: PlayerId,
-        request: Request<G::GameRequest>,
+        mut request: Request<G::GameRequest>,
         service: &mut G,
         arena_id: ArenaId,
         server_id: Option<ServerId>,
         players: &mut PlayerRepo<G>,
         teams: &mut TeamRepo<G>,
         chat: &mut ChatRepo<G>,
+        friends: &mut FriendRepo<G>,
+        voice: &mut VoiceRepo<G>,
         invitations: &mut InvitationRepo<G>,
+        shadow_bans: &mut ShadowBanRepo<G>,
         metrics: &mut MetricRepo<G>,
+        alias_policy: &AliasPolicy,
     ) -> Result<Option<Update<G::GameUpdate>>, &'static str> {
+        let action = match &request {
+            Request::Game(command) => G::rate_limited_action(command),
+            Request::Chat(ChatRequest::Send { .. }) => Some(RateLimitedAction::Chat),
+            Request::Invitation(InvitationRequest::CreateInvitation) => {
+                Some(RateLimitedAction::CreateInvitation)
+            }
+            Request::Client(ClientRequest::ReportFailure { .. }) => {
+                Some(RateLimitedAction::Telemetry)
+            }
+            _ => None,
+        };
+        if let Some(action) = action {
+            let ip_address = players
+                .borrow_player(player_id)
+                .and_then(|player| player.client().map(|client| client.ip_address))
+                .ok_or("nonexistent observer")?;
+            if self
+                .rate_limits
+                .should_limit_rate(action, player_id, ip_address)
+            {
+                request = match request {
+                    Request::Game(command) => {
+                        Request::Game(G::rate_limited_remainder(command).ok_or("rate limited")?)
+                    }
+                    _ => return Err("rate limited"),
+                };
+            }
+        }
+
         match request {
             // Goes first (fast path).
             Request::Game(command) => {
+                self.trace_input(player_id, &command, arena_id, &*players);
                 Self::handle_game_command(player_id, command, service, &*players)
                     .map(|u| u.map(Update::Game))
             }
             Request::Client(request) => self
-                .handle_client_request(player_id, request, &*players, metrics)
+                .handle_client_request(
+                    player_id,
+                    request,
+                    arena_id,
+                    &*players,
+                    metrics,
+                    alias_policy,
+                )
                 .map(|u| Some(Update::Client(u))),
             Request::Chat(request) => chat
-                .handle_chat_request(player_id, request, service, players, teams, metrics)
+                .handle_chat_request(
+                    player_id,
+                    request,
+                    service,
+                    players,
+                    teams,
+                    shadow_bans,
+                    metrics,
+                )
                 .map(|u| Some(Update::Chat(u))),
+            Request::Friend(request) => friends
+                .handle_friend_request(player_id, request, &*players)
+                .map(|u| Some(Update::Friend(u))),
             Request::Invitation(request) => invitations
-                .handle_invitation_request(player_id, request, arena_id, server_id, players)
+                .handle_invitation_request(
+                    player_id, request, arena_id, server_id, players, metrics,
+                )
                 .map(|u| Some(Update::Invitation(u))),
             Request::Player(request) => players
                 .handle_player_request(player_id, request, metrics)
@@ -843,6 +1366,9 @@ impl<G: GameArenaService> ClientRepo<G> {
             Request::Team(request) => teams
                 .handle_team_request(player_id, request, players)
                 .map(|u| Some(Update::Team(u))),
+            Request::Voice(request) => voice
+                .handle_voice_request(player_id, request, &*players, &*teams)
+                .map(|u| Some(Update::Voice(u))),
         }
     }
 
@@ -865,6 +1391,27 @@ impl<G: GameArenaService> ClientRepo<G> {
     }
 }
 
+/// Gets the account linked to an identity, linking the identity to the session's existing account
+/// (or a new account) if it isn't linked yet.
+async fn load_account(
+    storage: &'static dyn Storage,
+    login_type: LoginType,
+    login_id: &str,
+    existing_user_id: Option<UserId>,
+) -> Result<AccountItem, StorageError> {
+    let user_id = if let Some(user_id) = storage.get_user_id(login_type, login_id).await? {
+        user_id
+    } else {
+        let user_id = existing_user_id.unwrap_or_else(|| UserId(generate_id_64()));
+        storage.link_identity(login_type, login_id, user_id).await?;
+        user_id
+    };
+    Ok(storage
+        .get_account(user_id)
+        .await?
+        .unwrap_or_else(|| AccountItem::new(user_id)))
+}
+
 /// Don't let bad values sneak in.
 fn sanitize_tps(tps: f32) -> Option<f32> {
     tps.is_finite().then_some(tps.clamp(0.0, 144.0))
@@ -881,24 +1428,40 @@ pub struct PlayerClientData<G: GameArenaService> {
     pub(crate) status: ClientStatus<G>,
     /// Discord user id.
     pub(crate) discord_id: Option<NonZeroU64>,
+    /// How the player proved their identity, or [`None`] if they are anonymous.
+    pub(crate) login_type: Option<LoginType>,
     /// Ip address.
     pub(crate) ip_address: IpAddr,
+    /// Hash of the client files served when the client last authenticated (its version).
+    pub(crate) client_hash: u64,
     /// Is moderator for in-game chat?
     pub moderator: bool,
     /// Previous database item.
     pub(crate) session_item: Option<SessionItem>,
+    /// Account in storage, if signed in and storage is enabled.
+    pub(crate) account: Option<AccountItem>,
+    /// Previous storage item.
+    pub(crate) account_item: Option<AccountItem>,
     /// Metrics-related information associated with each client.
     pub(crate) metrics: ClientMetricData<G>,
     /// Invitation-related information associated with each client.
     pub(crate) invitation: ClientInvitationData,
     /// Chat-related information associated with each client.
     pub(crate) chat: ClientChatData,
+    /// Friend-related information associated with each client.
+    pub(crate) friend: ClientFriendData,
+    /// Voice-related information associated with each client.
+    pub(crate) voice: ClientVoiceData,
     /// Team-related information associated with each client.
     pub(crate) team: ClientTeamData,
     /// Players this client has reported.
     pub(crate) reported: HashSet<PlayerId>,
     /// Number of times sent error trace (in order to limit abuse).
     pub(crate) traces: u8,
+    /// Number of failures reported, for telemetry (in order to limit abuse).
+    pub(crate) failures: u8,
+    /// Whether to record the client's inputs, and game events involving them, in the trace log.
+    pub(crate) traced: bool,
     /// Game specific client data. Manually serialized
     pub(crate) data: AtomicRefCell<G::ClientData>,
 }
@@ -923,9 +1486,11 @@ impl<G: GameArenaService> PlayerClientData<G> {
     pub(crate) fn new(
         session_id: SessionId,
         metrics: ClientMetricData<G>,
-        invitation: Option<InvitationDto>,
+        invitation: Option<&Invitation>,
         discord_id: Option<NonZeroU64>,
+        login_type: Option<LoginType>,
         ip: IpAddr,
+        client_hash: u64,
         moderator: bool,
     ) -> Self {
         Self {
@@ -935,15 +1500,23 @@ impl<G: GameArenaService> PlayerClientData<G> {
                 expiry: Instant::now() + Duration::from_secs(10),
             },
             discord_id,
+            login_type,
             ip_address: ip,
+            client_hash,
             moderator,
             session_item: None,
+            account: None,
+            account_item: None,
             metrics,
             invitation: ClientInvitationData::new(invitation),
             chat: ClientChatData::default(),
+            friend: ClientFriendData::default(),
+            voice: ClientVoiceData::default(),
             team: ClientTeamData::default(),
             reported: Default::default(),
             traces: 0,
+            failures: 0,
+            traced: false,
             data: AtomicRefCell::new(G::ClientData::default()),
         }
     }
@@ -970,36 +1543,51 @@ impl<G: GameArenaService> Handler<ObserverMessage<Request<G::GameRequest>, Updat
         msg: ObserverMessage<Request<G::GameRequest>, Update<G::GameUpdate>>,
         _ctx: &mut Self::Context,
     ) {
+        let player_id = match &msg {
+            ObserverMessage::Register { player_id, .. }
+            | ObserverMessage::Unregister { player_id, .. }
+            | ObserverMessage::Request { player_id, .. }
+            | ObserverMessage::RoundTripTime { player_id, .. } => *player_id,
+        };
+        let arena = match self.player_arena_index(player_id) {
+            Some(index) => &mut self.arenas[index],
+            None => {
+                warn!("observer message for player not in any arena");
+                return;
+            }
+        };
+
         match msg {
             ObserverMessage::Register {
                 player_id,
                 observer,
                 ..
-            } => self.context_service.context.clients.register(
+            } => arena.context.clients.register(
                 player_id,
                 observer,
-                &mut self.context_service.context.players,
-                &mut self.context_service.context.teams,
-                &self.context_service.context.chat,
+                &mut arena.context.players,
+                &mut arena.context.teams,
+                &arena.context.chat,
                 &self.leaderboard,
-                &self.context_service.context.liveboard,
+                &arena.context.liveboard,
                 &mut self.metrics,
                 self.system.as_ref(),
-                self.context_service.context.arena_id,
+                &self.announcements,
+                &self.admin.news,
+                arena.context.arena_id,
                 self.server_id,
-                &mut self.context_service.service,
+                &mut arena.service,
             ),
             ObserverMessage::Unregister {
                 player_id,
                 observer,
-            } => self.context_service.context.clients.unregister(
-                player_id,
-                observer,
-                &self.context_service.context.players,
-            ),
+            } => arena
+                .context
+                .clients
+                .unregister(player_id, observer, &arena.context.players),
             ObserverMessage::Request { player_id, request } => {
-                let context = &mut self.context_service.context;
-                let service = &mut self.context_service.service;
+                let context = &mut arena.context;
+                let service = &mut arena.service;
                 match context.clients.handle_observer_request(
                     player_id,
                     request,
@@ -1009,8 +1597,12 @@ impl<G: GameArenaService> Handler<ObserverMessage<Request<G::GameRequest>, Updat
                     &mut context.players,
                     &mut context.teams,
                     &mut context.chat,
+                    &mut context.friends,
+                    &mut context.voice,
                     &mut self.invitations,
+                    &mut self.shadow_bans,
                     &mut self.metrics,
+                    &self.admin.alias_policy,
                 ) {
                     Ok(Some(message)) => {
                         let player = match context.players.borrow_player_mut(player_id) {
@@ -1041,11 +1633,10 @@ impl<G: GameArenaService> Handler<ObserverMessage<Request<G::GameRequest>, Updat
                     }
                 }
             }
-            ObserverMessage::RoundTripTime { player_id, rtt } => self
-                .context_service
+            ObserverMessage::RoundTripTime { player_id, rtt } => arena
                 .context
                 .clients
-                .handle_observer_rtt(player_id, rtt, &self.context_service.context.players),
+                .handle_observer_rtt(player_id, rtt, &arena.context.players),
         }
     }
 }
@@ -1059,6 +1650,8 @@ pub struct Authenticate {
     pub user_agent_id: Option<UserAgentId>,
     /// Referrer.
     pub referrer: Option<Referrer>,
+    /// Arena to join, if not the default. Ignored if resuming a session in another arena.
+    pub arena_id: Option<ArenaId>,
     /// Last valid credentials.
     pub arena_id_session_id: Option<(ArenaId, SessionId)>,
     /// Invitation?
@@ -1067,17 +1660,25 @@ pub struct Authenticate {
     pub oauth2_code: Option<Oauth2Code>,
 }
 
+/// Proof of a third-party identity, to be verified.
 pub enum Oauth2Code {
     Discord(String),
+    Google(String),
+    /// Not actually OAuth2, but an OpenID assertion (query string).
+    Steam(String),
 }
 
 impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
     type Result = ResponseActFuture<Self, Result<PlayerId, &'static str>>;
 
     fn handle(&mut self, mut msg: Authenticate, _ctx: &mut ActorContext<Self>) -> Self::Result {
-        let arena_id = self.context_service.context.arena_id;
-        let clients = &mut self.context_service.context.clients;
-        let players = &self.context_service.context.players;
+        let arena_index = self.arena_index(
+            msg.arena_id_session_id
+                .map(|(arena_id, _)| arena_id)
+                .or(msg.arena_id),
+        );
+        let arena_id = self.arenas[arena_index].context.arena_id;
+        let clients = &mut self.arenas[arena_index].context.clients;
 
         if clients
             .authenticate_rate_limiter
@@ -1088,6 +1689,8 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
             return Box::pin(fut::ready(Err("rate limit exceeded")));
         }
 
+        let players = &self.arenas[arena_index].context.players;
+
         // TODO: O(n) on players.
         let cached_session_id_player_id = msg
             .arena_id_session_id
@@ -1106,24 +1709,63 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
         let arena_id_session_id = msg.arena_id_session_id;
         let oauth2_code = std::mem::take(&mut msg.oauth2_code);
         let database = self.database();
+        let storage = self.storage;
         let discord_bot = self.discord_bot;
         let discord_oauth2 = self.discord_oauth2;
+        let google_oauth2 = self.google_oauth2;
+        let steam_openid = self.steam_openid;
+        // If the session already has an account, newly linked identities are linked to it.
+        let existing_user_id = cached_session_id_player_id.and_then(|(_, player_id)| {
+            let player = players.borrow_player(player_id)?;
+            let account = player.client()?.account.as_ref()?;
+            Some(account.user_id)
+        });
 
         Box::pin(
             async move {
-                let discord_id = if let Some((Oauth2Code::Discord(code), discord_oauth2)) =
-                    oauth2_code.zip(discord_oauth2)
-                {
-                    match discord_oauth2.authenticate(code).await {
-                        Ok(id) => Some(id),
-                        Err(e) => {
-                            warn!("{}", e);
-                            None
-                        }
+                let login = match oauth2_code {
+                    Some(Oauth2Code::Discord(code)) => match discord_oauth2 {
+                        Some(discord_oauth2) => Some(
+                            discord_oauth2
+                                .authenticate(code)
+                                .await
+                                .map(|id| (LoginType::Discord, id.to_string())),
+                        ),
+                        None => None,
+                    },
+                    Some(Oauth2Code::Google(code)) => match google_oauth2 {
+                        Some(google_oauth2) => Some(
+                            google_oauth2
+                                .authenticate(code)
+                                .await
+                                .map(|sub| (LoginType::Google, sub)),
+                        ),
+                        None => None,
+                    },
+                    Some(Oauth2Code::Steam(assertion)) => match steam_openid {
+                        Some(steam_openid) => Some(
+                            steam_openid
+                                .authenticate(assertion)
+                                .await
+                                .map(|id| (LoginType::Steam, id.to_string())),
+                        ),
+                        None => None,
+                    },
+                    None => None,
+                };
+                // Failing to log in falls back to anonymous play.
+                let login = match login {
+                    Some(Ok(login)) => Some(login),
+                    Some(Err(e)) => {
+                        warn!("{}", e);
+                        None
                     }
-                } else {
-                    None
+                    None => None,
                 };
+                let discord_id = login
+                    .as_ref()
+                    .filter(|(login_type, _)| *login_type == LoginType::Discord)
+                    .and_then(|(_, login_id)| login_id.parse::<NonZeroU64>().ok());
 
                 let is_moderator =
                     if let Some((discord_id, discord_bot)) = discord_id.zip(discord_bot) {
@@ -1138,6 +1780,20 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
                         false
                     };
 
+                let account =
+                    if let Some(((login_type, login_id), storage)) = login.as_ref().zip(storage) {
+                        match load_account(storage, *login_type, login_id, existing_user_id).await {
+                            Ok(account) => Some(account),
+                            Err(e) => {
+                                // Don't overwrite the account with an empty one.
+                                warn!("error getting account: {:?}", e);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
                 let session_item = if cached_session_id_player_id.is_some() {
                     // No need to load from database because session is in memory.
                     Result::Ok(None)
@@ -1148,17 +1804,15 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
                     Result::Ok(None)
                 };
 
-                (discord_id, is_moderator, session_item)
+                let login_type = login.map(|(login_type, _)| login_type);
+                (discord_id, login_type, is_moderator, account, session_item)
             }
             .into_actor(self)
             .map(
-                move |(discord_id, mut is_moderator, db_result), act, _ctx| {
+                move |(discord_id, login_type, mut is_moderator, account, db_result), act, _ctx| {
                     let invitation = msg
                         .invitation_id
-                        .and_then(|id| act.invitations.get(id).cloned());
-                    let invitation_dto = invitation.map(|i| InvitationDto {
-                        player_id: i.player_id,
-                    });
+                        .and_then(|id| act.invitations.click(id).cloned());
 
                     let mut client_metric_data = ClientMetricData::from(&msg);
 
@@ -1178,13 +1832,18 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
                         existing
                     } else {
                         let mut session_ids = HashSet::with_capacity(
-                            act.context_service.context.players.real_players,
+                            act.arenas
+                                .iter()
+                                .map(|arena| arena.context.players.real_players)
+                                .sum(),
                         );
 
                         // TODO: O(n) on players.
-                        for player in act.context_service.context.players.iter_borrow() {
-                            if let Some(client_data) = player.client() {
-                                session_ids.insert(client_data.session_id);
+                        for arena in &act.arenas {
+                            for player in arena.context.players.iter_borrow() {
+                                if let Some(client_data) = player.client() {
+                                    session_ids.insert(client_data.session_id);
+                                }
                             }
                         }
 
@@ -1197,7 +1856,8 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
 
                         let new_player_id = loop {
                             let player_id = PlayerId(generate_id());
-                            if !act.context_service.context.players.contains(player_id) {
+                            // Unique across arenas, so observer messages can find the arena.
+                            if act.player_arena_index(player_id).is_none() {
                                 break player_id;
                             }
                         };
@@ -1205,14 +1865,25 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
                         (new_session_id, new_player_id)
                     };
 
-                    match act.context_service.context.players.players.entry(player_id) {
+                    match act.arenas[arena_index]
+                        .context
+                        .players
+                        .players
+                        .entry(player_id)
+                    {
                         Entry::Occupied(mut occupied) => {
-                            if let Some(client) =
-                                occupied.get_mut().borrow_player_mut().client_mut()
-                            {
+                            let mut player = occupied.get_mut().borrow_player_mut();
+                            if let Some(account) = account {
+                                ClientRepo::restore_account(&mut *player, account);
+                            }
+                            if let Some(client) = player.client_mut() {
                                 client.metrics.date_renewed = get_unix_time_now();
                                 // Update the referrer, such that the correct snippet may be served.
                                 client.metrics.referrer = msg.referrer.or(client.metrics.referrer);
+                                client.client_hash = act.status.client_hash;
+                                if login_type.is_some() {
+                                    client.login_type = login_type;
+                                }
                                 if let Some(discord_id) = discord_id {
                                     client.discord_id = Some(discord_id);
                                     client.moderator = is_moderator;
@@ -1228,12 +1899,17 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
                             let client = PlayerClientData::new(
                                 session_id,
                                 client_metric_data,
-                                invitation_dto,
+                                invitation.as_ref(),
                                 discord_id,
+                                login_type,
                                 msg.ip_address,
+                                act.status.client_hash,
                                 is_moderator,
                             );
-                            let pd = PlayerData::new(player_id, Some(Box::new(client)));
+                            let mut pd = PlayerData::new(player_id, Some(Box::new(client)));
+                            if let Some(account) = account {
+                                ClientRepo::restore_account(&mut pd, account);
+                            }
                             let pt = Arc::new(PlayerTuple::new(pd));
                             vacant.insert(pt);
                         }
@@ -1245,3 +1921,94 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{ClientRepo, ClientStatus};
+    use crate::game_service::{real_player, MockGame};
+    use crate::infrastructure::Infrastructure;
+    use crate::player::{PlayerData, PlayerTuple};
+    use core_protocol::id::PlayerId;
+    use core_protocol::rpc::{ClientUpdate, Update};
+    use server_util::observer::ObserverUpdate;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    #[test]
+    fn hand_off() {
+        let mut infrastructure = Infrastructure::<MockGame>::test(2, None);
+        let player_id = real_player(0);
+        let mut receiver = infrastructure.test_connect(0, player_id);
+        let session_id = {
+            let mut player = infrastructure.arenas[0]
+                .context
+                .players
+                .borrow_player_mut(player_id)
+                .unwrap();
+            player.score = 42;
+            player.client().unwrap().session_id
+        };
+        let bot_id = PlayerId::nth_bot(0).unwrap();
+        infrastructure.arenas[0].context.players.insert(
+            bot_id,
+            Arc::new(PlayerTuple::new(PlayerData::new(bot_id, None))),
+        );
+
+        assert_eq!(
+            ClientRepo::hand_off(&mut infrastructure, player_id, 0, 0),
+            Err("same arena")
+        );
+        assert_eq!(
+            ClientRepo::hand_off(&mut infrastructure, real_player(1), 0, 1),
+            Err("nonexistent player")
+        );
+        assert_eq!(
+            ClientRepo::hand_off(&mut infrastructure, bot_id, 0, 1),
+            Err("not a real player")
+        );
+
+        // Keeps their session and score.
+        assert_eq!(
+            ClientRepo::hand_off(&mut infrastructure, player_id, 0, 1),
+            Ok(())
+        );
+        assert_eq!(infrastructure.player_arena_index(player_id), Some(1));
+        {
+            let player = infrastructure.arenas[1]
+                .context
+                .players
+                .borrow_player(player_id)
+                .unwrap();
+            assert_eq!(player.score, 42);
+            let client = player.client().unwrap();
+            assert_eq!(client.session_id, session_id);
+            assert!(matches!(client.status, ClientStatus::Connected { .. }));
+        }
+
+        // Welcomed into the new arena, over the same connection.
+        let arena_id = infrastructure.arenas[1].context.arena_id;
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(ObserverUpdate::Send {
+                message: Update::Client(ClientUpdate::SessionCreated { arena_id: id, .. }),
+            }) if id == arena_id
+        ));
+
+        // Disconnected players can't be handed off.
+        infrastructure.arenas[1]
+            .context
+            .players
+            .borrow_player_mut(player_id)
+            .unwrap()
+            .client_mut()
+            .unwrap()
+            .status = ClientStatus::Limbo {
+            expiry: Instant::now(),
+        };
+        assert_eq!(
+            ClientRepo::hand_off(&mut infrastructure, player_id, 1, 0),
+            Err("not connected")
+        );
+        assert_eq!(infrastructure.player_arena_index(player_id), Some(1));
+    }
+}
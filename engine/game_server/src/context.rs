@@ -3,13 +3,21 @@
 
 use crate::bot::BotRepo;
 use crate::chat::ChatRepo;
+use crate::chat_log::ChatLogConfig;
 use crate::client::ClientRepo;
+use crate::friend::FriendRepo;
 use crate::game_service::GameArenaService;
 use crate::liveboard::LiveboardRepo;
+use crate::match_archive::MatchArchiveRepo;
 use crate::player::PlayerRepo;
 use crate::team::TeamRepo;
-use core_protocol::id::ArenaId;
+use crate::voice::VoiceRepo;
+use core_protocol::dto::LiveboardDto;
+use core_protocol::id::{ArenaId, PlayerId};
+use core_protocol::UnixTime;
+use serde::Serialize;
 use server_util::rate_limiter::RateLimiterProps;
+use std::sync::Arc;
 
 /// Things that go along with every instance of a [`GameArenaService`].
 pub struct Context<G: GameArenaService> {
@@ -18,15 +26,18 @@ pub struct Context<G: GameArenaService> {
     pub(crate) clients: ClientRepo<G>,
     pub(crate) bots: BotRepo<G>,
     pub(crate) chat: ChatRepo<G>,
+    pub(crate) friends: FriendRepo<G>,
+    pub(crate) voice: VoiceRepo<G>,
     pub teams: TeamRepo<G>,
     pub(crate) liveboard: LiveboardRepo<G>,
+    pub(crate) matches: MatchArchiveRepo<G>,
 }
 
 impl<G: GameArenaService> Context<G> {
     pub fn new(
         arena_id: ArenaId,
         bots: BotRepo<G>,
-        chat_log: Option<String>,
+        chat_log: Option<ChatLogConfig>,
         trace_log: Option<String>,
         client_authenticate: RateLimiterProps,
     ) -> Self {
@@ -36,8 +47,41 @@ impl<G: GameArenaService> Context<G> {
             bots,
             players: PlayerRepo::new(),
             teams: TeamRepo::new(),
-            chat: ChatRepo::new(chat_log),
+            chat: ChatRepo::new(arena_id, chat_log),
+            friends: FriendRepo::new(),
+            voice: VoiceRepo::new(),
             liveboard: LiveboardRepo::new(),
+            matches: MatchArchiveRepo::new(),
         }
     }
+
+    /// Gets the current liveboard, highest score first.
+    pub fn liveboard(&self) -> &Arc<[LiveboardDto]> {
+        self.liveboard.get()
+    }
+
+    /// Archives the result of a match, such as a round of a ranked or tournament arena, so that
+    /// it appears in the match history of each logged-in participant. `participants` are the
+    /// player, their score, and the number of boats they sank, in any order.
+    pub fn record_match(
+        &mut self,
+        game_mode: &str,
+        date_started: UnixTime,
+        participants: impl IntoIterator<Item = (PlayerId, u32, u32)>,
+    ) {
+        self.matches.record(
+            self.arena_id,
+            game_mode,
+            date_started,
+            participants,
+            &self.players,
+        );
+    }
+
+    /// Records a game event involving a player in the trace log, if they are being traced (see
+    /// [`PlayerData::is_traced`][crate::player::PlayerData::is_traced]).
+    pub fn trace(&mut self, player_id: PlayerId, event: &impl Serialize) {
+        self.clients
+            .trace_event(player_id, event, self.arena_id, &self.players);
+    }
 }
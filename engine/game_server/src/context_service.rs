@@ -2,37 +2,174 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::bot::BotRepo;
+use crate::chat_log::ChatLogConfig;
 use crate::context::Context;
 use crate::game_service::GameArenaService;
 use crate::invitation::InvitationRepo;
 use crate::leaderboard::LeaderboardRepo;
 use crate::metric::MetricRepo;
-use core_protocol::dto::ServerDto;
-use core_protocol::id::{ArenaId, ServerId};
+use core_protocol::dto::{AnnouncementDto, ServerDto};
+use core_protocol::id::{AnnouncementId, ArenaId, ServerId};
 use server_util::rate_limiter::RateLimiterProps;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Settings that may differ between the arenas of one server.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ArenaConfig {
+    pub game_mode: Option<String>,
+    pub min_bots: Option<usize>,
+    pub max_bots: Option<usize>,
+    pub bot_percent: Option<usize>,
+    /// Initial world radius, if the game supports changing it.
+    pub radius: Option<f32>,
+    /// Seconds without input after which an alive player is warned that they are AFK.
+    pub afk_warning: Option<u64>,
+    /// Seconds without input after which an alive player is removed from play.
+    pub afk_timeout: Option<u64>,
+    /// Seconds per day/night cycle, or zero for perpetual day.
+    pub day_length: Option<u64>,
+    /// Whether teammates can damage each other.
+    pub friendly_fire: Option<FriendlyFire>,
+    /// Whether players' own weapons can damage them.
+    pub self_damage: Option<bool>,
+    /// Multiplier of damage from ramming.
+    pub ram_damage: Option<f32>,
+}
+
+/// Whether, and how, teammates can damage each other.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum FriendlyFire {
+    /// Teammates are immune to each other.
+    #[default]
+    Off,
+    /// Damage that would be done to a teammate is done to the attacker instead.
+    Reflected,
+    /// Teammates take damage like anyone else.
+    Full,
+}
+
+impl FromStr for FriendlyFire {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "off" => Self::Off,
+            "reflected" => Self::Reflected,
+            "full" => Self::Full,
+            _ => return Err(format!("expected off, reflected, or full, found {}", s)),
+        })
+    }
+}
+
+/// Parses e.g. `game_mode=ffa,min_bots=20,radius=5000,afk_timeout=300`. Omitted settings take their defaults.
+impl FromStr for ArenaConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn parse<T: FromStr>(key: &str, value: &str) -> Result<Option<T>, String> {
+            value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid {} {}", key, value))
+        }
+
+        let mut config = Self::default();
+        for setting in s.split(',').filter(|s| !s.is_empty()) {
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, found {}", setting))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "game_mode" => config.game_mode = Some(value.to_owned()),
+                "min_bots" => config.min_bots = parse(key, value)?,
+                "max_bots" => config.max_bots = parse(key, value)?,
+                "bot_percent" => config.bot_percent = parse(key, value)?,
+                "radius" => config.radius = parse(key, value)?,
+                "afk_warning" => config.afk_warning = parse(key, value)?,
+                "afk_timeout" => config.afk_timeout = parse(key, value)?,
+                "day_length" => config.day_length = parse(key, value)?,
+                "friendly_fire" => config.friendly_fire = parse(key, value)?,
+                "self_damage" => config.self_damage = parse(key, value)?,
+                "ram_damage" => config.ram_damage = parse(key, value)?,
+                _ => return Err(format!("unknown arena setting {}", key)),
+            }
+        }
+        Ok(config)
+    }
+}
 
 /// Contains a [`GameArenaService`] and the corresponding [`Context`].
 pub struct ContextService<G: GameArenaService> {
     pub context: Context<G>,
     pub service: G,
+    /// What the arena was created with, e.g. to open similar arenas.
+    pub(crate) config: ArenaConfig,
 }
 
 impl<G: GameArenaService> ContextService<G> {
     pub fn new(
         arena_id: ArenaId,
-        min_bots: Option<usize>,
-        max_bots: Option<usize>,
-        bot_percent: Option<usize>,
-        chat_log: Option<String>,
+        config: ArenaConfig,
+        chat_log: Option<ChatLogConfig>,
         trace_log: Option<String>,
+        content_pack: Option<&str>,
+        benchmark_budgets: Option<String>,
         client_authenticate: RateLimiterProps,
     ) -> Self {
-        let bots = BotRepo::new_from_options(min_bots, max_bots, bot_percent);
+        let bots = BotRepo::new_from_options(config.min_bots, config.max_bots, config.bot_percent);
+
+        let mut service = G::new(bots.min_bots);
+        if let Some(path) = content_pack {
+            if let Err(e) = service.load_content_pack(path) {
+                panic!("could not load content pack {}: {}", path, e);
+            }
+        }
+        if let Some(game_mode) = config.game_mode.as_deref() {
+            if let Err(e) = service.set_game_mode(game_mode) {
+                panic!("invalid game mode {}: {}", game_mode, e);
+            }
+        }
+        if let Some(radius) = config.radius {
+            if let Err(e) = service.set_radius(radius) {
+                panic!("invalid radius {}: {}", radius, e);
+            }
+        }
+        if config.afk_warning.is_some() || config.afk_timeout.is_some() {
+            let warning = config.afk_warning.map(Duration::from_secs);
+            let timeout = config.afk_timeout.map(Duration::from_secs);
+            if let Err(e) = service.set_afk_thresholds(warning, timeout) {
+                panic!("invalid AFK thresholds: {}", e);
+            }
+        }
+        if let Some(day_length) = config.day_length {
+            if let Err(e) = service.set_day_length(Duration::from_secs(day_length)) {
+                panic!("invalid day length {}: {}", day_length, e);
+            }
+        }
+        if config.friendly_fire.is_some()
+            || config.self_damage.is_some()
+            || config.ram_damage.is_some()
+        {
+            if let Err(e) = service.set_damage_rules(
+                config.friendly_fire,
+                config.self_damage,
+                config.ram_damage,
+            ) {
+                panic!("invalid damage rules: {}", e);
+            }
+        }
+        if let Some(budgets) = benchmark_budgets {
+            if let Err(e) = service.set_benchmark_budgets(&budgets) {
+                panic!("invalid benchmark budgets {}: {}", budgets, e);
+            }
+        }
 
         Self {
-            service: G::new(bots.min_bots),
+            service,
             context: Context::new(arena_id, bots, chat_log, trace_log, client_authenticate),
+            config,
         }
     }
 
@@ -43,6 +180,7 @@ impl<G: GameArenaService> ContextService<G> {
         metrics: &mut MetricRepo<G>,
         server_id: Option<ServerId>,
         server_delta: Option<(Arc<[ServerDto]>, Arc<[ServerId]>)>,
+        announcement_delta: Option<(Arc<[AnnouncementDto]>, Arc<[AnnouncementId]>)>,
     ) {
         // Spawn/de-spawn clients and bots.
         self.context.clients.prune(
@@ -65,6 +203,12 @@ impl<G: GameArenaService> ContextService<G> {
             &mut self.context.teams,
             metrics,
         );
+        invitations.update(
+            &mut self.service,
+            self.context.arena_id,
+            &self.context.players,
+            metrics,
+        );
 
         // Update clients and bots.
         self.context.clients.update(
@@ -74,6 +218,9 @@ impl<G: GameArenaService> ContextService<G> {
             &mut self.context.liveboard,
             leaderboard,
             server_delta,
+            announcement_delta,
+            self.context.arena_id,
+            server_id,
         );
         self.context
             .bots
@@ -91,3 +238,48 @@ impl<G: GameArenaService> ContextService<G> {
             .post_update(&mut self.service, &self.context.players);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::context_service::{ArenaConfig, FriendlyFire};
+
+    #[test]
+    fn parse_arena_config() {
+        assert_eq!("".parse::<ArenaConfig>(), Ok(ArenaConfig::default()));
+        assert_eq!(
+            "game_mode=ffa,min_bots=20,radius=5000".parse::<ArenaConfig>(),
+            Ok(ArenaConfig {
+                game_mode: Some(String::from("ffa")),
+                min_bots: Some(20),
+                radius: Some(5000.0),
+                ..ArenaConfig::default()
+            })
+        );
+        assert_eq!(
+            "afk_warning=60,afk_timeout=90"
+                .parse::<ArenaConfig>()
+                .map(|config| (config.afk_warning, config.afk_timeout)),
+            Ok((Some(60), Some(90)))
+        );
+        assert_eq!(
+            "day_length=0".parse::<ArenaConfig>(),
+            Ok(ArenaConfig {
+                day_length: Some(0),
+                ..ArenaConfig::default()
+            })
+        );
+        assert_eq!(
+            "friendly_fire=reflected,self_damage=true,ram_damage=0.5".parse::<ArenaConfig>(),
+            Ok(ArenaConfig {
+                friendly_fire: Some(FriendlyFire::Reflected),
+                self_damage: Some(true),
+                ram_damage: Some(0.5),
+                ..ArenaConfig::default()
+            })
+        );
+        assert!("friendly_fire=some".parse::<ArenaConfig>().is_err());
+        assert!("min_bots=lots".parse::<ArenaConfig>().is_err());
+        assert!("bots".parse::<ArenaConfig>().is_err());
+        assert!("color=blue".parse::<ArenaConfig>().is_err());
+    }
+}
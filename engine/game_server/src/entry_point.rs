@@ -5,15 +5,21 @@
 //! via web_socket.
 
 use crate::admin::ParameterizedAdminRequest;
+use crate::broadcast::{BroadcastQuery, BroadcastRequest, BROADCAST_PERIOD};
+use crate::chat_log::ChatLogConfig;
 use crate::client::{Authenticate, Oauth2Code};
+use crate::context_service::ArenaConfig;
 use crate::discord::{DiscordBotRepo, DiscordOauth2Repo};
 use crate::game_service::GameArenaService;
+use crate::google::{GoogleLoginQuery, GoogleOauth2Repo};
 use crate::infrastructure::Infrastructure;
 use crate::leaderboard::LeaderboardRequest;
 use crate::options::Options;
 use crate::static_files::{static_size_and_hash, StaticFilesHandler};
 use crate::status::StatusRequest;
+use crate::steam::SteamOpenIdRepo;
 use crate::system::{SystemRepo, SystemRequest};
+use crate::webhook::DiscordWebhookRepo;
 use actix::Actor;
 use axum::body::{boxed, Empty, Full, HttpBody};
 use axum::extract::ws::{CloseCode, CloseFrame, Message};
@@ -22,6 +28,7 @@ use axum::headers::HeaderName;
 use axum::http::header::CACHE_CONTROL;
 use axum::http::uri::{Authority, Scheme};
 use axum::http::{HeaderValue, Method, Response, StatusCode, Uri};
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Redirect};
 use axum::routing::get;
 use axum::{Json, Router};
@@ -43,6 +50,7 @@ use server_util::observer::{ObserverMessage, ObserverUpdate};
 use server_util::os::set_open_file_limit;
 use server_util::rate_limiter::{RateLimiterProps, RateLimiterState};
 use server_util::user_agent::UserAgent;
+use std::collections::HashSet;
 use std::convert::TryInto;
 use std::net::SocketAddr;
 use std::str::FromStr;
@@ -111,16 +119,51 @@ pub fn entry_point<G: GameArenaService>(game_client: MiniCdn, browser_router: bo
         let discord_guild_id = options.discord_guild_id;
         let discord_bot = options.discord_bot_token.and_then(|t| DiscordBotRepo::new(discord_guild_id, &t));
         let discord_client_id = options.discord_client_id;
+        let discord_webhook_url = options.discord_webhook_url;
+        let discord_webhook = (!discord_webhook_url.is_empty())
+            .then(|| &*Box::leak(Box::new(DiscordWebhookRepo::new(discord_webhook_url))));
         let domain = options.domain.map(|domain| &*Box::leak(domain.into_boxed_str()));
+        let content_pack = options.content_pack.map(|path| &*Box::leak(path.into_boxed_str()));
+        // Only the files that the content pack consisted of at startup are served.
+        let content_files: &'static HashSet<String> = Box::leak(Box::new(content_pack
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+            .map(|entries| entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| !name.starts_with('.'))
+                .collect())
+            .unwrap_or_default()));
+        let broadcast_token = options.broadcast_token.as_deref().map(|token| &*Box::leak(token.to_owned().into_boxed_str()));
+        let chat_log_format = options.chat_log_format;
+        let chat_log_max_megabytes = options.chat_log_max_megabytes;
+        let chat_log_max_hours = options.chat_log_max_hours;
+        let chat_log_retention_days = options.chat_log_retention_days;
+        let chat_log = options.chat_log.map(|path| ChatLogConfig {
+            path,
+            format: chat_log_format,
+            max_bytes: chat_log_max_megabytes.map(|megabytes| megabytes * 1_000_000),
+            max_age: chat_log_max_hours.map(|hours| Duration::from_secs(hours * 60 * 60)),
+            retention: chat_log_retention_days.map(|days| Duration::from_secs(days * 24 * 60 * 60)),
+        });
+        let login_redirect_url = domain
+            .filter(|_| cfg!(not(debug_assertions)))
+            .map(|d| format!("https://{d}"))
+            .unwrap_or_else(|| format!("http://localhost:{http_port}"));
         let discord_oauth2 = options.discord_client_secret
             .map(|client_secret| &*Box::leak(Box::new(DiscordOauth2Repo::new(
                 discord_client_id,
                 client_secret,
-                domain
-                    .filter(|_| cfg!(not(debug_assertions)))
-                    .map(|d| format!("https://{d}"))
-                    .unwrap_or_else(|| format!("http://localhost:{http_port}"))
+                login_redirect_url.clone(),
+            ))));
+        let google_oauth2 = options.google_client_id.zip(options.google_client_secret)
+            .map(|(client_id, client_secret)| &*Box::leak(Box::new(GoogleOauth2Repo::new(
+                client_id,
+                client_secret,
+                login_redirect_url.clone(),
             ))));
+        let steam_openid = options.steam_login
+            .then(|| &*Box::leak(Box::new(SteamOpenIdRepo::new(login_redirect_url))));
 
         // println!("{:?}", discord_bot.as_ref().unwrap().send_message("", "", None).await);
 
@@ -130,14 +173,35 @@ pub fn entry_point<G: GameArenaService>(game_client: MiniCdn, browser_router: bo
                 system,
                 discord_bot,
                 discord_oauth2,
+                discord_webhook,
+                google_oauth2,
+                steam_openid,
                 static_hash,
                 region_id,
                 options.database_read_only,
-                options.min_bots,
-                options.max_bots,
-                options.bot_percent,
-                options.chat_log,
+                options.storage_url,
+                std::iter::once(ArenaConfig {
+                    game_mode: options.game_mode,
+                    min_bots: options.min_bots,
+                    max_bots: options.max_bots,
+                    bot_percent: options.bot_percent,
+                    radius: None,
+                    afk_warning: options.afk_warning,
+                    afk_timeout: options.afk_timeout,
+                    day_length: options.day_length,
+                    friendly_fire: options.friendly_fire,
+                    self_damage: options.self_damage,
+                    ram_damage: options.ram_damage,
+                })
+                .chain(options.arenas)
+                .collect(),
+                options.arena_max_players,
+                options.arena_min_players,
+                options.max_arenas,
+                chat_log,
                 options.trace_log,
+                content_pack,
+                options.benchmark_budgets,
                 Arc::clone(&game_client),
                 &ALLOW_WEB_SOCKET_JSON,
                 options.admin_config_file,
@@ -159,6 +223,7 @@ pub fn entry_point<G: GameArenaService>(game_client: MiniCdn, browser_router: bo
         let admin_srv = srv.to_owned();
         let leaderboard_srv = srv.to_owned();
         let status_srv = srv.to_owned();
+        let broadcast_srv = srv.to_owned();
         let system_srv = srv.to_owned();
 
         #[cfg(not(debug_assertions))]
@@ -197,12 +262,49 @@ pub fn entry_point<G: GameArenaService>(game_client: MiniCdn, browser_router: bo
 
         let app = Router::new()
             .fallback_service(get(StaticFilesHandler{cdn: game_client, prefix: "", browser_router}))
+            .route("/content/:hash/:file", get(async move |axum::extract::Path((_hash, file)): axum::extract::Path<(String, String)>| {
+                // The hash in the path only serves to bust caches, as there is one pack at a time.
+                let not_found = || Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(boxed(Full::from("404 Not Found")))
+                    .unwrap();
+                // Never join anything but a plain file name, that is part of the pack, onto the
+                // directory, lest paths like `../` escape it.
+                let plain = matches!(
+                    std::path::Path::new(&file).components().collect::<Vec<_>>()[..],
+                    [std::path::Component::Normal(_)]
+                );
+                let dir = if let Some(dir) = content_pack.filter(|_| plain && content_files.contains(&file)) {
+                    dir
+                } else {
+                    return not_found();
+                };
+                match tokio::fs::read(std::path::Path::new(dir).join(&file)).await {
+                    Ok(bytes) => Response::builder()
+                        .header(CACHE_CONTROL, HeaderValue::from_static("max-age=31536000, immutable"))
+                        .body(boxed(Full::from(bytes)))
+                        .unwrap(),
+                    Err(_) => not_found(),
+                }
+            }))
             .route("/oauth2/discord", get(async move || {
                 discord_oauth2.map(|oauth2| oauth2.redirect().into_response()).unwrap_or_else(|| Response::builder()
                     .status(StatusCode::NOT_FOUND)
                     .body(boxed(Full::from("404 Not Found")))
                     .unwrap())
             }))
+            .route("/oauth2/google", get(async move |Query(query): Query<GoogleLoginQuery>| {
+                google_oauth2.map(|oauth2| oauth2.redirect(query.state.as_deref()).into_response()).unwrap_or_else(|| Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(boxed(Full::from("404 Not Found")))
+                    .unwrap())
+            }))
+            .route("/openid/steam", get(async move || {
+                steam_openid.map(|openid| openid.redirect().into_response()).unwrap_or_else(|| Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(boxed(Full::from("404 Not Found")))
+                    .unwrap())
+            }))
             .route("/ws", axum::routing::get(async move |upgrade: WebSocketUpgrade, ConnectInfo(addr): ConnectInfo<SocketAddr>, user_agent: Option<TypedHeader<axum::headers::UserAgent>>, Query(query): Query<WebSocketQuery>| {
                 let user_agent_id = user_agent
                     .map(|h| UserAgent::new(h.as_str()))
@@ -213,9 +315,14 @@ pub fn entry_point<G: GameArenaService>(game_client: MiniCdn, browser_router: bo
                     ip_address: addr.ip(),
                     referrer: query.referrer,
                     user_agent_id,
+                    arena_id: query.arena_id,
                     arena_id_session_id: query.arena_id.zip(query.session_id),
                     invitation_id: query.invitation_id,
-                    oauth2_code: query.login_id.filter(|id| id.len() <= 2048 && login_type == Some(LoginType::Discord)).map(Oauth2Code::Discord),
+                    oauth2_code: login_type.zip(query.login_id).and_then(|(login_type, id)| match login_type {
+                        LoginType::Discord => (id.len() <= 2048).then_some(Oauth2Code::Discord(id)),
+                        LoginType::Google => (id.len() <= 2048).then_some(Oauth2Code::Google(id)),
+                        LoginType::Steam => (id.len() <= SteamOpenIdRepo::MAX_ASSERTION_LEN).then_some(Oauth2Code::Steam(id)),
+                    }),
                 };
 
                 const MAX_MESSAGE_SIZE: usize = 32768;
@@ -490,6 +597,36 @@ pub fn entry_point<G: GameArenaService>(game_client: MiniCdn, browser_router: bo
                     }
                 }
             }))
+            .route("/broadcast", get(move |Query(query): Query<BroadcastQuery>| {
+                let srv = broadcast_srv.to_owned();
+                debug!("received broadcast request");
+
+                async move {
+                    let token = if let Some(token) = broadcast_token {
+                        token
+                    } else {
+                        return Err(StatusCode::NOT_FOUND.into_response());
+                    };
+                    // Avoid timing side channel attack that could be used to get the token.
+                    if !constant_time_eq::constant_time_eq(token.as_bytes(), query.token.as_bytes()) {
+                        return Err(StatusCode::UNAUTHORIZED.into_response());
+                    }
+
+                    // Server-sent events, throttled to one per period, until the client leaves.
+                    let arena_id = query.arena_id;
+                    let events = futures::stream::unfold(true, move |first| {
+                        let srv = srv.clone();
+                        async move {
+                            if !first {
+                                tokio::time::sleep(BROADCAST_PERIOD).await;
+                            }
+                            let broadcast = srv.send(BroadcastRequest { arena_id }).await.ok()?;
+                            Some((Event::default().json_data(broadcast), false))
+                        }
+                    });
+                    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+                }
+            }))
             .route("/admin/", admin_router.clone())
             .route("/admin/*path", admin_router)
             .layer(ServiceBuilder::new()
@@ -0,0 +1,390 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::client::{ClientStatus, PlayerClientData};
+use crate::game_service::GameArenaService;
+use crate::player::PlayerRepo;
+use core_protocol::dto::{FriendDto, PresenceDto};
+use core_protocol::id::{ArenaId, InvitationId, PlayerId, ServerId};
+use core_protocol::name::PlayerAlias;
+use core_protocol::rpc::{FriendRequest, FriendUpdate};
+use std::marker::PhantomData;
+
+/// Component of [`Context`] dedicated to friends and their presence.
+///
+/// Friends are identified by alias, as player ids change every session, so friendship is lost if
+/// the friend changes their alias. Presence is only known for friends on the same server; others
+/// appear offline.
+pub struct FriendRepo<G> {
+    _spooky: PhantomData<G>,
+}
+
+/// Friend related data stored in client.
+#[derive(Debug, Default)]
+pub struct ClientFriendData {
+    /// In the order they were added.
+    friends: Vec<PlayerAlias>,
+    /// For diffing.
+    previous: Option<Box<[FriendDto]>>,
+}
+
+impl ClientFriendData {
+    /// Call when it is reasonable to assume client has forgotten state.
+    pub fn forget_state(&mut self) {
+        self.previous = None;
+    }
+
+    /// Whether the player befriended another player.
+    fn befriended(&self, alias: PlayerAlias) -> bool {
+        self.friends.contains(&alias)
+    }
+}
+
+impl<G: GameArenaService> FriendRepo<G> {
+    /// Maximum number of friends per player, to bound the cost of computing presence.
+    const MAX_FRIENDS: usize = 64;
+
+    pub fn new() -> Self {
+        Self {
+            _spooky: PhantomData,
+        }
+    }
+
+    /// Whether a player with `alias` may befriend a player with `friend_alias`.
+    fn can_befriend(alias: PlayerAlias, friend_alias: PlayerAlias) -> Result<(), &'static str> {
+        if friend_alias == alias {
+            Err("cannot befriend self")
+        } else if friend_alias == G::default_alias() {
+            // Would befriend everyone who didn't choose an alias.
+            Err("cannot befriend player without alias")
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Befriends an online player by id. Friendship is one-way.
+    fn add_friend(
+        &mut self,
+        req_player_id: PlayerId,
+        friend_player_id: PlayerId,
+        players: &PlayerRepo<G>,
+    ) -> Result<FriendUpdate, &'static str> {
+        if req_player_id == friend_player_id {
+            return Err("cannot befriend self");
+        }
+
+        if friend_player_id.is_bot() {
+            return Err("cannot befriend bot");
+        }
+
+        let friend_alias = players
+            .borrow_player(friend_player_id)
+            .ok_or("friend player doesn't exist")?
+            .alias();
+
+        let mut req_player = players
+            .borrow_player_mut(req_player_id)
+            .ok_or("req player doesn't exist")?;
+
+        Self::can_befriend(req_player.alias(), friend_alias)?;
+
+        let req_client = req_player
+            .client_mut()
+            .ok_or("only clients can have friends")?;
+
+        if req_client.friend.befriended(friend_alias) {
+            return Err("already friends");
+        }
+
+        if req_client.friend.friends.len() >= Self::MAX_FRIENDS {
+            return Err("too many friends");
+        }
+
+        req_client.friend.friends.push(friend_alias);
+        Ok(FriendUpdate::Added(friend_alias))
+    }
+
+    /// Befriends an online player by alias.
+    fn add_friend_by_alias(
+        &mut self,
+        req_player_id: PlayerId,
+        alias: PlayerAlias,
+        players: &PlayerRepo<G>,
+    ) -> Result<FriendUpdate, &'static str> {
+        // Must not hold a borrow of the requesting player while looking up others.
+        let friend_player_id = players
+            .iter_borrow()
+            .find(|p| p.player_id != req_player_id && p.client().is_some() && p.alias() == alias)
+            .map(|p| p.player_id)
+            .ok_or("no player with that alias")?;
+
+        self.add_friend(req_player_id, friend_player_id, players)
+    }
+
+    /// Stops befriending another player.
+    fn remove_friend(
+        &mut self,
+        req_player_id: PlayerId,
+        friend_alias: PlayerAlias,
+        players: &PlayerRepo<G>,
+    ) -> Result<FriendUpdate, &'static str> {
+        let mut req_player = players
+            .borrow_player_mut(req_player_id)
+            .ok_or("req player doesn't exist")?;
+
+        let req_client = req_player
+            .client_mut()
+            .ok_or("only clients can have friends")?;
+
+        let index = req_client
+            .friend
+            .friends
+            .iter()
+            .position(|&alias| alias == friend_alias)
+            .ok_or("not friends")?;
+
+        req_client.friend.friends.remove(index);
+        Ok(FriendUpdate::Removed(friend_alias))
+    }
+
+    /// Restores a friends list that the client persisted.
+    fn set_friends(
+        &mut self,
+        req_player_id: PlayerId,
+        friend_aliases: Box<[PlayerAlias]>,
+        players: &PlayerRepo<G>,
+    ) -> Result<FriendUpdate, &'static str> {
+        let mut req_player = players
+            .borrow_player_mut(req_player_id)
+            .ok_or("req player doesn't exist")?;
+
+        let alias = req_player.alias();
+        let req_client = req_player
+            .client_mut()
+            .ok_or("only clients can have friends")?;
+
+        let friends = &mut req_client.friend.friends;
+        friends.clear();
+        for friend_alias in friend_aliases.into_vec() {
+            if friends.len() >= Self::MAX_FRIENDS {
+                break;
+            }
+            if Self::can_befriend(alias, friend_alias).is_ok() && !friends.contains(&friend_alias) {
+                friends.push(friend_alias);
+            }
+        }
+
+        Ok(FriendUpdate::Set)
+    }
+
+    pub fn handle_friend_request(
+        &mut self,
+        req_player_id: PlayerId,
+        request: FriendRequest,
+        players: &PlayerRepo<G>,
+    ) -> Result<FriendUpdate, &'static str> {
+        match request {
+            FriendRequest::Add(player_id) => self.add_friend(req_player_id, player_id, players),
+            FriendRequest::AddAlias(alias) => {
+                self.add_friend_by_alias(req_player_id, alias, players)
+            }
+            FriendRequest::Remove(alias) => self.remove_friend(req_player_id, alias, players),
+            FriendRequest::Set(aliases) => self.set_friends(req_player_id, aliases, players),
+        }
+    }
+
+    /// The invitation of a friend, which lets anyone who has it join their arena (and team). Only
+    /// shared if the friend befriended the player in return, as friendship is one-way.
+    fn shared_invitation(friend: &PlayerClientData<G>, alias: PlayerAlias) -> Option<InvitationId> {
+        friend
+            .invitation
+            .invitation_created
+            .filter(|_| friend.friend.befriended(alias))
+    }
+
+    /// Gets presence of player's friends, if it changed.
+    pub fn player_delta(
+        player_id: PlayerId,
+        players: &PlayerRepo<G>,
+        arena_id: ArenaId,
+        server_id: Option<ServerId>,
+    ) -> Option<FriendUpdate> {
+        let friends: Box<[FriendDto]> = {
+            let player = players.borrow_player(player_id)?;
+            let alias = player.alias();
+            let client = player.client()?;
+            client
+                .friend
+                .friends
+                .iter()
+                .map(|&friend_alias| {
+                    // If several online players share the alias, any of them will do.
+                    let online = players.iter_borrow().find(|friend| {
+                        friend.player_id != player_id
+                            && friend.alias() == friend_alias
+                            && friend.client().map_or(false, |c| {
+                                matches!(c.status, ClientStatus::Connected { .. })
+                            })
+                    });
+                    FriendDto {
+                        alias: friend_alias,
+                        presence: online.as_ref().map(|friend| PresenceDto {
+                            arena_id,
+                            server_id,
+                            invitation_id: friend
+                                .client()
+                                .and_then(|c| Self::shared_invitation(c, alias)),
+                        }),
+                    }
+                })
+                .collect()
+        };
+
+        let mut player = players.borrow_player_mut(player_id)?;
+        let client = player.client_mut()?;
+        if client.friend.previous.as_ref() == Some(&friends) {
+            None
+        } else {
+            client.friend.previous = Some(friends.clone());
+            Some(FriendUpdate::Presence(friends))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{Authenticate, PlayerClientData};
+    use crate::friend::FriendRepo;
+    use crate::game_service::{real_player, MockGame};
+    use crate::player::{PlayerData, PlayerRepo, PlayerTuple};
+    use core_protocol::id::{ArenaId, InvitationId, PlayerId, SessionId};
+    use core_protocol::name::PlayerAlias;
+    use core_protocol::rpc::{FriendRequest, FriendUpdate};
+    use server_util::generate_id::generate_id_64;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
+
+    fn insert_client(players: &mut PlayerRepo<MockGame>, player_id: PlayerId, alias: &str) {
+        let ip_address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let metrics = (&Authenticate {
+            ip_address,
+            user_agent_id: None,
+            referrer: None,
+            arena_id: None,
+            arena_id_session_id: None,
+            invitation_id: None,
+            oauth2_code: None,
+        })
+            .into();
+        let mut client = PlayerClientData::new(
+            SessionId(generate_id_64()),
+            metrics,
+            None,
+            None,
+            None,
+            ip_address,
+            0,
+            false,
+        );
+        client.alias = PlayerAlias::new_unsanitized(alias);
+        players.insert(
+            player_id,
+            Arc::new(PlayerTuple::new(PlayerData::new(
+                player_id,
+                Some(Box::new(client)),
+            ))),
+        );
+    }
+
+    #[test]
+    fn friend_requests() {
+        let mut players = PlayerRepo::<MockGame>::new();
+        let mut friends = FriendRepo::<MockGame>::new();
+        let (a, b, c, d) = (
+            real_player(0),
+            real_player(1),
+            real_player(2),
+            real_player(3),
+        );
+        insert_client(&mut players, a, "Alice");
+        insert_client(&mut players, b, "Bob");
+        insert_client(&mut players, c, "Carol");
+        insert_client(&mut players, d, "Guest");
+        let alias = PlayerAlias::new_unsanitized;
+        let mut request =
+            |player_id, request| friends.handle_friend_request(player_id, request, &players);
+
+        assert!(request(a, FriendRequest::Add(a)).is_err());
+        assert!(request(a, FriendRequest::Add(PlayerId::nth_bot(0).unwrap())).is_err());
+        assert!(request(a, FriendRequest::Add(d)).is_err());
+        assert!(matches!(
+            request(a, FriendRequest::Add(b)),
+            Ok(FriendUpdate::Added(added)) if added == alias("Bob")
+        ));
+        assert!(request(a, FriendRequest::AddAlias(alias("Bob"))).is_err());
+        assert!(request(a, FriendRequest::AddAlias(alias("Dave"))).is_err());
+        assert!(request(a, FriendRequest::Remove(alias("Carol"))).is_err());
+        assert!(matches!(
+            request(a, FriendRequest::Remove(alias("Bob"))),
+            Ok(FriendUpdate::Removed(removed)) if removed == alias("Bob")
+        ));
+
+        // Restoring a persisted list drops invalid and duplicate entries, but keeps friends who
+        // aren't online, such as those who haven't connected yet this session.
+        assert!(request(
+            a,
+            FriendRequest::Set(["Alice", "Bob", "Bob", "Guest", "Dave"].map(alias).into())
+        )
+        .is_ok());
+        let player = players.borrow_player(a).unwrap();
+        assert_eq!(
+            player.client().unwrap().friend.friends,
+            vec![alias("Bob"), alias("Dave")]
+        );
+    }
+
+    #[test]
+    fn presence() {
+        let mut players = PlayerRepo::<MockGame>::new();
+        let mut friends = FriendRepo::<MockGame>::new();
+        let (a, b) = (real_player(0), real_player(1));
+        insert_client(&mut players, a, "Alice");
+        insert_client(&mut players, b, "Bob");
+        let arena_id = ArenaId(NonZeroU32::new(1).unwrap());
+
+        friends
+            .handle_friend_request(a, FriendRequest::Add(b), &players)
+            .unwrap();
+        let update = FriendRepo::player_delta(a, &players, arena_id, None);
+        assert!(matches!(
+            update,
+            Some(FriendUpdate::Presence(ref dtos))
+                if dtos.len() == 1 && dtos[0].alias == PlayerAlias::new_unsanitized("Bob")
+        ));
+        // Unchanged, so not sent again.
+        assert!(FriendRepo::player_delta(a, &players, arena_id, None).is_none());
+
+        // Invitations are only shared with friends who were befriended in return.
+        let invitation_id = InvitationId(NonZeroU32::new(1).unwrap());
+        players
+            .borrow_player_mut(b)
+            .unwrap()
+            .client_mut()
+            .unwrap()
+            .invitation
+            .invitation_created = Some(invitation_id);
+        let shared = |players: &PlayerRepo<MockGame>| {
+            let player = players.borrow_player(b).unwrap();
+            FriendRepo::<MockGame>::shared_invitation(
+                player.client().unwrap(),
+                PlayerAlias::new_unsanitized("Alice"),
+            )
+        };
+        assert_eq!(shared(&players), None);
+        friends
+            .handle_friend_request(b, FriendRequest::Add(a), &players)
+            .unwrap();
+        assert_eq!(shared(&players), Some(invitation_id));
+    }
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::context::Context;
+use crate::context_service::FriendlyFire;
 use crate::player::{PlayerRepo, PlayerTuple};
+use core_protocol::dto::RateLimitedAction;
 use core_protocol::id::{GameId, PlayerId, TeamId};
 use core_protocol::name::PlayerAlias;
 use serde::de::DeserializeOwned;
@@ -37,7 +39,7 @@ pub trait GameArenaService: 'static + Unpin + Sized + Send + Sync {
     type Bot: 'static + Bot<Self>;
     type ClientData: 'static + Default + Debug + Unpin + Send + Sync;
     type GameUpdate: 'static + Sync + Send + Serialize;
-    type GameRequest: 'static + DeserializeOwned + Send + Unpin;
+    type GameRequest: 'static + DeserializeOwned + Serialize + Send + Unpin;
     type PlayerData: 'static + Default + Unpin + Send + Sync + Debug;
     type PlayerExtension: 'static + Default + Unpin + Send + Sync;
 
@@ -53,6 +55,91 @@ pub trait GameArenaService: 'static + Unpin + Sized + Send + Sync {
         PlayerAlias::new_unsanitized("Guest")
     }
 
+    /// Called once at startup, if a content pack directory was specified.
+    fn load_content_pack(&mut self, path: &str) -> Result<(), String> {
+        let _ = path;
+        Err(String::from("content packs are not supported"))
+    }
+
+    /// Called once at startup, if a game mode was specified.
+    fn set_game_mode(&mut self, game_mode: &str) -> Result<(), String> {
+        let _ = game_mode;
+        Err(String::from("game modes are not supported"))
+    }
+
+    /// Called once at startup, if the arena's radius was specified.
+    fn set_radius(&mut self, radius: f32) -> Result<(), String> {
+        let _ = radius;
+        Err(String::from("custom radii are not supported"))
+    }
+
+    /// Called once at startup, if either AFK threshold was specified. Omitted thresholds keep
+    /// their defaults.
+    fn set_afk_thresholds(
+        &mut self,
+        warning: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> Result<(), String> {
+        let _ = (warning, timeout);
+        Err(String::from("AFK detection is not supported"))
+    }
+
+    /// Called once at startup, if the length of the day/night cycle was specified. Zero means
+    /// perpetual day.
+    fn set_day_length(&mut self, day_length: Duration) -> Result<(), String> {
+        let _ = day_length;
+        Err(String::from("day/night cycles are not supported"))
+    }
+
+    /// Called once at startup, if any damage rules were specified. Omitted rules keep their
+    /// defaults.
+    fn set_damage_rules(
+        &mut self,
+        friendly_fire: Option<FriendlyFire>,
+        self_damage: Option<bool>,
+        ram_damage: Option<f32>,
+    ) -> Result<(), String> {
+        let _ = (friendly_fire, self_damage, ram_damage);
+        Err(String::from("damage rules are not supported"))
+    }
+
+    /// Called once at startup, if benchmark budgets were specified.
+    fn set_benchmark_budgets(&mut self, budgets: &str) -> Result<(), String> {
+        let _ = budgets;
+        Err(String::from("benchmark budgets are not supported"))
+    }
+
+    /// Why a player's score looks illegitimate, such as implausibly fast scoring, if it does. If
+    /// so, the score is quarantined for review instead of entering the persisted leaderboards.
+    fn score_anomaly(score: u32, player_data: &Self::PlayerData) -> Option<&'static str> {
+        let _ = (score, player_data);
+        None
+    }
+
+    /// Game-specific progress (e.g. unlocks) to store with the player's account, if any.
+    fn account_unlocks(player_data: &Self::PlayerData) -> Option<String> {
+        let _ = player_data;
+        None
+    }
+
+    /// Restores progress previously returned by [`Self::account_unlocks`], when a player signs in.
+    fn restore_account_unlocks(player_data: &mut Self::PlayerData, unlocks: &str) {
+        let _ = (player_data, unlocks);
+    }
+
+    /// Whether an invited player has progressed far enough for their inviter to be rewarded. If
+    /// this never returns true, referrals are never rewarded.
+    fn referral_qualified(player_data: &Self::PlayerData) -> bool {
+        let _ = player_data;
+        false
+    }
+
+    /// Rewards a player for inviting another player, who progressed far enough (see
+    /// [`Self::referral_qualified`]).
+    fn reward_referral(&mut self, player_tuple: &Arc<PlayerTuple<Self>>) {
+        let _ = player_tuple;
+    }
+
     /// Returning zero would disable teams.
     fn team_members_max(_players_online: usize) -> usize {
         10
@@ -75,6 +162,20 @@ pub trait GameArenaService: 'static + Unpin + Sized + Send + Sync {
         _players: &PlayerRepo<Self>,
     ) -> Option<Self::GameUpdate>;
 
+    /// Which rate limit, if any, applies to a command. Rate limited commands are replaced with
+    /// [`Self::rate_limited_remainder`] before reaching [`Self::player_command`].
+    fn rate_limited_action(command: &Self::GameRequest) -> Option<RateLimitedAction> {
+        let _ = command;
+        None
+    }
+
+    /// What remains of a command once the rate limited part is removed, e.g. steering without
+    /// firing, or [`None`] (the default) to drop the command entirely.
+    fn rate_limited_remainder(command: Self::GameRequest) -> Option<Self::GameRequest> {
+        let _ = command;
+        None
+    }
+
     /// Called when a player's [`TeamId`] changes.
     fn player_changed_team(
         &mut self,
@@ -120,6 +221,24 @@ pub trait GameArenaService: 'static + Unpin + Sized + Send + Sync {
     fn post_update(&mut self, context: &mut Context<Self>) {
         let _ = context;
     }
+
+    /// Game-specific state, such as objectives and recent kills, to include in the broadcast
+    /// feed (see [`crate::broadcast`]). [`None`] if not supported.
+    fn broadcast_state(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Cheap hash of the simulation state as of the latest tick, to detect divergence between
+    /// copies of the simulation. [`None`] if not supported.
+    fn checksum(&self) -> Option<u32> {
+        None
+    }
+
+    /// Where in the arena gameplay events happened since it started, as JSON, for the admin
+    /// interface. [`None`] if not supported.
+    fn heatmap(&self) -> Option<String> {
+        None
+    }
 }
 
 /// Implemented by game bots.
@@ -241,3 +360,9 @@ impl GameArenaService for MockGame {
 
     fn tick(&mut self, _context: &mut Context<Self>) {}
 }
+
+/// Gets the nth id that isn't reserved for bots or solo play.
+#[cfg(test)]
+pub fn real_player(n: u32) -> PlayerId {
+    PlayerId(std::num::NonZeroU32::new(u32::MAX - n).unwrap())
+}
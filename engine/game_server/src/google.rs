@@ -0,0 +1,127 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use axum::response::{IntoResponse, Redirect};
+use oauth2::{
+    basic::BasicClient, reqwest::async_http_client, AuthUrl, AuthorizationCode, ClientId,
+    ClientSecret, CsrfToken, RedirectUrl, Scope, TokenResponse, TokenUrl,
+};
+use serde::Deserialize;
+use std::time::Duration;
+
+pub struct GoogleOauth2Repo {
+    oauth2_client: BasicClient,
+    http_client: reqwest::Client,
+}
+
+impl GoogleOauth2Repo {
+    /// Prefixes the `state` query parameter passed back to the client, so that it can tell which
+    /// login the code is for. Followed by the random state that the client began the login with,
+    /// so that it can tell that it did (lest another site log the player in, i.e. CSRF).
+    pub const STATE_PREFIX: &'static str = "google.";
+    /// Where the client begins logins, by generating and remembering a random state.
+    const CLIENT_LOGIN_PATH: &'static str = "/#/login/google";
+
+    pub fn new(client_id: String, client_secret: String, redirect_url: String) -> Self {
+        let auth_url = String::from("https://accounts.google.com/o/oauth2/v2/auth");
+        let token_url = String::from("https://oauth2.googleapis.com/token");
+
+        let oauth2_client = BasicClient::new(
+            ClientId::new(client_id),
+            Some(ClientSecret::new(client_secret)),
+            AuthUrl::new(auth_url).unwrap(),
+            Some(TokenUrl::new(token_url).unwrap()),
+        )
+        .set_redirect_uri(RedirectUrl::new(redirect_url).unwrap());
+
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(4))
+            .build()
+            .unwrap();
+
+        Self {
+            oauth2_client,
+            http_client,
+        }
+    }
+
+    /// Redirects to Google with the client's random `state` or, if there isn't a valid one, to the
+    /// client so that it can generate one.
+    pub fn redirect(&self, state: Option<&str>) -> impl IntoResponse {
+        let state = if let Some(state) = state.filter(|state| Self::is_valid_state(state)) {
+            state
+        } else {
+            return Redirect::to(Self::CLIENT_LOGIN_PATH);
+        };
+        let (auth_url, _csrf_token) = self
+            .oauth2_client
+            .authorize_url(|| CsrfToken::new(format!("{}{}", Self::STATE_PREFIX, state)))
+            .add_scope(Scope::new("openid".to_string()))
+            .url();
+
+        Redirect::to(&auth_url.to_string())
+    }
+
+    /// Whether a state generated by the client looks random enough, i.e. is 32 hex digits.
+    fn is_valid_state(state: &str) -> bool {
+        state.len() == 32 && state.bytes().all(|b| b.is_ascii_hexdigit())
+    }
+
+    /// Returns the Google user's subject identifier.
+    pub async fn authenticate(&self, code: String) -> Result<String, String> {
+        let token = self
+            .oauth2_client
+            .exchange_code(AuthorizationCode::new(code))
+            .request_async(async_http_client)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // https://developers.google.com/identity/openid-connect/openid-connect#obtainuserinfo
+        #[derive(Debug, Deserialize)]
+        struct User {
+            sub: String,
+        }
+
+        let user: User = self
+            .http_client
+            .get("https://openidconnect.googleapis.com/v1/userinfo")
+            .timeout(Duration::from_secs(5))
+            .bearer_auth(token.access_token().secret())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .json::<User>()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if user.sub.is_empty() || user.sub.len() > 255 {
+            Err(String::from("invalid google subject"))
+        } else {
+            Ok(user.sub)
+        }
+    }
+}
+
+/// Query of the route that begins Google logins.
+#[derive(Debug, Deserialize)]
+pub struct GoogleLoginQuery {
+    /// Random state generated by the client.
+    pub state: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::google::GoogleOauth2Repo;
+
+    #[test]
+    fn is_valid_state() {
+        assert!(GoogleOauth2Repo::is_valid_state(
+            "0123456789abcdef0123456789ABCDEF"
+        ));
+        assert!(!GoogleOauth2Repo::is_valid_state("google"));
+        assert!(!GoogleOauth2Repo::is_valid_state(
+            "0123456789abcdef0123456789abcdeg"
+        ));
+        assert!(!GoogleOauth2Repo::is_valid_state(""));
+    }
+}
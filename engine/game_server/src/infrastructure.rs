@@ -2,22 +2,32 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::admin::AdminRepo;
+use crate::announcement::AnnouncementRepo;
+use crate::chat_log::ChatLogConfig;
 use crate::client::ClientRepo;
-use crate::context_service::ContextService;
+use crate::context_service::{ArenaConfig, ContextService};
 use crate::discord::{DiscordBotRepo, DiscordOauth2Repo};
 use crate::game_service::GameArenaService;
+use crate::google::GoogleOauth2Repo;
 use crate::invitation::InvitationRepo;
 use crate::leaderboard::LeaderboardRepo;
+use crate::match_archive::MatchArchiveRepo;
 use crate::metric::MetricRepo;
+use crate::scaling::ScalingRepo;
+use crate::shadow_ban::ShadowBanRepo;
 use crate::status::StatusRepo;
+use crate::steam::SteamOpenIdRepo;
 use crate::system::SystemRepo;
+use crate::webhook::{ArenaEvent, DiscordWebhookRepo};
 use actix::AsyncContext;
 use actix::{Actor, Context as ActorContext};
-use core_protocol::id::{ArenaId, RegionId, ServerId};
+use core_protocol::get_unix_time_now;
+use core_protocol::id::{ArenaId, PlayerId, RegionId, ServerId};
 use log::{error, info};
 use minicdn::MiniCdn;
 use server_util::database::Database;
 use server_util::rate_limiter::RateLimiterProps;
+use server_util::storage::{self, Storage};
 use std::num::NonZeroU32;
 use std::process;
 use std::sync::atomic::AtomicBool;
@@ -32,15 +42,27 @@ pub struct Infrastructure<G: GameArenaService> {
 
     /// API.
     pub(crate) database: &'static Database,
+    /// Optional account storage.
+    pub(crate) storage: Option<&'static dyn Storage>,
     pub(crate) system: Option<SystemRepo<G>>,
     pub(crate) discord_bot: Option<&'static DiscordBotRepo>,
     pub(crate) discord_oauth2: Option<&'static DiscordOauth2Repo>,
+    /// Where to announce arena events, if anywhere.
+    pub(crate) discord_webhook: Option<&'static DiscordWebhookRepo>,
+    pub(crate) google_oauth2: Option<&'static GoogleOauth2Repo>,
+    pub(crate) steam_openid: Option<&'static SteamOpenIdRepo>,
 
-    /// Game specific stuff. In the future, there could be multiple of these.
-    pub(crate) context_service: ContextService<G>,
+    /// Game specific stuff, one per arena. The first is the default arena.
+    pub(crate) arenas: Vec<ContextService<G>>,
+    /// Opens and retires arenas based on population.
+    pub(crate) scaling: ScalingRepo<G>,
 
     /// Shared invitations.
     pub(crate) invitations: InvitationRepo<G>,
+    /// Shared chat shadow bans.
+    pub(crate) shadow_bans: ShadowBanRepo<G>,
+    /// Shared announcements.
+    pub(crate) announcements: AnnouncementRepo,
     /// Shared admin interface.
     pub(crate) admin: AdminRepo<G>,
     /// Shared leaderboard.
@@ -65,6 +87,15 @@ impl<G: GameArenaService> Actor for Infrastructure<G> {
         ctx.set_mailbox_capacity(50);
 
         ctx.run_interval(Duration::from_secs_f32(G::TICK_PERIOD_SECS), Self::update);
+
+        if let Some(discord_webhook) = self.discord_webhook {
+            discord_webhook.post(
+                ArenaEvent::ServerRestart {
+                    server_id: self.server_id,
+                },
+                G::GAME_ID,
+            );
+        }
     }
 
     fn stopped(&mut self, _ctx: &mut Self::Context) {
@@ -82,23 +113,72 @@ impl<G: GameArenaService> Infrastructure<G> {
         system: Option<SystemRepo<G>>,
         discord_bot: Option<DiscordBotRepo>,
         discord_oauth2: Option<&'static DiscordOauth2Repo>,
+        discord_webhook: Option<&'static DiscordWebhookRepo>,
+        google_oauth2: Option<&'static GoogleOauth2Repo>,
+        steam_openid: Option<&'static SteamOpenIdRepo>,
         client_hash: u64,
         region_id: Option<RegionId>,
         database_read_only: bool,
-        min_bots: Option<usize>,
-        max_bots: Option<usize>,
-        bot_percent: Option<usize>,
-        chat_log: Option<String>,
+        storage_url: Option<String>,
+        arena_configs: Vec<ArenaConfig>,
+        arena_max_players: Option<usize>,
+        arena_min_players: Option<usize>,
+        max_arenas: usize,
+        chat_log: Option<ChatLogConfig>,
         trace_log: Option<String>,
+        content_pack: Option<&str>,
+        benchmark_budgets: Option<String>,
         game_client: Arc<RwLock<MiniCdn>>,
         allow_web_socket_json: &'static AtomicBool,
         admin_config_file: Option<String>,
         client_authenticate: RateLimiterProps,
     ) -> Self {
-        // TODO: If multiple arenas, generate randomly.
-        let arena_id = ArenaId(
-            NonZeroU32::new(server_id.map(|s| s.0.get()).unwrap_or(0) as u32 + 2000).unwrap(),
-        );
+        assert!(!arena_configs.is_empty(), "need at least one arena");
+        let content_pack = content_pack.map(String::from);
+        let new_arena = move |index: usize, config: ArenaConfig| {
+            // Additional arenas log to their own files.
+            let log_path = |path: &str| {
+                if index == 0 {
+                    path.to_owned()
+                } else {
+                    format!("{}.{}", path, index)
+                }
+            };
+            // Deterministic, so that sessions survive restarts.
+            let arena_id = ArenaId(
+                NonZeroU32::new(
+                    server_id.map(|s| s.0.get()).unwrap_or(0) as u32 + 2000 + index as u32 * 256,
+                )
+                .unwrap(),
+            );
+            ContextService::new(
+                arena_id,
+                config,
+                chat_log.as_ref().map(|config| ChatLogConfig {
+                    path: log_path(&config.path),
+                    ..config.clone()
+                }),
+                trace_log.as_deref().map(log_path),
+                content_pack.as_deref(),
+                benchmark_budgets.clone(),
+                client_authenticate,
+            )
+        };
+        let arenas: Vec<_> = arena_configs
+            .into_iter()
+            .enumerate()
+            .map(|(index, config)| new_arena(index, config))
+            .collect();
+
+        let storage = if let Some(storage_url) = storage_url {
+            let storage = storage::connect(&storage_url)
+                .await
+                .expect("could not connect to account storage");
+            info!("connected to account storage");
+            Some(&*Box::leak(storage))
+        } else {
+            None
+        };
 
         Self {
             server_id,
@@ -106,21 +186,29 @@ impl<G: GameArenaService> Infrastructure<G> {
             /// Leak the boxes, because static lifetime facilitates async code. This will probably
             /// only ever happen once, and it will last for the lifetime of the program.
             database: Box::leak(Box::new(Database::new(database_read_only).await)),
+            storage,
             system,
             discord_bot: discord_bot.map(|b| &*Box::leak(Box::new(b))),
             discord_oauth2,
+            discord_webhook,
+            google_oauth2,
+            steam_openid,
             admin: AdminRepo::new(game_client, admin_config_file, allow_web_socket_json),
-            context_service: ContextService::new(
-                arena_id,
-                min_bots,
-                max_bots,
-                bot_percent,
-                chat_log,
-                trace_log,
-                client_authenticate,
+            scaling: ScalingRepo::new(
+                arena_max_players,
+                arena_min_players,
+                max_arenas,
+                arenas.len(),
+                Box::new(new_arena),
             ),
+            arenas,
             invitations: InvitationRepo::new(),
-            leaderboard: LeaderboardRepo::new(),
+            shadow_bans: ShadowBanRepo::new(),
+            announcements: AnnouncementRepo::new(),
+            // If players can log in, only those that did may earn a place on the leaderboard.
+            leaderboard: LeaderboardRepo::new(
+                discord_oauth2.is_some() || google_oauth2.is_some() || steam_openid.is_some(),
+            ),
             metrics: MetricRepo::new(),
             status: StatusRepo::new(client_hash),
             last_update: Instant::now(),
@@ -139,15 +227,34 @@ impl<G: GameArenaService> Infrastructure<G> {
 
         let status = &self.status;
         let server_delta = self.system.as_mut().and_then(|system| system.delta(status));
-        self.context_service.update(
-            &mut self.leaderboard,
-            &mut self.invitations,
-            &mut self.metrics,
-            self.server_id,
-            server_delta,
+        let unix_now = get_unix_time_now();
+        self.announcements.prune(
+            unix_now,
+            self.arenas.iter().map(|arena| arena.context.arena_id),
         );
+        for arena in &mut self.arenas {
+            let announcement_delta = self.announcements.delta(arena.context.arena_id, unix_now);
+            arena.update(
+                &mut self.leaderboard,
+                &mut self.invitations,
+                &mut self.metrics,
+                self.server_id,
+                server_delta.clone(),
+                announcement_delta,
+            );
+        }
         self.leaderboard.clear_deltas();
         self.status.health.record_tick(G::TICK_PERIOD_SECS);
+        ScalingRepo::update(self);
+
+        // Only does anything when a match ended.
+        MatchArchiveRepo::update_to_storage(self, ctx);
+        // Only does anything once, and when shadow bans change, respectively.
+        ShadowBanRepo::update_from_storage(self, ctx);
+        ShadowBanRepo::update_to_storage(self, ctx);
+        // Only does anything when accounts come online, and when referrals change, respectively.
+        InvitationRepo::update_from_storage(self, ctx);
+        InvitationRepo::update_to_storage(self, ctx);
 
         // These are all rate-limited internally.
         LeaderboardRepo::update_to_database(self, ctx);
@@ -157,6 +264,32 @@ impl<G: GameArenaService> Infrastructure<G> {
         SystemRepo::update(self, ctx);
     }
 
+    /// Returns the index of the arena with the given id, or else of the arena new players join.
+    pub(crate) fn arena_index(&self, arena_id: Option<ArenaId>) -> usize {
+        arena_id
+            .and_then(|arena_id| {
+                self.arenas.iter().position(|arena| {
+                    arena.context.arena_id == arena_id && self.scaling.accepts(arena)
+                })
+            })
+            .unwrap_or_else(|| self.scaling.default_arena_index(&self.arenas))
+    }
+
+    /// Returns the index of the arena that a (real) player is in, if any.
+    pub(crate) fn player_arena_index(&self, player_id: PlayerId) -> Option<usize> {
+        self.arenas
+            .iter()
+            .position(|arena| arena.context.players.contains(player_id))
+    }
+
+    /// Number of real players in all arenas, that are alive or recently alive.
+    pub(crate) fn real_players_live(&self) -> usize {
+        self.arenas
+            .iter()
+            .map(|arena| arena.context.players.real_players_live)
+            .sum()
+    }
+
     /// Returns a static reference to the database singleton.
     pub fn database(&self) -> &'static Database {
         self.database
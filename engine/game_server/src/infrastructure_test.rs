@@ -0,0 +1,92 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::client::{Authenticate, ClientStatus, PlayerClientData};
+use crate::context_service::ArenaConfig;
+use crate::game_service::GameArenaService;
+use crate::infrastructure::Infrastructure;
+use crate::player::{PlayerData, PlayerTuple};
+use core_protocol::id::{PlayerId, SessionId};
+use core_protocol::rpc::Update;
+use minicdn::MiniCdn;
+use server_util::generate_id::generate_id_64;
+use server_util::observer::ObserverUpdate;
+use server_util::rate_limiter::RateLimiterProps;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+impl<G: GameArenaService> Infrastructure<G> {
+    /// Creates infrastructure with `arenas` arenas, and no external services, for testing
+    /// purposes.
+    pub(crate) fn test(arenas: usize, max_players: Option<usize>) -> Self {
+        static ALLOW_WEB_SOCKET_JSON: AtomicBool = AtomicBool::new(true);
+
+        actix::System::new().block_on(Self::new(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            0,
+            None,
+            true,
+            None,
+            vec![ArenaConfig::default(); arenas],
+            max_players,
+            None,
+            arenas.max(4),
+            None,
+            None,
+            None,
+            None,
+            Arc::new(RwLock::new(MiniCdn::Embedded(Default::default()))),
+            &ALLOW_WEB_SOCKET_JSON,
+            None,
+            RateLimiterProps::no_limit(),
+        ))
+    }
+
+    /// Adds a real player, connected over the returned channel, to an arena for testing
+    /// purposes.
+    pub(crate) fn test_connect(
+        &mut self,
+        arena: usize,
+        player_id: PlayerId,
+    ) -> UnboundedReceiver<ObserverUpdate<Update<G::GameUpdate>>> {
+        let ip_address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let metrics = (&Authenticate {
+            ip_address,
+            user_agent_id: None,
+            referrer: None,
+            arena_id: None,
+            arena_id_session_id: None,
+            invitation_id: None,
+            oauth2_code: None,
+        })
+            .into();
+        let mut client = PlayerClientData::new(
+            SessionId(generate_id_64()),
+            metrics,
+            None,
+            None,
+            None,
+            ip_address,
+            0,
+            false,
+        );
+        let (observer, receiver) = unbounded_channel();
+        client.status = ClientStatus::Connected { observer };
+        self.arenas[arena].context.players.insert(
+            player_id,
+            Arc::new(PlayerTuple::new(PlayerData::new(
+                player_id,
+                Some(Box::new(client)),
+            ))),
+        );
+        receiver
+    }
+}
@@ -1,24 +1,54 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::client::ClientStatus;
 use crate::game_service::GameArenaService;
+use crate::infrastructure::Infrastructure;
+use crate::metric::MetricRepo;
 use crate::player::{PlayerData, PlayerRepo};
 use crate::unwrap_or_return;
+use actix::{ActorStreamExt, Context as ActorContext, ContextFutureSpawner, WrapStream};
 use atomic_refcell::AtomicRefMut;
-use core_protocol::dto::InvitationDto;
-use core_protocol::id::{ArenaId, InvitationId, PlayerId, ServerId};
-use core_protocol::rpc::{InvitationRequest, InvitationUpdate};
+use core_protocol::dto::{InvitationDto, ReferralDto};
+use core_protocol::get_unix_time_now;
+use core_protocol::id::{ArenaId, InvitationId, PlayerId, ServerId, UserId};
+use core_protocol::rpc::{InvitationRequest, InvitationUpdate, Update};
+use core_protocol::UnixTime;
+use futures::stream::FuturesUnordered;
+use log::error;
+use server_util::observer::ObserverUpdate;
+use server_util::storage::ReferralItem;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
+use std::net::IpAddr;
 
 /// Invitations, shared by all arenas.
 pub struct InvitationRepo<G: GameArenaService> {
     // TODO: Prune.
     invitations: HashMap<InvitationId, Invitation>,
+    /// Invitations whose referrals changed since they were last sent to their creator.
+    changed: HashSet<InvitationId>,
+    /// Referrals of accounts, which are kept in storage, so that accounts are rewarded whenever
+    /// (and in whichever arena) their player is online.
+    referrals: HashMap<UserId, AccountReferrals>,
+    /// Referrals to put into storage.
+    pending: Vec<ReferralItem>,
+    /// Accounts whose referrals should be loaded from storage.
+    to_load: Vec<UserId>,
     _spooky: PhantomData<G>,
 }
 
+/// Referrals of one account.
+#[derive(Debug, Default)]
+struct AccountReferrals {
+    items: Vec<ReferralItem>,
+    /// Whether the stored referrals were requested.
+    requested: bool,
+    /// Whether the stored referrals were loaded, such that the account may be rewarded.
+    loaded: bool,
+}
+
 /// For routing invitations.
 #[derive(Clone, Debug)]
 pub struct Invitation {
@@ -26,6 +56,12 @@ pub struct Invitation {
     pub arena_id: ArenaId,
     /// Sender.
     pub player_id: PlayerId,
+    /// Progress of the players that accepted the invitation.
+    pub referrals: ReferralDto,
+    /// Account of the sender, if they were signed in. Only accounts are rewarded for referrals.
+    user_id: Option<UserId>,
+    /// Address of the sender, who can't refer themselves.
+    ip_address: IpAddr,
 }
 
 /// Invitation related data stored in player.
@@ -35,21 +71,42 @@ pub struct ClientInvitationData {
     pub invitation_accepted: Option<InvitationDto>,
     /// Outgoing invitation created by player.
     pub invitation_created: Option<InvitationId>,
+    /// Account and address of the sender of the accepted invitation, if they were signed in, so
+    /// that they can be rewarded even after leaving.
+    inviter: Option<(UserId, IpAddr)>,
+    /// Whether the player spawning was counted towards the referrals of the accepted invitation.
+    spawn_counted: bool,
+    /// Whether the player progressed far enough to reward the sender of the accepted invitation
+    /// (even if they weren't rewarded).
+    referral_claimed: bool,
 }
 
 impl ClientInvitationData {
-    pub fn new(invitation_accepted: Option<InvitationDto>) -> Self {
+    pub fn new(invitation_accepted: Option<&Invitation>) -> Self {
         Self {
-            invitation_accepted,
+            invitation_accepted: invitation_accepted.map(|invitation| InvitationDto {
+                player_id: invitation.player_id,
+            }),
             invitation_created: None,
+            inviter: invitation_accepted
+                .and_then(|invitation| Some((invitation.user_id?, invitation.ip_address))),
+            spawn_counted: false,
+            referral_claimed: false,
         }
     }
 }
 
 impl<G: GameArenaService> InvitationRepo<G> {
+    /// Most players that the sender of an invitation may be rewarded for.
+    pub const MAX_REWARDS: u32 = 5;
+
     pub fn new() -> Self {
         Self {
             invitations: HashMap::new(),
+            changed: HashSet::new(),
+            referrals: HashMap::new(),
+            pending: Vec::new(),
+            to_load: Vec::new(),
             _spooky: PhantomData,
         }
     }
@@ -59,6 +116,22 @@ impl<G: GameArenaService> InvitationRepo<G> {
         self.invitations.get(&invitation_id)
     }
 
+    /// Looks up an invitation by id, counting a visit via its link.
+    pub(crate) fn click(&mut self, invitation_id: InvitationId) -> Option<&Invitation> {
+        let invitation = self.invitations.get_mut(&invitation_id)?;
+        invitation.referrals.clicks = invitation.referrals.clicks.saturating_add(1);
+        self.changed.insert(invitation_id);
+        Some(invitation)
+    }
+
+    /// Looks up the invitation created by a player.
+    fn created_by(&mut self, player_id: PlayerId) -> Option<(InvitationId, &mut Invitation)> {
+        self.invitations
+            .iter_mut()
+            .find(|(_, invitation)| invitation.player_id == player_id)
+            .map(|(&invitation_id, invitation)| (invitation_id, invitation))
+    }
+
     /// Returns how many invitations are cached.
     pub fn len(&self) -> usize {
         self.invitations.len()
@@ -70,6 +143,7 @@ impl<G: GameArenaService> InvitationRepo<G> {
         if let Some(invitation_id) = client.invitation.invitation_created {
             let removed = self.invitations.remove(&invitation_id);
             debug_assert!(removed.is_some(), "invitation was cleared elsewhere");
+            self.changed.remove(&invitation_id);
             client.invitation.invitation_created = None;
         }
     }
@@ -81,6 +155,7 @@ impl<G: GameArenaService> InvitationRepo<G> {
         arena_id: ArenaId,
         server_id: Option<ServerId>,
         players: &mut PlayerRepo<G>,
+        metrics: &mut MetricRepo<G>,
     ) -> Result<InvitationUpdate, &'static str> {
         let mut req_player = players
             .borrow_player_mut(req_player_id)
@@ -94,14 +169,23 @@ impl<G: GameArenaService> InvitationRepo<G> {
         let invitation_id = if let Some(invitation_id) = req_client.invitation.invitation_created {
             invitation_id
         } else {
+            let user_id = req_client.account.as_ref().map(|account| account.user_id);
+            let rewards = user_id.map_or(0, |user_id| self.rewards(user_id));
             loop {
                 let invitation_id = InvitationId::generate(server_id);
                 if let Entry::Vacant(entry) = self.invitations.entry(invitation_id) {
                     entry.insert(Invitation {
                         arena_id,
                         player_id: req_player_id,
+                        referrals: ReferralDto {
+                            rewards,
+                            ..ReferralDto::default()
+                        },
+                        user_id,
+                        ip_address: req_client.ip_address,
                     });
                     req_client.invitation.invitation_created = Some(invitation_id);
+                    metrics.mutate_with(|m| m.invitations_created.increment(), &req_client.metrics);
                     break invitation_id;
                 }
             }
@@ -110,18 +194,304 @@ impl<G: GameArenaService> InvitationRepo<G> {
         Ok(InvitationUpdate::InvitationCreated(invitation_id))
     }
 
-    pub fn handle_invitation_request(
+    pub(crate) fn handle_invitation_request(
         &mut self,
         player_id: PlayerId,
         request: InvitationRequest,
         arena_id: ArenaId,
         server_id: Option<ServerId>,
         players: &mut PlayerRepo<G>,
+        metrics: &mut MetricRepo<G>,
     ) -> Result<InvitationUpdate, &'static str> {
         match request {
             InvitationRequest::CreateInvitation => {
-                self.create_invitation(player_id, arena_id, server_id, players)
+                self.create_invitation(player_id, arena_id, server_id, players, metrics)
+            }
+        }
+    }
+
+    /// Counts invited players of an arena that spawned or progressed far enough to reward the
+    /// sender of their invitation, rewards accounts of players in the arena for their referrals,
+    /// and sends changed referrals to the senders in the arena.
+    pub(crate) fn update(
+        &mut self,
+        service: &mut G,
+        arena_id: ArenaId,
+        players: &PlayerRepo<G>,
+        metrics: &mut MetricRepo<G>,
+    ) {
+        for player_tuple in players.iter() {
+            let mut player = player_tuple.borrow_player_mut();
+            let player_id = player.player_id;
+            let spawned = player.was_alive;
+            let first_session = player.is_first_session();
+            let qualified = G::referral_qualified(&player.data);
+            let client = match player.client_mut() {
+                Some(client) => client,
+                None => continue,
+            };
+            let sender_id = match client.invitation.invitation_accepted.as_ref() {
+                Some(invitation) => invitation.player_id,
+                None => continue,
+            };
+
+            if spawned && !client.invitation.spawn_counted {
+                client.invitation.spawn_counted = true;
+                metrics.mutate_with(|m| m.invitation_spawns.increment(), &client.metrics);
+                if let Some((invitation_id, invitation)) = self.created_by(sender_id) {
+                    invitation.referrals.spawns = invitation.referrals.spawns.saturating_add(1);
+                    self.changed.insert(invitation_id);
+                }
+            }
+
+            if !qualified || client.invitation.referral_claimed {
+                continue;
             }
+            client.invitation.referral_claimed = true;
+
+            // Only new players count, so that players can't farm rewards by inviting each other.
+            if let Some((user_id, inviter_ip_address)) = client.invitation.inviter {
+                let own_account = client.account.as_ref().map(|account| account.user_id);
+                if first_session
+                    && sender_id != player_id
+                    && own_account != Some(user_id)
+                    && inviter_ip_address != client.ip_address
+                {
+                    self.refer(user_id, client.ip_address, get_unix_time_now());
+                }
+            }
+        }
+
+        // Reward accounts for their referrals, wherever the referred players were.
+        for player_tuple in players.iter() {
+            let player = player_tuple.borrow_player();
+            let player_id = player.player_id;
+            let client = match player.client() {
+                Some(client) => client,
+                None => continue,
+            };
+            let user_id = match client.account.as_ref() {
+                Some(account) => account.user_id,
+                None => continue,
+            };
+            let credited = self.credit(user_id);
+            if credited == 0 {
+                continue;
+            }
+            for _ in 0..credited {
+                metrics.mutate_with(|m| m.referral_rewards.increment(), &client.metrics);
+            }
+            drop(player);
+
+            for _ in 0..credited {
+                service.reward_referral(player_tuple);
+            }
+            let rewards = self.rewards(user_id);
+            if let Some((invitation_id, invitation)) = self.created_by(player_id) {
+                invitation.referrals.rewards = rewards;
+                self.changed.insert(invitation_id);
+            }
+        }
+
+        let invitations = &self.invitations;
+        self.changed.retain(|invitation_id| {
+            let invitation = match invitations.get(invitation_id) {
+                Some(invitation) => invitation,
+                None => return false,
+            };
+            if invitation.arena_id != arena_id {
+                return true;
+            }
+            if let Some(player) = players.borrow_player(invitation.player_id) {
+                if let Some(ClientStatus::Connected { observer }) =
+                    player.client().map(|client| &client.status)
+                {
+                    let _ = observer.send(ObserverUpdate::Send {
+                        message: Update::Invitation(InvitationUpdate::ReferralsUpdated(
+                            invitation.referrals,
+                        )),
+                    });
+                }
+            }
+            false
+        });
+    }
+
+    /// Records that a player from `ip_address`, who followed the invitation link of an account,
+    /// progressed far enough for the account to be rewarded. Each address counts once per account.
+    fn refer(&mut self, user_id: UserId, ip_address: IpAddr, now: UnixTime) {
+        let account = self.referrals.entry(user_id).or_default();
+        if account
+            .items
+            .iter()
+            .any(|item| item.ip_address == ip_address)
+        {
+            return;
+        }
+        let item = ReferralItem {
+            user_id,
+            ip_address,
+            credited: false,
+            date_created: now,
+        };
+        account.items.push(item.clone());
+        self.pending.push(item);
+    }
+
+    /// Credits the referrals of an account whose player is online, up to [`Self::MAX_REWARDS`] in
+    /// total, returning how many were newly credited. Nothing is credited until the account's
+    /// stored referrals are loaded, which this requests.
+    fn credit(&mut self, user_id: UserId) -> u32 {
+        let account = self.referrals.entry(user_id).or_default();
+        if !account.requested {
+            account.requested = true;
+            self.to_load.push(user_id);
+        }
+        if !account.loaded {
+            return 0;
+        }
+        let mut rewards = account.items.iter().filter(|item| item.credited).count() as u32;
+        let mut credited = 0;
+        for item in account.items.iter_mut().filter(|item| !item.credited) {
+            if rewards >= Self::MAX_REWARDS {
+                break;
+            }
+            item.credited = true;
+            self.pending.push(item.clone());
+            rewards += 1;
+            credited += 1;
+        }
+        credited
+    }
+
+    /// Returns how many referrals an account was rewarded for, as far as is known.
+    fn rewards(&self, user_id: UserId) -> u32 {
+        self.referrals.get(&user_id).map_or(0, |account| {
+            account.items.iter().filter(|item| item.credited).count() as u32
+        })
+    }
+
+    /// Merges the stored referrals of an account with those made since they were requested.
+    fn load(&mut self, user_id: UserId, items: Vec<ReferralItem>) {
+        let account = self.referrals.entry(user_id).or_default();
+        for item in items {
+            if let Some(existing) = account
+                .items
+                .iter_mut()
+                .find(|existing| existing.ip_address == item.ip_address)
+            {
+                existing.credited |= item.credited;
+            } else {
+                account.items.push(item);
+            }
+        }
+        account.loaded = true;
+    }
+
+    /// Loads the referrals of accounts whose players came online. Without storage, referrals
+    /// only last until the server restarts.
+    pub(crate) fn update_from_storage(
+        infrastructure: &mut Infrastructure<G>,
+        ctx: &mut ActorContext<Infrastructure<G>>,
+    ) {
+        let invitations = &mut infrastructure.invitations;
+        if invitations.to_load.is_empty() {
+            return;
+        }
+        let to_load: Vec<UserId> = invitations.to_load.drain(..).collect();
+        let storage = if let Some(storage) = infrastructure.storage {
+            storage
+        } else {
+            for user_id in to_load {
+                invitations.load(user_id, Vec::new());
+            }
+            return;
+        };
+
+        to_load
+            .into_iter()
+            .map(move |user_id| async move { (user_id, storage.get_referrals(user_id).await) })
+            .collect::<FuturesUnordered<_>>()
+            .into_actor(infrastructure)
+            .map(|(user_id, result), act, _| match result {
+                Ok(items) => act.invitations.load(user_id, items),
+                Err(e) => error!("error getting referrals: {:?}", e),
+            })
+            .finish()
+            .spawn(ctx);
+    }
+
+    /// Puts new and credited referrals into storage, if any.
+    pub(crate) fn update_to_storage(
+        infrastructure: &mut Infrastructure<G>,
+        ctx: &mut ActorContext<Infrastructure<G>>,
+    ) {
+        if infrastructure.invitations.pending.is_empty() {
+            return;
+        }
+        let pending: Vec<ReferralItem> = infrastructure.invitations.pending.drain(..).collect();
+        let storage = unwrap_or_return!(infrastructure.storage);
+
+        pending
+            .into_iter()
+            .map(|item| storage.put_referral(item))
+            .collect::<FuturesUnordered<_>>()
+            .into_actor(infrastructure)
+            .map(|result, _, _| {
+                if let Err(e) = result {
+                    error!("error putting referral: {:?}", e);
+                }
+            })
+            .finish()
+            .spawn(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_service::MockGame;
+    use crate::invitation::InvitationRepo;
+    use core_protocol::id::UserId;
+    use server_util::storage::ReferralItem;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::num::NonZeroU64;
+
+    #[test]
+    fn referrals() {
+        let user_id = UserId(NonZeroU64::new(1).unwrap());
+        let ip = |n: u8| IpAddr::V4(Ipv4Addr::new(10, 0, 0, n));
+        let stored = |n: u8, credited: bool| ReferralItem {
+            user_id,
+            ip_address: ip(n),
+            credited,
+            date_created: 0,
+        };
+        let mut invitations = InvitationRepo::<MockGame>::new();
+
+        // Referred while the account's player was offline, or in another arena.
+        invitations.refer(user_id, ip(1), 0);
+        invitations.refer(user_id, ip(1), 0);
+        assert_eq!(invitations.pending.len(), 1);
+
+        // Credited once its stored referrals, one of which was already credited, are loaded.
+        assert_eq!(invitations.credit(user_id), 0);
+        assert_eq!(invitations.to_load, vec![user_id]);
+        invitations.load(user_id, vec![stored(1, false), stored(2, true)]);
+        assert_eq!(invitations.credit(user_id), 1);
+        assert_eq!(invitations.credit(user_id), 0);
+        assert_eq!(invitations.rewards(user_id), 2);
+        assert_eq!(invitations.pending.len(), 2);
+        assert!(invitations.pending[1].credited);
+
+        // Up to a limit.
+        for n in 3..20 {
+            invitations.refer(user_id, ip(n), 0);
         }
+        assert_eq!(
+            invitations.credit(user_id),
+            InvitationRepo::<MockGame>::MAX_REWARDS - 2
+        );
+        assert_eq!(invitations.credit(user_id), 0);
+        assert_eq!(invitations.to_load.len(), 1);
     }
 }
@@ -5,20 +5,21 @@ use crate::game_service::GameArenaService;
 use crate::infrastructure::Infrastructure;
 use crate::liveboard::LiveboardRepo;
 use crate::player::PlayerRepo;
+use crate::webhook::ArenaEvent;
 use actix::{
     ActorFutureExt, ActorStreamExt, Context as ActorContext, ContextFutureSpawner, Handler,
     WrapFuture, WrapStream,
 };
-use core_protocol::dto::LeaderboardDto;
-use core_protocol::get_unix_time_now;
-use core_protocol::id::PeriodId;
+use core_protocol::dto::{LeaderboardDto, QuarantinedScoreDto};
+use core_protocol::id::{PeriodId, PlayerId};
 use core_protocol::name::PlayerAlias;
-use core_protocol::rpc::{LeaderboardResponse, LeaderboardUpdate};
+use core_protocol::rpc::{AdminUpdate, LeaderboardResponse, LeaderboardUpdate};
+use core_protocol::{get_unix_time_now, UnixTime};
 use futures::stream::FuturesUnordered;
-use log::error;
+use log::{error, info};
 use server_util::database_schema::{GameIdScoreType, ScoreItem, ScoreType};
 use server_util::rate_limiter::RateLimiter;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
@@ -29,13 +30,25 @@ pub struct LeaderboardRepo<G: GameArenaService> {
     leaderboards: [(Arc<[LeaderboardDto]>, bool); std::mem::variant_count::<PeriodId>()],
     /// Scores that should be committed to database.
     pending: HashMap<(PlayerAlias, PeriodId), u32>,
+    /// Scores that looked illegitimate (see [`GameArenaService::score_anomaly`]), withheld from
+    /// the database until reviewed by an admin. Once quarantined, a player's scores stay
+    /// quarantined until reviewed, or discarded after [`Self::QUARANTINE_EXPIRY`].
+    quarantine: HashMap<PlayerId, QuarantinedScoreDto>,
+    /// Players whose quarantined scores were approved, exempt from anomaly detection.
+    approved: HashSet<PlayerId>,
     take_pending_rate_limit: RateLimiter,
     read_database_rate_limit: RateLimiter,
+    /// Whether only players with a linked identity may earn a place.
+    require_login: bool,
     _spooky: PhantomData<G>,
 }
 
 impl<G: GameArenaService> LeaderboardRepo<G> {
-    pub fn new() -> Self {
+    /// Milliseconds that a score stays quarantined, if not reviewed, before it is discarded (and
+    /// the player's new scores are judged afresh).
+    const QUARANTINE_EXPIRY: UnixTime = 24 * 60 * 60 * 1000;
+
+    pub fn new(require_login: bool) -> Self {
         Self {
             leaderboards: [
                 (Vec::new().into(), false),
@@ -43,8 +56,11 @@ impl<G: GameArenaService> LeaderboardRepo<G> {
                 (Vec::new().into(), false),
             ],
             pending: HashMap::new(),
+            quarantine: HashMap::new(),
+            approved: HashSet::new(),
             take_pending_rate_limit: RateLimiter::new(Duration::from_secs(60), 0),
             read_database_rate_limit: RateLimiter::new(Duration::from_secs(110), 0),
+            require_login,
             _spooky: PhantomData,
         }
     }
@@ -54,11 +70,22 @@ impl<G: GameArenaService> LeaderboardRepo<G> {
         &self.leaderboards[period_id as usize].0
     }
 
-    /// Leaderboard relies on an external source of data, such as a database.
-    pub fn put_leaderboard(&mut self, period_id: PeriodId, leaderboard: Arc<[LeaderboardDto]>) {
+    /// Leaderboard relies on an external source of data, such as a database. Returns the new first
+    /// place if it beat the previous first place's score (not if the leaderboard was empty).
+    pub fn put_leaderboard(
+        &mut self,
+        period_id: PeriodId,
+        leaderboard: Arc<[LeaderboardDto]>,
+    ) -> Option<LeaderboardDto> {
+        let previous_best = self.get(period_id).first().map(|dto| dto.score);
+        let new_best = leaderboard
+            .first()
+            .filter(|dto| previous_best.map(|best| dto.score > best).unwrap_or(false))
+            .cloned();
         if &leaderboard != self.get(period_id) {
             self.leaderboards[period_id as usize] = (leaderboard, true);
         }
+        new_best
     }
 
     /// Computes minimum score to earn a place on the given leaderboard.
@@ -80,6 +107,9 @@ impl<G: GameArenaService> LeaderboardRepo<G> {
             return;
         }
 
+        let now = get_unix_time_now();
+        self.expire_quarantine(now);
+
         for period_id in PeriodId::iter() {
             let minimum_score = self.minimum_score(period_id);
 
@@ -95,9 +125,24 @@ impl<G: GameArenaService> LeaderboardRepo<G> {
                         continue;
                     }
 
-                    let alias = player.alias();
-                    let entry = self.pending.entry((alias, period_id)).or_insert(0);
-                    *entry = dto.score.max(*entry);
+                    if self.require_login
+                        && player
+                            .client()
+                            .map(|client| client.login_type.is_none())
+                            .unwrap_or(true)
+                    {
+                        // Anonymous players may play, but their scores don't persist.
+                        continue;
+                    }
+
+                    self.submit(
+                        period_id,
+                        dto.player_id,
+                        player.alias(),
+                        dto.score,
+                        || G::score_anomaly(player.score, &player.data),
+                        now,
+                    );
                 } else {
                     // TODO: Is this legitimately possible?
                     debug_assert!(false, "player from liveboard doesn't exist");
@@ -106,6 +151,79 @@ impl<G: GameArenaService> LeaderboardRepo<G> {
         }
     }
 
+    /// Discards quarantined scores that weren't reviewed in time.
+    fn expire_quarantine(&mut self, now: UnixTime) {
+        self.quarantine
+            .retain(|_, dto| now.saturating_sub(dto.date_created) < Self::QUARANTINE_EXPIRY);
+    }
+
+    /// Commits a player's score to the database, unless it is (or should be, according to
+    /// `anomaly`) withheld in quarantine until reviewed.
+    fn submit(
+        &mut self,
+        period_id: PeriodId,
+        player_id: PlayerId,
+        alias: PlayerAlias,
+        score: u32,
+        anomaly: impl FnOnce() -> Option<&'static str>,
+        now: UnixTime,
+    ) {
+        if let Some(quarantined) = self.quarantine.get_mut(&player_id) {
+            quarantined.alias = alias;
+            quarantined.score = score.max(quarantined.score);
+            return;
+        }
+        if !self.approved.contains(&player_id) {
+            if let Some(reason) = anomaly() {
+                info!("quarantined score of {} ({})", alias, reason);
+                self.quarantine.insert(
+                    player_id,
+                    QuarantinedScoreDto {
+                        alias,
+                        player_id,
+                        score,
+                        reason: reason.to_owned(),
+                        date_created: now,
+                    },
+                );
+                return;
+            }
+        }
+
+        let entry = self.pending.entry((alias, period_id)).or_insert(0);
+        *entry = score.max(*entry);
+    }
+
+    /// Gets the quarantined scores, oldest first.
+    pub(crate) fn request_quarantined_scores(&self) -> Result<AdminUpdate, &'static str> {
+        let mut scores: Vec<_> = self.quarantine.values().cloned().collect();
+        scores.sort_by_key(|dto| dto.date_created);
+        Ok(AdminUpdate::QuarantinedScoresRequested(scores.into()))
+    }
+
+    /// Approves (letting it onto the leaderboards) or discards a quarantined score.
+    pub(crate) fn review_quarantined_score(
+        &mut self,
+        player_id: PlayerId,
+        approve: bool,
+    ) -> Result<AdminUpdate, &'static str> {
+        let quarantined = self
+            .quarantine
+            .remove(&player_id)
+            .ok_or("no such quarantined score")?;
+        if approve {
+            for period_id in PeriodId::iter() {
+                let entry = self
+                    .pending
+                    .entry((quarantined.alias, period_id))
+                    .or_insert(0);
+                *entry = quarantined.score.max(*entry);
+            }
+            self.approved.insert(player_id);
+        }
+        Ok(AdminUpdate::QuarantinedScoreReviewed(approve))
+    }
+
     /// Returns scores pending database commit, draining them in the process. Rate limited.
     pub fn take_pending(&mut self) -> Option<impl Iterator<Item = ScoreItem> + '_> {
         if self.pending.is_empty() || self.take_pending_rate_limit.should_limit_rate() {
@@ -176,7 +294,22 @@ impl<G: GameArenaService> LeaderboardRepo<G> {
                         let leaderboard =
                             heap.into_iter_sorted().take(G::LEADERBOARD_SIZE).collect();
 
-                        act.leaderboard.put_leaderboard(period_id, leaderboard)
+                        let new_best = act.leaderboard.put_leaderboard(period_id, leaderboard);
+
+                        // Daily and weekly records are too frequent to be worth announcing.
+                        if let Some((dto, discord_webhook)) = new_best
+                            .filter(|_| period_id == PeriodId::AllTime)
+                            .zip(act.discord_webhook)
+                        {
+                            discord_webhook.post(
+                                ArenaEvent::HighScore {
+                                    alias: dto.alias,
+                                    score: dto.score,
+                                    period_id,
+                                },
+                                G::GAME_ID,
+                            );
+                        }
                     }
                     Err(e) => {
                         error!("error reading leaderboard scores: {:?}", e);
@@ -286,7 +419,7 @@ impl<G: GameArenaService> Handler<LeaderboardRequest> for Infrastructure<G> {
     type Result = LeaderboardResponse;
 
     fn handle(&mut self, _request: LeaderboardRequest, _: &mut Self::Context) -> Self::Result {
-        let local_players = self.context_service.context.players.real_players_live as u32;
+        let local_players = self.real_players_live() as u32;
 
         LeaderboardResponse {
             leaderboard: Arc::clone(self.leaderboard.get(PeriodId::AllTime)),
@@ -304,3 +437,73 @@ impl<G: GameArenaService> Handler<LeaderboardRequest> for Infrastructure<G> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::game_service::MockGame;
+    use crate::leaderboard::LeaderboardRepo;
+    use core_protocol::id::{PeriodId, PlayerId};
+    use core_protocol::name::PlayerAlias;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn quarantine() {
+        let mut leaderboard = LeaderboardRepo::<MockGame>::new(false);
+        let player_id = |n: u32| PlayerId(NonZeroU32::new(n).unwrap());
+        let (cheater, honest) = (player_id(1000), player_id(1001));
+        let alias = PlayerAlias::new_unsanitized("Someone");
+        let period_id = PeriodId::AllTime;
+        let now = 1_000_000;
+
+        leaderboard.submit(period_id, cheater, alias, 500, || Some("score rate"), now);
+        // Once quarantined, scores stay quarantined, regardless of anomalies.
+        leaderboard.submit(period_id, cheater, alias, 900, || None, now);
+        assert!(leaderboard.pending.is_empty());
+        assert_eq!(leaderboard.quarantine[&cheater].score, 900);
+
+        // Quarantine is per player, not per alias.
+        leaderboard.submit(period_id, honest, alias, 700, || None, now);
+        assert_eq!(leaderboard.pending[&(alias, period_id)], 700);
+
+        // Discarding.
+        assert!(leaderboard.review_quarantined_score(cheater, false).is_ok());
+        assert!(leaderboard
+            .review_quarantined_score(cheater, false)
+            .is_err());
+        assert_eq!(leaderboard.pending[&(alias, period_id)], 700);
+
+        // Approving, after which the player is exempt.
+        leaderboard.submit(period_id, cheater, alias, 1000, || Some("score rate"), now);
+        assert!(leaderboard.review_quarantined_score(cheater, true).is_ok());
+        assert_eq!(leaderboard.pending[&(alias, period_id)], 1000);
+        leaderboard.submit(period_id, cheater, alias, 2000, || Some("score rate"), now);
+        assert_eq!(leaderboard.pending[&(alias, period_id)], 2000);
+        assert!(leaderboard.quarantine.is_empty());
+    }
+
+    #[test]
+    fn quarantine_expiry() {
+        let mut leaderboard = LeaderboardRepo::<MockGame>::new(false);
+        let player_id = PlayerId(NonZeroU32::new(1000).unwrap());
+        let alias = PlayerAlias::new_unsanitized("Someone");
+        let period_id = PeriodId::Daily;
+        let now = 1_000_000;
+
+        leaderboard.submit(
+            period_id,
+            player_id,
+            alias,
+            500,
+            || Some("repeated kills"),
+            now,
+        );
+        leaderboard.expire_quarantine(now + LeaderboardRepo::<MockGame>::QUARANTINE_EXPIRY - 1);
+        assert_eq!(leaderboard.quarantine.len(), 1);
+        leaderboard.expire_quarantine(now + LeaderboardRepo::<MockGame>::QUARANTINE_EXPIRY);
+        assert!(leaderboard.quarantine.is_empty());
+
+        // Judged afresh.
+        leaderboard.submit(period_id, player_id, alias, 600, || None, now);
+        assert_eq!(leaderboard.pending[&(alias, period_id)], 600);
+    }
+}
@@ -16,26 +16,42 @@
 #![feature(result_option_inspect)]
 
 pub mod admin;
+pub mod alias_policy;
+pub mod announcement;
 pub mod bot;
+pub mod broadcast;
 pub mod chat;
+pub mod chat_log;
 pub mod client;
 pub mod context;
 pub mod context_service;
 pub mod entry_point;
+pub mod friend;
 pub mod game_service;
 pub mod infrastructure;
+#[cfg(test)]
+mod infrastructure_test;
 pub mod invitation;
 pub mod leaderboard;
 pub mod liveboard;
+pub mod match_archive;
 pub mod metric;
 pub mod ordered_set;
 pub mod player;
+pub mod rate_limit;
+pub mod scaling;
+pub mod shadow_ban;
 pub mod status;
 pub mod team;
+pub mod trace_log;
 #[macro_use]
 pub mod util;
+pub mod voice;
 pub mod discord;
+pub mod google;
 pub(crate) mod log;
 pub(crate) mod options;
 pub mod static_files;
+pub mod steam;
 pub mod system;
+pub mod webhook;
@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::game_service::GameArenaService;
+use crate::infrastructure::Infrastructure;
+use crate::player::PlayerRepo;
+use actix::{
+    fut, ActorFutureExt, ActorStreamExt, Context as ActorContext, ContextFutureSpawner,
+    ResponseActFuture, WrapFuture, WrapStream,
+};
+use core_protocol::dto::{MatchDto, MatchParticipantDto};
+use core_protocol::get_unix_time_now;
+use core_protocol::id::{ArenaId, MatchId, PlayerId, UserId};
+use core_protocol::rpc::AdminUpdate;
+use core_protocol::UnixTime;
+use futures::stream::FuturesUnordered;
+use log::error;
+use server_util::generate_id::generate_id_64;
+use std::marker::PhantomData;
+
+/// Results of matches that ended in an arena, such as rounds of a ranked or tournament arena,
+/// until they are archived in storage.
+pub struct MatchArchiveRepo<G: GameArenaService> {
+    pending: Vec<MatchDto>,
+    _spooky: PhantomData<G>,
+}
+
+impl<G: GameArenaService> MatchArchiveRepo<G> {
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            _spooky: PhantomData,
+        }
+    }
+
+    /// Records the result of a match that just ended. `participants` are the player, their score,
+    /// and the number of boats they sank, in any order.
+    pub(crate) fn record(
+        &mut self,
+        arena_id: ArenaId,
+        game_mode: &str,
+        date_started: UnixTime,
+        participants: impl IntoIterator<Item = (PlayerId, u32, u32)>,
+        players: &PlayerRepo<G>,
+    ) {
+        let mut participants: Vec<_> = participants
+            .into_iter()
+            .filter_map(|(player_id, score, kills)| {
+                let player = players.borrow_player(player_id)?;
+                Some(MatchParticipantDto {
+                    alias: player.alias(),
+                    user_id: player
+                        .client()
+                        .and_then(|client| client.account.as_ref())
+                        .map(|account| account.user_id),
+                    score,
+                    kills,
+                })
+            })
+            .collect();
+        if participants.is_empty() {
+            return;
+        }
+        participants.sort_by(|a, b| b.score.cmp(&a.score));
+
+        self.pending.push(MatchDto {
+            match_id: MatchId(generate_id_64()),
+            arena_id,
+            // Filled in when archiving.
+            server_id: None,
+            game_mode: game_mode.to_owned(),
+            date_started,
+            date_ended: get_unix_time_now(),
+            participants,
+        });
+    }
+
+    /// Puts matches that ended since last time into storage, if any.
+    pub(crate) fn update_to_storage(
+        infrastructure: &mut Infrastructure<G>,
+        ctx: &mut ActorContext<Infrastructure<G>>,
+    ) {
+        let server_id = infrastructure.server_id;
+        let pending: Vec<MatchDto> = infrastructure
+            .arenas
+            .iter_mut()
+            .flat_map(|arena| arena.context.matches.pending.drain(..))
+            .collect();
+        let storage = match infrastructure.storage {
+            Some(storage) if !pending.is_empty() => storage,
+            _ => return,
+        };
+
+        pending
+            .into_iter()
+            .map(|mut item| {
+                item.server_id = server_id;
+                storage.put_match(item)
+            })
+            .collect::<FuturesUnordered<_>>()
+            .into_actor(infrastructure)
+            .map(|result, _, _| {
+                if let Err(e) = result {
+                    error!("error putting match: {:?}", e);
+                }
+            })
+            .finish()
+            .spawn(ctx);
+    }
+
+    /// Gets the most recent matches that an account took part in, newest first.
+    pub(crate) fn request_matches(
+        infrastructure: &Infrastructure<G>,
+        user_id: UserId,
+    ) -> ResponseActFuture<Infrastructure<G>, Result<AdminUpdate, &'static str>> {
+        let storage = if let Some(storage) = infrastructure.storage {
+            storage
+        } else {
+            return Box::pin(fut::ready(Err("no storage")));
+        };
+        Box::pin(
+            async move { storage.get_matches(user_id).await }
+                .into_actor(infrastructure)
+                .map(|result, _, _| match result {
+                    Ok(matches) => Ok(AdminUpdate::MatchesRequested(matches.into())),
+                    Err(e) => {
+                        error!("error getting matches: {:?}", e);
+                        Err("failed to load")
+                    }
+                }),
+        )
+    }
+}
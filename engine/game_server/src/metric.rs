@@ -11,6 +11,7 @@ use actix::Context as ActorContext;
 use actix::{ActorFutureExt, ContextFutureSpawner, WrapFuture};
 use core_protocol::dto::{MetricFilter, MetricsDataPointDto};
 use core_protocol::id::{CohortId, RegionId, SessionId, UserAgentId};
+use core_protocol::metrics::FunnelStage;
 use core_protocol::name::Referrer;
 use core_protocol::{get_unix_time_now, UnixTime};
 use heapless::HistoryBuffer;
@@ -71,6 +72,10 @@ pub struct ClientMetricData<G: GameArenaService> {
     pub visit_plays: u32,
     /// How many plays on previous sessions, for database purposes.
     pub previous_plays: u32,
+    /// Which [`FunnelStage`]s were reached on the current visit, one bit per stage.
+    pub funnel_reached: u8,
+    /// When the first kill of the current play happened, until it is recorded in the funnel.
+    pub first_kill: Option<Instant>,
     _spooky: PhantomData<G>,
 }
 
@@ -96,6 +101,8 @@ impl<G: GameArenaService> From<&Authenticate> for ClientMetricData<G> {
             plays: 0,
             visit_plays: 0,
             previous_plays: 0,
+            funnel_reached: 0,
+            first_kill: None,
             _spooky: PhantomData,
         }
     }
@@ -278,6 +285,27 @@ impl<G: GameArenaService> MetricRepo<G> {
         );
     }
 
+    /// Records the first time, on the current visit, that a client reached a stage of the funnel.
+    pub fn reach_funnel_stage(
+        &mut self,
+        stage: FunnelStage,
+        at: Instant,
+        client_metric_data: &mut ClientMetricData<G>,
+    ) {
+        let bit = 1 << stage as u8;
+        if client_metric_data.funnel_reached & bit != 0 {
+            return;
+        }
+        client_metric_data.funnel_reached |= bit;
+
+        let seconds = client_metric_data
+            .visit_started
+            .map_or(0.0, |visit_started| {
+                at.saturating_duration_since(visit_started).as_secs_f32()
+            });
+        self.mutate_with(|m| m.funnel.push(stage, seconds), client_metric_data);
+    }
+
     /// Call when a websocket connects.
     pub fn start_visit(&mut self, client: &mut PlayerClientData<G>) {
         let renewed = client.metrics.session_id_previous.is_some()
@@ -290,12 +318,16 @@ impl<G: GameArenaService> MetricRepo<G> {
         );
         client.metrics.visit_stopped = None;
         client.metrics.visit_started = Some(Instant::now());
+        client.metrics.funnel_reached = 0;
 
         self.mutate_with(
             |m| {
                 m.visits.increment();
                 m.invited
                     .push(client.invitation.invitation_accepted.is_some());
+                if client.invitation.invitation_accepted.is_some() {
+                    m.invitation_clicks.increment();
+                }
                 if renewed {
                     m.renews.increment();
                 }
@@ -307,6 +339,7 @@ impl<G: GameArenaService> MetricRepo<G> {
             },
             &client.metrics,
         );
+        self.reach_funnel_stage(FunnelStage::Load, Instant::now(), &mut client.metrics);
     }
 
     pub fn start_play(&mut self, player: &mut PlayerData<G>) {
@@ -326,6 +359,13 @@ impl<G: GameArenaService> MetricRepo<G> {
             client.metrics.play_stopped = None;
         }
 
+        let stage = if client.metrics.visit_plays == 0 {
+            FunnelStage::Spawn
+        } else {
+            FunnelStage::Respawn
+        };
+        self.reach_funnel_stage(stage, now, &mut client.metrics);
+
         client.metrics.play_started = Some(now);
         client.metrics.plays += 1;
         client.metrics.visit_plays += 1;
@@ -357,6 +397,11 @@ impl<G: GameArenaService> MetricRepo<G> {
             debug_assert!(false, "wasn't started");
         }
 
+        if let Some(first_kill) = client.metrics.first_kill.take() {
+            self.reach_funnel_stage(FunnelStage::FirstKill, first_kill, &mut client.metrics);
+        }
+        self.reach_funnel_stage(FunnelStage::FirstDeath, now, &mut client.metrics);
+
         client.metrics.play_stopped = Some(now);
     }
 
@@ -425,13 +470,16 @@ impl<G: GameArenaService> MetricRepo<G> {
         }
         metrics_repo.next_update = Self::round_down_to_minute(now) + Self::MINUTE_IN_MILLIS;
 
-        let context = &mut infrastructure.context_service.context;
+        let arenas = &infrastructure.arenas;
         let uptime = infrastructure.status.uptime();
         let health = &mut infrastructure.status.health;
 
         let mut concurrent = Bundle::<u32>::default();
 
-        for player in context.players.iter_borrow() {
+        for player in arenas
+            .iter()
+            .flat_map(|arena| arena.context.players.iter_borrow())
+        {
             if !player.is_alive() {
                 continue;
             }
@@ -618,19 +666,22 @@ impl<G: GameArenaService> MetricRepo<G> {
             .cloned()
             .unwrap_or_default();
 
-        // For now, the infrastructure is always hosting one arena.
         // Must increment arena id even when filtering, as the database compare and swap relies
         // on it changing.
-        metrics.arenas_cached.increment();
+        metrics
+            .arenas_cached
+            .add_length(infrastructure.arenas.len());
 
         // But these don't matter for the compare and swap and do not pertain to individual filters.
         if filter.is_none() {
-            metrics
-                .players_cached
-                .add_length(infrastructure.context_service.context.players.len());
-            metrics
-                .sessions_cached
-                .add_length(infrastructure.context_service.context.players.real_players);
+            for arena in &infrastructure.arenas {
+                metrics
+                    .players_cached
+                    .add_length(arena.context.players.len());
+                metrics
+                    .sessions_cached
+                    .add_length(arena.context.players.real_players);
+            }
             metrics
                 .invitations_cached
                 .add_length(infrastructure.invitations.len());
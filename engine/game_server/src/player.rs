@@ -206,6 +206,9 @@ impl<G: GameArenaService> PlayerRepo<G> {
             let previous_team_id = p.team.previous_team_id;
             // We will inform the game service later in this function call.
             p.team.previous_team_id = current_team_id;
+            p.team.was_captain = current_team_id
+                .and_then(|team_id| teams.get(team_id))
+                .map_or(false, |team| team.is_captain(player_id));
 
             drop(p);
 
@@ -434,6 +437,11 @@ impl<G: GameArenaService> PlayerData<G> {
         self.team.team_id()
     }
 
+    /// A lagging indicator of whether the player is the captain of their team.
+    pub fn is_team_captain(&self) -> bool {
+        self.team.was_captain
+    }
+
     /// Gets any invitation accepted by the player (always [`None`] for bots).
     pub fn invitation_accepted(&self) -> Option<&InvitationDto> {
         self.client()
@@ -461,6 +469,26 @@ impl<G: GameArenaService> PlayerData<G> {
         self.player_id.is_bot()
     }
 
+    /// Returns true iff the player is a real player on their first session.
+    pub fn is_first_session(&self) -> bool {
+        self.client().map_or(false, |client| {
+            client.metrics.session_id_previous.is_none() && client.metrics.previous_plays == 0
+        })
+    }
+
+    /// Returns true iff the player is a real player whose inputs, and game events involving them,
+    /// are being recorded in the trace log.
+    pub fn is_traced(&self) -> bool {
+        self.client().map_or(false, |client| client.traced)
+    }
+
+    /// Call when the player sinks another player, for the funnel metrics.
+    pub fn tally_kill(&mut self) {
+        if let Some(client) = self.client_mut() {
+            client.metrics.first_kill.get_or_insert_with(Instant::now);
+        }
+    }
+
     /// Returns true iff the player 1) never played yet 2) stopped playing over half a minute ago.
     pub fn is_out_of_game(&self) -> bool {
         !self.was_ever_alive
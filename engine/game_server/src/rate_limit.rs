@@ -0,0 +1,158 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::dto::{RateLimitDto, RateLimitScope, RateLimitedAction};
+use core_protocol::id::PlayerId;
+use server_util::hierarchical_rate_limiter::HierarchicalRateLimiter;
+use server_util::rate_limiter::{RateLimiterProps, Units};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Limits the rate of [`RateLimitedAction`]s per client, per IP address, and globally. The limits
+/// may be tuned at runtime via the admin interface.
+pub struct RateLimitRepo {
+    limiters: HashMap<RateLimitedAction, HierarchicalRateLimiter<PlayerId>>,
+}
+
+impl RateLimitRepo {
+    pub fn new() -> Self {
+        let props =
+            |millis: u64, burst: Units| RateLimiterProps::new(Duration::from_millis(millis), burst);
+
+        Self {
+            limiters: RateLimitedAction::ALL
+                .iter()
+                .map(|&action| {
+                    let limiter = match action {
+                        // Chat has additional limits, based on content, in [`ChatRepo`].
+                        RateLimitedAction::Chat => HierarchicalRateLimiter::new(
+                            props(1000, 5),
+                            props(500, 10),
+                            props(10, 100),
+                        ),
+                        RateLimitedAction::CreateInvitation => HierarchicalRateLimiter::new(
+                            props(5000, 2),
+                            props(2000, 5),
+                            RateLimiterProps::no_limit(),
+                        ),
+                        // Games limit firing further (e.g. reloading), so this only stops floods.
+                        RateLimitedAction::Fire => HierarchicalRateLimiter::new(
+                            props(25, 20),
+                            props(10, 50),
+                            RateLimiterProps::no_limit(),
+                        ),
+                        RateLimitedAction::Spawn => HierarchicalRateLimiter::new(
+                            props(1000, 3),
+                            props(500, 10),
+                            props(10, 100),
+                        ),
+                        RateLimitedAction::Telemetry => HierarchicalRateLimiter::new(
+                            props(10000, 5),
+                            props(2000, 10),
+                            props(10, 100),
+                        ),
+                    };
+                    (action, limiter)
+                })
+                .collect(),
+        }
+    }
+
+    /// Marks the action as being performed by the player at the ip address.
+    /// Returns true if the action should be blocked (rate limited).
+    pub fn should_limit_rate(
+        &mut self,
+        action: RateLimitedAction,
+        player_id: PlayerId,
+        ip: IpAddr,
+    ) -> bool {
+        self.limiters
+            .get_mut(&action)
+            .map(|limiter| limiter.should_limit_rate(player_id, ip))
+            .unwrap_or(false)
+    }
+
+    /// Describes every scope of every rate limit.
+    pub(crate) fn request_rate_limits(&self) -> Box<[RateLimitDto]> {
+        RateLimitedAction::ALL
+            .iter()
+            .flat_map(|&action| {
+                RateLimitScope::ALL
+                    .iter()
+                    .filter_map(move |&scope| self.get(action, scope))
+            })
+            .collect()
+    }
+
+    fn get(&self, action: RateLimitedAction, scope: RateLimitScope) -> Option<RateLimitDto> {
+        let props = self.limiters.get(&action)?.props(scope);
+        Some(RateLimitDto {
+            action,
+            scope,
+            period_millis: props
+                .rate_limit()
+                .as_millis()
+                .try_into()
+                .unwrap_or(u32::MAX),
+            burst: props.burst(),
+        })
+    }
+
+    /// Changes one scope of one rate limit.
+    pub(crate) fn set_rate_limit(
+        &mut self,
+        dto: RateLimitDto,
+    ) -> Result<RateLimitDto, &'static str> {
+        if dto.burst == Units::MAX {
+            return Err("burst too large");
+        }
+        let props = if dto.period_millis == 0 {
+            RateLimiterProps::no_limit()
+        } else {
+            RateLimiterProps::new(Duration::from_millis(dto.period_millis as u64), dto.burst)
+        };
+        self.limiters
+            .get_mut(&dto.action)
+            .ok_or("unknown action")?
+            .set_props(dto.scope, props);
+        self.get(dto.action, dto.scope).ok_or("unknown action")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rate_limit::RateLimitRepo;
+    use core_protocol::dto::{RateLimitDto, RateLimitScope, RateLimitedAction};
+    use core_protocol::id::PlayerId;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn set_rate_limit() {
+        let mut rate_limits = RateLimitRepo::new();
+        let dtos = rate_limits.request_rate_limits();
+        assert_eq!(dtos.len(), 15);
+
+        let player_id = PlayerId(NonZeroU32::new(1).unwrap());
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let dto = RateLimitDto {
+            action: RateLimitedAction::Spawn,
+            scope: RateLimitScope::Client,
+            period_millis: 60000,
+            burst: 0,
+        };
+        assert_eq!(rate_limits.set_rate_limit(dto), Ok(dto));
+        assert!(!rate_limits.should_limit_rate(RateLimitedAction::Spawn, player_id, ip));
+        assert!(rate_limits.should_limit_rate(RateLimitedAction::Spawn, player_id, ip));
+        assert!(!rate_limits.should_limit_rate(RateLimitedAction::Chat, player_id, ip));
+
+        assert!(rate_limits
+            .set_rate_limit(RateLimitDto {
+                burst: u32::MAX,
+                ..dto
+            })
+            .is_err());
+    }
+}
@@ -0,0 +1,270 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::client::ClientRepo;
+use crate::context_service::{ArenaConfig, ContextService};
+use crate::game_service::GameArenaService;
+use crate::infrastructure::Infrastructure;
+use core_protocol::id::ArenaId;
+use log::{info, warn};
+use server_util::rate_limiter::RateLimiter;
+use std::time::{Duration, Instant};
+
+/// Opens arenas when existing ones fill up, and retires arenas it opened once they empty out.
+pub struct ScalingRepo<G: GameArenaService> {
+    /// Open another arena when every arena has at least this many real players.
+    max_players: Option<usize>,
+    /// Retire an automatically opened arena with fewer than this many real players.
+    min_players: usize,
+    /// Most arenas to host at once, including configured ones.
+    max_arenas: usize,
+    /// Creates an arena, given its index (which determines its id) and config.
+    new_arena: Box<dyn Fn(usize, ArenaConfig) -> ContextService<G> + Send + Sync>,
+    /// Index of the next automatically opened arena.
+    next_index: usize,
+    /// Automatically opened arenas, and when they were opened.
+    opened: Vec<(ArenaId, Instant)>,
+    /// Arena whose players are being handed off to other arenas, before it is retired.
+    pub(crate) draining: Option<ArenaId>,
+    rate_limiter: RateLimiter,
+}
+
+impl<G: GameArenaService> ScalingRepo<G> {
+    /// Arenas this young are never retired, giving them time to fill up.
+    const GRACE_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+    pub fn new(
+        max_players: Option<usize>,
+        min_players: Option<usize>,
+        max_arenas: usize,
+        configured_arenas: usize,
+        new_arena: Box<dyn Fn(usize, ArenaConfig) -> ContextService<G> + Send + Sync>,
+    ) -> Self {
+        Self {
+            max_players,
+            min_players: min_players.unwrap_or(max_players.unwrap_or(0) / 4),
+            max_arenas: max_arenas.max(configured_arenas),
+            new_arena,
+            next_index: configured_arenas,
+            opened: Vec::new(),
+            draining: None,
+            rate_limiter: RateLimiter::new(Duration::from_secs(30), 0),
+        }
+    }
+
+    /// Returns the index of the arena that new players should join, absent a preference.
+    pub(crate) fn default_arena_index(&self, arenas: &[ContextService<G>]) -> usize {
+        let max_players = match self.max_players {
+            Some(max_players) => max_players,
+            None => return 0,
+        };
+        let accepting = arenas
+            .iter()
+            .enumerate()
+            .filter(|(_, arena)| self.accepts(arena));
+        accepting
+            .clone()
+            .find(|(_, arena)| arena.context.players.real_players_live < max_players)
+            .or_else(|| accepting.min_by_key(|(_, arena)| arena.context.players.real_players_live))
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+
+    /// Whether new players may join the arena.
+    pub(crate) fn accepts(&self, arena: &ContextService<G>) -> bool {
+        self.draining != Some(arena.context.arena_id)
+    }
+
+    /// Opens, drains, and retires arenas as needed. Rate limited internally.
+    pub(crate) fn update(infrastructure: &mut Infrastructure<G>) {
+        let scaling = &mut infrastructure.scaling;
+        let max_players = match scaling.max_players {
+            Some(max_players) => max_players,
+            None => return,
+        };
+        if scaling.rate_limiter.should_limit_rate() {
+            return;
+        }
+
+        if let Some(draining) = scaling.draining {
+            Self::drain(infrastructure, draining);
+            return;
+        }
+
+        let arenas = &infrastructure.arenas;
+        let live = |arena: &ContextService<G>| arena.context.players.real_players_live;
+
+        if arenas.len() < scaling.max_arenas
+            && arenas.iter().all(|arena| live(arena) >= max_players)
+        {
+            let index = scaling.next_index;
+            scaling.next_index += 1;
+            let arena = (scaling.new_arena)(index, arenas[0].config.clone());
+            info!("opening arena {:?}", arena.context.arena_id);
+            scaling
+                .opened
+                .push((arena.context.arena_id, Instant::now()));
+            infrastructure.arenas.push(arena);
+            return;
+        }
+
+        // Retire at most one arena at a time, provided its players fit in another arena.
+        let now = Instant::now();
+        let retire = scaling
+            .opened
+            .iter()
+            .filter(|(_, opened)| now - *opened >= Self::GRACE_PERIOD)
+            .filter_map(|&(arena_id, _)| arenas.iter().find(|a| a.context.arena_id == arena_id))
+            .find(|arena| {
+                live(arena) < scaling.min_players
+                    && arenas
+                        .iter()
+                        .filter(|other| other.context.arena_id != arena.context.arena_id)
+                        .any(|other| live(other) + live(arena) <= max_players)
+            })
+            .map(|arena| arena.context.arena_id);
+        if let Some(arena_id) = retire {
+            info!("draining arena {:?}", arena_id);
+            scaling.draining = Some(arena_id);
+            Self::drain(infrastructure, arena_id);
+        }
+    }
+
+    /// Hands off connected players to the least populated other arenas, and retires the arena
+    /// once no real players remain (disconnected players eventually expire).
+    fn drain(infrastructure: &mut Infrastructure<G>, arena_id: ArenaId) {
+        let from = match infrastructure
+            .arenas
+            .iter()
+            .position(|arena| arena.context.arena_id == arena_id)
+        {
+            Some(from) => from,
+            None => {
+                infrastructure.scaling.draining = None;
+                return;
+            }
+        };
+
+        for player_id in
+            ClientRepo::connected_player_ids(&infrastructure.arenas[from].context.players)
+        {
+            let to = infrastructure
+                .arenas
+                .iter()
+                .enumerate()
+                .filter(|&(index, _)| index != from)
+                .min_by_key(|(_, arena)| arena.context.players.real_players_live)
+                .map(|(index, _)| index);
+            if let Some(to) = to {
+                if let Err(e) = ClientRepo::hand_off(infrastructure, player_id, from, to) {
+                    warn!("could not hand off {:?}: {}", player_id, e);
+                }
+            }
+        }
+
+        if infrastructure.arenas[from].context.players.real_players == 0 {
+            info!("retiring arena {:?}", arena_id);
+            infrastructure.arenas.remove(from);
+            let scaling = &mut infrastructure.scaling;
+            scaling.opened.retain(|&(id, _)| id != arena_id);
+            scaling.draining = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::ClientStatus;
+    use crate::context_service::{ArenaConfig, ContextService};
+    use crate::game_service::{real_player, MockGame};
+    use crate::infrastructure::Infrastructure;
+    use crate::scaling::ScalingRepo;
+    use core_protocol::id::ArenaId;
+    use server_util::rate_limiter::RateLimiterProps;
+    use std::num::NonZeroU32;
+    use std::time::Instant;
+
+    fn new_arena(index: usize, config: ArenaConfig) -> ContextService<MockGame> {
+        ContextService::new(
+            ArenaId(NonZeroU32::new(index as u32 + 1).unwrap()),
+            config,
+            None,
+            None,
+            None,
+            None,
+            RateLimiterProps::no_limit(),
+        )
+    }
+
+    #[test]
+    fn default_arena_index() {
+        let arenas = vec![
+            new_arena(0, ArenaConfig::default()),
+            new_arena(1, ArenaConfig::default()),
+        ];
+
+        let disabled = ScalingRepo::new(None, None, 4, arenas.len(), Box::new(new_arena));
+        assert_eq!(disabled.default_arena_index(&arenas), 0);
+
+        let mut scaling = ScalingRepo::new(Some(10), None, 4, arenas.len(), Box::new(new_arena));
+        assert_eq!(scaling.default_arena_index(&arenas), 0);
+        scaling.draining = Some(arenas[0].context.arena_id);
+        assert!(!scaling.accepts(&arenas[0]));
+        assert_eq!(scaling.default_arena_index(&arenas), 1);
+    }
+
+    #[test]
+    fn drain() {
+        let mut infrastructure = Infrastructure::<MockGame>::test(2, Some(10));
+        let arena_id = infrastructure.arenas[1].context.arena_id;
+        let connected = real_player(0);
+        let _receiver = infrastructure.test_connect(1, connected);
+        let disconnected = real_player(1);
+        let _ = infrastructure.test_connect(1, disconnected);
+        infrastructure.arenas[1]
+            .context
+            .players
+            .borrow_player_mut(disconnected)
+            .unwrap()
+            .client_mut()
+            .unwrap()
+            .status = ClientStatus::Limbo {
+            expiry: Instant::now(),
+        };
+
+        // Recounts real players, as each update does.
+        let drain = |infrastructure: &mut Infrastructure<MockGame>| {
+            for arena in &mut infrastructure.arenas {
+                let context = &mut arena.context;
+                context.players.delta(&context.teams);
+            }
+            ScalingRepo::drain(infrastructure, arena_id);
+        };
+        infrastructure.scaling.draining = Some(arena_id);
+        infrastructure
+            .scaling
+            .opened
+            .push((arena_id, Instant::now()));
+
+        // Connected players are handed off, but the disconnected player remains until they
+        // expire, and so does the arena.
+        drain(&mut infrastructure);
+        drain(&mut infrastructure);
+        assert_eq!(infrastructure.player_arena_index(connected), Some(0));
+        assert_eq!(infrastructure.player_arena_index(disconnected), Some(1));
+        assert_eq!(infrastructure.arenas.len(), 2);
+        assert_eq!(infrastructure.scaling.draining, Some(arena_id));
+
+        let context = &mut infrastructure.arenas[1].context;
+        context.players.forget(
+            disconnected,
+            &mut context.teams,
+            &mut infrastructure.invitations,
+        );
+        drain(&mut infrastructure);
+        assert_eq!(infrastructure.arenas.len(), 1);
+        assert_ne!(infrastructure.arenas[0].context.arena_id, arena_id);
+        assert_eq!(infrastructure.scaling.draining, None);
+        assert!(infrastructure.scaling.opened.is_empty());
+    }
+}
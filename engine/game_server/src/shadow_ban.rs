@@ -0,0 +1,246 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::game_service::GameArenaService;
+use crate::infrastructure::Infrastructure;
+use crate::player::PlayerRepo;
+use actix::{
+    ActorFutureExt, ActorStreamExt, Context as ActorContext, ContextFutureSpawner, WrapFuture,
+    WrapStream,
+};
+use core_protocol::get_unix_time_now;
+use core_protocol::id::{PlayerId, SessionId, UserId};
+use core_protocol::UnixTime;
+use futures::stream::FuturesUnordered;
+use log::{error, info};
+use server_util::storage::ShadowBanItem;
+use std::marker::PhantomData;
+use std::net::IpAddr;
+
+/// Chat shadow bans, shared by all arenas and kept in storage, so that they survive reconnecting
+/// and server restarts. A softer tier of enforcement than muting, as the player isn't told.
+pub struct ShadowBanRepo<G: GameArenaService> {
+    bans: Vec<ShadowBanItem>,
+    /// Changes to put into storage.
+    pending: Vec<ShadowBanItem>,
+    /// Whether shadow bans were (or are being) loaded from storage.
+    loaded: bool,
+    _spooky: PhantomData<G>,
+}
+
+impl<G: GameArenaService> ShadowBanRepo<G> {
+    /// Shadow bans may last up to a week.
+    const MAX_MINUTES: u32 = 7 * 24 * 60;
+
+    pub fn new() -> Self {
+        Self {
+            bans: Vec::new(),
+            pending: Vec::new(),
+            loaded: false,
+            _spooky: PhantomData,
+        }
+    }
+
+    /// Replaces the shadow ban of a player, or lifts it if `date_expires` is [`None`].
+    fn set(
+        &mut self,
+        session_id: SessionId,
+        user_id: Option<UserId>,
+        ip_address: IpAddr,
+        date_expires: Option<UnixTime>,
+        now: UnixTime,
+    ) {
+        // Replace any shadow ban that would match, so lifting one actually lets the player chat.
+        // Those of other sessions are expired in storage (the same session is just overwritten).
+        let pending = &mut self.pending;
+        self.bans.retain(|ban| {
+            let keep = !ban.matches(session_id, user_id, ip_address);
+            if !keep && (date_expires.is_none() || ban.session_id != session_id) {
+                pending.push(ShadowBanItem {
+                    date_expires: now,
+                    ..ban.clone()
+                });
+            }
+            keep
+        });
+        if let Some(date_expires) = date_expires {
+            let item = ShadowBanItem {
+                session_id,
+                user_id,
+                ip_address,
+                date_expires,
+            };
+            self.pending.push(item.clone());
+            self.bans.push(item);
+        }
+    }
+
+    /// Whether a player is shadow banned. Forgets expired shadow bans.
+    fn contains(
+        &mut self,
+        session_id: SessionId,
+        user_id: Option<UserId>,
+        ip_address: IpAddr,
+        now: UnixTime,
+    ) -> bool {
+        self.bans.retain(|ban| ban.date_expires > now);
+        self.bans
+            .iter()
+            .any(|ban| ban.matches(session_id, user_id, ip_address))
+    }
+
+    /// Whether a player's chat messages should be delivered only to themselves.
+    pub(crate) fn is_shadow_banned(
+        &mut self,
+        player_id: PlayerId,
+        players: &PlayerRepo<G>,
+    ) -> bool {
+        players
+            .borrow_player(player_id)
+            .and_then(|player| {
+                let client = player.client()?;
+                Some(self.contains(
+                    client.session_id,
+                    client.account.as_ref().map(|a| a.user_id),
+                    client.ip_address,
+                    get_unix_time_now(),
+                ))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Delivers a player's messages only to themselves for `minutes` (zero lifts the shadow ban),
+    /// even if they reconnect. Admins don't have a player, so `req_player_id` is [`None`].
+    pub(crate) fn shadow_ban_player(
+        &mut self,
+        req_player_id: Option<PlayerId>,
+        shadow_ban_player_id: PlayerId,
+        minutes: u32,
+        players: &PlayerRepo<G>,
+    ) -> Result<u32, &'static str> {
+        if let Some(req_player_id) = req_player_id {
+            if req_player_id == shadow_ban_player_id {
+                return Err("cannot shadow ban self");
+            }
+            let req_player = players
+                .borrow_player(req_player_id)
+                .ok_or("nonexistent player")?;
+            let req_client = req_player.client().ok_or("not a real player")?;
+            if !req_client.moderator {
+                return Err("permission denied");
+            }
+        }
+        let shadow_ban_player = players
+            .borrow_player(shadow_ban_player_id)
+            .ok_or("nonexistent player")?;
+        let shadow_ban_client = shadow_ban_player.client().ok_or("not a real player")?;
+        let minutes = minutes.min(Self::MAX_MINUTES);
+        let now = get_unix_time_now();
+        self.set(
+            shadow_ban_client.session_id,
+            shadow_ban_client.account.as_ref().map(|a| a.user_id),
+            shadow_ban_client.ip_address,
+            (minutes != 0).then(|| now + minutes as UnixTime * 60 * 1000),
+            now,
+        );
+        Ok(minutes)
+    }
+
+    /// Loads shadow bans from storage, once.
+    pub(crate) fn update_from_storage(
+        infrastructure: &mut Infrastructure<G>,
+        ctx: &mut ActorContext<Infrastructure<G>>,
+    ) {
+        let storage = match infrastructure.storage {
+            Some(storage) if !infrastructure.shadow_bans.loaded => storage,
+            _ => return,
+        };
+        infrastructure.shadow_bans.loaded = true;
+
+        let now = get_unix_time_now();
+        async move { storage.get_shadow_bans(now).await }
+            .into_actor(infrastructure)
+            .map(|result, act, _| match result {
+                Ok(bans) => {
+                    info!("loaded {} shadow bans", bans.len());
+                    let shadow_bans = &mut act.shadow_bans;
+                    // Shadow bans set in the meantime take precedence.
+                    for ban in bans {
+                        if !shadow_bans
+                            .bans
+                            .iter()
+                            .any(|b| b.session_id == ban.session_id)
+                        {
+                            shadow_bans.bans.push(ban);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("error getting shadow bans: {:?}", e);
+                }
+            })
+            .spawn(ctx);
+    }
+
+    /// Puts changed shadow bans into storage, if any.
+    pub(crate) fn update_to_storage(
+        infrastructure: &mut Infrastructure<G>,
+        ctx: &mut ActorContext<Infrastructure<G>>,
+    ) {
+        let storage = match infrastructure.storage {
+            Some(storage) if !infrastructure.shadow_bans.pending.is_empty() => storage,
+            _ => return,
+        };
+        let pending: Vec<ShadowBanItem> = infrastructure.shadow_bans.pending.drain(..).collect();
+
+        pending
+            .into_iter()
+            .map(|item| storage.put_shadow_ban(item))
+            .collect::<FuturesUnordered<_>>()
+            .into_actor(infrastructure)
+            .map(|result, _, _| {
+                if let Err(e) = result {
+                    error!("error putting shadow ban: {:?}", e);
+                }
+            })
+            .finish()
+            .spawn(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_service::MockGame;
+    use crate::shadow_ban::ShadowBanRepo;
+    use core_protocol::id::{SessionId, UserId};
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::num::NonZeroU64;
+
+    #[test]
+    fn shadow_bans() {
+        let session_id = |n: u64| SessionId(NonZeroU64::new(n).unwrap());
+        let user_id = Some(UserId(NonZeroU64::new(1).unwrap()));
+        let ip = |n: u8| IpAddr::V4(Ipv4Addr::new(10, 0, 0, n));
+        let now = 1_000_000;
+        let mut shadow_bans = ShadowBanRepo::<MockGame>::new();
+
+        shadow_bans.set(session_id(1), user_id, ip(1), Some(now + 60_000), now);
+        assert_eq!(shadow_bans.pending.len(), 1);
+        assert!(shadow_bans.contains(session_id(1), user_id, ip(2), now));
+        // Reconnecting from elsewhere with the same account.
+        assert!(shadow_bans.contains(session_id(2), user_id, ip(2), now));
+        // Someone else behind the same NAT.
+        assert!(!shadow_bans.contains(session_id(3), None, ip(1), now));
+        // Expired.
+        assert!(!shadow_bans.contains(session_id(1), user_id, ip(1), now + 60_000));
+        assert!(shadow_bans.bans.is_empty());
+
+        // Lifting is stored too.
+        shadow_bans.pending.clear();
+        shadow_bans.set(session_id(1), None, ip(1), Some(now + 60_000), now);
+        shadow_bans.set(session_id(1), None, ip(1), None, now);
+        assert!(!shadow_bans.contains(session_id(1), None, ip(1), now));
+        assert_eq!(shadow_bans.pending.len(), 2);
+        assert_eq!(shadow_bans.pending[1].date_expires, now);
+    }
+}
@@ -4,6 +4,7 @@
 use crate::game_service::GameArenaService;
 use crate::infrastructure::Infrastructure;
 use actix::{Handler, Message};
+use core_protocol::dto::ArenaDto;
 use core_protocol::rpc::StatusResponse;
 use server_util::health::Health;
 use std::time::{Duration, Instant};
@@ -48,8 +49,21 @@ impl<G: GameArenaService> Handler<StatusRequest> for Infrastructure<G> {
             region_id: self.region_id,
             redirect_server_id: self.admin.redirect_server_id_preference,
             client_hash: Some(self.status.client_hash),
-            // TODO: In the future, this will sum players for all arenas.
-            player_count: Some(self.context_service.context.players.real_players_live as u32),
+            player_count: Some(self.real_players_live() as u32),
+            game_mode: self.arenas[0].config.game_mode.clone(),
+            arenas: if self.arenas.len() > 1 {
+                self.arenas
+                    .iter()
+                    .filter(|arena| self.scaling.accepts(arena))
+                    .map(|arena| ArenaDto {
+                        arena_id: arena.context.arena_id,
+                        player_count: arena.context.players.real_players_live as u32,
+                        game_mode: arena.config.game_mode.clone(),
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            },
             dying_server_ids: self
                 .system
                 .as_ref()
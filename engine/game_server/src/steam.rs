@@ -0,0 +1,167 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use axum::response::{IntoResponse, Redirect};
+use reqwest::Url;
+use std::num::NonZeroU64;
+use std::time::Duration;
+
+/// Steam login, which uses OpenID 2.0 instead of OAuth2.
+pub struct SteamOpenIdRepo {
+    return_to: String,
+    http_client: reqwest::Client,
+}
+
+impl SteamOpenIdRepo {
+    const LOGIN_URL: &'static str = "https://steamcommunity.com/openid/login";
+    const CLAIMED_ID_PREFIX: &'static str = "https://steamcommunity.com/openid/id/";
+    const IDENTIFIER_SELECT: &'static str = "http://specs.openid.net/auth/2.0/identifier_select";
+    const NS: &'static str = "http://specs.openid.net/auth/2.0";
+    /// Longest assertion to accept, which is much longer than a legitimate one.
+    pub const MAX_ASSERTION_LEN: usize = 4096;
+
+    pub fn new(return_to: String) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(4))
+            .build()
+            .unwrap();
+
+        Self {
+            return_to,
+            http_client,
+        }
+    }
+
+    pub fn redirect(&self) -> impl IntoResponse {
+        let url = Url::parse_with_params(
+            Self::LOGIN_URL,
+            &[
+                ("openid.ns", Self::NS),
+                ("openid.mode", "checkid_setup"),
+                ("openid.return_to", &self.return_to),
+                ("openid.realm", &self.return_to),
+                ("openid.identity", Self::IDENTIFIER_SELECT),
+                ("openid.claimed_id", Self::IDENTIFIER_SELECT),
+            ],
+        )
+        .unwrap();
+
+        Redirect::to(url.as_str())
+    }
+
+    /// Verifies an assertion (the `openid.*` query parameters Steam redirected the player back
+    /// with) and returns the 64-bit Steam id.
+    pub async fn authenticate(&self, assertion: String) -> Result<NonZeroU64, String> {
+        if assertion.len() > Self::MAX_ASSERTION_LEN {
+            return Err(String::from("steam assertion too long"));
+        }
+        let mut params: Vec<(String, String)> = url_query_pairs(&assertion)
+            .filter(|(k, _)| k.starts_with("openid."))
+            .collect();
+        let param = |key: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+
+        if param("openid.mode") != Some("id_res") {
+            return Err(String::from("steam assertion has wrong mode"));
+        }
+        if param("openid.op_endpoint") != Some(Self::LOGIN_URL) {
+            return Err(String::from("steam assertion has wrong endpoint"));
+        }
+        if !param("openid.return_to")
+            .map(|r| is_same_endpoint(&self.return_to, r))
+            .unwrap_or(false)
+        {
+            return Err(String::from("steam assertion has wrong return_to"));
+        }
+        let steam_id = param("openid.claimed_id")
+            .and_then(|c| c.strip_prefix(Self::CLAIMED_ID_PREFIX))
+            .and_then(|id| id.parse::<u64>().ok())
+            .and_then(NonZeroU64::new)
+            .ok_or_else(|| String::from("steam assertion has invalid claimed_id"))?;
+
+        // Ask Steam to verify the signature.
+        for (key, value) in &mut params {
+            if key == "openid.mode" {
+                *value = String::from("check_authentication");
+            }
+        }
+        let response = self
+            .http_client
+            .post(Self::LOGIN_URL)
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.lines().any(|line| line.trim() == "is_valid:true") {
+            Ok(steam_id)
+        } else {
+            Err(String::from("steam assertion is invalid"))
+        }
+    }
+}
+
+/// Whether the URL `actual` has the same scheme, host, port, and path as `expected`. A prefix check
+/// wouldn't do, as it accepts hosts like `mk48.io.example.com`.
+fn is_same_endpoint(expected: &str, actual: &str) -> bool {
+    match (Url::parse(expected), Url::parse(actual)) {
+        (Ok(expected), Ok(actual)) => {
+            expected.scheme() == actual.scheme()
+                && expected.host_str() == actual.host_str()
+                && expected.port_or_known_default() == actual.port_or_known_default()
+                && expected.path() == actual.path()
+        }
+        _ => false,
+    }
+}
+
+fn url_query_pairs(query: &str) -> impl Iterator<Item = (String, String)> {
+    let mut url = Url::parse("http://localhost/").unwrap();
+    url.set_query(Some(query.trim_start_matches('?')));
+    url.query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::steam::{is_same_endpoint, url_query_pairs};
+
+    #[test]
+    fn same_endpoint() {
+        let expected = "https://mk48.io/";
+        assert!(is_same_endpoint(expected, "https://mk48.io/"));
+        assert!(is_same_endpoint(expected, "https://mk48.io:443/?foo=bar"));
+        assert!(!is_same_endpoint(expected, "https://mk48.io.example.com/"));
+        assert!(!is_same_endpoint(expected, "http://mk48.io/"));
+        assert!(!is_same_endpoint(expected, "https://mk48.io:8443/"));
+        assert!(!is_same_endpoint(expected, "https://mk48.io/evil"));
+        assert!(!is_same_endpoint(expected, "not a url"));
+    }
+
+    #[test]
+    fn query_pairs() {
+        let pairs: Vec<_> = url_query_pairs(
+            "?openid.mode=id_res&openid.claimed_id=https%3A%2F%2Fsteamcommunity.com%2Fopenid%2Fid%2F7",
+        )
+        .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("openid.mode".to_owned(), "id_res".to_owned()),
+                (
+                    "openid.claimed_id".to_owned(),
+                    "https://steamcommunity.com/openid/id/7".to_owned()
+                ),
+            ]
+        );
+    }
+}
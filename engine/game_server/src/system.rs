@@ -10,7 +10,7 @@ use actix::{
     ActorFutureExt, ActorStreamExt, Context as ActorContext, ContextFutureSpawner, Handler,
     Message, WrapFuture, WrapStream,
 };
-use core_protocol::dto::ServerDto;
+use core_protocol::dto::{ArenaDto, ServerDto};
 use core_protocol::id::{InvitationId, RegionId, ServerId};
 use core_protocol::rpc::{StatusResponse, SystemResponse, SystemUpdate};
 use db_ip::{include_region_database, DbIpDatabase, Region};
@@ -58,6 +58,8 @@ pub(crate) struct ServerData {
     pub home: bool,
     /// Network round-trip-time.
     pub rtt: Duration,
+    /// Exponential moving average of [`Self::rtt`], if the server was ever reachable.
+    pub average_rtt: Option<Duration>,
     /// Last known status of server.
     pub status: ServerStatus,
 }
@@ -108,11 +110,20 @@ pub(crate) enum ServerStatus {
 }
 
 /// Fields that a healthy/unhealthy server may advertise about itself.
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub(crate) struct ServerAdvertisement {
     pub(crate) redirect_server_id: Option<ServerId>,
     pub(crate) client_hash: Option<u64>,
     pub(crate) player_count: Option<u32>,
+    pub(crate) game_mode: Option<String>,
+    pub(crate) arenas: Vec<ArenaDto>,
+}
+
+impl ServerAdvertisement {
+    /// Longest game mode to pass along to clients.
+    const MAX_GAME_MODE_LEN: usize = 32;
+    /// Most arenas to pass along to clients.
+    const MAX_ARENAS: usize = 8;
 }
 
 impl ServerStatus {
@@ -218,12 +229,14 @@ impl<G: GameArenaService> SystemRepo<G> {
         self.servers
             .iter()
             .filter_map(|(&server_id, server)| {
-                if let &ServerStatus::Healthy {
+                if let ServerStatus::Healthy {
                     advertisement:
                         ServerAdvertisement {
                             redirect_server_id,
                             client_hash,
                             player_count,
+                            game_mode,
+                            arenas,
                         },
                     ..
                 } = &server.status
@@ -233,13 +246,16 @@ impl<G: GameArenaService> SystemRepo<G> {
                         .unwrap_or(Self::MISSING_HASH_IS_COMPATIBLE)
                         && redirect_server_id.is_none()
                     {
-                        if let Some((region_id, player_count)) =
-                            server.region_id.zip(player_count.or(Some(0)))
-                        {
+                        if let Some(region_id) = server.region_id {
                             return Some(ServerDto {
                                 server_id,
                                 region_id,
-                                player_count,
+                                player_count: player_count.unwrap_or(0),
+                                game_mode: game_mode.clone(),
+                                ping: server
+                                    .average_rtt
+                                    .map(|rtt| rtt.as_millis().min(u16::MAX as u128) as u16),
+                                arenas: arenas.clone(),
                             });
                         }
                     }
@@ -401,6 +417,21 @@ impl<G: GameArenaService> SystemRepo<G> {
                                 redirect_server_id: status.redirect_server_id,
                                 client_hash: status.client_hash,
                                 player_count: status.player_count,
+                                game_mode: status.game_mode.filter(|game_mode| {
+                                    game_mode.len() <= ServerAdvertisement::MAX_GAME_MODE_LEN
+                                }),
+                                arenas: status
+                                    .arenas
+                                    .into_iter()
+                                    .take(ServerAdvertisement::MAX_ARENAS)
+                                    .map(|mut arena| {
+                                        arena.game_mode = arena.game_mode.filter(|game_mode| {
+                                            game_mode.len()
+                                                <= ServerAdvertisement::MAX_GAME_MODE_LEN
+                                        });
+                                        arena
+                                    })
+                                    .collect(),
                             };
                             if status.healthy {
                                 info!("watchdog {:?} is healthy", server_id);
@@ -463,6 +494,7 @@ impl<G: GameArenaService> SystemRepo<G> {
                 region_id: Self::ip_to_region_id(ping_result.ip),
                 home: ping_result.home,
                 rtt: ping_result.rtt,
+                average_rtt: None,
                 // Will be overwritten.
                 status: ServerStatus::Incompatible,
             });
@@ -470,6 +502,14 @@ impl<G: GameArenaService> SystemRepo<G> {
         server.ip = ping_result.ip;
         server.home = ping_result.home;
         server.rtt = ping_result.rtt;
+        if !matches!(ping_result.status, PingResultStatus::Unreachable) {
+            server.average_rtt = Some(
+                server
+                    .average_rtt
+                    .map(|average| average.mul_f32(0.75) + ping_result.rtt.mul_f32(0.25))
+                    .unwrap_or(ping_result.rtt),
+            );
+        }
         server.status = match ping_result.status {
             PingResultStatus::Unreachable => {
                 let tries = match &server.status {
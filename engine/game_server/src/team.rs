@@ -83,6 +83,8 @@ pub struct PlayerTeamData {
     status: PlayerTeamStatus,
     /// The last [`TeamId`] the game service was informed of via [`GameArenaService::player_changed_team`].
     pub(crate) previous_team_id: Option<TeamId>,
+    /// Whether the player was the captain of their team last time we checked.
+    pub(crate) was_captain: bool,
 }
 
 /// Data related to team, stored in client.
@@ -0,0 +1,191 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::dto::ClientFailure;
+use core_protocol::get_unix_time_now;
+use core_protocol::id::{ArenaId, GameId, PlayerId, RegionId, UserAgentId};
+use core_protocol::name::Referrer;
+use core_protocol::UnixTime;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::error;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::mem;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// What happened, in a [`TraceRecord`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "data")]
+pub enum TraceEvent<'a> {
+    /// A client-side error message, logged for every client (up to a limit).
+    Error {
+        ip: IpAddr,
+        region_id: Option<RegionId>,
+        referrer: Option<Referrer>,
+        user_agent_id: Option<UserAgentId>,
+        message: &'a str,
+    },
+    /// A client failure, reported for telemetry (up to a limit).
+    Failure {
+        ip: IpAddr,
+        user_agent_id: Option<UserAgentId>,
+        /// Version of the client.
+        client_hash: u64,
+        failure: ClientFailure,
+        message: Option<&'a str>,
+    },
+    /// A game request sent by a traced player.
+    Input(serde_json::Value),
+    /// A game event involving a traced player, reported by the game.
+    Event(serde_json::Value),
+}
+
+/// One line of the trace log.
+#[derive(Debug, Serialize)]
+pub struct TraceRecord<'a> {
+    pub timestamp: UnixTime,
+    /// Ticks of the arena since the server started, for ordering records within a second, and
+    /// replaying them.
+    pub tick: u64,
+    pub game_id: GameId,
+    pub arena_id: ArenaId,
+    pub player_id: PlayerId,
+    pub event: TraceEvent<'a>,
+}
+
+/// Appends [`TraceRecord`]s, one JSON object per line, to a gzip compressed file. Records are
+/// buffered and written as one gzip member per batch, so the whole file can be decompressed
+/// with e.g. `zcat`.
+pub(crate) struct TraceLog {
+    path: Arc<str>,
+    buffer: Vec<u8>,
+    flushed: Instant,
+    tick: u64,
+}
+
+impl TraceLog {
+    /// Write the buffer once it is this large.
+    const FLUSH_BYTES: usize = 64 * 1024;
+    /// Write the buffer at least this often, unless it is empty.
+    const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+    pub fn new(path: String) -> Self {
+        Self {
+            path: path.into(),
+            buffer: Vec::new(),
+            flushed: Instant::now(),
+            tick: 0,
+        }
+    }
+
+    /// Buffers a record of an event, stamped with the current time and tick.
+    pub fn record(
+        &mut self,
+        game_id: GameId,
+        arena_id: ArenaId,
+        player_id: PlayerId,
+        event: TraceEvent,
+    ) {
+        let record = TraceRecord {
+            timestamp: get_unix_time_now(),
+            tick: self.tick,
+            game_id,
+            arena_id,
+            player_id,
+            event,
+        };
+        if let Err(e) = serde_json::to_writer(&mut self.buffer, &record) {
+            error!("error composing trace record: {:?}", e);
+            return;
+        }
+        self.buffer.push(b'\n');
+
+        if self.buffer.len() >= Self::FLUSH_BYTES {
+            self.flush();
+        }
+    }
+
+    /// Call once per tick.
+    pub fn update(&mut self) {
+        self.tick += 1;
+        if !self.buffer.is_empty() && self.flushed.elapsed() >= Self::FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    /// Compresses and appends the buffered records, without blocking.
+    fn flush(&mut self) {
+        self.flushed = Instant::now();
+        let lines = mem::take(&mut self.buffer);
+        let path = Arc::clone(&self.path);
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = Self::compress(&lines).and_then(|member| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&*path)
+                    .and_then(|mut file| file.write_all(&member))
+            }) {
+                error!("error logging traces: {:?}", e);
+            }
+        });
+    }
+
+    /// Compresses lines into a gzip member.
+    fn compress(lines: &[u8]) -> io::Result<Vec<u8>> {
+        let mut encoder =
+            GzEncoder::new(Vec::with_capacity(lines.len() / 4), Compression::default());
+        encoder.write_all(lines)?;
+        encoder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trace_log::{TraceEvent, TraceLog};
+    use core_protocol::id::{ArenaId, GameId, PlayerId};
+    use flate2::read::MultiGzDecoder;
+    use std::io::Read;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn members() {
+        let mut log = TraceLog::new(String::from("unused"));
+        let arena_id = ArenaId(NonZeroU32::new(1).unwrap());
+        let player_id = PlayerId(NonZeroU32::new(1).unwrap());
+
+        log.tick = 5;
+        log.record(
+            GameId::Redacted,
+            arena_id,
+            player_id,
+            TraceEvent::Input(serde_json::json!({"Spawn": {}})),
+        );
+        let first = TraceLog::compress(&std::mem::take(&mut log.buffer)).unwrap();
+        log.record(
+            GameId::Redacted,
+            arena_id,
+            player_id,
+            TraceEvent::Event(serde_json::json!("sunk")),
+        );
+        let second = TraceLog::compress(&log.buffer).unwrap();
+
+        // Concatenated members decompress to all the lines.
+        let mut lines = String::new();
+        MultiGzDecoder::new(&[first, second].concat()[..])
+            .read_to_string(&mut lines)
+            .unwrap();
+        let lines: Vec<serde_json::Value> = lines
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["tick"], 5);
+        assert_eq!(lines[0]["event"]["kind"], "input");
+        assert_eq!(lines[1]["event"]["data"], "sunk");
+    }
+}
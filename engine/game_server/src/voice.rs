@@ -0,0 +1,604 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::game_service::GameArenaService;
+use crate::player::PlayerRepo;
+use crate::team::TeamRepo;
+use core_protocol::id::{PlayerId, TeamId};
+use core_protocol::rpc::{VoiceRequest, VoiceSignal, VoiceUpdate};
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+/// Component of [`Context`] dedicated to relaying voice chat signaling.
+///
+/// Each team has one voice room. The server never sees any media, only the signaling that members
+/// need to connect to each other peer-to-peer.
+pub struct VoiceRepo<G> {
+    _spooky: PhantomData<G>,
+}
+
+/// Voice related data stored in client.
+#[derive(Debug, Default)]
+pub struct ClientVoiceData {
+    /// Team whose voice room the client is in.
+    room: Option<TeamId>,
+    /// Team whose captain revoked the client's permission to speak.
+    revoked: Option<TeamId>,
+    /// Players this client has muted.
+    muted: HashSet<PlayerId>,
+    /// Signals that need to be sent to the client, oldest first.
+    inbox: Vec<(PlayerId, VoiceSignal)>,
+    /// For diffing.
+    previous_members: Option<Box<[PlayerId]>>,
+}
+
+impl ClientVoiceData {
+    /// Call when it is reasonable to assume client has forgotten state.
+    pub fn forget_state(&mut self) {
+        self.room = None;
+        self.inbox.clear();
+        self.previous_members = None;
+    }
+
+    /// Returns true iff the client is in the voice room of `team_id`, and may speak there.
+    fn is_speaking_in(&self, team_id: TeamId) -> bool {
+        self.room == Some(team_id) && self.revoked != Some(team_id)
+    }
+
+    /// Returns true iff the client muted `player_id`.
+    fn is_muted(&self, player_id: PlayerId) -> bool {
+        self.muted.contains(&player_id)
+    }
+}
+
+impl<G: GameArenaService> VoiceRepo<G> {
+    /// Maximum length of a relayed signal, which is enough for typical SDP.
+    const MAX_SIGNAL_LENGTH: usize = 8192;
+    /// Maximum number of signals waiting to be sent to a client.
+    const MAX_INBOX: usize = 32;
+
+    pub fn new() -> Self {
+        Self {
+            _spooky: PhantomData,
+        }
+    }
+
+    /// Joins the voice room of the player's team.
+    fn join(
+        &mut self,
+        req_player_id: PlayerId,
+        players: &PlayerRepo<G>,
+    ) -> Result<VoiceUpdate, &'static str> {
+        let mut req_player = players
+            .borrow_player_mut(req_player_id)
+            .ok_or("req player doesn't exist")?;
+
+        let team_id = req_player.team_id().ok_or("not in a team")?;
+
+        let req_client = req_player
+            .client_mut()
+            .ok_or("only clients can use voice")?;
+
+        if req_client.voice.room == Some(team_id) {
+            return Err("already joined");
+        }
+
+        req_client.voice.room = Some(team_id);
+        req_client.voice.inbox.clear();
+        Ok(VoiceUpdate::Joined)
+    }
+
+    /// Leaves the voice room.
+    fn leave(
+        &mut self,
+        req_player_id: PlayerId,
+        players: &PlayerRepo<G>,
+    ) -> Result<VoiceUpdate, &'static str> {
+        let mut req_player = players
+            .borrow_player_mut(req_player_id)
+            .ok_or("req player doesn't exist")?;
+
+        let req_client = req_player
+            .client_mut()
+            .ok_or("only clients can use voice")?;
+
+        req_client.voice.room.take().ok_or("not in voice room")?;
+        req_client.voice.inbox.clear();
+        Ok(VoiceUpdate::Left)
+    }
+
+    /// Stops or resumes exchanging signals with another player.
+    fn mute_player(
+        &mut self,
+        req_player_id: PlayerId,
+        mute_player_id: PlayerId,
+        mute: bool,
+        players: &PlayerRepo<G>,
+    ) -> Result<VoiceUpdate, &'static str> {
+        if req_player_id == mute_player_id {
+            return Err("cannot mute self");
+        }
+
+        let mut req_player = players
+            .borrow_player_mut(req_player_id)
+            .ok_or("req player doesn't exist")?;
+
+        let req_client = req_player
+            .client_mut()
+            .ok_or("only clients can use voice")?;
+
+        if mute {
+            if req_client.voice.muted.insert(mute_player_id) {
+                Ok(VoiceUpdate::Muted(mute_player_id))
+            } else {
+                Err("already muted")
+            }
+        } else if req_client.voice.muted.remove(&mute_player_id) {
+            Ok(VoiceUpdate::Unmuted(mute_player_id))
+        } else {
+            Err("wasn't muted")
+        }
+    }
+
+    /// Revokes or restores a team member's permission to speak. Team captain only.
+    fn permit_player(
+        &mut self,
+        req_player_id: PlayerId,
+        target_player_id: PlayerId,
+        permit: bool,
+        players: &PlayerRepo<G>,
+        teams: &TeamRepo<G>,
+    ) -> Result<VoiceUpdate, &'static str> {
+        if req_player_id == target_player_id {
+            return Err("cannot revoke self");
+        }
+
+        let team_id = players
+            .borrow_player(req_player_id)
+            .ok_or("req player doesn't exist")?
+            .team_id()
+            .ok_or("not in a team")?;
+
+        let team = teams.get(team_id).ok_or("team doesn't exist")?;
+
+        if !team.is_captain(req_player_id) {
+            return Err("only captain can revoke or permit");
+        }
+
+        if !team.is_member(target_player_id) {
+            return Err("not a team member");
+        }
+
+        let mut target_player = players
+            .borrow_player_mut(target_player_id)
+            .ok_or("target player doesn't exist")?;
+
+        let target_client = target_player
+            .client_mut()
+            .ok_or("only clients can use voice")?;
+
+        if permit {
+            if target_client.voice.revoked.take() == Some(team_id) {
+                Ok(VoiceUpdate::Permitted(target_player_id))
+            } else {
+                Err("wasn't revoked")
+            }
+        } else if target_client.voice.revoked.replace(team_id) == Some(team_id) {
+            Err("already revoked")
+        } else {
+            target_client.voice.inbox.clear();
+            Ok(VoiceUpdate::Revoked(target_player_id))
+        }
+    }
+
+    /// Relays a signal to another member of the same voice room.
+    fn signal(
+        &mut self,
+        req_player_id: PlayerId,
+        target_player_id: PlayerId,
+        signal: VoiceSignal,
+        players: &PlayerRepo<G>,
+    ) -> Result<VoiceUpdate, &'static str> {
+        if req_player_id == target_player_id {
+            return Err("cannot signal self");
+        }
+
+        if signal.payload().len() > Self::MAX_SIGNAL_LENGTH {
+            return Err("signal too long");
+        }
+
+        let team_id = {
+            let req_player = players
+                .borrow_player(req_player_id)
+                .ok_or("req player doesn't exist")?;
+
+            let team_id = req_player.team_id().ok_or("not in a team")?;
+
+            let req_client = req_player.client().ok_or("only clients can use voice")?;
+
+            if !req_client.voice.is_speaking_in(team_id) {
+                return Err("not in voice room");
+            }
+
+            if req_client.voice.is_muted(target_player_id) {
+                return Err("target is muted");
+            }
+
+            team_id
+        };
+
+        let mut target_player = players
+            .borrow_player_mut(target_player_id)
+            .ok_or("target player doesn't exist")?;
+
+        if target_player.team_id() != Some(team_id) {
+            return Err("not a teammate");
+        }
+
+        let target_client = target_player
+            .client_mut()
+            .ok_or("only clients can use voice")?;
+
+        if !target_client.voice.is_speaking_in(team_id) {
+            return Err("target not in voice room");
+        }
+
+        if target_client.voice.is_muted(req_player_id) {
+            return Err("muted by target");
+        }
+
+        if target_client.voice.inbox.len() >= Self::MAX_INBOX {
+            return Err("too many signals");
+        }
+
+        target_client.voice.inbox.push((req_player_id, signal));
+        Ok(VoiceUpdate::Signaled)
+    }
+
+    pub fn handle_voice_request(
+        &mut self,
+        req_player_id: PlayerId,
+        request: VoiceRequest,
+        players: &PlayerRepo<G>,
+        teams: &TeamRepo<G>,
+    ) -> Result<VoiceUpdate, &'static str> {
+        match request {
+            VoiceRequest::Join => self.join(req_player_id, players),
+            VoiceRequest::Leave => self.leave(req_player_id, players),
+            VoiceRequest::Mute(player_id) => {
+                self.mute_player(req_player_id, player_id, true, players)
+            }
+            VoiceRequest::Permit(player_id) => {
+                self.permit_player(req_player_id, player_id, true, players, teams)
+            }
+            VoiceRequest::Revoke(player_id) => {
+                self.permit_player(req_player_id, player_id, false, players, teams)
+            }
+            VoiceRequest::Signal { player_id, signal } => {
+                self.signal(req_player_id, player_id, signal, players)
+            }
+            VoiceRequest::Unmute(player_id) => {
+                self.mute_player(req_player_id, player_id, false, players)
+            }
+        }
+    }
+
+    /// Gets the other members of the player's voice room (if they changed) and signals relayed to
+    /// the player. Leaves the voice room if the player is no longer in its team.
+    pub fn player_delta(player_id: PlayerId, players: &PlayerRepo<G>) -> Option<VoiceUpdate> {
+        let members: Box<[PlayerId]> = {
+            let player = players.borrow_player(player_id)?;
+            let voice = &player.client()?.voice;
+            match player
+                .team_id()
+                .filter(|&team_id| voice.is_speaking_in(team_id))
+            {
+                Some(team_id) => players
+                    .iter_borrow()
+                    .filter(|p| p.player_id != player_id && p.team_id() == Some(team_id))
+                    .filter(|p| {
+                        p.client().map_or(false, |c| {
+                            c.voice.is_speaking_in(team_id) && !c.voice.is_muted(player_id)
+                        })
+                    })
+                    .filter(|p| !voice.is_muted(p.player_id))
+                    .map(|p| p.player_id)
+                    .collect(),
+                None => Box::default(),
+            }
+        };
+
+        let mut player = players.borrow_player_mut(player_id)?;
+        let team_id = player.team_id();
+        let voice = &mut player.client_mut()?.voice;
+
+        if voice.room.is_some() && voice.room != team_id {
+            voice.room = None;
+            voice.inbox.clear();
+            voice.previous_members = Some(Box::default());
+            return Some(VoiceUpdate::Left);
+        }
+
+        let members = if voice.previous_members.as_ref() == Some(&members) {
+            None
+        } else {
+            voice.previous_members = Some(members.clone());
+            Some(members)
+        };
+
+        if members.is_none() && voice.inbox.is_empty() {
+            None
+        } else {
+            Some(VoiceUpdate::Updated {
+                members,
+                signals: std::mem::take(&mut voice.inbox).into_boxed_slice(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_service::{real_player, MockGame};
+    use crate::infrastructure::Infrastructure;
+    use crate::voice::VoiceRepo;
+    use core_protocol::id::PlayerId;
+    use core_protocol::name::TeamName;
+    use core_protocol::rpc::{TeamRequest, TeamUpdate, VoiceRequest, VoiceSignal, VoiceUpdate};
+
+    /// Creates an arena in which the first player is captain of a team, the second player is
+    /// a member of that team, and the third player is solo.
+    fn setup() -> (Infrastructure<MockGame>, [PlayerId; 3]) {
+        let mut infrastructure = Infrastructure::<MockGame>::test(1, None);
+        let player_ids = [real_player(0), real_player(1), real_player(2)];
+        for player_id in player_ids {
+            infrastructure.test_connect(0, player_id);
+        }
+
+        let context = &mut infrastructure.arenas[0].context;
+        for player_id in player_ids {
+            context
+                .players
+                .borrow_player_mut(player_id)
+                .unwrap()
+                .was_alive = true;
+        }
+        let [captain, member, _] = player_ids;
+        let team_id = match context.teams.handle_team_request(
+            captain,
+            TeamRequest::Create(TeamName::new_sanitized("voice")),
+            &mut context.players,
+        ) {
+            Ok(TeamUpdate::Created(team_id, _)) => team_id,
+            res => unreachable!("{:?}", res),
+        };
+        assert!(context
+            .teams
+            .handle_team_request(member, TeamRequest::Join(team_id), &mut context.players)
+            .is_ok());
+        assert!(context
+            .teams
+            .handle_team_request(captain, TeamRequest::Accept(member), &mut context.players)
+            .is_ok());
+
+        (infrastructure, player_ids)
+    }
+
+    /// Players must be solo when they are dropped.
+    fn teardown(mut infrastructure: Infrastructure<MockGame>, player_ids: [PlayerId; 3]) {
+        let context = &mut infrastructure.arenas[0].context;
+        for player_id in player_ids {
+            let _ = context.teams.handle_team_request(
+                player_id,
+                TeamRequest::Leave,
+                &mut context.players,
+            );
+        }
+    }
+
+    fn request(
+        infrastructure: &mut Infrastructure<MockGame>,
+        player_id: PlayerId,
+        request: VoiceRequest,
+    ) -> Result<VoiceUpdate, &'static str> {
+        let context = &mut infrastructure.arenas[0].context;
+        context
+            .voice
+            .handle_voice_request(player_id, request, &context.players, &context.teams)
+    }
+
+    fn delta(
+        infrastructure: &Infrastructure<MockGame>,
+        player_id: PlayerId,
+    ) -> Option<VoiceUpdate> {
+        VoiceRepo::player_delta(player_id, &infrastructure.arenas[0].context.players)
+    }
+
+    fn signal(player_id: PlayerId) -> VoiceRequest {
+        VoiceRequest::Signal {
+            player_id,
+            signal: VoiceSignal::Offer(String::from("v=0")),
+        }
+    }
+
+    #[test]
+    fn relay() {
+        let (mut infrastructure, player_ids) = setup();
+        let [captain, member, outsider] = player_ids;
+
+        assert_eq!(
+            request(&mut infrastructure, outsider, VoiceRequest::Join).err(),
+            Some("not in a team")
+        );
+        assert_eq!(
+            request(&mut infrastructure, member, signal(captain)).err(),
+            Some("not in voice room")
+        );
+        assert!(matches!(
+            request(&mut infrastructure, member, VoiceRequest::Join),
+            Ok(VoiceUpdate::Joined)
+        ));
+        assert_eq!(
+            request(&mut infrastructure, member, VoiceRequest::Join).err(),
+            Some("already joined")
+        );
+        assert_eq!(
+            request(&mut infrastructure, member, signal(captain)).err(),
+            Some("target not in voice room")
+        );
+        assert!(matches!(
+            request(&mut infrastructure, captain, VoiceRequest::Join),
+            Ok(VoiceUpdate::Joined)
+        ));
+
+        // Members are sent once, until they change.
+        match delta(&infrastructure, captain) {
+            Some(VoiceUpdate::Updated {
+                members: Some(members),
+                signals,
+            }) => {
+                assert_eq!(&*members, &[member]);
+                assert!(signals.is_empty());
+            }
+            update => panic!("{:?}", update),
+        }
+        assert!(delta(&infrastructure, captain).is_none());
+
+        // Signals are relayed verbatim, and only once.
+        assert!(matches!(
+            request(&mut infrastructure, member, signal(captain)),
+            Ok(VoiceUpdate::Signaled)
+        ));
+        match delta(&infrastructure, captain) {
+            Some(VoiceUpdate::Updated {
+                members: None,
+                signals,
+            }) => {
+                assert_eq!(
+                    &*signals,
+                    &[(member, VoiceSignal::Offer(String::from("v=0")))]
+                );
+            }
+            update => panic!("{:?}", update),
+        }
+        assert!(delta(&infrastructure, captain).is_none());
+
+        assert_eq!(
+            request(&mut infrastructure, member, signal(member)).err(),
+            Some("cannot signal self")
+        );
+        assert_eq!(
+            request(&mut infrastructure, member, signal(outsider)).err(),
+            Some("not a teammate")
+        );
+        assert_eq!(
+            request(
+                &mut infrastructure,
+                member,
+                VoiceRequest::Signal {
+                    player_id: captain,
+                    signal: VoiceSignal::IceCandidate("a".repeat(10000)),
+                }
+            )
+            .err(),
+            Some("signal too long")
+        );
+
+        // Leaving the team leaves the voice room.
+        let context = &mut infrastructure.arenas[0].context;
+        assert!(context
+            .teams
+            .handle_team_request(member, TeamRequest::Leave, &mut context.players)
+            .is_ok());
+        assert!(matches!(
+            delta(&infrastructure, member),
+            Some(VoiceUpdate::Left)
+        ));
+        match delta(&infrastructure, captain) {
+            Some(VoiceUpdate::Updated {
+                members: Some(members),
+                ..
+            }) => assert!(members.is_empty()),
+            update => panic!("{:?}", update),
+        }
+
+        teardown(infrastructure, player_ids);
+    }
+
+    #[test]
+    fn mute_and_revoke() {
+        let (mut infrastructure, player_ids) = setup();
+        let [captain, member, _] = player_ids;
+        for player_id in [captain, member] {
+            assert!(request(&mut infrastructure, player_id, VoiceRequest::Join).is_ok());
+        }
+        for player_id in [captain, member] {
+            assert!(delta(&infrastructure, player_id).is_some());
+        }
+
+        // Muting stops signals in both directions, and hides the muted member.
+        assert!(matches!(
+            request(&mut infrastructure, captain, VoiceRequest::Mute(member)),
+            Ok(VoiceUpdate::Muted(_))
+        ));
+        assert_eq!(
+            request(&mut infrastructure, captain, VoiceRequest::Mute(member)).err(),
+            Some("already muted")
+        );
+        assert_eq!(
+            request(&mut infrastructure, member, signal(captain)).err(),
+            Some("muted by target")
+        );
+        assert_eq!(
+            request(&mut infrastructure, captain, signal(member)).err(),
+            Some("target is muted")
+        );
+        for player_id in [captain, member] {
+            match delta(&infrastructure, player_id) {
+                Some(VoiceUpdate::Updated {
+                    members: Some(members),
+                    ..
+                }) => assert!(members.is_empty()),
+                update => panic!("{:?}", update),
+            }
+        }
+        assert!(matches!(
+            request(&mut infrastructure, captain, VoiceRequest::Unmute(member)),
+            Ok(VoiceUpdate::Unmuted(_))
+        ));
+        assert!(request(&mut infrastructure, member, signal(captain)).is_ok());
+
+        // Only the captain may revoke, and the revoked member can no longer speak.
+        assert_eq!(
+            request(&mut infrastructure, member, VoiceRequest::Revoke(captain)).err(),
+            Some("only captain can revoke or permit")
+        );
+        assert!(matches!(
+            request(&mut infrastructure, captain, VoiceRequest::Revoke(member)),
+            Ok(VoiceUpdate::Revoked(_))
+        ));
+        assert_eq!(
+            request(&mut infrastructure, captain, VoiceRequest::Revoke(member)).err(),
+            Some("already revoked")
+        );
+        assert_eq!(
+            request(&mut infrastructure, member, signal(captain)).err(),
+            Some("not in voice room")
+        );
+        assert_eq!(
+            request(&mut infrastructure, captain, signal(member)).err(),
+            Some("target not in voice room")
+        );
+        assert!(matches!(
+            request(&mut infrastructure, captain, VoiceRequest::Permit(member)),
+            Ok(VoiceUpdate::Permitted(_))
+        ));
+        assert_eq!(
+            request(&mut infrastructure, captain, VoiceRequest::Permit(member)).err(),
+            Some("wasn't revoked")
+        );
+        assert!(request(&mut infrastructure, member, signal(captain)).is_ok());
+
+        teardown(infrastructure, player_ids);
+    }
+}
@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::id::{GameId, PeriodId, ServerId};
+use core_protocol::name::PlayerAlias;
+use log::warn;
+use serde::Serialize;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Something that happened in an arena, worth announcing outside the game.
+#[derive(Clone, Debug)]
+pub enum ArenaEvent {
+    /// The server started, such as after an update or crash.
+    ServerRestart { server_id: Option<ServerId> },
+    /// A player took first place on a leaderboard.
+    HighScore {
+        alias: PlayerAlias,
+        score: u32,
+        period_id: PeriodId,
+    },
+    /// Final standings of a tournament, best first.
+    TournamentResults {
+        name: String,
+        standings: Vec<(PlayerAlias, u32)>,
+    },
+}
+
+impl ArenaEvent {
+    /// Maximum number of tournament standings to include in a message.
+    const MAX_STANDINGS: usize = 10;
+
+    /// Formats the event as a (Discord markdown) message. Aliases are escaped, so they can't
+    /// format the rest of the message.
+    pub fn message(&self, game_id: GameId) -> String {
+        match self {
+            Self::ServerRestart { server_id } => match server_id {
+                Some(server_id) => {
+                    format!("{} server {} restarted.", game_id.name(), server_id.0.get())
+                }
+                None => format!("{} server restarted.", game_id.name()),
+            },
+            Self::HighScore {
+                alias,
+                score,
+                period_id,
+            } => format!(
+                "**{}** set a new {} high score of **{}** in {}!",
+                escape_markdown(alias.as_str()),
+                match period_id {
+                    PeriodId::AllTime => "all-time",
+                    PeriodId::Daily => "daily",
+                    PeriodId::Weekly => "weekly",
+                },
+                score,
+                game_id.name()
+            ),
+            Self::TournamentResults { name, standings } => {
+                let mut message = format!("**{}** results:", escape_markdown(name));
+                for (i, (alias, score)) in standings.iter().take(Self::MAX_STANDINGS).enumerate() {
+                    let _ = write!(
+                        message,
+                        "\n{}. {} ({})",
+                        i + 1,
+                        escape_markdown(alias.as_str()),
+                        score
+                    );
+                }
+                message
+            }
+        }
+    }
+}
+
+/// Escapes characters that Discord would interpret as markdown, or as mentions.
+fn escape_markdown(text: &str) -> String {
+    const SPECIAL: &str = "\\*_~`|<>#@[]():";
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if SPECIAL.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Posts [`ArenaEvent`]s to Discord webhooks.
+pub struct DiscordWebhookRepo {
+    urls: Vec<String>,
+    client: reqwest::Client,
+}
+
+impl DiscordWebhookRepo {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self {
+            urls,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(3))
+                .build()
+                .unwrap(),
+        }
+    }
+
+    /// Posts the event to every webhook in the background, logging any errors.
+    pub fn post(&'static self, event: ArenaEvent, game_id: GameId) {
+        let message = event.message(game_id);
+        tokio::spawn(async move {
+            for url in &self.urls {
+                if let Err(e) = self.post_message(url, &message).await {
+                    warn!("error posting to webhook: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn post_message(&self, url: &str, message: &str) -> Result<(), String> {
+        #[derive(Serialize)]
+        struct AllowedMentions {
+            parse: [&'static str; 0],
+        }
+
+        #[derive(Serialize)]
+        struct ExecuteWebhook<'a> {
+            content: &'a str,
+            /// Never ping anyone, whatever the message contains.
+            allowed_mentions: AllowedMentions,
+        }
+
+        self.client
+            .post(url)
+            .json(&ExecuteWebhook {
+                content: message,
+                allowed_mentions: AllowedMentions { parse: [] },
+            })
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::webhook::{escape_markdown, ArenaEvent};
+    use core_protocol::id::{GameId, PeriodId};
+    use core_protocol::name::PlayerAlias;
+
+    #[test]
+    fn message() {
+        let high_score = ArenaEvent::HighScore {
+            alias: PlayerAlias::new_unsanitized("Captain"),
+            score: 12345,
+            period_id: PeriodId::AllTime,
+        };
+        assert_eq!(
+            high_score.message(GameId::Mk48),
+            "**Captain** set a new all-time high score of **12345** in Mk48.io!"
+        );
+
+        let tournament = ArenaEvent::TournamentResults {
+            name: "Weekend Cup".to_owned(),
+            standings: vec![
+                (PlayerAlias::new_unsanitized("Captain"), 300),
+                (PlayerAlias::new_unsanitized("Admiral"), 200),
+            ],
+        };
+        assert_eq!(
+            tournament.message(GameId::Mk48),
+            "**Weekend Cup** results:\n1. Captain (300)\n2. Admiral (200)"
+        );
+
+        let everyone = ArenaEvent::HighScore {
+            alias: PlayerAlias::new_unsanitized("@everyone"),
+            score: 1,
+            period_id: PeriodId::Daily,
+        };
+        assert_eq!(
+            everyone.message(GameId::Mk48),
+            "**\\@everyone** set a new daily high score of **1** in Mk48.io!"
+        );
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(escape_markdown("Captain"), "Captain");
+        assert_eq!(escape_markdown("**bold**"), "\\*\\*bold\\*\\*");
+        assert_eq!(escape_markdown("<@&123>"), "\\<\\@&123\\>");
+        assert_eq!(escape_markdown("a\\b"), "a\\\\b");
+    }
+}
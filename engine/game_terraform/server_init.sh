@@ -174,7 +174,7 @@ ExecStart=/root/server \
   --ip-address $IP_ADDRESS \
   --domain $DOMAIN \
   --chat-log /root/chat.log \
-  --trace-log /root/trace.log \
+  --trace-log /root/trace.jsonl.gz \
   --admin-config-file /root/admin.toml \
   --discord-client-secret $DISCORD_CLIENT_SECRET \
   --discord-bot-token $DISCORD_BOT_TOKEN \
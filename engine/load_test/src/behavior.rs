@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::name::PlayerAlias;
+use core_protocol::rpc::{ChatRequest, ClientRequest, Request};
+use rand::{Rng, RngCore};
+use std::time::Duration;
+
+/// Scripts what a simulated client sends. Games may implement this to send game requests too.
+pub trait Behavior<GR>: Send + 'static {
+    /// How often to call [`Self::tick`].
+    const PERIOD: Duration = Duration::from_millis(100);
+
+    /// Called every [`Self::PERIOD`], with the time since connecting. Returns requests to send.
+    fn tick(&mut self, elapsed: Duration, rng: &mut dyn RngCore) -> Vec<Request<GR>>;
+
+    /// Called with each (bincode encoded) update received, so that requests may depend on the
+    /// game, e.g. where the client's boat is.
+    fn receive(&mut self, update: &[u8]) {
+        let _ = update;
+    }
+}
+
+/// Game-agnostic behavior, which picks an alias and then chats occasionally.
+pub struct CoreBehavior {
+    index: usize,
+    started: bool,
+    next_chat: Duration,
+}
+
+impl CoreBehavior {
+    /// Creates the behavior of the `index`-th simulated client.
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            started: false,
+            next_chat: Duration::from_secs(10),
+        }
+    }
+}
+
+impl<GR: Send + 'static> Behavior<GR> for CoreBehavior {
+    fn tick(&mut self, elapsed: Duration, rng: &mut dyn RngCore) -> Vec<Request<GR>> {
+        let mut requests = Vec::new();
+        if !self.started {
+            self.started = true;
+            requests.push(Request::Client(ClientRequest::SetAlias(
+                PlayerAlias::new_unsanitized(&format!("Load {}", self.index)),
+            )));
+        }
+        if elapsed >= self.next_chat {
+            self.next_chat = elapsed + Duration::from_secs(rng.gen_range(20..40));
+            requests.push(Request::Chat(ChatRequest::Send {
+                message: format!("Load test message from client {}", self.index),
+                whisper: false,
+            }));
+        }
+        requests
+    }
+}
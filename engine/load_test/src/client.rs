@@ -0,0 +1,87 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::behavior::Behavior;
+use crate::impairment::Impairment;
+use crate::stats::Stats;
+use futures::{SinkExt, StreamExt};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Runs one simulated client until `until`, or until it is disconnected.
+pub(crate) async fn simulate_client<GR: Serialize + Send + 'static, B: Behavior<GR>>(
+    url: String,
+    impairment: Impairment,
+    mut behavior: B,
+    stats: Arc<Mutex<Stats>>,
+    until: Instant,
+) -> Result<(), String> {
+    let (web_socket, _) = connect_async(url).await.map_err(|e| e.to_string())?;
+    let (mut sink, mut stream) = web_socket.split();
+    stats.lock().unwrap().connected += 1;
+
+    // Sends requests once their injected latency elapses.
+    let (sender, mut receiver) = mpsc::unbounded_channel::<(Instant, Vec<u8>)>();
+    let send_task = tokio::spawn(async move {
+        while let Some((send_at, bytes)) = receiver.recv().await {
+            tokio::time::sleep_until(send_at.into()).await;
+            if sink.send(Message::Binary(bytes)).await.is_err() {
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    let mut rng = StdRng::from_entropy();
+    let start = Instant::now();
+    let mut tick = tokio::time::interval(B::PERIOD);
+    // Web sockets preserve order, so a message is never sent or received before its predecessor.
+    let mut last_sent_at = start;
+    let mut last_received_at = None;
+
+    let result = loop {
+        tokio::select! {
+            _ = tick.tick() => {
+                let now = Instant::now();
+                if now >= until {
+                    break Ok(());
+                }
+                for request in behavior.tick(now - start, &mut rng) {
+                    if impairment.lose(&mut rng) {
+                        stats.lock().unwrap().lost_requests += 1;
+                        continue;
+                    }
+                    last_sent_at = last_sent_at.max(now + impairment.delay(&mut rng));
+                    let bytes = bincode::serialize(&request).unwrap();
+                    let _ = sender.send((last_sent_at, bytes));
+                }
+            }
+            message = stream.next() => match message {
+                Some(Ok(Message::Binary(bytes))) => {
+                    // Rather than delaying processing, measure when the update would have arrived.
+                    let received_at = last_received_at
+                        .unwrap_or(start)
+                        .max(Instant::now() + impairment.reliable_delay(&mut rng));
+                    let interval = last_received_at.map(|last| received_at - last);
+                    last_received_at = Some(received_at);
+                    stats.lock().unwrap().record_update(bytes.len(), interval);
+                    behavior.receive(&bytes);
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => break Err(e.to_string()),
+                None => break Err(String::from("disconnected by server")),
+            }
+        }
+    };
+
+    drop(sender);
+    let _ = send_task.await;
+    stats.lock().unwrap().connected -= 1;
+    result
+}
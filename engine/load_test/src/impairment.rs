@@ -0,0 +1,67 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use rand::Rng;
+use std::time::Duration;
+
+/// Simulated network conditions of one direction of a connection.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Impairment {
+    /// Delay added to every message.
+    pub latency: Duration,
+    /// Maximum additional random delay.
+    pub jitter: Duration,
+    /// Probability, from 0 to 1, that a message is lost.
+    pub loss: f32,
+}
+
+impl Impairment {
+    /// Samples how long a message should be delayed.
+    pub fn delay(&self, rng: &mut impl Rng) -> Duration {
+        self.latency + self.jitter.mul_f32(rng.gen::<f32>())
+    }
+
+    /// Samples whether a message should be lost.
+    pub fn lose(&self, rng: &mut impl Rng) -> bool {
+        self.loss > 0.0 && rng.gen_bool(self.loss.min(1.0) as f64)
+    }
+
+    /// Samples how long a message should be delayed over a reliable transport (web sockets use
+    /// TCP), where a lost message is retransmitted after roughly a round trip instead of dropped.
+    pub fn reliable_delay(&self, rng: &mut impl Rng) -> Duration {
+        let delay = self.delay(rng);
+        if self.lose(rng) {
+            delay + self.delay(rng) * 2
+        } else {
+            delay
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::impairment::Impairment;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::time::Duration;
+
+    #[test]
+    fn impairment() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(Impairment::default().delay(&mut rng), Duration::ZERO);
+        assert!(!Impairment::default().lose(&mut rng));
+
+        let impairment = Impairment {
+            latency: Duration::from_millis(50),
+            jitter: Duration::from_millis(20),
+            loss: 1.0,
+        };
+        for _ in 0..100 {
+            let delay = impairment.delay(&mut rng);
+            assert!(delay >= impairment.latency);
+            assert!(delay <= impairment.latency + impairment.jitter);
+            assert!(impairment.lose(&mut rng));
+            assert!(impairment.reliable_delay(&mut rng) >= impairment.latency * 3);
+        }
+    }
+}
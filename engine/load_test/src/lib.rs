@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Simulates many clients connecting to a game server, with injected latency, jitter, and loss,
+//! and reports how steadily the server sends updates and how much bandwidth they use.
+//!
+//! The server should be run with `--client-authenticate-rate-limit 0` so that it accepts every
+//! simulated client despite them sharing an IP address.
+
+use crate::behavior::Behavior;
+use crate::client::simulate_client;
+use crate::impairment::Impairment;
+use crate::stats::Stats;
+use core_protocol::rpc::WebSocketQuery;
+use core_protocol::web_socket::WebSocketProtocol;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+pub mod behavior;
+mod client;
+pub mod impairment;
+pub mod stats;
+
+#[derive(Debug, StructOpt)]
+pub struct LoadTestOptions {
+    /// Web socket URL of the server.
+    #[structopt(long, default_value = "ws://localhost:8080/ws")]
+    pub url: String,
+    /// Number of simulated clients.
+    #[structopt(long, default_value = "100")]
+    pub clients: usize,
+    /// Seconds over which to spread out connecting the clients.
+    #[structopt(long, default_value = "10")]
+    pub ramp_up: f32,
+    /// Seconds to keep the clients connected, after ramping up.
+    #[structopt(long, default_value = "60")]
+    pub duration: f32,
+    /// Injected one-way latency, in milliseconds.
+    #[structopt(long, default_value = "0")]
+    pub latency: u64,
+    /// Maximum injected random one-way latency, in addition to `latency`, in milliseconds.
+    #[structopt(long, default_value = "0")]
+    pub jitter: u64,
+    /// Probability, from 0 to 1, of each message being lost. Lost requests are dropped, whereas
+    /// lost updates are delayed, as if TCP retransmitted them.
+    #[structopt(long, default_value = "0")]
+    pub loss: f32,
+    /// Expected seconds between updates, i.e. the server's tick period.
+    #[structopt(long, default_value = "0.1")]
+    pub tick_period: f32,
+    /// Seconds between reports.
+    #[structopt(long, default_value = "5")]
+    pub report_interval: f32,
+}
+
+impl LoadTestOptions {
+    fn impairment(&self) -> Impairment {
+        Impairment {
+            latency: Duration::from_millis(self.latency),
+            jitter: Duration::from_millis(self.jitter),
+            loss: self.loss.clamp(0.0, 1.0),
+        }
+    }
+
+    fn web_socket_url(&self) -> String {
+        let query = WebSocketQuery {
+            protocol: Some(WebSocketProtocol::Binary),
+            arena_id: None,
+            session_id: None,
+            invitation_id: None,
+            login_id: None,
+            login_type: None,
+            referrer: None,
+        };
+        format!(
+            "{}?{}",
+            self.url,
+            serde_urlencoded::to_string(&query).unwrap()
+        )
+    }
+}
+
+/// Runs a load test, printing a report every [`LoadTestOptions::report_interval`]. Each
+/// simulated client behaves according to `new_behavior(index)`.
+pub async fn run<GR: Serialize + Send + 'static, B: Behavior<GR>, F: FnMut(usize) -> B>(
+    options: LoadTestOptions,
+    mut new_behavior: F,
+) {
+    let stats = Arc::new(Mutex::new(Stats::default()));
+    let start = Instant::now();
+    let ramp_up = Duration::from_secs_f32(options.ramp_up.max(0.0));
+    let until = start + ramp_up + Duration::from_secs_f32(options.duration.max(0.0));
+    let url = options.web_socket_url();
+    let impairment = options.impairment();
+
+    let clients: Vec<_> = (0..options.clients)
+        .map(|index| {
+            let connect_at = start + ramp_up.mul_f32(index as f32 / options.clients as f32);
+            let url = url.clone();
+            let behavior = new_behavior(index);
+            let stats = Arc::clone(&stats);
+            tokio::spawn(async move {
+                tokio::time::sleep_until(connect_at.into()).await;
+                if let Err(e) =
+                    simulate_client(url, impairment, behavior, Arc::clone(&stats), until).await
+                {
+                    eprintln!("client {} failed: {}", index, e);
+                    stats.lock().unwrap().failed += 1;
+                }
+            })
+        })
+        .collect();
+
+    let tick_period = Duration::from_secs_f32(options.tick_period);
+    let mut report_interval =
+        tokio::time::interval(Duration::from_secs_f32(options.report_interval.max(0.1)));
+    // The first tick is immediate.
+    report_interval.tick().await;
+    let mut last_report = Instant::now();
+    while Instant::now() < until {
+        report_interval.tick().await;
+        let now = Instant::now();
+        let report = stats
+            .lock()
+            .unwrap()
+            .take_report(now - last_report, tick_period);
+        last_report = now;
+        println!("[{:>5.1}s] {}", (now - start).as_secs_f32(), report);
+    }
+
+    for client in clients {
+        let _ = client.await;
+    }
+}
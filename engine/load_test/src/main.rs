@@ -0,0 +1,14 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use load_test::behavior::CoreBehavior;
+use load_test::LoadTestOptions;
+use structopt::StructOpt;
+
+/// Load tests a server with game-agnostic simulated clients. Games may call [`load_test::run`]
+/// with their own [`load_test::behavior::Behavior`] to also send game requests.
+#[tokio::main]
+async fn main() {
+    let options = LoadTestOptions::from_args();
+    load_test::run::<(), _, _>(options, CoreBehavior::new).await;
+}
@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+/// Measurements shared by all simulated clients.
+#[derive(Debug, Default)]
+pub struct Stats {
+    /// Clients currently connected.
+    pub connected: usize,
+    /// Clients that failed to connect or were disconnected before the end of the test.
+    pub failed: usize,
+    /// Requests lost to injected packet loss.
+    pub lost_requests: u64,
+    /// Bytes received since the last report.
+    bytes: u64,
+    /// Updates received since the last report.
+    updates: u64,
+    /// Time between consecutive updates to the same client since the last report.
+    intervals: Vec<Duration>,
+}
+
+impl Stats {
+    /// Records an update, and the time since the client's previous update (if any).
+    pub fn record_update(&mut self, bytes: usize, interval: Option<Duration>) {
+        self.bytes += bytes as u64;
+        self.updates += 1;
+        self.intervals.extend(interval);
+    }
+
+    /// Summarizes measurements since the last report, and starts measuring anew.
+    pub fn take_report(&mut self, elapsed: Duration, tick_period: Duration) -> Report {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.sort_unstable();
+        let percentile = |p: f32| -> Duration {
+            intervals
+                .get(((intervals.len() as f32 * p) as usize).min(intervals.len().saturating_sub(1)))
+                .copied()
+                .unwrap_or_default()
+        };
+        // Updates that took more than an extra half tick to arrive.
+        let late = intervals
+            .iter()
+            .filter(|&&interval| interval > tick_period.mul_f32(1.5))
+            .count();
+        let seconds = elapsed.as_secs_f32().max(f32::EPSILON);
+
+        let report = Report {
+            connected: self.connected,
+            failed: self.failed,
+            lost_requests: self.lost_requests,
+            bytes_per_second: self.bytes as f32 / seconds,
+            updates_per_second: self.updates as f32 / seconds,
+            interval_p50: percentile(0.5),
+            interval_p99: percentile(0.99),
+            interval_max: intervals.last().copied().unwrap_or_default(),
+            late_fraction: late as f32 / intervals.len().max(1) as f32,
+        };
+        self.bytes = 0;
+        self.updates = 0;
+        report
+    }
+}
+
+/// Summary of a period of a load test.
+#[derive(Debug)]
+pub struct Report {
+    pub connected: usize,
+    pub failed: usize,
+    pub lost_requests: u64,
+    /// Total of all clients.
+    pub bytes_per_second: f32,
+    /// Total of all clients.
+    pub updates_per_second: f32,
+    pub interval_p50: Duration,
+    pub interval_p99: Duration,
+    pub interval_max: Duration,
+    /// Fraction of updates that arrived more than half a tick late.
+    pub late_fraction: f32,
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let per_client = 1.0 / self.connected.max(1) as f32;
+        write!(
+            f,
+            "{} connected ({} failed), {:.1} kB/s ({:.2} kB/s per client), {:.1} updates/s, \
+            interval p50={}ms p99={}ms max={}ms, {:.1}% late, {} requests lost",
+            self.connected,
+            self.failed,
+            self.bytes_per_second * 0.001,
+            self.bytes_per_second * 0.001 * per_client,
+            self.updates_per_second,
+            self.interval_p50.as_millis(),
+            self.interval_p99.as_millis(),
+            self.interval_max.as_millis(),
+            self.late_fraction * 100.0,
+            self.lost_requests,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::stats::Stats;
+    use std::time::Duration;
+
+    #[test]
+    fn take_report() {
+        let tick_period = Duration::from_millis(100);
+        let mut stats = Stats::default();
+        stats.connected = 2;
+        stats.record_update(1000, None);
+        for millis in 1..=100 {
+            stats.record_update(1000, Some(Duration::from_millis(millis * 2)));
+        }
+
+        let report = stats.take_report(Duration::from_secs(10), tick_period);
+        assert_eq!(report.bytes_per_second, 10100.0);
+        assert_eq!(report.updates_per_second, 10.1);
+        assert_eq!(report.interval_p50, Duration::from_millis(102));
+        assert_eq!(report.interval_p99, Duration::from_millis(200));
+        assert_eq!(report.interval_max, Duration::from_millis(200));
+        assert_eq!(report.late_fraction, 0.25);
+
+        // Measurements start anew, but not the connection counts.
+        let report = stats.take_report(Duration::from_secs(10), tick_period);
+        assert_eq!(report.bytes_per_second, 0.0);
+        assert_eq!(report.interval_max, Duration::ZERO);
+        assert_eq!(report.connected, 2);
+    }
+}
@@ -23,6 +23,10 @@ struct ParticleVertex<T: Vertex + Copy + Pod + Zeroable + 'static> {
 pub struct ParticleLayer<X: Particle> {
     buffer: PointDeque<ParticleVertex<X>>,
     time: f32,
+    /// Fraction of particles to add (a quality knob).
+    density: f32,
+    /// Accumulates density, so that particles are skipped evenly.
+    credit: f32,
 }
 
 impl<X: Particle> DefaultRender for ParticleLayer<X> {
@@ -30,18 +34,33 @@ impl<X: Particle> DefaultRender for ParticleLayer<X> {
         Self {
             buffer: PointDeque::new(renderer),
             time: 0.0,
+            density: 1.0,
+            credit: 0.0,
         }
     }
 }
 
 impl<X: Particle> ParticleLayer<X> {
     /// Adds a particle. The particle will stay alive for its [`LIFESPAN`][`Particle::LIFESPAN`].
+    /// Some particles are skipped if [`Self::set_density`] was less than 1.
     pub fn add(&mut self, p: X) {
+        self.credit += self.density;
+        if self.credit < 1.0 {
+            return;
+        }
+        self.credit -= 1.0;
+
         self.buffer.push_back(ParticleVertex {
             inner: p,
             created: self.time,
         });
     }
+
+    /// Sets the fraction (0 to 1) of particles that are actually added, in order to reduce
+    /// quality on slow devices. Defaults to 1.
+    pub fn set_density(&mut self, density: f32) {
+        self.density = density.clamp(0.0, 1.0);
+    }
 }
 
 impl<X: Particle> Layer for ParticleLayer<X> {
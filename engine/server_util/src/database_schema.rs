@@ -8,7 +8,7 @@ use core_protocol::id::{
     ArenaId, CohortId, GameId, LoginType, PlayerId, ServerId, SessionId, UserAgentId, UserId,
 };
 use core_protocol::metrics::{
-    ContinuousExtremaMetric, DiscreteMetric, HistogramMetric, Metric, RatioMetric,
+    ContinuousExtremaMetric, DiscreteMetric, FunnelMetric, HistogramMetric, Metric, RatioMetric,
 };
 use core_protocol::name::{PlayerAlias, Referrer};
 use core_protocol::serde_util::StrVisitor;
@@ -167,6 +167,9 @@ pub struct Metrics {
     /// Ratio of new players that leave without ever playing.
     #[serde(default, skip_serializing_if = "is_default")]
     pub bounce: RatioMetric,
+    /// Number of client panics reported.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub client_panics: DiscreteMetric,
     /// How many concurrent players.
     #[serde(default, skip_serializing_if = "is_default")]
     pub concurrent: ContinuousExtremaMetric,
@@ -185,12 +188,27 @@ pub struct Metrics {
     /// Client frames per second.
     #[serde(default, skip_serializing_if = "is_default")]
     pub fps: ContinuousExtremaMetric,
+    /// Progress of visits from loading to respawning, and how long it took.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub funnel: FunnelMetric,
+    /// Number of times client graphics quality was lowered automatically.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub graphics_quality_lowered: DiscreteMetric,
     /// Ratio of new players who were invited to new players who were not.
     #[serde(default, skip_serializing_if = "is_default")]
     pub invited: RatioMetric,
+    /// Number of visits via an invitation link.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub invitation_clicks: DiscreteMetric,
+    /// Number of invited players that spawned.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub invitation_spawns: DiscreteMetric,
     /// Number of invitations in RAM cache.
     #[serde(default, skip_serializing_if = "is_default")]
     pub invitations_cached: DiscreteMetric,
+    /// Number of invitation links created.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub invitations_created: DiscreteMetric,
     /// Ratio of players with FPS below 24 to all players.
     #[serde(default, skip_serializing_if = "is_default")]
     pub low_fps: RatioMetric,
@@ -221,6 +239,9 @@ pub struct Metrics {
     /// Percent of available server RAM required by service.
     #[serde(default, skip_serializing_if = "is_default")]
     pub ram: ContinuousExtremaMetric,
+    /// Number of times an inviter was rewarded for the progress of an invited player.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub referral_rewards: DiscreteMetric,
     /// Number of times session was renewed.
     #[serde(default, skip_serializing_if = "is_default")]
     pub renews: DiscreteMetric,
@@ -242,6 +263,9 @@ pub struct Metrics {
     /// Total sessions in cache.
     #[serde(default, skip_serializing_if = "is_default")]
     pub sessions_cached: DiscreteMetric,
+    /// Number of abnormally long client frames reported.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub slow_frames: DiscreteMetric,
     /// Seconds per tick.
     #[serde(default, skip_serializing_if = "is_default")]
     pub spt: ContinuousExtremaMetric,
@@ -263,6 +287,9 @@ pub struct Metrics {
     /// Visits
     #[serde(default, skip_serializing_if = "is_default")]
     pub visits: DiscreteMetric,
+    /// Number of client WebGL context losses reported.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub webgl_context_losses: DiscreteMetric,
 }
 
 macro_rules! fields {
@@ -288,14 +315,20 @@ impl Metrics {
             bandwidth_tx,
             banner_ads,
             bounce,
+            client_panics,
             concurrent,
             connections,
             cpu,
             cpu_steal,
             flop,
             fps,
+            funnel,
+            graphics_quality_lowered,
             invited,
+            invitation_clicks,
+            invitation_spawns,
             invitations_cached,
+            invitations_created,
             low_fps,
             minutes_per_play,
             minutes_per_visit,
@@ -306,6 +339,7 @@ impl Metrics {
             plays_per_visit,
             plays_total,
             ram,
+            referral_rewards,
             renews,
             retention_days,
             retention_histogram,
@@ -313,13 +347,15 @@ impl Metrics {
             rtt,
             score,
             sessions_cached,
+            slow_frames,
             spt,
             teamed,
             toxicity,
             tps,
             uptime,
             video_ads,
-            visits
+            visits,
+            webgl_context_losses
         )
     }
 
@@ -335,14 +371,19 @@ impl Metrics {
             bandwidth_tx,
             banner_ads,
             bounce,
+            client_panics,
             concurrent,
             connections,
             cpu,
             cpu_steal,
             flop,
             fps,
+            graphics_quality_lowered,
             invited,
+            invitation_clicks,
+            invitation_spawns,
             invitations_cached,
+            invitations_created,
             low_fps,
             minutes_per_play,
             minutes_per_visit,
@@ -353,19 +394,22 @@ impl Metrics {
             plays_per_visit,
             plays_total,
             ram,
+            referral_rewards,
             renews,
             retention_days,
             rewarded_ads,
             rtt,
             score,
             sessions_cached,
+            slow_frames,
             spt,
             teamed,
             toxicity,
             tps,
             uptime,
             video_ads,
-            visits
+            visits,
+            webgl_context_losses
         }
     }
 }
@@ -0,0 +1,208 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::rate_limiter::{RateLimiterProps, Units};
+use core_protocol::dto::RateLimitScope;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::time::Instant;
+
+/// Limits the rate of an action per client, per IP address, and globally. An action is only
+/// allowed if it is within the limits of every scope, and only then counts towards them.
+pub struct HierarchicalRateLimiter<C> {
+    client_props: RateLimiterProps,
+    ip_props: RateLimiterProps,
+    global_props: RateLimiterProps,
+    clients: HashMap<C, TokenBucket>,
+    ips: HashMap<IpAddr, TokenBucket>,
+    global: TokenBucket,
+    prune_counter: u8,
+}
+
+/// A token bucket that holds up to `burst + 1` tokens and gains one every `rate_limit`,
+/// represented by when it will be full again.
+#[derive(Clone, Copy, Debug)]
+struct TokenBucket {
+    full_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(now: Instant) -> Self {
+        Self { full_at: now }
+    }
+
+    /// Returns the bucket after taking `usage` tokens, or [`None`] if it doesn't have enough.
+    fn take(self, props: &RateLimiterProps, now: Instant, usage: Units) -> Option<Self> {
+        if props.rate_limit().is_zero() {
+            return Some(self);
+        }
+        let full_at = self
+            .full_at
+            .max(now)
+            .checked_add(props.rate_limit().saturating_mul(usage))?;
+        let capacity = props
+            .rate_limit()
+            .saturating_mul(props.burst().saturating_add(1));
+        (full_at.saturating_duration_since(now) <= capacity).then_some(Self { full_at })
+    }
+
+    fn is_full(&self, now: Instant) -> bool {
+        self.full_at <= now
+    }
+}
+
+impl<C: Copy + Eq + Hash> HierarchicalRateLimiter<C> {
+    /// Creates a rate limiter with the properties of each scope.
+    pub fn new(
+        client_props: RateLimiterProps,
+        ip_props: RateLimiterProps,
+        global_props: RateLimiterProps,
+    ) -> Self {
+        Self {
+            client_props,
+            ip_props,
+            global_props,
+            clients: HashMap::new(),
+            ips: HashMap::new(),
+            global: TokenBucket::new(Instant::now()),
+            prune_counter: 0,
+        }
+    }
+
+    /// Gets the properties of one scope.
+    pub fn props(&self, scope: RateLimitScope) -> RateLimiterProps {
+        match scope {
+            RateLimitScope::Client => self.client_props,
+            RateLimitScope::Ip => self.ip_props,
+            RateLimitScope::Global => self.global_props,
+        }
+    }
+
+    /// Changes the properties of one scope, taking effect immediately.
+    pub fn set_props(&mut self, scope: RateLimitScope, props: RateLimiterProps) {
+        match scope {
+            RateLimitScope::Client => self.client_props = props,
+            RateLimitScope::Ip => self.ip_props = props,
+            RateLimitScope::Global => self.global_props = props,
+        }
+    }
+
+    /// Marks the action as being performed by the client at the ip address.
+    /// Returns true if the action should be blocked (rate limited).
+    pub fn should_limit_rate(&mut self, client: C, ip: IpAddr) -> bool {
+        self.should_limit_rate_with_now_and_usage(client, ip, Instant::now(), 1)
+    }
+
+    /// Like [`Self::should_limit_rate`] but with a known time and amount of usage.
+    pub fn should_limit_rate_with_now_and_usage(
+        &mut self,
+        client: C,
+        ip: IpAddr,
+        now: Instant,
+        usage: Units,
+    ) -> bool {
+        let new = TokenBucket::new(now);
+        let client_bucket = self.clients.get(&client).copied().unwrap_or(new);
+        let ip_bucket = self.ips.get(&ip).copied().unwrap_or(new);
+
+        // Check every scope before taking from any, so that limited actions don't count.
+        let (client_bucket, ip_bucket, global) = match (
+            client_bucket.take(&self.client_props, now, usage),
+            ip_bucket.take(&self.ip_props, now, usage),
+            self.global.take(&self.global_props, now, usage),
+        ) {
+            (Some(client_bucket), Some(ip_bucket), Some(global)) => {
+                (client_bucket, ip_bucket, global)
+            }
+            _ => return true,
+        };
+        self.clients.insert(client, client_bucket);
+        self.ips.insert(ip, ip_bucket);
+        self.global = global;
+
+        self.prune_counter = self.prune_counter.wrapping_add(1);
+        if self.prune_counter == 0 {
+            self.prune();
+        }
+
+        false
+    }
+
+    /// Clean up full buckets, which are equivalent to new ones. Called automatically, so it is
+    /// not necessary to call manually.
+    pub fn prune(&mut self) {
+        let now = Instant::now();
+        self.clients.retain(|_, bucket| !bucket.is_full(now));
+        self.ips.retain(|_, bucket| !bucket.is_full(now));
+    }
+
+    /// Returns size of internal data-structures.
+    pub fn len(&self) -> usize {
+        self.clients.len() + self.ips.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hierarchical_rate_limiter::HierarchicalRateLimiter;
+    use crate::rate_limiter::RateLimiterProps;
+    use core_protocol::dto::RateLimitScope;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn scopes() {
+        let ip_one = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let ip_two = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+        let second = Duration::from_secs(1);
+        let mut limiter = HierarchicalRateLimiter::new(
+            RateLimiterProps::new(second, 1),
+            RateLimiterProps::new(second, 2),
+            RateLimiterProps::no_limit(),
+        );
+        let now = Instant::now();
+        let mut limit =
+            |client: u32, ip| limiter.should_limit_rate_with_now_and_usage(client, ip, now, 1);
+
+        // Client burst.
+        assert!(!limit(1, ip_one));
+        assert!(!limit(1, ip_one));
+        assert!(limit(1, ip_one));
+
+        // Another client on the same IP address only gets the rest of the IP's burst.
+        assert!(!limit(2, ip_one));
+        assert!(limit(2, ip_one));
+
+        // But not on a different IP address.
+        assert!(!limit(3, ip_two));
+
+        // Bursts refill.
+        let later = now + second * 2;
+        assert!(!limiter.should_limit_rate_with_now_and_usage(1, ip_one, later, 1));
+
+        // Global limit can be tuned.
+        limiter.set_props(
+            RateLimitScope::Global,
+            RateLimiterProps::new(second * 10, 0),
+        );
+        assert_eq!(limiter.props(RateLimitScope::Global).burst(), 0);
+        assert!(!limiter.should_limit_rate_with_now_and_usage(4, ip_two, later, 1));
+        assert!(limiter.should_limit_rate_with_now_and_usage(5, ip_two, later, 1));
+    }
+
+    #[test]
+    fn prune() {
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let mut limiter = HierarchicalRateLimiter::new(
+            RateLimiterProps::new(Duration::from_millis(10), 0),
+            RateLimiterProps::no_limit(),
+            RateLimiterProps::no_limit(),
+        );
+        assert!(!limiter.should_limit_rate(1u32, ip));
+        assert_eq!(limiter.len(), 2);
+        std::thread::sleep(Duration::from_millis(20));
+        limiter.prune();
+        assert_eq!(limiter.len(), 0);
+    }
+}
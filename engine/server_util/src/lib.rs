@@ -10,6 +10,7 @@ pub mod database;
 pub mod database_schema;
 pub mod generate_id;
 pub mod health;
+pub mod hierarchical_rate_limiter;
 pub mod http;
 pub mod ip_rate_limiter;
 pub mod linode;
@@ -18,4 +19,9 @@ pub mod observer;
 pub mod os;
 pub mod rate_limiter;
 pub mod ssl;
+pub mod storage;
+#[cfg(feature = "postgres")]
+pub mod storage_postgres;
+#[cfg(feature = "sqlite")]
+pub mod storage_sqlite;
 pub mod user_agent;
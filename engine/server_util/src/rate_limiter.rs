@@ -21,6 +21,7 @@ pub struct RateLimiterState {
 }
 
 /// The (sharable) properties of a rate limiter.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct RateLimiterProps {
     rate_limit: Duration,
     burst: Units,
@@ -109,6 +110,16 @@ impl RateLimiterProps {
             burst: 0,
         }
     }
+
+    /// Average time between actions, or zero if there is no limit.
+    pub fn rate_limit(&self) -> Duration {
+        self.rate_limit
+    }
+
+    /// Number of actions allowed in a burst, in addition to the average rate.
+    pub fn burst(&self) -> Units {
+        self.burst
+    }
 }
 
 impl RateLimiter {
@@ -0,0 +1,542 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use async_trait::async_trait;
+use core_protocol::dto::MatchDto;
+use core_protocol::id::{LoginType, SessionId, UserId};
+use core_protocol::name::PlayerAlias;
+use core_protocol::UnixTime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::num::NonZeroU64;
+use std::str::FromStr;
+
+/// Account data that is synchronized across a player's devices.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountItem {
+    /// Identifies the account, independently of which identities are linked to it.
+    pub user_id: UserId,
+    pub alias: Option<PlayerAlias>,
+    /// Client settings, by storage key.
+    pub settings: BTreeMap<String, String>,
+    /// Game-specific progress, such as what cosmetics are unlocked.
+    pub unlocks: Option<String>,
+    pub date_updated: UnixTime,
+}
+
+impl AccountItem {
+    /// Maximum number of settings per account.
+    pub const MAX_SETTINGS: usize = 64;
+    /// Maximum length of each setting's key and value.
+    pub const MAX_SETTING_LEN: usize = 1024;
+
+    /// Creates an empty account.
+    pub fn new(user_id: UserId) -> Self {
+        Self {
+            user_id,
+            alias: None,
+            settings: BTreeMap::new(),
+            unlocks: None,
+            date_updated: 0,
+        }
+    }
+
+    /// Replaces the settings, returning an error if there are too many or they are too long.
+    pub fn set_settings(
+        &mut self,
+        settings: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<(), &'static str> {
+        let mut new = BTreeMap::new();
+        for (key, value) in settings {
+            if key.len() > Self::MAX_SETTING_LEN || value.len() > Self::MAX_SETTING_LEN {
+                return Err("setting too long");
+            }
+            new.insert(key, value);
+            if new.len() > Self::MAX_SETTINGS {
+                return Err("too many settings");
+            }
+        }
+        self.settings = new;
+        Ok(())
+    }
+}
+
+/// A chat shadow ban, stored so that it survives reconnecting and server restarts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShadowBanItem {
+    /// Session of the shadow banned player, which is kept when reconnecting.
+    pub session_id: SessionId,
+    /// Account of the shadow banned player, if they were signed in.
+    pub user_id: Option<UserId>,
+    /// IP address of the shadow banned player. Only used if neither they nor the player being
+    /// matched are signed in, as many players may share one (e.g. behind NAT).
+    pub ip_address: IpAddr,
+    /// When the shadow ban expires. Lifting a shadow ban expires it immediately.
+    pub date_expires: UnixTime,
+}
+
+impl ShadowBanItem {
+    /// Whether the shadow ban applies to a player, by session, then account, and only then (if
+    /// neither is signed in) IP address.
+    pub fn matches(
+        &self,
+        session_id: SessionId,
+        user_id: Option<UserId>,
+        ip_address: IpAddr,
+    ) -> bool {
+        self.session_id == session_id
+            || match (self.user_id, user_id) {
+                (Some(a), Some(b)) => a == b,
+                (None, None) => self.ip_address == ip_address,
+                _ => false,
+            }
+    }
+}
+
+/// A player who followed the invitation link of an account, and progressed far enough for the
+/// account to be rewarded. Stored so that the reward isn't lost if the account's player leaves.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReferralItem {
+    /// Account that sent the invitation.
+    pub user_id: UserId,
+    /// IP address of the invited player, so that one person can't be referred repeatedly.
+    pub ip_address: IpAddr,
+    /// Whether the account was rewarded for the referral.
+    pub credited: bool,
+    pub date_created: UnixTime,
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    /// Error from the database.
+    Sql(String),
+    /// Stored data couldn't be (de)serialized.
+    Serde(serde_json::Error),
+    /// The URL's scheme isn't supported (or support wasn't compiled in).
+    Unsupported(&'static str),
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl From<sqlx::Error> for StorageError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Sql(e.to_string())
+    }
+}
+
+/// Optional server-side storage of accounts, so that they may be synchronized across devices.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Applies any of [`MIGRATIONS`] that weren't already applied.
+    async fn migrate(&self) -> Result<(), StorageError>;
+
+    async fn get_account(&self, user_id: UserId) -> Result<Option<AccountItem>, StorageError>;
+
+    /// Inserts or replaces an account.
+    async fn put_account(&self, account: AccountItem) -> Result<(), StorageError>;
+
+    /// Gets the account linked to a third-party identity, such as a Discord user id.
+    async fn get_user_id(
+        &self,
+        login_type: LoginType,
+        login_id: &str,
+    ) -> Result<Option<UserId>, StorageError>;
+
+    /// Links a third-party identity to an account, replacing any previous link.
+    async fn link_identity(
+        &self,
+        login_type: LoginType,
+        login_id: &str,
+        user_id: UserId,
+    ) -> Result<(), StorageError>;
+
+    /// Archives the result of a match, indexed by the user ids of its participants.
+    async fn put_match(&self, item: MatchDto) -> Result<(), StorageError>;
+
+    /// Gets the most recent matches (up to [`MAX_MATCHES`]) that an account took part in, newest
+    /// first.
+    async fn get_matches(&self, user_id: UserId) -> Result<Vec<MatchDto>, StorageError>;
+
+    /// Inserts or replaces the shadow ban of a session.
+    async fn put_shadow_ban(&self, item: ShadowBanItem) -> Result<(), StorageError>;
+
+    /// Gets the shadow bans that expire after `now`.
+    async fn get_shadow_bans(&self, now: UnixTime) -> Result<Vec<ShadowBanItem>, StorageError>;
+
+    /// Inserts a referral, unless the account already has one from the same IP address, in which
+    /// case it is only updated to be credited (never un-credited).
+    async fn put_referral(&self, item: ReferralItem) -> Result<(), StorageError>;
+
+    /// Gets the referrals of an account.
+    async fn get_referrals(&self, user_id: UserId) -> Result<Vec<ReferralItem>, StorageError>;
+}
+
+/// Maximum number of matches returned by [`Storage::get_matches`].
+pub const MAX_MATCHES: usize = 20;
+
+/// How a [`LoginType`] is stored. Must never change.
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+pub(crate) fn login_type_str(login_type: LoginType) -> &'static str {
+    match login_type {
+        LoginType::Discord => "discord",
+        LoginType::Google => "google",
+        LoginType::Steam => "steam",
+    }
+}
+
+/// Schema changes, applied in order by [`Storage::migrate`]. The number of applied migrations is
+/// stored in the `schema_version` table. Existing migrations must never be edited; append new
+/// ones instead. Must be valid for both SQLite and PostgreSQL.
+pub const MIGRATIONS: &[&str] = &[
+    // 0: Accounts. Discord ids are stored as signed 64-bit integers.
+    "CREATE TABLE accounts (
+        discord_id BIGINT PRIMARY KEY,
+        alias TEXT,
+        settings TEXT NOT NULL,
+        unlocks TEXT,
+        date_updated BIGINT NOT NULL
+    )",
+    // 1-3: Accounts may be linked to multiple identities, not just Discord. Existing accounts keep
+    // their Discord id as their user id.
+    "ALTER TABLE accounts RENAME COLUMN discord_id TO user_id",
+    "CREATE TABLE identities (
+        login_type TEXT NOT NULL,
+        login_id TEXT NOT NULL,
+        user_id BIGINT NOT NULL,
+        PRIMARY KEY (login_type, login_id)
+    )",
+    "INSERT INTO identities (login_type, login_id, user_id)
+        SELECT 'discord', CAST(user_id AS TEXT), user_id FROM accounts",
+    // 4-6: Archive of match results, which may be looked up by participant.
+    "CREATE TABLE matches (
+        match_id BIGINT PRIMARY KEY,
+        data TEXT NOT NULL,
+        date_ended BIGINT NOT NULL
+    )",
+    "CREATE TABLE match_participants (
+        user_id BIGINT NOT NULL,
+        match_id BIGINT NOT NULL,
+        PRIMARY KEY (user_id, match_id)
+    )",
+    "CREATE INDEX matches_date_ended ON matches (date_ended)",
+    // 7: Chat shadow bans.
+    "CREATE TABLE shadow_bans (
+        session_id BIGINT PRIMARY KEY,
+        user_id BIGINT,
+        ip_address TEXT NOT NULL,
+        date_expires BIGINT NOT NULL
+    )",
+    // 8: Referrals, by the account that sent the invitation.
+    "CREATE TABLE referrals (
+        user_id BIGINT NOT NULL,
+        ip_address TEXT NOT NULL,
+        credited BOOLEAN NOT NULL,
+        date_created BIGINT NOT NULL,
+        PRIMARY KEY (user_id, ip_address)
+    )",
+];
+
+/// Connects to storage at a URL such as `sqlite://accounts.db` or `postgres://user@host/db`, and
+/// applies any pending migrations.
+pub async fn connect(url: &str) -> Result<Box<dyn Storage>, StorageError> {
+    let storage = if url.starts_with("sqlite:") {
+        connect_sqlite(url).await?
+    } else if url.starts_with("postgres:") || url.starts_with("postgresql:") {
+        connect_postgres(url).await?
+    } else {
+        return Err(StorageError::Unsupported("unknown storage url scheme"));
+    };
+    storage.migrate().await?;
+    Ok(storage)
+}
+
+#[cfg(feature = "sqlite")]
+async fn connect_sqlite(url: &str) -> Result<Box<dyn Storage>, StorageError> {
+    Ok(Box::new(
+        crate::storage_sqlite::SqliteStorage::connect(url).await?,
+    ))
+}
+
+#[cfg(not(feature = "sqlite"))]
+async fn connect_sqlite(_url: &str) -> Result<Box<dyn Storage>, StorageError> {
+    Err(StorageError::Unsupported("compiled without sqlite feature"))
+}
+
+#[cfg(feature = "postgres")]
+async fn connect_postgres(url: &str) -> Result<Box<dyn Storage>, StorageError> {
+    Ok(Box::new(
+        crate::storage_postgres::PostgresStorage::connect(url).await?,
+    ))
+}
+
+#[cfg(not(feature = "postgres"))]
+async fn connect_postgres(_url: &str) -> Result<Box<dyn Storage>, StorageError> {
+    Err(StorageError::Unsupported(
+        "compiled without postgres feature",
+    ))
+}
+
+/// Columns of the accounts table, as stored.
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+pub(crate) struct AccountRow {
+    pub user_id: i64,
+    pub alias: Option<String>,
+    pub settings: String,
+    pub unlocks: Option<String>,
+    pub date_updated: i64,
+}
+
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+impl AccountRow {
+    pub fn from_item(item: &AccountItem) -> Result<Self, StorageError> {
+        Ok(Self {
+            user_id: item.user_id.0.get() as i64,
+            alias: item.alias.map(|alias| alias.as_str().to_owned()),
+            settings: serde_json::to_string(&item.settings).map_err(StorageError::Serde)?,
+            unlocks: item.unlocks.clone(),
+            date_updated: item.date_updated as i64,
+        })
+    }
+
+    pub fn into_item(self) -> Result<AccountItem, StorageError> {
+        Ok(AccountItem {
+            user_id: NonZeroU64::new(self.user_id as u64)
+                .map(UserId)
+                .ok_or(StorageError::Sql(String::from("zero user id")))?,
+            alias: self.alias.map(|alias| PlayerAlias::new_unsanitized(&alias)),
+            settings: serde_json::from_str(&self.settings).map_err(StorageError::Serde)?,
+            unlocks: self.unlocks,
+            date_updated: self.date_updated as UnixTime,
+        })
+    }
+}
+
+/// Columns of the matches table, as stored. The match is stored as JSON, so that its format may
+/// change without a migration.
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+pub(crate) struct MatchRow {
+    pub match_id: i64,
+    pub data: String,
+    pub date_ended: i64,
+}
+
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+impl MatchRow {
+    pub fn from_item(item: &MatchDto) -> Result<Self, StorageError> {
+        Ok(Self {
+            match_id: item.match_id.0.get() as i64,
+            data: serde_json::to_string(item).map_err(StorageError::Serde)?,
+            date_ended: item.date_ended as i64,
+        })
+    }
+
+    pub fn into_item(self) -> Result<MatchDto, StorageError> {
+        serde_json::from_str(&self.data).map_err(StorageError::Serde)
+    }
+
+    /// User ids of the participants that were logged in, without duplicates.
+    pub fn user_ids(item: &MatchDto) -> Vec<i64> {
+        let mut user_ids: Vec<i64> = item
+            .participants
+            .iter()
+            .filter_map(|p| p.user_id)
+            .map(|user_id| user_id.0.get() as i64)
+            .collect();
+        user_ids.sort_unstable();
+        user_ids.dedup();
+        user_ids
+    }
+}
+
+/// Columns of the shadow bans table, as stored.
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+pub(crate) struct ShadowBanRow {
+    pub session_id: i64,
+    pub user_id: Option<i64>,
+    pub ip_address: String,
+    pub date_expires: i64,
+}
+
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+impl ShadowBanRow {
+    pub fn from_item(item: &ShadowBanItem) -> Self {
+        Self {
+            session_id: item.session_id.0.get() as i64,
+            user_id: item.user_id.map(|user_id| user_id.0.get() as i64),
+            ip_address: item.ip_address.to_string(),
+            date_expires: item.date_expires as i64,
+        }
+    }
+
+    pub fn into_item(self) -> Result<ShadowBanItem, StorageError> {
+        Ok(ShadowBanItem {
+            session_id: NonZeroU64::new(self.session_id as u64)
+                .map(SessionId)
+                .ok_or(StorageError::Sql(String::from("zero session id")))?,
+            user_id: self
+                .user_id
+                .and_then(|user_id| NonZeroU64::new(user_id as u64))
+                .map(UserId),
+            ip_address: IpAddr::from_str(&self.ip_address)
+                .map_err(|_| StorageError::Sql(String::from("invalid ip address")))?,
+            date_expires: self.date_expires as UnixTime,
+        })
+    }
+}
+
+/// Columns of the referrals table, as stored.
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+pub(crate) struct ReferralRow {
+    pub user_id: i64,
+    pub ip_address: String,
+    pub credited: bool,
+    pub date_created: i64,
+}
+
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+impl ReferralRow {
+    pub fn from_item(item: &ReferralItem) -> Self {
+        Self {
+            user_id: item.user_id.0.get() as i64,
+            ip_address: item.ip_address.to_string(),
+            credited: item.credited,
+            date_created: item.date_created as i64,
+        }
+    }
+
+    pub fn into_item(self) -> Result<ReferralItem, StorageError> {
+        Ok(ReferralItem {
+            user_id: NonZeroU64::new(self.user_id as u64)
+                .map(UserId)
+                .ok_or(StorageError::Sql(String::from("zero user id")))?,
+            ip_address: IpAddr::from_str(&self.ip_address)
+                .map_err(|_| StorageError::Sql(String::from("invalid ip address")))?,
+            credited: self.credited,
+            date_created: self.date_created as UnixTime,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::storage::{
+        AccountItem, AccountRow, MatchRow, ReferralItem, ReferralRow, ShadowBanItem, ShadowBanRow,
+    };
+    use core_protocol::dto::{MatchDto, MatchParticipantDto};
+    use core_protocol::id::{ArenaId, MatchId, SessionId, UserId};
+    use core_protocol::name::PlayerAlias;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::num::{NonZeroU32, NonZeroU64};
+
+    #[test]
+    fn account_row() {
+        let mut account = AccountItem::new(UserId(NonZeroU64::new(u64::MAX).unwrap()));
+        account.alias = Some(PlayerAlias::new_unsanitized("Captain"));
+        account
+            .set_settings([("volume".to_owned(), "0.5".to_owned())])
+            .unwrap();
+        account.unlocks = Some("5".to_owned());
+
+        let row = AccountRow::from_item(&account).unwrap();
+        assert_eq!(row.user_id, -1);
+        assert_eq!(row.into_item().unwrap(), account);
+    }
+
+    #[test]
+    fn match_row() {
+        let participant = |alias: &str, user_id: Option<u64>| MatchParticipantDto {
+            alias: PlayerAlias::new_unsanitized(alias),
+            user_id: user_id.and_then(NonZeroU64::new).map(UserId),
+            score: 100,
+            kills: 1,
+        };
+        let item = MatchDto {
+            match_id: MatchId(NonZeroU64::new(u64::MAX).unwrap()),
+            arena_id: ArenaId(NonZeroU32::new(1).unwrap()),
+            server_id: None,
+            game_mode: "ffa".to_owned(),
+            date_started: 1000,
+            date_ended: 2000,
+            participants: vec![
+                participant("Captain", Some(2)),
+                participant("Guest", None),
+                participant("Admiral", Some(1)),
+                participant("Captain", Some(2)),
+            ],
+        };
+
+        let row = MatchRow::from_item(&item).unwrap();
+        assert_eq!(row.match_id, -1);
+        assert_eq!(row.date_ended, 2000);
+        assert_eq!(MatchRow::user_ids(&item), vec![1, 2]);
+        assert_eq!(row.into_item().unwrap(), item);
+    }
+
+    #[test]
+    fn set_settings() {
+        let mut account = AccountItem::new(UserId(NonZeroU64::new(1).unwrap()));
+        let too_many = (0..=AccountItem::MAX_SETTINGS).map(|i| (i.to_string(), String::new()));
+        assert!(account.set_settings(too_many).is_err());
+        let too_long = [(String::new(), "x".repeat(AccountItem::MAX_SETTING_LEN + 1))];
+        assert!(account.set_settings(too_long).is_err());
+        assert!(account.settings.is_empty());
+    }
+
+    #[test]
+    fn shadow_ban_row() {
+        let item = ShadowBanItem {
+            session_id: SessionId(NonZeroU64::new(u64::MAX).unwrap()),
+            user_id: Some(UserId(NonZeroU64::new(2).unwrap())),
+            ip_address: IpAddr::V6(Ipv6Addr::LOCALHOST),
+            date_expires: 1000,
+        };
+        let row = ShadowBanRow::from_item(&item);
+        assert_eq!(row.session_id, -1);
+        assert_eq!(row.into_item().unwrap(), item);
+    }
+
+    #[test]
+    fn referral_row() {
+        let item = ReferralItem {
+            user_id: UserId(NonZeroU64::new(u64::MAX).unwrap()),
+            ip_address: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            credited: true,
+            date_created: 1000,
+        };
+        let row = ReferralRow::from_item(&item);
+        assert_eq!(row.user_id, -1);
+        assert_eq!(row.ip_address, "10.0.0.1");
+        assert_eq!(row.into_item().unwrap(), item);
+    }
+
+    #[test]
+    fn shadow_ban_matches() {
+        let session_id = |n: u64| SessionId(NonZeroU64::new(n).unwrap());
+        let user_id = |n: u64| Some(UserId(NonZeroU64::new(n).unwrap()));
+        let ip = |n: u8| IpAddr::V4(Ipv4Addr::new(10, 0, 0, n));
+        let anonymous = ShadowBanItem {
+            session_id: session_id(1),
+            user_id: None,
+            ip_address: ip(1),
+            date_expires: 1000,
+        };
+        let signed_in = ShadowBanItem {
+            user_id: user_id(1),
+            ..anonymous.clone()
+        };
+
+        for ban in [&anonymous, &signed_in] {
+            // Reconnecting from elsewhere with the same session.
+            assert!(ban.matches(session_id(1), None, ip(2)));
+            // Someone else, signed in, behind the same NAT.
+            assert!(!ban.matches(session_id(2), user_id(2), ip(1)));
+        }
+        // A new session, without signing in, from the same IP address.
+        assert!(anonymous.matches(session_id(2), None, ip(1)));
+        assert!(!signed_in.matches(session_id(2), None, ip(1)));
+        // Signing in from elsewhere.
+        assert!(signed_in.matches(session_id(2), user_id(1), ip(2)));
+        assert!(!anonymous.matches(session_id(2), user_id(1), ip(2)));
+        assert!(!anonymous.matches(session_id(2), None, ip(2)));
+    }
+}
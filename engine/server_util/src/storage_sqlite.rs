@@ -0,0 +1,264 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::storage::{
+    login_type_str, AccountItem, AccountRow, MatchRow, ReferralItem, ReferralRow, ShadowBanItem,
+    ShadowBanRow, Storage, StorageError, MAX_MATCHES, MIGRATIONS,
+};
+use async_trait::async_trait;
+use core_protocol::dto::MatchDto;
+use core_protocol::id::{LoginType, UserId};
+use core_protocol::UnixTime;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::num::NonZeroU64;
+use std::str::FromStr;
+
+/// [`Storage`] in a SQLite database file, suitable for a single server.
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// Opens (creating if necessary) the database at a URL like `sqlite://accounts.db`.
+    pub async fn connect(url: &str) -> Result<Self, StorageError> {
+        let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect_with(options)
+            .await?;
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn migrate(&self) -> Result<(), StorageError> {
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query("CREATE TABLE IF NOT EXISTS schema_version (version BIGINT NOT NULL)")
+            .execute(&mut transaction)
+            .await?;
+        let version: Option<i64> = sqlx::query_scalar("SELECT version FROM schema_version")
+            .fetch_optional(&mut transaction)
+            .await?;
+        let applied = version.unwrap_or(0) as usize;
+        if applied > MIGRATIONS.len() {
+            return Err(StorageError::Sql(format!(
+                "schema version {} is newer than this server",
+                applied
+            )));
+        }
+        for migration in &MIGRATIONS[applied..] {
+            sqlx::query(migration).execute(&mut transaction).await?;
+        }
+        sqlx::query("DELETE FROM schema_version")
+            .execute(&mut transaction)
+            .await?;
+        sqlx::query("INSERT INTO schema_version (version) VALUES (?)")
+            .bind(MIGRATIONS.len() as i64)
+            .execute(&mut transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn get_account(&self, user_id: UserId) -> Result<Option<AccountItem>, StorageError> {
+        let row: Option<(i64, Option<String>, String, Option<String>, i64)> = sqlx::query_as(
+            "SELECT user_id, alias, settings, unlocks, date_updated FROM accounts \
+            WHERE user_id = ?",
+        )
+        .bind(user_id.0.get() as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|(user_id, alias, settings, unlocks, date_updated)| {
+            AccountRow {
+                user_id,
+                alias,
+                settings,
+                unlocks,
+                date_updated,
+            }
+            .into_item()
+        })
+        .transpose()
+    }
+
+    async fn put_account(&self, account: AccountItem) -> Result<(), StorageError> {
+        let row = AccountRow::from_item(&account)?;
+        sqlx::query(
+            "INSERT INTO accounts (user_id, alias, settings, unlocks, date_updated) \
+            VALUES (?, ?, ?, ?, ?) ON CONFLICT (user_id) DO UPDATE SET \
+            alias = excluded.alias, settings = excluded.settings, unlocks = excluded.unlocks, \
+            date_updated = excluded.date_updated",
+        )
+        .bind(row.user_id)
+        .bind(row.alias)
+        .bind(row.settings)
+        .bind(row.unlocks)
+        .bind(row.date_updated)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_user_id(
+        &self,
+        login_type: LoginType,
+        login_id: &str,
+    ) -> Result<Option<UserId>, StorageError> {
+        let user_id: Option<i64> = sqlx::query_scalar(
+            "SELECT user_id FROM identities WHERE login_type = ? AND login_id = ?",
+        )
+        .bind(login_type_str(login_type))
+        .bind(login_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(user_id
+            .and_then(|id| NonZeroU64::new(id as u64))
+            .map(UserId))
+    }
+
+    async fn link_identity(
+        &self,
+        login_type: LoginType,
+        login_id: &str,
+        user_id: UserId,
+    ) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO identities (login_type, login_id, user_id) VALUES (?, ?, ?) \
+            ON CONFLICT (login_type, login_id) DO UPDATE SET user_id = excluded.user_id",
+        )
+        .bind(login_type_str(login_type))
+        .bind(login_id)
+        .bind(user_id.0.get() as i64)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn put_match(&self, item: MatchDto) -> Result<(), StorageError> {
+        let user_ids = MatchRow::user_ids(&item);
+        let row = MatchRow::from_item(&item)?;
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO matches (match_id, data, date_ended) VALUES (?, ?, ?) \
+            ON CONFLICT (match_id) DO NOTHING",
+        )
+        .bind(row.match_id)
+        .bind(row.data)
+        .bind(row.date_ended)
+        .execute(&mut transaction)
+        .await?;
+        for user_id in user_ids {
+            sqlx::query(
+                "INSERT INTO match_participants (user_id, match_id) VALUES (?, ?) \
+                ON CONFLICT (user_id, match_id) DO NOTHING",
+            )
+            .bind(user_id)
+            .bind(row.match_id)
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn get_matches(&self, user_id: UserId) -> Result<Vec<MatchDto>, StorageError> {
+        let rows: Vec<(i64, String, i64)> = sqlx::query_as(
+            "SELECT matches.match_id, matches.data, matches.date_ended FROM matches \
+            INNER JOIN match_participants ON matches.match_id = match_participants.match_id \
+            WHERE match_participants.user_id = ? ORDER BY matches.date_ended DESC LIMIT ?",
+        )
+        .bind(user_id.0.get() as i64)
+        .bind(MAX_MATCHES as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(match_id, data, date_ended)| {
+                MatchRow {
+                    match_id,
+                    data,
+                    date_ended,
+                }
+                .into_item()
+            })
+            .collect()
+    }
+
+    async fn put_shadow_ban(&self, item: ShadowBanItem) -> Result<(), StorageError> {
+        let row = ShadowBanRow::from_item(&item);
+        sqlx::query(
+            "INSERT INTO shadow_bans (session_id, user_id, ip_address, date_expires) \
+            VALUES (?, ?, ?, ?) ON CONFLICT (session_id) DO UPDATE SET \
+            user_id = excluded.user_id, ip_address = excluded.ip_address, \
+            date_expires = excluded.date_expires",
+        )
+        .bind(row.session_id)
+        .bind(row.user_id)
+        .bind(row.ip_address)
+        .bind(row.date_expires)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_shadow_bans(&self, now: UnixTime) -> Result<Vec<ShadowBanItem>, StorageError> {
+        let rows: Vec<(i64, Option<i64>, String, i64)> = sqlx::query_as(
+            "SELECT session_id, user_id, ip_address, date_expires FROM shadow_bans \
+            WHERE date_expires > ?",
+        )
+        .bind(now as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(session_id, user_id, ip_address, date_expires)| {
+                ShadowBanRow {
+                    session_id,
+                    user_id,
+                    ip_address,
+                    date_expires,
+                }
+                .into_item()
+            })
+            .collect()
+    }
+
+    async fn put_referral(&self, item: ReferralItem) -> Result<(), StorageError> {
+        let row = ReferralRow::from_item(&item);
+        sqlx::query(
+            "INSERT INTO referrals (user_id, ip_address, credited, date_created) \
+            VALUES (?, ?, ?, ?) ON CONFLICT (user_id, ip_address) DO UPDATE SET \
+            credited = referrals.credited OR excluded.credited",
+        )
+        .bind(row.user_id)
+        .bind(row.ip_address)
+        .bind(row.credited)
+        .bind(row.date_created)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_referrals(&self, user_id: UserId) -> Result<Vec<ReferralItem>, StorageError> {
+        let rows: Vec<(i64, String, bool, i64)> = sqlx::query_as(
+            "SELECT user_id, ip_address, credited, date_created FROM referrals WHERE user_id = ?",
+        )
+        .bind(user_id.0.get() as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(user_id, ip_address, credited, date_created)| {
+                ReferralRow {
+                    user_id,
+                    ip_address,
+                    credited,
+                    date_created,
+                }
+                .into_item()
+            })
+            .collect()
+    }
+}
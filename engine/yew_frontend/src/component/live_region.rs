@@ -0,0 +1,37 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use stylist::yew::styled_component;
+use yew::{html, Html, Properties};
+
+#[derive(PartialEq, Properties)]
+pub struct LiveRegionProps {
+    /// Recent announcements, oldest first, each with a unique id.
+    pub announcements: Vec<(u32, String)>,
+}
+
+/// Visually hidden log, whose additions are read aloud by screen readers.
+#[styled_component(LiveRegion)]
+pub fn live_region(props: &LiveRegionProps) -> Html {
+    let div_css_class = css!(
+        r#"
+        border: 0;
+        clip: rect(0 0 0 0);
+        height: 1px;
+        margin: -1px;
+        overflow: hidden;
+        padding: 0;
+        position: absolute;
+        white-space: nowrap;
+        width: 1px;
+    "#
+    );
+
+    html! {
+        <div class={div_css_class} role="log" aria-live="polite" aria-relevant="additions">
+            {props.announcements.iter().map(|(id, text)| html!{
+                <p key={*id}>{text.clone()}</p>
+            }).collect::<Html>()}
+        </div>
+    }
+}
@@ -11,6 +11,7 @@ pub mod language_menu;
 pub mod level_meter;
 pub mod link;
 mod link_icon;
+pub mod live_region;
 pub mod meter;
 pub mod positioner;
 pub mod privacy_link;
@@ -85,6 +85,8 @@ pub fn section(props: &SectionProps) -> Html {
         }
     });
 
+    let clickable = onclick.is_some();
+
     let h2_css_class = css!(
         r#"
         color: white;
@@ -152,17 +154,30 @@ pub fn section(props: &SectionProps) -> Html {
         <>
             <div id={props.id.clone()} {style} class={high_contrast.then_some(high_contrast_style)}>
                 <h2
-                    class={classes!(h2_css_class, onclick.is_some().then_some(h2_clickable_css_class))}
+                    class={classes!(h2_css_class, clickable.then_some(h2_clickable_css_class))}
+                    tabindex={clickable.then_some("0")}
+                    role={clickable.then_some("button")}
+                    aria-expanded={clickable.then(|| open.to_string())}
                     {onclick}
                     >
                     if let Some(maybe_callback) = props.left_arrow.unpack(open) {
-                        <span class={classes!(span_css_class.clone(), maybe_callback.is_none().then(|| reserved_style.clone()))} onclick={maybe_callback}>
+                        <span
+                            class={classes!(span_css_class.clone(), maybe_callback.is_none().then(|| reserved_style.clone()))}
+                            tabindex={maybe_callback.is_some().then_some("0")}
+                            role={maybe_callback.is_some().then_some("button")}
+                            onclick={maybe_callback.clone()}
+                        >
                             <Icon icon_id={IconId::FontAwesomeSolidSquareCaretLeft} width={ICON_WIDTH.to_string()} height={ICON_HEIGHT.to_string()}/>
                         </span>
                     }
                     {&props.name}
                     if let Some(maybe_callback) = props.right_arrow.unpack(open) {
-                        <span class={classes!(span_css_class, maybe_callback.is_none().then_some(reserved_style))} onclick={maybe_callback}>
+                        <span
+                            class={classes!(span_css_class, maybe_callback.is_none().then_some(reserved_style))}
+                            tabindex={maybe_callback.is_some().then_some("0")}
+                            role={maybe_callback.is_some().then_some("button")}
+                            onclick={maybe_callback.clone()}
+                        >
                             <Icon icon_id={IconId::FontAwesomeSolidSquareCaretRight} width={ICON_WIDTH.to_string()} height={ICON_HEIGHT.to_string()}/>
                         </span>
                     }
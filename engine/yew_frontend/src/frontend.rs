@@ -8,9 +8,12 @@ use client_util::frontend::Frontend;
 use client_util::game_client::GameClient;
 use client_util::js_util::referrer;
 use client_util::setting::CommonSettings;
-use core_protocol::id::{GameId, ServerId};
+use core_protocol::id::{ArenaId, GameId, ServerId};
 use core_protocol::name::Referrer;
-use core_protocol::rpc::{ChatRequest, PlayerRequest, SystemQuery, SystemResponse, TeamRequest};
+use core_protocol::rpc::{
+    ChatRequest, FriendRequest, PlayerRequest, SystemQuery, SystemResponse, TeamRequest,
+    VoiceRequest,
+};
 use js_hooks::console_log;
 use std::ops::Deref;
 use wasm_bindgen::{JsCast, JsValue};
@@ -58,15 +61,18 @@ pub struct Ctw {
     pub change_common_settings_callback:
         Callback<Box<dyn FnOnce(&mut CommonSettings, &mut BrowserStorages)>>,
     pub chat_request_callback: Callback<ChatRequest>,
+    pub friend_request_callback: Callback<FriendRequest>,
     pub player_request_callback: Callback<PlayerRequest>,
     pub raw_zoom_callback: Callback<f32>,
     pub recreate_renderer_callback: Callback<()>,
     pub set_server_id_callback: Callback<Option<ServerId>>,
+    pub set_arena_callback: Callback<(Option<ServerId>, ArenaId)>,
     pub set_context_menu_callback: Callback<Option<Html>>,
     pub(crate) routes: Vec<&'static str>,
     /// A copy of the core state.
     pub state: WeakCoreState,
     pub team_request_callback: Callback<TeamRequest>,
+    pub voice_request_callback: Callback<VoiceRequest>,
     pub licenses: &'static [(&'static str, &'static [&'static str])],
 }
 
@@ -80,6 +86,11 @@ pub fn use_chat_request_callback() -> Callback<ChatRequest> {
     use_ctw().chat_request_callback
 }
 
+#[hook]
+pub fn use_friend_request_callback() -> Callback<FriendRequest> {
+    use_ctw().friend_request_callback
+}
+
 #[hook]
 pub fn use_player_request_callback() -> Callback<PlayerRequest> {
     use_ctw().player_request_callback
@@ -121,6 +132,11 @@ pub fn use_team_request_callback() -> Callback<TeamRequest> {
     use_ctw().team_request_callback
 }
 
+#[hook]
+pub fn use_voice_request_callback() -> Callback<VoiceRequest> {
+    use_ctw().voice_request_callback
+}
+
 #[hook]
 pub fn use_outbound_enabled() -> bool {
     use_ctw().outbound_enabled
@@ -174,6 +190,7 @@ pub fn use_gctw<G: GameClient>() -> Gctw<G> {
 
 pub struct Yew<P> {
     set_ui_props: Callback<P>,
+    announce: Callback<String>,
     referrer: Option<Referrer>,
     system_info: Option<SystemInfo>,
 }
@@ -186,9 +203,10 @@ pub(crate) struct SystemInfo {
 }
 
 impl<P: PartialEq> Yew<P> {
-    pub(crate) async fn new(set_ui_props: Callback<P>) -> Self {
+    pub(crate) async fn new(set_ui_props: Callback<P>, announce: Callback<String>) -> Self {
         Self {
             set_ui_props,
+            announce,
             referrer: get_real_referrer(),
             system_info: SystemInfo::new()
                 .await
@@ -258,6 +276,10 @@ impl<P: PartialEq> Frontend<P> for Yew<P> {
         self.set_ui_props.emit(props);
     }
 
+    fn announce(&self, text: String) {
+        self.announce.emit(text);
+    }
+
     fn get_real_referrer(&self) -> Option<Referrer> {
         self.referrer
     }
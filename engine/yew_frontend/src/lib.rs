@@ -20,11 +20,13 @@ pub mod translation;
 pub mod window;
 
 use crate::canvas::Canvas;
+use crate::component::live_region::LiveRegion;
 use crate::dialog::licensing_dialog::LicensingDialog;
 use crate::dialog::privacy_dialog::PrivacyDialog;
 use crate::dialog::terms_dialog::TermsDialog;
 use crate::error_tracer::ErrorTracer;
 use crate::frontend::{post_message, RewardedAd};
+use crate::overlay::announcement::AnnouncementOverlay;
 use crate::overlay::fatal_error::FatalError;
 use crate::overlay::reconnecting::Reconnecting;
 use crate::window::event_listener::WindowEventListener;
@@ -35,16 +37,17 @@ use client_util::game_client::GameClient;
 use client_util::infrastructure::Infrastructure;
 use client_util::setting::CommonSettings;
 use client_util::setting::Settings;
-use core_protocol::id::{InvitationId, ServerId};
+use core_protocol::id::{ArenaId, InvitationId, ServerId};
 use core_protocol::name::Referrer;
-use core_protocol::rpc::{AdType, ChatRequest, PlayerRequest, Request, TeamRequest};
+use core_protocol::rpc::{
+    AdType, ChatRequest, FriendRequest, PlayerRequest, Request, TeamRequest, VoiceRequest,
+};
 use engine_macros::SmolRoutable;
 use frontend::{Ctw, Gctw, PropertiesWrapper, Yew};
 use gloo_render::{request_animation_frame, AnimationFrame};
 use js_hooks::console_log;
 use keyboard::KeyboardEventsListener;
 use std::marker::PhantomData;
-use std::num::NonZeroU8;
 use stylist::{global_style, GlobalStyle};
 use wasm_bindgen::JsValue;
 use wasm_bindgen_futures::future_to_promise;
@@ -64,6 +67,9 @@ struct App<
     context_menu: Option<Html>,
     infrastructure: PendingInfrastructure<G>,
     ui_props: G::UiProps,
+    /// Recent announcements for screen readers, oldest first, and the id of the next one.
+    announcements: Vec<(u32, String)>,
+    next_announcement_id: u32,
     rewarded_ad: RewardedAd,
     fatal_error: Option<String>,
     /// After [`AppMsg::RecreateCanvas`] is received, before [`AppMsg::RecreateRenderer`] is received.
@@ -76,6 +82,7 @@ struct App<
     /// Message from parent window.
     _message_listener: WindowEventListener<MessageEvent>,
     _context_menu_inhibitor: WindowEventListener<MouseEvent>,
+    _webgl_context_lost_listener: WindowEventListener<Event>,
     _error_tracer: ErrorTracer,
     _global_style: GlobalStyle,
     _spooky: PhantomData<(UI, R)>,
@@ -149,6 +156,7 @@ enum AppMsg<G: GameClient> {
     /// Signals just the renderer should be recreated.
     RecreateRenderer,
     SetServerId(Option<ServerId>),
+    SetArena(Option<ServerId>, ArenaId),
     #[allow(unused)]
     FatalError(String),
     Frame {
@@ -160,15 +168,21 @@ enum AppMsg<G: GameClient> {
     Mouse(MouseEvent),
     RawZoom(f32),
     SendChatRequest(ChatRequest),
+    SendFriendRequest(FriendRequest),
     SendPlayerRequest(PlayerRequest),
     SendTeamRequest(TeamRequest),
+    SendVoiceRequest(VoiceRequest),
     SendUiEvent(G::UiEvent),
     SetContextMenuProps(Option<Html>),
     SetUiProps(G::UiProps),
+    /// Announce text to screen readers.
+    Announce(String),
     Touch(TouchEvent),
     /// Error trace.
     Trace(String),
     VisibilityChange(Event),
+    /// The canvas lost its WebGL context.
+    WebGlContextLost,
     RequestRewardedAd,
     ConsumeRewardedAd,
     /// Message from parent window.
@@ -184,6 +198,9 @@ impl<
 where
     G::UiProps: Default + PartialEq + Clone,
 {
+    /// Maximum number of recent announcements to keep in the live region.
+    const MAX_ANNOUNCEMENTS: usize = 5;
+
     pub fn create_animation_frame(ctx: &Context<Self>) -> AnimationFrame {
         let link = ctx.link().clone();
         request_animation_frame(move |time| link.send_message(AppMsg::Frame { time }))
@@ -207,6 +224,7 @@ where
         let visibility_callback = ctx.link().callback(AppMsg::VisibilityChange);
         let message_callback = ctx.link().callback(AppMsg::Message);
         let trace_callback = ctx.link().callback(AppMsg::Trace);
+        let webgl_context_lost_callback = ctx.link().callback(|_| AppMsg::WebGlContextLost);
 
         // First load local storage common settings.
         // Not guaranteed to set either or both to Some. Could fail to load.
@@ -222,6 +240,8 @@ where
                 settings,
             },
             ui_props: G::UiProps::default(),
+            announcements: Vec::new(),
+            next_announcement_id: 0,
             recreating_canvas: RecreatingCanvas::default(),
             rewarded_ad: RewardedAd::Unavailable,
             fatal_error: None,
@@ -260,6 +280,10 @@ where
                 move |event: &MouseEvent| event.prevent_default(),
                 true,
             ),
+            _webgl_context_lost_listener: WindowEventListener::new_capture(
+                "webglcontextlost",
+                move |event: &Event| webgl_context_lost_callback.emit(event.clone()),
+            ),
             _error_tracer: ErrorTracer::new(trace_callback),
             _global_style: global_style!(
                 r#"
@@ -281,6 +305,12 @@ where
                 a {
                     color: white;
                 }
+
+                /* More specific than classes that remove the outline, so keyboard focus is visible. */
+                html :focus-visible {
+                    outline: 2px solid #75AAFF;
+                    outline-offset: 2px;
+                }
             "#
             )
             .expect("failed to mount global style"),
@@ -404,17 +434,25 @@ where
                     infrastructure.choose_server_id(server_id);
                 }
             }
+            AppMsg::SetArena(server_id, arena_id) => {
+                if let Some(infrastructure) = self.infrastructure.as_mut() {
+                    infrastructure.choose_arena(server_id, arena_id);
+                }
+            }
             AppMsg::FatalError(e) => {
                 self.fatal_error = Some(e);
                 return true;
             }
             AppMsg::Frame { time } => {
+                self._animation_frame = Self::create_animation_frame(ctx);
                 if self.recreating_canvas != RecreatingCanvas::Started {
                     if let Some(infrastructure) = self.infrastructure.as_mut() {
+                        let quality = infrastructure.context.common_settings.graphics_quality;
                         infrastructure.frame((time * 0.001) as f32);
+                        // Resize canvas if quality was lowered automatically.
+                        return infrastructure.context.common_settings.graphics_quality != quality;
                     }
                 }
-                self._animation_frame = Self::create_animation_frame(ctx);
             }
             AppMsg::Keyboard(event) => {
                 if let Some(infrastructure) = self.infrastructure.as_mut() {
@@ -446,6 +484,11 @@ where
                     infrastructure.send_request(Request::Chat(request));
                 }
             }
+            AppMsg::SendFriendRequest(request) => {
+                if let Some(infrastructure) = self.infrastructure.as_mut() {
+                    infrastructure.send_request(Request::Friend(request));
+                }
+            }
             AppMsg::SetContextMenuProps(props) => {
                 self.context_menu = props;
                 return true;
@@ -460,6 +503,11 @@ where
                     infrastructure.send_request(Request::Team(request));
                 }
             }
+            AppMsg::SendVoiceRequest(request) => {
+                if let Some(infrastructure) = self.infrastructure.as_mut() {
+                    infrastructure.send_request(Request::Voice(request));
+                }
+            }
             AppMsg::SendUiEvent(event) => {
                 if let Some(infrastructure) = self.infrastructure.as_mut() {
                     infrastructure.ui_event(event);
@@ -469,6 +517,15 @@ where
                 self.ui_props = props;
                 return true;
             }
+            AppMsg::Announce(text) => {
+                // Screen readers only need to notice additions, so keep a few for context.
+                if self.announcements.len() >= Self::MAX_ANNOUNCEMENTS {
+                    self.announcements.remove(0);
+                }
+                self.announcements.push((self.next_announcement_id, text));
+                self.next_announcement_id = self.next_announcement_id.wrapping_add(1);
+                return true;
+            }
             AppMsg::Touch(event) => {
                 if let Some(infrastructure) = self.infrastructure.as_mut() {
                     infrastructure.touch(event);
@@ -497,6 +554,11 @@ where
                     infrastructure.visibility_change(event);
                 }
             }
+            AppMsg::WebGlContextLost => {
+                if let Some(infrastructure) = self.infrastructure.as_mut() {
+                    infrastructure.webgl_context_lost();
+                }
+            }
             AppMsg::Message(message) => {
                 console_log!("received message: {}", message);
                 match message.as_str() {
@@ -530,6 +592,11 @@ where
                             };
                         }
                     }
+                    "splash" => {
+                        if let Some(infrastructure) = self.infrastructure.as_mut() {
+                            infrastructure.tally_splash();
+                        }
+                    }
                     "tallyBannerAd" => {
                         if let Some(infrastructure) = self.infrastructure.as_mut() {
                             infrastructure.tally_ad(AdType::Banner);
@@ -574,13 +641,18 @@ where
         let change_common_settings_callback = ctx.link().callback(AppMsg::ChangeCommonSettings);
         let change_settings_callback = ctx.link().callback(AppMsg::ChangeSettings);
         let chat_request_callback = ctx.link().callback(AppMsg::SendChatRequest);
+        let friend_request_callback = ctx.link().callback(AppMsg::SendFriendRequest);
         let player_request_callback = ctx.link().callback(AppMsg::SendPlayerRequest);
         let raw_zoom_callback = ctx.link().callback(AppMsg::RawZoom);
         let recreate_renderer_callback = ctx.link().callback(|_| AppMsg::RecreateCanvas);
         let set_server_id_callback = ctx.link().callback(AppMsg::SetServerId);
+        let set_arena_callback = ctx
+            .link()
+            .callback(|(server_id, arena_id)| AppMsg::SetArena(server_id, arena_id));
         let send_ui_event_callback = ctx.link().callback(AppMsg::SendUiEvent);
         let set_context_menu_callback = ctx.link().callback(AppMsg::SetContextMenuProps);
         let team_request_callback = ctx.link().callback(AppMsg::SendTeamRequest);
+        let voice_request_callback = ctx.link().callback(AppMsg::SendVoiceRequest);
 
         // Combine game and engine routes, except those with path parameters.
         let routes = R::routes()
@@ -596,6 +668,7 @@ where
         let context = Ctw {
             chat_request_callback,
             change_common_settings_callback,
+            friend_request_callback,
             game_id: G::GAME_ID,
             outbound_enabled: self.outbound_enabled,
             rewarded_ad: self.rewarded_ad.clone(),
@@ -603,6 +676,7 @@ where
             raw_zoom_callback,
             recreate_renderer_callback,
             set_server_id_callback,
+            set_arena_callback,
             set_context_menu_callback,
             routes,
             licenses: G::LICENSES,
@@ -624,6 +698,7 @@ where
                 .map(|i| WeakCoreState::new(&i.context.state.core))
                 .unwrap_or_default(),
             team_request_callback,
+            voice_request_callback,
         };
 
         let game_context = Gctw {
@@ -641,13 +716,15 @@ where
             change_settings_callback,
         };
 
+        let resolution_divisor = context.setting_cache.graphics_quality.resolution_divisor();
+
         html! {
             <BrowserRouter>
                 <ContextProvider<Ctw> {context}>
                     <ContextProvider<Gctw<G>> context={game_context}>
                         if self.recreating_canvas != RecreatingCanvas::Started {
                             <Canvas
-                                resolution_divisor={NonZeroU8::new(1).unwrap()}
+                                {resolution_divisor}
                                 mouse_callback={ctx.link().callback(AppMsg::Mouse)}
                                 touch_callback={ctx.link().callback(AppMsg::Touch)}
                                 focus_callback={ctx.link().callback(AppMsg::MouseFocus)}
@@ -662,6 +739,8 @@ where
                             <>
                                 <UI props={self.ui_props.clone()}/>
                                 <Switch<Route> render={switch}/>
+                                <AnnouncementOverlay/>
+                                <LiveRegion announcements={self.announcements.clone()}/>
                                 if let Some(context_menu) = self.context_menu.as_ref() {
                                     {context_menu.clone()}
                                 }
@@ -679,9 +758,10 @@ where
     fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
         if first_render {
             let set_ui_props = ctx.link().callback(AppMsg::SetUiProps);
+            let announce = ctx.link().callback(AppMsg::Announce);
             let frontend_created_callback = ctx.link().callback(AppMsg::FrontendCreated);
             let _ = future_to_promise(async move {
-                frontend_created_callback.emit(Box::new(Yew::new(set_ui_props).await));
+                frontend_created_callback.emit(Box::new(Yew::new(set_ui_props, announce).await));
                 Ok(JsValue::NULL)
             });
         }
@@ -0,0 +1,69 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::component::positioner::{Position, Positioner};
+use crate::component::x_button::XButton;
+use crate::frontend::use_core_state;
+use core_protocol::id::AnnouncementId;
+use std::collections::HashSet;
+use stylist::yew::styled_component;
+use yew::{html, use_state, Callback, Html};
+
+/// Shows announcements from the server as banners, each of which the player may dismiss.
+#[styled_component(AnnouncementOverlay)]
+pub fn announcement_overlay() -> Html {
+    let banner_css_class = css!(
+        r#"
+        align-items: center;
+        background-color: #00000080;
+        border-radius: 0.5rem;
+        color: white;
+        display: flex;
+        font-size: 1.1rem;
+        gap: 0.5rem;
+        justify-content: space-between;
+        margin-bottom: 0.25rem;
+        padding: 0.5rem 0.5rem 0.5rem 1rem;
+        pointer-events: all;
+        word-break: break-word;
+        "#
+    );
+
+    let core_state = use_core_state();
+    let dismissed = use_state(HashSet::<AnnouncementId>::new);
+
+    let mut visible = core_state
+        .announcements
+        .iter()
+        .filter(|announcement| !dismissed.contains(&announcement.announcement_id))
+        .peekable();
+    if visible.peek().is_none() {
+        return html! {};
+    }
+
+    let banners = visible
+        .map(|announcement| {
+            let onclick = {
+                let dismissed = dismissed.clone();
+                let announcement_id = announcement.announcement_id;
+                Callback::from(move |_| {
+                    let mut new = (*dismissed).clone();
+                    new.insert(announcement_id);
+                    dismissed.set(new);
+                })
+            };
+            html! {
+                <div class={banner_css_class.clone()}>
+                    <span>{announcement.message.clone()}</span>
+                    <XButton {onclick}/>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Positioner id="announcements" position={Position::TopMiddle{margin: "0.5rem"}} max_width="min(40rem, 90%)">
+            {banners}
+        </Positioner>
+    }
+}
@@ -185,12 +185,19 @@ pub fn chat_overlay(props: &ChatProps) -> Html {
     };
 
     const ENTER: u32 = 13;
+    const ESCAPE: u32 = 27;
 
     let onkeydown = {
         let help_hint = help_hint.clone();
         let chat_request_callback = ctw.chat_request_callback;
 
         move |event: KeyboardEvent| {
+            if event.key_code() == ESCAPE {
+                // Return the keyboard to the game, keeping the unsent message.
+                let input: HtmlInputElement = event_target(&event);
+                let _ = input.blur();
+                return;
+            }
             if event.key_code() != ENTER {
                 return;
             }
@@ -308,6 +315,12 @@ pub fn chat_overlay(props: &ChatProps) -> Html {
                         chat_request_callback.emit(ChatRequest::RestrictPlayer{player_id, minutes: 5 });
                     })
                 };
+                let onclick_shadow_ban = |minutes: u32| {
+                    let chat_request_callback = chat_request_callback.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        chat_request_callback.emit(ChatRequest::ShadowBanPlayer{player_id, minutes});
+                    })
+                };
                 let onclick_copy_player_id = Callback::from(move |_: MouseEvent| {
                     if let Some(clipboard) = window().unwrap().navigator().clipboard() {
                         let _ = clipboard.write_text(&format!("{}", player_id.0));
@@ -324,6 +337,9 @@ pub fn chat_overlay(props: &ChatProps) -> Html {
                         if moderator {
                             if !is_me {
                                 <ContextMenuButton onclick={onclick_restrict_5m}>{"Restrict (5m)"}</ContextMenuButton>
+                                <ContextMenuButton onclick={onclick_shadow_ban(60)}>{"Shadow Ban (1h)"}</ContextMenuButton>
+                                <ContextMenuButton onclick={onclick_shadow_ban(24 * 60)}>{"Shadow Ban (1d)"}</ContextMenuButton>
+                                <ContextMenuButton onclick={onclick_shadow_ban(0)}>{"Lift Shadow Ban"}</ContextMenuButton>
                             }
                             <ContextMenuButton onclick={onclick_copy_player_id}>{"Copy ID"}</ContextMenuButton>
                             if let Some(onclick_copy_team_id) = onclick_copy_team_id {
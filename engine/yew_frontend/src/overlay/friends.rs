@@ -0,0 +1,217 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::component::positioner::Position;
+use crate::component::section::Section;
+use crate::event::event_target;
+use crate::frontend::{use_core_state, use_ctw};
+use crate::translation::Translation;
+use crate::Route;
+use core_protocol::dto::{FriendDto, PresenceDto};
+use core_protocol::id::PlayerId;
+use core_protocol::name::PlayerAlias;
+use core_protocol::rpc::FriendRequest;
+use std::str::FromStr;
+use stylist::yew::styled_component;
+use web_sys::{HtmlInputElement, InputEvent, SubmitEvent};
+use yew::{
+    classes, html, html_nested, use_node_ref, use_state_eq, virtual_dom::AttrValue, Html,
+    Properties,
+};
+use yew_router::Routable;
+
+#[derive(PartialEq, Properties)]
+pub struct FriendsOverlayProps {
+    pub position: Position,
+    #[prop_or(None)]
+    pub style: Option<AttrValue>,
+}
+
+#[styled_component(FriendsOverlay)]
+pub fn friends_overlay(props: &FriendsOverlayProps) -> Html {
+    let button_css_class = css!(
+        r#"
+        border-radius: 0.25em;
+        box-sizing: border-box;
+        color: white;
+        cursor: pointer;
+        font-size: 1em;
+        margin-top: 0.25em;
+        text-decoration: none;
+        white-space: nowrap;
+        background-color: transparent;
+        border: 0;
+        width: min-content;
+        padding: 0.1em 0.5em;
+
+        :disabled {
+            visibility: hidden;
+        }
+
+        :hover:not(:disabled) {
+            background-color: #00000025;
+        }
+        "#
+    );
+
+    let input_css_class = css!(
+        r#"
+        border-radius: 0.25em;
+        box-sizing: border-box;
+        cursor: pointer;
+        font-size: 1em;
+        font-weight: bold;
+        outline: 0;
+        padding: 0.5em;
+        pointer-events: all;
+        white-space: nowrap;
+        margin-top: 0.25em;
+        background-color: #00000025;
+        border: 0;
+        color: white;
+        width: 9em;
+        "#
+    );
+
+    let table_css_class = css!(
+        r#"
+        color: white;
+        width: 100%;
+        border-spacing: 0;
+        border-collapse: collapse;
+        "#
+    );
+
+    let name_css_class = css!(
+        r#"
+        color: white;
+        white-space: nowrap;
+        "#
+    );
+
+    let offline_css_class = css!(
+        r#"
+        filter: brightness(0.7);
+        "#
+    );
+
+    let no_padding_class = css!(
+        r#"
+        padding: 0;
+        "#
+    );
+
+    let ctw = use_ctw();
+    let t = ctw.setting_cache.language;
+    let core_state = use_core_state();
+    let friend_request_callback = ctw.friend_request_callback;
+    let input_ref = use_node_ref();
+    let input_empty = use_state_eq(|| true);
+
+    let on_input_change = {
+        let input_empty = input_empty.clone();
+        move |event: InputEvent| {
+            if !event.is_composing() {
+                let input: HtmlInputElement = event_target(&event);
+                input_empty.set(input.value().is_empty());
+            }
+        }
+    };
+
+    let on_add_friend = {
+        let cb = friend_request_callback.clone();
+        let input_ref = input_ref.clone();
+        let input_empty = input_empty.clone();
+        move || {
+            if let Some(input) = input_ref.cast::<HtmlInputElement>() {
+                let value = input.value();
+                let value = value.trim();
+                if !value.is_empty() {
+                    // Friend codes are player ids, otherwise assume an alias.
+                    let request = if let Ok(player_id) = PlayerId::from_str(value) {
+                        FriendRequest::Add(player_id)
+                    } else {
+                        FriendRequest::AddAlias(PlayerAlias::new_input_sanitized(value))
+                    };
+                    input.set_value("");
+                    input_empty.set(true);
+                    cb.emit(request);
+                }
+            }
+        }
+    };
+
+    let on_remove_friend = {
+        let cb = friend_request_callback.clone();
+        move |alias: PlayerAlias| {
+            cb.emit(FriendRequest::Remove(alias));
+        }
+    };
+
+    // Only offer to join friends that are playing elsewhere.
+    let arena_id = ctw.setting_cache.arena_id;
+    let server_id = ctw.setting_cache.server_id;
+    let join_href = |presence: &Option<PresenceDto>| -> Option<String> {
+        presence
+            .filter(|p| Some(p.arena_id) != arena_id || p.server_id != server_id)
+            .and_then(|p| p.invitation_id)
+            .map(|invitation_id| Route::Invitation { invitation_id }.to_path())
+    };
+
+    const X_MARK: &'static str = "✘";
+
+    html! {
+        <Section
+            id="friends"
+            name={t.friends_label()}
+            position={props.position}
+            style={props.style.clone()}
+        >
+            <form onsubmit={move |e: SubmitEvent| {e.prevent_default(); on_add_friend();}}>
+                <table class={table_css_class}>
+                    {core_state.friends.iter().map(|&FriendDto{alias, presence}| {
+                        let on_remove_friend = on_remove_friend.clone();
+                        let href = join_href(&presence);
+
+                        html_nested!{
+                            <tr>
+                                <td class={classes!(name_css_class.clone(), presence.is_none().then(|| offline_css_class.clone()))}>
+                                    {if presence.is_some() {alias.to_string()} else {format!("{} ({})", alias, t.friend_offline_label())}}
+                                </td>
+                                <td>
+                                    if let Some(href) = href {
+                                        <a {href} class={button_css_class.clone()}>{t.friend_join_hint()}</a>
+                                    }
+                                </td>
+                                <td>
+                                    <button type="button" class={button_css_class.clone()} onclick={move |_| on_remove_friend(alias)} title={t.friend_remove_hint()}>{X_MARK}</button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect::<Html>()}
+                    <tr>
+                        <td class={no_padding_class}>
+                            <input
+                                ref={input_ref}
+                                type="text"
+                                minlength="1"
+                                maxlength="12"
+                                placeholder={t.friend_add_placeholder()}
+                                oninput={on_input_change}
+                                class={input_css_class}
+                            />
+                        </td>
+                        <td>
+                            <button disabled={*input_empty} class={button_css_class}>{t.friend_add_hint()}</button>
+                        </td>
+                    </tr>
+                </table>
+            </form>
+            if let Some(player_id) = core_state.player_id {
+                <p style="color: white; margin: 0.25em 0 0 0; opacity: 0.8; user-select: text;">
+                    {format!("{}: {}", t.friend_id_label(), player_id.0)}
+                </p>
+            }
+        </Section>
+    }
+}
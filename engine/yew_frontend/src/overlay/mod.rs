@@ -1,9 +1,13 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+pub mod announcement;
 pub mod chat;
 pub mod fatal_error;
+pub mod friends;
 pub mod leaderboard;
+pub mod news;
 pub(crate) mod reconnecting;
+pub mod server_browser;
 pub mod spawn;
 pub mod team;
@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::component::link::Link;
+use crate::component::positioner::Position;
+use crate::component::section::Section;
+use crate::frontend::use_ctw;
+use crate::translation::Translation;
+use core_protocol::dto::{NewsItemDto, NewsKind};
+use stylist::yew::styled_component;
+use wasm_bindgen::JsValue;
+use yew::{classes, html, virtual_dom::AttrValue, Html, Properties};
+
+#[derive(PartialEq, Properties)]
+pub struct NewsOverlayProps {
+    pub position: Position,
+    #[prop_or(None)]
+    pub style: Option<AttrValue>,
+}
+
+/// Shows the news feed pushed by the server (or the one cached from last time), if any.
+#[styled_component(NewsOverlay)]
+pub fn news_overlay(props: &NewsOverlayProps) -> Html {
+    let item_css_class = css!(
+        r#"
+        color: white;
+        margin: 0.5em 0;
+        user-select: text;
+        word-break: break-word;
+
+        h4 {
+            margin: 0;
+        }
+
+        p {
+            margin: 0.25em 0;
+            white-space: pre-line;
+        }
+        "#
+    );
+
+    let event_css_class = css!(
+        r#"
+        background-color: #00000040;
+        border-left: 0.25em solid #f0c040;
+        border-radius: 0.25em;
+        padding: 0.25em 0.5em;
+        "#
+    );
+
+    let date_css_class = css!(
+        r#"
+        font-size: 0.8em;
+        opacity: 0.8;
+        "#
+    );
+
+    let ctw = use_ctw();
+    let t = ctw.setting_cache.language;
+    let news = ctw.setting_cache.news.0;
+
+    if news.is_empty() {
+        return html! {};
+    }
+
+    // YYYY-MM-DD
+    let date = |item: &NewsItemDto| -> String {
+        let iso =
+            String::from(js_sys::Date::new(&JsValue::from_f64(item.date as f64)).to_iso_string());
+        iso.get(..10).unwrap_or_default().to_owned()
+    };
+
+    html! {
+        <Section
+            id="news"
+            name={t.news_label()}
+            position={props.position}
+            style={props.style.clone()}
+        >
+            {news.iter().map(|item| html! {
+                <div class={classes!(item_css_class.clone(), (item.kind == NewsKind::Event).then(|| event_css_class.clone()))}>
+                    <h4>{item.title.clone()}</h4>
+                    <span class={date_css_class.clone()}>{date(item)}</span>
+                    if !item.body.is_empty() {
+                        <p>{item.body.clone()}</p>
+                    }
+                    if let Some(link) = item.link.clone() {
+                        <Link href={link}>{t.news_read_more_label()}</Link>
+                    }
+                </div>
+            }).collect::<Html>()}
+        </Section>
+    }
+}
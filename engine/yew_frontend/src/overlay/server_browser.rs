@@ -0,0 +1,198 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::component::positioner::Position;
+use crate::component::section::Section;
+use crate::frontend::{use_core_state, use_ctw};
+use crate::translation::Translation;
+use client_util::js_util::{domain_name_of, host, is_https};
+use core_protocol::dto::ServerDto;
+use core_protocol::id::ServerId;
+use std::collections::HashMap;
+use std::rc::Rc;
+use stylist::yew::styled_component;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::{window, Request, RequestInit, RequestMode, Response};
+use yew::{
+    classes, html, html_nested, use_effect_with_deps, use_reducer, virtual_dom::AttrValue, Html,
+    Properties, Reducible,
+};
+
+#[derive(PartialEq, Properties)]
+pub struct ServerBrowserOverlayProps {
+    pub position: Position,
+    #[prop_or(None)]
+    pub style: Option<AttrValue>,
+}
+
+/// Lists servers, so that players may pick one instead of being routed automatically.
+#[styled_component(ServerBrowserOverlay)]
+pub fn server_browser_overlay(props: &ServerBrowserOverlayProps) -> Html {
+    let table_css_class = css!(
+        r#"
+        color: white;
+        width: 100%;
+        border-spacing: 0;
+        border-collapse: collapse;
+        "#
+    );
+
+    let row_css_class = css!(
+        r#"
+        cursor: pointer;
+        white-space: nowrap;
+
+        :hover {
+            background-color: #00000025;
+        }
+
+        td {
+            padding: 0.1em 0.25em;
+        }
+        "#
+    );
+
+    let selected_css_class = css!(
+        r#"
+        font-weight: bold;
+        "#
+    );
+
+    let ctw = use_ctw();
+    let t = ctw.setting_cache.language;
+    let core_state = use_core_state();
+    let selected_server_id = ctw.setting_cache.server_id;
+    let selected_arena_id = ctw.setting_cache.arena_id;
+
+    let pings = use_reducer(Pings::default);
+
+    // Measure each server from this client, since that is the latency that playing on it would
+    // have (the server's own probes only say how far it is from the server that sent the list).
+    {
+        let pings = pings.dispatcher();
+        let mut server_ids: Vec<ServerId> = core_state.servers.keys().copied().collect();
+        server_ids.sort_unstable();
+
+        use_effect_with_deps(
+            move |server_ids| {
+                let host = host();
+                // Local servers don't have a subdomain per server.
+                if !host.starts_with("localhost") {
+                    let scheme = if is_https() { "https" } else { "http" };
+                    let domain = domain_name_of(&host);
+                    for &server_id in server_ids {
+                        let pings = pings.clone();
+                        let url = format!("{}://{}.{}/status.json", scheme, server_id.0, domain);
+                        let _ = future_to_promise(async move {
+                            if let Some(ping) = measure_ping(&url).await {
+                                pings.dispatch((server_id, ping));
+                            }
+                            Ok(JsValue::NULL)
+                        });
+                    }
+                }
+                || ()
+            },
+            server_ids,
+        );
+    }
+
+    let mut servers: Vec<&ServerDto> = core_state.servers.values().collect();
+    // Closest first, with unmeasured servers last.
+    servers.sort_by_key(|dto| {
+        (
+            pings.0.get(&dto.server_id).copied().unwrap_or(u16::MAX),
+            dto.server_id,
+        )
+    });
+
+    html! {
+        <Section
+            id="servers"
+            name={t.servers_label()}
+            position={props.position}
+            style={props.style.clone()}
+            open={false}
+        >
+            <table class={table_css_class}>
+                {servers.into_iter().flat_map(|dto| {
+                    let server_id = dto.server_id;
+                    let ping = pings.0.get(&server_id).map(|ping| format!("{}ms", ping)).unwrap_or_default();
+
+                    if dto.arenas.is_empty() {
+                        let onclick = ctw.set_server_id_callback.reform(move |_| Some(server_id));
+                        let selected = selected_server_id == Some(server_id);
+
+                        vec![html_nested!{
+                            <tr {onclick} class={classes!(row_css_class.clone(), selected.then(|| selected_css_class.clone()))}>
+                                <td>{format!("#{}", server_id.0)}</td>
+                                <td>{t.region_label(dto.region_id)}</td>
+                                <td>{dto.game_mode.clone().unwrap_or_default()}</td>
+                                <td>{t.online(dto.player_count)}</td>
+                                <td>{ping}</td>
+                            </tr>
+                        }]
+                    } else {
+                        // One row per arena.
+                        dto.arenas.iter().map(|arena| {
+                            let arena_id = arena.arena_id;
+                            let onclick = ctw.set_arena_callback.reform(move |_| (Some(server_id), arena_id));
+                            let selected = selected_server_id == Some(server_id) && selected_arena_id == Some(arena_id);
+
+                            html_nested!{
+                                <tr {onclick} class={classes!(row_css_class.clone(), selected.then(|| selected_css_class.clone()))}>
+                                    <td>{format!("#{}", server_id.0)}</td>
+                                    <td>{t.region_label(dto.region_id)}</td>
+                                    <td>{arena.game_mode.clone().unwrap_or_default()}</td>
+                                    <td>{t.online(arena.player_count)}</td>
+                                    <td>{ping.clone()}</td>
+                                </tr>
+                            }
+                        }).collect()
+                    }
+                }).collect::<Html>()}
+            </table>
+        </Section>
+    }
+}
+
+/// Round-trip times from this client to servers, in milliseconds.
+#[derive(Default, PartialEq)]
+struct Pings(HashMap<ServerId, u16>);
+
+impl Reducible for Pings {
+    type Action = (ServerId, u16);
+
+    fn reduce(self: Rc<Self>, (server_id, ping): Self::Action) -> Rc<Self> {
+        let mut pings = self.0.clone();
+        pings.insert(server_id, ping);
+        Rc::new(Self(pings))
+    }
+}
+
+/// Measures the round-trip time to a url, in milliseconds, taking the best of a few requests so
+/// that connection setup doesn't count.
+async fn measure_ping(url: &str) -> Option<u16> {
+    let mut best: Option<u16> = None;
+    for _ in 0..3 {
+        let mut opts = RequestInit::new();
+        opts.method("GET");
+        opts.mode(RequestMode::Cors);
+        let request = Request::new_with_str_and_init(url, &opts).ok()?;
+
+        let start = js_sys::Date::now();
+        let response: Response = JsFuture::from(window()?.fetch_with_request(&request))
+            .await
+            .ok()?
+            .dyn_into()
+            .ok()?;
+        if !response.ok() {
+            return None;
+        }
+        // Saturates.
+        let ping = (js_sys::Date::now() - start) as u16;
+        best = Some(best.map_or(ping, |best| best.min(ping)));
+    }
+    best
+}
@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::component::invitation_link::use_copy_invitation_link;
 use crate::component::positioner::Position;
 use crate::component::section::Section;
 use crate::event::event_target;
@@ -8,7 +9,7 @@ use crate::frontend::{use_core_state, use_ctw};
 use crate::translation::Translation;
 use client_util::browser_storage::BrowserStorages;
 use client_util::setting::CommonSettings;
-use core_protocol::dto::{PlayerDto, TeamDto};
+use core_protocol::dto::{TeamDto, TeamMemberDto};
 use core_protocol::id::{LanguageId, PlayerId, TeamId};
 use core_protocol::name::TeamName;
 use core_protocol::rpc::TeamRequest;
@@ -138,6 +139,15 @@ pub fn team_overlay(props: &TeamOverlayProps) -> Html {
         "#
     );
 
+    let referrals_css_class = css!(
+        r#"
+        color: white;
+        filter: brightness(0.7);
+        font-size: 0.8em;
+        white-space: nowrap;
+        "#
+    );
+
     let ctw = use_ctw();
     let t = ctw.setting_cache.language;
     let core_state = use_core_state();
@@ -210,6 +220,8 @@ pub fn team_overlay(props: &TeamOverlayProps) -> Html {
         move || cb.emit(TeamRequest::Leave)
     };
 
+    let on_copy_invitation_link = use_copy_invitation_link();
+
     let on_reject_join_team = {
         let cb = team_request_callback.clone();
         move |player_id: PlayerId| {
@@ -252,6 +264,7 @@ pub fn team_overlay(props: &TeamOverlayProps) -> Html {
         };
 
     const CHECK_MARK: &'static str = "✔";
+    const CROWN: &'static str = "♛";
     const X_MARK: &'static str = "✘";
 
     // TODO (use settings): on_open_changed={|o| ctw.dialogs.teams = o}}
@@ -266,14 +279,16 @@ pub fn team_overlay(props: &TeamOverlayProps) -> Html {
         >
             if team_name.is_some() {
                 <table class={table_css_class}>
-                    {core_state.members.iter().filter_map(|player_id| core_state.player_or_bot(*player_id)).map(|PlayerDto{alias, player_id, team_captain, ..}| {
+                    {core_state.members.iter().enumerate().map(|(i, &TeamMemberDto{alias, player_id})| {
+                        // Members are ordered, so the first is captain.
+                        let team_captain = i == 0;
                         let on_kick_from_team = on_kick_from_team.clone();
                         let on_promote_to_captain = on_promote_to_captain.clone();
                         html_nested!{
                             <tr class={tr_css_class.clone()}>
                                 if i_am_team_captain {
-                                    <td class={classes!(name_css_class.clone(), team_captain.then(|| owner_css_class.clone()))} onclick={move |_| on_promote_to_captain(player_id)} title={t.team_promote_hint()}>{alias}</td>
-                                    <td><button class={classes!(button_css_class.clone(), hidden_css_class.clone())}>{CHECK_MARK}</button></td>
+                                    <td class={classes!(name_css_class.clone(), team_captain.then(|| owner_css_class.clone()))}>{alias}</td>
+                                    <td><button class={classes!(button_css_class.clone(), team_captain.then(|| hidden_css_class.clone()))} onclick={move |_| on_promote_to_captain(player_id)} title={t.team_promote_hint()}>{CROWN}</button></td>
                                     <td><button class={classes!(button_css_class.clone(), team_captain.then(|| hidden_css_class.clone()))} onclick={move |_| on_kick_from_team(player_id)} title={t.team_kick_hint()}>{X_MARK}</button></td>
                                 } else {
                                     <td class={classes!(name_css_class.clone(), team_captain.then(|| owner_css_class.clone()))}>{alias}</td>                                     
@@ -281,20 +296,33 @@ pub fn team_overlay(props: &TeamOverlayProps) -> Html {
                             </tr>
                         }
                     }).collect::<Html>()}
-                    {core_state.joiners.iter().filter_map(|player_id| core_state.player_or_bot(*player_id)).map(|PlayerDto{alias, player_id, ..}| {
+                    {core_state.joiners.iter().map(|&TeamMemberDto{alias, player_id}| {
                         let on_accept_join_team = on_accept_join_team.clone();
                         let on_reject_join_team = on_reject_join_team.clone();
-                        
+
                         html_nested!{
                             <tr class={tr_css_class.clone()}>
                                 <td class={classes!(name_css_class.clone(), name_pending_css_class.clone())}>{alias}</td>
-                                <td><button class={classes!(button_css_class.clone(), team_full.then(|| disabled_css_class.clone()))} onclick={move |_| on_accept_join_team(player_id)} title={t.team_accept_hint()}>{CHECK_MARK}</button></td>
+                                <td><button class={classes!(button_css_class.clone(), team_full.then(|| disabled_css_class.clone()))} onclick={move |_| on_accept_join_team(player_id)} title={if team_full { t.team_accept_full_hint() } else { t.team_accept_hint() }}>{CHECK_MARK}</button></td>
                                 <td><button class={button_css_class.clone()} onclick={move |_| on_reject_join_team(player_id)} title={t.team_deny_hint()}>{X_MARK}</button></td>
                             </tr>
                         }
                     }).collect::<Html>()}
                 </table>
-                <button onclick={move |_| on_leave_team()} class={button_css_class}>{t.team_leave_hint()}</button>
+                <button onclick={move |_| on_leave_team()} class={button_css_class.clone()}>{t.team_leave_hint()}</button>
+                <button
+                    disabled={core_state.created_invitation_id.is_none()}
+                    onclick={on_copy_invitation_link.clone()}
+                    class={button_css_class}
+                    title={t.invitation_hint()}
+                >
+                    {if on_copy_invitation_link.is_some() { t.invitation_label() } else { t.invitation_copied_label() }}
+                </button>
+                if core_state.referrals.clicks > 0 {
+                    <div class={referrals_css_class}>
+                        {t.invitation_referrals_label(core_state.referrals.spawns, core_state.referrals.rewards)}
+                    </div>
+                }
             } else {
                 <form onsubmit={move |e: SubmitEvent| {e.prevent_default(); on_create_team();}}>
                     <table class={table_css_class}>
@@ -3,7 +3,7 @@
 
 use crate::frontend::use_ctw;
 use core_protocol::id::LanguageId::*;
-use core_protocol::id::{GameId, LanguageId, PeriodId};
+use core_protocol::id::{GameId, LanguageId, PeriodId, RegionId};
 use yew::hook;
 
 /// Only works in function component.
@@ -107,6 +107,39 @@ pub trait Translation: Sized {
     s!(invitation_hint);
     s!(invitation_label);
     s!(invitation_copied_label);
+    fn invitation_referrals_label(self, joined: u32, rewarded: u32) -> String;
+
+    // Friends.
+    s!(friends_label);
+    s!(friend_add_hint);
+    s!(friend_add_placeholder);
+    s!(friend_id_label);
+    s!(friend_join_hint);
+    s!(friend_offline_label);
+    s!(friend_remove_hint);
+
+    // News.
+    s!(news_label);
+    s!(news_read_more_label);
+
+    // Server browser.
+    s!(servers_label);
+    fn region_label(self, region_id: RegionId) -> &'static str {
+        match region_id {
+            RegionId::Africa => self.region_africa_label(),
+            RegionId::Asia => self.region_asia_label(),
+            RegionId::Europe => self.region_europe_label(),
+            RegionId::NorthAmerica => self.region_north_america_label(),
+            RegionId::Oceania => self.region_oceania_label(),
+            RegionId::SouthAmerica => self.region_south_america_label(),
+        }
+    }
+    s!(region_africa_label);
+    s!(region_asia_label);
+    s!(region_europe_label);
+    s!(region_north_america_label);
+    s!(region_oceania_label);
+    s!(region_south_america_label);
 
     // Connection lost.
     s!(connection_losing_message);
@@ -751,6 +784,295 @@ impl Translation for LanguageId {
         }
     }
 
+    fn invitation_referrals_label(self, joined: u32, rewarded: u32) -> String {
+        match self {
+            Bork => format!("{joined} borked in, {rewarded} borked up"),
+            German => format!("{joined} beigetreten, {rewarded} belohnt"),
+            English => format!("{joined} joined, {rewarded} rewarded"),
+            Spanish => format!("{joined} se unieron, {rewarded} recompensados"),
+            French => format!("{joined} ont rejoint, {rewarded} récompensés"),
+            Italian => format!("{joined} entrati, {rewarded} premiati"),
+            Arabic => format!("انضم {joined}، مكافأة {rewarded}"),
+            Japanese => format!("{joined}人参加、{rewarded}人報酬"),
+            Russian => format!("Присоединились: {joined}, награды: {rewarded}"),
+            Vietnamese => format!("{joined} đã tham gia, {rewarded} đã thưởng"),
+            SimplifiedChinese => format!("{joined}人加入，{rewarded}人获奖励"),
+            Hindi => format!("{joined} शामिल हुए, {rewarded} पुरस्कृत"),
+        }
+    }
+
+    fn friends_label(self) -> &'static str {
+        match self {
+            Bork => "Bork buddies",
+            German => "Freunde",
+            English => "Friends",
+            Spanish => "Amigos",
+            French => "Amis",
+            Italian => "Amici",
+            Arabic => "أصدقاء",
+            Japanese => "フレンド",
+            Russian => "Друзья",
+            Vietnamese => "Bạn bè",
+            SimplifiedChinese => "好友",
+            Hindi => "दोस्त",
+        }
+    }
+
+    fn friend_add_hint(self) -> &'static str {
+        match self {
+            Bork => "Bork",
+            German => "Hinzufügen",
+            English => "Add",
+            Spanish => "Añadir",
+            French => "Ajouter",
+            Italian => "Aggiungi",
+            Arabic => "إضافة",
+            Japanese => "追加",
+            Russian => "Добавить",
+            Vietnamese => "Thêm",
+            SimplifiedChinese => "添加",
+            Hindi => "जोड़ें",
+        }
+    }
+
+    fn friend_add_placeholder(self) -> &'static str {
+        match self {
+            Bork => "Bork or ID",
+            German => "Name oder ID",
+            English => "Alias or ID",
+            Spanish => "Alias o ID",
+            French => "Pseudo ou ID",
+            Italian => "Alias o ID",
+            Arabic => "الاسم أو المعرف",
+            Japanese => "名前またはID",
+            Russian => "Имя или ID",
+            Vietnamese => "Tên hoặc ID",
+            SimplifiedChinese => "昵称或ID",
+            Hindi => "उपनाम या आईडी",
+        }
+    }
+
+    fn friend_id_label(self) -> &'static str {
+        match self {
+            Bork => "Your bork",
+            German => "Deine ID",
+            English => "Your ID",
+            Spanish => "Tu ID",
+            French => "Ton ID",
+            Italian => "Il tuo ID",
+            Arabic => "معرفك",
+            Japanese => "あなたのID",
+            Russian => "Ваш ID",
+            Vietnamese => "ID của bạn",
+            SimplifiedChinese => "你的ID",
+            Hindi => "आपकी आईडी",
+        }
+    }
+
+    fn friend_join_hint(self) -> &'static str {
+        match self {
+            Bork => "Bork",
+            German => "Beitreten",
+            English => "Join",
+            Spanish => "Unirse",
+            French => "Rejoindre",
+            Italian => "Unisciti",
+            Arabic => "انضمام",
+            Japanese => "参加",
+            Russian => "Присоединиться",
+            Vietnamese => "Tham gia",
+            SimplifiedChinese => "加入",
+            Hindi => "शामिल हों",
+        }
+    }
+
+    fn friend_offline_label(self) -> &'static str {
+        match self {
+            Bork => "Asleep",
+            German => "Offline",
+            English => "Offline",
+            Spanish => "Desconectado",
+            French => "Hors ligne",
+            Italian => "Offline",
+            Arabic => "غير متصل",
+            Japanese => "オフライン",
+            Russian => "Не в сети",
+            Vietnamese => "Ngoại tuyến",
+            SimplifiedChinese => "离线",
+            Hindi => "ऑफ़लाइन",
+        }
+    }
+
+    fn friend_remove_hint(self) -> &'static str {
+        match self {
+            Bork => "Unbork",
+            German => "Entfernen",
+            English => "Remove",
+            Spanish => "Eliminar",
+            French => "Retirer",
+            Italian => "Rimuovi",
+            Arabic => "إزالة",
+            Japanese => "削除",
+            Russian => "Удалить",
+            Vietnamese => "Xóa",
+            SimplifiedChinese => "删除",
+            Hindi => "हटाएं",
+        }
+    }
+
+    fn news_label(self) -> &'static str {
+        match self {
+            Bork => "Bork news",
+            German => "Neuigkeiten",
+            English => "News",
+            Spanish => "Noticias",
+            French => "Actualités",
+            Italian => "Notizie",
+            Arabic => "الأخبار",
+            Japanese => "ニュース",
+            Russian => "Новости",
+            Vietnamese => "Tin tức",
+            SimplifiedChinese => "新闻",
+            Hindi => "समाचार",
+        }
+    }
+
+    fn news_read_more_label(self) -> &'static str {
+        match self {
+            Bork => "Bork more",
+            German => "Weiterlesen",
+            English => "Read more",
+            Spanish => "Leer más",
+            French => "En savoir plus",
+            Italian => "Leggi di più",
+            Arabic => "اقرأ المزيد",
+            Japanese => "続きを読む",
+            Russian => "Подробнее",
+            Vietnamese => "Đọc thêm",
+            SimplifiedChinese => "阅读更多",
+            Hindi => "और पढ़ें",
+        }
+    }
+
+    fn servers_label(self) -> &'static str {
+        match self {
+            Bork => "Borkers",
+            German => "Server",
+            English => "Servers",
+            Spanish => "Servidores",
+            French => "Serveurs",
+            Italian => "Server",
+            Arabic => "الخوادم",
+            Japanese => "サーバー",
+            Russian => "Серверы",
+            Vietnamese => "Máy chủ",
+            SimplifiedChinese => "服务器",
+            Hindi => "सर्वर",
+        }
+    }
+
+    fn region_africa_label(self) -> &'static str {
+        match self {
+            Bork => "Borkfrica",
+            German => "Afrika",
+            English => "Africa",
+            Spanish => "África",
+            French => "Afrique",
+            Italian => "Africa",
+            Arabic => "أفريقيا",
+            Japanese => "アフリカ",
+            Russian => "Африка",
+            Vietnamese => "Châu Phi",
+            SimplifiedChinese => "非洲",
+            Hindi => "अफ्रीका",
+        }
+    }
+
+    fn region_asia_label(self) -> &'static str {
+        match self {
+            Bork => "Borksia",
+            German => "Asien",
+            English => "Asia",
+            Spanish => "Asia",
+            French => "Asie",
+            Italian => "Asia",
+            Arabic => "آسيا",
+            Japanese => "アジア",
+            Russian => "Азия",
+            Vietnamese => "Châu Á",
+            SimplifiedChinese => "亚洲",
+            Hindi => "एशिया",
+        }
+    }
+
+    fn region_europe_label(self) -> &'static str {
+        match self {
+            Bork => "Borkope",
+            German => "Europa",
+            English => "Europe",
+            Spanish => "Europa",
+            French => "Europe",
+            Italian => "Europa",
+            Arabic => "أوروبا",
+            Japanese => "ヨーロッパ",
+            Russian => "Европа",
+            Vietnamese => "Châu Âu",
+            SimplifiedChinese => "欧洲",
+            Hindi => "यूरोप",
+        }
+    }
+
+    fn region_north_america_label(self) -> &'static str {
+        match self {
+            Bork => "North Borkmerica",
+            German => "Nordamerika",
+            English => "North America",
+            Spanish => "Norteamérica",
+            French => "Amérique du Nord",
+            Italian => "Nord America",
+            Arabic => "أمريكا الشمالية",
+            Japanese => "北アメリカ",
+            Russian => "Северная Америка",
+            Vietnamese => "Bắc Mỹ",
+            SimplifiedChinese => "北美洲",
+            Hindi => "उत्तरी अमेरिका",
+        }
+    }
+
+    fn region_oceania_label(self) -> &'static str {
+        match self {
+            Bork => "Borkeania",
+            German => "Ozeanien",
+            English => "Oceania",
+            Spanish => "Oceanía",
+            French => "Océanie",
+            Italian => "Oceania",
+            Arabic => "أوقيانوسيا",
+            Japanese => "オセアニア",
+            Russian => "Океания",
+            Vietnamese => "Châu Đại Dương",
+            SimplifiedChinese => "大洋洲",
+            Hindi => "ओशिनिया",
+        }
+    }
+
+    fn region_south_america_label(self) -> &'static str {
+        match self {
+            Bork => "South Borkmerica",
+            German => "Südamerika",
+            English => "South America",
+            Spanish => "Sudamérica",
+            French => "Amérique du Sud",
+            Italian => "Sud America",
+            Arabic => "أمريكا الجنوبية",
+            Japanese => "南アメリカ",
+            Russian => "Южная Америка",
+            Vietnamese => "Nam Mỹ",
+            SimplifiedChinese => "南美洲",
+            Hindi => "दक्षिण अमेरिका",
+        }
+    }
+
     fn connection_lost_message(self) -> &'static str {
         match self {
             Bork => "Your connection was borked. Try again later!",
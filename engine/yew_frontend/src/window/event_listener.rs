@@ -44,6 +44,20 @@ impl<E: JsCast> WindowEventListener<E> {
         Self::new_inner(&window().unwrap(), name, callback, allow_prevent_default)
     }
 
+    /// Listens during the capture phase, so as to also receive events that don't bubble up to the
+    /// window (e.g. from the canvas).
+    pub fn new_capture(name: &'static str, mut callback: impl FnMut(&E) + 'static) -> Self {
+        Self {
+            _inner: EventListener::new_with_options(
+                &window().unwrap(),
+                name,
+                EventListenerOptions::run_in_capture_phase(),
+                move |event| callback(event.unchecked_ref::<E>()),
+            ),
+            _spooky: PhantomData,
+        }
+    }
+
     /// Goes on the body instead of the window.
     pub fn new_body(
         name: &'static str,
@@ -0,0 +1,184 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::angle::Angle;
+use common::contact::ContactTrait;
+use common::cosmetic::Cosmetics;
+use common::entity::EntityType;
+use common::guidance::Guidance;
+use common::protocol::{Command, Control, Fire, Spawn, Update};
+use common::spawn_region::SpawnRegion;
+use core_protocol::id::PlayerId;
+use core_protocol::rpc::{ClientUpdate, Request};
+use glam::Vec2;
+use load_test::behavior::{Behavior, CoreBehavior};
+use load_test::LoadTestOptions;
+use rand::seq::IteratorRandom;
+use rand::{Rng, RngCore};
+use std::f32::consts::PI;
+use std::time::Duration;
+use structopt::StructOpt;
+
+/// Plays like a (not very good) real player: spawns, wanders, and fires at random points near its
+/// boat, so that the server simulates and sends as much as it would for real players.
+struct Mk48Behavior {
+    core: CoreBehavior,
+    /// Assigned by the server once connected.
+    player_id: Option<PlayerId>,
+    /// Type and position of the client's boat, as of the last update, if alive.
+    boat: Option<(EntityType, Vec2)>,
+    direction: Angle,
+    next_spawn: Duration,
+    next_turn: Duration,
+    next_fire: Duration,
+}
+
+impl Mk48Behavior {
+    /// Time between attempts to spawn, while dead.
+    const SPAWN_PERIOD: Duration = Duration::from_secs(2);
+    /// Meters from the boat within which to aim.
+    const AIM_RADIUS: f32 = 500.0;
+
+    fn new(index: usize) -> Self {
+        Self {
+            core: CoreBehavior::new(index),
+            player_id: None,
+            boat: None,
+            direction: Angle::ZERO,
+            next_spawn: Duration::ZERO,
+            next_turn: Duration::ZERO,
+            next_fire: Duration::ZERO,
+        }
+    }
+}
+
+impl Behavior<Command> for Mk48Behavior {
+    fn tick(&mut self, elapsed: Duration, rng: &mut dyn RngCore) -> Vec<Request<Command>> {
+        let mut requests = Behavior::<Command>::tick(&mut self.core, elapsed, rng);
+
+        if let Some((entity_type, position)) = self.boat {
+            let data = entity_type.data();
+            if elapsed >= self.next_turn {
+                self.next_turn = elapsed + Duration::from_secs(rng.gen_range(5..15));
+                self.direction = Angle::from_radians(rng.gen_range(-PI..PI));
+            }
+            let fire = if elapsed >= self.next_fire && !data.armaments.is_empty() {
+                self.next_fire = elapsed + Duration::from_millis(rng.gen_range(500..2000));
+                Some(Fire {
+                    armament_index: rng.gen_range(0..data.armaments.len()) as u8,
+                })
+            } else {
+                None
+            };
+            let aim_target = position
+                + Angle::from_radians(rng.gen_range(-PI..PI)).to_vec()
+                    * rng.gen_range(0.0..Self::AIM_RADIUS);
+
+            requests.push(Request::Game(Command::Control(Control {
+                guidance: Some(Guidance {
+                    direction_target: self.direction,
+                    velocity_target: data.speed * 0.5,
+                }),
+                submerge: false,
+                periscope: false,
+                aim_target: Some(aim_target),
+                active: true,
+                fire,
+                pay: None,
+                hint: None,
+                horn: false,
+                repair_crew: false,
+                searchlight: false,
+                emcon: false,
+                throttle: None,
+                cruise: false,
+                collision_assist: false,
+                turret_locks: Vec::new(),
+            })));
+        } else if self.player_id.is_some() && elapsed >= self.next_spawn {
+            self.next_spawn = elapsed + Self::SPAWN_PERIOD;
+            if let Some(entity_type) = EntityType::spawn_options(0, false, false).choose(rng) {
+                requests.push(Request::Game(Command::Spawn(Spawn {
+                    entity_type,
+                    cosmetics: Cosmetics::default(),
+                    region: SpawnRegion::default(),
+                })));
+            }
+        }
+
+        requests
+    }
+
+    fn receive(&mut self, update: &[u8]) {
+        match bincode::deserialize::<core_protocol::rpc::Update<Update>>(update) {
+            Ok(core_protocol::rpc::Update::Client(ClientUpdate::SessionCreated {
+                player_id,
+                ..
+            })) => self.player_id = Some(player_id),
+            Ok(core_protocol::rpc::Update::Game(update)) => {
+                let player_id = self.player_id;
+                self.boat = update.entity_type.and_then(|entity_type| {
+                    update
+                        .contacts
+                        .iter()
+                        .find(|contact| contact.is_boat() && contact.player_id() == player_id)
+                        .map(|contact| (entity_type, contact.transform().position))
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Load tests a server with simulated clients that spawn, steer, and fire.
+#[tokio::main]
+async fn main() {
+    let options = LoadTestOptions::from_args();
+    load_test::run(options, Mk48Behavior::new).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Mk48Behavior;
+    use common::entity::EntityType;
+    use common::protocol::Command;
+    use core_protocol::id::PlayerId;
+    use core_protocol::rpc::Request;
+    use glam::Vec2;
+    use load_test::behavior::Behavior;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::num::NonZeroU32;
+    use std::time::Duration;
+
+    #[test]
+    fn spawn_steer_and_fire() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut behavior = Mk48Behavior::new(0);
+        let spawns = |requests: &[Request<Command>]| {
+            requests
+                .iter()
+                .filter(|r| matches!(r, Request::Game(Command::Spawn(_))))
+                .count()
+        };
+
+        // Waits to be assigned a player.
+        let requests = behavior.tick(Duration::ZERO, &mut rng);
+        assert_eq!(spawns(&requests), 0);
+
+        behavior.player_id = Some(PlayerId(NonZeroU32::new(u32::MAX).unwrap()));
+        let requests = behavior.tick(Duration::from_secs(1), &mut rng);
+        assert_eq!(spawns(&requests), 1);
+        let requests = behavior.tick(Duration::from_millis(1500), &mut rng);
+        assert_eq!(spawns(&requests), 0);
+
+        behavior.boat = Some((EntityType::Yamato, Vec2::ZERO));
+        let requests = behavior.tick(Duration::from_secs(2), &mut rng);
+        assert_eq!(spawns(&requests), 0);
+        assert!(requests.iter().any(|r| matches!(
+            r,
+            Request::Game(Command::Control(control))
+                if control.guidance.is_some() && control.fire.is_some()
+        )));
+    }
+}
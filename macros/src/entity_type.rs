@@ -1,12 +1,19 @@
 use common_util::angle::Angle;
 use common_util::range::map_ranges;
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Span, TokenTree};
 use quote::{quote, ToTokens};
+use serde::{de, Deserialize, Deserializer};
 use std::collections::HashMap;
 use std::ops::Mul;
+use std::path::Path;
 use std::str::FromStr;
-use syn::{parse_macro_input, Data, DataEnum, DeriveInput, Lit, Meta, MetaNameValue, NestedMeta};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{
+    bracketed, parse_macro_input, Data, DataEnum, DeriveInput, Lit, LitBool, LitStr, Meta,
+    MetaNameValue, NestedMeta, Token,
+};
 
 pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
     let DeriveInput { ident, data, .. } = parse_macro_input!(input);
@@ -22,17 +29,32 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
         .map(|variant| variant.ident.to_string())
         .collect::<Vec<_>>();
 
+    let mut errors = Vec::new();
+    // Files that entities were loaded from, which must trigger a rebuild if changed.
+    let mut tracked_paths = Vec::new();
+
     let mut entities = variants
         .into_iter()
         .map(|variant| {
-            let mut entity = Entity::default();
+            let mut entity = Entity {
+                span: Some(variant.ident.span()),
+                ..Entity::default()
+            };
+            let mut file = None;
+            let mut inline = false;
 
             for attr in variant.attrs
             /* TODO filter */
             {
+                let span = attr.span();
                 let meta = attr.parse_meta().expect("couldn't parse as meta");
                 let list = match meta {
                     Meta::List(list) => list,
+                    // Not data, so allowed alongside a data file.
+                    Meta::NameValue(_) if meta.path().is_ident("wire_id") => {
+                        set_usize(&mut entity.wire_id, meta);
+                        continue;
+                    }
                     Meta::Path(_) => panic!("unexpected top-level path"),
                     Meta::NameValue(_) => panic!("unexpected top-level name-value pair"),
                 };
@@ -120,7 +142,23 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                 }
 
                 let path = list.path.get_ident().unwrap().to_string();
+                inline |= path != "data";
                 match path.as_str() {
+                    "data" => {
+                        let mut nested = list.nested.into_iter();
+                        let (Some(NestedMeta::Lit(Lit::Str(str_lit))), None) = (nested.next(), nested.next()) else {
+                            panic!("expected a single path for data");
+                        };
+                        file = Some((str_lit.value(), span));
+                    }
+                    "template" => {
+                        let mut nested = list.nested.into_iter();
+                        let (Some(NestedMeta::Meta(Meta::Path(path))), None) = (nested.next(), nested.next()) else {
+                            panic!("expected a single entity type for template");
+                        };
+                        entity.template = Some(path.get_ident().unwrap().to_string());
+                        entity.template_span = Some(span);
+                    }
                     "info" => {
                         for nested in list.nested {
                             let NestedMeta::Meta(nested) = nested else {
@@ -202,7 +240,25 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                             );
                         }
                     }
+                    "fx" => {
+                        for nested in list.nested {
+                            let NestedMeta::Meta(nested) = nested else {
+                                panic!("expected nested meta");
+                            };
+
+                            let path = nested.path().get_ident().unwrap().to_string();
+
+                            match path.as_str() {
+                                "fire_sound" => set_string(&mut entity.fire_sound, nested),
+                                "explosion" => set_string(&mut entity.explosion, nested),
+                                "wake_scale" => set_f32(&mut entity.wake_scale, nested),
+                                _ => panic!("unexpected fx path: {path}"),
+                            }
+                        }
+                    }
                     "props" => {
+                        entity.props_span = Some(span);
+
                         for nested in list.nested {
                             let NestedMeta::Meta(nested) = nested else {
                             panic!("expected nested meta");
@@ -232,6 +288,9 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                                 "damage" => {
                                     set_f32(&mut entity.damage, nested);
                                 }
+                                "base_damage" => {
+                                    set_f32(&mut entity.base_damage, nested);
+                                }
                                 "ram_damage" => {
                                     set_f32(&mut entity.ram_damage, nested);
                                 }
@@ -269,7 +328,10 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                         }
                     }
                     "armament" => {
-                        let mut armament = Armament::default();
+                        let mut armament = Armament {
+                            span: Some(span),
+                            ..Armament::default()
+                        };
 
                         for (i, nested) in list.nested.into_iter().enumerate() {
                             let NestedMeta::Meta(nested) = nested else {
@@ -310,6 +372,9 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                                     "vertical" => {
                                         set_bool(&mut armament.vertical, nested);
                                     }
+                                    "group" => {
+                                        set_string(&mut armament.group, nested);
+                                    }
                                     _ => panic!("unexpected armament path: {path}"),
                                 },
                             }
@@ -318,7 +383,10 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                         entity.armaments.push(armament);
                     }
                     "turret" => {
-                        let mut turret = Turret::default();
+                        let mut turret = Turret {
+                            span: Some(span),
+                            ..Turret::default()
+                        };
                         let mut speed = None;
 
                         for (i, nested) in list.nested.into_iter().enumerate() {
@@ -382,16 +450,15 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                             }
                         }
 
-                        turret.speed = Some(match speed.as_deref() {
-                            Some("slow") => Angle::PI * 0.3,
-                            Some("fast") => Angle::PI * 0.6,
-                            _ => Angle::PI * 0.45,
-                        });
+                        turret.speed = Some(turret_speed(speed.as_deref()).unwrap());
 
                         entity.turrets.push(turret);
                     }
                     "exhaust" => {
-                        let mut exhaust = Exhaust::default();
+                        let mut exhaust = Exhaust {
+                            span: Some(span),
+                            ..Exhaust::default()
+                        };
 
                         for nested in list.nested {
                             let NestedMeta::Meta(nested) = nested else {
@@ -420,12 +487,48 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                 }
             }
 
+            if let Some((path, span)) = file {
+                if inline {
+                    errors.push(syn::Error::new(
+                        span,
+                        "entity loaded from data file must not have other attributes",
+                    ));
+                }
+                match load_entity(&path, span) {
+                    Ok((loaded, tracked_path)) => {
+                        entity = Entity {
+                            span: Some(variant.ident.span()),
+                            props_span: Some(span),
+                            wire_id: entity.wire_id,
+                            ..loaded
+                        };
+                        tracked_paths.push(tracked_path);
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
+
             (variant.ident.to_string(), entity)
         })
         .collect::<HashMap<_, _>>();
 
     //panic!("{entities:?}");
 
+    for name in &ordered_entity_names {
+        resolve_template(name, &mut entities, &mut Vec::new(), &mut errors);
+    }
+
+    // Report all invalid attributes at once, before they can cause panics or nonsense data below.
+    for name in &ordered_entity_names {
+        validate(&entities[name], &entities, &mut errors);
+    }
+    if let Some(error) = errors.into_iter().reduce(|mut a, b| {
+        a.combine(b);
+        a
+    }) {
+        return error.to_compile_error().into();
+    }
+
     let original_entities = entities.clone();
     let mut max_radius = 0f32;
     let mut max_boat_level = 0;
@@ -474,6 +577,9 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                         "Laser" => {
                             max_range = 2000.0;
                         }
+                        "Beam" => {
+                            max_range = 1000.0;
+                        }
                         "Sam" | "Rocket" | "RocketTorpedo" | "Missile" => {
                             max_range = map_ranges(entity.length(), 1.0..10.0, 500.0..1200.0, true);
 
@@ -551,152 +657,40 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
             _ => {}
         }
 
-        let mut damage = None;
-        match entity.kind() {
-            "Boat" => {
-                // Damage means health (i.e. how much damage before death).
-                let mut factor: f32 = 20.0 / 10.0 / 60.0;
-                if entity.sub_kind() == "Starship" {factor = 20.0 / 10.0 / 60.0 / 3.0};
-                damage = Some(factor.max(factor * entity.length()));
-            }
-            "Weapon" => {
-                // Damage means damage dealt.
-                match entity.sub_kind() {
-                    "Torpedo" => {
-                        damage = Some(0.27 * entity.length().powf(0.7));
-                        // Homing torpedoes do less damage.
-                        /*
-                        if entity.sensors.contains_key("sonar") {
-                            *damage.as_mut().unwrap() -= 0.1;
-                        }
-                         */
-                    }
-                    "Sam" => {
-                        damage = Some(0.19 * entity.length().powf(0.7));
-                    }
-                    "Mine" => {
-                        damage = Some(entity.length());
-                    }
-                    "DepthCharge" => {
-                        damage = Some(0.7);
-                    }
-                    "Rocket" | "Missile" => {
-                        damage = Some(0.19 * entity.length().powf(0.7));
-                        if entity.length() > 1.3 && entity.sub_kind() == "Rocket" {damage = Some(0.75);}
-                        if entity.length() < 2.0 && entity.sub_kind() == "Missile" {damage = Some(1.5);}
-                    }
-                    "RocketTorpedo" => damage = Some(0.0),
-                    "Shell" => {
-                        let normal = 0.5 * entity.length().powf(0.35);
-                        let special = 0.14 * entity.length().powi(3);
-                        if entity.width() > 0.3 {
-                            damage = Some(normal.max(special));
-                        } else {
-                            // Very long, small shells do not benefit from "special" damage calculation.
-                            damage = Some(normal);
-                        }
-                    }
-                    "TankShell" => {
-                        let normal = entity.length() * 20.0 * entity.width();
-                        damage = Some(normal);
-                    }
-                    "Laser" => {
-                        damage = Some(entity.length() * 0.25);
-                    }
-                    "GlideBomb" => {
-                        damage = Some(entity.length());
-                    }
-                    _ => {}
-                }
-            }
-            _ => {}
-        }
-        if let Some(damage) = damage {
-            entity.damage = Some(if let Some(multiplier) = entity.damage {
-                damage * multiplier
-            } else {
-                damage
-            });
+        let multiplier = entity.damage;
+        entity.damage_source = match (entity.base_damage, multiplier) {
+            (None, None) => "formula",
+            (None, Some(_)) => "formula × multiplier",
+            (Some(_), None) => "override",
+            (Some(_), Some(_)) => "override × multiplier",
+        };
+        if let Some(damage) = entity.base_damage.or_else(|| derive_damage(entity)) {
+            entity.damage = Some(damage * multiplier.unwrap_or(1.0));
         } else {
             assert_eq!(
                 entity.damage, None,
                 "unexpected damage multiplier for {variant}"
             );
+            entity.damage_source = "";
         }
 
-        if entity.reload.is_none() {
-            match entity.kind() {
-                "Weapon" => {
-                    entity.reload = Some(match entity.sub_kind() {
-                        "Depositor" => 1.0,
-                        "Shovel" => 1.0,
-                        "Rocket" => 2.5,
-                        "RocketTorpedo" => 20.0,
-                        "Mine" => 15.0,
-                        "Sam" => 16.0,
-                        "Missile" => map_ranges(entity.length(), 1.0..6.0, 4.0..12.0, true),
-                        "Shell" => map_ranges(entity.length(), 0.25..2.0, 8.0..15.0, true),
-                        "TankShell" => 2.0,
-                        "Laser" => 2.0,
-                        "Torpedo" => {
-                            let mut reload = 8.0;
-                            if !entity.sensors.is_empty() {
-                                // Homing torpedoes take longer to reload
-                                reload *= 1.5;
-                            }
-                            reload
-                        }
-                        "DepthCharge" => 5.0,
-                        "GlideBomb" => 12.0,
-                        _ => 8.0,
-                    });
-                }
-                "Aircraft" => {
-                    entity.reload = Some(10.0);
-                }
-                "Decoy" => {
-                    entity.reload = Some(20.0);
-                }
-                _ => {}
-            }
+        if entity.reload.is_some() {
+            entity.reload_source = "override";
+        } else {
+            entity.reload = derive_reload(entity);
+            entity.reload_source = if entity.reload.is_some() {
+                "formula"
+            } else {
+                ""
+            };
         }
 
         let mut armaments = std::mem::take(&mut entity.armaments);
         let turrets = std::mem::take(&mut entity.turrets);
         let exhausts = std::mem::take(&mut entity.exhausts);
 
-        for mut turret in turrets {
-            turret.angle = Some(turret.angle.unwrap_or_default());
-
-            if let Some(azimuth) = turret.azimuth {
-                turret.azimuth_b = Some(azimuth);
-                turret.azimuth_f = Some(azimuth);
-                turret.azimuth = None;
-            }
-            if let Some(azimuth_f) = turret.azimuth_f {
-                turret.azimuth_fl = Some(azimuth_f);
-                turret.azimuth_fr = Some(azimuth_f);
-                turret.azimuth_f = None;
-            }
-            if let Some(azimuth_b) = turret.azimuth_b {
-                turret.azimuth_bl = Some(azimuth_b);
-                turret.azimuth_br = Some(azimuth_b);
-                turret.azimuth_b = None;
-            }
-
-            let symmetrical = std::mem::take(&mut turret.symmetrical);
-            entity.turrets.push(turret.clone());
-            if symmetrical {
-                entity.turrets.push(Turret {
-                    angle: turret.angle.map(|a| -a),
-                    azimuth_fl: turret.azimuth_fr,
-                    azimuth_fr: turret.azimuth_fl,
-                    azimuth_bl: turret.azimuth_br,
-                    azimuth_br: turret.azimuth_bl,
-                    position_side: turret.position_side.map(|p| -p),
-                    ..turret
-                });
-            }
+        for turret in turrets {
+            entity.turrets.extend(turret.expand());
         }
         for (i, turret) in entity.turrets.iter().enumerate() {
             if let Some(_type) = turret._type.as_deref() {
@@ -762,7 +756,7 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                     _ => unreachable!("invalid sensor {typ}"),
                 };
 
-                sensor.range = Some(2000f32.min(base + factor * entity.length()));
+                sensor.range = Some(MAX_SENSOR_RANGE.min(base + factor * entity.length()));
             }
         }
         entity.sensors = sensors;
@@ -785,7 +779,7 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                     }
                 }
                 ("Weapon", "TankShell") => 10,
-                ("Weapon", "Laser") => 10,
+                ("Weapon", "Laser") | ("Weapon", "Beam") => 10,
                 ("Weapon", "DepthCharge") | ("Weapon", "Mine") => 1,
                 ("Weapon", "Sam") => -5,
                 ("Decoy", _) => -8,
@@ -808,6 +802,52 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
         });
         entity.armaments = armaments;
 
+        // Armaments without an explicit group are grouped by type.
+        for armament in &mut entity.armaments {
+            let name = armament
+                .group
+                .clone()
+                .unwrap_or_else(|| name_to_string(armament._type()).to_owned());
+            let index = entity
+                .armament_groups
+                .iter()
+                .position(|(n, _)| *n == name)
+                .unwrap_or_else(|| {
+                    entity.armament_groups.push((name, Vec::new()));
+                    entity.armament_groups.len() - 1
+                });
+            armament.group_index = index as u8;
+        }
+        for (i, armament) in entity.armaments.iter().enumerate() {
+            entity.armament_groups[armament.group_index as usize]
+                .1
+                .push(i as u8);
+        }
+
+        if entity.fire_sound.is_none() {
+            entity.fire_sound = match (entity.kind(), entity.sub_kind()) {
+                ("Weapon", "Torpedo") => Some("torpedo_launch"),
+                ("Weapon", "Missile" | "Rocket" | "Sam" | "RocketTorpedo") => Some("rocket"),
+                ("Weapon", "DepthCharge" | "Mine") => Some("splash"),
+                ("Weapon", "Shell" | "TankShell") => Some("shell"),
+                _ => None,
+            }
+            .map(String::from);
+        }
+        if entity.explosion.is_none() {
+            entity.explosion = match (entity.kind(), entity.sub_kind()) {
+                ("Boat" | "Aircraft", _) => Some("splash"),
+                (
+                    "Weapon",
+                    "Missile" | "GlideBomb" | "Sam" | "Rocket" | "RocketTorpedo" | "Shell"
+                    | "TankShell",
+                ) => Some("explosion"),
+                ("Weapon", _) => Some("splash"),
+                _ => None,
+            }
+            .map(String::from);
+        }
+
         entity.stealth = Some(entity.stealth.unwrap_or_default());
         entity.radius = glam::Vec2::new(entity.width(), entity.length())
             .mul(0.5)
@@ -821,6 +861,36 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
         }
     }
 
+    // Speeds are only final after processing, so can't be validated earlier.
+    if let Some(error) = ordered_entity_names
+        .iter()
+        .filter_map(|name| {
+            let entity = &entities[name];
+            let speed = entity.speed.filter(|&speed| speed > MAX_SPEED)?;
+            Some(syn::Error::new(
+                entity.props_span.or(entity.span).unwrap_or_else(Span::call_site),
+                format!("{name}: speed {speed} exceeds maximum representable speed {MAX_SPEED}"),
+            ))
+        })
+        .reduce(|mut a, b| {
+            a.combine(b);
+            a
+        })
+    {
+        return error.to_compile_error().into();
+    }
+
+    for name in &ordered_entity_names {
+        for (span, message) in lint(&entities[name], &entities) {
+            span.unwrap_or_else(Span::call_site)
+                .unwrap()
+                .warning(format!("{name}: {message}"))
+                .emit();
+        }
+    }
+
+    let balance_table = balance_table(&ordered_entity_names, &entities);
+
     let entity_datas = ordered_entity_names
         .iter()
         .map(|s| entities.get(&*s).unwrap());
@@ -830,24 +900,46 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
         .map(|s| EntityTypeAsStr::new(s.to_string()))
         .collect();
 
-    let entity_type_from_strs: Vec<EntityTypeFromStr> = ordered_entity_names
+    let entity_type_from_u8s: Vec<EntityTypeFromU8> = ordered_entity_names
         .iter()
-        .map(|s| EntityTypeFromStr::new(s.to_string()))
+        .map(|s| EntityTypeFromU8::new(s.to_string(), entities[s].wire_id.unwrap() as u8))
         .collect();
 
-    let entity_type_from_u8s: Vec<EntityTypeFromU8> = ordered_entity_names
+    let entity_type_to_u8s = entity_type_from_u8s
         .iter()
-        .enumerate()
-        .map(|(i, s)| {
-            EntityTypeFromU8::new(
-                s.to_string(),
-                i.try_into()
-                    .expect("u8 cannot fit more than 256 entity types"),
-            )
-        })
-        .collect();
+        .map(|EntityTypeFromU8(name, wire_id)| {
+            let ident = string_to_ident(name);
+            quote! {
+                Self::#ident => #wire_id
+            }
+        });
+
+    let (displacements, slots) = perfect_hash(
+        &ordered_entity_names
+            .iter()
+            .map(|s| name_to_string(s))
+            .collect::<Vec<_>>(),
+    );
+    let slots = slots.into_iter().map(|slot| {
+        quote_option(slot.map(|i| {
+            let ident = string_to_ident(&ordered_entity_names[i]);
+            quote! {
+                Self::#ident
+            }
+        }))
+    });
+
+    let sprite_entities = ordered_entity_names.iter().map(|name| {
+        let ident = string_to_ident(name);
+        let boat = entities[name].kind() == "Boat";
+        quote! {
+            #ident: #boat
+        }
+    });
 
     quote! {
+        #(const _: &[u8] = include_bytes!(#tracked_paths);)*
+
         impl EntityType {
             pub fn as_str(&self) -> &'static str {
                 match self {
@@ -855,18 +947,27 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
                 }
             }
 
-            pub fn from_str(s: &str) -> Option<Self> {
-                Some(match s {
-                    #(#entity_type_from_strs),*,
-                    _ => return None
-                })
+            /// Returns the stable identifier of the entity type, for binary protocols.
+            pub fn to_wire_id(self) -> u8 {
+                match self {
+                    #(#entity_type_to_u8s),*
+                }
             }
 
-            pub fn from_u8(i: u8) -> Option<Self> {
-                Some(match i {
-                    #(#entity_type_from_u8s),*,
-                    _ => return None
-                })
+            /// Lookup table for `from_str`, see `perfect_hash` in the macro.
+            const HASH_DISPLACEMENTS: &'static [u32] = &[#(#displacements),*];
+            const HASH_SLOTS: &'static [Option<Self>] = &[#(#slots),*];
+
+            /// Must match `str_hash` in the macro.
+            fn str_hash(s: &str, seed: u32) -> u32 {
+                let mut hash = 0x811c9dc5u32 ^ seed;
+                for &b in s.as_bytes() {
+                    hash ^= b as u32;
+                    hash = hash.wrapping_mul(0x01000193);
+                }
+                hash ^= hash >> 16;
+                hash = hash.wrapping_mul(0x7feb352d);
+                hash ^ (hash >> 15)
             }
 
             const DATA: &[EntityData] = &[
@@ -874,6 +975,39 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
             ];
         }
 
+        /// Generates `fn entity_sprite(EntityType) -> Option<&'static EntitySprite>` from a sprite
+        /// sheet atlas (in the format of `UvSpriteSheet`), relative to the invoking crate. Fails to
+        /// compile if a boat has no sprite.
+        #[macro_export]
+        macro_rules! entity_sprites {
+            ($atlas:literal) => {
+                $crate::entity::__entity_sprites!($crate, $atlas, [#(#sprite_entities),*]);
+            };
+        }
+
+        impl std::str::FromStr for EntityType {
+            type Err = &'static str;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let displacement = Self::HASH_DISPLACEMENTS
+                    [Self::str_hash(s, 0) as usize % Self::HASH_DISPLACEMENTS.len()];
+                Self::HASH_SLOTS[Self::str_hash(s, displacement) as usize % Self::HASH_SLOTS.len()]
+                    .filter(|entity_type| entity_type.as_str() == s)
+                    .ok_or("invalid entity type")
+            }
+        }
+
+        impl ::std::convert::TryFrom<u8> for EntityType {
+            type Error = &'static str;
+
+            fn try_from(wire_id: u8) -> Result<Self, Self::Error> {
+                Ok(match wire_id {
+                    #(#entity_type_from_u8s),*,
+                    _ => return Err("invalid entity type wire id")
+                })
+            }
+        }
+
         impl std::fmt::Debug for EntityType {
             fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
                 f.write_str(self.as_str())
@@ -889,19 +1023,544 @@ pub(crate) fn derive_entity_type(input: TokenStream) -> TokenStream {
         impl EntityData {
             pub const MAX_RADIUS: f32 = #max_radius;
             pub const MAX_BOAT_LEVEL: u8 = #max_boat_level;
+            /// Final damage and reload of each entity, as a markdown table.
+            pub const BALANCE_TABLE: &'static str = #balance_table;
         }
     }
     .into()
 }
 
-#[derive(Clone, Debug, Default)]
+/// Derives damage from an entity's kind and dimensions. For boats, damage means health (i.e. how
+/// much damage before death). For weapons, damage means damage dealt. May be overridden with
+/// `#[props(base_damage = ..)]`, and multiplied with `#[props(damage = ..)]`.
+fn derive_damage(entity: &Entity) -> Option<f32> {
+    Some(match entity.kind() {
+        "Boat" => {
+            let factor: f32 = if entity.sub_kind() == "Starship" {
+                20.0 / 10.0 / 60.0 / 3.0
+            } else {
+                20.0 / 10.0 / 60.0
+            };
+            factor.max(factor * entity.length())
+        }
+        "Weapon" => match entity.sub_kind() {
+            "Torpedo" => 0.27 * entity.length().powf(0.7),
+            "Sam" => 0.19 * entity.length().powf(0.7),
+            "Mine" | "GlideBomb" => entity.length(),
+            "DepthCharge" => 0.7,
+            "Rocket" if entity.length() > 1.3 => 0.75,
+            "Missile" if entity.length() < 2.0 => 1.5,
+            "Rocket" | "Missile" => 0.19 * entity.length().powf(0.7),
+            "RocketTorpedo" => 0.0,
+            "Shell" => {
+                let normal = 0.5 * entity.length().powf(0.35);
+                let special = 0.14 * entity.length().powi(3);
+                if entity.width() > 0.3 {
+                    normal.max(special)
+                } else {
+                    // Very long, small shells do not benefit from "special" damage calculation.
+                    normal
+                }
+            }
+            "TankShell" => entity.length() * 20.0 * entity.width(),
+            "Laser" => entity.length() * 0.25,
+            // Per second that the beam is held on target.
+            "Beam" => entity.length() * 0.25,
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// Derives reload, in seconds, from an entity's kind and dimensions. May be overridden with
+/// `#[props(reload = ..)]`.
+fn derive_reload(entity: &Entity) -> Option<f32> {
+    Some(match entity.kind() {
+        "Weapon" => match entity.sub_kind() {
+            "Depositor" | "Shovel" => 1.0,
+            "Rocket" => 2.5,
+            "RocketTorpedo" => 20.0,
+            "Mine" => 15.0,
+            "Sam" => 16.0,
+            "Missile" => map_ranges(entity.length(), 1.0..6.0, 4.0..12.0, true),
+            "Shell" => map_ranges(entity.length(), 0.25..2.0, 8.0..15.0, true),
+            "TankShell" | "Laser" => 2.0,
+            // Time to cool down after overheating.
+            "Beam" => 6.0,
+            // Homing torpedoes take longer to reload.
+            "Torpedo" if !entity.sensors.is_empty() => 12.0,
+            "DepthCharge" => 5.0,
+            "GlideBomb" => 12.0,
+            _ => 8.0,
+        },
+        "Aircraft" => 10.0,
+        "Decoy" => 20.0,
+        _ => return None,
+    })
+}
+
+/// Documents the final damage and reload of every entity that has them, as a markdown table, so
+/// that balance changes are reviewable.
+fn balance_table(names: &[String], entities: &HashMap<String, Entity>) -> String {
+    let mut table = String::from(
+        "| Entity | Kind | Damage | Damage source | Reload (s) | Reload source |\n\
+         |--------|------|--------|---------------|------------|---------------|\n",
+    );
+    for name in names {
+        let entity = &entities[name];
+        if entity.damage.is_none() && entity.reload.is_none() {
+            continue;
+        }
+        let number = |value: Option<f32>| value.map(|v| format!("{v:.3}")).unwrap_or_default();
+        table.push_str(&format!(
+            "| {} | {}/{} | {} | {} | {} | {} |\n",
+            name_to_string(name),
+            entity.kind(),
+            entity.sub_kind(),
+            number(entity.damage),
+            entity.damage_source,
+            number(entity.reload),
+            entity.reload_source,
+        ));
+    }
+    table
+}
+
+/// Sensors beyond this range won't see anything, as entities further away aren't sent to clients.
+const MAX_SENSOR_RANGE: f32 = 2000.0;
+/// Maximum speed, in meters per second, that `Velocity` can represent (must match common), with a
+/// factor of 4 of headroom for relative velocities.
+const MAX_SPEED: f32 = i32::MAX as f32 / 32.0 / 4.0;
+
+/// Checks fully processed entities for physically implausible data, which is still valid in that
+/// it compiles and doesn't panic. Returns warnings.
+fn lint(entity: &Entity, entities: &HashMap<String, Entity>) -> Vec<(Option<Span>, String)> {
+    let mut warnings = Vec::new();
+
+    // Allow some slack, since hulls aren't rectangular and some armaments overhang.
+    let within_footprint = |forward: Option<f32>, side: Option<f32>| {
+        const SLACK: f32 = 1.1;
+        forward.unwrap_or_default().abs() <= entity.length() * 0.5 * SLACK
+            && side.unwrap_or_default().abs() <= entity.width() * 0.5 * SLACK
+    };
+
+    for armament in &entity.armaments {
+        // Turreted armaments are relative to their turret, which is checked below.
+        if armament.turret.is_none()
+            && !within_footprint(armament.position_forward, armament.position_side)
+        {
+            warnings.push((
+                armament.span,
+                format!("{} armament is outside the hull", armament._type()),
+            ));
+        }
+    }
+
+    for (i, turret) in entity.turrets.iter().enumerate() {
+        if !within_footprint(turret.position_forward, turret.position_side) {
+            warnings.push((turret.span, format!("turret {i} is outside the hull")));
+        }
+
+        let Some(length) = turret._type.as_ref().map(|t| entities[t].length()) else {
+            continue;
+        };
+        let position = |turret: &Turret| {
+            glam::Vec2::new(
+                turret.position_forward.unwrap_or_default(),
+                turret.position_side.unwrap_or_default(),
+            )
+        };
+        for (j, other) in entity.turrets.iter().enumerate().skip(i + 1) {
+            let Some(other_length) = other._type.as_ref().map(|t| entities[t].length()) else {
+                continue;
+            };
+            if position(turret).distance(position(other)) < length.min(other_length) * 0.5 {
+                warnings.push((turret.span, format!("turrets {i} and {j} overlap")));
+            }
+        }
+    }
+
+    for (typ, sensor) in &entity.sensors {
+        let range = sensor.range.unwrap_or_default();
+        if range > MAX_SENSOR_RANGE {
+            warnings.push((
+                entity.span,
+                format!("{typ} range {range} exceeds update radius {MAX_SENSOR_RANGE}"),
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Checks for attribute combinations that would otherwise silently produce nonsense data (or
+/// panic with an unhelpful message).
+fn validate(entity: &Entity, entities: &HashMap<String, Entity>, errors: &mut Vec<syn::Error>) {
+    let mut error = |span: Option<Span>, message: &str| {
+        errors.push(syn::Error::new(
+            span.unwrap_or_else(Span::call_site),
+            message,
+        ));
+    };
+
+    if entity.kind.is_none() || entity.sub_kind.is_none() {
+        error(entity.span, "missing #[entity(Kind, SubKind)]");
+        return;
+    }
+    if entity.length.is_none() || entity.width.is_none() {
+        error(entity.span, "missing #[size(length = .., width = ..)]");
+    }
+    match entity.wire_id {
+        None => error(entity.span, "missing #[wire_id = ..]"),
+        Some(wire_id) if wire_id > u8::MAX as usize => {
+            error(entity.span, "wire_id must fit in a u8")
+        }
+        Some(wire_id) => {
+            if entities
+                .values()
+                .filter(|e| e.wire_id == Some(wire_id))
+                .count()
+                > 1
+            {
+                error(entity.span, &format!("duplicate wire_id {wire_id}"));
+            }
+        }
+    }
+    if entity.label.is_none() {
+        error(entity.span, "missing #[info(label = ..)]");
+    }
+    if entity.kind() == "Boat" && entity.level.is_none() {
+        error(entity.span, "boat is missing #[entity(.., level = ..)]");
+    }
+
+    if entity.kind() == "Weapon" {
+        if let Some(props_span) = entity.props_span {
+            if entity.range.is_some()
+                && entity.speed.is_none()
+                && !matches!(entity.sub_kind(), "Depositor" | "Shovel")
+            {
+                error(Some(props_span), "weapon with range must also have speed");
+            }
+            if entity.range.is_none() && entity.lifespan.is_none() {
+                error(Some(props_span), "weapon must have range or lifespan");
+            }
+        } else {
+            error(entity.span, "weapon is missing #[props(..)]");
+        }
+    }
+
+    for armament in &entity.armaments {
+        if let Some(_type) = armament._type.as_deref() {
+            if !entities.contains_key(_type) {
+                error(armament.span, &format!("unknown armament type {_type}"));
+            }
+        }
+        if armament.symmetrical && armament.position_side.unwrap_or_default() == 0.0 {
+            error(armament.span, "symmetrical armament must have nonzero side");
+        }
+    }
+
+    for turret in &entity.turrets {
+        if let Some(_type) = turret._type.as_deref() {
+            if !entities.contains_key(_type) {
+                error(turret.span, &format!("unknown turret type {_type}"));
+            }
+        }
+        if turret.symmetrical && turret.position_side.unwrap_or_default() == 0.0 {
+            error(turret.span, "symmetrical turret must have nonzero side");
+        }
+    }
+
+    for exhaust in &entity.exhausts {
+        if exhaust.symmetrical && exhaust.position_side.unwrap_or_default() == 0.0 {
+            error(exhaust.span, "symmetrical exhaust must have nonzero side");
+        }
+    }
+
+    // Armaments refer to turrets by index, after symmetrical turrets are mirrored.
+    let turrets = entity
+        .turrets
+        .iter()
+        .cloned()
+        .flat_map(Turret::expand)
+        .collect::<Vec<_>>();
+
+    for turret in &turrets {
+        if !turret.can_aim(Angle::ZERO) {
+            error(
+                turret.span,
+                "turret azimuth excludes every angle its armaments could aim at",
+            );
+        }
+    }
+
+    for armament in &entity.armaments {
+        if let Some(index) = armament.turret {
+            if let Some(turret) = turrets.get(index) {
+                // An armament at zero angle is covered by the turret's own check, above.
+                let angle = armament.angle.unwrap_or_default();
+                if angle != Angle::ZERO && turret.can_aim(Angle::ZERO) && !turret.can_aim(angle) {
+                    error(armament.span, "turret azimuth excludes armament angle");
+                }
+            } else {
+                error(
+                    armament.span,
+                    &format!(
+                        "armament refers to turret {index}, but there are only {} turrets",
+                        turrets.len()
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Makes the entity inherit from its template, if any, after resolving the template's own template.
+fn resolve_template(
+    name: &str,
+    entities: &mut HashMap<String, Entity>,
+    resolving: &mut Vec<String>,
+    errors: &mut Vec<syn::Error>,
+) {
+    let entity = &entities[name];
+    let Some(template) = entity.template.clone() else {
+        return;
+    };
+    let span = entity
+        .template_span
+        .or(entity.props_span)
+        .or(entity.span)
+        .unwrap_or_else(Span::call_site);
+
+    if resolving.iter().any(|n| n == name) {
+        errors.push(syn::Error::new(span, format!("cyclic template {template}")));
+        entities.get_mut(name).unwrap().template = None;
+        return;
+    }
+    if !entities.contains_key(&template) {
+        errors.push(syn::Error::new(
+            span,
+            format!("unknown template {template}"),
+        ));
+        entities.get_mut(name).unwrap().template = None;
+        return;
+    }
+
+    resolving.push(name.to_owned());
+    resolve_template(&template, entities, resolving, errors);
+    resolving.pop();
+
+    let template = entities[&template].clone();
+    let entity = entities.get_mut(name).unwrap();
+    entity.template = None;
+    entity.inherit(&template);
+}
+
+/// Reads a data file, relative to the crate being compiled. Also returns the absolute path, for
+/// tracking.
+fn read_data(path: &str, span: Span) -> Result<(String, String), syn::Error> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| syn::Error::new(span, "CARGO_MANIFEST_DIR not set"))?;
+    let full_path = Path::new(&manifest_dir).join(path);
+    let full_path = full_path.to_str().ok_or_else(|| {
+        syn::Error::new(
+            span,
+            format!("data path {} is not valid UTF-8", full_path.display()),
+        )
+    })?;
+
+    let data = std::fs::read_to_string(full_path)
+        .map_err(|e| syn::Error::new(span, format!("couldn't read {full_path}: {e}")))?;
+    Ok((data, full_path.to_owned()))
+}
+
+/// Loads an entity from a JSON data file, relative to the crate being compiled. Also returns the
+/// absolute path, for tracking.
+fn load_entity(path: &str, span: Span) -> Result<(Entity, String), syn::Error> {
+    let (json, full_path) = read_data(path, span)?;
+    let mut entity: Entity = serde_json::from_str(&json)
+        .map_err(|e| syn::Error::new(span, format!("couldn't parse {full_path}: {e}")))?;
+
+    // Point errors at the attribute, since the file has no spans.
+    for armament in &mut entity.armaments {
+        armament.span = Some(span);
+    }
+    for turret in &mut entity.turrets {
+        turret.span = Some(span);
+        turret.speed.get_or_insert(turret_speed(None).unwrap());
+    }
+    for exhaust in &mut entity.exhausts {
+        exhaust.span = Some(span);
+    }
+
+    Ok((entity, full_path))
+}
+
+/// Input of `entity_sprites`, which is generated by the `entity_sprites!` macro that
+/// `derive_entity_type` generates, i.e. `$crate, "atlas.json", [EntityType: is_boat, ...]`.
+struct EntitySpritesInput {
+    /// Path to the crate that defines `EntityType`.
+    krate: TokenTree,
+    atlas: LitStr,
+    /// Every entity type, in order, and whether it is a boat.
+    entities: Vec<(Ident, bool)>,
+}
+
+impl Parse for EntitySpritesInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let krate = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let atlas = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let content;
+        bracketed!(content in input);
+        let mut entities = Vec::new();
+        while !content.is_empty() {
+            let ident = content.parse()?;
+            content.parse::<Token![:]>()?;
+            let boat: LitBool = content.parse()?;
+            entities.push((ident, boat.value));
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Self {
+            krate,
+            atlas,
+            entities,
+        })
+    }
+}
+
+pub(crate) fn entity_sprites(input: TokenStream) -> TokenStream {
+    let EntitySpritesInput {
+        krate,
+        atlas: atlas_path,
+        entities,
+    } = parse_macro_input!(input);
+
+    let span = atlas_path.span();
+    let (atlas, tracked_path) = match load_atlas(&atlas_path.value(), span) {
+        Ok(loaded) => loaded,
+        Err(e) => return e.into_compile_error().into(),
+    };
+
+    let mut errors = Vec::new();
+    if atlas.is_empty() {
+        errors.push(syn::Error::new(span, "atlas has no sprites"));
+    }
+    for (ident, boat) in &entities {
+        if *boat && !atlas.contains_key(name_to_string(&ident.to_string())) {
+            errors.push(syn::Error::new(
+                span,
+                format!("{ident} has no sprite in atlas"),
+            ));
+        }
+    }
+    if let Some(error) = errors.into_iter().reduce(|mut a, b| {
+        a.combine(b);
+        a
+    }) {
+        return error.into_compile_error().into();
+    }
+
+    let sprites = entities.iter().map(|(ident, _)| {
+        quote_option(atlas.get(name_to_string(&ident.to_string())).map(
+            |AtlasSprite { uvs, aspect }| {
+                let uvs = uvs.iter().map(|[u, v]| quote! { [#u, #v] });
+                quote! {
+                    #krate::entity::EntitySprite {
+                        uvs: [#(#uvs),*],
+                        aspect: #aspect,
+                    }
+                }
+            },
+        ))
+    });
+
+    quote! {
+        const _: &[u8] = include_bytes!(#tracked_path);
+
+        /// Location of the entity type's sprite in the atlas, if it has one. Boats always do.
+        pub fn entity_sprite(
+            entity_type: #krate::entity::EntityType,
+        ) -> Option<&'static #krate::entity::EntitySprite> {
+            const SPRITES: &[Option<#krate::entity::EntitySprite>] = &[#(#sprites),*];
+            SPRITES[entity_type as usize].as_ref()
+        }
+    }
+    .into()
+}
+
+/// Loads the sprites of a sprite sheet (in the format of `UvSpriteSheet`), keyed by name.
+fn load_atlas(
+    path: &str,
+    span: Span,
+) -> Result<(HashMap<String, AtlasSprite>, String), syn::Error> {
+    #[derive(Deserialize)]
+    struct Atlas {
+        sprites: HashMap<String, AtlasSprite>,
+    }
+
+    let (json, full_path) = read_data(path, span)?;
+    let atlas: Atlas = serde_json::from_str(&json)
+        .map_err(|e| syn::Error::new(span, format!("couldn't parse {full_path}: {e}")))?;
+    Ok((atlas.sprites, full_path))
+}
+
+/// Turret rotation speed, given `slow`, `medium` (the default), or `fast`.
+fn turret_speed(speed: Option<&str>) -> Option<Angle> {
+    Some(match speed {
+        Some("slow") => Angle::PI * 0.3,
+        Some("medium") | None => Angle::PI * 0.45,
+        Some("fast") => Angle::PI * 0.6,
+        Some(_) => return None,
+    })
+}
+
+fn deserialize_degrees<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Angle>, D::Error> {
+    Ok(Option::<f32>::deserialize(deserializer)?.map(Angle::from_degrees))
+}
+
+fn deserialize_turret_speed<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Angle>, D::Error> {
+    let speed = String::deserialize(deserializer)?;
+    turret_speed(Some(&speed))
+        .map(Some)
+        .ok_or_else(|| de::Error::unknown_variant(&speed, &["slow", "medium", "fast"]))
+}
+
+/// Fields that are only computed by the macro are skipped when loading from a data file, and
+/// others are named like their attribute counterparts.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 struct Entity {
+    /// Where the entity is defined, for error reporting.
+    #[serde(skip)]
+    span: Option<Span>,
+    /// Where the entity's props are defined, for error reporting.
+    #[serde(skip)]
+    props_span: Option<Span>,
     name: Option<String>,
+    /// Stable identifier in binary protocols.
+    #[serde(skip)]
+    wire_id: Option<usize>,
+    /// Entity type to inherit unspecified attributes from.
+    template: Option<String>,
+    #[serde(skip)]
+    template_span: Option<Span>,
     label: Option<String>,
     link: Option<String>,
     kind: Option<String>,
     sub_kind: Option<String>,
+    #[serde(rename = "forward")]
     position_forward: Option<f32>,
+    #[serde(rename = "side")]
     position_side: Option<f32>,
     level: Option<usize>,
     length: Option<f32>,
@@ -914,21 +1573,99 @@ struct Entity {
     range: Option<f32>,
     lifespan: Option<f32>,
     stealth: Option<f32>,
+    /// Multiplier, until derived.
     damage: Option<f32>,
+    /// Replaces derived damage.
+    base_damage: Option<f32>,
+    /// How damage was derived, for the balance table.
+    #[serde(skip)]
+    damage_source: &'static str,
+    /// How reload was derived, for the balance table.
+    #[serde(skip)]
+    reload_source: &'static str,
     ram_damage: Option<f32>,
     torpedo_resistance: Option<f32>,
+    fire_sound: Option<String>,
+    explosion: Option<String>,
+    wake_scale: Option<f32>,
     sensors: HashMap<String, Sensor>,
     armaments: Vec<Armament>,
     turrets: Vec<Turret>,
     exhausts: Vec<Exhaust>,
+    /// Names of armament groups, and the indices of their armaments.
+    #[serde(skip)]
+    armament_groups: Vec<(String, Vec<u8>)>,
+    #[serde(skip)]
     limited: bool,
+    #[serde(skip)]
     npc: bool,
+    #[serde(skip)]
     anti_aircraft: f32,
+    #[serde(skip)]
     radius: f32,
+    #[serde(skip)]
     inv_size: f32,
 }
 
 impl Entity {
+    /// Fills in any attributes that weren't specified from a (resolved) template. Sensors,
+    /// armaments, turrets, and exhausts are inherited as a whole, i.e. only if none were specified.
+    /// The label and link identify the entity, so they are never inherited.
+    fn inherit(&mut self, template: &Self) {
+        macro_rules! inherit {
+            ($($field:ident),*) => {
+                $(self.$field = self.$field.or(template.$field);)*
+            };
+        }
+
+        if self.kind.is_none() {
+            self.kind = template.kind.clone();
+        }
+        if self.sub_kind.is_none() {
+            self.sub_kind = template.sub_kind.clone();
+        }
+        if self.fire_sound.is_none() {
+            self.fire_sound = template.fire_sound.clone();
+        }
+        if self.explosion.is_none() {
+            self.explosion = template.explosion.clone();
+        }
+        inherit!(
+            props_span,
+            position_forward,
+            position_side,
+            level,
+            length,
+            width,
+            draft,
+            mast,
+            reload,
+            depth,
+            speed,
+            range,
+            lifespan,
+            stealth,
+            damage,
+            base_damage,
+            ram_damage,
+            torpedo_resistance,
+            wake_scale
+        );
+
+        if self.sensors.is_empty() {
+            self.sensors = template.sensors.clone();
+        }
+        if self.armaments.is_empty() {
+            self.armaments = template.armaments.clone();
+        }
+        if self.turrets.is_empty() {
+            self.turrets = template.turrets.clone();
+        }
+        if self.exhausts.is_empty() {
+            self.exhausts = template.exhausts.clone();
+        }
+    }
+
     fn kind(&self) -> &str {
         self.kind.as_deref().unwrap()
     }
@@ -946,16 +1683,24 @@ impl Entity {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 struct Sensor {
     range: Option<f32>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 struct Armament {
+    #[serde(skip)]
+    span: Option<Span>,
+    #[serde(rename = "type")]
     _type: Option<String>,
+    #[serde(rename = "forward")]
     position_forward: Option<f32>,
+    #[serde(rename = "side")]
     position_side: Option<f32>,
+    #[serde(deserialize_with = "deserialize_degrees")]
     angle: Option<Angle>,
     symmetrical: bool,
     turret: Option<usize>,
@@ -963,6 +1708,9 @@ struct Armament {
     hidden: bool,
     external: bool,
     vertical: bool,
+    group: Option<String>,
+    #[serde(skip)]
+    group_index: u8,
 }
 
 impl Armament {
@@ -971,75 +1719,194 @@ impl Armament {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 struct Turret {
+    #[serde(skip)]
+    span: Option<Span>,
+    #[serde(rename = "type")]
     _type: Option<String>,
+    #[serde(rename = "forward")]
     position_forward: Option<f32>,
+    #[serde(rename = "side")]
     position_side: Option<f32>,
+    #[serde(deserialize_with = "deserialize_turret_speed")]
     speed: Option<Angle>,
+    #[serde(deserialize_with = "deserialize_degrees")]
     angle: Option<Angle>,
+    #[serde(deserialize_with = "deserialize_degrees")]
     azimuth: Option<Angle>,
+    #[serde(deserialize_with = "deserialize_degrees")]
     azimuth_b: Option<Angle>,
+    #[serde(deserialize_with = "deserialize_degrees")]
     azimuth_br: Option<Angle>,
+    #[serde(deserialize_with = "deserialize_degrees")]
     azimuth_bl: Option<Angle>,
+    #[serde(deserialize_with = "deserialize_degrees")]
     azimuth_f: Option<Angle>,
+    #[serde(deserialize_with = "deserialize_degrees")]
     azimuth_fr: Option<Angle>,
+    #[serde(deserialize_with = "deserialize_degrees")]
     azimuth_fl: Option<Angle>,
     symmetrical: bool,
     hidden: bool,
 }
 
-#[derive(Clone, Debug, Default)]
+impl Turret {
+    /// Resolves azimuth shorthands, and mirrors the turret if it is symmetrical.
+    fn expand(mut self) -> Vec<Self> {
+        self.angle = Some(self.angle.unwrap_or_default());
+
+        if let Some(azimuth) = self.azimuth {
+            self.azimuth_b = Some(azimuth);
+            self.azimuth_f = Some(azimuth);
+            self.azimuth = None;
+        }
+        if let Some(azimuth_f) = self.azimuth_f {
+            self.azimuth_fl = Some(azimuth_f);
+            self.azimuth_fr = Some(azimuth_f);
+            self.azimuth_f = None;
+        }
+        if let Some(azimuth_b) = self.azimuth_b {
+            self.azimuth_bl = Some(azimuth_b);
+            self.azimuth_br = Some(azimuth_b);
+            self.azimuth_b = None;
+        }
+
+        let symmetrical = std::mem::take(&mut self.symmetrical);
+        let mut turrets = vec![self.clone()];
+        if symmetrical {
+            turrets.push(Self {
+                angle: self.angle.map(|a| -a),
+                azimuth_fl: self.azimuth_fr,
+                azimuth_fr: self.azimuth_fl,
+                azimuth_bl: self.azimuth_br,
+                azimuth_br: self.azimuth_bl,
+                position_side: self.position_side.map(|p| -p),
+                ..self
+            });
+        }
+        turrets
+    }
+
+    /// Returns whether the turret can stop at an angle that also points an armament, at `angle`
+    /// relative to the turret, in an unrestricted direction. Torpedo mounts are commonly stowed
+    /// within their restrictions, so the turret's own angle doesn't count.
+    fn can_aim(&self, angle: Angle) -> bool {
+        (0..360)
+            .map(|degrees| Angle::from_degrees(degrees as f32))
+            .any(|curr| self.within_azimuth(curr) && self.within_azimuth(curr + angle))
+    }
+
+    /// Same as `Turret::within_azimuth` in `common`, but for an expanded turret.
+    fn within_azimuth(&self, curr: Angle) -> bool {
+        let angle = self.angle.unwrap_or_default();
+        let azimuth_f = curr - angle;
+        if -self.azimuth_fr.unwrap_or_default() < azimuth_f
+            && azimuth_f < self.azimuth_fl.unwrap_or_default()
+        {
+            false
+        } else {
+            let azimuth_b = Angle::PI + curr - angle;
+            !(-self.azimuth_bl.unwrap_or_default() < azimuth_b
+                && azimuth_b < self.azimuth_br.unwrap_or_default())
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 struct Exhaust {
+    #[serde(skip)]
+    span: Option<Span>,
+    #[serde(rename = "forward")]
     position_forward: Option<f32>,
+    #[serde(rename = "side")]
     position_side: Option<f32>,
     symmetrical: bool,
 }
 
-fn name_to_string(name: &str) -> &str {
-    name.trim_start_matches('_')
+/// Must match `EntityType::str_hash` in the generated code.
+fn str_hash(s: &str, seed: u32) -> u32 {
+    let mut hash = 0x811c9dc5u32 ^ seed;
+    for &b in s.as_bytes() {
+        hash ^= b as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x7feb352d);
+    hash ^ (hash >> 15)
 }
 
-struct EntityTypeAsStr(String);
+/// Builds a minimal perfect hash table (hash and displace) of `names`, so that looking one up takes
+/// two hashes and a single comparison. Returns the displacement of each bucket, and the index of
+/// the name in each slot.
+fn perfect_hash(names: &[&str]) -> (Vec<u32>, Vec<Option<usize>>) {
+    let slot_count = names.len().next_power_of_two();
+    let bucket_count = (slot_count / 4).max(1);
 
-impl EntityTypeAsStr {
-    pub fn new(name: String) -> Self {
-        Self(name)
+    let mut buckets = vec![Vec::new(); bucket_count];
+    for (i, name) in names.iter().enumerate() {
+        buckets[str_hash(name, 0) as usize % bucket_count].push(i);
     }
-}
 
-impl quote::ToTokens for EntityTypeAsStr {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let name = name_to_string(&self.0);
-        let ident = string_to_ident(&self.0);
+    // Place the largest buckets first, while there are the most free slots.
+    let mut order: Vec<usize> = (0..bucket_count).collect();
+    order.sort_by_key(|&b| std::cmp::Reverse(buckets[b].len()));
 
-        let ts: proc_macro2::TokenStream = {
-            quote! {
-               Self::#ident => #name
-            }
+    let mut displacements = vec![0; bucket_count];
+    let mut slots = vec![None; slot_count];
+    for b in order {
+        if buckets[b].is_empty() {
+            continue;
+        }
+        let displacement = (1..)
+            .find(|&displacement| {
+                let mut taken = Vec::new();
+                buckets[b].iter().all(|&i| {
+                    let slot = str_hash(names[i], displacement) as usize % slot_count;
+                    let free = slots[slot].is_none() && !taken.contains(&slot);
+                    taken.push(slot);
+                    free
+                })
+            })
+            .unwrap();
+        displacements[b] = displacement;
+        for &i in &buckets[b] {
+            slots[str_hash(names[i], displacement) as usize % slot_count] = Some(i);
         }
-        .into();
-
-        tokens.extend(ts);
     }
+
+    (displacements, slots)
 }
 
-struct EntityTypeFromStr(String);
+/// Texture coordinates of a sprite, see `UvSprite`.
+#[derive(Deserialize)]
+struct AtlasSprite {
+    uvs: [[f32; 2]; 4],
+    aspect: f32,
+}
 
-impl EntityTypeFromStr {
+fn name_to_string(name: &str) -> &str {
+    name.trim_start_matches('_')
+}
+
+struct EntityTypeAsStr(String);
+
+impl EntityTypeAsStr {
     pub fn new(name: String) -> Self {
         Self(name)
     }
 }
 
-impl quote::ToTokens for EntityTypeFromStr {
+impl quote::ToTokens for EntityTypeAsStr {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let name = name_to_string(&self.0);
         let ident = string_to_ident(&self.0);
 
         let ts: proc_macro2::TokenStream = {
             quote! {
-               #name => Self::#ident
+               Self::#ident => #name
             }
         }
         .into();
@@ -1112,6 +1979,14 @@ impl quote::ToTokens for Entity {
             .unwrap_or_default();
 
         let armaments = &self.armaments;
+        let armament_groups = self.armament_groups.iter().map(|(name, indices)| {
+            quote! {
+                ArmamentGroup{
+                    name: #name,
+                    armaments: &[#(#indices),*],
+                }
+            }
+        });
         let turrets = &self.turrets;
         let exhausts = &self.exhausts;
 
@@ -1120,6 +1995,9 @@ impl quote::ToTokens for Entity {
         let range = self.range.unwrap_or_default();
         let position_forward = self.position_forward.unwrap_or_default();
         let position_side = self.position_side.unwrap_or_default();
+        let fire_sound = quote_option(self.fire_sound.as_deref());
+        let explosion = quote_option(self.explosion.as_deref());
+        let wake_scale = self.wake_scale.unwrap_or(1.0);
 
         let ts: proc_macro2::TokenStream = {
             quote! {
@@ -1156,8 +2034,14 @@ impl quote::ToTokens for Entity {
                         }
                     },
                     armaments: &[#(#armaments),*],
+                    armament_groups: &[#(#armament_groups),*],
                     turrets: &[#(#turrets),*],
                     exhausts: &[#(#exhausts),*],
+                    fx: EntityFx{
+                        fire_sound: #fire_sound,
+                        explosion: #explosion,
+                        wake_scale: #wake_scale,
+                    },
                     label: #label,
                     link: #link,
                     range: #range,
@@ -1182,6 +2066,7 @@ impl quote::ToTokens for Armament {
         let position_side = self.position_side.unwrap_or_default();
         let angle = self.angle.unwrap_or_default().0;
         let turret = quote_option(self.turret);
+        let group = self.group_index;
 
         let ts: proc_macro2::TokenStream = {
             quote! {
@@ -1194,6 +2079,7 @@ impl quote::ToTokens for Armament {
                     position_side: #position_side,
                     angle: Angle(#angle),
                     turret: #turret,
+                    group: #group,
                 }
             }
         }
@@ -1277,3 +2163,120 @@ fn quote_option<TT: ToTokens>(opt: Option<TT>) -> proc_macro2::TokenStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::entity_type::Turret;
+    use common_util::angle::Angle;
+
+    fn deg(degrees: f32) -> Angle {
+        Angle::from_degrees(degrees)
+    }
+
+    fn turret(angle: f32, azimuth_f: f32, azimuth_b: f32) -> Turret {
+        let mut turrets = Turret {
+            angle: Some(deg(angle)),
+            azimuth_f: Some(deg(azimuth_f)),
+            azimuth_b: Some(deg(azimuth_b)),
+            ..Turret::default()
+        }
+        .expand();
+        assert_eq!(turrets.len(), 1);
+        turrets.pop().unwrap()
+    }
+
+    #[test]
+    fn azimuth_boundaries() {
+        let turret = turret(0.0, 30.0, 40.0);
+
+        // Front limits are exclusive, so aiming exactly at them is allowed.
+        assert!(turret.within_azimuth(deg(30.0)));
+        assert!(turret.within_azimuth(deg(-30.0)));
+        assert!(!turret.within_azimuth(deg(29.0)));
+        assert!(!turret.within_azimuth(deg(-29.0)));
+        assert!(!turret.within_azimuth(deg(0.0)));
+
+        // Same for back limits.
+        assert!(turret.within_azimuth(deg(140.0)));
+        assert!(turret.within_azimuth(deg(-140.0)));
+        assert!(!turret.within_azimuth(deg(141.0)));
+        assert!(!turret.within_azimuth(deg(-141.0)));
+        assert!(!turret.within_azimuth(deg(180.0)));
+
+        // Broadside is unrestricted.
+        assert!(turret.within_azimuth(deg(90.0)));
+        assert!(turret.within_azimuth(deg(-90.0)));
+    }
+
+    #[test]
+    fn azimuth_wrap_around() {
+        // Facing almost backwards, so the front restriction straddles ±180°, and the back
+        // restriction straddles 0°. Limits are approached from inside, as rotated limits are
+        // subject to fixed point rounding.
+        let turret = turret(170.0, 30.0, 40.0);
+
+        assert!(!turret.within_azimuth(deg(179.0)));
+        assert!(!turret.within_azimuth(deg(-179.0)));
+        assert!(!turret.within_azimuth(deg(-170.0)));
+        assert!(!turret.within_azimuth(deg(-161.0)));
+        assert!(turret.within_azimuth(deg(-159.0)));
+        assert!(turret.within_azimuth(deg(139.0)));
+        assert!(!turret.within_azimuth(deg(141.0)));
+
+        assert!(!turret.within_azimuth(deg(0.0)));
+        assert!(!turret.within_azimuth(deg(-49.0)));
+        assert!(turret.within_azimuth(deg(-51.0)));
+        assert!(!turret.within_azimuth(deg(29.0)));
+        assert!(turret.within_azimuth(deg(31.0)));
+
+        assert!(turret.within_azimuth(deg(90.0)));
+        assert!(turret.within_azimuth(deg(-90.0)));
+    }
+
+    #[test]
+    fn azimuth_symmetrical() {
+        let turrets = Turret {
+            angle: Some(deg(20.0)),
+            azimuth_fl: Some(deg(10.0)),
+            azimuth_fr: Some(deg(60.0)),
+            position_side: Some(5.0),
+            symmetrical: true,
+            ..Turret::default()
+        }
+        .expand();
+        assert_eq!(turrets.len(), 2);
+        let (left, right) = (&turrets[0], &turrets[1]);
+
+        // Mirroring swaps left and right limits, as well as negating the angle. Limits are
+        // approached from inside, as rotated limits are subject to fixed point rounding.
+        assert!(left.within_azimuth(deg(31.0)));
+        assert!(!left.within_azimuth(deg(-30.0)));
+        assert!(!right.within_azimuth(deg(30.0)));
+        assert!(right.within_azimuth(deg(-31.0)));
+        assert!(left.within_azimuth(deg(-41.0)));
+        assert!(right.within_azimuth(deg(41.0)));
+    }
+
+    #[test]
+    fn azimuth_excludes_everything() {
+        assert!(turret(0.0, 170.0, 0.0).can_aim(Angle::ZERO));
+        assert!(turret(90.0, 170.0, 5.0).can_aim(Angle::ZERO));
+        assert!(!turret(0.0, 179.0, 179.0).can_aim(Angle::ZERO));
+        assert!(!turret(-135.0, 179.0, 179.0).can_aim(Angle::ZERO));
+    }
+
+    #[test]
+    fn azimuth_excludes_armament() {
+        // Stowed facing forward, within the restriction, like a torpedo mount.
+        let mount = turret(0.0, 45.0, 45.0);
+        assert!(mount.can_aim(Angle::ZERO));
+        assert!(mount.can_aim(deg(180.0)));
+
+        // Only aims within 10° of forward, so an armament facing backwards never fires out.
+        let narrow = turret(0.0, 0.0, 170.0);
+        assert!(narrow.can_aim(Angle::ZERO));
+        assert!(narrow.can_aim(deg(5.0)));
+        assert!(!narrow.can_aim(deg(180.0)));
+        assert!(!narrow.can_aim(deg(90.0)));
+    }
+}
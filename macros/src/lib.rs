@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+#![feature(proc_macro_diagnostic)]
 #![feature(proc_macro_span)]
 #![feature(let_else)]
 
@@ -10,8 +11,17 @@ use proc_macro::TokenStream;
 
 #[proc_macro_derive(
     EntityTypeData,
-    attributes(info, entity, size, offset, props, sensors, armament, turret, exhaust)
+    attributes(
+        data, template, wire_id, info, entity, size, offset, props, sensors, armament, turret,
+        exhaust, fx
+    )
 )]
 pub fn entity_type_data(input: TokenStream) -> TokenStream {
     crate::entity_type::derive_entity_type(input)
 }
+
+/// Invoked by the `entity_sprites!` macro that `EntityTypeData` generates.
+#[proc_macro]
+pub fn entity_sprites(input: TokenStream) -> TokenStream {
+    crate::entity_type::entity_sprites(input)
+}
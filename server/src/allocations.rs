@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+thread_local! {
+    /// Whether allocations made by the current thread are counted.
+    static COUNTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Allocations made by counted threads so far.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the system allocator, counting allocations (including reallocations) made by threads
+/// that called [`count_allocations`], so that tests can enforce an allocation budget. Other
+/// threads, such as those running other tests, aren't counted.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn count() {
+        // Ignore allocations while the thread is being torn down.
+        if COUNTED.try_with(Cell::get).unwrap_or(false) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::count();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::count();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::count();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Counts allocations made by the current thread from now on. Call it from every thread that
+/// does the work being measured (e.g. worker threads, when updating in parallel).
+pub fn count_allocations() {
+    COUNTED.with(|counted| counted.set(true));
+}
+
+/// Returns the number of allocations made by counted threads so far.
+pub fn allocations() -> usize {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
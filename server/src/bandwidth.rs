@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+/// Accounts for the bandwidth used by updates to one client, and decides how much to reduce the
+/// detail of future updates.
+#[derive(Debug, Default)]
+pub struct Bandwidth {
+    /// Moving average of estimated bytes sent per second.
+    bytes_per_second: f32,
+    /// From 0 (full detail) to 1 (minimum detail).
+    reduction: f32,
+}
+
+impl Bandwidth {
+    /// Bytes per second that each client should be able to receive.
+    const BUDGET: f32 = 48.0 * 1024.0;
+    /// Approximate size of one contact, for estimating the size of an update.
+    pub const CONTACT_BYTES: usize = 24;
+    /// How quickly the moving average follows the latest update, per update.
+    const SMOOTHING: f32 = 0.1;
+
+    /// Records the estimated size of an update, sent `delta_seconds` after the previous one, and
+    /// whether the client recently reported congestion, adjusting the reduction in detail.
+    pub fn record(&mut self, bytes: usize, delta_seconds: f32, congested: bool) {
+        let rate = bytes as f32 / delta_seconds;
+        self.bytes_per_second += (rate - self.bytes_per_second) * Self::SMOOTHING;
+
+        if congested || self.bytes_per_second > Self::BUDGET {
+            // Back off quickly.
+            self.reduction = (self.reduction + 0.1).min(1.0);
+        } else {
+            // Recover slowly.
+            self.reduction = (self.reduction - 0.01).max(0.0);
+        }
+    }
+
+    /// Returns how much to reduce detail, from 0 (full detail) to 1 (minimum detail).
+    pub fn reduction(&self) -> f32 {
+        self.reduction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bandwidth::Bandwidth;
+
+    #[test]
+    fn reduction() {
+        let mut bandwidth = Bandwidth::default();
+        assert_eq!(bandwidth.reduction(), 0.0);
+
+        // Within budget.
+        for _ in 0..100 {
+            bandwidth.record(1000, 0.1, false);
+        }
+        assert_eq!(bandwidth.reduction(), 0.0);
+
+        // Congested client.
+        for _ in 0..5 {
+            bandwidth.record(1000, 0.1, true);
+        }
+        assert!(bandwidth.reduction() > 0.4);
+
+        // Recovers.
+        for _ in 0..100 {
+            bandwidth.record(1000, 0.1, false);
+        }
+        assert_eq!(bandwidth.reduction(), 0.0);
+
+        // Over budget.
+        for _ in 0..100 {
+            bandwidth.record(10000, 0.1, false);
+        }
+        assert_eq!(bandwidth.reduction(), 1.0);
+    }
+}
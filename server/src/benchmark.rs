@@ -0,0 +1,218 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::ticks::Ticks;
+use log::warn;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// Measures the time spent in each phase of world updates, so that operators can see the
+/// effect of parallelism (the `rayon` feature) on each phase, and be warned when updates
+/// take longer than their budget.
+#[derive(Debug, Default)]
+pub struct Benchmark {
+    /// Phases in order of first use.
+    phases: Vec<Phase>,
+    /// Configured budgets, by phase name. [`Self::UPDATE`] is the budget of a whole update.
+    budgets: Vec<(String, Duration)>,
+    /// Duration of each update since the last report.
+    updates: Vec<Duration>,
+    /// Number of updates since the last report that exceeded their budget.
+    overruns: u32,
+}
+
+#[derive(Debug)]
+struct Phase {
+    name: &'static str,
+    budget: Option<Duration>,
+    /// Duration so far in the current update.
+    current: Duration,
+    /// Duration in each update since the last report.
+    samples: Vec<Duration>,
+}
+
+impl Benchmark {
+    /// Name of the budget of a whole update.
+    pub const UPDATE: &'static str = "update";
+
+    /// Parses budgets in milliseconds, e.g. `update=80,physics=40`. Phases without a budget only
+    /// have to fit within the update's budget, which defaults to one tick.
+    pub fn set_budgets(&mut self, budgets: &str) -> Result<(), &'static str> {
+        let mut parsed = Vec::new();
+        for budget in budgets.split(',').filter(|s| !s.is_empty()) {
+            let (name, millis) = budget.split_once('=').ok_or("expected name=millis")?;
+            let millis: f32 = millis.trim().parse().map_err(|_| "invalid milliseconds")?;
+            if !(millis.is_finite() && millis > 0.0) {
+                return Err("milliseconds must be positive");
+            }
+            parsed.push((
+                name.trim().to_owned(),
+                Duration::from_secs_f32(millis * 0.001),
+            ));
+        }
+        self.budgets = parsed;
+        for phase in &mut self.phases {
+            phase.budget = Self::find_budget(&self.budgets, phase.name);
+        }
+        Ok(())
+    }
+
+    fn find_budget(budgets: &[(String, Duration)], name: &str) -> Option<Duration> {
+        budgets.iter().find(|(n, _)| n == name).map(|&(_, d)| d)
+    }
+
+    /// Records that a phase, which began at `start`, just ended.
+    pub fn record(&mut self, phase: &'static str, start: Instant) {
+        let elapsed = start.elapsed();
+        if let Some(existing) = self.phases.iter_mut().find(|p| p.name == phase) {
+            existing.current += elapsed;
+        } else {
+            self.phases.push(Phase {
+                name: phase,
+                budget: Self::find_budget(&self.budgets, phase),
+                current: elapsed,
+                samples: Vec::new(),
+            });
+        }
+    }
+
+    /// Marks the end of one update, warning if it exceeded its budget.
+    pub fn end_update(&mut self) {
+        let total: Duration = self.phases.iter().map(|p| p.current).sum();
+        let budget = Self::find_budget(&self.budgets, Self::UPDATE)
+            .unwrap_or_else(|| Ticks::ONE.to_duration());
+
+        if let Some((phase, over)) = self.overrun(total, budget) {
+            // Only warn once per report, to avoid flooding the log while overloaded.
+            if self.overruns == 0 {
+                warn!(
+                    "update overrun: total_ms={:.3} budget_ms={:.3} phase={} phase_ms={:.3} \
+                    over_ms={:.3}",
+                    total.as_secs_f64() * 1000.0,
+                    budget.as_secs_f64() * 1000.0,
+                    phase.name,
+                    phase.current.as_secs_f64() * 1000.0,
+                    over.as_secs_f64() * 1000.0,
+                );
+            }
+            self.overruns += 1;
+        }
+
+        for phase in &mut self.phases {
+            phase.samples.push(std::mem::take(&mut phase.current));
+        }
+        self.updates.push(total);
+    }
+
+    /// If the update or any phase exceeded its budget, returns the phase most responsible, and
+    /// by how much it exceeded its own budget (or the whole update's budget).
+    fn overrun(&self, total: Duration, budget: Duration) -> Option<(&Phase, Duration)> {
+        let worst_over_budget = self
+            .phases
+            .iter()
+            .filter_map(|p| Some((p, p.current.checked_sub(p.budget?)?)))
+            .filter(|(_, over)| !over.is_zero())
+            .max_by_key(|(_, over)| *over);
+
+        if worst_over_budget.is_some() {
+            worst_over_budget
+        } else if total > budget {
+            // No phase exceeded its own budget, so blame the slowest.
+            self.phases
+                .iter()
+                .max_by_key(|p| p.current)
+                .map(|p| (p, total - budget))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the 50th, 95th, and 99th percentile duration of each phase and the whole update
+    /// since the last report, if there were any updates, and starts over.
+    pub fn take_report(&mut self) -> Option<String> {
+        if self.updates.is_empty() {
+            return None;
+        }
+        let mut report = String::new();
+        for phase in &mut self.phases {
+            Self::write_percentiles(&mut report, phase.name, &mut phase.samples);
+            phase.samples.clear();
+        }
+        Self::write_percentiles(&mut report, Self::UPDATE, &mut self.updates);
+        let _ = write!(report, ", overruns: {}", self.overruns);
+        self.updates.clear();
+        self.overruns = 0;
+        Some(report)
+    }
+
+    fn write_percentiles(report: &mut String, name: &str, samples: &mut [Duration]) {
+        if samples.is_empty() {
+            return;
+        }
+        samples.sort_unstable();
+        let percentile = |p: f32| {
+            let index = ((samples.len() - 1) as f32 * p).round() as usize;
+            samples[index].as_secs_f64() * 1000.0
+        };
+        if !report.is_empty() {
+            report.push_str(", ");
+        }
+        let _ = write!(
+            report,
+            "{}: p50 {:.3}ms p95 {:.3}ms p99 {:.3}ms",
+            name,
+            percentile(0.5),
+            percentile(0.95),
+            percentile(0.99)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::benchmark::Benchmark;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn report() {
+        let mut benchmark = Benchmark::default();
+        assert_eq!(benchmark.take_report(), None);
+
+        for _ in 0..2 {
+            let start = Instant::now();
+            benchmark.record("physics", start);
+            benchmark.record("physics_radius", start);
+            benchmark.record("physics", start);
+            benchmark.end_update();
+        }
+
+        let report = benchmark.take_report().unwrap();
+        assert!(report.starts_with("physics: p50 "), "{}", report);
+        assert!(report.contains(", physics_radius: p50 "), "{}", report);
+        assert!(report.contains(", update: p50 "), "{}", report);
+        assert!(report.ends_with(", overruns: 0"), "{}", report);
+        assert_eq!(benchmark.take_report(), None);
+    }
+
+    #[test]
+    fn budgets() {
+        let mut benchmark = Benchmark::default();
+        assert!(benchmark.set_budgets("update=").is_err());
+        assert!(benchmark.set_budgets("physics").is_err());
+        assert!(benchmark.set_budgets("update=-1").is_err());
+        benchmark.set_budgets("update=1000,physics=0.001").unwrap();
+
+        let start = Instant::now() - Duration::from_millis(1);
+        benchmark.record("physics", start);
+        benchmark.record("physics_radius", Instant::now());
+        let (phase, over) = benchmark
+            .overrun(Duration::from_millis(1), Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(phase.name, "physics");
+        assert!(!over.is_zero());
+        benchmark.end_update();
+
+        let report = benchmark.take_report().unwrap();
+        assert!(report.ends_with(", overruns: 1"), "{}", report);
+    }
+}
@@ -0,0 +1,198 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::Entity;
+use common::altitude::Altitude;
+use common::angle::Angle;
+use common::entity::EntitySubKind;
+use common::velocity::Velocity;
+use glam::Vec2;
+use rand::{thread_rng, Rng};
+
+/// Altitude units per second that unguided weapons descend at (see `World::physics`).
+const FALL_RATE: f32 = 3.0;
+/// Meters per second squared that a released bomb, having no thrust, slows down at.
+const DRAG: f32 = 5.0;
+/// Fastest a bomb may leave its aircraft, in meters per second.
+pub const MAX_DROP_SPEED: f32 = 50.0;
+/// Meters a glide bomb travels per meter of altitude it loses.
+const GLIDE_RATIO: f32 = 12.0;
+/// Meters of error per meter of release altitude.
+const ALTITUDE_ERROR: f32 = 0.05;
+/// Meters of error per meter per second of target speed.
+const SPEED_ERROR: f32 = 0.5;
+
+/// Returns true if the sub kind is an unpowered bomb that falls from aircraft, as opposed to a
+/// glide bomb that flies to its target.
+pub fn is_ballistic(sub_kind: EntitySubKind) -> bool {
+    matches!(sub_kind, EntitySubKind::Mine | EntitySubKind::DepthCharge)
+}
+
+/// Altitude at which a falling bomb may first strike a target on the surface.
+fn strike_altitude(sub_kind: EntitySubKind) -> Altitude {
+    match sub_kind {
+        // Depth charges only hit once in the water.
+        EntitySubKind::DepthCharge => Altitude::ZERO,
+        _ => Altitude::SPECIAL_OVERLAP_MARGIN,
+    }
+}
+
+/// Seconds a bomb released at altitude takes to fall low enough to strike.
+pub fn fall_secs(sub_kind: EntitySubKind, altitude: Altitude) -> f32 {
+    (altitude - strike_altitude(sub_kind)).0.max(0) as f32 * (1.0 / FALL_RATE)
+}
+
+/// Horizontal meters a bomb released at speed travels within secs, slowed by drag.
+pub fn carry(speed: f32, secs: f32) -> f32 {
+    let secs = secs.min(speed * (1.0 / DRAG));
+    speed * secs - 0.5 * DRAG * secs.powi(2)
+}
+
+/// Inverse of `carry`: the release speed needed to travel distance within secs.
+fn release_speed(distance: f32, secs: f32) -> f32 {
+    if secs > 0.0 {
+        let speed = distance / secs + 0.5 * DRAG * secs;
+        if speed >= DRAG * secs {
+            // Still moving on impact.
+            return speed;
+        }
+    }
+    // Comes to a stop before impact.
+    (2.0 * DRAG * distance).sqrt()
+}
+
+/// Horizontal meters a glide bomb released at altitude can travel.
+pub fn glide_range(altitude: Altitude) -> f32 {
+    altitude.to_meters().max(0.0) * GLIDE_RATIO
+}
+
+/// Radius, in meters, by which a bomb may miss its aim, growing with release altitude and target
+/// speed.
+pub fn release_error(altitude: Altitude, target_speed: f32) -> f32 {
+    altitude.to_meters().max(0.0) * ALTITUDE_ERROR + target_speed.abs() * SPEED_ERROR
+}
+
+/// Returns aim, randomly displaced by up to error meters.
+pub fn scatter(aim: Vec2, error: f32) -> Vec2 {
+    let mut rng = thread_rng();
+    aim + rng.gen::<Angle>().to_vec() * (error * rng.gen::<f32>())
+}
+
+/// Returns true if the bomber is approaching or overflying aim, as opposed to having passed it.
+pub fn approaching(bomber: &Entity, aim: Vec2) -> bool {
+    let diff = aim - bomber.transform.position;
+    diff.length_squared() <= bomber.data().radius.powi(2)
+        || (Angle::from(diff) - bomber.transform.direction).abs() <= Angle::from_degrees(45.0)
+}
+
+/// Where an entity will be after secs, if it holds its course.
+fn predict(entity: &Entity, secs: f32) -> Vec2 {
+    entity.transform.position
+        + entity.transform.direction.to_vec() * (entity.transform.velocity.to_mps() * secs)
+}
+
+/// If releasing a ballistic bomb now would land it on the target's predicted position, returns
+/// where to aim it, including release error.
+pub fn release(bomber: &Entity, target: &Entity, sub_kind: EntitySubKind) -> Option<Vec2> {
+    let secs = fall_secs(sub_kind, bomber.altitude);
+    let aim = predict(target, secs);
+    if !approaching(bomber, aim) {
+        return None;
+    }
+
+    let speed = bomber
+        .transform
+        .velocity
+        .to_mps()
+        .clamp(0.0, MAX_DROP_SPEED);
+    let impact =
+        bomber.transform.position + bomber.transform.direction.to_vec() * carry(speed, secs);
+    if impact.distance_squared(aim) > target.data().radius.powi(2) {
+        return None;
+    }
+
+    let error = release_error(bomber.altitude, target.transform.velocity.to_mps());
+    Some(scatter(aim, error))
+}
+
+/// Throws a freshly released ballistic bomb such that it falls onto aim, as far as its release
+/// speed allows.
+pub fn aim_ballistic(bomb: &mut Entity, aim: Vec2) {
+    let diff = aim - bomb.transform.position;
+    let secs = fall_secs(bomb.data().sub_kind, bomb.altitude);
+
+    if diff != Vec2::ZERO {
+        bomb.transform.direction = Angle::from(diff);
+    }
+    bomb.guidance.direction_target = bomb.transform.direction;
+    bomb.transform.velocity =
+        Velocity::from_mps(release_speed(diff.length(), secs).min(MAX_DROP_SPEED));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bombing::*;
+    use common::entity::EntityType;
+
+    #[test]
+    fn test_carry() {
+        for (distance, secs) in [(0.0, 0.0), (50.0, 2.0), (100.0, 4.0), (10.0, 8.0)] {
+            let speed = release_speed(distance, secs);
+            assert!(
+                (carry(speed, secs) - distance).abs() < 0.01,
+                "{} {}",
+                distance,
+                secs
+            );
+        }
+        assert_eq!(carry(0.0, 5.0), 0.0);
+        assert_eq!(carry(MAX_DROP_SPEED, 1000.0), carry(MAX_DROP_SPEED, 10.0));
+    }
+
+    #[test]
+    fn test_release_error() {
+        assert_eq!(release_error(Altitude::ZERO, 0.0), 0.0);
+        assert!(release_error(Altitude(100), 0.0) > release_error(Altitude(20), 0.0));
+        assert!(release_error(Altitude(20), 15.0) > release_error(Altitude(20), 0.0));
+    }
+
+    #[test]
+    fn test_release() {
+        let mut bomber = Entity::new(EntityType::Type96, None);
+        bomber.altitude = Altitude(47);
+        bomber.transform.velocity = Velocity::from_mps(40.0);
+        let mut target = Entity::new(EntityType::Zubr, None);
+
+        // Overflying.
+        assert!(release(&bomber, &target, EntitySubKind::Mine).is_some());
+
+        // Too far ahead.
+        target.transform.position = Vec2::new(500.0, 0.0);
+        assert!(release(&bomber, &target, EntitySubKind::Mine).is_none());
+
+        // Already passed.
+        target.transform.position = Vec2::new(-60.0, 0.0);
+        assert!(release(&bomber, &target, EntitySubKind::Mine).is_none());
+
+        // From higher up, bombs must be released well before reaching the target.
+        bomber.altitude = Altitude(100);
+        let ahead = carry(40.0, fall_secs(EntitySubKind::Mine, bomber.altitude));
+        target.transform.position = Vec2::new(ahead, 0.0);
+        assert!(release(&bomber, &target, EntitySubKind::Mine).is_some());
+        target.transform.position = Vec2::ZERO;
+        assert!(release(&bomber, &target, EntitySubKind::Mine).is_none());
+    }
+
+    #[test]
+    fn test_aim_ballistic() {
+        let mut bomb = Entity::new(EntityType::Mk82, None);
+        bomb.altitude = Altitude(100);
+        let aim = Vec2::new(0.0, 80.0);
+        aim_ballistic(&mut bomb, aim);
+
+        let secs = fall_secs(EntitySubKind::Mine, bomb.altitude);
+        let landing = bomb.transform.position
+            + bomb.transform.direction.to_vec() * carry(bomb.transform.velocity.to_mps(), secs);
+        assert!(landing.distance(aim) < 2.0, "{:?}", landing);
+    }
+}
@@ -4,10 +4,13 @@
 use crate::complete_ref::CompleteRef;
 use crate::contact_ref::ContactRef;
 use crate::server::Server;
-use common::altitude::Altitude;
+use crate::world_pirates::PirateRaid;
+use common::altitude::{Altitude, DepthBand};
 use common::angle::Angle;
 use common::complete::CompleteTrait;
 use common::contact::ContactTrait;
+use common::cosmetic::Cosmetics;
+use common::spawn_region::SpawnRegion;
 use common::entity::*;
 use common::guidance::Guidance;
 use common::protocol::*;
@@ -22,6 +25,7 @@ use rand::rngs::ThreadRng;
 use rand::seq::IteratorRandom;
 use rand::{thread_rng, Rng};
 use std::sync::Arc;
+use std::time::Instant;
 
 /// Bot implements a ship-controlling AI that is, in many ways, equivalent to a player.
 pub struct Bot {
@@ -67,6 +71,14 @@ impl Bot {
     /// This arbitrary value controls how chill the bots are. If too high, bots are trigger-happy
     /// maniacs, and the waters get filled with stray torpedoes.
     const MAX_AGGRESSION: f32 = 0.35;
+    /// Chance of pirates attacking, which don't share the restraint of ordinary bots.
+    const PIRATE_AGGRESSION: f32 = 0.6;
+    /// How much more strongly pirates are drawn to their prey than to loot.
+    const PIRATE_PURSUIT: f32 = 10.0;
+    /// Bots roam freely within this distance of the structure they patrol.
+    const PATROL_RADIUS: f32 = 300.0;
+    /// How strongly patrolling bots are drawn back to their structure.
+    const PATROL_PULL: f32 = 20.0;
 
     /// Returns true if there is land or border at the given position.
     fn is_land_or_border(pos: Vec2, terrain: &Terrain, world_radius: f32) -> bool {
@@ -77,11 +89,15 @@ impl Bot {
         terrain.sample(pos).unwrap_or(Altitude::MIN) >= terrain::SAND_LEVEL
     }
 
-    /// update processes a complete update and returns some command (or None to quit).
+    /// update processes a complete update and returns some command (or None to quit). Pirates
+    /// hunt real players' cargo boats, and leave other bots alone. Bots with a patrol post stay
+    /// near it.
     fn update<'a, U: 'a + CompleteTrait<'a>>(
         &mut self,
         mut update: U,
         player_id: PlayerId,
+        pirate: bool,
+        patrol: Option<Vec2>,
     ) -> BotAction<Command> {
         let mut rng = thread_rng();
 
@@ -137,6 +153,18 @@ impl Bot {
                 }
             }
 
+            if let Some(post) = patrol {
+                let delta_position = post - boat.transform().position;
+                let distance_squared = delta_position.length_squared();
+                if distance_squared > Self::PATROL_RADIUS.powi(2) {
+                    attract(
+                        &mut movement,
+                        delta_position * Self::PATROL_PULL,
+                        distance_squared,
+                    );
+                }
+            }
+
             let mut closest_enemy: Option<(U::Contact, f32)> = None;
 
             // Scan sensor contacts to help make decisions.
@@ -151,6 +179,19 @@ impl Bot {
                     let distance_squared = delta_position.length_squared();
 
                     let friendly = contact.player_id() == Some(player_id);
+                    let bot = contact.player_id().map(|id| id.is_bot()).unwrap_or(false);
+                    let prey = pirate
+                        && !bot
+                        && contact_data.kind == EntityKind::Boat
+                        && PirateRaid::is_cargo(contact_data.sub_kind);
+
+                    if prey {
+                        attract(
+                            &mut movement,
+                            delta_position * Self::PIRATE_PURSUIT,
+                            distance_squared,
+                        );
+                    }
 
                     if contact_data.kind == EntityKind::Collectible {
                         attract(&mut movement, delta_position, distance_squared);
@@ -172,13 +213,18 @@ impl Bot {
                         }
                     } else if match contact_data.kind {
                         // Don't kill smol/peaceful boats unless they get too close.
+                        EntityKind::Boat if pirate => {
+                            prey
+                                || distance_squared < 1.5 * data.radius.powi(2)
+                                || (!bot && health_percent < 1.0 / 3.0)
+                        }
                         EntityKind::Boat => {
                             (contact_data.level + 1 >= data.level
                                 && !matches!(
                                     contact_data.sub_kind,
                                     EntitySubKind::Dredger | EntitySubKind::Icebreaker | EntitySubKind::Passenger
                                 ))
-                                || contact.player_id().map(|id| id.is_bot()).unwrap_or(false)
+                                || bot
                                 || distance_squared < 1.5 * data.radius.powi(2)
                                 || health_percent < 1.0 / 3.0
                         }
@@ -223,7 +269,16 @@ impl Bot {
                     if !matches!(
                         armament_entity_data.kind,
                         EntityKind::Weapon | EntityKind::Aircraft | EntityKind::Decoy
-                    ) {
+                    ) || armament.entity_type == EntityType::StarShell
+                    {
+                        // Star shells only light up the night.
+                        continue;
+                    }
+
+                    if armament_entity_data.sub_kind == EntitySubKind::Missile
+                        && boat.altitude().depth_band() == Some(DepthBand::Deep)
+                    {
+                        // Must come up to periscope depth first.
                         continue;
                     }
 
@@ -329,25 +384,43 @@ impl Bot {
                 false
             };
 
+            let aggression = if pirate {
+                Self::PIRATE_AGGRESSION
+            } else {
+                self.aggression
+            };
+
             let mut ret = Command::Control(Control {
                 guidance: Some(Guidance {
                     direction_target: Angle::from(movement) + self.steer_bias,
                     velocity_target: data.speed * 0.8,
                 }),
                 submerge: self.was_submerging,
+                periscope: false,
                 aim_target: best_firing_solution.map(|solution| solution.1 + self.aim_bias),
                 active: health_percent >= 0.5,
                 fire: best_firing_solution
-                    .filter(|_| rng.gen_bool(self.aggression as f64))
+                    .filter(|_| rng.gen_bool(aggression as f64))
                     .map(|sol| Fire {
                         armament_index: sol.0,
                     }),
                 pay: None,
                 hint: None,
                 horn: false,
+                repair_crew: health_percent < 0.5,
+                searchlight: false,
+                emcon: false,
+                throttle: None,
+                cruise: false,
+                collision_assist: false,
+                turret_locks: Vec::new(),
             });
 
-            if rng.gen_bool(self.aggression as f64) && data.level < self.level_ambition {
+            // Pirates keep the boats of their squadron.
+            if !pirate
+                && rng.gen_bool(self.aggression as f64)
+                && data.level < self.level_ambition
+            {
                 // Upgrade, if possible.
                 if let Some(entity_type) = boat_type
                     .upgrade_options(update.score(), true, false)
@@ -366,6 +439,8 @@ impl Bot {
                 entity_type: EntityType::spawn_options(0, true, false)
                     .choose(&mut rng)
                     .expect("there must be at least one entity type to spawn as"),
+                cosmetics: Cosmetics::default(),
+                region: SpawnRegion::Fleet,
             }))
         } else {
             self.has_waited_one_tick = true;
@@ -375,14 +450,30 @@ impl Bot {
 }
 
 impl game_server::game_service::Bot<Server> for Bot {
-    type Input<'a> = CompleteRef<'a, impl Iterator<Item = ContactRef<'a>>>;
+    /// The bot's view of the world, whether it is a pirate, and the structure it patrols.
+    type Input<'a> = (
+        CompleteRef<'a, impl Iterator<Item = ContactRef<'a>>>,
+        bool,
+        Option<Vec2>,
+    );
 
     fn get_input<'a>(
         server: &'a Server,
         player: &'a Arc<PlayerTuple<Server>>,
         _players: &'a PlayerRepo<Server>,
     ) -> Self::Input<'a> {
-        server.world.get_player_complete(player)
+        let (pirate, patrol) = {
+            let data = &player.borrow_player().data;
+            (data.pirate, data.patrol)
+        };
+        let now = Instant::now();
+        (
+            server.world.get_player_complete(player, None, None),
+            pirate,
+            patrol
+                .filter(|&(_, until)| until > now)
+                .map(|(post, _)| post),
+        )
     }
 
     fn update(
@@ -391,6 +482,7 @@ impl game_server::game_service::Bot<Server> for Bot {
         player_id: PlayerId,
         _players: &PlayerRepo<Server>,
     ) -> BotAction<<Server as GameArenaService>::GameRequest> {
-        self.update(update, player_id)
+        let (update, pirate, patrol) = update;
+        self.update(update, player_id, pirate, patrol)
     }
 }
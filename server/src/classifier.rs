@@ -0,0 +1,259 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::Entity;
+use common::entity::{EntityId, EntityType};
+use common::ghost::Ghost;
+use common::ticks::Ticks;
+use common::transform::Transform;
+use std::collections::HashMap;
+
+/// How much an observer knows about what a contact is, from least to most.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Classification {
+    /// Only detected, as a blip.
+    Unknown,
+    /// The sub-kind (e.g. submarine) is known, but not the exact type.
+    SubKind,
+    /// The exact type is known.
+    Type,
+}
+
+impl Classification {
+    /// Returns the most that can be known about a contact sensed with `uncertainty`, however long
+    /// it is tracked.
+    pub fn limit(uncertainty: f32) -> Self {
+        if uncertainty < Classifier::IDENTIFIED {
+            Self::Type
+        } else if uncertainty < Classifier::CLASSIFIED {
+            Self::SubKind
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Track {
+    /// Progress towards identifying the contact, from 0 to 1.
+    progress: f32,
+    /// How well the contact was classified, as of when it was last tracked.
+    classification: Classification,
+    /// Type, and transform, of the contact when it was last tracked.
+    entity_type: EntityType,
+    transform: Transform,
+    /// Tick in which the contact was last tracked.
+    tick: Ticks,
+    /// Update in which the contact was last tracked.
+    used: u32,
+}
+
+impl Track {
+    /// Returns what is known about the contact, now that it was lost.
+    fn ghost(&self, id: EntityId) -> Ghost {
+        Ghost {
+            id,
+            entity_type: (self.classification == Classification::Type).then_some(self.entity_type),
+            transform: self.transform,
+            tick: self.tick,
+        }
+    }
+}
+
+/// Tracks how well one client's sensors classified each boat they detect. Boats start as unknown
+/// blips, and are classified and then identified the longer, closer, and more clearly they are
+/// tracked. Boats that are lost leave behind a [`Ghost`].
+#[derive(Debug, Default)]
+pub struct Classifier {
+    tracks: HashMap<EntityId, Track>,
+    /// Number of updates.
+    updates: u32,
+    /// Tick of the current update.
+    tick: Ticks,
+}
+
+impl Classifier {
+    /// Uncertainty below which contacts can be identified, such that their type is known.
+    pub const IDENTIFIED: f32 = 0.5;
+    /// Uncertainty below which contacts can be classified, such that their sub-kind is known.
+    pub const CLASSIFIED: f32 = 0.75;
+    /// Seconds of tracking a contact, with no uncertainty, to identify it. Takes longer the more
+    /// uncertain it is.
+    const IDENTIFY_SECS: f32 = 3.0;
+    /// Progress at which contacts are classified.
+    const CLASSIFY_PROGRESS: f32 = 0.4;
+
+    /// Must be called before each update, in tick `tick`. Forgets contacts that weren't tracked
+    /// in the previous update (e.g. went out of range), so they must be classified again, and
+    /// returns ghosts of them.
+    pub fn begin_update(&mut self, tick: Ticks) -> Vec<Ghost> {
+        let previous = self.updates;
+        let mut ghosts = Vec::new();
+        self.tracks.retain(|&id, track| {
+            let tracked = track.used == previous;
+            if !tracked {
+                ghosts.push(track.ghost(id));
+            }
+            tracked
+        });
+        self.updates += 1;
+        self.tick = tick;
+        ghosts
+    }
+
+    /// Tracks a contact for another update, returning how well it is classified. Contacts may be
+    /// `identified` regardless, e.g. because they are very close.
+    pub fn classify(
+        &mut self,
+        entity: &Entity,
+        uncertainty: f32,
+        identified: bool,
+    ) -> Classification {
+        let track = self.tracks.entry(entity.id).or_insert(Track {
+            progress: 0.0,
+            classification: Classification::Unknown,
+            entity_type: entity.entity_type,
+            transform: entity.transform,
+            tick: self.tick,
+            used: self.updates,
+        });
+        if identified {
+            track.progress = track.progress.max(1.0);
+        } else {
+            track.progress +=
+                (1.0 - uncertainty).max(0.0) * (Ticks::PERIOD_SECS / Self::IDENTIFY_SECS);
+        }
+        track.entity_type = entity.entity_type;
+        track.transform = entity.transform;
+        track.tick = self.tick;
+        track.used = self.updates;
+
+        let classification = if track.progress >= 1.0 {
+            Classification::Type
+        } else if track.progress >= Self::CLASSIFY_PROGRESS {
+            Classification::SubKind
+        } else {
+            Classification::Unknown
+        };
+        track.classification = if identified {
+            classification
+        } else {
+            classification.min(Classification::limit(uncertainty))
+        };
+        track.classification
+    }
+
+    /// Returns the ids of contacts identified in the current update.
+    pub fn identified(&self) -> impl Iterator<Item = EntityId> + '_ {
+        let updates = self.updates;
+        self.tracks
+            .iter()
+            .filter(move |(_, track)| {
+                track.used == updates && track.classification == Classification::Type
+            })
+            .map(|(&id, _)| id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::classifier::{Classification, Classifier};
+    use crate::entity::Entity;
+    use common::entity::{EntityId, EntityType};
+    use common::ticks::Ticks;
+    use glam::vec2;
+
+    fn boat() -> Entity {
+        let mut boat = Entity::new(EntityType::G5, None);
+        boat.id = EntityId::new(1).unwrap();
+        boat
+    }
+
+    /// Returns how many updates it takes to track a contact up to `classification`.
+    fn updates_to(classification: Classification, uncertainty: f32) -> usize {
+        let mut classifier = Classifier::default();
+        let boat = boat();
+        (1..1000)
+            .find(|_| {
+                classifier.begin_update(Ticks::ZERO);
+                classifier.classify(&boat, uncertainty, false) >= classification
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn classify() {
+        // Clear contacts are classified, and then identified, after being tracked for a while.
+        let classify = updates_to(Classification::SubKind, 0.0);
+        let identify = updates_to(Classification::Type, 0.0);
+        assert!(classify > 1);
+        assert!(identify > classify);
+        assert!(identify as f32 * Ticks::PERIOD_SECS <= Classifier::IDENTIFY_SECS + 0.5);
+
+        // Less clear contacts take longer.
+        assert!(updates_to(Classification::SubKind, 0.6) > classify);
+
+        // Contacts that are too unclear are never identified, unless close.
+        let mut classifier = Classifier::default();
+        let boat = boat();
+        for _ in 0..1000 {
+            classifier.begin_update(Ticks::ZERO);
+            assert_ne!(classifier.classify(&boat, 0.6, false), Classification::Type);
+        }
+        classifier.begin_update(Ticks::ZERO);
+        assert_eq!(classifier.classify(&boat, 0.6, true), Classification::Type);
+
+        // Losing track of a contact means classifying it again.
+        assert!(classifier.begin_update(Ticks::ZERO).is_empty());
+        assert_eq!(classifier.begin_update(Ticks::ZERO).len(), 1);
+        assert_eq!(
+            classifier.classify(&boat, 0.0, false),
+            Classification::Unknown
+        );
+    }
+
+    #[test]
+    fn identified() {
+        let mut classifier = Classifier::default();
+        let boat = boat();
+        classifier.begin_update(Ticks::ZERO);
+        classifier.classify(&boat, 0.0, false);
+        assert_eq!(classifier.identified().count(), 0);
+
+        classifier.begin_update(Ticks::ZERO);
+        classifier.classify(&boat, 0.0, true);
+        assert_eq!(classifier.identified().collect::<Vec<_>>(), vec![boat.id]);
+
+        // Only while tracked.
+        classifier.begin_update(Ticks::ZERO);
+        assert_eq!(classifier.identified().count(), 0);
+    }
+
+    #[test]
+    fn ghost() {
+        let mut classifier = Classifier::default();
+        let mut boat = boat();
+        let tick = Ticks::from_repr(5);
+        classifier.begin_update(tick);
+        classifier.classify(&boat, 0.0, true);
+        boat.transform.position = vec2(10.0, 20.0);
+        classifier.begin_update(tick.next());
+        classifier.classify(&boat, 0.0, false);
+
+        // Lost contacts leave behind a ghost, where they were last tracked.
+        assert!(classifier.begin_update(Ticks::from_repr(10)).is_empty());
+        let ghosts = classifier.begin_update(Ticks::from_repr(11));
+        assert_eq!(ghosts.len(), 1);
+        let ghost = ghosts[0];
+        assert_eq!(ghost.id, boat.id);
+        assert_eq!(ghost.entity_type, Some(boat.entity_type));
+        assert_eq!(ghost.transform.position, vec2(10.0, 20.0));
+        assert_eq!(ghost.tick, tick.next());
+
+        // Unless identified, the type of the ghost isn't known.
+        classifier.begin_update(Ticks::ZERO);
+        classifier.classify(&boat, 0.0, false);
+        classifier.begin_update(Ticks::ZERO);
+        assert_eq!(classifier.begin_update(Ticks::ZERO)[0].entity_type, None);
+    }
+}
@@ -23,6 +23,42 @@ pub fn radius_collision(
     d2 <= r2
 }
 
+/// swept_circle_impact checks a point moving along a segment from start to end against a circle.
+/// If the point comes within radius of center, returns the fraction of the way along the segment
+/// at which it first does (its time of impact), and its closest approach to center.
+pub fn swept_circle_impact(
+    start: Vec2,
+    end: Vec2,
+    center: Vec2,
+    radius: f32,
+) -> Option<(f32, Vec2)> {
+    let delta = end - start;
+    let offset = start - center;
+    let length_squared = delta.length_squared();
+
+    let closest_t = if length_squared > 0.0 {
+        (-offset.dot(delta) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let closest = start + delta * closest_t;
+    if closest.distance_squared(center) > radius.powi(2) {
+        return None;
+    }
+
+    // Solve |offset + delta * t| = radius for the smaller t, which exists since closest is within
+    // radius (if start is within radius, t is 0).
+    let c = offset.length_squared() - radius.powi(2);
+    let t = if c <= 0.0 {
+        0.0
+    } else {
+        let half_b = offset.dot(delta);
+        (-half_b - (half_b.powi(2) - length_squared * c).max(0.0).sqrt()) / length_squared
+    };
+
+    Some((t.clamp(0.0, closest_t), closest))
+}
+
 /// sat_collision performs continuous rectangle-based separating axis theorem collision.
 pub fn sat_collision(
     mut transform: Transform,
@@ -130,3 +166,35 @@ fn sat_collision_half(
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::collision::swept_circle_impact;
+    use glam::Vec2;
+
+    #[test]
+    fn swept_circle() {
+        let center = Vec2::new(50.0, 0.0);
+        let start = Vec2::ZERO;
+        let end = Vec2::new(100.0, 0.0);
+
+        // Passes through the middle of the circle, entering halfway minus the radius.
+        let (t, closest) = swept_circle_impact(start, end, center, 10.0).unwrap();
+        assert!((t - 0.4).abs() < 0.001, "{}", t);
+        assert_eq!(closest, center);
+
+        // Passes beside the circle.
+        let offset = Vec2::new(0.0, 20.0);
+        assert_eq!(
+            swept_circle_impact(start + offset, end + offset, center, 10.0),
+            None
+        );
+
+        // Stops short of the circle.
+        assert_eq!(swept_circle_impact(start, end * 0.3, center, 10.0), None);
+
+        // Starts inside the circle.
+        let (t, _) = swept_circle_impact(center, end, center, 10.0).unwrap();
+        assert_eq!(t, 0.0);
+    }
+}
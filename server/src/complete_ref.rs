@@ -1,19 +1,25 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::bandwidth::Bandwidth;
 use crate::contact_ref::ContactRef;
 use crate::player::Status;
-use crate::server::Server;
+use crate::server::{ClientData, Server};
 use crate::world::World;
 use atomic_refcell::AtomicRef;
+use common::audio_event::AudioEvent;
 use common::complete::CompleteTrait;
 use common::contact::ContactTrait;
 use common::death_reason::DeathReason;
+use common::entity::EntityId;
+use common::intercept::Intercept;
 use common::protocol::Update;
+use common::summary::Summary;
 use common::terrain;
 use common::terrain::{ChunkSet, Terrain};
 use common::ticks::{Ticks, TicksRepr};
 use common::velocity::Velocity;
+use common_util::range::lerp;
 use game_server::player::PlayerData;
 use glam::Vec2;
 use std::ops::RangeInclusive;
@@ -45,15 +51,32 @@ impl<'a, I: Iterator<Item = ContactRef<'a>>> CompleteRef<'a, I> {
         }
     }
 
-    pub fn into_update(self, counter: Ticks, loaded_chunks: &mut ChunkSet) -> Update {
+    pub fn into_update(self, counter: Ticks, client_data: &mut ClientData) -> Update {
         let death_reason = if let Status::Dead { reason, .. } = &self.player.data.status {
             Some(reason.clone())
         } else {
             None
         };
 
+        // Only send the summary once per life, after it ends by dying or leaving (in which case
+        // it is sent upon returning). A default summary means there was no previous life.
+        let summary = if self.player.data.status.is_alive() {
+            client_data.summary_sent = false;
+            None
+        } else if client_data.summary_sent || self.player.data.summary == Summary::default() {
+            None
+        } else {
+            client_data.summary_sent = true;
+            Some(Box::new(self.player.data.summary.clone()))
+        };
+
+        let loaded_chunks = &mut client_data.loaded_chunks;
+        let coarse_chunks = &mut client_data.coarse_chunks;
+
         // Any updated chunks are now no longer loaded.
         let mut new_loaded_chunks = loaded_chunks.and(&self.world.terrain.updated.not());
+        // Coarse chunks can't be updated incrementally, so are resent if still nearby.
+        let mut new_coarse_chunks = coarse_chunks.and(&self.world.terrain.updated.not());
 
         // All chunks that are currently visible (on screen).
         // Uses a rect instead of a circle because that is what the client renders,
@@ -69,6 +92,20 @@ impl<'a, I: Iterator<Item = ContactRef<'a>>> CompleteRef<'a, I> {
         // The chunks that will be loaded following this message.
         new_loaded_chunks = visible.or(&new_loaded_chunks);
 
+        // Reduce detail for clients that are congested or over their bandwidth budget.
+        let reduction = client_data.bandwidth.reduction();
+
+        // Chunks just beyond the visible area, sent at a lower level of detail so that panning
+        // and zooming out don't reveal missing terrain. Skipped when reducing detail.
+        let nearby_dims = if reduction < 0.5 {
+            self.camera_dims * 2.0
+        } else {
+            Vec2::ZERO
+        };
+        let nearby = ChunkSet::new_rect(self.camera_pos, nearby_dims).and(&new_loaded_chunks.not());
+        let loading_coarse = nearby.and(&new_coarse_chunks.not());
+        new_coarse_chunks = nearby.or(&new_coarse_chunks.and(&new_loaded_chunks.not()));
+
         let terrain = loading
             .into_iter()
             .map(|id| {
@@ -81,40 +118,201 @@ impl<'a, I: Iterator<Item = ContactRef<'a>>> CompleteRef<'a, I> {
                     ),
                 )
             })
+            .chain(loading_coarse.into_iter().map(|id| {
+                (
+                    id,
+                    self.world
+                        .terrain
+                        .get_chunk(id)
+                        .to_coarse_serialized_chunk(),
+                )
+            }))
             .collect();
 
         *loaded_chunks = new_loaded_chunks;
+        *coarse_chunks = new_coarse_chunks;
+
+        let boat = if let Status::Alive { entity_index, .. } = self.player.data.status {
+            Some(&self.world.entities[entity_index])
+        } else {
+            None
+        };
+
+        // Area effects that overlap the screen.
+        let area_effects = self
+            .world
+            .area_effects
+            .iter()
+            .map(|state| state.effect)
+            .filter(|effect| {
+                let half_dims = self.camera_dims * 0.5 + Vec2::splat(effect.radius);
+                let delta = (effect.position - self.camera_pos).abs();
+                delta.x <= half_dims.x && delta.y <= half_dims.y
+            })
+            .collect();
+
+        // Intercepts on screen, and whether their defender is a contact (otherwise, they would
+        // reveal its position).
+        let mut intercepts: Vec<(EntityId, Intercept, bool)> = self
+            .world
+            .intercepts
+            .iter()
+            .filter(|(_, intercept)| {
+                let delta = (intercept.target - self.camera_pos).abs();
+                let half_dims = self.camera_dims * 0.5;
+                delta.x <= half_dims.x && delta.y <= half_dims.y
+            })
+            .map(|&(defender_id, intercept)| (defender_id, intercept, false))
+            .collect();
+
+        let beams = self
+            .world
+            .beams
+            .iter()
+            .copied()
+            .filter(|beam| {
+                // Either end may be on screen.
+                let half_dims = self.camera_dims * 0.5;
+                [beam.source, beam.target].iter().any(|&end| {
+                    let delta = (end - self.camera_pos).abs();
+                    delta.x <= half_dims.x && delta.y <= half_dims.y
+                })
+            })
+            .collect();
 
-        Update {
-            contacts: self
-                .contacts
-                .unwrap()
-                .filter_map(|contact| {
-                    let modulus = if let Some(entity_type) = contact.entity_type() {
-                        let range: RangeInclusive<Ticks> = entity_type.data().kind.keep_alive();
-
-                        if contact.transform().velocity.abs() > Velocity::from_mps(1.0) {
-                            // Send more often if moving.
-                            *range.start()
-                        } else {
-                            *range.end()
-                        }
+        // Sounds that can be heard but not seen, loudest first.
+        let mut audio_events: Vec<AudioEvent> = self
+            .world
+            .audio_events
+            .iter()
+            .copied()
+            .filter(|event| event.is_heard_offscreen(self.camera_pos, self.camera_dims))
+            .collect();
+        if audio_events.len() > AudioEvent::MAX_PER_UPDATE {
+            let loudness = |event: &AudioEvent| {
+                event.intensity / (1.0 + event.position.distance(self.camera_pos))
+            };
+            audio_events.sort_unstable_by(|a, b| loudness(b).total_cmp(&loudness(a)));
+            audio_events.truncate(AudioEvent::MAX_PER_UPDATE);
+        }
+
+        // Moving contacts further than this have their detail reduced sooner.
+        let near_squared = (self.camera_dims.max_element() * 0.25).powi(2);
+        let camera_pos = self.camera_pos;
+        // Casters are sent fewer updates, so can't skip contacts in any of them.
+        let casting = self.player.data.casting.is_some();
+
+        let contacts = self
+            .contacts
+            .unwrap()
+            .filter_map(|contact| {
+                for (defender_id, _, seen) in &mut intercepts {
+                    *seen |= *defender_id == contact.id();
+                }
+
+                let modulus = if let Some(entity_type) = contact.entity_type() {
+                    let range: RangeInclusive<Ticks> = entity_type.data().kind.keep_alive();
+                    let transform = contact.transform();
+
+                    if transform.velocity.abs() > Velocity::from_mps(1.0) {
+                        // Send more often if moving, unless reducing detail.
+                        let amount =
+                            if transform.position.distance_squared(camera_pos) < near_squared {
+                                reduction
+                            } else {
+                                (reduction * 2.0).min(1.0)
+                            };
+                        let (start, end) = (range.start().0 as f32, range.end().0 as f32);
+                        Ticks::from_repr(lerp(start, end, amount).round() as TicksRepr)
                     } else {
-                        Ticks::from_repr(5)
-                    };
+                        *range.end()
+                    }
+                } else {
+                    Ticks::from_repr(5)
+                };
 
-                    let send = counter
-                        .wrapping_add(Ticks::from_repr(contact.id().get() as TicksRepr))
+                let send = casting
+                    || counter.wrapping_add(Ticks::from_repr(contact.id().get() as TicksRepr))
                         % (modulus + Ticks::ONE)
                         == Ticks::ZERO;
-                    send.then(|| contact.into_contact())
-                })
-                .collect(),
+                send.then(|| contact.into_contact())
+            })
+            .collect();
+
+        // Closures can't borrow `self` now that `contacts` has been moved out of it.
+        let world = self.world;
+        let update = Update {
+            tick: counter,
+            contacts,
             death_reason,
+            summary,
             score: self.player.score,
+            entity_type: boat.map(|boat| boat.entity_type),
+            // Filled in by the server, which has access to teammates.
+            signals: Vec::new(),
+            drawings: Vec::new(),
             world_radius: self.world.radius,
             terrain,
-        }
+            // Filled in by the server, which loaded it.
+            content_pack: None,
+            interpolation: None,
+            envelopes: None,
+            afk: false,
+            port_progress: None,
+            scuttle_progress: None,
+            banked_coins: 0,
+            duels: Vec::new(),
+            escorts: Vec::new(),
+            area_effects,
+            status_effects: boat
+                .map(|boat| boat.extension().status_effects)
+                .unwrap_or_default(),
+            ammo: boat
+                .map(|boat| boat.extension().ammo.clone())
+                .unwrap_or_default(),
+            cargo: boat.and_then(|boat| boat.extension().cargo),
+            terrain_objective: self
+                .player
+                .data
+                .terrain_objective
+                .map(|(objective, _)| objective),
+            time_of_day: self.world.time_of_day,
+            sea_state: self.world.sea_state,
+            radar_warnings: boat
+                .map(|boat| world.radar_warnings(boat))
+                .unwrap_or_default(),
+            collision_warning: boat
+                .map_or(false, |boat| boat.extension().collision_limit.is_some()),
+            target_lock: boat.and_then(|boat| boat.extension().target_lock),
+            // Filled in by the server, which tracks contacts across updates.
+            ghosts: Vec::new(),
+            threat_warnings: boat
+                .map(|boat| boat.extension().threat_warnings.clone())
+                .unwrap_or_default(),
+            intercepts: intercepts
+                .into_iter()
+                .filter(|&(_, _, seen)| seen)
+                .map(|(_, intercept, _)| intercept)
+                .collect(),
+            beams,
+            audio_events,
+            bounties: self.world.bounty_markers.clone(),
+            casting,
+            // Filled in by the server, which has access to players.
+            scores: Vec::new(),
+        };
+
+        let bytes = update.contacts.len() * Bandwidth::CONTACT_BYTES
+            + update
+                .terrain
+                .iter()
+                .map(|(_, chunk)| chunk.encoded_len())
+                .sum::<usize>();
+        client_data
+            .bandwidth
+            .record(bytes, Ticks::PERIOD_SECS, self.player.data.hint.congested);
+
+        update
     }
 }
 
@@ -154,3 +352,47 @@ impl<'a, I: Iterator<Item = ContactRef<'a>>> CompleteTrait<'a> for CompleteRef<'
         &self.world.terrain
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::player::Status;
+    use crate::server::{ClientData, Server};
+    use crate::world::World;
+    use common::death_reason::DeathReason;
+    use common::entity::EntityType;
+    use common::protocol::Update;
+    use common::ticks::Ticks;
+    use game_server::player::PlayerTuple;
+    use glam::Vec2;
+
+    fn update(world: &World, player: &PlayerTuple<Server>, client_data: &mut ClientData) -> Update {
+        world
+            .get_player_complete(player, None, None)
+            .into_update(Ticks::ZERO, client_data)
+    }
+
+    #[test]
+    fn summary() {
+        let mut world = World::test_empty(1000.0);
+        let mut client_data = ClientData::default();
+
+        // Ending a life by dying or by leaving (and returning) sends its summary once.
+        for left_game in [false, true] {
+            let (player, index) = world.test_boat(EntityType::G5, u32::MAX - 1, Vec2::ZERO);
+            assert!(update(&world, &player, &mut client_data).summary.is_none());
+
+            player.borrow_player_mut().data.flags.left_game = left_game;
+            world.remove(index, DeathReason::Unknown);
+            assert_eq!(
+                matches!(player.borrow_player().data.status, Status::Dead { .. }),
+                !left_game
+            );
+
+            let summary = update(&world, &player, &mut client_data)
+                .summary
+                .expect("summary");
+            assert_eq!(summary.highest_level, EntityType::G5.data().level);
+            assert!(update(&world, &player, &mut client_data).summary.is_none());
+        }
+    }
+}
@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::classifier::Classification;
 use crate::entity::Entity;
 use bitvec::prelude::*;
 use common::altitude::Altitude;
@@ -8,9 +9,11 @@ use common::angle::Angle;
 use common::contact::{
     Contact, ContactTrait, ReloadsStorage, ANGLE_ARRAY_ZERO, RELOADS_ARRAY_ZERO,
 };
+use common::cosmetic::Cosmetics;
 use common::entity::EntityId;
-use common::entity::EntityType;
+use common::entity::{EntitySubKind, EntityType};
 use common::guidance::Guidance;
+use common::shield::ShieldState;
 use common::ticks::Ticks;
 use common::transform::Transform;
 use core_protocol::id::PlayerId;
@@ -19,13 +22,20 @@ use std::sync::Arc;
 /// A contact that references world data to avoid additional allocation.
 pub struct ContactRef<'a> {
     entity: &'a Entity,
+    classification: Classification,
     has_type: bool,
     reloads: Option<BitArray<ReloadsStorage>>,
 }
 
 impl<'a> ContactRef<'a> {
     /// Creates a new `ContactRef`, referencing an entity, and having certain visibility parameters.
-    pub fn new(entity: &'a Entity, visible: bool, known: bool, has_type: bool) -> Self {
+    pub fn new(
+        entity: &'a Entity,
+        visible: bool,
+        known: bool,
+        classification: Classification,
+    ) -> Self {
+        let has_type = classification == Classification::Type;
         let reloads = (has_type && entity.is_boat() && (visible || known)).then(|| {
             let reloads = &*entity.extension().reloads;
             let mut arr = BitArray::ZERO;
@@ -41,6 +51,7 @@ impl<'a> ContactRef<'a> {
 
         Self {
             entity,
+            classification,
             has_type,
             reloads,
         }
@@ -50,6 +61,7 @@ impl<'a> ContactRef<'a> {
     pub fn into_contact(self) -> Contact {
         Contact::new(
             self.altitude(),
+            self.cosmetics(),
             self.damage(),
             self.entity_type(),
             *self.guidance(),
@@ -59,6 +71,9 @@ impl<'a> ContactRef<'a> {
             *self.transform(),
             self.turrets_arc().cloned(),
             if self.entity.is_boat() {self.entity.extension().horn} else {false}, // non-boats never have horn
+            self.shield(),
+            self.bounty(),
+            self.sub_kind(),
         )
     }
 
@@ -77,6 +92,15 @@ impl<'a> ContactTrait for ContactRef<'a> {
         self.entity.altitude
     }
 
+    #[inline]
+    fn cosmetics(&self) -> Cosmetics {
+        if self.is_boat() {
+            self.entity.borrow_player().data.cosmetics
+        } else {
+            Cosmetics::default()
+        }
+    }
+
     #[inline]
     fn damage(&self) -> Ticks {
         // Don't send lifespan to client.
@@ -96,6 +120,15 @@ impl<'a> ContactTrait for ContactRef<'a> {
         }
     }
 
+    #[inline]
+    fn sub_kind(&self) -> Option<EntitySubKind> {
+        if self.classification >= Classification::SubKind {
+            Some(self.entity.data().sub_kind)
+        } else {
+            None
+        }
+    }
+
     #[inline]
     fn guidance(&self) -> &Guidance {
         &self.entity.guidance
@@ -146,4 +179,19 @@ impl<'a> ContactTrait for ContactRef<'a> {
     fn horn(&self) -> bool {
         self.entity.extension().horn
     }
+
+    #[inline]
+    fn shield(&self) -> Option<ShieldState> {
+        // Only sent along with the type, which tells whether there is a shield.
+        if self.has_type && self.entity.is_boat() {
+            self.entity.shield_state()
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn bounty(&self) -> bool {
+        self.entity.is_boat() && self.entity.borrow_player().data.bounty != 0
+    }
 }
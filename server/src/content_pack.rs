@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::content_pack::ContentPackInfo;
+use common::entity::EntityType;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::str::FromStr;
+
+/// Additional entity definitions, loaded from a directory at startup.
+///
+/// Each `<name>.json` file (in the same format as `common/entities`) defines one entity, and
+/// [`ContentPackInfo::ATLAS`] must contain a sprite for each additional boat. Definitions are
+/// validated and assigned wire ids here, and the files themselves are served as-is (see
+/// [`ContentPackInfo::asset_path`]).
+pub struct ContentPack {
+    pub info: ContentPackInfo,
+}
+
+impl ContentPack {
+    pub fn load(dir: &str) -> Result<Self, String> {
+        let mut files = BTreeMap::new();
+        for entry in fs::read_dir(dir).map_err(|e| format!("couldn't read {dir}: {e}"))? {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let bytes = fs::read(&path).map_err(|e| format!("couldn't read {path:?}: {e}"))?;
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| format!("invalid file name {path:?}"))?;
+            files.insert(name.to_owned(), bytes);
+        }
+
+        // Iterating a BTreeMap is ordered, so the hash and wire ids are deterministic.
+        let hash = files
+            .iter()
+            .fold(0xcbf29ce484222325, |hash, (name, bytes)| {
+                fnv1a(fnv1a(hash, name.as_bytes()), bytes)
+            });
+
+        let sprites = files
+            .get(ContentPackInfo::ATLAS)
+            .map(|bytes| serde_json::from_slice::<Value>(bytes))
+            .transpose()
+            .map_err(|e| format!("couldn't parse {}: {e}", ContentPackInfo::ATLAS))?
+            .and_then(|atlas| atlas.get("sprites").cloned());
+
+        let mut info = ContentPackInfo {
+            hash,
+            entities: Vec::new(),
+        };
+
+        let mut wire_id = ContentPackInfo::first_wire_id();
+        for (file, bytes) in &files {
+            let name = if let Some(name) = file.strip_suffix(".json") {
+                name
+            } else {
+                continue;
+            };
+            if file == ContentPackInfo::ATLAS {
+                continue;
+            }
+            if EntityType::from_str(name).is_ok() {
+                return Err(format!("{name} is already a built-in entity"));
+            }
+
+            let definition: Value =
+                serde_json::from_slice(bytes).map_err(|e| format!("couldn't parse {file}: {e}"))?;
+            let kind = definition
+                .get("kind")
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("{name} has no kind"))?;
+            if kind == "Boat" && sprites.as_ref().and_then(|s| s.get(name)).is_none() {
+                return Err(format!(
+                    "{name} has no sprite in {}",
+                    ContentPackInfo::ATLAS
+                ));
+            }
+
+            let id = u8::try_from(wire_id).map_err(|_| "too many entities in content pack")?;
+            info.entities.push((id, name.to_owned()));
+            wire_id += 1;
+        }
+
+        Ok(Self { info })
+    }
+}
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::content_pack::ContentPack;
+    use common::content_pack::ContentPackInfo;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn load() {
+        // Unique, so that concurrent test runs don't interfere.
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "mk48_content_pack_test_{}_{nanos}",
+            std::process::id()
+        ));
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("Blimp.json"), r#"{"kind": "Boat"}"#).unwrap();
+        fs::write(dir.join("Railgun.json"), r#"{"kind": "Weapon"}"#).unwrap();
+        let dir = dir.to_str().unwrap();
+
+        assert!(ContentPack::load(dir).is_err(), "Blimp has no sprite");
+
+        fs::write(
+            format!("{dir}/{}", ContentPackInfo::ATLAS),
+            r#"{"sprites": {"Blimp": {}}, "animations": {}}"#,
+        )
+        .unwrap();
+        let pack = ContentPack::load(dir).unwrap();
+        // Wire ids are assigned in order of file name.
+        let first = ContentPackInfo::first_wire_id() as u8;
+        assert_eq!(pack.info.entity_name(first), Some("Blimp"));
+        assert_eq!(pack.info.entity_name(first + 1), Some("Railgun"));
+        assert_eq!(pack.info.entities.len(), 2);
+        assert_eq!(ContentPack::load(dir).unwrap().info.hash, pack.info.hash);
+
+        fs::write(format!("{dir}/Harpoon.json"), r#"{"kind": "Weapon"}"#).unwrap();
+        assert!(ContentPack::load(dir).is_err(), "Harpoon is built-in");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
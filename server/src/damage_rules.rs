@@ -0,0 +1,67 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::Entity;
+use common::entity::{EntityKind, EntitySubKind};
+use common::ticks::Ticks;
+use game_server::context_service::FriendlyFire;
+use std::sync::Arc;
+
+/// Per-arena rules for damage between friendly entities (see [`Entity::is_friendly`]), and from
+/// ramming. Beam weapons always pass through friendly boats.
+#[derive(Copy, Clone, Debug)]
+pub struct DamageRules {
+    /// Whether, and how, teammates' weapons and rams damage each other.
+    pub friendly_fire: FriendlyFire,
+    /// Whether players' own weapons damage them.
+    pub self_damage: bool,
+    /// Multiplier of damage from ramming.
+    pub ram_damage: f32,
+}
+
+impl Default for DamageRules {
+    fn default() -> Self {
+        Self {
+            friendly_fire: FriendlyFire::Off,
+            self_damage: false,
+            ram_damage: 1.0,
+        }
+    }
+}
+
+impl DamageRules {
+    /// Own weapons can't hit their launcher until they are this old, so they can get clear of it.
+    const SELF_DAMAGE_ARMING: Ticks = Ticks::from_whole_secs(1);
+
+    /// How a weapon hits a boat that is friendly to it, which is [`FriendlyFire::Off`] if it
+    /// doesn't.
+    pub fn friendly_hit(&self, weapon: &Entity, boat: &Entity) -> FriendlyFire {
+        let weapon_data = weapon.data();
+        // Aircraft and drones fly around their own side.
+        if weapon_data.kind != EntityKind::Weapon
+            || weapon_data.sub_kind == EntitySubKind::Drone
+            || boat.data().sub_kind == EntitySubKind::Drone
+        {
+            return FriendlyFire::Off;
+        }
+        let (player, other_player) = match (weapon.player.as_ref(), boat.player.as_ref()) {
+            (Some(player), Some(other_player)) => (player, other_player),
+            _ => return FriendlyFire::Off,
+        };
+        if Arc::ptr_eq(player, other_player) {
+            if self.self_damage && weapon.ticks > Self::SELF_DAMAGE_ARMING {
+                FriendlyFire::Full
+            } else {
+                FriendlyFire::Off
+            }
+        } else {
+            self.friendly_fire
+        }
+    }
+
+    /// Whether friendly boats that collide damage each other. Reflecting doesn't apply, since
+    /// both boats are rammers.
+    pub fn friendly_ram(&self) -> bool {
+        self.friendly_fire == FriendlyFire::Full
+    }
+}
@@ -0,0 +1,411 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::server::Server;
+use common::duel::{DuelDto, DuelStatus};
+use common::protocol::Duel;
+use core_protocol::id::PlayerId;
+use game_server::player::PlayerTuple;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A player taking part in a challenge or duel.
+#[derive(Clone)]
+pub struct Duelist {
+    /// Stored separately, so that it can be compared without borrowing the player.
+    player_id: PlayerId,
+    player: Arc<PlayerTuple<Server>>,
+}
+
+impl Duelist {
+    pub fn new(player_id: PlayerId, player: &Arc<PlayerTuple<Server>>) -> Self {
+        Self {
+            player_id,
+            player: Arc::clone(player),
+        }
+    }
+
+    /// Adds score to the player, such as a refunded stake.
+    fn pay(&self, score: u32) {
+        let mut player = self.player.borrow_player_mut();
+        player.score = player.score.saturating_add(score);
+    }
+
+    /// Takes score from the player, such as a stake. Stakes may end up with the opponent, so they
+    /// count towards the same limit as transfers (see
+    /// [`crate::player::Player::record_transfer`]), lest duels be
+    /// thrown to farm score with alternate accounts.
+    fn charge(&self, score: u32) -> Result<(), &'static str> {
+        let mut player = self.player.borrow_player_mut();
+        player.data.record_transfer(score)?;
+        player.score = player.score.saturating_sub(score);
+        Ok(())
+    }
+}
+
+/// A challenge to duel. The challenger's stake is held in escrow until it is accepted, declined,
+/// or expires.
+struct Challenge {
+    challenger: Duelist,
+    challenged: Duelist,
+    stake: u32,
+    expires: Instant,
+}
+
+impl Challenge {
+    fn involves(&self, player_id: PlayerId) -> bool {
+        self.challenger.player_id == player_id || self.challenged.player_id == player_id
+    }
+
+    /// Returns the challenger's stake.
+    fn refund(&self) {
+        self.challenger.pay(self.stake);
+    }
+}
+
+/// A duel. Both stakes are held in escrow until one player sinks the other, or time runs out.
+struct ActiveDuel {
+    players: [Duelist; 2],
+    stake: u32,
+    expires: Instant,
+}
+
+impl ActiveDuel {
+    fn involves(&self, player_id: PlayerId) -> bool {
+        self.players.iter().any(|p| p.player_id == player_id)
+    }
+
+    /// Returns the player's opponent, assuming the player is involved.
+    fn opponent(&self, player_id: PlayerId) -> &Duelist {
+        if self.players[0].player_id == player_id {
+            &self.players[1]
+        } else {
+            &self.players[0]
+        }
+    }
+
+    /// Returns both stakes.
+    fn refund(&self) {
+        for player in &self.players {
+            player.pay(self.stake);
+        }
+    }
+}
+
+/// Challenges to duel, and duels, between players. Each player may have at most one outstanding
+/// challenge to someone else, and take part in at most one duel.
+#[derive(Default)]
+pub struct DuelRepo {
+    challenges: Vec<Challenge>,
+    duels: Vec<ActiveDuel>,
+}
+
+impl DuelRepo {
+    /// Whether a player is taking part in a duel.
+    fn is_dueling(&self, player_id: PlayerId) -> bool {
+        self.duels.iter().any(|d| d.involves(player_id))
+    }
+
+    fn challenge_index(&self, challenger_id: PlayerId, challenged_id: PlayerId) -> Option<usize> {
+        self.challenges.iter().position(|c| {
+            c.challenger.player_id == challenger_id && c.challenged.player_id == challenged_id
+        })
+    }
+
+    /// Returns the stake of a challenge, if it exists.
+    pub fn challenge_stake(&self, challenger_id: PlayerId, challenged_id: PlayerId) -> Option<u32> {
+        self.challenge_index(challenger_id, challenged_id)
+            .map(|i| self.challenges[i].stake)
+    }
+
+    /// Challenges a player to duel, taking the challenger's stake into escrow. The challenger must
+    /// be able to afford it.
+    pub fn challenge(
+        &mut self,
+        challenger: Duelist,
+        challenged: Duelist,
+        stake: u32,
+    ) -> Result<(), &'static str> {
+        if challenger.player_id == challenged.player_id {
+            return Err("cannot duel oneself");
+        }
+        if self.is_dueling(challenger.player_id) || self.is_dueling(challenged.player_id) {
+            return Err("already dueling");
+        }
+        if self
+            .challenges
+            .iter()
+            .any(|c| c.challenger.player_id == challenger.player_id)
+        {
+            return Err("already challenging");
+        }
+        if self
+            .challenge_index(challenged.player_id, challenger.player_id)
+            .is_some()
+        {
+            return Err("already challenged");
+        }
+
+        challenger.charge(stake)?;
+        self.challenges.push(Challenge {
+            challenger,
+            challenged,
+            stake,
+            expires: Instant::now() + Duel::CHALLENGE_LIFESPAN,
+        });
+        Ok(())
+    }
+
+    /// Accepts a challenge, taking the challenged player's stake into escrow and starting the
+    /// duel. The challenged player must be able to afford it. Other challenges involving either
+    /// player are withdrawn.
+    pub fn accept(
+        &mut self,
+        challenger_id: PlayerId,
+        challenged_id: PlayerId,
+    ) -> Result<(), &'static str> {
+        let index = self
+            .challenge_index(challenger_id, challenged_id)
+            .ok_or("no such challenge")?;
+        if self.is_dueling(challenger_id) || self.is_dueling(challenged_id) {
+            return Err("already dueling");
+        }
+
+        self.challenges[index]
+            .challenged
+            .charge(self.challenges[index].stake)?;
+        let challenge = self.challenges.swap_remove(index);
+        self.challenges.retain(|c| {
+            let keep = !c.involves(challenger_id) && !c.involves(challenged_id);
+            if !keep {
+                c.refund();
+            }
+            keep
+        });
+        self.duels.push(ActiveDuel {
+            players: [challenge.challenger, challenge.challenged],
+            stake: challenge.stake,
+            expires: Instant::now() + Duel::TIME_LIMIT,
+        });
+        Ok(())
+    }
+
+    /// Declines a challenge from, or withdraws a challenge to, an opponent, refunding the
+    /// challenger's stake.
+    pub fn decline(
+        &mut self,
+        player_id: PlayerId,
+        opponent_id: PlayerId,
+    ) -> Result<(), &'static str> {
+        let index = self
+            .challenge_index(opponent_id, player_id)
+            .or_else(|| self.challenge_index(player_id, opponent_id))
+            .ok_or("no such challenge")?;
+        self.challenges.swap_remove(index).refund();
+        Ok(())
+    }
+
+    /// Ends the duel, if any, between a killer and the player they killed, returning the pot
+    /// that the killer won.
+    pub fn settle(&mut self, killer_id: PlayerId, victim_id: PlayerId) -> u32 {
+        if let Some(index) = self
+            .duels
+            .iter()
+            .position(|d| d.involves(killer_id) && d.involves(victim_id))
+        {
+            self.duels.swap_remove(index).stake * 2
+        } else {
+            0
+        }
+    }
+
+    /// Withdraws the challenges of, and forfeits the duel of, a player that left the game. Their
+    /// opponent wins the pot.
+    pub fn forfeit(&mut self, player_id: PlayerId) {
+        self.challenges.retain(|c| {
+            let keep = !c.involves(player_id);
+            if !keep {
+                c.refund();
+            }
+            keep
+        });
+        if let Some(index) = self.duels.iter().position(|d| d.involves(player_id)) {
+            let duel = self.duels.swap_remove(index);
+            duel.opponent(player_id).pay(duel.stake * 2);
+        }
+    }
+
+    /// Expires unanswered challenges and calls off undecided duels, refunding their stakes.
+    pub fn update(&mut self, now: Instant) {
+        self.challenges.retain(|c| {
+            let keep = c.expires > now;
+            if !keep {
+                c.refund();
+            }
+            keep
+        });
+        self.duels.retain(|d| {
+            let keep = d.expires > now;
+            if !keep {
+                d.refund();
+            }
+            keep
+        });
+    }
+
+    /// Returns the challenges and duel involving a player, for their client.
+    pub fn player_duels(&self, player_id: PlayerId) -> Vec<DuelDto> {
+        let now = Instant::now();
+        let seconds_remaining =
+            |expires: Instant| expires.saturating_duration_since(now).as_secs() as u16;
+
+        self.duels
+            .iter()
+            .filter(|d| d.involves(player_id))
+            .map(|d| DuelDto {
+                opponent: d.opponent(player_id).player_id,
+                stake: d.stake,
+                status: DuelStatus::Active,
+                seconds_remaining: seconds_remaining(d.expires),
+            })
+            .chain(
+                self.challenges
+                    .iter()
+                    .filter(|c| c.involves(player_id))
+                    .map(|c| {
+                        let (opponent, status) = if c.challenger.player_id == player_id {
+                            (c.challenged.player_id, DuelStatus::Challenging)
+                        } else {
+                            (c.challenger.player_id, DuelStatus::Challenged)
+                        };
+                        DuelDto {
+                            opponent,
+                            stake: c.stake,
+                            status,
+                            seconds_remaining: seconds_remaining(c.expires),
+                        }
+                    }),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duel::{DuelRepo, Duelist};
+    use crate::server::Server;
+    use common::duel::DuelStatus;
+    use common::protocol::{Duel, Transfer};
+    use core_protocol::id::PlayerId;
+    use game_server::player::{PlayerData, PlayerTuple};
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    fn duelist(id: u32, score: u32) -> Duelist {
+        let player_id = PlayerId(NonZeroU32::new(id).unwrap());
+        let mut player = PlayerData::<Server>::new(player_id, None);
+        player.score = score;
+        Duelist::new(player_id, &Arc::new(PlayerTuple::new(player)))
+    }
+
+    fn score(duelist: &Duelist) -> u32 {
+        duelist.player.borrow_player().score
+    }
+
+    #[test]
+    fn winner_takes_pot() {
+        let mut repo = DuelRepo::default();
+        let a = duelist(1, 1000);
+        let b = duelist(2, 1000);
+        let c = duelist(3, 1000);
+
+        assert!(repo.challenge(a.clone(), a.clone(), 100).is_err());
+        repo.challenge(a.clone(), b.clone(), 100).unwrap();
+        assert_eq!(score(&a), 900);
+        assert!(repo.challenge(a.clone(), c.clone(), 100).is_err());
+        repo.challenge(c.clone(), a.clone(), 200).unwrap();
+        assert_eq!(repo.challenge_stake(c.player_id, a.player_id), Some(200));
+
+        let duels = repo.player_duels(b.player_id);
+        assert_eq!(duels.len(), 1);
+        assert_eq!(duels[0].opponent, a.player_id);
+        assert_eq!(duels[0].status, DuelStatus::Challenged);
+
+        // Accepting withdraws the other challenge.
+        repo.accept(a.player_id, b.player_id).unwrap();
+        assert_eq!(score(&b), 900);
+        assert_eq!(score(&c), 1000);
+        assert!(repo.accept(c.player_id, a.player_id).is_err());
+        assert!(repo.challenge(c.clone(), b.clone(), 100).is_err());
+
+        // Kills by others don't count.
+        assert_eq!(repo.settle(c.player_id, a.player_id), 0);
+        assert_eq!(repo.settle(b.player_id, a.player_id), 200);
+        assert_eq!(repo.settle(b.player_id, a.player_id), 0);
+        assert!(repo.player_duels(a.player_id).is_empty());
+    }
+
+    #[test]
+    fn refunds() {
+        let mut repo = DuelRepo::default();
+        let a = duelist(1, 1000);
+        let b = duelist(2, 1000);
+        let c = duelist(3, 1000);
+
+        repo.challenge(a.clone(), b.clone(), 100).unwrap();
+        repo.decline(b.player_id, a.player_id).unwrap();
+        assert_eq!(score(&a), 1000);
+        assert!(repo.decline(b.player_id, a.player_id).is_err());
+
+        // Expiry.
+        repo.challenge(a.clone(), b.clone(), 100).unwrap();
+        repo.update(Instant::now() + Duel::CHALLENGE_LIFESPAN);
+        assert_eq!(score(&a), 1000);
+
+        repo.challenge(a.clone(), b.clone(), 100).unwrap();
+        repo.accept(a.player_id, b.player_id).unwrap();
+        repo.update(Instant::now() + Duel::TIME_LIMIT);
+        assert_eq!(score(&a), 1000);
+        assert_eq!(score(&b), 1000);
+
+        // Leaving forfeits.
+        repo.challenge(c.clone(), a.clone(), 100).unwrap();
+        repo.forfeit(a.player_id);
+        assert_eq!(score(&c), 1000);
+        repo.challenge(a.clone(), b.clone(), 100).unwrap();
+        repo.accept(a.player_id, b.player_id).unwrap();
+        repo.forfeit(a.player_id);
+        assert_eq!(score(&b), 1100);
+        assert!(repo.player_duels(b.player_id).is_empty());
+    }
+
+    #[test]
+    fn transfer_cap() {
+        let mut repo = DuelRepo::default();
+        let a = duelist(1, 10000);
+        let b = duelist(2, 10000);
+
+        // Refunded stakes still count, as the duel could have been thrown.
+        repo.challenge(a.clone(), b.clone(), Transfer::CAP).unwrap();
+        repo.decline(b.player_id, a.player_id).unwrap();
+        assert_eq!(
+            repo.challenge(a.clone(), b.clone(), 100),
+            Err("transfer limit reached")
+        );
+        assert_eq!(score(&a), 10000);
+
+        // Accepting is limited the same way, without losing the challenge.
+        b.player
+            .borrow_player_mut()
+            .data
+            .record_transfer(Transfer::CAP)
+            .unwrap();
+        repo.challenge(b.clone(), a.clone(), 100).unwrap_err();
+        let c = duelist(3, 10000);
+        repo.challenge(c.clone(), b.clone(), 100).unwrap();
+        assert!(repo.accept(c.player_id, b.player_id).is_err());
+        assert_eq!(score(&b), 10000);
+        assert_eq!(repo.challenge_stake(c.player_id, b.player_id), Some(100));
+    }
+}
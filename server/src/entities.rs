@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::entity::*;
+use crate::entity_store::{EntityRow, EntityStore};
 use common::death_reason::DeathReason;
 use glam::Vec2;
 use maybe_parallel_iterator::{
@@ -12,37 +13,20 @@ use std::ops::{Index, IndexMut, RangeInclusive};
 
 const SIZE: usize = 32 * common::world::SIZE;
 const SCALE: f32 = 800.0;
+/// Width of a block, the coarse level of the index, in sectors.
+const BLOCK_SIZE: usize = 4;
+const BLOCKS: usize = SIZE / BLOCK_SIZE;
 
-/// An efficient collection of entities.
+/// An efficient collection of entities, indexed by a two level grid.
 pub struct Entities {
-    sectors: [Sector; SIZE * SIZE],
+    /// Each sector stores the entities within it.
+    sectors: [EntityStore; SIZE * SIZE],
+    /// Number of entities in each block of sectors, so that large radius queries can skip empty
+    /// blocks without visiting each of their sectors.
+    block_counts: [u32; BLOCKS * BLOCKS],
 }
 
-/// A single square sector, storing the entities within it.
-pub struct Sector {
-    entities: Vec<Entity>,
-}
-
-impl Sector {
-    /// new allocates an empty sector.
-    const fn new() -> Self {
-        Self {
-            entities: Vec::new(),
-        }
-    }
-
-    /// shrink may reduce the allocation size of a sector if its entity count dropped sufficiently.
-    fn shrink(&mut self) {
-        if self.entities.capacity() > self.entities.len() * 3 {
-            let new_size = (self.entities.len() * 3 / 2).next_power_of_two().max(4);
-            if new_size < self.entities.capacity() {
-                self.entities.shrink_to(new_size);
-            }
-        }
-    }
-}
-
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct SectorId(u8, u8);
 
 impl SectorId {
@@ -80,13 +64,19 @@ impl SectorId {
         }
     }
 
+    /// Returns the index of the block containing the sector.
+    fn as_block_index(&self) -> usize {
+        self.0 as usize / BLOCK_SIZE * BLOCKS + self.1 as usize / BLOCK_SIZE
+    }
+
     /// Iterates all `SectorId`s in a rectangle defined by corners start and end.
     fn iter(start: Self, end: Self) -> impl Iterator<Item = Self> {
         // Range inclusive is slow so add 1.
         (start.0..end.0 + 1).flat_map(move |x| (start.1..end.1 + 1).map(move |y| Self(x, y)))
     }
 
-    /// Iterates all `SectorId`s in a circle.
+    /// Iterates all `SectorId`s in a circle. See also `Entities::iter_occupied_sectors`.
+    #[cfg(test)]
     fn iter_radius(center: Vec2, radius: f32) -> impl Iterator<Item = Self> {
         let start = Self::saturating_from(center - radius);
         let end = Self::saturating_from(center + radius);
@@ -120,7 +110,7 @@ impl TryFrom<Vec2> for SectorId {
     }
 }
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct EntityIndex(SectorId, u16);
 
 impl EntityIndex {
@@ -131,9 +121,10 @@ impl EntityIndex {
 
 impl Entities {
     pub fn new() -> Self {
-        const INIT: Sector = Sector::new();
+        const INIT: EntityStore = EntityStore::new();
         Self {
             sectors: [INIT; SIZE * SIZE],
+            block_counts: [0; BLOCKS * BLOCKS],
         }
     }
 
@@ -143,22 +134,51 @@ impl Entities {
     //     ((SIZE - 1) / 2) as f32 * SCALE
     // }
 
-    fn get_sector(&self, sector_id: SectorId) -> &Sector {
+    fn get_sector(&self, sector_id: SectorId) -> &EntityStore {
         &self.sectors[sector_id.as_sector_index()]
     }
 
-    fn mut_sector(&mut self, sector_id: SectorId) -> &mut Sector {
+    fn mut_sector(&mut self, sector_id: SectorId) -> &mut EntityStore {
         &mut self.sectors[sector_id.as_sector_index()]
     }
 
+    /// Iterates all `SectorId`s in a circle, skipping blocks that contain no entities.
+    fn iter_occupied_sectors(
+        &self,
+        center: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = SectorId> + '_ {
+        let start = SectorId::saturating_from(center - radius);
+        let end = SectorId::saturating_from(center + radius);
+        let block = BLOCK_SIZE as u8;
+
+        // Iterate blocks overlapping the rectangle, then the sectors they share with it.
+        SectorId::iter(
+            SectorId(start.0 / block, start.1 / block),
+            SectorId(end.0 / block, end.1 / block),
+        )
+        .filter(move |b| self.block_counts[b.0 as usize * BLOCKS + b.1 as usize] != 0)
+        .flat_map(move |b| {
+            SectorId::iter(
+                SectorId((b.0 * block).max(start.0), (b.1 * block).max(start.1)),
+                SectorId(
+                    (b.0 * block + block - 1).min(end.0),
+                    (b.1 * block + block - 1).min(end.1),
+                ),
+            )
+        })
+        .filter(move |id| id.in_radius(center, radius))
+    }
+
     pub fn add_internal(&mut self, mut entity: Entity) {
         assert_ne!(entity.id, unset_entity_id());
         let sector_id = entity.transform.position.try_into().unwrap();
         let sector = self.mut_sector(sector_id);
         if entity.is_boat() {
-            entity.create_index(EntityIndex(sector_id, sector.entities.len() as u16));
+            entity.create_index(EntityIndex(sector_id, sector.len() as u16));
         }
-        sector.entities.push(entity);
+        sector.push(entity);
+        self.block_counts[sector_id.as_block_index()] += 1;
     }
 
     /// When an entity moves, it may reside in a different sector. This function commits that
@@ -168,21 +188,23 @@ impl Entities {
         let i = index.1 as usize;
         let sector = self.mut_sector(sector_id);
 
-        let last = sector.entities.len() - 1;
-        if i != last && sector.entities[last].is_boat() {
-            sector.entities[last].set_index(index)
+        let last = sector.len() - 1;
+        if i != last && sector[last].is_boat() {
+            sector[last].set_index(index)
         }
 
-        let mut entity = sector.entities.swap_remove(i as usize);
-        sector.shrink();
+        let mut entity = sector.swap_remove(i);
 
         let new_sector_id = entity.transform.position.try_into().unwrap();
         let new_sector = self.mut_sector(new_sector_id);
 
         if entity.is_boat() {
-            entity.set_index(EntityIndex(new_sector_id, new_sector.entities.len() as u16))
+            entity.set_index(EntityIndex(new_sector_id, new_sector.len() as u16))
         }
-        new_sector.entities.push(entity);
+        new_sector.push(entity);
+
+        self.block_counts[sector_id.as_block_index()] -= 1;
+        self.block_counts[new_sector_id.as_block_index()] += 1;
     }
 
     /// Don't use directly. Wrapped by world's remove.
@@ -191,13 +213,13 @@ impl Entities {
         let i = index.1 as usize;
         let sector = self.mut_sector(sector_id);
 
-        let last = sector.entities.len() - 1;
-        if i != last && sector.entities[last].is_boat() {
-            sector.entities[last].set_index(index)
+        let last = sector.len() - 1;
+        if i != last && sector[last].is_boat() {
+            sector[last].set_index(index)
         }
 
-        let mut entity = sector.entities.swap_remove(i as usize);
-        sector.shrink();
+        let mut entity = sector.swap_remove(i);
+        self.block_counts[sector_id.as_block_index()] -= 1;
 
         if entity.is_boat() {
             entity.delete_index(death_reason);
@@ -205,6 +227,14 @@ impl Entities {
         entity
     }
 
+    /// Copies the scanned fields of each entity to the arrays used by radius queries. Must be
+    /// called after entities move (i.e. by World::physics).
+    pub fn sync_columns(&mut self) {
+        for sector in self.sectors.iter_mut() {
+            sector.sync();
+        }
+    }
+
     /// Iterates all entities in parallel.
     pub fn par_iter(&self) -> impl IntoMaybeParallelIterator<Item = (EntityIndex, &Entity)> {
         self.sectors
@@ -214,8 +244,8 @@ impl Entities {
                 let sector_id = SectorId::from_sector_index(sector_index);
 
                 sector
-                    .entities
-                    .maybe_par_iter()
+                    .entities()
+                    .into_maybe_parallel_iter()
                     .with_min_sequential(256)
                     .enumerate()
                     .map(move |(index, entity)| {
@@ -236,8 +266,8 @@ impl Entities {
                 let sector_id = SectorId::from_sector_index(sector_index);
 
                 sector
-                    .entities
-                    .maybe_par_iter_mut()
+                    .entities_mut()
+                    .into_maybe_parallel_iter()
                     .with_min_sequential(256)
                     .enumerate()
                     .map(move |(index, entity)| {
@@ -253,15 +283,41 @@ impl Entities {
         center: Vec2,
         radius: f32,
     ) -> impl Iterator<Item = (EntityIndex, &Entity)> {
+        self.iter_radius_indices(center, radius)
+            .map(move |index| (index, &self[index]))
+    }
+
+    /// Like `iter_radius`, but only reads the scanned fields of each entity, for loops that can
+    /// reject most entities without looking at the rest of them.
+    pub fn iter_radius_rows(
+        &self,
+        center: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (EntityIndex, EntityRow)> + '_ {
+        self.iter_radius_indices(center, radius)
+            .map(move |index| (index, self.get_sector(index.0).row(index.1 as usize)))
+    }
+
+    /// Iterates the indices of all entities in a given radius around center, by position alone.
+    fn iter_radius_indices(
+        &self,
+        center: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = EntityIndex> + '_ {
         let r2 = radius * radius;
-        SectorId::iter_radius(center, radius).flat_map(move |sector_id| {
-            self.get_sector(sector_id)
-                .entities
-                .iter()
-                .enumerate()
-                .filter(move |(_, e)| e.transform.position.distance_squared(center) <= r2)
-                .map(move |(index, entity)| (EntityIndex(sector_id, index as u16), entity))
-        })
+        self.iter_occupied_sectors(center, radius)
+            .flat_map(move |sector_id| {
+                let sector = self.get_sector(sector_id);
+                sector
+                    .positions()
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, p)| p.distance_squared(center) <= r2)
+                    .map(move |(index, p)| {
+                        debug_assert_eq!(*p, sector[index].transform.position);
+                        EntityIndex(sector_id, index as u16)
+                    })
+            })
     }
 }
 
@@ -269,12 +325,136 @@ impl Index<EntityIndex> for Entities {
     type Output = Entity;
 
     fn index(&self, i: EntityIndex) -> &Self::Output {
-        &self.get_sector(i.0).entities[i.1 as usize]
+        &self.get_sector(i.0)[i.1 as usize]
     }
 }
 
 impl IndexMut<EntityIndex> for Entities {
     fn index_mut(&mut self, i: EntityIndex) -> &mut Self::Output {
-        &mut self.mut_sector(i.0).entities[i.1 as usize]
+        &mut self.mut_sector(i.0)[i.1 as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::{Entities, SectorId};
+    use crate::entity::Entity;
+    use common::entity::{EntityId, EntityType};
+    use glam::Vec2;
+    use rand::{thread_rng, Rng};
+    use test::{black_box, Bencher};
+
+    const WORLD_RADIUS: f32 = 12000.0;
+
+    fn random_position() -> Vec2 {
+        let mut rng = thread_rng();
+        Vec2::new(
+            rng.gen_range(-WORLD_RADIUS..WORLD_RADIUS),
+            rng.gen_range(-WORLD_RADIUS..WORLD_RADIUS),
+        )
+    }
+
+    /// Adds n entities, concentrated in a few clusters like a real world.
+    fn populate(entities: &mut Entities, n: usize) {
+        let clusters: Vec<Vec2> = (0..8).map(|_| random_position()).collect();
+        let mut rng = thread_rng();
+        for i in 0..n {
+            let mut entity = Entity::new(EntityType::Barrel, None);
+            entity.id = EntityId::new(i as u32 + 1).unwrap();
+            let offset = Vec2::new(
+                rng.gen_range(-1000.0..1000.0),
+                rng.gen_range(-1000.0..1000.0),
+            );
+            entity.transform.position = (clusters[i % clusters.len()] + offset)
+                .clamp(Vec2::splat(-WORLD_RADIUS), Vec2::splat(WORLD_RADIUS));
+            entities.add_internal(entity);
+        }
+    }
+
+    fn brute_force_count(entities: &Entities, center: Vec2, radius: f32) -> usize {
+        entities
+            .sectors
+            .iter()
+            .flat_map(|sector| sector.entities().iter())
+            .filter(|e| e.transform.position.distance(center) <= radius)
+            .count()
+    }
+
+    #[test]
+    fn iter_radius() {
+        let mut entities = Entities::new();
+        populate(&mut entities, 2000);
+
+        let check = |entities: &Entities| {
+            for radius in [100.0, 1000.0, 5000.0, 30000.0] {
+                let center = random_position();
+                assert_eq!(
+                    entities.iter_radius(center, radius).count(),
+                    brute_force_count(entities, center, radius)
+                );
+            }
+        };
+        check(&entities);
+
+        // Move some entities, possibly to different blocks.
+        let indices: Vec<_> = entities
+            .iter_radius(Vec2::ZERO, 30000.0)
+            .map(|(i, _)| i)
+            .collect();
+        for index in indices.into_iter().rev().step_by(3) {
+            entities[index].transform.position = random_position();
+            if index.changed(&entities[index]) {
+                entities.move_sector(index);
+            }
+        }
+        entities.sync_columns();
+        check(&entities);
+
+        assert_eq!(
+            entities.block_counts.iter().sum::<u32>(),
+            entities.iter_radius(Vec2::ZERO, 30000.0).count() as u32
+        );
+    }
+
+    fn bench_radius(b: &mut Bencher, radius: f32, hierarchical: bool) {
+        let mut entities = Entities::new();
+        populate(&mut entities, 10000);
+        let center = random_position();
+
+        b.iter(|| {
+            let center = black_box(center);
+            if hierarchical {
+                black_box(entities.iter_radius(center, radius).count())
+            } else {
+                // The single level index, for comparison.
+                let r2 = radius * radius;
+                black_box(
+                    SectorId::iter_radius(center, radius)
+                        .flat_map(|id| entities.get_sector(id).entities().iter())
+                        .filter(|e| e.transform.position.distance_squared(center) <= r2)
+                        .count(),
+                )
+            }
+        })
+    }
+
+    #[bench]
+    fn bench_iter_radius_small(b: &mut Bencher) {
+        bench_radius(b, 500.0, true);
+    }
+
+    #[bench]
+    fn bench_iter_radius_small_single_level(b: &mut Bencher) {
+        bench_radius(b, 500.0, false);
+    }
+
+    #[bench]
+    fn bench_iter_radius_large(b: &mut Bencher) {
+        bench_radius(b, 6000.0, true);
+    }
+
+    #[bench]
+    fn bench_iter_radius_large_single_level(b: &mut Bencher) {
+        bench_radius(b, 6000.0, false);
     }
 }
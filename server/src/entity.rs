@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::arena::Arena;
-use crate::collision::{radius_collision, sat_collision};
+use crate::collision::{radius_collision, sat_collision, swept_circle_impact};
 use crate::entities::*;
 use crate::entity_extension::EntityExtension;
 use crate::player::*;
@@ -13,12 +13,16 @@ use common::angle::Angle;
 use common::death_reason::DeathReason;
 use common::entity::*;
 use common::guidance::Guidance;
+use common::shield::{Shield, ShieldState};
+use common::status_effects::StatusEffects;
+use common::summary::Summary;
 use common::terrain::*;
 use common::ticks::{Ticks, TicksRepr};
 use common::transform::{DimensionTransform, Transform};
 use common::util::hash_u32_to_f32;
 use game_server::player::{PlayerData, PlayerTuple};
 use glam::Vec2;
+use std::convert::TryInto;
 use std::ptr;
 use std::sync::Arc;
 use std::time::Instant;
@@ -154,6 +158,7 @@ impl Entity {
     pub fn create_index(&mut self, i: EntityIndex) {
         debug_assert!(self.is_boat());
 
+        let level = self.data().level;
         let mut player = self.borrow_player_mut();
 
         // Set status to alive.
@@ -162,11 +167,29 @@ impl Entity {
 
         // Clear flags when player's boat is spawned.
         player.data.flags = Flags::default();
+
+        // A new boat isn't recruited to patrol anything yet.
+        player.data.patrol = None;
+
+        // Start a new summary.
+        player.data.summary = Summary::default();
+        player.data.summary.reached_level(level);
+        player.data.highest_level = player.data.highest_level.max(level);
+        let score = player.score;
+        player.data.summary.sample_score(score);
+        player.data.spawn_time = Instant::now();
+        player.data.spawn_score = player.score;
         drop(player);
 
         // Change entity type (allocate turrets/reloads).
         let entity_type = self.entity_type;
-        self.extension_mut().change_entity_type(entity_type);
+        let extension = self.extension_mut();
+        extension.change_entity_type(entity_type);
+
+        // A new boat isn't on fire or flooding, unlike one that was upgraded, and carries no
+        // cargo.
+        extension.status_effects = StatusEffects::default();
+        extension.cargo = None;
     }
 
     /// Adjusts player's pointer to self, if applicable.
@@ -185,6 +208,8 @@ impl Entity {
         let visual_range = self.data().sensors.visual.range;
 
         let mut player = self.borrow_player_mut();
+        let time_alive = player.data.spawn_time.elapsed().as_secs();
+        player.data.summary.time_alive = time_alive.try_into().unwrap_or(u32::MAX);
         player.data.status = if player.data.flags.left_game {
             Status::Spawning
         } else {
@@ -239,6 +264,16 @@ impl Entity {
         self.entity_type.data().kind == EntityKind::Boat
     }
 
+    /// Returns true if the entity may be intercepted by SAMs, i.e. is airborne, or is a missile or
+    /// rocket that may be skimming the sea.
+    pub fn is_air_target(&self) -> bool {
+        self.altitude.is_airborne()
+            || matches!(
+                self.data().sub_kind,
+                EntitySubKind::Missile | EntitySubKind::Rocket
+            )
+    }
+
     /// Returns if this entity is owned by a real player (not a bot, not ownerless).
     /// For printing debug info without being too verbose (including bots).
     #[cfg(debug_assertions)]
@@ -260,14 +295,21 @@ impl Entity {
         if data.sub_kind == EntitySubKind::Starship && data.kind == other_data.kind {return false;}
 
         if data.sub_kind == EntitySubKind::Sam || other_data.sub_kind == EntitySubKind::Sam {
+            // SAMs only intercept airborne targets, passing over everything else.
+            let target = if data.sub_kind == EntitySubKind::Sam {
+                other
+            } else {
+                self
+            };
             // SAMs collide if within radius, simulating their blast-fragmentation warheads.
-            radius_collision(
-                self.transform,
-                data.radius,
-                other.transform,
-                other_data.radius,
-                delta_seconds,
-            )
+            target.is_air_target()
+                && radius_collision(
+                    self.transform,
+                    data.radius,
+                    other.transform,
+                    other_data.radius,
+                    delta_seconds,
+                )
         } else if data.kind == EntityKind::Boat
             && other_data.sub_kind == EntitySubKind::DepthCharge
             && self.altitude.is_submerged()
@@ -291,6 +333,32 @@ impl Entity {
         }
     }
 
+    /// If self is a weapon fast enough to pass through other between ticks, returns where along
+    /// its path (relative to other) until the next tick it would hit other, see
+    /// `swept_circle_impact`. Otherwise, returns None without checking for a hit.
+    pub fn swept_impact(
+        &self,
+        other: &Self,
+        delta_seconds: f32,
+    ) -> Option<Option<(f32, Vec2)>> {
+        let data = self.data();
+        let sweep = self.transform.velocity.to_mps() * delta_seconds;
+        if data.kind != EntityKind::Weapon || sweep.abs() <= data.length {
+            return None;
+        }
+        let other_sweep = other.transform.velocity.to_mps() * delta_seconds;
+
+        let start = self.transform.position;
+        let end = start + self.transform.direction.to_vec() * sweep
+            - other.transform.direction.to_vec() * other_sweep;
+        Some(swept_circle_impact(
+            start,
+            end,
+            other.transform.position,
+            other.data().radius,
+        ))
+    }
+
     /// Combines transform and dimensions.
     pub fn dimension_transform(&self) -> DimensionTransform {
         DimensionTransform {
@@ -343,20 +411,21 @@ impl Entity {
             panic!("boat's player was not alive in update_turret_aim()");
         };
 
-        self.data().update_turret_aim(
-            self.transform,
-            self.extension_mut().turrets_mut(),
-            aim_target,
-            delta_seconds,
-        );
+        let data = self.data();
+        let transform = self.transform;
+        let (turrets, turret_locks) = self.extension_mut().turrets_mut();
+        data.update_turret_aim(transform, turrets, turret_locks, aim_target, delta_seconds);
     }
 
     /// Marks a particular armament as consumed.
     pub fn consume_armament(&mut self, index: usize) {
         let a = &self.data().armaments[index];
 
-        // Limited armaments start their timer when they die.
-        let reload = if a.entity_type.data().limited {
+        // Limited armaments start their timer when they die, and armaments that are out of
+        // ammunition stay empty until resupplied.
+        let reload = if a.entity_type.data().limited
+            || !self.extension_mut().ammo.take(a.entity_type)
+        {
             Ticks::MAX
         } else {
             a.reload()
@@ -365,6 +434,24 @@ impl Entity {
         self.extension_mut().reloads_mut()[index] = reload;
     }
 
+    /// Returns true if an armament is empty for lack of ammunition.
+    pub fn is_out_of_ammo(&self, index: usize) -> bool {
+        !self.data().armaments[index].entity_type.data().limited
+            && self.extension().reloads[index] == Ticks::MAX
+    }
+
+    /// Replenishes ammunition by a fraction of capacity, and starts reloading armaments that
+    /// were empty for lack of it.
+    pub fn resupply(&mut self, fraction: f32) {
+        let entity_type = self.entity_type;
+        self.extension_mut().ammo.resupply(entity_type, fraction);
+        for (index, armament) in self.data().armaments.iter().enumerate() {
+            if self.is_out_of_ammo(index) && self.extension_mut().ammo.take(armament.entity_type) {
+                self.extension_mut().reloads[index] = armament.reload();
+            }
+        }
+    }
+
     /// Repairs by a certain amount, up to maximum health.
     pub fn repair(&mut self, amount: Ticks) {
         self.ticks = self.ticks.saturating_sub(amount);
@@ -426,6 +513,24 @@ impl Entity {
         self.ticks == data.max_health()
     }
 
+    /// Lets the boat's shield, if any, absorb damage from a hit, and returns the rest, which is
+    /// yet to be applied with `Self::damage`.
+    pub fn absorb(&mut self, amount: Ticks) -> Ticks {
+        match Shield::of(self.entity_type) {
+            Some(shield) => {
+                let max_health = self.data().max_health();
+                self.extension_mut().absorb(shield, max_health, amount)
+            }
+            None => amount,
+        }
+    }
+
+    /// Returns the state of the boat's shield, if it has one.
+    pub fn shield_state(&self) -> Option<ShieldState> {
+        Shield::of(self.entity_type)
+            .map(|shield| self.extension().shield_state(shield, self.data().max_health()))
+    }
+
     /// Apply damage to ultimately kill an entity in kill_time, assuming delta ticks elapsed. Returns true if now dead.
     pub fn kill_in(&mut self, delta: Ticks, kill_time: Ticks) -> bool {
         self.damage(delta * (self.data().max_health() / kill_time).max(Ticks::ONE))
@@ -450,7 +555,10 @@ impl Entity {
         if (self.altitude.is_airborne() && (self.data().sub_kind == EntitySubKind::Shell || self.data().sub_kind == EntitySubKind::Rocket || self.data().sub_kind == EntitySubKind::Missile || self.data().sub_kind == EntitySubKind::Laser) && !other.altitude.is_submerged()) || (other.altitude.is_airborne() && (other.data().sub_kind == EntitySubKind::Shell || other.data().sub_kind == EntitySubKind::Rocket || other.data().sub_kind == EntitySubKind::Missile || other.data().sub_kind == EntitySubKind::Laser) && !self.altitude.is_submerged()) {
             return true;
         }
-        if (self.altitude.is_airborne() && self.data().sub_kind == EntitySubKind::Aeroplane && other.altitude.is_airborne() ) || (other.altitude.is_airborne() && other.data().sub_kind == EntitySubKind::Aeroplane && self.altitude.is_airborne()) {return true;}
+        if (self.data().sub_kind == EntitySubKind::Aeroplane || other.data().sub_kind == EntitySubKind::Aeroplane) && self.altitude.is_airborne() && other.altitude.is_airborne() {
+            // Aircraft only intercept each other within the same band of airspace.
+            return self.altitude.air_band() == other.altitude.air_band();
+        }
         self.altitude.difference(other.altitude)
             <= if self.special_altitude_overlap() || other.special_altitude_overlap() {
                 Altitude::SPECIAL_OVERLAP_MARGIN
@@ -689,8 +797,16 @@ impl Eq for Entity {}
 #[cfg(test)]
 mod tests {
     use crate::entity::Entity;
-    use common::entity::{EntityId, EntityType};
+    use crate::player::Status;
+    use crate::world::World;
+    use common::altitude::Altitude;
+    use common::entity::{EntityId, EntityKind, EntitySubKind, EntityType};
+    use common::protocol::TurretLock;
+    use common::terrain::Terrain;
+    use common::test_util;
+    use common::ticks::Ticks;
     use glam::Vec2;
+    use proptest::prelude::*;
     use std::mem;
 
     #[test]
@@ -713,6 +829,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sam_only_intercepts_air_targets() {
+        let sam = Entity::new(EntityType::Magic, None);
+        let mut boat = Entity::new(EntityType::Zubr, None);
+        assert!(!sam.collides_with(&boat, 1.0));
+        assert!(!boat.collides_with(&sam, 1.0));
+
+        boat.altitude = Altitude::UNIT;
+        assert!(sam.collides_with(&boat, 1.0));
+
+        let missile = Entity::new(EntityType::Exocet, None);
+        assert!(sam.collides_with(&missile, 1.0));
+    }
+
+    #[test]
+    fn fighters() {
+        for entity_type in [EntityType::J15, EntityType::TieFighter, EntityType::Type96] {
+            assert!(entity_type.data().is_fighter(), "{:?}", entity_type);
+        }
+        assert!(!EntityType::Zubr.data().is_fighter());
+    }
+
     #[test]
     fn eq() {
         let mut e1 = Entity::new(EntityType::Zubr, None);
@@ -722,4 +860,71 @@ mod tests {
         assert_eq!(e2, e2);
         assert_ne!(e1, e2)
     }
+
+    #[test]
+    fn turret_locks() {
+        let mut world = World::test_empty(1000.0);
+        let (player, index) = world.test_boat(EntityType::Yamato, u32::MAX, Vec2::ZERO);
+        player.borrow_player_mut().data.status = Status::Alive {
+            entity_index: index,
+            aim_target: Some(Vec2::new(0.0, 1000.0)),
+        };
+        let data = EntityType::Yamato.data();
+        let entity = &mut world.entities[index];
+
+        // Hold the first turret where it is, and let the others aim.
+        let lock = Some(TurretLock::Relative(data.turrets[0].angle));
+        entity.extension_mut().set_turret_locks(&[lock]);
+        for _ in 0..10 {
+            entity.update_turret_aim(0.1);
+        }
+        let turrets = &entity.extension().turrets;
+        assert_eq!(turrets[0], data.turrets[0].angle);
+        assert!((1..turrets.len()).any(|i| turrets[i] != data.turrets[i].angle));
+
+        // Unlocking lets it aim too.
+        entity.extension_mut().set_turret_locks(&[]);
+        entity.update_turret_aim(0.1);
+        assert_ne!(entity.extension().turrets[0], data.turrets[0].angle);
+    }
+
+    /// Boats that stay on or under the water.
+    fn nautical_boat_type() -> impl Strategy<Value = EntityType> {
+        test_util::entity_type_of_kind(EntityKind::Boat).prop_filter("airborne", |t| {
+            !matches!(
+                t.data().sub_kind,
+                EntitySubKind::Aeroplane
+                    | EntitySubKind::Drone
+                    | EntitySubKind::Ekranoplan
+                    | EntitySubKind::Helicopter
+                    | EntitySubKind::Starship
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn altitude_respects_depth(
+            entity_type in nautical_boat_type(),
+            position in test_util::position(5000.0),
+            targets in prop::collection::vec(prop::option::of(test_util::altitude()), 1..50),
+            speed in 1u8..=4,
+        ) {
+            let terrain = Terrain::new();
+            let mut entity = Entity::new(entity_type, None);
+            entity.transform.position = position;
+            let data = entity.data();
+
+            for target in targets {
+                entity.apply_altitude_target(&terrain, target, speed as f32, Ticks::ONE);
+                prop_assert!(
+                    entity.altitude >= -data.depth && entity.altitude <= Altitude::ZERO,
+                    "{:?} reached {:?} (depth {:?})",
+                    entity_type,
+                    entity.altitude,
+                    data.depth
+                );
+            }
+        }
+    }
 }
@@ -1,12 +1,25 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use common::altitude::Altitude;
+use crate::world_collision_assist::CollisionLimit;
+use common::altitude::{Altitude, DepthBand};
+use common::ammo::Ammo;
 use common::angle::Angle;
+use common::cargo::Cargo;
 use common::entity::*;
-use common::ticks::Ticks;
+use common::guidance::{Guidance, Waypoint};
+use common::protocol::{Scuttle, TurretLock};
+use common::shield::{Shield, ShieldState};
+use common::status_effects::StatusEffects;
+use common::target_lock::TargetLock;
+use common::terrain::TerrainEditBudget;
+use common::threat_warning::ThreatWarning;
+use common::ticks::{Ticks, TicksRepr};
 use common::util::make_mut_slice;
+use common::velocity::Velocity;
 use common_util::alloc::{arc_default_n, box_default_n};
+use glam::Vec2;
+use std::collections::VecDeque;
 use std::iter::FromIterator;
 use std::sync::Arc;
 
@@ -18,12 +31,16 @@ pub struct EntityExtension {
     // Can't submerge right away to prevent dodging missiles.
     submerge: bool,
     submerge_delay: Ticks,
+    /// Whether to hold at periscope depth, rather than diving deep, while submerged.
+    pub periscope: bool,
 
     /// Whether the player *wants* active sensors. To tell if the player *has* active sensors, use
     /// Used by Self::is_active().
     /// Active stays on for a an extra duration to avoid rapid switching, which could induce flickering on other player's screens.
     active: bool,
     deactivate_delay: Ticks,
+    /// Whether radar is kept silent, even if sensors are active.
+    pub emcon: bool,
 
     /// Whether to sound horn
     pub horn: bool,
@@ -32,6 +49,27 @@ pub struct EntityExtension {
     /// Ticks of protection ticks remaining, zeroed if showing signs of aggression.
     spawn_protection_remaining: Ticks,
 
+    /// Ticks remaining in which a recent gun flash reveals the boat at night.
+    muzzle_flash: Ticks,
+    /// Whether the boat is shining a searchlight, which also reveals it at night.
+    pub searchlight: bool,
+
+    /// Armament index of the beam weapon whose trigger is held, if any, and for how much longer
+    /// it stays held unless pulled again.
+    beam: Option<(u8, Ticks)>,
+    /// Seconds of heat built up by firing beam weapons, which overheat at `Self::BEAM_MAX_HEAT`.
+    beam_heat: f32,
+
+    /// Damage absorbed by the shield, if the boat has one, which recharges back to zero.
+    shield_damage: Ticks,
+    /// Ticks remaining before the shield starts recharging, after absorbing a hit.
+    shield_delay: Ticks,
+
+    /// Ammunition in reserve, which armaments reload from.
+    pub ammo: Ammo,
+    /// Cargo being delivered between ports, if any.
+    pub cargo: Option<Cargo>,
+
     // 1 reload per armament, 0 = reloaded.
     // Not an arc because converted to a bitset with max len of 32.
     pub reloads: Box<[Ticks]>,
@@ -39,6 +77,40 @@ pub struct EntityExtension {
     // 1 angle per turret relative to boat.
     // Arc to save allocations
     pub turrets: Arc<[Angle]>,
+    /// 1 lock per turret, which holds it at a bearing instead of aiming it.
+    turret_locks: Box<[Option<TurretLock>]>,
+
+    /// Remaining waypoints, which take precedence over steering commands.
+    pub route: VecDeque<Waypoint>,
+
+    /// Limits how quickly depositors and shovels may change terrain.
+    pub terrain_budget: TerrainEditBudget,
+
+    /// Fires, flooding, and damage control.
+    pub status_effects: StatusEffects,
+    /// Fraction of a tick of reloading carried over, while damage control slows reloading.
+    reload_carry: f32,
+    /// Whether the repair crew was called since the last physics update.
+    pub repair_crew: bool,
+
+    /// Ticks remaining until the boat is out of combat, after taking damage.
+    combat_cooldown: Ticks,
+
+    /// Whether the player opted into slowing down and steering automatically to avoid running
+    /// aground.
+    pub collision_assist: bool,
+    /// How collision assist limits the boat, because of land or an obstacle ahead.
+    pub collision_limit: Option<CollisionLimit>,
+
+    /// Enemy boat that the player locked onto, as of the last update of target locks.
+    pub target_lock: Option<TargetLock>,
+
+    /// Weapons that threatened the boat as of the last update of threat warnings, which were
+    /// already warned of.
+    pub threats: Vec<EntityId>,
+    /// Warnings of weapons that newly threaten the boat, as of the last update of threat
+    /// warnings.
+    pub threat_warnings: Vec<ThreatWarning>,
 }
 
 impl EntityExtension {
@@ -51,6 +123,18 @@ impl EntityExtension {
     const SUBMERGE_DELAY: Ticks = Ticks::from_repr(8);
     /// How long horns are delayed.
     const HORN_DELAY: Ticks = Ticks::from_repr(8);
+    /// How long firing a gun reveals the boat for.
+    const MUZZLE_FLASH: Ticks = Ticks::from_whole_secs(2);
+    /// How long a beam weapon keeps firing after its trigger is pulled, which is repeatedly while
+    /// held.
+    const BEAM_TRIGGER: Ticks = Ticks::from_repr(5);
+    /// Seconds that beam weapons may fire continuously before overheating.
+    const BEAM_MAX_HEAT: f32 = 5.0;
+    /// Fraction of the rate of heating that beam weapons cool down at, while not firing.
+    const BEAM_COOLING: f32 = 0.5;
+    /// Terrain edit amount regained per second, and maximum that can be saved up.
+    const TERRAIN_EDIT_RATE: f32 = 240.0;
+    const TERRAIN_EDIT_BURST: f32 = 480.0;
 
     /// Allocates reloads and turrets, sized to a particular entity type.
     /// It can also give spawn protection.
@@ -64,13 +148,45 @@ impl EntityExtension {
             Ticks::ZERO
         };
         self.reloads = box_default_n(data.armaments.len());
+        self.ammo = Ammo::new(entity_type);
         self.turrets = Arc::from_iter(data.turrets.iter().map(|t| t.angle));
+        self.turret_locks = box_default_n(data.turrets.len());
+        self.route.clear();
+        self.target_lock = None;
+        self.threats.clear();
+        self.threat_warnings.clear();
+        self.shield_damage = Ticks::ZERO;
+        self.shield_delay = Ticks::ZERO;
+    }
+
+    /// Steers `guidance` towards the next waypoint of the route, if any, discarding waypoints
+    /// that were reached. Stops once the last waypoint is reached, instead of steaming onwards.
+    pub fn follow_route(&mut self, position: Vec2, guidance: &mut Guidance) {
+        if self.route.is_empty() {
+            return;
+        }
+        while self
+            .route
+            .front()
+            .map_or(false, |waypoint| waypoint.is_reached(position))
+        {
+            self.route.pop_front();
+        }
+        if let Some(waypoint) = self.route.front() {
+            *guidance = waypoint.guidance(position);
+        } else {
+            guidance.velocity_target = Velocity::ZERO;
+        }
     }
 
-    /// Returns the target altitude of the boat from submerge.
+    /// Returns the target altitude of the boat from submerge and periscope.
     pub fn altitude_target(&self) -> Altitude {
         if self.submerge && self.submerge_delay == Ticks::ZERO {
-            Altitude::MIN
+            if self.periscope {
+                DepthBand::PERISCOPE_DEPTH
+            } else {
+                Altitude::MIN
+            }
         } else {
             Altitude::ZERO
         }
@@ -101,6 +217,11 @@ impl EntityExtension {
         self.active || self.deactivate_delay > Ticks::ZERO
     }
 
+    /// Returns whether radar is emitting, i.e. active and not under emission control.
+    pub fn is_radar_active(&self) -> bool {
+        self.is_active() && !self.emcon
+    }
+
     /// Sets active, possibly also setting deactivate_delay to an appropriate value.
     pub fn set_active(&mut self, active: bool) {
         if !active && self.active {
@@ -109,12 +230,93 @@ impl EntityExtension {
         self.active = active;
     }
 
+    /// Reveals the boat for a moment, e.g. after firing a gun at night.
+    pub fn flash(&mut self) {
+        self.muzzle_flash = Self::MUZZLE_FLASH;
+    }
+
+    /// Returns whether a recent flash or a searchlight makes the boat visible at full range, even
+    /// at night.
+    pub fn is_revealed(&self) -> bool {
+        self.muzzle_flash > Ticks::ZERO || self.searchlight
+    }
+
+    /// Pulls the trigger of the beam weapon at an armament index, which fires it for a moment.
+    pub fn pull_beam_trigger(&mut self, index: usize) {
+        self.beam = Some((index as u8, Self::BEAM_TRIGGER));
+    }
+
+    /// Heats up beam weapons while one is fired, otherwise cools them down. Returns the armament
+    /// index of the beam weapon being fired, if any, and whether it overheated, in which case it
+    /// stops firing.
+    pub fn update_beam(&mut self, delta: Ticks) -> Option<(usize, bool)> {
+        let (index, trigger) = match &mut self.beam {
+            Some(beam) => beam,
+            None => {
+                self.beam_heat = (self.beam_heat - delta.to_secs() * Self::BEAM_COOLING).max(0.0);
+                return None;
+            }
+        };
+        let index = *index as usize;
+
+        *trigger = trigger.saturating_sub(delta);
+        self.beam_heat += delta.to_secs();
+        let overheated = self.beam_heat >= Self::BEAM_MAX_HEAT;
+        if overheated {
+            self.beam_heat = 0.0;
+            self.beam = None;
+        } else if *trigger == Ticks::ZERO {
+            self.beam = None;
+        }
+        Some((index, overheated))
+    }
+
+    /// Absorbs as much of the damage from a hit as the shield can, returning the rest, which
+    /// reaches the hull.
+    pub fn absorb(&mut self, shield: Shield, max_health: Ticks, damage: Ticks) -> Ticks {
+        let capacity = max_health * shield.capacity;
+        let absorbed = damage.min(capacity.saturating_sub(self.shield_damage));
+        if absorbed > Ticks::ZERO {
+            self.shield_damage += absorbed;
+            self.shield_delay = Shield::RECHARGE_DELAY;
+        }
+        damage - absorbed
+    }
+
+    /// Recharges the shield, unless it recently absorbed a hit.
+    pub fn recharge_shield(&mut self, shield: Shield, max_health: Ticks, delta: Ticks) {
+        if self.shield_delay > Ticks::ZERO {
+            self.shield_delay = self.shield_delay.saturating_sub(delta);
+            return;
+        }
+        let recharge = max_health * (shield.capacity * shield.recharge * delta.to_secs());
+        self.shield_damage = self.shield_damage.saturating_sub(recharge.max(Ticks::ONE));
+    }
+
+    /// Returns the state of the shield, for clients to see.
+    pub fn shield_state(&self, shield: Shield, max_health: Ticks) -> ShieldState {
+        let capacity = max_health * shield.capacity;
+        let fraction = 1.0 - self.shield_damage.to_secs() / capacity.to_secs().max(f32::EPSILON);
+        let impact = self.shield_delay + Shield::IMPACT > Shield::RECHARGE_DELAY;
+        ShieldState::new(fraction, impact)
+    }
+
     /// Returns a multiplier for damage taken, taking into account spawn protection.
     pub fn spawn_protection(&self) -> f32 {
         (Self::SPAWN_PROTECTION_INITIAL - self.spawn_protection_remaining).to_secs()
             / Self::SPAWN_PROTECTION_INITIAL.to_secs()
     }
 
+    /// Marks the boat as in combat, for [`Scuttle::COMBAT_COOLDOWN`].
+    pub fn enter_combat(&mut self) {
+        self.combat_cooldown = Scuttle::COMBAT_COOLDOWN;
+    }
+
+    /// Returns whether the boat took damage recently.
+    pub fn in_combat(&self) -> bool {
+        self.combat_cooldown != Ticks::ZERO
+    }
+
     /// Clears any remaining spawn protection (useful if showing signs of aggression, and thus
     /// no longer deserving of spawn protection).
     pub fn clear_spawn_protection(&mut self) {
@@ -126,11 +328,29 @@ impl EntityExtension {
     /// deactivate_delay
     /// horn_delay
     /// spawn_protection_remaining
+    /// muzzle_flash
+    /// combat_cooldown
+    /// terrain_budget
     pub fn update_tickers(&mut self, delta: Ticks) {
         self.submerge_delay = self.submerge_delay.saturating_sub(delta);
         self.deactivate_delay = self.deactivate_delay.saturating_sub(delta);
         self.horn_delay = self.horn_delay.saturating_sub(delta);
         self.spawn_protection_remaining = self.spawn_protection_remaining.saturating_sub(delta);
+        self.muzzle_flash = self.muzzle_flash.saturating_sub(delta);
+        self.combat_cooldown = self.combat_cooldown.saturating_sub(delta);
+        self.terrain_budget.update(delta.to_secs());
+    }
+
+    /// Returns how much armaments reload by over `delta`, which is less during damage control.
+    pub fn reload_amount(&mut self, delta: Ticks) -> Ticks {
+        if !self.status_effects.damage_control {
+            self.reload_carry = 0.0;
+            return delta;
+        }
+        self.reload_carry += delta.0 as f32 * StatusEffects::DAMAGE_CONTROL_RELOAD;
+        let whole = self.reload_carry.floor();
+        self.reload_carry -= whole;
+        Ticks::from_repr(whole as TicksRepr)
     }
 
     /// reloads_mut returns a mutable reference to the reloads component of the extension.
@@ -138,9 +358,17 @@ impl EntityExtension {
         &mut self.reloads
     }
 
-    /// reloads_mut returns a mutable reference to the turret angles component of the extension.
-    pub fn turrets_mut(&mut self) -> &mut [Angle] {
-        make_mut_slice(&mut self.turrets)
+    /// turrets_mut returns a mutable reference to the turret angles component of the extension,
+    /// along with the turrets' locks.
+    pub fn turrets_mut(&mut self) -> (&mut [Angle], &[Option<TurretLock>]) {
+        (make_mut_slice(&mut self.turrets), &self.turret_locks)
+    }
+
+    /// Locks (or unlocks) turrets, indexed like the turrets. Missing turrets are unlocked.
+    pub fn set_turret_locks(&mut self, turret_locks: &[Option<TurretLock>]) {
+        for (i, lock) in self.turret_locks.iter_mut().enumerate() {
+            *lock = turret_locks.get(i).copied().flatten();
+        }
     }
 }
 
@@ -151,13 +379,130 @@ impl Default for EntityExtension {
         Self {
             submerge: false,
             submerge_delay: Ticks::ZERO,
+            periscope: false,
             active: true,
             deactivate_delay: Ticks::ZERO,
+            emcon: false,
             horn: false,
             horn_delay: Ticks::ZERO,
             spawn_protection_remaining: Self::SPAWN_PROTECTION_INITIAL,
+            muzzle_flash: Ticks::ZERO,
+            searchlight: false,
+            beam: None,
+            beam_heat: 0.0,
+            shield_damage: Ticks::ZERO,
+            shield_delay: Ticks::ZERO,
+            ammo: Ammo::default(),
+            cargo: None,
             reloads: box_default_n(0),
             turrets: arc_default_n(0),
+            turret_locks: box_default_n(0),
+            route: VecDeque::new(),
+            terrain_budget: TerrainEditBudget::new(
+                Self::TERRAIN_EDIT_RATE,
+                Self::TERRAIN_EDIT_BURST,
+            ),
+            status_effects: StatusEffects::default(),
+            reload_carry: 0.0,
+            repair_crew: false,
+            combat_cooldown: Ticks::ZERO,
+            collision_assist: false,
+            collision_limit: None,
+            target_lock: None,
+            threats: Vec::new(),
+            threat_warnings: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::entity_extension::EntityExtension;
+    use common::entity::EntityType;
+    use common::guidance::{Guidance, Waypoint};
+    use common::protocol::Scuttle;
+    use common::shield::Shield;
+    use common::ticks::Ticks;
+    use common::velocity::Velocity;
+    use glam::Vec2;
+
+    #[test]
+    fn combat() {
+        let mut extension = EntityExtension::default();
+        assert!(!extension.in_combat());
+
+        extension.enter_combat();
+        extension.update_tickers(Scuttle::COMBAT_COOLDOWN - Ticks::ONE);
+        assert!(extension.in_combat());
+        extension.update_tickers(Ticks::ONE);
+        assert!(!extension.in_combat());
+    }
+
+    #[test]
+    fn shield() {
+        let shield = Shield::of(EntityType::Xwing).unwrap();
+        let max_health = EntityType::Xwing.data().max_health();
+        let capacity = max_health * shield.capacity;
+        let mut extension = EntityExtension::default();
+        assert_eq!(extension.shield_state(shield, max_health).fraction(), 1.0);
+
+        // Absorbs what it can, letting the rest through.
+        assert_eq!(
+            extension.absorb(shield, max_health, capacity - Ticks::ONE),
+            Ticks::ZERO
+        );
+        let state = extension.shield_state(shield, max_health);
+        assert!(state.impact);
+        assert!(!state.is_down());
+        assert_eq!(
+            extension.absorb(shield, max_health, Ticks::from_repr(5)),
+            Ticks::from_repr(4)
+        );
+        assert!(extension.shield_state(shield, max_health).is_down());
+
+        // Waits before recharging.
+        extension.recharge_shield(shield, max_health, Shield::RECHARGE_DELAY);
+        let state = extension.shield_state(shield, max_health);
+        assert!(state.is_down());
+        assert!(!state.impact);
+
+        // Recharges fully, given enough time.
+        for _ in 0..100 {
+            extension.recharge_shield(shield, max_health, Ticks::from_whole_secs(1));
+        }
+        assert_eq!(extension.shield_state(shield, max_health).fraction(), 1.0);
+    }
+
+    #[test]
+    fn route() {
+        let waypoint = |x: f32| Waypoint {
+            position: Vec2::new(x, 0.0),
+            arrival_radius: Waypoint::MIN_ARRIVAL_RADIUS,
+            velocity_target: Velocity::from_mps(10.0),
+        };
+        let mut extension = EntityExtension::default();
+        let steering = Guidance {
+            direction_target: Default::default(),
+            velocity_target: Velocity::from_mps(5.0),
+        };
+
+        // Without a route, steering is left alone.
+        let mut guidance = steering;
+        extension.follow_route(Vec2::ZERO, &mut guidance);
+        assert_eq!(guidance, steering);
+
+        // Reached waypoints are skipped.
+        extension.route = vec![waypoint(5.0), waypoint(100.0)].into();
+        extension.follow_route(Vec2::ZERO, &mut guidance);
+        assert_eq!(extension.route.len(), 1);
+        assert_eq!(guidance, waypoint(100.0).guidance(Vec2::ZERO));
+
+        // Stops at the last waypoint.
+        extension.follow_route(Vec2::new(95.0, 0.0), &mut guidance);
+        assert!(extension.route.is_empty());
+        assert_eq!(guidance.velocity_target, Velocity::ZERO);
+        guidance = steering;
+        extension.follow_route(Vec2::new(95.0, 0.0), &mut guidance);
+        assert_eq!(guidance, steering);
+    }
+}
@@ -0,0 +1,196 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::Entity;
+use common::entity::EntityType;
+use common::velocity::Velocity;
+use glam::Vec2;
+use std::ops::{Index, IndexMut};
+
+/// The fields of an entity that hot loops (radius queries, pairwise physics) scan, copied out of
+/// the entity so that scanning doesn't touch the rest of it.
+#[derive(Copy, Clone)]
+pub struct EntityRow {
+    pub position: Vec2,
+    pub velocity: Velocity,
+    pub entity_type: EntityType,
+}
+
+/// Stores entities as a struct of arrays. Positions, velocities, and types each live in their own
+/// contiguous array, alongside the whole entities, which remain the source of truth and serve as
+/// the compatibility view for code that wants an `&Entity`.
+///
+/// The arrays share length and order. Pushing and removing keep them aligned, but changes to
+/// entities in place are only reflected after `sync` (i.e. each World::physics). Ticks are not
+/// stored separately, as damage and lifespans change throughout an update.
+pub struct EntityStore {
+    positions: Vec<Vec2>,
+    velocities: Vec<Velocity>,
+    types: Vec<EntityType>,
+    entities: Vec<Entity>,
+}
+
+impl EntityStore {
+    /// new allocates an empty store.
+    pub const fn new() -> Self {
+        Self {
+            positions: Vec::new(),
+            velocities: Vec::new(),
+            types: Vec::new(),
+            entities: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn push(&mut self, entity: Entity) {
+        self.positions.push(entity.transform.position);
+        self.velocities.push(entity.transform.velocity);
+        self.types.push(entity.entity_type);
+        self.entities.push(entity);
+    }
+
+    pub fn swap_remove(&mut self, i: usize) -> Entity {
+        self.positions.swap_remove(i);
+        self.velocities.swap_remove(i);
+        self.types.swap_remove(i);
+        let entity = self.entities.swap_remove(i);
+        self.shrink();
+        entity
+    }
+
+    /// shrink may reduce the allocation size of a store if its entity count dropped sufficiently.
+    fn shrink(&mut self) {
+        if self.entities.capacity() > self.entities.len() * 3 {
+            let new_size = (self.entities.len() * 3 / 2).next_power_of_two().max(4);
+            if new_size < self.entities.capacity() {
+                self.positions.shrink_to(new_size);
+                self.velocities.shrink_to(new_size);
+                self.types.shrink_to(new_size);
+                self.entities.shrink_to(new_size);
+            }
+        }
+    }
+
+    /// Copies the scanned fields of each entity to their arrays. Must be called after entities
+    /// change in place.
+    pub fn sync(&mut self) {
+        for (i, entity) in self.entities.iter().enumerate() {
+            self.positions[i] = entity.transform.position;
+            self.velocities[i] = entity.transform.velocity;
+            self.types[i] = entity.entity_type;
+        }
+    }
+
+    /// Returns the scanned fields of the i-th entity, without touching the entity.
+    pub fn row(&self, i: usize) -> EntityRow {
+        let row = EntityRow {
+            position: self.positions[i],
+            velocity: self.velocities[i],
+            entity_type: self.types[i],
+        };
+        debug_assert!(self.is_synced(i));
+        row
+    }
+
+    /// Returns the contiguous array of positions.
+    pub fn positions(&self) -> &[Vec2] {
+        &self.positions
+    }
+
+    /// Returns whole entities.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Mutably returns whole entities. Call `sync` after changing scanned fields.
+    pub fn entities_mut(&mut self) -> &mut [Entity] {
+        &mut self.entities
+    }
+
+    /// Returns true if the arrays agree with the i-th entity.
+    fn is_synced(&self, i: usize) -> bool {
+        let entity = &self.entities[i];
+        self.positions[i] == entity.transform.position
+            && self.velocities[i] == entity.transform.velocity
+            && self.types[i] == entity.entity_type
+    }
+}
+
+impl Index<usize> for EntityStore {
+    type Output = Entity;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.entities[i]
+    }
+}
+
+impl IndexMut<usize> for EntityStore {
+    fn index_mut(&mut self, i: usize) -> &mut Self::Output {
+        &mut self.entities[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::Entity;
+    use crate::entity_store::EntityStore;
+    use common::entity::{EntityId, EntityType};
+    use common::velocity::Velocity;
+    use glam::Vec2;
+
+    fn entity(i: u32) -> Entity {
+        let mut entity = Entity::new(
+            if i % 2 == 0 {
+                EntityType::Barrel
+            } else {
+                EntityType::Crate
+            },
+            None,
+        );
+        entity.id = EntityId::new(i + 1).unwrap();
+        entity.transform.position = Vec2::splat(i as f32);
+        entity.transform.velocity = Velocity::from_mps(i as f32);
+        entity
+    }
+
+    #[test]
+    fn aligned() {
+        let mut store = EntityStore::new();
+        for i in 0..10 {
+            store.push(entity(i));
+        }
+
+        let check = |store: &EntityStore| {
+            assert_eq!(store.positions().len(), store.len());
+            for (i, entity) in store.entities().iter().enumerate() {
+                let row = store.row(i);
+                assert_eq!(row.position, entity.transform.position);
+                assert!(row.velocity == entity.transform.velocity);
+                assert_eq!(row.entity_type, entity.entity_type);
+            }
+        };
+        check(&store);
+
+        // Removing swaps in the last entity, whose fields must follow it.
+        let removed = store.swap_remove(2);
+        assert_eq!(removed.id, EntityId::new(3).unwrap());
+        assert_eq!(store[2].id, EntityId::new(10).unwrap());
+        check(&store);
+
+        // In place changes are reflected after syncing.
+        for entity in store.entities_mut() {
+            entity.transform.position += Vec2::ONE;
+            entity.transform.velocity = Velocity::ZERO;
+        }
+        store.sync();
+        check(&store);
+
+        while store.len() > 0 {
+            store.swap_remove(0);
+        }
+        check(&store);
+    }
+}
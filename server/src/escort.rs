@@ -0,0 +1,337 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::server::Server;
+use common::escort::{EscortDto, EscortStatus};
+use common::protocol::Escort;
+use core_protocol::id::PlayerId;
+use game_server::player::PlayerTuple;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A player taking part in an escort contract, or an offer of one.
+#[derive(Clone)]
+pub struct Party {
+    /// Stored separately, so that it can be compared without borrowing the player.
+    player_id: PlayerId,
+    player: Arc<PlayerTuple<Server>>,
+}
+
+impl Party {
+    pub fn new(player_id: PlayerId, player: &Arc<PlayerTuple<Server>>) -> Self {
+        Self {
+            player_id,
+            player: Arc::clone(player),
+        }
+    }
+
+    fn pay(&self, score: u32) {
+        let mut player = self.player.borrow_player_mut();
+        player.score = player.score.saturating_add(score);
+    }
+}
+
+/// An offer, by the player carrying cargo, of a contract to escort it.
+struct Offer {
+    client: Party,
+    escort: Party,
+    share: u8,
+    expires: Instant,
+}
+
+impl Offer {
+    fn involves(&self, player_id: PlayerId) -> bool {
+        self.client.player_id == player_id || self.escort.player_id == player_id
+    }
+}
+
+/// A contract to escort cargo, which lasts until it is delivered or lost.
+struct Contract {
+    client: Party,
+    escort: Party,
+    share: u8,
+    /// Players who damaged the client's boat during the run, and haven't been sunk for it yet.
+    attackers: Vec<PlayerId>,
+    attackers_sunk: u8,
+}
+
+impl Contract {
+    fn involves(&self, player_id: PlayerId) -> bool {
+        self.client.player_id == player_id || self.escort.player_id == player_id
+    }
+}
+
+/// Escort contracts, and offers of them, between players carrying cargo and players protecting
+/// them. Each player may have at most one outstanding offer to someone else, and take part in at
+/// most one contract.
+#[derive(Default)]
+pub struct EscortRepo {
+    offers: Vec<Offer>,
+    contracts: Vec<Contract>,
+}
+
+impl EscortRepo {
+    /// Whether a player is taking part in a contract.
+    fn is_contracted(&self, player_id: PlayerId) -> bool {
+        self.contracts.iter().any(|c| c.involves(player_id))
+    }
+
+    fn offer_index(&self, client_id: PlayerId, escort_id: PlayerId) -> Option<usize> {
+        self.offers
+            .iter()
+            .position(|o| o.client.player_id == client_id && o.escort.player_id == escort_id)
+    }
+
+    /// Offers a player a share of the payout of the client's cargo to escort it.
+    pub fn offer(&mut self, client: Party, escort: Party, share: u8) -> Result<(), &'static str> {
+        if client.player_id == escort.player_id {
+            return Err("cannot escort oneself");
+        }
+        if self.is_contracted(client.player_id) || self.is_contracted(escort.player_id) {
+            return Err("already under contract");
+        }
+        if self
+            .offers
+            .iter()
+            .any(|o| o.client.player_id == client.player_id)
+        {
+            return Err("already offering");
+        }
+
+        self.offers.push(Offer {
+            client,
+            escort,
+            share,
+            expires: Instant::now() + Escort::OFFER_LIFESPAN,
+        });
+        Ok(())
+    }
+
+    /// Accepts an offer, starting the contract. Other offers involving either player are
+    /// withdrawn.
+    pub fn accept(&mut self, client_id: PlayerId, escort_id: PlayerId) -> Result<(), &'static str> {
+        let index = self
+            .offer_index(client_id, escort_id)
+            .ok_or("no such offer")?;
+        if self.is_contracted(client_id) || self.is_contracted(escort_id) {
+            return Err("already under contract");
+        }
+
+        let offer = self.offers.swap_remove(index);
+        self.offers
+            .retain(|o| !o.involves(client_id) && !o.involves(escort_id));
+        self.contracts.push(Contract {
+            client: offer.client,
+            escort: offer.escort,
+            share: offer.share,
+            attackers: Vec::new(),
+            attackers_sunk: 0,
+        });
+        Ok(())
+    }
+
+    /// Declines an offer from, withdraws an offer to, or ends a contract with, another player.
+    /// Nobody is paid for an ended contract.
+    pub fn cancel(&mut self, player_id: PlayerId, other_id: PlayerId) -> Result<(), &'static str> {
+        if let Some(index) = self
+            .offer_index(other_id, player_id)
+            .or_else(|| self.offer_index(player_id, other_id))
+        {
+            self.offers.swap_remove(index);
+            Ok(())
+        } else if let Some(index) = self
+            .contracts
+            .iter()
+            .position(|c| c.involves(player_id) && c.involves(other_id))
+        {
+            self.contracts.swap_remove(index);
+            Ok(())
+        } else {
+            Err("no such offer or contract")
+        }
+    }
+
+    /// Remembers that a player damaged the boat of a client, so that sinking them counts towards
+    /// the bonus.
+    pub fn record_attack(&mut self, attacker_id: PlayerId, victim_id: PlayerId) {
+        if let Some(contract) = self
+            .contracts
+            .iter_mut()
+            .find(|c| c.client.player_id == victim_id && !c.involves(attacker_id))
+        {
+            if !contract.attackers.contains(&attacker_id) {
+                contract.attackers.push(attacker_id);
+            }
+        }
+    }
+
+    /// Counts a kill towards the bonus of the killer's contract, if the victim attacked the
+    /// client.
+    pub fn record_kill(&mut self, killer_id: PlayerId, victim_id: PlayerId) {
+        if let Some(contract) = self.contracts.iter_mut().find(|c| c.involves(killer_id)) {
+            if let Some(index) = contract.attackers.iter().position(|&a| a == victim_id) {
+                contract.attackers.swap_remove(index);
+                contract.attackers_sunk = (contract.attackers_sunk + 1).min(Escort::MAX_ATTACKERS);
+            }
+        }
+    }
+
+    /// Ends the contract, if any, of a client that delivered their cargo, paying the escort their
+    /// share and bonus. Returns what the client is paid, out of the cargo's payout.
+    pub fn complete(&mut self, client_id: PlayerId, payout: u32) -> u32 {
+        if let Some(index) = self
+            .contracts
+            .iter()
+            .position(|c| c.client.player_id == client_id)
+        {
+            let contract = self.contracts.swap_remove(index);
+            let share = payout * contract.share as u32 / 100;
+            let bonus =
+                (payout as f32 * Escort::ATTACKER_BONUS * contract.attackers_sunk as f32) as u32;
+            contract.escort.pay(share + bonus);
+            payout - share + bonus
+        } else {
+            payout
+        }
+    }
+
+    /// Ends the contract, if any, of a client whose cargo was lost, without paying anyone.
+    pub fn cargo_lost(&mut self, client_id: PlayerId) {
+        self.offers.retain(|o| o.client.player_id != client_id);
+        self.contracts.retain(|c| c.client.player_id != client_id);
+    }
+
+    /// Withdraws the offers of, and ends the contracts of, a player that left the game.
+    pub fn forfeit(&mut self, player_id: PlayerId) {
+        self.offers.retain(|o| !o.involves(player_id));
+        self.contracts.retain(|c| !c.involves(player_id));
+    }
+
+    /// Expires unanswered offers.
+    pub fn update(&mut self, now: Instant) {
+        self.offers.retain(|o| o.expires > now);
+    }
+
+    /// Returns the offers and contract involving a player, for their client.
+    pub fn player_escorts(&self, player_id: PlayerId) -> Vec<EscortDto> {
+        self.contracts
+            .iter()
+            .filter(|c| c.involves(player_id))
+            .map(|c| {
+                let (other, status) = if c.client.player_id == player_id {
+                    (c.escort.player_id, EscortStatus::Escorted)
+                } else {
+                    (c.client.player_id, EscortStatus::Escorting)
+                };
+                EscortDto {
+                    other,
+                    share: c.share,
+                    status,
+                    attackers_sunk: c.attackers_sunk,
+                }
+            })
+            .chain(
+                self.offers
+                    .iter()
+                    .filter(|o| o.involves(player_id))
+                    .map(|o| {
+                        let (other, status) = if o.client.player_id == player_id {
+                            (o.escort.player_id, EscortStatus::Offering)
+                        } else {
+                            (o.client.player_id, EscortStatus::Offered)
+                        };
+                        EscortDto {
+                            other,
+                            share: o.share,
+                            status,
+                            attackers_sunk: 0,
+                        }
+                    }),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::escort::{EscortRepo, Party};
+    use crate::server::Server;
+    use common::escort::EscortStatus;
+    use common::protocol::Escort;
+    use core_protocol::id::PlayerId;
+    use game_server::player::{PlayerData, PlayerTuple};
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    fn party(id: u32) -> Party {
+        let player_id = PlayerId(NonZeroU32::new(id).unwrap());
+        Party::new(
+            player_id,
+            &Arc::new(PlayerTuple::new(PlayerData::<Server>::new(player_id, None))),
+        )
+    }
+
+    fn score(party: &Party) -> u32 {
+        party.player.borrow_player().score
+    }
+
+    #[test]
+    fn delivery() {
+        let mut repo = EscortRepo::default();
+        let client = party(1);
+        let escort = party(2);
+        let attacker = party(3);
+
+        assert!(repo.offer(client.clone(), client.clone(), 25).is_err());
+        repo.offer(client.clone(), escort.clone(), 25).unwrap();
+        assert!(repo.offer(client.clone(), attacker.clone(), 25).is_err());
+        let escorts = repo.player_escorts(escort.player_id);
+        assert_eq!(escorts.len(), 1);
+        assert_eq!(escorts[0].status, EscortStatus::Offered);
+
+        repo.accept(client.player_id, escort.player_id).unwrap();
+        assert!(repo.offer(attacker.clone(), escort.clone(), 25).is_err());
+
+        // Only sinking players who attacked the client counts, and only once per attack.
+        let bystander = party(4);
+        repo.record_kill(escort.player_id, attacker.player_id);
+        repo.record_attack(escort.player_id, client.player_id);
+        repo.record_attack(attacker.player_id, escort.player_id);
+        repo.record_attack(attacker.player_id, client.player_id);
+        repo.record_attack(attacker.player_id, client.player_id);
+        repo.record_kill(escort.player_id, client.player_id);
+        repo.record_kill(escort.player_id, bystander.player_id);
+        assert_eq!(repo.player_escorts(client.player_id)[0].attackers_sunk, 0);
+        repo.record_kill(escort.player_id, attacker.player_id);
+        repo.record_kill(escort.player_id, attacker.player_id);
+        assert_eq!(repo.player_escorts(client.player_id)[0].attackers_sunk, 1);
+
+        let bonus = (1000.0 * Escort::ATTACKER_BONUS) as u32;
+        assert_eq!(repo.complete(client.player_id, 1000), 750 + bonus);
+        assert_eq!(score(&escort), 250 + bonus);
+        assert!(repo.player_escorts(client.player_id).is_empty());
+        assert_eq!(repo.complete(client.player_id, 1000), 1000);
+    }
+
+    #[test]
+    fn cancellation() {
+        let mut repo = EscortRepo::default();
+        let client = party(1);
+        let escort = party(2);
+
+        repo.offer(client.clone(), escort.clone(), 10).unwrap();
+        repo.update(Instant::now() + Escort::OFFER_LIFESPAN);
+        assert!(repo.accept(client.player_id, escort.player_id).is_err());
+
+        repo.offer(client.clone(), escort.clone(), 10).unwrap();
+        repo.cancel(escort.player_id, client.player_id).unwrap();
+        assert!(repo.cancel(escort.player_id, client.player_id).is_err());
+
+        repo.offer(client.clone(), escort.clone(), 10).unwrap();
+        repo.accept(client.player_id, escort.player_id).unwrap();
+        repo.cargo_lost(client.player_id);
+        assert_eq!(repo.complete(client.player_id, 1000), 1000);
+        assert_eq!(score(&escort), 0);
+    }
+}
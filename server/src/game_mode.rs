@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::server::Server;
+use common::util::{kill_score, level_to_score, ram_score, respawn_score};
+use core_protocol::id::PlayerId;
+use game_server::player::{PlayerData, PlayerRepo};
+use rand::{thread_rng, Rng};
+
+/// Rules that differ between game modes, such as scoring, who may spawn, and how a round is won.
+/// Each arena's [`World`](crate::world::World) has one, selected by `--game-mode`.
+pub trait GameMode: Send + Sync + 'static {
+    /// Name of the mode, as given to `--game-mode` and advertised in the server browser.
+    fn name(&self) -> &'static str;
+
+    /// Score awarded for sinking a boat whose player had `score`.
+    fn kill_score(&self, score: u32, killer_score: u32) -> u32 {
+        kill_score(score, killer_score)
+    }
+
+    /// Score awarded for ramming a boat whose player had `score`.
+    fn ram_score(&self, score: u32, killer_score: u32) -> u32 {
+        ram_score(score, killer_score)
+    }
+
+    /// Score that a player keeps after their boat sinks.
+    fn respawn_score(&self, score: u32, bot: bool) -> u32 {
+        if bot {
+            // Make sure there are bots in the shallow area.
+            respawn_score(score).min(level_to_score(thread_rng().gen_range(1..=2)))
+        } else {
+            respawn_score(score)
+        }
+    }
+
+    /// Whether a player may spawn now, e.g. a battle royale may not allow joining mid-round.
+    fn can_spawn(&self, player: &PlayerData<Server>) -> Result<(), &'static str> {
+        let _ = player;
+        Ok(())
+    }
+
+    /// Called every tick. Returns the winner, if the round just ended.
+    fn round_winner(&mut self, players: &PlayerRepo<Server>) -> Option<PlayerId> {
+        let _ = players;
+        None
+    }
+}
+
+/// Every player for themselves. Rounds never end.
+#[derive(Debug, Default)]
+pub struct FreeForAll;
+
+impl GameMode for FreeForAll {
+    fn name(&self) -> &'static str {
+        "ffa"
+    }
+}
+
+/// Returns the game mode with the given name.
+pub fn game_mode_from_name(name: &str) -> Result<Box<dyn GameMode>, String> {
+    match name {
+        "ffa" => Ok(Box::new(FreeForAll)),
+        _ => Err(format!("unsupported game mode {}", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_mode::game_mode_from_name;
+
+    #[test]
+    fn from_name() {
+        assert_eq!(game_mode_from_name("ffa").unwrap().name(), "ffa");
+        assert!(game_mode_from_name("battle-royale").is_err());
+    }
+}
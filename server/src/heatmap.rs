@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Coordinates of a cell of a [`Heatmap`], in multiples of [`Heatmap::CELL_SIZE`].
+pub type HeatmapKey = (i16, i16);
+
+/// Counts of gameplay events within one cell of a [`Heatmap`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeatmapCell {
+    /// Boats sunk by another player.
+    pub kills: u64,
+    /// Boats sunk, for any reason.
+    pub deaths: u64,
+    /// Samples of boats passing through.
+    pub traffic: u64,
+    /// Loot collected.
+    pub loot: u64,
+}
+
+/// Where in the world fights happen, boats go, and loot is collected, downsampled into square
+/// cells, so that spawn densities and objective placement can be tuned. Accumulates events for
+/// the lifetime of the arena, to be requested via the admin interface.
+#[derive(Default)]
+pub struct Heatmap {
+    cells: HashMap<HeatmapKey, HeatmapCell>,
+}
+
+impl Heatmap {
+    /// Meters per side of each cell.
+    pub const CELL_SIZE: f32 = 1000.0;
+
+    fn key(position: Vec2) -> HeatmapKey {
+        let cell = (position / Self::CELL_SIZE).floor();
+        (cell.x as i16, cell.y as i16)
+    }
+
+    fn cell_mut(&mut self, position: Vec2) -> &mut HeatmapCell {
+        self.cells.entry(Self::key(position)).or_default()
+    }
+
+    pub fn record_kill(&mut self, position: Vec2) {
+        self.cell_mut(position).kills += 1;
+    }
+
+    pub fn record_death(&mut self, position: Vec2) {
+        self.cell_mut(position).deaths += 1;
+    }
+
+    pub fn record_traffic(&mut self, position: Vec2) {
+        self.cell_mut(position).traffic += 1;
+    }
+
+    pub fn record_loot(&mut self, position: Vec2) {
+        self.cell_mut(position).loot += 1;
+    }
+
+    /// Returns the events accumulated so far, ordered by cell.
+    pub fn cells(&self) -> Vec<(HeatmapKey, HeatmapCell)> {
+        let mut cells: Vec<_> = self.cells.iter().map(|(k, v)| (*k, *v)).collect();
+        cells.sort_unstable_by_key(|(key, _)| *key);
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::heatmap::{Heatmap, HeatmapCell};
+    use glam::Vec2;
+
+    #[test]
+    fn downsample() {
+        let mut heatmap = Heatmap::default();
+        heatmap.record_kill(Vec2::new(10.0, 10.0));
+        heatmap.record_death(Vec2::new(990.0, 10.0));
+        heatmap.record_traffic(Vec2::new(-10.0, 10.0));
+        heatmap.record_loot(Vec2::new(-10.0, -1500.0));
+
+        let cells = heatmap.cells();
+        assert_eq!(
+            cells.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+            vec![(-1, -2), (-1, 0), (0, 0)]
+        );
+        assert_eq!(cells[0].1.loot, 1);
+        assert_eq!(cells[1].1.traffic, 1);
+        assert_eq!(
+            cells[2].1,
+            HeatmapCell {
+                kills: 1,
+                deaths: 1,
+                ..HeatmapCell::default()
+            }
+        );
+
+        // Events accumulate.
+        heatmap.record_kill(Vec2::ZERO);
+        assert_eq!(heatmap.cells()[2].1.kills, 2);
+    }
+}
@@ -0,0 +1,105 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::bounty::BountyMarker;
+use common::death_reason::DeathReason;
+use common::entity::EntityType;
+use core_protocol::name::PlayerAlias;
+use core_protocol::{get_unix_time_now, UnixTime};
+use glam::Vec2;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// A boat sunk by another player.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Kill {
+    pub killer: PlayerAlias,
+    pub victim: PlayerAlias,
+    /// [`None`] if rammed or shot down by anti-aircraft guns.
+    pub weapon: Option<EntityType>,
+    pub time: UnixTime,
+}
+
+/// Most recent kills, for the broadcast feed.
+#[derive(Default)]
+pub struct KillFeed {
+    /// Oldest first.
+    kills: VecDeque<Kill>,
+}
+
+impl KillFeed {
+    /// Older kills are forgotten.
+    const MAX_KILLS: usize = 10;
+
+    /// Records the death of `victim`'s boat, if caused by another player.
+    pub fn record(&mut self, victim: PlayerAlias, reason: &DeathReason) {
+        let (killer, weapon) = match *reason {
+            DeathReason::Weapon(killer, weapon) => (killer, Some(weapon)),
+            DeathReason::Ram(killer) | DeathReason::Boat(killer) | DeathReason::AntiAir(killer) => {
+                (killer, None)
+            }
+            _ => return,
+        };
+        if self.kills.len() >= Self::MAX_KILLS {
+            self.kills.pop_front();
+        }
+        self.kills.push_back(Kill {
+            killer,
+            victim,
+            weapon,
+            time: get_unix_time_now(),
+        });
+    }
+
+    /// Oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Kill> + '_ {
+        self.kills.iter()
+    }
+}
+
+/// Game-specific part of the broadcast feed, for overlays.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastState {
+    pub game_mode: &'static str,
+    pub world_radius: f32,
+    /// Bounties on the top players.
+    pub bounties: Vec<BountyMarker>,
+    /// Where the pirate squadron will spawn, if a raid is imminent.
+    pub pirate_raid: Option<Vec2>,
+    /// Oldest first.
+    pub kills: Vec<Kill>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kill_feed::KillFeed;
+    use common::death_reason::DeathReason;
+    use common::entity::EntityType;
+    use core_protocol::name::PlayerAlias;
+
+    #[test]
+    fn record() {
+        let alias = |s: &str| PlayerAlias::new_unsanitized(s);
+        let mut kill_feed = KillFeed::default();
+
+        kill_feed.record(alias("a"), &DeathReason::Terrain);
+        assert_eq!(kill_feed.iter().count(), 0);
+
+        kill_feed.record(
+            alias("b"),
+            &DeathReason::Weapon(alias("c"), EntityType::Mark18),
+        );
+        let kill = kill_feed.iter().next().unwrap();
+        assert_eq!(kill.killer, alias("c"));
+        assert_eq!(kill.victim, alias("b"));
+        assert_eq!(kill.weapon, Some(EntityType::Mark18));
+
+        for _ in 0..KillFeed::MAX_KILLS {
+            kill_feed.record(alias("d"), &DeathReason::Ram(alias("e")));
+        }
+        assert_eq!(kill_feed.iter().count(), KillFeed::MAX_KILLS);
+        assert!(kill_feed.iter().all(|kill| kill.weapon.is_none()));
+    }
+}
@@ -8,32 +8,67 @@
 #![feature(hash_drain_filter)]
 #![feature(type_alias_impl_trait)]
 #![feature(generic_associated_types)]
+#![feature(test)]
 
 //! The game server has authority over all game logic. Clients are served the client, which connects
 //! via websocket.
 
+#[cfg(test)]
+extern crate test;
+
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: allocations::CountingAllocator = allocations::CountingAllocator;
+
 use crate::server::Server;
 use common::entity::EntityType;
 
+#[cfg(test)]
+mod allocations;
 mod arena;
+mod bandwidth;
+mod benchmark;
+mod bombing;
 mod bot;
+mod classifier;
 mod collision;
 mod complete_ref;
+mod content_pack;
 mod contact_ref;
+mod damage_rules;
+mod duel;
 mod entities;
 mod entity;
 mod entity_extension;
+mod entity_store;
+mod escort;
+mod game_mode;
+mod heatmap;
+mod kill_feed;
 mod noise;
 mod player;
 mod protocol;
+mod sensor_cache;
 mod server;
+mod spawn_selector;
+mod trace;
 mod world;
+mod world_area_effects;
+mod world_audio;
+mod world_beam;
+mod world_cargo;
+mod world_collision_assist;
 mod world_inbound;
 mod world_mutation;
 mod world_outbound;
 mod world_physics;
 mod world_physics_radius;
+mod world_pirates;
+mod world_point_defense;
 mod world_spawn;
+mod world_structures;
+mod world_target_lock;
+mod world_threat_warning;
 #[cfg(test)]
 mod world_test;
 
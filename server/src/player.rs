@@ -2,11 +2,19 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::entities::*;
+use common::cosmetic::Cosmetics;
 use common::death_reason::DeathReason;
-use common::protocol::Hint;
+use common::drawing::Drawing;
+use common::entity::{EntityId, EntityType};
+use common::guidance::Guidance;
+use common::protocol::{Control, Hint, Signal, Transfer};
+use common::summary::Summary;
+use common::terrain_objective::TerrainObjective;
+use common::util::level_to_score;
+use core_protocol::id::PlayerId;
 use glam::Vec2;
 use std::fmt::Debug;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// A player's view into the world.
 #[allow(dead_code)]
@@ -99,6 +107,56 @@ pub struct Player {
     pub hint: Hint,
     /// Current status e.g. Alive, Dead, or Spawning.
     pub status: Status,
+    /// Statistics about the current life (or, if dead, the previous life).
+    pub summary: Summary,
+    /// When the current (or previous) life began.
+    pub spawn_time: Instant,
+    /// Cosmetics chosen when spawning, after validation.
+    pub cosmetics: Cosmetics,
+    /// Highest boat level reached over all lives, which unlocks cosmetics. Stored with the
+    /// player's account, if they sign in.
+    pub highest_level: u8,
+    /// Most recent signal to teammates, and when it was placed.
+    pub last_signal: Option<(Signal, Instant)>,
+    /// Recent drawings for teammates, and when they were placed (oldest first).
+    pub drawings: Vec<(Drawing, Instant)>,
+    /// When the player last gave input, for detecting AFK players.
+    pub last_input: Instant,
+    /// Steering, and aim relative to the boat, of the most recent [`Control`].
+    pub last_control: (Option<Guidance>, Option<Vec2>),
+    /// Players who recently sank this player, and when, so that spawning can avoid them, and so
+    /// that rivalries aren't mistaken for collusion.
+    pub recent_killers: Vec<(PlayerId, Instant)>,
+    /// When the player started returning to port. Taking damage cancels it.
+    pub returning_to_port: Option<Instant>,
+    /// When the player started scuttling their boat.
+    pub scuttling: Option<Instant>,
+    /// Coins banked by returning to port, stored with the player's account.
+    pub banked_coins: u32,
+    /// Whether the player's current boat is an NPC pirate raider. Only bots are drafted.
+    pub pirate: bool,
+    /// Position of the structure that the player's boat patrols, and until when. Only bots are
+    /// recruited.
+    pub patrol: Option<(Vec2, Instant)>,
+    /// Score awarded for sinking the player, if they are one of the top players, otherwise zero.
+    pub bounty: u32,
+    /// Score recently sent to fleet members, and when, for limiting transfers.
+    pub recent_transfers: Vec<(u32, Instant)>,
+    /// Objective to reshape the terrain, if the player's boat can, and when it expires.
+    pub terrain_objective: Option<(TerrainObjective, Instant)>,
+    /// If the player is new, the type of the first boat they lost, and whether they respawned
+    /// after, for measuring retention.
+    pub first_hull: Option<(EntityType, bool)>,
+    /// Where the player is looking, if casting (see [`Cast`][common::protocol::Cast]).
+    pub casting: Option<Vec2>,
+    /// Boats sunk since the current round began, for the match archive.
+    pub round_kills: u32,
+    /// Score when the current (or previous) life began, for detecting implausibly fast scoring.
+    pub spawn_score: u32,
+    /// Real players recently sunk by this player, and when, for detecting collusion.
+    pub recent_victims: Vec<(PlayerId, Instant)>,
+    /// Boats that the player's sensors identified as of their latest update, for target locks.
+    pub identified: Vec<EntityId>,
 }
 
 impl Default for Player {
@@ -108,6 +166,173 @@ impl Default for Player {
             flags: Flags::default(),
             hint: Hint::default(),
             status: Status::Spawning,
+            summary: Summary::default(),
+            spawn_time: Instant::now(),
+            cosmetics: Cosmetics::default(),
+            highest_level: 1,
+            last_signal: None,
+            drawings: Vec::new(),
+            last_input: Instant::now(),
+            last_control: (None, None),
+            recent_killers: Vec::new(),
+            returning_to_port: None,
+            scuttling: None,
+            banked_coins: 0,
+            pirate: false,
+            patrol: None,
+            bounty: 0,
+            recent_transfers: Vec::new(),
+            terrain_objective: None,
+            first_hull: None,
+            casting: None,
+            round_kills: 0,
+            spawn_score: 0,
+            recent_victims: Vec::new(),
+            identified: Vec::new(),
         }
     }
 }
+
+impl Player {
+    /// How long a killer is avoided when spawning.
+    const KILLER_MEMORY: Duration = Duration::from_secs(120);
+    /// How long victims are remembered, for detecting collusion.
+    const VICTIM_MEMORY: Duration = Duration::from_secs(15 * 60);
+    /// Sinking the same player this many times within [`Self::VICTIM_MEMORY`], without them ever
+    /// sinking this player, is suspicious.
+    const COLLUSION_KILLS: usize = 4;
+    /// Scoring rate is averaged over at least this long, so that one big kill isn't suspicious.
+    const MIN_SCORING_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+    /// Meters that the aim must move, relative to the boat, to count as input.
+    const AIM_INPUT: f32 = 10.0;
+
+    /// Records `control`, sent while the player's boat is at `position`, as input if it changes
+    /// the steering or aim, or fires. Clients resend their controls periodically, even if the
+    /// player is away, so resent controls aren't input. Aim is compared relative to the boat, as
+    /// it moves under a still mouse cursor.
+    pub fn record_control(&mut self, control: &Control, position: Vec2) {
+        let (guidance, aim) = self.last_control;
+        let aim_target = control.aim_target.map(|aim_target| aim_target - position);
+        let steered = control.guidance.map_or(false, |g| Some(g) != guidance);
+        let aimed = match (aim_target, aim) {
+            (Some(a), Some(b)) => a.distance(b) > Self::AIM_INPUT,
+            (a, b) => a.is_some() != b.is_some(),
+        };
+        if steered || aimed || control.fire.is_some() || control.pay.is_some() {
+            self.last_input = Instant::now();
+        }
+        self.last_control = (control.guidance.or(guidance), aim_target);
+    }
+
+    /// Remembers that `killer` sank this player.
+    pub fn record_killer(&mut self, killer: PlayerId) {
+        // Remembered as long as victims, for telling rivalries from collusion.
+        self.recent_killers
+            .retain(|&(id, time)| id != killer && time.elapsed() < Self::VICTIM_MEMORY);
+        self.recent_killers.push((killer, Instant::now()));
+    }
+
+    /// Remembers that this player sank `victim`, unless it was a bot. Kills that settle a duel
+    /// shouldn't be recorded, as rematches are arranged by both players.
+    pub fn record_victim(&mut self, victim: PlayerId) {
+        if victim.is_bot() {
+            return;
+        }
+        self.recent_victims
+            .retain(|&(_, time)| time.elapsed() < Self::VICTIM_MEMORY);
+        self.recent_victims.push((victim, Instant::now()));
+    }
+
+    /// Why `score` looks illegitimate, if it does, so that it is quarantined instead of entering
+    /// the leaderboards: scoring faster than the boat's level allows, or sinking the same player
+    /// over and over (e.g. one's own alt account) without them ever fighting back.
+    pub fn score_anomaly(&self, score: u32) -> Option<&'static str> {
+        let minutes = self
+            .spawn_time
+            .elapsed()
+            .max(Self::MIN_SCORING_PERIOD)
+            .as_secs_f32()
+            / 60.0;
+        let max_per_minute = level_to_score(self.summary.highest_level.max(1) + 2);
+        if score.saturating_sub(self.spawn_score) as f32 > max_per_minute as f32 * minutes {
+            return Some("score rate");
+        }
+
+        let recent_victims = || {
+            self.recent_victims
+                .iter()
+                .filter(|&&(_, time)| time.elapsed() < Self::VICTIM_MEMORY)
+        };
+        // Rivals sink each other.
+        let fought_back = |victim: PlayerId| {
+            self.recent_killers
+                .iter()
+                .any(|&(id, time)| id == victim && time.elapsed() < Self::VICTIM_MEMORY)
+        };
+        if recent_victims().any(|&(victim, _)| {
+            !fought_back(victim)
+                && recent_victims().filter(|&&(id, _)| id == victim).count()
+                    >= Self::COLLUSION_KILLS
+        }) {
+            return Some("repeated kills");
+        }
+        None
+    }
+
+    /// Returns whether `player_id` sank this player recently enough to be avoided when spawning.
+    pub fn is_recent_killer(&self, player_id: PlayerId) -> bool {
+        self.recent_killers
+            .iter()
+            .any(|&(id, time)| id == player_id && time.elapsed() < Self::KILLER_MEMORY)
+    }
+
+    /// Remembers that this player sent `amount` to a fleet member, unless it would exceed
+    /// [`Transfer::CAP`] within [`Transfer::CAP_PERIOD`].
+    pub fn record_transfer(&mut self, amount: u32) -> Result<(), &'static str> {
+        self.recent_transfers.retain(|&(_, time)| time.elapsed() < Transfer::CAP_PERIOD);
+        let sent: u32 = self.recent_transfers.iter().map(|&(a, _)| a).sum();
+        if sent + amount > Transfer::CAP {
+            return Err("transfer limit reached");
+        }
+        self.recent_transfers.push((amount, Instant::now()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::player::Player;
+    use core_protocol::id::PlayerId;
+    use std::num::NonZeroU32;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn score_anomaly() {
+        let mut player = Player::default();
+        player.spawn_score = 100;
+        player.spawn_time = Instant::now() - Duration::from_secs(600);
+        assert_eq!(player.score_anomaly(500), None);
+        assert_eq!(player.score_anomaly(100_000), Some("score rate"));
+
+        // Bots may be sunk over and over.
+        let bot = PlayerId::nth_bot(0).unwrap();
+        let real = PlayerId(NonZeroU32::new(u32::MAX).unwrap());
+        for _ in 0..Player::COLLUSION_KILLS {
+            player.record_victim(bot);
+        }
+        assert_eq!(player.score_anomaly(500), None);
+        for _ in 0..Player::COLLUSION_KILLS {
+            player.record_victim(real);
+        }
+        assert_eq!(player.score_anomaly(500), Some("repeated kills"));
+
+        // A rivalry, rather than collusion.
+        player.record_killer(real);
+        assert_eq!(player.score_anomaly(500), None);
+        assert!(player.is_recent_killer(real));
+        player.recent_killers[0].1 = Instant::now() - Duration::from_secs(5 * 60);
+        assert!(!player.is_recent_killer(real));
+        assert_eq!(player.score_anomaly(500), None);
+    }
+}
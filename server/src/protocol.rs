@@ -23,8 +23,19 @@ pub trait AsCommandTrait {
 impl AsCommandTrait for Command {
     fn as_command(&self) -> &dyn CommandTrait {
         match *self {
+            Command::Cast(ref v) => v as &dyn CommandTrait,
             Command::Control(ref v) => v as &dyn CommandTrait,
+            Command::DamageControl(ref v) => v as &dyn CommandTrait,
+            Command::Draw(ref v) => v as &dyn CommandTrait,
+            Command::Duel(ref v) => v as &dyn CommandTrait,
+            Command::Escort(ref v) => v as &dyn CommandTrait,
+            Command::LockTarget(ref v) => v as &dyn CommandTrait,
+            Command::ReturnToPort(ref v) => v as &dyn CommandTrait,
+            Command::Route(ref v) => v as &dyn CommandTrait,
+            Command::Scuttle(ref v) => v as &dyn CommandTrait,
+            Command::Signal(ref v) => v as &dyn CommandTrait,
             Command::Spawn(ref v) => v as &dyn CommandTrait,
+            Command::Transfer(ref v) => v as &dyn CommandTrait,
             Command::Upgrade(ref v) => v as &dyn CommandTrait,
         }
     }
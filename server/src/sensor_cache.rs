@@ -0,0 +1,197 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::altitude::Altitude;
+use common::entity::{EntityId, EntityType};
+use glam::Vec2;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// Everything that sensing a contact depends on.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SensorKey {
+    pub camera_position: Vec2,
+    /// Visual, revealed visual, radar, and sonar ranges (which account for night).
+    pub camera_ranges: [f32; 4],
+    pub camera_speed: f32,
+    /// Active sensors, and active radar.
+    pub camera_active: [bool; 2],
+    pub position: Vec2,
+    pub altitude: Altitude,
+    pub entity_type: EntityType,
+    /// Speed of the contact, which affects radar and passive sonar.
+    pub speed: f32,
+    /// Whether the contact's active radar, active sonar, and horn give it away, and whether it
+    /// fired recently.
+    pub emissions: [bool; 4],
+    /// Whether the contact gave itself away, e.g. by firing guns at night.
+    pub revealed: bool,
+}
+
+impl SensorKey {
+    /// How far the camera or contact may move before a cached result is reevaluated.
+    const MAX_MOVEMENT: f32 = 5.0;
+    /// How much the camera's or contact's speed (which affect radar and sonar) may change.
+    const MAX_SPEED_CHANGE: f32 = 1.0;
+
+    /// Returns whether a result for other is still a good approximation of a result for self.
+    fn is_close_to(&self, other: &Self) -> bool {
+        self.camera_position.distance_squared(other.camera_position) < Self::MAX_MOVEMENT.powi(2)
+            && self.position.distance_squared(other.position) < Self::MAX_MOVEMENT.powi(2)
+            && (self.camera_speed - other.camera_speed).abs() < Self::MAX_SPEED_CHANGE
+            && (self.speed - other.speed).abs() < Self::MAX_SPEED_CHANGE
+            && self.camera_ranges == other.camera_ranges
+            && self.camera_active == other.camera_active
+            && self.altitude == other.altitude
+            && self.entity_type == other.entity_type
+            && self.emissions == other.emissions
+            && self.revealed == other.revealed
+    }
+}
+
+/// Whether a contact is visible, and the uncertainty of detecting it.
+pub type Sensed = (bool, f32);
+
+#[derive(Debug)]
+struct Cached {
+    key: SensorKey,
+    sensed: Sensed,
+    /// Update in which sensed was evaluated.
+    evaluated: u32,
+    /// Update in which the entry was last used.
+    used: u32,
+}
+
+/// Caches what one client's sensors detected about each contact, so that contacts that didn't
+/// move relative to the client aren't reevaluated every update.
+#[derive(Debug, Default)]
+pub struct SensorCache {
+    entries: HashMap<EntityId, Cached>,
+    /// Number of updates.
+    updates: u32,
+}
+
+impl SensorCache {
+    /// Maximum number of updates a result is reused for.
+    const MAX_AGE: u32 = 10;
+
+    /// Must be called before each update. Forgets contacts that weren't sensed in the previous
+    /// update (e.g. went out of range).
+    pub fn begin_update(&mut self) {
+        let previous = self.updates;
+        self.entries.retain(|_, entry| entry.used == previous);
+        self.updates += 1;
+    }
+
+    /// Returns the cached result for a contact if it is still valid, otherwise evaluates it.
+    pub fn get_or_insert_with(
+        &mut self,
+        id: EntityId,
+        key: SensorKey,
+        sense: impl FnOnce() -> Sensed,
+    ) -> Sensed {
+        let updates = self.updates;
+        match self.entries.entry(id) {
+            Entry::Occupied(mut occupied) => {
+                let cached = occupied.get_mut();
+                if updates - cached.evaluated >= Self::MAX_AGE || !cached.key.is_close_to(&key) {
+                    // Stale.
+                    cached.key = key;
+                    cached.sensed = sense();
+                    cached.evaluated = updates;
+                }
+                cached.used = updates;
+                cached.sensed
+            }
+            Entry::Vacant(vacant) => {
+                let sensed = sense();
+                vacant.insert(Cached {
+                    key,
+                    sensed,
+                    evaluated: updates,
+                    used: updates,
+                });
+                sensed
+            }
+        }
+    }
+
+    /// Number of cached contacts.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sensor_cache::{SensorCache, SensorKey};
+    use common::altitude::Altitude;
+    use common::entity::{EntityId, EntityType};
+    use glam::Vec2;
+    use std::cell::Cell;
+
+    fn key(position: Vec2) -> SensorKey {
+        SensorKey {
+            camera_position: Vec2::ZERO,
+            camera_ranges: [500.0, 500.0, 1000.0, 0.0],
+            camera_speed: 10.0,
+            camera_active: [false; 2],
+            position,
+            altitude: Altitude::ZERO,
+            entity_type: EntityType::Barrel,
+            speed: 0.0,
+            emissions: [false; 4],
+            revealed: false,
+        }
+    }
+
+    #[test]
+    fn expiry() {
+        let mut cache = SensorCache::default();
+        let id = EntityId::new(1).unwrap();
+        let evaluations = Cell::new(0);
+        let sense = |cache: &mut SensorCache, position: Vec2| {
+            cache.get_or_insert_with(id, key(position), || {
+                evaluations.set(evaluations.get() + 1);
+                (true, 0.5)
+            })
+        };
+
+        // Static contact is evaluated once, and then reused until it is too old.
+        for _ in 0..SensorCache::MAX_AGE {
+            cache.begin_update();
+            assert_eq!(sense(&mut cache, Vec2::ONE), (true, 0.5));
+        }
+        assert_eq!(evaluations.get(), 1);
+        cache.begin_update();
+        sense(&mut cache, Vec2::ONE);
+        assert_eq!(evaluations.get(), 2);
+
+        // Moving contact is reevaluated.
+        cache.begin_update();
+        sense(&mut cache, Vec2::splat(10.0));
+        assert_eq!(evaluations.get(), 3);
+
+        // As is one that speeds up, or switches on its radar.
+        let changes: [fn(&mut SensorKey); 2] =
+            [|key| key.speed = 5.0, |key| key.emissions[0] = true];
+        for change in changes.iter() {
+            let mut changed = key(Vec2::splat(10.0));
+            change(&mut changed);
+            cache.begin_update();
+            cache.get_or_insert_with(id, changed, || {
+                evaluations.set(evaluations.get() + 1);
+                (true, 0.5)
+            });
+        }
+        assert_eq!(evaluations.get(), 5);
+
+        // Contact that isn't sensed for an update is forgotten.
+        cache.begin_update();
+        cache.begin_update();
+        assert_eq!(cache.len(), 0);
+        sense(&mut cache, Vec2::splat(10.0));
+        assert_eq!(evaluations.get(), 6);
+    }
+}
@@ -1,29 +1,78 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::bandwidth::Bandwidth;
 use crate::bot::*;
+use crate::classifier::Classifier;
+use crate::content_pack::ContentPack;
 use crate::entity_extension::EntityExtension;
+use crate::game_mode::game_mode_from_name;
+use crate::kill_feed::BroadcastState;
 use crate::player::*;
 use crate::protocol::*;
+use crate::sensor_cache::SensorCache;
 use crate::world::World;
-use common::entity::EntityType;
-use common::protocol::{Command, Update};
+use common::angle::Angle;
+use common::bounty::BountyMarker;
+use common::death_reason::DeathReason;
+use common::drawing::Drawing;
+use common::entity::{EntityData, EntityKind, EntityType};
+use common::envelope::HullEnvelopes;
+use common::protocol::{Cast, Command, Control, Interpolation, ReturnToPort, Scuttle, Update};
+use common::scheduler::Scheduler;
+use common::signal::{SignalKind, SignalMarker};
 use common::terrain::ChunkSet;
+use common::terrain_objective::TerrainObjective;
 use common::ticks::Ticks;
 use common::util::level_to_score;
+use core_protocol::dto::RateLimitedAction;
 use core_protocol::id::*;
+use core_protocol::{get_unix_time_now, UnixTime};
 use game_server::context::Context;
+use game_server::context_service::FriendlyFire;
 use game_server::game_service::GameArenaService;
-use game_server::player::{PlayerRepo, PlayerTuple};
-use log::{error, warn};
+use game_server::player::{PlayerData, PlayerRepo, PlayerTuple};
+use log::{error, info, warn};
+use rand::{thread_rng, Rng};
 use std::cell::UnsafeCell;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// A game server.
 pub struct Server {
     pub world: World,
     pub counter: Ticks,
+    pub scheduler: Scheduler<ServerTask>,
+    pub content_pack: Option<ContentPack>,
+    /// Engagement envelopes of each type of boat, computed once at startup.
+    pub envelopes: HashMap<EntityType, HullEnvelopes>,
+    /// Time without input after which a real player is warned that they are AFK.
+    pub afk_warning: Duration,
+    /// Time without input after which a real player's boat is scuttled.
+    pub afk_timeout: Duration,
+    /// Of the new players that left since last logged, by the type of the first boat they lost,
+    /// how many did, and how many of those respawned.
+    pub retention: HashMap<EntityType, (u32, u32)>,
+    /// When the current round of the game mode began, for the match archive.
+    pub round_start: UnixTime,
+}
+
+/// Periodic tasks of the [`Server`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ServerTask {
+    SampleScores,
+    LogPlaytime,
+    LogBenchmark,
+    ScuttleAfk,
+    ReturnToPort,
+    Scuttle,
+    Duels,
+    Escorts,
+    LogRetention,
+    Bounties,
+    TerrainObjectives,
+    SampleHeatmap,
 }
 
 /// Stores a player, and metadata related to it. Data stored here may only be accessed when processing,
@@ -31,6 +80,26 @@ pub struct Server {
 #[derive(Default, Debug)]
 pub struct ClientData {
     pub loaded_chunks: ChunkSet,
+    /// Chunks loaded at a coarse level of detail, which are never in loaded_chunks.
+    pub coarse_chunks: ChunkSet,
+    /// Whether the summary of the player's last life was already sent.
+    pub summary_sent: bool,
+    /// When teammates' signals were last sent.
+    pub signals_sent: Option<Instant>,
+    /// When the fleet leader's drawings were last sent, and to which team.
+    pub drawings_sent: Option<(TeamId, Instant)>,
+    /// Whether the content pack was already advertised.
+    pub content_pack_sent: bool,
+    /// Whether interpolation parameters were already sent.
+    pub interpolation_sent: bool,
+    /// Types of boat whose engagement envelopes were already sent, which the client caches.
+    pub envelopes_sent: HashSet<EntityType>,
+    /// What the player's sensors detected in previous updates.
+    pub sensor_cache: SensorCache,
+    /// How well the player's sensors classified each boat they detect.
+    pub classifier: Classifier,
+    /// Bandwidth used by updates, and resulting reduction in detail.
+    pub bandwidth: Bandwidth,
 }
 
 #[derive(Default)]
@@ -62,7 +131,142 @@ impl GameArenaService for Server {
         Self {
             world: World::new(6500.0),
             counter: Ticks::ZERO,
+            scheduler: {
+                let mut scheduler = Scheduler::new();
+                scheduler.repeat(ServerTask::SampleScores, Ticks::from_whole_secs(5));
+                scheduler.repeat_with_jitter(
+                    ServerTask::LogPlaytime,
+                    Ticks::from_whole_secs(60),
+                    Ticks::from_whole_secs(5),
+                );
+                scheduler.repeat(ServerTask::LogBenchmark, Ticks::from_whole_secs(60));
+                scheduler.repeat_with_jitter(
+                    ServerTask::LogRetention,
+                    Ticks::from_whole_secs(300),
+                    Ticks::from_whole_secs(5),
+                );
+                scheduler.repeat(ServerTask::ScuttleAfk, Ticks::from_whole_secs(1));
+                scheduler.repeat(ServerTask::ReturnToPort, Ticks::from_whole_secs(1));
+                scheduler.repeat(ServerTask::Scuttle, Ticks::from_whole_secs(1));
+                scheduler.repeat(ServerTask::Duels, Ticks::from_whole_secs(1));
+                scheduler.repeat(ServerTask::Escorts, Ticks::from_whole_secs(1));
+                scheduler.repeat(ServerTask::Bounties, BountyMarker::PERIOD);
+                scheduler.repeat(ServerTask::TerrainObjectives, Ticks::from_whole_secs(2));
+                scheduler.repeat(ServerTask::SampleHeatmap, Ticks::from_whole_secs(5));
+                scheduler
+            },
+            content_pack: None,
+            envelopes: EntityType::iter()
+                .filter(|entity_type| entity_type.data().kind == EntityKind::Boat)
+                .map(|entity_type| (entity_type, HullEnvelopes::new(entity_type)))
+                .collect(),
+            afk_warning: Duration::from_secs(120),
+            afk_timeout: Duration::from_secs(180),
+            retention: HashMap::new(),
+            round_start: get_unix_time_now(),
+        }
+    }
+
+    fn load_content_pack(&mut self, path: &str) -> Result<(), String> {
+        let content_pack = ContentPack::load(path)?;
+        info!(
+            "loaded {} entities from content pack {:016x}",
+            content_pack.info.entities.len(),
+            content_pack.info.hash
+        );
+        self.content_pack = Some(content_pack);
+        Ok(())
+    }
+
+    fn set_game_mode(&mut self, game_mode: &str) -> Result<(), String> {
+        self.world.game_mode = game_mode_from_name(game_mode)?;
+        Ok(())
+    }
+
+    fn set_radius(&mut self, radius: f32) -> Result<(), String> {
+        if !(radius.is_finite() && radius >= 1000.0) {
+            return Err(String::from("radius must be at least 1000"));
         }
+        self.world.radius = radius;
+        self.world.target_radius = radius;
+        Ok(())
+    }
+
+    fn set_afk_thresholds(
+        &mut self,
+        warning: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> Result<(), String> {
+        let warning = warning.unwrap_or(self.afk_warning);
+        let timeout = timeout.unwrap_or(self.afk_timeout);
+        if warning > timeout {
+            return Err(String::from("AFK warning must not be after timeout"));
+        }
+        self.afk_warning = warning;
+        self.afk_timeout = timeout;
+        Ok(())
+    }
+
+    fn set_day_length(&mut self, day_length: Duration) -> Result<(), String> {
+        self.world.day_length = day_length.as_secs_f32();
+        Ok(())
+    }
+
+    fn set_damage_rules(
+        &mut self,
+        friendly_fire: Option<FriendlyFire>,
+        self_damage: Option<bool>,
+        ram_damage: Option<f32>,
+    ) -> Result<(), String> {
+        let rules = &mut self.world.damage_rules;
+        if let Some(ram_damage) = ram_damage {
+            if !(0.0..=10.0).contains(&ram_damage) {
+                return Err(String::from("ram damage must be between 0 and 10"));
+            }
+            rules.ram_damage = ram_damage;
+        }
+        rules.friendly_fire = friendly_fire.unwrap_or(rules.friendly_fire);
+        rules.self_damage = self_damage.unwrap_or(rules.self_damage);
+        Ok(())
+    }
+
+    fn set_benchmark_budgets(&mut self, budgets: &str) -> Result<(), String> {
+        self.world
+            .benchmark
+            .set_budgets(budgets)
+            .map_err(String::from)
+    }
+
+    fn score_anomaly(score: u32, player: &Player) -> Option<&'static str> {
+        player.score_anomaly(score)
+    }
+
+    /// Formatted as `highest_level,banked_coins`. Older accounts only have `highest_level`.
+    fn account_unlocks(player: &Player) -> Option<String> {
+        Some(format!("{},{}", player.highest_level, player.banked_coins))
+    }
+
+    fn restore_account_unlocks(player: &mut Player, unlocks: &str) {
+        let mut fields = unlocks.split(',');
+        if let Some(Ok(level)) = fields.next().map(str::parse::<u8>) {
+            player.highest_level = player
+                .highest_level
+                .max(level.min(EntityData::MAX_BOAT_LEVEL));
+        }
+        if let Some(Ok(banked_coins)) = fields.next().map(str::parse::<u32>) {
+            player.banked_coins = player.banked_coins.max(banked_coins);
+        }
+    }
+
+    /// Reaching level 3 shows that an invited player stuck around.
+    fn referral_qualified(player: &Player) -> bool {
+        player.summary.highest_level >= 3
+    }
+
+    /// Banks coins, which are stored with the account, so that the reward isn't lost on death.
+    fn reward_referral(&mut self, player_tuple: &Arc<PlayerTuple<Self>>) {
+        let mut player = player_tuple.borrow_player_mut();
+        player.data.banked_coins = player.data.banked_coins.saturating_add(Self::REFERRAL_REWARD);
     }
 
     fn team_members_max(_players: usize) -> usize {
@@ -78,9 +282,7 @@ impl GameArenaService for Server {
         player.data.flags.left_game = false;
         #[cfg(debug_assertions)]
         {
-            use common::entity::EntityData;
             //use common::util::level_to_score;
-            use rand::{thread_rng, Rng};
             let highest_level_score = level_to_score(EntityData::MAX_BOAT_LEVEL);
             player.score = if player.is_bot() {
                 thread_rng().gen_range(0..=highest_level_score)
@@ -96,12 +298,35 @@ impl GameArenaService for Server {
         player: &Arc<PlayerTuple<Self>>,
         _players: &PlayerRepo<Server>,
     ) -> Option<Update> {
+        // Controls are only input if they change something (see `Player::record_control`).
+        if !matches!(update, Command::Control(_)) {
+            player.borrow_player_mut().data.last_input = Instant::now();
+        }
         if let Err(e) = update.as_command().apply(&mut self.world, player) {
             warn!("Command resulted in {}", e);
         }
         None
     }
 
+    fn rate_limited_action(command: &Command) -> Option<RateLimitedAction> {
+        match command {
+            Command::Control(Control { fire: Some(_), .. }) => Some(RateLimitedAction::Fire),
+            Command::Spawn(_) => Some(RateLimitedAction::Spawn),
+            _ => None,
+        }
+    }
+
+    /// Too much firing shouldn't also prevent steering.
+    fn rate_limited_remainder(command: Command) -> Option<Command> {
+        match command {
+            Command::Control(control) => Some(Command::Control(Control {
+                fire: None,
+                ..control
+            })),
+            _ => None,
+        }
+    }
+
     fn player_changed_team(
         &mut self,
         player_tuple: &Arc<PlayerTuple<Self>>,
@@ -130,10 +355,22 @@ impl GameArenaService for Server {
             drop(player);
         }
 
+        // Forfeit any duel, so that the opponent wins by default, and any escort contract.
+        let player_id = player_tuple.borrow_player().player_id;
+        self.world.duels.forfeit(player_id);
+        self.world.escorts.forfeit(player_id);
+
         let mut player = player_tuple.borrow_player_mut();
 
         // Clear player's score.
         player.score = 0;
+        player.data.casting = None;
+
+        if let Some((entity_type, respawned)) = player.data.first_hull.take() {
+            let (left, retained) = self.retention.entry(entity_type).or_default();
+            *left += 1;
+            *retained += respawned as u32;
+        }
 
         // Delete all player's entities (efficiently, in the next update cycle).
         player.data.flags.left_game = true;
@@ -143,13 +380,74 @@ impl GameArenaService for Server {
         &self,
         player: &Arc<PlayerTuple<Self>>,
         client_data: &mut Self::ClientData,
-        _players: &PlayerRepo<Server>,
+        players: &PlayerRepo<Server>,
     ) -> Option<Self::GameUpdate> {
-        Some(
-            self.world
-                .get_player_complete(player)
-                .into_update(self.counter, &mut client_data.loaded_chunks),
-        )
+        let casting = player.borrow_player().data.casting;
+        let mut update = if let Some(camera) = casting {
+            if self.counter % Cast::UPDATE_PERIOD != Ticks::ZERO {
+                return None;
+            }
+            let mut update = self
+                .world
+                .get_caster_complete(player, camera)
+                .into_update(self.counter, client_data);
+            update.scores = players
+                .iter_borrow()
+                .filter(|p| p.data.status.is_alive())
+                .map(|p| (p.player_id, p.score))
+                .collect();
+            update
+        } else {
+            // Taken so that contacts can borrow them while into_update borrows client_data.
+            let mut sensor_cache = std::mem::take(&mut client_data.sensor_cache);
+            let mut classifier = std::mem::take(&mut client_data.classifier);
+            sensor_cache.begin_update();
+            let mut ghosts = classifier.begin_update(self.counter);
+            ghosts.retain(|ghost| self.world.is_ghost_lost(player, ghost));
+            let mut update = self
+                .world
+                .get_player_complete(player, Some(&mut sensor_cache), Some(&mut classifier))
+                .into_update(self.counter, client_data);
+            {
+                let identified = &mut player.borrow_player_mut().data.identified;
+                identified.clear();
+                identified.extend(classifier.identified());
+            }
+            client_data.sensor_cache = sensor_cache;
+            client_data.classifier = classifier;
+            update.ghosts = ghosts;
+            update
+        };
+        update.signals = Self::team_signals(player, client_data, players);
+        update.drawings = Self::team_drawings(player, client_data, players);
+        if !client_data.content_pack_sent {
+            client_data.content_pack_sent = true;
+            update.content_pack = self
+                .content_pack
+                .as_ref()
+                .map(|content_pack| Box::new(content_pack.info.clone()));
+        }
+        if !client_data.interpolation_sent {
+            client_data.interpolation_sent = true;
+            update.interpolation = Some(Interpolation::default());
+        }
+        if let Some(entity_type) = update.entity_type {
+            if client_data.envelopes_sent.insert(entity_type) {
+                update.envelopes = self.envelopes.get(&entity_type).cloned().map(Box::new);
+            }
+        }
+        let player = player.borrow_player();
+        update.afk = self.is_afk(&player, self.afk_warning);
+        update.port_progress = player.data.returning_to_port.map(|since| {
+            (since.elapsed().as_secs_f32() / ReturnToPort::CHANNEL.as_secs_f32()).min(1.0)
+        });
+        update.scuttle_progress = player.data.scuttling.map(|since| {
+            (since.elapsed().as_secs_f32() / Scuttle::DELAY.as_secs_f32()).min(1.0)
+        });
+        update.banked_coins = player.data.banked_coins;
+        update.duels = self.world.duels.player_duels(player.player_id);
+        update.escorts = self.world.escorts.player_escorts(player.player_id);
+        Some(update)
     }
 
     fn is_alive(&self, player_tuple: &Arc<PlayerTuple<Self>>) -> bool {
@@ -163,81 +461,668 @@ impl GameArenaService for Server {
 
         self.world.update(Ticks::ONE);
 
+        if let Some(winner) = self.world.game_mode.round_winner(&context.players) {
+            info!("{:?} won the {} round", winner, self.world.game_mode.name());
+            self.end_round(context);
+        }
+
         // Needs to be called before clients receive updates, but after World::update.
         self.world.terrain.pre_update();
 
-        if self.counter.every(Ticks::from_whole_secs(60)) {
-            use std::collections::{BTreeMap, HashMap};
-            use std::fs::OpenOptions;
-            use std::io::{Read, Seek, Write};
-
-            let mut count_score = HashMap::<EntityType, (usize, f32)>::new();
-
-            for player in context.players.iter_borrow() {
-                if let Status::Alive { entity_index, .. } = player.status {
-                    let entity = &self.world.entities[entity_index];
-                    debug_assert!(entity.is_boat());
-
-                    let (current_count, current_score) =
-                        count_score.entry(entity.entity_type).or_default();
-                    *current_count += 1;
-
-                    let level = entity.data().level;
-                    let level_score = level_to_score(level);
-                    let next_level_score = level_to_score(level + 1);
-                    let progress = common_util::range::map_ranges(
-                        player.score as f32,
-                        level_score as f32..next_level_score as f32,
-                        0.0..1.0,
-                        false,
-                    );
-                    if progress.is_finite() {
-                        *current_score += progress;
+        for task in self.scheduler.update(Ticks::ONE) {
+            match task {
+                ServerTask::SampleScores => Self::sample_scores(context),
+                ServerTask::LogPlaytime => self.log_playtime(context),
+                ServerTask::LogRetention => self.log_retention(),
+                ServerTask::ScuttleAfk => self.scuttle_afk(context),
+                ServerTask::ReturnToPort => self.return_to_port(context),
+                ServerTask::Scuttle => self.scuttle(context),
+                ServerTask::Duels => self.world.duels.update(Instant::now()),
+                ServerTask::Escorts => self.world.escorts.update(Instant::now()),
+                ServerTask::Bounties => self.update_bounties(context),
+                ServerTask::TerrainObjectives => self.update_terrain_objectives(context),
+                ServerTask::SampleHeatmap => self.sample_heatmap(context),
+                ServerTask::LogBenchmark => {
+                    if let Some(report) = self.world.benchmark.take_report() {
+                        info!(
+                            "world update: {} (checksum {:08x})",
+                            report, self.world.checksum
+                        );
                     }
                 }
             }
+        }
 
-            tokio::task::spawn_blocking(move || {
-                if let Err(e) = OpenOptions::new()
-                    .create(true)
-                    .read(true)
-                    .write(true)
-                    .open(&*"playtime.json")
-                    .and_then(move |mut file| {
-                        let mut buf = Vec::new();
-                        file.read_to_end(&mut buf)?;
-                        let mut old = if let Ok(old) =
-                            serde_json::from_slice::<BTreeMap<EntityType, (u64, f32)>>(&buf)
-                        {
-                            old
-                        } else {
-                            error!("error loading old playtime.");
-                            BTreeMap::new()
-                        };
-
-                        for (entity_type, (new_count, new_score)) in count_score {
-                            if new_count > 0 {
-                                let (old_count, old_score) = old.entry(entity_type).or_default();
-                                *old_count = old_count.saturating_add(new_count as u64);
-                                *old_score += new_score;
-                            }
-                        }
+        for (player_id, event) in self.world.traces.drain(..) {
+            context.trace(player_id, &event);
+        }
+    }
 
-                        file.set_len(0)?;
-                        file.rewind()?;
+    fn post_update(&mut self, _context: &mut Context<Self>) {
+        // Needs to be after clients receive updates.
+        self.world.terrain.post_update();
+        self.world.audio_events.clear();
+    }
 
-                        let serialized = serde_json::to_vec(&old).unwrap_or_default();
-                        file.write_all(&serialized)
-                    })
+    fn broadcast_state(&self) -> Option<serde_json::Value> {
+        let state = BroadcastState {
+            game_mode: self.world.game_mode.name(),
+            world_radius: self.world.radius,
+            bounties: self.world.bounty_markers.clone(),
+            pirate_raid: self.world.pirate_raid.as_ref().map(|raid| raid.position),
+            kills: self.world.kill_feed.iter().cloned().collect(),
+        };
+        serde_json::to_value(state).ok()
+    }
+
+    fn checksum(&self) -> Option<u32> {
+        Some(self.world.checksum)
+    }
+
+    fn heatmap(&self) -> Option<String> {
+        // JSON maps can't have tuple keys, so cells (in multiples of
+        // [`crate::heatmap::Heatmap::CELL_SIZE`]) are listed along with their counts.
+        serde_json::to_string(&self.world.heatmap.cells()).ok()
+    }
+}
+
+impl Server {
+    /// Coins banked for inviting a player who reaches level 3.
+    const REFERRAL_REWARD: u32 = 500;
+
+    /// Archives the results of the round that just ended, and starts counting kills afresh.
+    fn end_round(&mut self, context: &mut Context<Self>) {
+        let participants: Vec<_> = context
+            .players
+            .iter_borrow_mut()
+            .filter_map(|mut player| {
+                let kills = std::mem::take(&mut player.data.round_kills);
+                (!player.is_bot()).then(|| (player.player_id, player.score, kills))
+            })
+            .collect();
+        let game_mode = self.world.game_mode.name();
+        context.record_match(game_mode, self.round_start, participants);
+        self.round_start = get_unix_time_now();
+    }
+
+    /// Whether a real player has had a boat, but no input, for at least `threshold`.
+    fn is_afk(&self, player: &PlayerData<Self>, threshold: Duration) -> bool {
+        !player.is_bot()
+            && player.data.status.is_alive()
+            && !player.data.flags.left_game
+            && player.data.last_input.elapsed() >= threshold
+    }
+
+    /// Scuttles the boats of players that have been AFK for too long, so they don't take up space
+    /// or give away free score. They spectate from where they sank, until they respawn.
+    fn scuttle_afk(&mut self, context: &Context<Self>) {
+        self.scuttle_afk_players(context.players.iter());
+    }
+
+    /// Scuttles the boats of those of `players` that have been AFK for too long.
+    fn scuttle_afk_players<'a>(
+        &mut self,
+        players: impl Iterator<Item = &'a Arc<PlayerTuple<Self>>>,
+    ) {
+        let afk: Vec<_> = players
+            .filter(|player_tuple| self.is_afk(&player_tuple.borrow_player(), self.afk_timeout))
+            .cloned()
+            .collect();
+
+        for player_tuple in afk {
+            // Must not borrow the player while removing its boat.
+            let entity_index = if let Status::Alive { entity_index, .. } =
+                player_tuple.borrow_player().data.status
+            {
+                entity_index
+            } else {
+                continue;
+            };
+            self.world.remove(entity_index, DeathReason::Afk);
+        }
+    }
+
+    /// Cancels returning to port for boats that have enemies nearby (taking damage cancels it
+    /// too, see [`Mutation`][crate::world_mutation::Mutation]), and removes the boats that stayed
+    /// safe for long enough, banking part of their players' score.
+    fn return_to_port(&mut self, context: &Context<Self>) {
+        let returning: Vec<_> = context
+            .players
+            .iter()
+            .filter(|player_tuple| player_tuple.borrow_player().data.returning_to_port.is_some())
+            .cloned()
+            .collect();
+
+        for player_tuple in returning {
+            let player = player_tuple.borrow_player();
+            let since = player.data.returning_to_port.unwrap();
+            let entity_index = match player.data.status {
+                Status::Alive { entity_index, .. } if !player.data.flags.left_game => {
+                    Some(entity_index)
+                }
+                _ => None,
+            };
+            drop(player);
+
+            let entity_index = match entity_index {
+                Some(entity_index) if self.world.in_safe_area(entity_index) => entity_index,
+                _ => {
+                    player_tuple.borrow_player_mut().data.returning_to_port = None;
+                    continue;
+                }
+            };
+
+            if since.elapsed() < ReturnToPort::CHANNEL {
+                continue;
+            }
+
+            let mut player = player_tuple.borrow_player_mut();
+            let banked = (player.score as f32 * ReturnToPort::BANK_FRACTION) as u32;
+            player.score -= banked;
+            player.data.banked_coins = player.data.banked_coins.saturating_add(banked);
+            player.data.returning_to_port = None;
+            drop(player);
+            self.world.remove(entity_index, DeathReason::ReturnedToPort(banked));
+        }
+    }
+
+    /// Cancels scuttling for boats that took damage recently, and removes the boats that have
+    /// been scuttling for long enough.
+    fn scuttle(&mut self, context: &Context<Self>) {
+        let scuttling: Vec<_> = context
+            .players
+            .iter()
+            .filter(|player_tuple| player_tuple.borrow_player().data.scuttling.is_some())
+            .cloned()
+            .collect();
+
+        for player_tuple in scuttling {
+            let player = player_tuple.borrow_player();
+            let since = player.data.scuttling.unwrap();
+            let entity_index = match player.data.status {
+                Status::Alive { entity_index, .. } if !player.data.flags.left_game => {
+                    Some(entity_index)
+                }
+                _ => None,
+            };
+            drop(player);
+
+            let entity_index = match entity_index {
+                Some(entity_index)
+                    if !self.world.entities[entity_index].extension().in_combat() =>
                 {
-                    error!("error logging playtime: {:?}", e);
+                    entity_index
+                }
+                _ => {
+                    player_tuple.borrow_player_mut().data.scuttling = None;
+                    continue;
                 }
-            });
+            };
+
+            if since.elapsed() < Scuttle::DELAY {
+                continue;
+            }
+
+            player_tuple.borrow_player_mut().data.scuttling = None;
+            self.world.remove(entity_index, DeathReason::Scuttled);
         }
     }
 
-    fn post_update(&mut self, _context: &mut Context<Self>) {
-        // Needs to be after clients receive updates.
-        self.world.terrain.post_update();
+    /// Places bounties on the top players of the liveboard, or grows their existing bounties, and
+    /// moves the markers of bounties to near where those players are now. Other players' bounties
+    /// are called off.
+    fn update_bounties(&mut self, context: &Context<Self>) {
+        let top: Vec<PlayerId> = context
+            .liveboard()
+            .iter()
+            .take(BountyMarker::TOP_PLAYERS)
+            .map(|dto| dto.player_id)
+            .collect();
+
+        let mut rng = thread_rng();
+        self.world.bounty_markers.clear();
+        for player_tuple in context.players.iter() {
+            let mut player = player_tuple.borrow_player_mut();
+            if !top.contains(&player.player_id) {
+                player.data.bounty = 0;
+                continue;
+            }
+            player.data.bounty = BountyMarker::grow(player.data.bounty);
+
+            if let Status::Alive { entity_index, .. } = player.data.status {
+                let offset = rng.gen::<Angle>().to_vec() * (BountyMarker::ERROR * rng.gen::<f32>());
+                self.world.bounty_markers.push(BountyMarker {
+                    player_id: player.player_id,
+                    position: self.world.entities[entity_index].transform.position + offset,
+                    bounty: player.data.bounty,
+                });
+            }
+        }
+    }
+
+    /// Pays players whose terrain objectives were completed, withdraws expired objectives, and
+    /// generates new objectives for players whose boats can edit terrain.
+    fn update_terrain_objectives(&mut self, context: &Context<Self>) {
+        let now = Instant::now();
+        let mut rng = thread_rng();
+        for player_tuple in context.players.iter() {
+            let mut player = player_tuple.borrow_player_mut();
+            let boat = if let Status::Alive { entity_index, .. } = player.data.status {
+                Some(&self.world.entities[entity_index])
+            } else {
+                None
+            };
+            let boat = match boat {
+                Some(boat) if !player.is_bot() && TerrainObjective::can_pursue(boat.entity_type) => {
+                    boat
+                }
+                _ => {
+                    player.data.terrain_objective = None;
+                    continue;
+                }
+            };
+
+            match player.data.terrain_objective {
+                Some((objective, _)) if objective.is_complete(&self.world.terrain) => {
+                    player.score = player.score.saturating_add(objective.reward());
+                    player.data.terrain_objective = None;
+                }
+                Some((_, expires)) if expires <= now => {
+                    player.data.terrain_objective = None;
+                }
+                Some(_) => {}
+                None => {
+                    player.data.terrain_objective = TerrainObjective::generate(
+                        &self.world.terrain,
+                        boat.transform.position,
+                        self.world.radius,
+                        &mut rng,
+                    )
+                    .map(|objective| (objective, now + TerrainObjective::TIME_LIMIT));
+                }
+            }
+        }
+    }
+
+    /// Samples the score of each living player, for their summary.
+    fn sample_scores(context: &mut Context<Self>) {
+        for mut player in context.players.iter_borrow_mut() {
+            if player.data.status.is_alive() {
+                let score = player.score;
+                player.data.summary.sample_score(score);
+            }
+        }
+    }
+
+    /// Accumulates the playtime and progress of each boat type in `playtime.json`.
+    fn log_playtime(&self, context: &Context<Self>) {
+        use std::collections::BTreeMap;
+        use std::fs::OpenOptions;
+        use std::io::{Read, Seek, Write};
+
+        let mut count_score = HashMap::<EntityType, (usize, f32)>::new();
+
+        for player in context.players.iter_borrow() {
+            if let Status::Alive { entity_index, .. } = player.status {
+                let entity = &self.world.entities[entity_index];
+                debug_assert!(entity.is_boat());
+
+                let (current_count, current_score) =
+                    count_score.entry(entity.entity_type).or_default();
+                *current_count += 1;
+
+                let level = entity.data().level;
+                let level_score = level_to_score(level);
+                let next_level_score = level_to_score(level + 1);
+                let progress = common_util::range::map_ranges(
+                    player.score as f32,
+                    level_score as f32..next_level_score as f32,
+                    0.0..1.0,
+                    false,
+                );
+                if progress.is_finite() {
+                    *current_score += progress;
+                }
+            }
+        }
+
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = OpenOptions::new()
+                .create(true)
+                .read(true)
+                .write(true)
+                .open(&*"playtime.json")
+                .and_then(move |mut file| {
+                    let mut buf = Vec::new();
+                    file.read_to_end(&mut buf)?;
+                    let mut old = if let Ok(old) =
+                        serde_json::from_slice::<BTreeMap<EntityType, (u64, f32)>>(&buf)
+                    {
+                        old
+                    } else {
+                        error!("error loading old playtime.");
+                        BTreeMap::new()
+                    };
+
+                    for (entity_type, (new_count, new_score)) in count_score {
+                        if new_count > 0 {
+                            let (old_count, old_score) = old.entry(entity_type).or_default();
+                            *old_count = old_count.saturating_add(new_count as u64);
+                            *old_score += new_score;
+                        }
+                    }
+
+                    file.set_len(0)?;
+                    file.rewind()?;
+
+                    let serialized = serde_json::to_vec(&old).unwrap_or_default();
+                    file.write_all(&serialized)
+                })
+            {
+                error!("error logging playtime: {:?}", e);
+            }
+        });
+    }
+
+    /// Accumulates, in `retention.json`, how many new players left after losing their first boat,
+    /// and how many of those respawned before leaving, by the type of that boat.
+    fn log_retention(&mut self) {
+        use std::collections::BTreeMap;
+        use std::fs::OpenOptions;
+        use std::io::{Read, Seek, Write};
+
+        let new = std::mem::take(&mut self.retention);
+        if new.is_empty() {
+            return;
+        }
+
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = OpenOptions::new()
+                .create(true)
+                .read(true)
+                .write(true)
+                .open(&*"retention.json")
+                .and_then(move |mut file| {
+                    let mut buf = Vec::new();
+                    file.read_to_end(&mut buf)?;
+                    let mut old = if let Ok(old) =
+                        serde_json::from_slice::<BTreeMap<EntityType, (u64, u64)>>(&buf)
+                    {
+                        old
+                    } else {
+                        error!("error loading old retention.");
+                        BTreeMap::new()
+                    };
+
+                    for (entity_type, (new_left, new_retained)) in new {
+                        let (old_left, old_retained) = old.entry(entity_type).or_default();
+                        *old_left = old_left.saturating_add(new_left as u64);
+                        *old_retained = old_retained.saturating_add(new_retained as u64);
+                    }
+
+                    file.set_len(0)?;
+                    file.rewind()?;
+
+                    let serialized = serde_json::to_vec(&old).unwrap_or_default();
+                    file.write_all(&serialized)
+                })
+            {
+                error!("error logging retention: {:?}", e);
+            }
+        });
+    }
+
+    /// Samples the position of each living boat, for the traffic of the heatmap.
+    fn sample_heatmap(&mut self, context: &Context<Self>) {
+        for player in context.players.iter_borrow() {
+            if let Status::Alive { entity_index, .. } = player.status {
+                let position = self.world.entities[entity_index].transform.position;
+                self.world.heatmap.record_traffic(position);
+            }
+        }
+    }
+
+    /// Gets signals placed by the player's team (including the player) since they were last sent.
+    fn team_signals(
+        player_tuple: &Arc<PlayerTuple<Self>>,
+        client_data: &mut ClientData,
+        players: &PlayerRepo<Server>,
+    ) -> Vec<SignalMarker> {
+        let now = Instant::now();
+        let since = client_data.signals_sent.replace(now);
+
+        let team_id = if let Some(team_id) = player_tuple.borrow_player().team_id() {
+            team_id
+        } else {
+            return Vec::new();
+        };
+
+        players
+            .iter_borrow()
+            .filter(|p| p.team_id() == Some(team_id))
+            .filter_map(|p| Self::new_signal(&p, since))
+            .collect()
+    }
+
+    /// Gets the player's signal, if it is still shown and wasn't placed before `since` (i.e. it
+    /// wasn't already sent).
+    fn new_signal(player: &PlayerData<Self>, since: Option<Instant>) -> Option<SignalMarker> {
+        let (signal, time) = player.data.last_signal.as_ref()?;
+        (since.map_or(true, |since| *time >= since) && time.elapsed() < SignalKind::LIFESPAN).then(
+            || SignalMarker {
+                player_id: player.player_id,
+                kind: signal.kind,
+                position: signal.position,
+            },
+        )
+    }
+
+    /// Gets drawings placed by the player's fleet leader since they were last sent. Players that
+    /// just joined the team get all current drawings.
+    fn team_drawings(
+        player_tuple: &Arc<PlayerTuple<Self>>,
+        client_data: &mut ClientData,
+        players: &PlayerRepo<Server>,
+    ) -> Vec<Drawing> {
+        let team_id = if let Some(team_id) = player_tuple.borrow_player().team_id() {
+            team_id
+        } else {
+            client_data.drawings_sent = None;
+            return Vec::new();
+        };
+
+        let now = Instant::now();
+        let since = client_data
+            .drawings_sent
+            .replace((team_id, now))
+            .filter(|&(previous_team_id, _)| previous_team_id == team_id)
+            .map(|(_, since)| since);
+
+        players
+            .iter_borrow()
+            .filter(|p| p.team_id() == Some(team_id) && p.is_team_captain())
+            .flat_map(|p| {
+                p.data
+                    .drawings
+                    .iter()
+                    .filter(|(_, time)| {
+                        since.map_or(true, |since| *time >= since)
+                            && time.elapsed() < Drawing::LIFESPAN
+                    })
+                    .map(|(drawing, _)| *drawing)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::player::Player;
+    use crate::protocol::AsCommandTrait;
+    use crate::server::Server;
+    use crate::world::World;
+    use common::cosmetic::{Cosmetics, Flag, HullPaint};
+    use common::entity::EntityType;
+    use common::protocol::{Command, Control, Fire, Scuttle, Signal};
+    use common::signal::SignalKind;
+    use core_protocol::dto::RateLimitedAction;
+    use game_server::game_service::GameArenaService;
+    use glam::Vec2;
+    use std::iter;
+    use std::time::{Duration, Instant};
+
+    fn control(aim_target: Vec2) -> Command {
+        Command::Control(Control {
+            guidance: None,
+            submerge: false,
+            periscope: false,
+            aim_target: Some(aim_target),
+            active: false,
+            fire: None,
+            pay: None,
+            hint: None,
+            horn: false,
+            repair_crew: false,
+            searchlight: false,
+            emcon: false,
+            throttle: None,
+            cruise: false,
+            collision_assist: false,
+            turret_locks: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn scuttle_afk() {
+        let mut server = Server::new(0);
+        server.world = World::test_empty(1000.0);
+        let (player, _) = server
+            .world
+            .test_boat(EntityType::Zubr, u32::MAX, Vec2::ZERO);
+        let idle = |server: &mut Server| {
+            player.borrow_player_mut().data.last_input = Instant::now() - server.afk_timeout;
+        };
+
+        control(Vec2::new(100.0, 0.0))
+            .as_command()
+            .apply(&mut server.world, &player)
+            .unwrap();
+
+        // Moving the mouse is input.
+        idle(&mut server);
+        control(Vec2::new(0.0, 100.0))
+            .as_command()
+            .apply(&mut server.world, &player)
+            .unwrap();
+        server.scuttle_afk_players(iter::once(&player));
+        assert!(player.borrow_player().data.status.is_alive());
+
+        // Clients resend controls even if the player is away, which isn't.
+        idle(&mut server);
+        control(Vec2::new(0.0, 100.0))
+            .as_command()
+            .apply(&mut server.world, &player)
+            .unwrap();
+        server.scuttle_afk_players(iter::once(&player));
+        assert!(!player.borrow_player().data.status.is_alive());
+    }
+
+    #[test]
+    fn account_unlocks() {
+        let cosmetics = Cosmetics {
+            flag: Flag::Pirate,
+            paint: HullPaint::Crimson,
+        };
+
+        assert_eq!(
+            cosmetics.unlocked(Player::default().highest_level),
+            Cosmetics::default()
+        );
+        let player = Player {
+            highest_level: Flag::Pirate.unlock_level(),
+            banked_coins: 42,
+            ..Player::default()
+        };
+
+        // Unlocks survive signing in on another device (or after a restart).
+        let unlocks = Server::account_unlocks(&player).unwrap();
+        let mut restored = Player::default();
+        Server::restore_account_unlocks(&mut restored, &unlocks);
+        assert_eq!(restored.highest_level, player.highest_level);
+        assert_eq!(restored.banked_coins, 42);
+        assert_eq!(cosmetics.unlocked(restored.highest_level), cosmetics);
+
+        // Restoring never takes away progress from the current session.
+        let mut ahead = Player {
+            highest_level: 8,
+            ..Player::default()
+        };
+        Server::restore_account_unlocks(&mut ahead, &unlocks);
+        assert_eq!(ahead.highest_level, 8);
+
+        // Older accounts only stored the level, and garbage is ignored.
+        let mut old = Player::default();
+        Server::restore_account_unlocks(&mut old, "4");
+        assert_eq!(old.highest_level, 4);
+        assert_eq!(old.banked_coins, 0);
+        Server::restore_account_unlocks(&mut old, "x,y");
+        assert_eq!(old.highest_level, 4);
+    }
+
+    #[test]
+    fn rate_limited_remainder() {
+        let mut command = control(Vec2::new(100.0, 0.0));
+        if let Command::Control(control) = &mut command {
+            control.fire = Some(Fire { armament_index: 0 });
+        }
+        assert_eq!(
+            Server::rate_limited_action(&command),
+            Some(RateLimitedAction::Fire)
+        );
+
+        // Only firing is dropped; the rest of the control still applies.
+        match Server::rate_limited_remainder(command) {
+            Some(Command::Control(control)) => {
+                assert!(control.fire.is_none());
+                assert_eq!(control.aim_target, Some(Vec2::new(100.0, 0.0)));
+            }
+            _ => panic!("expected control"),
+        }
+        assert!(
+            Server::rate_limited_remainder(Command::Scuttle(Scuttle { cancel: false })).is_none()
+        );
+    }
+
+    #[test]
+    fn signals() {
+        let mut world = World::test_empty(1000.0);
+        let (player, _) = world.test_boat(EntityType::Zubr, u32::MAX, Vec2::ZERO);
+        let signal = Signal {
+            kind: SignalKind::Help,
+            position: Vec2::new(100.0, 0.0),
+        };
+
+        // Signals are only for teammates.
+        assert!(Command::Signal(signal.clone())
+            .as_command()
+            .apply(&mut world, &player)
+            .is_err());
+        assert!(player.borrow_player().data.last_signal.is_none());
+
+        let now = Instant::now();
+        player.borrow_player_mut().data.last_signal = Some((signal.clone(), now));
+        let marker = Server::new_signal(&player.borrow_player(), None).unwrap();
+        assert_eq!(marker.kind, SignalKind::Help);
+        assert_eq!(marker.position, signal.position);
+        assert_eq!(marker.player_id, player.borrow_player().player_id);
+
+        // Not sent again.
+        let since = now + Duration::from_millis(1);
+        assert!(Server::new_signal(&player.borrow_player(), Some(since)).is_none());
+
+        // Expired.
+        player.borrow_player_mut().data.last_signal = Some((signal, now - SignalKind::LIFESPAN));
+        assert!(Server::new_signal(&player.borrow_player(), None).is_none());
     }
 }
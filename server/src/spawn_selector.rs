@@ -0,0 +1,116 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::world::World;
+use common::entity::{EntityKind, EntityType};
+use common::world::distance_to_soft_area_border;
+use common_util::range::gen_radius;
+use glam::Vec2;
+use rand::Rng;
+
+/// Chooses where a boat should spawn, by scoring candidate positions by their distance from
+/// recent killers, proximity to teammates, and nearby loot.
+#[derive(Debug, Default)]
+pub struct SpawnSelector {
+    /// Positions of boats whose players recently sank the spawning player.
+    pub threats: Vec<Vec2>,
+    /// Positions of teammates' boats.
+    pub allies: Vec<Vec2>,
+}
+
+impl SpawnSelector {
+    /// Number of random candidates, in addition to the center.
+    const CANDIDATES: usize = 16;
+    /// Threats further than this are as good as absent.
+    const THREAT_RANGE: f32 = 3000.0;
+    /// Allies further than this are as good as absent.
+    const ALLY_RANGE: f32 = 2000.0;
+    /// Radius around a candidate in which loot is counted.
+    const LOOT_RANGE: f32 = 400.0;
+    /// Amount of loot beyond which a candidate is not considered any better.
+    const MAX_LOOT: usize = 10;
+    /// Radius around the selected position that spawning may adjust within, to avoid collisions.
+    pub const SPREAD: f32 = 500.0;
+
+    /// Returns the best of several random positions within `radius` of `center` that are in the
+    /// world and in the right area for `entity_type`, or `center` if there are none.
+    pub fn select(
+        &self,
+        world: &World,
+        entity_type: EntityType,
+        center: Vec2,
+        radius: f32,
+        rng: &mut impl Rng,
+    ) -> Vec2 {
+        if radius <= 0.0 {
+            return center;
+        }
+
+        std::iter::once(center)
+            .chain((0..Self::CANDIDATES).map(|_| center + gen_radius(rng, radius)))
+            .filter(|&position| {
+                position.length() <= world.radius
+                    && distance_to_soft_area_border(entity_type, position)
+                        > entity_type.data().radius
+            })
+            .map(|position| (position, self.score(position, Self::loot(world, position))))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(position, _)| position)
+            .unwrap_or(center)
+    }
+
+    /// Counts loot near `position`, up to [`Self::MAX_LOOT`].
+    fn loot(world: &World, position: Vec2) -> usize {
+        world
+            .entities
+            .iter_radius(position, Self::LOOT_RANGE)
+            .filter(|(_, entity)| entity.data().kind == EntityKind::Collectible)
+            .take(Self::MAX_LOOT)
+            .count()
+    }
+
+    /// Scores a candidate position, given the amount of loot near it. Higher is better. Safety
+    /// from threats outweighs both company and loot.
+    fn score(&self, position: Vec2, loot: usize) -> f32 {
+        let nearest = |positions: &[Vec2]| {
+            positions
+                .iter()
+                .map(|p| p.distance(position))
+                .fold(f32::INFINITY, f32::min)
+        };
+
+        let safety = (nearest(&self.threats) / Self::THREAT_RANGE).min(1.0);
+        let company = 1.0 - (nearest(&self.allies) / Self::ALLY_RANGE).min(1.0);
+        let loot = loot.min(Self::MAX_LOOT) as f32 / Self::MAX_LOOT as f32;
+
+        3.0 * safety + company + 0.5 * loot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spawn_selector::SpawnSelector;
+    use glam::Vec2;
+
+    #[test]
+    fn score() {
+        let selector = SpawnSelector {
+            threats: vec![Vec2::ZERO],
+            allies: vec![Vec2::new(2000.0, 0.0)],
+        };
+
+        let near_threat = selector.score(Vec2::new(500.0, 0.0), 0);
+        let near_ally = selector.score(Vec2::new(1900.0, 0.0), 0);
+        let far = selector.score(Vec2::new(-2500.0, 0.0), 0);
+        assert!(near_ally > far);
+        assert!(far > near_threat);
+
+        // Loot is a tiebreaker, not a reason to spawn near a threat.
+        assert!(selector.score(Vec2::new(500.0, 0.0), 10) < near_ally);
+        assert!(selector.score(Vec2::new(1900.0, 0.0), 10) > near_ally);
+
+        // Nothing to avoid or seek.
+        let empty = SpawnSelector::default();
+        assert_eq!(empty.score(Vec2::ZERO, 0), empty.score(Vec2::ONE, 0));
+    }
+}
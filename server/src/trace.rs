@@ -0,0 +1,26 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::entity::EntityType;
+use core_protocol::id::PlayerId;
+use glam::Vec2;
+use serde::Serialize;
+
+/// Key world events involving a player, recorded in the trace log alongside their inputs while
+/// they are being traced, so that incidents can be investigated and replayed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceEvent {
+    /// The player's boat spawned.
+    Spawned {
+        entity_type: EntityType,
+        position: Vec2,
+    },
+    /// The player sank another player's boat.
+    Sank { victim: PlayerId, position: Vec2 },
+    /// The player's boat sank.
+    Sunk {
+        entity_type: EntityType,
+        position: Vec2,
+    },
+}
@@ -2,14 +2,44 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::arena::Arena;
+use crate::benchmark::Benchmark;
+use crate::damage_rules::DamageRules;
+use crate::duel::DuelRepo;
+use crate::escort::EscortRepo;
 use crate::entities::{Entities, EntityIndex};
 use crate::entity::Entity;
+use crate::game_mode::{FreeForAll, GameMode};
+use crate::heatmap::Heatmap;
+use crate::kill_feed::KillFeed;
 use crate::noise::noise_generator;
+use crate::server::Server;
+use crate::trace::TraceEvent;
+use crate::world_area_effects::AreaEffectState;
 use crate::world_mutation::Mutation;
+use crate::world_pirates::PirateRaid;
+use crate::world_structures::StructureDefense;
+use common::audio_event::AudioEvent;
+use common::beam::Beam;
+use common::bounty::BountyMarker;
+use common::day_night::TimeOfDay;
 use common::death_reason::DeathReason;
+use common::intercept::Intercept;
+use common::protocol::ReturnToPort;
+use common::sea_state::SeaState;
 //use common::entity::{EntityKind, EntityType};
-use common::terrain::Terrain;
+use common::entity::EntityId;
+use common::terrain::{Terrain, TerrainMutation};
 use common::ticks::Ticks;
+use common_util::hash::{hash_f32s, CompatHasher};
+use core_protocol::id::PlayerId;
+use game_server::player::PlayerTuple;
+use glam::Vec2;
+use maybe_parallel_iterator::IntoMaybeParallelIterator;
+use rand::Rng;
+use std::collections::HashMap;
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::time::Instant;
 
 /// A game world of variable radius, consisting of entities and a terrain.
 pub struct World {
@@ -17,9 +47,74 @@ pub struct World {
     pub entities: Entities,
     pub terrain: Terrain,
     pub radius: f32,
+    /// What radius tends towards.
+    pub target_radius: f32,
+    /// Time spent in each phase of update.
+    pub benchmark: Benchmark,
+    /// Result of [`Self::checksum`] as of the latest update.
+    pub checksum: u32,
+    /// Rules of the arena, such as scoring.
+    pub game_mode: Box<dyn GameMode>,
+    /// Friendly fire, self damage, and ram damage.
+    pub damage_rules: DamageRules,
+    /// Difficulty of each HQ and oil platform, by id (which, unlike index, is stable).
+    pub structures: HashMap<EntityId, StructureDefense>,
+    /// Pirate squadron waiting for bots to crew it, if any.
+    pub pirate_raid: Option<PirateRaid>,
+    /// Time until the next pirate raid is planned.
+    pub pirate_cooldown: Ticks,
+    /// Oil slicks and fires.
+    pub area_effects: Vec<AreaEffectState>,
+    /// Weapons shot down by point defenses in the latest update, and the defender that shot them
+    /// down, which must be a contact for the intercept to be sent.
+    pub intercepts: Vec<(EntityId, Intercept)>,
+    /// What the seeker of each homing weapon acquired in the latest update, by the weapon's id.
+    pub seeker_targets: HashMap<EntityId, EntityId>,
+    /// Beam weapons fired in the latest update.
+    pub beams: Vec<Beam>,
+    /// Sounds made since clients last received updates, for those that can hear but not see them.
+    pub audio_events: Vec<AudioEvent>,
+    /// Time until active sonars next ping.
+    pub sonar_ping_cooldown: Ticks,
+    /// Duels between players, and challenges to duel, whose stakes are held in escrow.
+    pub duels: DuelRepo,
+    /// Contracts to escort cargo, and offers of them.
+    pub escorts: EscortRepo,
+    /// Bounties on the top players, as of the last time they were updated.
+    pub bounty_markers: Vec<BountyMarker>,
+    /// Where gameplay events happened since the arena started.
+    pub heatmap: Heatmap,
+    /// Recent kills, for the broadcast feed.
+    pub kill_feed: KillFeed,
+    /// Events involving traced players, to be recorded in the trace log.
+    pub traces: Vec<(PlayerId, TraceEvent)>,
+    /// Position in the day/night cycle.
+    pub time_of_day: TimeOfDay,
+    /// Seconds per day/night cycle, or zero for perpetual day.
+    pub day_length: f32,
+    /// Wind and waves, which slow small boats.
+    pub sea_state: SeaState,
+    /// Seconds the sea state has been advanced by.
+    pub sea_time: f32,
+    /// Buffers reused by each update.
+    pub scratch: Scratch,
+}
+
+/// Buffers that are reused between updates, to avoid allocating every tick. They are empty
+/// outside of the phase that uses them.
+#[derive(Default)]
+pub struct Scratch {
+    pub(crate) mutations: Vec<(EntityIndex, Mutation)>,
+    pub(crate) swept_hits: Vec<(EntityIndex, f32, EntityIndex, Mutation)>,
+    pub(crate) terrain_mutations: Vec<(EntityIndex, TerrainMutation, bool)>,
+    pub(crate) barrel_spawns: Vec<(EntityIndex, Vec2)>,
+    pub(crate) reset_flags: Vec<Arc<PlayerTuple<Server>>>,
 }
 
 impl World {
+    /// Default seconds per day/night cycle.
+    const DAY_LENGTH: f32 = 20.0 * 60.0;
+
     /// Creates a new World with the given parameters.
     pub fn new(initial_radius: f32) -> Self {
         Self {
@@ -27,15 +122,89 @@ impl World {
             entities: Entities::new(),
             terrain: Terrain::with_generator(noise_generator),
             radius: initial_radius,
+            target_radius: 6500.0,
+            benchmark: Benchmark::default(),
+            checksum: 0,
+            game_mode: Box::new(FreeForAll),
+            damage_rules: DamageRules::default(),
+            structures: HashMap::new(),
+            pirate_raid: None,
+            pirate_cooldown: PirateRaid::INTERVAL,
+            area_effects: Vec::new(),
+            intercepts: Vec::new(),
+            seeker_targets: HashMap::new(),
+            beams: Vec::new(),
+            audio_events: Vec::new(),
+            sonar_ping_cooldown: Ticks::ZERO,
+            duels: DuelRepo::default(),
+            escorts: EscortRepo::default(),
+            bounty_markers: Vec::new(),
+            heatmap: Heatmap::default(),
+            kill_feed: KillFeed::default(),
+            traces: Vec::new(),
+            time_of_day: TimeOfDay::NOON,
+            day_length: Self::DAY_LENGTH,
+            sea_state: SeaState::at(rand::thread_rng().gen(), 0.0),
+            sea_time: 0.0,
+            scratch: Scratch::default(),
         }
     }
 
     /// Updates the internals of the world, spawning and updating existing entities.
     pub fn update(&mut self, delta: Ticks) {
+        let start = Instant::now();
         self.spawn_statics(delta);
+        self.benchmark.record("spawn_statics", start);
+
+        let start = Instant::now();
+        self.update_collision_assist();
+        self.benchmark.record("collision_assist", start);
+
+        // Records its own phases.
         self.physics(delta);
+
+        let start = Instant::now();
         self.physics_radius(delta);
+        self.benchmark.record("physics_radius", start);
+
+        let start = Instant::now();
+        self.update_point_defense(delta);
+        self.benchmark.record("point_defense", start);
+
+        let start = Instant::now();
+        self.update_beams(delta);
+        self.benchmark.record("beams", start);
+
+        let start = Instant::now();
+        self.update_sonar_pings(delta);
+        self.benchmark.record("sonar_pings", start);
+
+        let start = Instant::now();
+        self.update_structures(delta);
+        self.benchmark.record("structures", start);
+
+        let start = Instant::now();
+        self.update_pirates(delta);
+        self.benchmark.record("pirates", start);
+
+        let start = Instant::now();
+        self.update_area_effects(delta);
+        self.benchmark.record("area_effects", start);
+
+        let start = Instant::now();
+        self.update_target_locks();
+        self.benchmark.record("target_locks", start);
+
+        let start = Instant::now();
+        self.update_threat_warnings();
+        self.benchmark.record("threat_warnings", start);
+
+        let start = Instant::now();
+        self.checksum = self.checksum();
+        self.benchmark.record("checksum", start);
+
         self.arena.recycle();
+        self.benchmark.end_update();
 
         // let total_visual_area = EntityType::iter()
         //     .map(|t| {
@@ -48,11 +217,17 @@ impl World {
         //     })
         //     .sum::<f32>();
 
-        let target_radius: f32 = 6500.0; 
         let s = delta.to_secs();
 
+        if self.day_length > 0.0 {
+            self.time_of_day = self.time_of_day.advance(s, self.day_length);
+        }
+
+        self.sea_time += s;
+        self.sea_state = self.sea_state.advance(self.sea_time, s);
+
         
-        self.radius += (target_radius - self.radius).clamp(-s, 2.0 * s);
+        self.radius += (self.target_radius - self.radius).clamp(-s, 2.0 * s);
     }
 
     /// Adds an entity to the world (assigning it an id).
@@ -69,6 +244,41 @@ impl World {
         self.arena.drop_entity(entity);
     }
 
+    /// Returns whether no enemy boats are within [`ReturnToPort::SAFE_RANGE`] of a boat.
+    pub fn in_safe_area(&self, index: EntityIndex) -> bool {
+        let boat = &self.entities[index];
+        !self
+            .entities
+            .iter_radius(boat.transform.position, ReturnToPort::SAFE_RANGE)
+            .any(|(other_index, other)| {
+                other_index != index && other.is_boat() && !boat.is_friendly(other)
+            })
+    }
+
+    /// Cheaply hashes the type, position, direction, velocity, altitude, and damage (or lifetime)
+    /// of every entity, so that diverging copies of a simulation can be detected. Independent of
+    /// the order of entities, and of the platform.
+    pub fn checksum(&self) -> u32 {
+        self.entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .map(|(_, entity)| {
+                let mut hasher = CompatHasher::default();
+                hasher.write_u32(entity.id.get());
+                hasher.write_u32(entity.entity_type as u32);
+                hash_f32s(entity.transform.position, &mut hasher);
+                hasher.write_u16(entity.transform.direction.0 as u16);
+                hasher.write_u32(entity.transform.velocity.0 as u32);
+                hasher.write_u8(entity.altitude.0 as u8);
+                hasher.write_u16(entity.ticks.0);
+                // Summing in 64 bits, then truncating, is the same as wrapping addition.
+                hasher.finish() as u32 as u64
+            })
+            .collect::<Vec<u64>>()
+            .into_iter()
+            .sum::<u64>() as u32
+    }
+
     /// Returns the area of the world, based on it's radius.
     pub fn area(&self) -> f32 {
         self.radius.powi(2) * std::f32::consts::PI
@@ -91,3 +301,118 @@ impl World {
     //     Entities::max_world_radius().min(Terrain::max_world_radius())
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::allocations::{allocations, count_allocations};
+    use crate::entity::Entity;
+    use crate::world::World;
+    use crate::Server;
+    use common::entity::{EntityKind, EntitySubKind, EntityType};
+    use common::terrain::Terrain;
+    use common::ticks::Ticks;
+    use core_protocol::id::PlayerId;
+    use game_server::player::{PlayerData, PlayerTuple};
+    use glam::Vec2;
+    use maybe_parallel_iterator::IntoMaybeParallelIterator;
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
+
+    /// Maximum average allocations per update of a world with a steady population.
+    const ALLOCATION_BUDGET: usize = 128;
+
+    #[test]
+    fn allocations_per_update() {
+        let mut world = World::new(5000.0);
+        world.terrain = Terrain::new();
+
+        // Without land, only boats that float can spawn.
+        let players: Vec<Arc<PlayerTuple<Server>>> = EntityType::iter()
+            .filter(|t| {
+                let data = t.data();
+                data.kind == EntityKind::Boat
+                    && !matches!(
+                        data.sub_kind,
+                        EntitySubKind::Tank | EntitySubKind::Helicopter
+                    )
+            })
+            .take(20)
+            .enumerate()
+            .map(|(i, entity_type)| {
+                let player = Arc::new(PlayerTuple::new(PlayerData::new(
+                    PlayerId(NonZeroU32::new(i as u32 + 1).unwrap()),
+                    None,
+                )));
+                let entity = Entity::new(entity_type, Some(Arc::clone(&player)));
+                assert!(
+                    world.spawn_here_or_nearby(entity, 5000.0, None),
+                    "could not spawn {:?}",
+                    entity_type
+                );
+                player
+            })
+            .collect();
+
+        // Parallel updates also allocate on worker threads, so run them on a pool whose threads
+        // are counted.
+        #[cfg(feature = "rayon")]
+        let pool = rayon::ThreadPoolBuilder::new()
+            .start_handler(|_| count_allocations())
+            .build()
+            .unwrap();
+        let update = |world: &mut World| {
+            #[cfg(feature = "rayon")]
+            pool.install(|| world.update(Ticks::ONE));
+            #[cfg(not(feature = "rayon"))]
+            world.update(Ticks::ONE);
+        };
+        count_allocations();
+
+        // Let statics spawn and scratch buffers grow.
+        for _ in 0..100 {
+            update(&mut world);
+        }
+
+        const UPDATES: usize = 50;
+        let before = allocations();
+        for _ in 0..UPDATES {
+            update(&mut world);
+        }
+        let average = (allocations() - before) / UPDATES;
+        assert!(
+            average <= ALLOCATION_BUDGET,
+            "{} allocations per update",
+            average
+        );
+
+        drop(players);
+    }
+
+    #[test]
+    fn checksum() {
+        let new_world = || {
+            let mut world = World::new(1000.0);
+            world.terrain = Terrain::new();
+            for i in 0..10 {
+                let mut entity = Entity::new(EntityType::Crate, None);
+                entity.transform.position = Vec2::new(i as f32 * 50.0, 0.0);
+                world.add(entity);
+            }
+            world
+        };
+
+        let a = new_world();
+        let mut b = new_world();
+        assert_eq!(a.checksum(), b.checksum());
+
+        b.entities
+            .par_iter_mut()
+            .into_maybe_parallel_iter()
+            .for_each(|(_, entity)| {
+                if entity.transform.position.x == 100.0 {
+                    entity.transform.position.y += 0.01;
+                }
+            });
+        assert_ne!(a.checksum(), b.checksum());
+    }
+}
@@ -0,0 +1,193 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entities::EntityIndex;
+use crate::world::World;
+use common::area_effect::{AreaEffect, AreaEffectKind};
+use common::death_reason::DeathReason;
+use common::ticks::Ticks;
+use glam::Vec2;
+
+/// An [`AreaEffect`], and the state needed to spread and expire it.
+#[derive(Debug)]
+pub struct AreaEffectState {
+    pub effect: AreaEffect,
+    /// Radius that the effect spreads to.
+    max_radius: f32,
+    /// Time until the effect disappears.
+    remaining: Ticks,
+}
+
+impl AreaEffectState {
+    /// Lifespan of an oil slick that isn't set alight.
+    const OIL_LIFESPAN: Ticks = Ticks::from_whole_secs(3 * 60);
+    /// Longest that an oil slick burns for, once set alight.
+    const FIRE_LIFESPAN: Ticks = Ticks::from_whole_secs(30);
+    /// How long a star shell lights up the area it bursts over.
+    const LIGHT_LIFESPAN: Ticks = Ticks::from_whole_secs(20);
+    /// Radius of the area that a star shell lights up.
+    const LIGHT_RADIUS: f32 = 400.0;
+    /// Meters per second that oil slicks spread at.
+    const SPREAD_SPEED: f32 = 3.0;
+    /// Fraction of their speed that surface boats are limited to, in oil.
+    const SLOW: f32 = 0.5;
+    /// Fraction of their health that surface boats lose per second, in a fire.
+    const BURN: f32 = 0.03;
+
+    /// Creates an oil slick that spreads from where a boat of the given length sank.
+    pub fn oil(position: Vec2, length: f32) -> Self {
+        Self {
+            effect: AreaEffect {
+                kind: AreaEffectKind::Oil,
+                position,
+                radius: 0.25 * length,
+                remaining: 1.0,
+            },
+            max_radius: length,
+            remaining: Self::OIL_LIFESPAN,
+        }
+    }
+
+    /// Creates the light of a star shell that burst over `position`.
+    pub fn light(position: Vec2) -> Self {
+        Self {
+            effect: AreaEffect {
+                kind: AreaEffectKind::Light,
+                position,
+                radius: Self::LIGHT_RADIUS,
+                remaining: 1.0,
+            },
+            max_radius: Self::LIGHT_RADIUS,
+            remaining: Self::LIGHT_LIFESPAN,
+        }
+    }
+
+    /// Sets an oil slick alight, which burns it up sooner.
+    fn ignite(&mut self) {
+        if self.effect.kind == AreaEffectKind::Oil {
+            self.effect.kind = AreaEffectKind::Fire;
+            self.remaining = self.remaining.min(Self::FIRE_LIFESPAN);
+        }
+    }
+
+    /// Spreads and ages the effect, returning whether it remains.
+    fn update(&mut self, delta: Ticks) -> bool {
+        self.remaining = self.remaining.saturating_sub(delta);
+        self.effect.radius =
+            (self.effect.radius + Self::SPREAD_SPEED * delta.to_secs()).min(self.max_radius);
+        let lifespan = match self.effect.kind {
+            AreaEffectKind::Oil => Self::OIL_LIFESPAN,
+            AreaEffectKind::Fire => Self::FIRE_LIFESPAN,
+            AreaEffectKind::Light => Self::LIGHT_LIFESPAN,
+        };
+        self.effect.remaining = (self.remaining.to_secs() / lifespan.to_secs()).min(1.0);
+        self.remaining != Ticks::ZERO
+    }
+}
+
+impl World {
+    /// Sets alight any oil slicks at `position`, such as where a shell landed.
+    pub fn ignite_area_effects(&mut self, position: Vec2) {
+        for state in &mut self.area_effects {
+            if state.effect.contains(position) {
+                state.ignite();
+            }
+        }
+    }
+
+    /// Returns whether `position` is lit up by a star shell.
+    pub fn is_lit(&self, position: Vec2) -> bool {
+        self.area_effects.iter().any(|state| {
+            state.effect.kind == AreaEffectKind::Light && state.effect.contains(position)
+        })
+    }
+
+    /// Spreads and expires area effects, slows surface boats in oil, and burns those in fires.
+    pub fn update_area_effects(&mut self, delta: Ticks) {
+        self.area_effects.retain_mut(|state| state.update(delta));
+
+        let mut affected: Vec<(EntityIndex, AreaEffectKind)> = Vec::new();
+        for state in &self.area_effects {
+            let effect = &state.effect;
+            if effect.kind == AreaEffectKind::Light {
+                continue;
+            }
+            affected.extend(
+                self.entities
+                    .iter_radius(effect.position, effect.radius)
+                    .filter(|(_, entity)| {
+                        entity.is_boat()
+                            && !entity.altitude.is_submerged()
+                            && effect.contains(entity.transform.position)
+                    })
+                    .map(|(index, _)| (index, effect.kind)),
+            );
+        }
+
+        // Sorted in reverse to remove correctly. Burning takes precedence over oil.
+        affected.sort_unstable_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| (b.1 == AreaEffectKind::Fire).cmp(&(a.1 == AreaEffectKind::Fire)))
+        });
+        affected.dedup_by_key(|(index, _)| *index);
+        for (index, kind) in affected {
+            let boat = &mut self.entities[index];
+            let max_speed = boat.data().speed * AreaEffectState::SLOW;
+            boat.transform.velocity = boat.transform.velocity.clamp_magnitude(max_speed);
+
+            if kind == AreaEffectKind::Fire {
+                let damage = boat.data().max_health() * (AreaEffectState::BURN * delta.to_secs());
+                if boat.damage(damage.max(Ticks::ONE)) {
+                    self.remove(index, DeathReason::Fire);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world_area_effects::AreaEffectState;
+    use common::area_effect::AreaEffectKind;
+    use common::ticks::Ticks;
+    use glam::Vec2;
+
+    #[test]
+    fn oil() {
+        let mut oil = AreaEffectState::oil(Vec2::ZERO, 100.0);
+        let initial = oil.effect.radius;
+        assert!(oil.update(Ticks::from_whole_secs(10)));
+        assert!(oil.effect.radius > initial);
+        assert!(oil.effect.contains(Vec2::new(initial, 0.0)));
+
+        // Spreads up to the length of the boat.
+        assert!(oil.update(Ticks::from_whole_secs(100)));
+        assert!(oil.effect.radius <= 100.0);
+
+        // Burns up quickly.
+        oil.ignite();
+        assert_eq!(oil.effect.kind, AreaEffectKind::Fire);
+        assert!(oil.update(Ticks::from_whole_secs(10)));
+        assert!(!oil.update(AreaEffectState::FIRE_LIFESPAN));
+    }
+
+    #[test]
+    fn light() {
+        let mut light = AreaEffectState::light(Vec2::ZERO);
+        let radius = light.effect.radius;
+        assert!(light.update(Ticks::from_whole_secs(10)));
+        assert!(light.effect.radius <= radius);
+
+        // Light doesn't burn.
+        light.ignite();
+        assert_eq!(light.effect.kind, AreaEffectKind::Light);
+        assert!(!light.update(AreaEffectState::LIGHT_LIFESPAN));
+    }
+
+    #[test]
+    fn expire() {
+        let mut oil = AreaEffectState::oil(Vec2::ZERO, 100.0);
+        assert!(oil.update(Ticks::from_whole_secs(60)));
+        assert!(!oil.update(AreaEffectState::OIL_LIFESPAN));
+    }
+}
@@ -0,0 +1,39 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::world::World;
+use common::audio_event::{AudioEvent, AudioEventKind};
+use common::ticks::Ticks;
+use maybe_parallel_iterator::IntoMaybeParallelIterator;
+
+/// Time between pings of active sonars.
+const SONAR_PING_INTERVAL: Ticks = Ticks::from_whole_secs(5);
+
+impl World {
+    /// Periodically records the pings of active sonars, for clients that can hear but not see
+    /// them. Other sounds are recorded as they happen.
+    pub fn update_sonar_pings(&mut self, delta: Ticks) {
+        self.sonar_ping_cooldown = self.sonar_ping_cooldown.saturating_sub(delta);
+        if self.sonar_ping_cooldown != Ticks::ZERO {
+            return;
+        }
+        self.sonar_ping_cooldown = SONAR_PING_INTERVAL;
+
+        let pings: Vec<AudioEvent> = self
+            .entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .filter_map(|(_, entity)| {
+                (entity.is_boat()
+                    && entity.data().sensors.sonar.range > 0.0
+                    && entity.extension().is_active())
+                .then_some(AudioEvent {
+                    kind: AudioEventKind::SonarPing,
+                    position: entity.transform.position,
+                    intensity: 1.0,
+                })
+            })
+            .collect();
+        self.audio_events.extend(pings);
+    }
+}
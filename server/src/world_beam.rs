@@ -0,0 +1,234 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entities::EntityIndex;
+use crate::entity::Entity;
+use crate::player::Status;
+use crate::server::Server;
+use crate::world::World;
+use crate::world_mutation::Mutation;
+use common::altitude::Altitude;
+use common::angle::Angle;
+use common::beam::Beam;
+use common::entity::{EntityData, EntityKind, EntitySubKind, EntityType};
+use common::terrain::{Terrain, SCALE};
+use common::ticks;
+use common::ticks::Ticks;
+use game_server::player::PlayerTuple;
+use glam::Vec2;
+use maybe_parallel_iterator::IntoMaybeParallelIterator;
+use std::sync::Arc;
+
+/// Degrees, either side of a beam weapon's direction, that it may be aimed.
+const MAX_DEVIATION_DEGREES: f32 = 30.0;
+
+/// Returns how far along a ray, from origin in a normalized direction, it first meets terrain
+/// at or above threshold, if within length.
+fn terrain_occlusion(
+    terrain: &Terrain,
+    origin: Vec2,
+    direction: Vec2,
+    length: f32,
+    threshold: Altitude,
+) -> Option<f32> {
+    // Fine enough not to skip over narrow strips of land.
+    let step = SCALE * 0.25;
+    let steps = (length / step).ceil() as usize;
+    (1..=steps)
+        .map(|i| (i as f32 * step).min(length))
+        .find(|&distance| {
+            terrain
+                .sample(origin + direction * distance)
+                .map_or(false, |altitude| altitude >= threshold)
+        })
+}
+
+/// Returns how far along a ray, from origin in a normalized direction, it first meets an entity,
+/// if within length.
+fn entity_occlusion(entity: &Entity, origin: Vec2, direction: Vec2, length: f32) -> Option<f32> {
+    // In the entity's frame of reference, it is an axis-aligned box.
+    let forward = entity.transform.direction.to_vec();
+    let tangent = Vec2::new(-forward.y, forward.x);
+    let relative = origin - entity.transform.position;
+    let origin = Vec2::new(relative.dot(forward), relative.dot(tangent));
+    let direction = Vec2::new(direction.dot(forward), direction.dot(tangent));
+    let half_dimensions = entity.data().dimensions() * 0.5;
+
+    // Distances along the ray at which it crosses the box's sides.
+    let inverse = direction.recip();
+    let a = (-half_dimensions - origin) * inverse;
+    let b = (half_dimensions - origin) * inverse;
+    let near = a.min(b).max_element().max(0.0);
+    let far = a.max(b).min_element().min(length);
+    (near <= far).then_some(near)
+}
+
+impl World {
+    /// Fires beam weapons whose trigger is held, continuously damaging the first enemy boat in
+    /// each beam's path, unless terrain or an obstacle blocks it first.
+    pub fn update_beams(&mut self, delta: Ticks) {
+        self.beams.clear();
+
+        // Heats up beam weapons that are firing, and cools down the rest.
+        let firing: Vec<(EntityIndex, usize)> = self
+            .entities
+            .par_iter_mut()
+            .into_maybe_parallel_iter()
+            .filter_map(|(index, entity)| {
+                if !entity.is_boat() {
+                    return None;
+                }
+                let (armament_index, overheated) = entity.extension_mut().update_beam(delta)?;
+                if overheated {
+                    // Must cool down, like reloading, before firing again.
+                    for (i, armament) in entity.data().armaments.iter().enumerate() {
+                        if armament.entity_type.data().sub_kind == EntitySubKind::Beam {
+                            entity.consume_armament(i);
+                        }
+                    }
+                }
+                // Beams give away the shooter, like guns.
+                entity.extension_mut().flash();
+                Some((index, armament_index))
+            })
+            .collect();
+
+        let mut hits: Vec<(EntityIndex, Arc<PlayerTuple<Server>>, EntityType, Ticks)> = Vec::new();
+        for (index, armament_index) in firing {
+            let boat = &self.entities[index];
+            let data = boat.data();
+            let armament = match data.armaments.get(armament_index) {
+                // May have upgraded since pulling the trigger.
+                Some(armament) if armament.entity_type.data().sub_kind == EntitySubKind::Beam => {
+                    armament
+                }
+                _ => continue,
+            };
+            if boat.altitude.is_submerged() {
+                continue;
+            }
+            let armament_data = armament.entity_type.data();
+
+            let transform =
+                boat.transform + data.armament_transform(&boat.extension().turrets, armament_index);
+            let mut angle = transform.direction;
+            if let Status::Alive {
+                aim_target: Some(aim_target),
+                ..
+            } = boat.borrow_player().data.status
+            {
+                let deviation = Angle::from(aim_target - transform.position) - angle;
+                angle += deviation.clamp_magnitude(Angle::from_degrees(MAX_DEVIATION_DEGREES));
+            }
+            let origin = transform.position;
+            let direction = angle.to_vec();
+            let range = armament_data.range();
+
+            let mut length = terrain_occlusion(
+                &self.terrain,
+                origin,
+                direction,
+                range,
+                boat.altitude.max(Altitude::ZERO),
+            )
+            .unwrap_or(range);
+
+            // Find the closest boat or obstacle in the way.
+            let mut target = None;
+            let center = origin + direction * (length * 0.5);
+            for (other_index, other) in self
+                .entities
+                .iter_radius(center, length * 0.5 + EntityData::MAX_RADIUS)
+            {
+                if other_index == index {
+                    continue;
+                }
+                let other_data = other.data();
+                let blocks = match other_data.kind {
+                    EntityKind::Obstacle => true,
+                    EntityKind::Boat => !other.altitude.is_submerged() && !other.is_friendly(boat),
+                    _ => false,
+                };
+                if !blocks {
+                    continue;
+                }
+                if let Some(distance) = entity_occlusion(other, origin, direction, length) {
+                    length = distance;
+                    target = (other_data.kind == EntityKind::Boat).then_some((other_index, other));
+                }
+            }
+
+            if let Some((target_index, target)) = target {
+                let resistance = target.data().resistance_to_subkind(armament_data.sub_kind)
+                    * target.extension().spawn_protection();
+                let damage =
+                    ticks::from_damage(armament_data.damage * delta.to_secs() * resistance);
+                hits.push((
+                    target_index,
+                    Arc::clone(boat.player.as_ref().unwrap()),
+                    armament.entity_type,
+                    damage,
+                ));
+            }
+
+            self.beams.push(Beam {
+                source: origin,
+                target: origin + direction * length,
+            });
+        }
+
+        // Sorted in reverse to remove correctly. Damage from multiple beams adds up.
+        hits.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+        hits.dedup_by(|hit, kept| {
+            let same = hit.0 == kept.0;
+            if same {
+                kept.3 = kept.3.saturating_add(hit.3);
+            }
+            same
+        });
+        for (index, player, entity_type, damage) in hits {
+            Mutation::HitBy(player, entity_type, damage).apply(self, index, delta, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::Entity;
+    use crate::world_beam::entity_occlusion;
+    use common::angle::Angle;
+    use common::entity::EntityType;
+    use glam::Vec2;
+
+    #[test]
+    fn entity_occlusion_along_keel() {
+        let boat = Entity::new(EntityType::Zubr, None);
+        let half_length = EntityType::Zubr.data().length * 0.5;
+
+        // From astern, the beam meets the stern.
+        let origin = Vec2::new(-100.0, 0.0);
+        let distance = entity_occlusion(&boat, origin, Vec2::X, 1000.0).unwrap();
+        assert!(
+            (distance - (100.0 - half_length)).abs() < 0.01,
+            "{}",
+            distance
+        );
+
+        // Too short to reach.
+        assert_eq!(entity_occlusion(&boat, origin, Vec2::X, 10.0), None);
+
+        // Pointing away.
+        assert_eq!(entity_occlusion(&boat, origin, -Vec2::X, 1000.0), None);
+
+        // Passing abeam, wide of the boat.
+        let origin = Vec2::new(0.0, 100.0);
+        let direction = Angle::from_degrees(10.0).to_vec();
+        assert_eq!(entity_occlusion(&boat, origin, direction, 1000.0), None);
+
+        // Starting inside.
+        assert_eq!(
+            entity_occlusion(&boat, Vec2::ZERO, Vec2::Y, 1000.0),
+            Some(0.0)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entities::EntityIndex;
+use crate::entity::Entity;
+use crate::world::World;
+use common::cargo::Cargo;
+use common::entity::EntityType;
+use common_util::range::gen_radius;
+use glam::Vec2;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+impl World {
+    /// Ports this close to a cargo's destination accept it.
+    const PORT_TOLERANCE: f32 = 1.0;
+
+    /// Delivers the cargo of a boat docked at the port at `position`, if it is the destination,
+    /// and loads new cargo bound for one of `ports` if the boat is empty and can carry it.
+    pub(crate) fn dock_cargo(
+        &mut self,
+        index: EntityIndex,
+        position: Vec2,
+        difficulty: f32,
+        ports: &[Vec2],
+    ) {
+        let boat = &mut self.entities[index];
+        let is_port = |p: Vec2| p.distance_squared(position) < Self::PORT_TOLERANCE.powi(2);
+
+        if let Some(cargo) = boat.extension().cargo {
+            // If the destination closed down, any port far enough from the origin will do.
+            let destination_closed = !ports
+                .iter()
+                .any(|p| p.distance_squared(cargo.destination) < Self::PORT_TOLERANCE.powi(2));
+            let delivered = is_port(cargo.destination)
+                || (destination_closed
+                    && cargo.origin.distance_squared(position) >= Cargo::MIN_DISTANCE.powi(2));
+            if !delivered {
+                return;
+            }
+            boat.extension_mut().cargo = None;
+            let player_id = boat.borrow_player().player_id;
+            // Escorts are paid out of the payout.
+            let payout = self.escorts.complete(player_id, cargo.payout(difficulty));
+            let mut player = boat.borrow_player_mut();
+            player.score = player.score.saturating_add(payout);
+        }
+
+        if !Cargo::can_carry(boat.entity_type) {
+            return;
+        }
+        let destinations: Vec<Vec2> = ports
+            .iter()
+            .copied()
+            .filter(|p| p.distance_squared(position) >= Cargo::MIN_DISTANCE.powi(2))
+            .collect();
+        if let Some(&destination) = destinations.choose(&mut thread_rng()) {
+            boat.extension_mut().cargo = Some(Cargo {
+                origin: position,
+                destination,
+            });
+        }
+    }
+
+    /// Scatters lost cargo as crates around where a boat carrying it was sunk.
+    pub(crate) fn spill_cargo(&mut self, cargo: Cargo, position: Vec2, radius: f32) {
+        let mut rng = thread_rng();
+        for _ in 0..cargo.loot() {
+            let mut crate_entity = Entity::new(EntityType::Crate, None);
+            crate_entity.transform.position = position + gen_radius(&mut rng, radius);
+            self.spawn_here_or_nearby(crate_entity, radius, None);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entities::EntityIndex;
+use crate::entity::Entity;
+use crate::world::World;
+use common::altitude::Altitude;
+use common::angle::Angle;
+use common::entity::{EntityKind, EntitySubKind};
+use common::terrain::Terrain;
+use common::velocity::Velocity;
+use common::world::ARCTIC;
+use glam::Vec2;
+use maybe_parallel_iterator::IntoMaybeParallelIterator;
+
+/// Seconds of travel, at the current speed, to look ahead for land and obstacles.
+const LOOKAHEAD_SECONDS: f32 = 5.0;
+/// Meters between samples of terrain along the path ahead.
+const SAMPLE_SPACING: f32 = 10.0;
+/// Seconds to leave for slowing down, which sets how fast a boat may approach a hazard.
+const STOPPING_SECONDS: f32 = 4.0;
+/// Degrees between the headings that are tried, either side of the player's, to steer clear.
+const DEFLECTION_STEP_DEGREES: f32 = 15.0;
+/// Most degrees that the player's heading is deflected by, so the assist stays gentle.
+const MAX_DEFLECTION_DEGREES: f32 = 45.0;
+
+/// How collision assist overrides the guidance of a boat, because of land or an obstacle ahead.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CollisionLimit {
+    /// Speed to slow down to, if the boat is heading towards a hazard.
+    pub speed: Option<Velocity>,
+    /// Heading to steer instead, if the player's heading leads to a hazard and one nearby
+    /// doesn't.
+    pub direction: Option<Angle>,
+}
+
+impl World {
+    /// Limits the speed and turning of boats whose players opted into collision assist (see
+    /// [`EntityExtension::collision_assist`][crate::entity_extension::EntityExtension::collision_assist]),
+    /// if land or an obstacle is ahead, so that they slow down and steer clear instead of running
+    /// aground.
+    pub fn update_collision_assist(&mut self) {
+        let limits: Vec<(EntityIndex, Option<CollisionLimit>)> = self
+            .entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .filter_map(|(index, boat)| {
+                if !boat.is_boat() {
+                    return None;
+                }
+                let limit = if boat.extension().collision_assist {
+                    self.collision_assist_limit(boat)
+                } else {
+                    None
+                };
+                Some((index, limit))
+            })
+            .collect();
+
+        for (index, limit) in limits {
+            self.entities[index].extension_mut().collision_limit = limit;
+        }
+    }
+
+    /// Returns how a boat must be limited to avoid running into a hazard ahead, if at all.
+    fn collision_assist_limit(&self, boat: &Entity) -> Option<CollisionLimit> {
+        let data = boat.data();
+        let position = boat.transform.position;
+        // Some boats are meant to leave the water, and submerged or airborne boats don't run
+        // aground.
+        if matches!(
+            data.sub_kind,
+            EntitySubKind::Hovercraft
+                | EntitySubKind::Dredger
+                | EntitySubKind::Tank
+                | EntitySubKind::LandingShip
+                | EntitySubKind::Helicopter
+                | EntitySubKind::Drone
+        ) || (data.sub_kind == EntitySubKind::Icebreaker && position.y >= ARCTIC)
+            || boat.altitude.is_submerged()
+            || boat.altitude.is_airborne()
+        {
+            return None;
+        }
+
+        let speed = boat.transform.velocity.to_mps();
+        if speed <= 0.0 {
+            return None;
+        }
+
+        let bow = data.length * 0.5;
+        let range = bow + speed * LOOKAHEAD_SECONDS;
+
+        // Slow down for what is ahead now.
+        let speed = self
+            .hazard_ahead(boat, boat.transform.direction, range)
+            .map(|distance| {
+                let clearance = (distance - bow).max(0.0);
+                Velocity::from_mps(clearance / STOPPING_SECONDS)
+            });
+
+        // Steer clear of what the player is heading towards, by as little as possible.
+        let target = boat.guidance.direction_target;
+        let direction = if self.hazard_ahead(boat, target, range).is_some() {
+            let steps = (MAX_DEFLECTION_DEGREES / DEFLECTION_STEP_DEGREES) as u32;
+            (1..=steps)
+                .flat_map(|step| {
+                    let deflection = Angle::from_degrees(step as f32 * DEFLECTION_STEP_DEGREES);
+                    [target + deflection, target - deflection]
+                })
+                .find(|&heading| self.hazard_ahead(boat, heading, range).is_none())
+        } else {
+            None
+        };
+
+        (speed.is_some() || direction.is_some()).then(|| CollisionLimit { speed, direction })
+    }
+
+    /// Returns the distance from a boat to the first land or obstacle within `range` meters along
+    /// `heading`, if any.
+    fn hazard_ahead(&self, boat: &Entity, heading: Angle, range: f32) -> Option<f32> {
+        let data = boat.data();
+        let position = boat.transform.position;
+        let heading = heading.to_vec();
+
+        let terrain_distance = Self::terrain_ahead(
+            &self.terrain,
+            position,
+            heading,
+            data.length * 0.5,
+            range,
+            boat.altitude,
+        );
+        let obstacle_distance = self
+            .entities
+            .iter_radius(position, range + data.radius)
+            .filter(|(_, other)| other.data().kind == EntityKind::Obstacle)
+            .filter_map(|(_, other)| {
+                let offset = other.transform.position - position;
+                let along = offset.dot(heading);
+                let across = offset.perp_dot(heading).abs();
+                (along > 0.0 && across < other.data().radius + data.width * 0.5)
+                    .then(|| along - other.data().radius)
+            })
+            .reduce(f32::min);
+
+        match (terrain_distance, obstacle_distance) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns the distance to the first land ahead, if any, sampling from `start` to `end`
+    /// meters along `heading`.
+    fn terrain_ahead(
+        terrain: &Terrain,
+        position: Vec2,
+        heading: Vec2,
+        start: f32,
+        end: f32,
+        altitude: Altitude,
+    ) -> Option<f32> {
+        let threshold = Altitude::ZERO.max(altitude);
+        let mut distance = start;
+        while distance <= end {
+            if terrain
+                .sample(position + heading * distance)
+                .map_or(false, |sample| sample > threshold)
+            {
+                return Some(distance);
+            }
+            distance += SAMPLE_SPACING;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::Entity;
+    use crate::world::World;
+    use common::altitude::Altitude;
+    use common::angle::Angle;
+    use common::entity::EntityType;
+    use common::terrain::{Terrain, TerrainMutation, SCALE};
+    use common::velocity::Velocity;
+    use glam::Vec2;
+
+    #[test]
+    fn terrain_ahead() {
+        let mut terrain = Terrain::new();
+        // A north-south strip of land, about 500m east.
+        let mut y = -200.0;
+        while y <= 200.0 {
+            for x in [500.0 - SCALE, 500.0, 500.0 + SCALE] {
+                for _ in 0..10 {
+                    terrain.modify(TerrainMutation::simple(Vec2::new(x, y), 100.0));
+                }
+            }
+            y += SCALE;
+        }
+        assert!(terrain.sample(Vec2::new(500.0, 0.0)).unwrap() > Altitude::ZERO);
+
+        let ahead = |heading: Vec2, end: f32| {
+            World::terrain_ahead(&terrain, Vec2::ZERO, heading, 10.0, end, Altitude::ZERO)
+        };
+
+        // Found, to within the spacing of samples and terrain.
+        let distance = ahead(Vec2::X, 1000.0).unwrap();
+        assert!((distance - 500.0).abs() <= 2.0 * SCALE, "{}", distance);
+
+        // Out of range, or in another direction.
+        assert_eq!(ahead(Vec2::X, 400.0), None);
+        assert_eq!(ahead(-Vec2::X, 1000.0), None);
+        assert_eq!(ahead(Vec2::Y, 1000.0), None);
+    }
+
+    #[test]
+    fn collision_assist_limit() {
+        let mut world = World::test_empty(5000.0);
+        let mut platform = Entity::new(EntityType::OilPlatform, None);
+        platform.transform.position = Vec2::new(200.0, 0.0);
+        world.add(platform);
+        let (_, index) = world.test_boat(EntityType::Yamato, u32::MAX, Vec2::ZERO);
+
+        let limit = |world: &mut World, direction: Angle, target: Angle, speed: f32| {
+            let boat = &mut world.entities[index];
+            boat.transform.direction = direction;
+            boat.transform.velocity = Velocity::from_mps(speed);
+            boat.guidance.direction_target = target;
+            let boat = &world.entities[index];
+            world.collision_assist_limit(boat)
+        };
+
+        // Heading for the platform, so slow down and steer a little to one side.
+        let speed = 10.0;
+        let ahead = limit(&mut world, Angle::ZERO, Angle::ZERO, speed).unwrap();
+        assert!(ahead.speed.unwrap().to_mps() < speed);
+        let deflection = ahead.direction.unwrap().abs().to_degrees();
+        assert!(
+            deflection > 0.0 && deflection <= 45.0 + 0.1,
+            "{}",
+            deflection
+        );
+
+        // Already turning away, so only slow down.
+        let turning = limit(&mut world, Angle::ZERO, Angle::PI_2, speed).unwrap();
+        assert!(turning.speed.is_some());
+        assert_eq!(turning.direction, None);
+
+        // Turning towards it, so only steer.
+        let turning = limit(&mut world, Angle::PI_2, Angle::ZERO, speed).unwrap();
+        assert_eq!(turning.speed, None);
+        assert!(turning.direction.is_some());
+
+        // Heading away, or stopped.
+        assert!(limit(&mut world, Angle::PI, Angle::PI, speed).is_none());
+        assert!(limit(&mut world, Angle::ZERO, Angle::ZERO, 0.0).is_none());
+    }
+}
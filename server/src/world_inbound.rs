@@ -1,17 +1,28 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::bombing;
+use crate::duel::Duelist;
 use crate::entity::Entity;
+use crate::escort::Party;
 use crate::player::Status;
 use crate::protocol::*;
 use crate::server::Server;
+use crate::spawn_selector::SpawnSelector;
+use crate::trace::TraceEvent;
 use crate::world::World;
+use crate::world_pirates::PirateRaid;
 use common::angle::Angle;
-use common::altitude::Altitude;
+use common::audio_event::AudioEvent;
+use common::altitude::{AirBand, Altitude, DepthBand};
+use common::drawing::Drawing;
+use common::guidance::Waypoint;
 use common::entity::*;
 use common::protocol::*;
+use common::signal::SignalKind;
 use common::terrain::TerrainMutation;
 use common::ticks::Ticks;
+use common::velocity::Velocity;
 use common::util::{level_to_score, score_to_level};
 use common::world::{clamp_y_to_strict_area_border, outside_strict_area, ARCTIC};
 use common_util::range::map_ranges;
@@ -19,9 +30,10 @@ use game_server::player::PlayerTuple;
 use glam::Vec2;
 use maybe_parallel_iterator::IntoMaybeParallelIterator;
 use rand::{thread_rng, Rng};
+use std::collections::VecDeque;
 use std::ops::Range;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 impl CommandTrait for Spawn {
     fn apply(
@@ -43,16 +55,37 @@ impl CommandTrait for Spawn {
             return Err("cannot spawn while already alive");
         }
 
+        if player.data.casting.is_some() {
+            return Err("cannot spawn while casting");
+        }
+
+        world.game_mode.can_spawn(&player)?;
+
         if !self.entity_type.can_spawn_as(player.score, player.is_bot(), moderator) {
             return Err("cannot spawn as given entity type");
         }
 
+        let mut rng = thread_rng();
+
+        // Bots may be drafted into a pirate raid, regardless of what they asked to spawn as.
+        let mut entity_type = self.entity_type;
+        let mut pirate_position = None;
+        if player.is_bot() {
+            if let Some(raid) = &mut world.pirate_raid {
+                if let Some(pirate_type) = raid.crew(&mut rng) {
+                    entity_type = pirate_type;
+                    pirate_position = Some(raid.position);
+                }
+                if raid.is_crewed() {
+                    world.pirate_raid = None;
+                }
+            }
+        }
+
         // These initial positions may be overwritten later.
         let mut spawn_position = Vec2::ZERO;
         let mut spawn_radius = 0.8 * world.radius;
 
-        let mut rng = thread_rng();
-
         if !(player.is_bot() && rng.gen()) {
             // Default to spawning near the center of the world, with more points making you spawn further north.
             let raw_spawn_y = map_ranges(
@@ -66,7 +99,7 @@ impl CommandTrait for Spawn {
             debug_assert!((-world.radius..=world.radius).contains(&raw_spawn_y));
 
             // Don't spawn in wrong area.
-            let spawn_y = clamp_y_to_strict_area_border(self.entity_type, raw_spawn_y);
+            let spawn_y = clamp_y_to_strict_area_border(entity_type, raw_spawn_y);
 
             if spawn_y.abs() > world.radius {
                 return Err("unable to spawn this type of boat");
@@ -127,7 +160,17 @@ impl CommandTrait for Spawn {
             _ => None,
         };
 
-        if player.team_id().is_some() || player.invitation_accepted().is_some() {
+        // Honor the player's choice of quadrant, as long as their boat can go there.
+        let quadrant = self.region.quadrant(world.radius);
+        if let Some((center, radius)) = quadrant {
+            let y = clamp_y_to_strict_area_border(entity_type, center.y);
+            spawn_position = Vec2::new(center.x, y);
+            spawn_radius = radius;
+        }
+
+        if self.region.follows_fleet()
+            && (player.team_id().is_some() || player.invitation_accepted().is_some())
+        {
             // TODO: Inefficient to scan all entities; only need to scan all players. Unfortunately,
             // that data is not available here, currently.
             if let Some((_, team_boat)) = world
@@ -163,9 +206,58 @@ impl CommandTrait for Spawn {
             }
         }
 
+        // Pirates spawn together, wherever their raid was planned.
+        if let Some(position) = pirate_position {
+            let y = clamp_y_to_strict_area_border(entity_type, position.y);
+            spawn_position = Vec2::new(position.x, y);
+            spawn_radius = PirateRaid::SPREAD;
+        }
+
+        // Steer away from boats that recently sank the player, and towards teammates and loot.
+        let boats: Vec<_> = world
+            .entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .filter_map(|(_, entity)| {
+                if !entity.is_boat() {
+                    return None;
+                }
+                let other = entity.borrow_player();
+                if player.data.is_recent_killer(other.player_id) {
+                    Some((entity.transform.position, false))
+                } else if player.team_id().is_some() && other.team_id() == player.team_id() {
+                    Some((entity.transform.position, true))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let selector = SpawnSelector {
+            threats: boats.iter().filter(|(_, ally)| !ally).map(|&(p, _)| p).collect(),
+            allies: boats.iter().filter(|(_, ally)| *ally).map(|&(p, _)| p).collect(),
+        };
+        spawn_position = selector.select(
+            world,
+            entity_type,
+            spawn_position,
+            spawn_radius,
+            &mut rng,
+        );
+        spawn_radius = spawn_radius.min(SpawnSelector::SPREAD);
+
+        drop(player);
+
+        // Locked cosmetics fall back to defaults, instead of preventing spawning.
+        let mut player = player_tuple.borrow_player_mut();
+        player.data.cosmetics = self.cosmetics.unlocked(player.data.highest_level);
+        player.data.pirate = pirate_position.is_some();
+        if player.data.pirate {
+            // Make pirates worth sinking.
+            player.score = player.score.max(level_to_score(entity_type.data().level));
+        }
         drop(player);
 
-        let mut boat = Entity::new(self.entity_type, Some(Arc::clone(player_tuple)));
+        let mut boat = Entity::new(entity_type, Some(Arc::clone(player_tuple)));
         boat.transform.position = spawn_position;
         //#[cfg(debug_assertions)]
         //let begin = std::time::Instant::now();
@@ -175,9 +267,24 @@ impl CommandTrait for Spawn {
             println!(
                 "took {:?} to spawn a {:?}",
                 begin.elapsed(),
-                self.entity_type
+                entity_type
             );
              */
+            let mut player = player_tuple.borrow_player_mut();
+            if let Some((_, respawned)) = &mut player.data.first_hull {
+                *respawned = true;
+            }
+            if player.is_traced() {
+                if let Status::Alive { entity_index, .. } = player.data.status {
+                    world.traces.push((
+                        player.player_id,
+                        TraceEvent::Spawned {
+                            entity_type,
+                            position: world.entities[entity_index].transform.position,
+                        },
+                    ));
+                }
+            }
             Ok(())
         } else {
             Err("failed to find enough space to spawn")
@@ -185,6 +292,32 @@ impl CommandTrait for Spawn {
     }
 }
 
+impl CommandTrait for Cast {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let mut player = player_tuple.borrow_player_mut();
+
+        if let Some(camera) = self.camera {
+            if !player.client().map(|c| c.moderator).unwrap_or(false) {
+                return Err("only moderators can cast");
+            }
+            if player.data.status.is_alive() {
+                return Err("cannot cast while alive");
+            }
+            if !camera.is_finite() {
+                return Err("cannot cast from an invalid position");
+            }
+            player.data.casting = Some(camera.clamp_length_max(world.radius));
+        } else {
+            player.data.casting = None;
+        }
+        Ok(())
+    }
+}
+
 impl CommandTrait for Control {
     fn apply(
         &self,
@@ -193,6 +326,11 @@ impl CommandTrait for Control {
     ) -> Result<(), &'static str> {
         let mut player = player_tuple.borrow_player_mut();
 
+        if let Status::Alive { entity_index, .. } = player.data.status {
+            let position = world.entities[entity_index].transform.position;
+            player.data.record_control(self, position);
+        }
+
         // Pre-borrow.
         let world_radius = world.radius;
 
@@ -204,9 +342,18 @@ impl CommandTrait for Control {
         {
             let entity = &mut world.entities[*entity_index];
 
-            // Movement
-            if let Some(guidance) = self.guidance {
-                entity.guidance = guidance;
+            // Movement, unless following a route.
+            if entity.extension().route.is_empty() {
+                let velocity_target = entity.guidance.velocity_target;
+                if let Some(guidance) = self.guidance {
+                    entity.guidance = guidance;
+                    if self.cruise {
+                        entity.guidance.velocity_target = velocity_target;
+                    }
+                }
+                if let Some(throttle) = self.throttle {
+                    entity.guidance.velocity_target = throttle.velocity_target(entity.data().speed);
+                }
             }
             *aim_target = if let Some(mut aim_target) = self.aim_target {
                 sanitize_floats(aim_target.as_mut(), -world_radius * 2.0..world_radius * 2.0)?;
@@ -218,14 +365,31 @@ impl CommandTrait for Control {
             } else {
                 None
             };
+            if self.turret_locks.len() > entity.data().turrets.len() {
+                return Err("too many turret locks");
+            }
+            // Searchlights are mounted on turrets, above deck.
+            let searchlight =
+                self.searchlight && !self.submerge && !entity.data().turrets.is_empty();
             let extension = entity.extension_mut();
             extension.set_submerge(self.submerge);
+            extension.periscope = self.periscope;
             extension.set_active(self.active);
+            extension.emcon = self.emcon;
+            extension.collision_assist = self.collision_assist;
+            extension.set_turret_locks(&self.turret_locks);
             extension.sound_horn(self.horn);
+            extension.repair_crew |= self.repair_crew;
+            extension.searchlight = searchlight;
 
             drop(player);
 
             if let Some(fire) = &self.fire {
+                // Can't fire while returning to port or scuttling.
+                let mut player = player_tuple.borrow_player_mut();
+                player.data.returning_to_port = None;
+                player.data.scuttling = None;
+                drop(player);
                 fire.apply(world, player_tuple)?;
             }
 
@@ -272,6 +436,10 @@ impl CommandTrait for Fire {
                 return Err("armament index out of bounds");
             }
 
+            if entity.is_out_of_ammo(index) {
+                return Err("out of ammunition");
+            }
+
             if entity.extension().reloads[index] != Ticks::ZERO {
                 return Err("armament not yet reloaded");
             }
@@ -279,6 +447,23 @@ impl CommandTrait for Fire {
             let armament = &data.armaments[index];
             let armament_entity_data = armament.entity_type.data();
 
+            // Guided weapons are launched towards where the locked target will be, if any, instead
+            // of the aim target.
+            let aim_target = if armament_entity_data.kind == EntityKind::Weapon
+                && armament_entity_data.sensors.any()
+                && armament_entity_data.sub_kind != EntitySubKind::Rocket
+            {
+                let position = entity.transform.position;
+                let speed = armament_entity_data.speed.to_mps();
+                entity
+                    .extension()
+                    .target_lock
+                    .and_then(|lock| lock.lead(position, speed))
+                    .or(aim_target)
+            } else {
+                aim_target
+            };
+
             // Can't fire if boat is a submerged former submarine.
             if entity.altitude.is_submerged()
                 && (data.sub_kind != EntitySubKind::Submarine
@@ -291,10 +476,34 @@ impl CommandTrait for Fire {
                 return Err("cannot fire while surfacing as a boat");
             }
 
+            if armament_entity_data.sub_kind == EntitySubKind::Missile
+                && entity.altitude.depth_band() == Some(DepthBand::Deep)
+            {
+                return Err("cannot launch missiles below periscope depth");
+            }
+
             if entity.altitude > Altitude(50) && !(matches!(data.sub_kind, EntitySubKind::Aeroplane | EntitySubKind::Starship | EntitySubKind::Helicopter) ){
                 return Err("cannot fire while flying high (lol)");
             }
 
+            if armament_entity_data.sub_kind == EntitySubKind::DepthCharge
+                && entity.altitude.air_band() > Some(AirBand::BOMB_RELEASE_CEILING)
+            {
+                return Err("cannot release bombs while flying high");
+            }
+
+            if armament_entity_data.sub_kind == EntitySubKind::GlideBomb {
+                let aim = aim_target.ok_or("cannot release glide bomb without aim target")?;
+                if !bombing::approaching(entity, aim) {
+                    return Err("must approach target to release glide bomb");
+                }
+                if entity.transform.position.distance_squared(aim)
+                    > bombing::glide_range(entity.altitude).powi(2)
+                {
+                    return Err("target beyond glide range");
+                }
+            }
+
             if let Some(turret_index) = armament.turret {
                 let turret_angle = entity.extension().turrets[turret_index];
                 let turret = &data.turrets[turret_index];
@@ -306,6 +515,14 @@ impl CommandTrait for Fire {
                 }
             }
 
+            if armament_entity_data.sub_kind == EntitySubKind::Beam {
+                // Beams fire for as long as the trigger is held, instead of spawning a weapon.
+                let extension = entity.extension_mut();
+                extension.pull_beam_trigger(index);
+                extension.clear_spawn_protection();
+                return Ok(());
+            }
+
             let armament_transform =
                 entity.transform + data.armament_transform(&entity.extension().turrets, index);
 
@@ -320,7 +537,19 @@ impl CommandTrait for Fire {
                     let pos =
                         clamp_to_range(depositor, target, DEPOSITOR_RANGE, DEPOSITOR_RANGE * 2.0)?;
 
-                    world.terrain.modify(TerrainMutation::simple(pos, 120.0));
+                    let was_colliding = entity.collides_with_terrain(&world.terrain, 0.0).is_some();
+                    let edit = world.terrain.edit(
+                        [TerrainMutation::simple(pos, 120.0)],
+                        &mut entity.extension_mut().terrain_budget,
+                    )?;
+
+                    // Roll back deposits that would beach the depositing ship.
+                    if !was_colliding
+                        && entity.collides_with_terrain(&world.terrain, 0.0).is_some()
+                    {
+                        edit.undo(&mut world.terrain);
+                        return Err("cannot deposit onto own ship");
+                    }
                 } else {
                     return Err("cannot deposit without aim target");
                 }
@@ -335,7 +564,10 @@ impl CommandTrait for Fire {
                     let pos =
                         clamp_to_range(shovel, target, DEPOSITOR_RANGE, DEPOSITOR_RANGE * 2.0)?;
 
-                    world.terrain.modify(TerrainMutation::simple(pos, -120.0));
+                    let _ = world.terrain.edit(
+                        [TerrainMutation::simple(pos, -120.0)],
+                        &mut entity.extension_mut().terrain_budget,
+                    )?;
                 } else {
                     return Err("cannot shovel without aim target");
                 }
@@ -347,7 +579,18 @@ impl CommandTrait for Fire {
 
                 armament_entity.transform = armament_transform;
                 armament_entity.altitude = entity.altitude;
-                armament_entity.transform.velocity = armament_entity.transform.velocity * 0.667;
+
+                // Bombs dropped while approaching the aim are thrown towards it, missing by more
+                // when dropped from higher up.
+                let bomb_aim = aim_target.filter(|&aim| {
+                    entity.altitude.is_airborne() && bombing::approaching(entity, aim)
+                });
+                if let Some(aim) = bomb_aim {
+                    let error = bombing::release_error(entity.altitude, 0.0);
+                    bombing::aim_ballistic(&mut armament_entity, bombing::scatter(aim, error));
+                } else {
+                    armament_entity.transform.velocity = armament_entity.transform.velocity * 0.667;
+                }
                 if !world.spawn_here_or_nearby(armament_entity, 0.0, None) {
                     return Err("failed to fire from current location");
                 }
@@ -362,6 +605,14 @@ impl CommandTrait for Fire {
                 armament_entity.altitude = entity.altitude;
 
                 let aim_angle = aim_target
+                    .map(|aim| {
+                        if armament_entity_data.sub_kind == EntitySubKind::GlideBomb {
+                            // Glide bombs miss by more when released from higher up.
+                            bombing::scatter(aim, bombing::release_error(entity.altitude, 0.0))
+                        } else {
+                            aim
+                        }
+                    })
                     .map(|aim| Angle::from(aim - armament_entity.transform.position))
                     .unwrap_or(entity.transform.direction);
 
@@ -377,7 +628,7 @@ impl CommandTrait for Fire {
                 let deviation = match armament_entity_data.sub_kind {
                     EntitySubKind::Rocket | EntitySubKind::RocketTorpedo => 0.05,
                     EntitySubKind::Shell | EntitySubKind::TankShell => 0.01,
-                    EntitySubKind::Laser => 0.0,
+                    EntitySubKind::Laser | EntitySubKind::GlideBomb => 0.0,
                     _ => 0.03,
                 };
                 armament_entity.transform.direction += thread_rng().gen::<Angle>() * deviation;
@@ -385,11 +636,22 @@ impl CommandTrait for Fire {
                 if !world.spawn_here_or_nearby(armament_entity, 0.0, None) {
                     return Err("failed to fire from current location");
                 }
+                world.audio_events.extend(AudioEvent::launch(
+                    armament.entity_type,
+                    armament_transform.position,
+                ));
             }
 
             let entity = &mut world.entities[entity_index];
             entity.consume_armament(index);
             entity.extension_mut().clear_spawn_protection();
+            if matches!(
+                armament_entity_data.sub_kind,
+                EntitySubKind::Shell | EntitySubKind::TankShell | EntitySubKind::Rocket
+            ) {
+                // Guns give away the shooter, which matters at night.
+                entity.extension_mut().flash();
+            }
 
             Ok(())
         } else {
@@ -455,7 +717,409 @@ impl CommandTrait for Hint {
     ) -> Result<(), &'static str> {
         player_tuple.borrow_player_mut().data.hint = Hint {
             aspect: sanitize_float(self.aspect, 0.5..2.0)?,
+            congested: self.congested,
+        };
+        Ok(())
+    }
+}
+
+impl CommandTrait for Drawing {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let mut player = player_tuple.borrow_player_mut();
+
+        if !player.is_team_captain() {
+            return Err("only fleet leaders can draw");
+        }
+
+        if !self.is_valid(world.radius) {
+            return Err("cannot draw outside the world");
+        }
+
+        let drawings = &mut player.data.drawings;
+        drawings.retain(|(_, time)| time.elapsed() < Drawing::LIFESPAN);
+
+        if drawings
+            .last()
+            .map_or(false, |(_, time)| time.elapsed() < Drawing::COOLDOWN)
+        {
+            return Err("drawing on cooldown");
+        }
+
+        if drawings.len() >= Drawing::MAX {
+            drawings.remove(0);
+        }
+        drawings.push((*self, Instant::now()));
+        Ok(())
+    }
+}
+
+impl CommandTrait for Duel {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let player = player_tuple.borrow_player();
+        let player_id = player.player_id;
+        let entity_index = if let Status::Alive { entity_index, .. } = player.data.status {
+            entity_index
+        } else {
+            return Err("cannot duel while not alive");
+        };
+        // Like paying, stakes may not dip into the score needed for one's level.
+        let funds = player
+            .score
+            .saturating_sub(level_to_score(world.entities[entity_index].data().level));
+        drop(player);
+
+        match *self {
+            Self::Challenge {
+                player_id: opponent_id,
+                stake,
+            } => {
+                if !Self::STAKES.contains(&stake) {
+                    return Err("invalid stake");
+                }
+                if funds < stake {
+                    return Err("insufficient funds");
+                }
+                if opponent_id.is_bot() {
+                    return Err("cannot duel bots");
+                }
+
+                // TODO: Inefficient to scan all entities; only need to scan all players.
+                let boat = &world.entities[entity_index];
+                let (_, opponent_boat) = world
+                    .entities
+                    .par_iter()
+                    .into_maybe_parallel_iter()
+                    .find_any(|(_, entity)| {
+                        entity.is_boat() && entity.borrow_player().player_id == opponent_id
+                    })
+                    .ok_or("cannot duel players that aren't alive")?;
+                if opponent_boat.is_friendly(boat) {
+                    return Err("cannot duel teammates");
+                }
+                let opponent = Duelist::new(opponent_id, opponent_boat.player.as_ref().unwrap());
+
+                world
+                    .duels
+                    .challenge(Duelist::new(player_id, player_tuple), opponent, stake)
+            }
+            Self::Accept(challenger_id) => {
+                let stake = world
+                    .duels
+                    .challenge_stake(challenger_id, player_id)
+                    .ok_or("no such challenge")?;
+                if funds < stake {
+                    return Err("insufficient funds");
+                }
+                world.duels.accept(challenger_id, player_id)
+            }
+            Self::Decline(opponent_id) => world.duels.decline(player_id, opponent_id),
+        }
+    }
+}
+
+impl CommandTrait for Escort {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let player = player_tuple.borrow_player();
+        let player_id = player.player_id;
+        let entity_index = if let Status::Alive { entity_index, .. } = player.data.status {
+            entity_index
+        } else {
+            return Err("cannot escort while not alive");
+        };
+        drop(player);
+
+        match *self {
+            Self::Offer {
+                player_id: escort_id,
+                share,
+            } => {
+                if !Self::SHARES.contains(&share) {
+                    return Err("invalid share");
+                }
+                if world.entities[entity_index].extension().cargo.is_none() {
+                    return Err("no cargo to escort");
+                }
+                if escort_id.is_bot() {
+                    return Err("cannot hire bots");
+                }
+
+                // TODO: Inefficient to scan all entities; only need to scan all players.
+                let (_, escort_boat) = world
+                    .entities
+                    .par_iter()
+                    .into_maybe_parallel_iter()
+                    .find_any(|(_, entity)| {
+                        entity.is_boat() && entity.borrow_player().player_id == escort_id
+                    })
+                    .ok_or("cannot hire players that aren't alive")?;
+                let escort = Party::new(escort_id, escort_boat.player.as_ref().unwrap());
+
+                world
+                    .escorts
+                    .offer(Party::new(player_id, player_tuple), escort, share)
+            }
+            Self::Accept(client_id) => world.escorts.accept(client_id, player_id),
+            Self::Cancel(other_id) => world.escorts.cancel(player_id, other_id),
+        }
+    }
+}
+
+impl CommandTrait for Transfer {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        if !Self::AMOUNTS.contains(&self.amount) {
+            return Err("invalid amount");
+        }
+        if self.player_id.is_bot() {
+            return Err("cannot transfer to bots");
+        }
+
+        let player = player_tuple.borrow_player();
+        if player.player_id == self.player_id {
+            return Err("cannot transfer to oneself");
+        }
+        if player.data.highest_level < Self::MIN_HIGHEST_LEVEL {
+            return Err("account too new to transfer");
+        }
+        let entity_index = if let Status::Alive { entity_index, .. } = player.data.status {
+            entity_index
+        } else {
+            return Err("cannot transfer while not alive");
+        };
+        let boat = &world.entities[entity_index];
+        // Like paying, transfers may not dip into the score needed for one's level.
+        if player.score < level_to_score(boat.data().level) + self.amount {
+            return Err("insufficient funds");
+        }
+        drop(player);
+
+        // TODO: Inefficient to scan all entities; only need to scan all players.
+        let (_, recipient_boat) = world
+            .entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .find_any(|(_, entity)| {
+                entity.is_boat() && entity.borrow_player().player_id == self.player_id
+            })
+            .ok_or("cannot transfer to players that aren't alive")?;
+        let recipient = recipient_boat.player.as_ref().unwrap();
+        let same_team = player_tuple.borrow_player().team_id().is_some()
+            && player_tuple.borrow_player().team_id() == recipient.borrow_player().team_id();
+        if !same_team {
+            return Err("can only transfer to fleet members");
+        }
+        if boat
+            .transform
+            .position
+            .distance_squared(recipient_boat.transform.position)
+            > Self::RANGE.powi(2)
+        {
+            return Err("too far away to transfer");
+        }
+
+        let mut player = player_tuple.borrow_player_mut();
+        player.data.record_transfer(self.amount)?;
+        player.score -= self.amount;
+        drop(player);
+        let mut recipient = recipient.borrow_player_mut();
+        recipient.score = recipient.score.saturating_add(Self::received(self.amount));
+        Ok(())
+    }
+}
+
+impl CommandTrait for Route {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let entity_index = if let Status::Alive { entity_index, .. } =
+            player_tuple.borrow_player().data.status
+        {
+            entity_index
+        } else {
+            return Err("cannot set route while not alive");
         };
+
+        if self.waypoints.len() > Waypoint::MAX_ROUTE_LEN {
+            return Err("too many waypoints");
+        }
+
+        let world_radius = world.radius;
+        let entity = &mut world.entities[entity_index];
+        let max_speed = entity.data().speed;
+        let mut route = VecDeque::with_capacity(self.waypoints.len());
+        for waypoint in &self.waypoints {
+            let mut waypoint = *waypoint;
+            sanitize_floats(waypoint.position.as_mut(), -world_radius..world_radius)?;
+            waypoint.arrival_radius = sanitize_float(
+                waypoint.arrival_radius,
+                Waypoint::MIN_ARRIVAL_RADIUS..world_radius,
+            )?;
+            waypoint.velocity_target = waypoint
+                .velocity_target
+                .clamp(max_speed * Velocity::MAX_REVERSE_SCALE, max_speed);
+            route.push_back(waypoint);
+        }
+        entity.extension_mut().route = route;
+        Ok(())
+    }
+}
+
+impl CommandTrait for DamageControl {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let player = player_tuple.borrow_player();
+
+        let entity_index = if let Status::Alive { entity_index, .. } = player.data.status {
+            entity_index
+        } else {
+            return Err("cannot do damage control while not alive");
+        };
+        drop(player);
+
+        world.entities[entity_index]
+            .extension_mut()
+            .status_effects
+            .damage_control = self.active;
+        Ok(())
+    }
+}
+
+impl CommandTrait for ReturnToPort {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let mut player = player_tuple.borrow_player_mut();
+
+        if self.cancel {
+            player.data.returning_to_port = None;
+            return Ok(());
+        }
+
+        let entity_index = if let Status::Alive { entity_index, .. } = player.data.status {
+            entity_index
+        } else {
+            return Err("cannot return to port while not alive");
+        };
+
+        if player.data.returning_to_port.is_some() {
+            return Err("already returning to port");
+        }
+        drop(player);
+
+        if !world.in_safe_area(entity_index) {
+            return Err("cannot return to port with enemies nearby");
+        }
+
+        player_tuple.borrow_player_mut().data.returning_to_port = Some(Instant::now());
+        Ok(())
+    }
+}
+
+impl CommandTrait for LockTarget {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let player = player_tuple.borrow_player();
+
+        let entity_index = if let Status::Alive { entity_index, .. } = player.data.status {
+            entity_index
+        } else {
+            return Err("cannot lock target while not alive");
+        };
+
+        let lock = if let Some(id) = self.target {
+            let boat = &world.entities[entity_index];
+            Some(world.track(boat, id).ok_or("target not identified")?)
+        } else {
+            None
+        };
+        world.entities[entity_index].extension_mut().target_lock = lock;
+        Ok(())
+    }
+}
+
+impl CommandTrait for Scuttle {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let mut player = player_tuple.borrow_player_mut();
+
+        if self.cancel {
+            player.data.scuttling = None;
+            return Ok(());
+        }
+
+        let entity_index = if let Status::Alive { entity_index, .. } = player.data.status {
+            entity_index
+        } else {
+            return Err("cannot scuttle while not alive");
+        };
+
+        if player.data.scuttling.is_some() {
+            return Err("already scuttling");
+        }
+
+        if world.entities[entity_index].extension().in_combat() {
+            return Err("cannot scuttle in combat");
+        }
+
+        player.data.scuttling = Some(Instant::now());
+        Ok(())
+    }
+}
+
+impl CommandTrait for Signal {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let mut player = player_tuple.borrow_player_mut();
+
+        if player.team_id().is_none() {
+            return Err("cannot signal without a team");
+        }
+
+        if !self.position.is_finite() || self.position.length() > world.radius {
+            return Err("cannot signal outside the world");
+        }
+
+        if player
+            .data
+            .last_signal
+            .as_ref()
+            .map_or(false, |(_, time)| time.elapsed() < SignalKind::COOLDOWN)
+        {
+            return Err("signal on cooldown");
+        }
+
+        player.data.last_signal = Some((self.clone(), Instant::now()));
         Ok(())
     }
 }
@@ -485,6 +1149,9 @@ impl CommandTrait for Upgrade {
             }
 
             player.data.flags.upgraded = true;
+            let level = self.entity_type.data().level;
+            player.data.summary.reached_level(level);
+            player.data.highest_level = player.data.highest_level.max(level);
 
             let below_full_potential = self.entity_type.data().level < score_to_level(player.score);
 
@@ -534,3 +1201,45 @@ fn clamp_to_range(
         Ok(center + delta.clamp_length_max(range))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::protocol::CommandTrait;
+    use crate::world::World;
+    use common::death_reason::DeathReason;
+    use common::entity::EntityType;
+    use common::protocol::Scuttle;
+    use glam::Vec2;
+
+    #[test]
+    fn scuttle() {
+        let mut world = World::test_empty(1000.0);
+        let (player, index) = world.test_boat(EntityType::Zubr, u32::MAX, Vec2::ZERO);
+        let start = Scuttle { cancel: false };
+        let cancel = Scuttle { cancel: true };
+        let scuttling = || player.borrow_player().data.scuttling.is_some();
+
+        assert_eq!(start.apply(&mut world, &player), Ok(()));
+        assert!(scuttling());
+        assert_eq!(start.apply(&mut world, &player), Err("already scuttling"));
+        assert!(scuttling());
+
+        assert_eq!(cancel.apply(&mut world, &player), Ok(()));
+        assert!(!scuttling());
+
+        // Can't escape a fight by scuttling.
+        world.entities[index].extension_mut().enter_combat();
+        assert_eq!(
+            start.apply(&mut world, &player),
+            Err("cannot scuttle in combat")
+        );
+        assert!(!scuttling());
+
+        world.remove(index, DeathReason::Border);
+        assert_eq!(
+            start.apply(&mut world, &player),
+            Err("cannot scuttle while not alive")
+        );
+        assert!(!scuttling());
+    }
+}
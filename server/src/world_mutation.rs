@@ -1,23 +1,30 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::bombing;
 use crate::entities::EntityIndex;
 use crate::entity::Entity;
 use crate::player::Status;
 use crate::server::Server;
+use crate::trace::TraceEvent;
 use crate::world::World;
+use crate::world_area_effects::AreaEffectState;
 use crate::world_physics_radius::MINE_SPEED;
+use crate::world_pirates::PirateRaid;
 use common::altitude::Altitude;
 use common::angle::Angle;
+use common::audio_event::AudioEvent;
 use common::death_reason::DeathReason;
 use common::entity::*;
 use common::guidance::Guidance;
+use common::protocol::Scuttle;
+use common::status_effects::StatusEffects;
 use common::terrain::TerrainMutation;
 use common::ticks::Ticks;
-use common::util::*;
 use common::velocity::Velocity;
 use game_server::player::PlayerTuple;
 use glam::Vec2;
+use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng};
 use std::sync::Arc;
 
@@ -45,6 +52,8 @@ pub(crate) enum Mutation {
     Remove(DeathReason),
     Repair(Ticks),
     Reload(Ticks),
+    /// Replenishes a fraction of ammunition capacity.
+    Resupply(f32),
     // For things that may only be collected once.
     CollectedBy(Arc<PlayerTuple<Server>>, u32),
     HitBy(Arc<PlayerTuple<Server>>, EntityType, Ticks),
@@ -53,15 +62,18 @@ pub(crate) enum Mutation {
         direction_target: Angle,
         altitude_target: Altitude,
         signal_strength: f32,
+        /// What the seeker acquired.
+        target: EntityId,
     },
-    FireAll(EntitySubKind),
+    // Bombs are aimed at the point, if any.
+    FireAll(EntitySubKind, Option<Vec2>),
 }
 
 impl Mutation {
     /// absolute_priority returns the priority of this mutation, higher means higher priority (going first).
     pub fn absolute_priority(&self) -> i8 {
         match self {
-            Self::FireAll(_) => 127, // so that ASROC can fire before expiring
+            Self::FireAll(_, _) => 127, // so that ASROC can fire before expiring
             Self::Remove(_) => 126,
             Self::HitBy(_, _, _) => 125,
             Self::CollidedWithBoat { .. } => 124,
@@ -126,19 +138,77 @@ impl Mutation {
             }
             Self::HitBy(other_player, weapon_type, damage) => {
                 let e = &mut entities[index];
+                world.audio_events.extend(AudioEvent::explosion(weapon_type, e.transform.position));
+                let damage = e.absorb(damage);
+                if damage != Ticks::ZERO {
+                    Self::enter_combat(e);
+                    world.escorts.record_attack(
+                        other_player.borrow_player().player_id,
+                        e.borrow_player().player_id,
+                    );
+                }
                 if e.damage(damage) {
-                    let killer_alias = {
+                    // Friendly fire (see `DamageRules`) sinks without rewarding anyone.
+                    if e.is_friendly_to_player(Some(&*other_player)) {
+                        let killer_alias = other_player.borrow_player().alias();
+                        world.remove(index, DeathReason::Weapon(killer_alias, weapon_type));
+                        return true;
+                    }
+                    let (killer_alias, killer_id) = {
                         let e_score = e.borrow_player().score;
+                        let victim_id = e.borrow_player().player_id;
+                        let bounty = std::mem::take(&mut e.borrow_player_mut().data.bounty);
                         let mut other_player = other_player.borrow_player_mut();
-                        other_player.score += kill_score(e_score, other_player.score);
+                        let score = world.game_mode.kill_score(e_score, other_player.score);
+                        // Sinking a dueling opponent also wins the pot, and sinking a top player
+                        // collects the bounty on them.
+                        let pot = world.duels.settle(other_player.player_id, victim_id);
+                        world.escorts.record_kill(other_player.player_id, victim_id);
+                        world.heatmap.record_kill(e.transform.position);
+                        other_player.tally_kill();
+                        other_player.data.round_kills += 1;
+                        if pot == 0 {
+                            other_player.data.record_victim(victim_id);
+                        }
+                        if other_player.is_traced() {
+                            world.traces.push((
+                                other_player.player_id,
+                                TraceEvent::Sank {
+                                    victim: victim_id,
+                                    position: e.transform.position,
+                                },
+                            ));
+                        }
+                        other_player.score += score + pot + bounty;
+                        other_player.data.summary.add_kill(Some(weapon_type), score);
                         let alias = other_player.alias();
+                        let player_id = other_player.player_id;
                         drop(other_player);
-                        alias
+                        (alias, player_id)
                     };
+                    e.borrow_player_mut().data.record_killer(killer_id);
 
                     world.remove(index, DeathReason::Weapon(killer_alias, weapon_type));
                     return true;
                 }
+
+                // Hits that don't sink a boat may still start fires or flooding, unless a shield
+                // absorbed them.
+                if damage == Ticks::ZERO {
+                    return false;
+                }
+                let status_effects = &mut e.extension_mut().status_effects;
+                match weapon_type.data().sub_kind {
+                    EntitySubKind::Shell if weapon_type != EntityType::StarShell => {
+                        if thread_rng().gen_bool(StatusEffects::IGNITE_CHANCE) {
+                            status_effects.ignite();
+                        }
+                    }
+                    EntitySubKind::Torpedo | EntitySubKind::RocketTorpedo => {
+                        status_effects.spring_leak();
+                    }
+                    _ => {}
+                }
             }
             Self::CollidedWithBoat {
                 damage,
@@ -147,15 +217,58 @@ impl Mutation {
                 ram,
             } => {
                 let entity = &mut entities[index];
+                let damage = entity.absorb(damage);
+                if damage != Ticks::ZERO {
+                    Self::enter_combat(entity);
+                    world.escorts.record_attack(
+                        other_player.borrow_player().player_id,
+                        entity.borrow_player().player_id,
+                    );
+                }
                 if entity.damage(damage) {
+                    if entity.is_friendly_to_player(Some(&*other_player)) {
+                        let killer_alias = other_player.borrow_player().alias();
+                        world.remove(
+                            index,
+                            if ram {
+                                DeathReason::Ram(killer_alias)
+                            } else {
+                                DeathReason::Boat(killer_alias)
+                            },
+                        );
+                        return true;
+                    }
                     let e_score = entity.borrow_player().score;
-                    let killer_alias = {
+                    let victim_id = entity.borrow_player().player_id;
+                    let bounty = std::mem::take(&mut entity.borrow_player_mut().data.bounty);
+                    let (killer_alias, killer_id) = {
                         let mut other_player = other_player.borrow_player_mut();
-                        other_player.score += ram_score(entity.borrow_player().score, e_score);
+                        let score = world.game_mode.ram_score(entity.borrow_player().score, e_score);
+                        let pot = world.duels.settle(other_player.player_id, victim_id);
+                        world.escorts.record_kill(other_player.player_id, victim_id);
+                        world.heatmap.record_kill(entity.transform.position);
+                        other_player.tally_kill();
+                        other_player.data.round_kills += 1;
+                        if pot == 0 {
+                            other_player.data.record_victim(victim_id);
+                        }
+                        if other_player.is_traced() {
+                            world.traces.push((
+                                other_player.player_id,
+                                TraceEvent::Sank {
+                                    victim: victim_id,
+                                    position: entity.transform.position,
+                                },
+                            ));
+                        }
+                        other_player.score += score + pot + bounty;
+                        other_player.data.summary.add_kill(None, score);
                         let alias = other_player.alias();
+                        let player_id = other_player.player_id;
                         drop(other_player);
-                        alias
+                        (alias, player_id)
                     };
+                    entity.borrow_player_mut().data.record_killer(killer_id);
 
                     world.remove(
                         index,
@@ -185,15 +298,39 @@ impl Mutation {
             Self::HitByAntiAir{other_player, anti_aircraft} => {
                 let entity = &mut entities[index];
                 let e_score = entity.borrow_player().score;
+                Self::enter_combat(entity);
 
                 if entity.kill_in(delta, Ticks::from_secs(1.0/anti_aircraft)) {
-                    let killer_alias = {
+                    let victim_id = entity.borrow_player().player_id;
+                    let bounty = std::mem::take(&mut entity.borrow_player_mut().data.bounty);
+                    let (killer_alias, killer_id) = {
                         let mut other_player = other_player.borrow_player_mut();
-                        other_player.score += kill_score(entity.borrow_player().score, e_score);
+                        let score = world.game_mode.kill_score(entity.borrow_player().score, e_score);
+                        let pot = world.duels.settle(other_player.player_id, victim_id);
+                        world.escorts.record_kill(other_player.player_id, victim_id);
+                        world.heatmap.record_kill(entity.transform.position);
+                        other_player.tally_kill();
+                        other_player.data.round_kills += 1;
+                        if pot == 0 {
+                            other_player.data.record_victim(victim_id);
+                        }
+                        if other_player.is_traced() {
+                            world.traces.push((
+                                other_player.player_id,
+                                TraceEvent::Sank {
+                                    victim: victim_id,
+                                    position: entity.transform.position,
+                                },
+                            ));
+                        }
+                        other_player.score += score + pot + bounty;
+                        other_player.data.summary.add_kill(None, score);
                         let alias = other_player.alias();
+                        let player_id = other_player.player_id;
                         drop(other_player);
-                        alias
-                    };    
+                        (alias, player_id)
+                    };
+                    entity.borrow_player_mut().data.record_killer(killer_id);
                     world.remove(index, DeathReason::AntiAir(killer_alias))
                 }
             }
@@ -209,17 +346,28 @@ impl Mutation {
             Self::Reload(amount) => {
                 entities[index].reload(amount);
             }
+            Self::Resupply(fraction) => {
+                entities[index].resupply(fraction);
+            }
             Self::Score(score) => {
                 entities[index].borrow_player_mut().score += score;
             }
             Self::CollectedBy(player, score) => {
-                player.borrow_player_mut().score += score;
+                world.heatmap.record_loot(entities[index].transform.position);
+                let mut player = player.borrow_player_mut();
+                player.score += score;
+                player
+                    .data
+                    .summary
+                    .add_loot(entities[index].entity_type, score);
+                drop(player);
                 world.remove(index, DeathReason::Unknown);
                 return true;
             }
             Self::Guidance {
                 direction_target,
                 altitude_target,
+                target,
                 ..
             } => {
                 // apply_altitude_target is not reversed by another Guidance mutation, so must
@@ -228,6 +376,7 @@ impl Mutation {
                     let entity = &mut entities[index];
                     entity.guidance.direction_target = direction_target;
                     entity.apply_altitude_target(&world.terrain, Some(altitude_target), 5.0, delta);
+                    world.seeker_targets.insert(entity.id, target);
                 }
             }
             Self::Attraction(delta_pos, velocity, delta_altitude) => {
@@ -239,7 +388,7 @@ impl Mutation {
                     entity.altitude += delta_altitude.clamp_magnitude(Altitude::UNIT * 5.0 * delta);
                 }
             }
-            Self::FireAll(sub_kind) => {
+            Self::FireAll(sub_kind, aim) => {
                 let entity = &mut entities[index];
 
                 // Reset entity lifespan (because it is actively engaging in battle.
@@ -274,7 +423,11 @@ impl Mutation {
                             armament_entity.transform.velocity = armament_entity
                                 .transform
                                 .velocity
-                                .clamp_magnitude(Velocity::from_mps(50.0));
+                                .clamp_magnitude(Velocity::from_mps(bombing::MAX_DROP_SPEED));
+
+                            if let Some(aim) = aim.filter(|_| bombing::is_ballistic(sub_kind)) {
+                                bombing::aim_ballistic(&mut armament_entity, aim);
+                            }
 
                             Some(armament_entity)
                         } else {
@@ -298,6 +451,24 @@ impl Mutation {
         let data: &EntityData = entity_type.data();
 
         if data.kind == EntityKind::Boat {
+            // Sinking is heard from afar, unless the boat left quietly.
+            if !matches!(
+                reason,
+                DeathReason::Unknown
+                    | DeathReason::Afk
+                    | DeathReason::ReturnedToPort(_)
+                    | DeathReason::Scuttled
+            ) {
+                let position = world.entities[index].transform.position;
+                world.audio_events.extend(AudioEvent::explosion(entity_type, position));
+            }
+
+            // The kill feed only records deaths due to players.
+            if reason.is_due_to_player() {
+                let victim = world.entities[index].borrow_player().alias();
+                world.kill_feed.record(victim, reason);
+            }
+
             // If killed by a player, that player will get the coins. If killed by land or by
             // fleeing combat, score should be converted into coins to prevent destruction of score.
             // DeathReason::Unknown means player left game.
@@ -306,16 +477,35 @@ impl Mutation {
                 DeathReason::Border
                     | DeathReason::Terrain
                     | DeathReason::Unknown
+                    | DeathReason::Afk
+                    | DeathReason::Fire
                     | DeathReason::Obstacle(_)
             );
 
-            Self::boat_died(world, index, score_to_coins);
+            // Scuttling leaves less loot, so it can't be used to hand loot to others. Boats that
+            // returned to port already banked their value, so they leave none.
+            let loot_fraction = match reason {
+                DeathReason::Scuttled => Scuttle::LOOT_FRACTION,
+                DeathReason::ReturnedToPort(_) => 0.0,
+                _ => 1.0,
+            };
+
+            Self::boat_died(world, index, score_to_coins, loot_fraction);
         } else {
             if matches!(reason, DeathReason::Terrain) || data.sub_kind == EntitySubKind::DepthCharge
             {
                 Self::maybe_damage_terrain(world, index);
             }
 
+            // Shells set oil slicks alight, wherever they land. Star shells also burst into light.
+            if data.sub_kind == EntitySubKind::Shell {
+                let position = world.entities[index].transform.position;
+                world.ignite_area_effects(position);
+                if entity_type == EntityType::StarShell {
+                    world.area_effects.push(AreaEffectState::light(position));
+                }
+            }
+
             if data.limited {
                 let boat_index = {
                     let entity = &world.entities[index];
@@ -343,19 +533,29 @@ impl Mutation {
 
     /// Called by on_world_remove when a boat dies.
     /// Applies the effect of a boat dying, such as a reduction in the corresponding player's
-    /// score and the spawning of loot.
-    fn boat_died(world: &mut World, index: EntityIndex, score_to_coins: bool) {
+    /// score and the spawning of loot, of which only `loot_fraction` is spawned.
+    fn boat_died(world: &mut World, index: EntityIndex, score_to_coins: bool, loot_fraction: f32) {
         let entity = &mut world.entities[index];
+        let entity_type = entity.entity_type;
         let mut player = entity.borrow_player_mut();
         let mut rng = thread_rng();
         let score = player.score;
-        player.score = if player.is_bot() {
-            // Make sure there are bots in the shallow area.
-            respawn_score(player.score).min(level_to_score(rng.gen_range(1..=2)))
-        } else {
-            respawn_score(player.score)
-        };
+        player.score = world.game_mode.respawn_score(player.score, player.is_bot());
+        let pirate = std::mem::take(&mut player.data.pirate);
+        // Bounties are lost along with the boat, unless already collected.
+        player.data.bounty = 0;
+        player.data.scuttling = None;
+        if player.is_first_session() && player.data.first_hull.is_none() {
+            player.data.first_hull = Some((entity_type, false));
+        }
+        let player_id = player.player_id;
+        let traced = player.is_traced();
         drop(player);
+        world.bounty_markers.retain(|m| m.player_id != player_id);
+        let cargo = entity.extension_mut().cargo.take();
+        if cargo.is_some() {
+            world.escorts.cargo_lost(player_id);
+        }
 
         let data = entity.data();
         debug_assert_eq!(data.kind, EntityKind::Boat);
@@ -363,11 +563,31 @@ impl Mutation {
         // Loot is based on the length of the boat.
 
         let center = entity.transform.position;
+        world.heatmap.record_death(center);
+        if traced {
+            world.traces.push((
+                player_id,
+                TraceEvent::Sunk {
+                    entity_type,
+                    position: center,
+                },
+            ));
+        }
         let normal = entity.transform.direction.to_vec();
         let tangent = Vec2::new(-normal.y, normal.x);
         let altitude = entity.altitude;
 
-        for loot_type in entity.entity_type.loot(score, score_to_coins) {
+        // Pirates are plundered for more loot.
+        let rolls = if pirate { PirateRaid::LOOT_ROLLS } else { 1 };
+
+        let mut loot: Vec<_> = (0..rolls)
+            .flat_map(|_| entity_type.loot(score, score_to_coins))
+            .collect();
+        let loot_count = (loot.len() as f32 * loot_fraction).round() as usize;
+        // Loot is rolled in order of kind (e.g. coins last), so keep a random selection of it.
+        loot.shuffle(&mut rng);
+
+        for loot_type in loot.into_iter().take(loot_count) {
             let mut loot_entity = Entity::new(loot_type, None);
 
             // Make loot roughly conform to rectangle of ship.
@@ -384,6 +604,22 @@ impl Mutation {
 
             world.spawn_here_or_nearby(loot_entity, data.radius * 0.15, None);
         }
+
+        // Tankers spill their cargo, unless they left intact.
+        if data.sub_kind == EntitySubKind::Tanker && loot_fraction > 0.0 {
+            world.area_effects.push(AreaEffectState::oil(center, data.length));
+        }
+
+        // Cargo that was being delivered is lost to whoever picks it up.
+        if let Some(cargo) = cargo {
+            world.spill_cargo(cargo, center, data.radius);
+        }
+    }
+
+    /// Marks a boat that took damage as in combat, which also cancels returning to port.
+    fn enter_combat(entity: &mut Entity) {
+        entity.extension_mut().enter_combat();
+        entity.borrow_player_mut().data.returning_to_port = None;
     }
 
     /// Called by on_world_remove when a non-boat dies.
@@ -491,3 +727,50 @@ impl Mutation {
          */
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::world::World;
+    use crate::world_mutation::Mutation;
+    use common::death_reason::DeathReason;
+    use common::entity::{EntityKind, EntityType};
+    use common::ticks::Ticks;
+    use glam::Vec2;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    #[test]
+    fn returned_to_port_leaves_no_loot() {
+        // Returns how much loot, and how many oil slicks, a sinking tanker leaves behind.
+        let remains = |reason: DeathReason| {
+            let mut world = World::test_empty(1000.0);
+            let (_player, index) = world.test_boat(EntityType::Tanker, 1, Vec2::ZERO);
+            world.remove(index, reason);
+            let loot = world
+                .entities
+                .iter_radius(Vec2::ZERO, world.radius)
+                .filter(|(_, entity)| entity.data().kind == EntityKind::Collectible)
+                .count();
+            (loot, world.area_effects.len())
+        };
+
+        let (loot, oil) = remains(DeathReason::Border);
+        assert!(loot > 0);
+        assert_eq!(oil, 1);
+        assert_eq!(remains(DeathReason::ReturnedToPort(0)), (0, 0));
+    }
+
+    #[test]
+    fn damage_cancels_return_to_port() {
+        let mut world = World::test_empty(1000.0);
+        let (player, index) = world.test_boat(EntityType::Zubr, 1, Vec2::ZERO);
+        let (attacker, _) = world.test_boat(EntityType::Zubr, 2, Vec2::new(500.0, 0.0));
+        player.borrow_player_mut().data.returning_to_port = Some(Instant::now());
+
+        // Even damage that is repaired before the channel completes cancels it.
+        let hit = Mutation::HitBy(Arc::clone(&attacker), EntityType::Mark18, Ticks::ONE);
+        assert!(!hit.apply(&mut world, index, Ticks::ONE, true));
+        assert!(player.borrow_player().data.returning_to_port.is_none());
+        assert!(world.entities[index].extension().in_combat());
+    }
+}
@@ -1,24 +1,37 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::classifier::{Classification, Classifier};
 use crate::complete_ref::CompleteRef;
 use crate::contact_ref::ContactRef;
 use crate::entity::Entity;
 use crate::player::Status;
+use crate::sensor_cache::{Sensed, SensorCache, SensorKey};
 use crate::server::Server;
 use crate::world::World;
+use common::altitude::{AirBand, DepthBand};
+use common::angle::Angle;
 use common::entity::{EntityKind, EntitySubKind, EntityType};
+use common::ghost::Ghost;
+use common::protocol::Cast;
 use common::ticks::Ticks;
 use common_util::range::{map_ranges, map_ranges_fast};
 use game_server::player::PlayerTuple;
 use glam::{vec2, Vec2};
+use lazy_static::lazy_static;
+use maybe_parallel_iterator::IntoMaybeParallelIterator;
 
 impl World {
     /// get_player_complete gets the complete update for a player, corresponding to everything they
     /// are able to see at the current moment.
+    ///
+    /// Reuses what the player's sensors detected in previous updates, if given a cache. Boats are
+    /// classified gradually, if given a classifier, otherwise as soon as they are clear enough.
     pub fn get_player_complete<'a>(
         &'a self,
         tuple: &'a PlayerTuple<Server>,
+        mut cache: Option<&'a mut SensorCache>,
+        mut classifier: Option<&'a mut Classifier>,
     ) -> CompleteRef<'a, impl Iterator<Item = ContactRef>> {
         let player = tuple.borrow_player();
         let player_entity = match &player.data.status {
@@ -30,49 +43,10 @@ impl World {
             _ => None,
         };
 
-        struct Camera {
-            active: bool,
-            inner: f32,
-            position: Vec2,
-            radar: f32,
-            sonar: f32,
-            speed: f32,
-            view: f32,
-            visual: f32,
-        }
-
         // Players, whether alive or dead, can see other entities based on these parameters.
         let camera = if let Some(entity) = player_entity {
-            let data = entity.data();
-            let sensors = &data.sensors;
-
-            // Ranges from -1.0 to 1.0 where 0.0 is sea level.
-            let norm_altitude = entity.altitude.to_norm();
-
-            // Radar and visual don't work well under water.
-            let visual_radar_efficacy = map_ranges(norm_altitude, -0.35..0.0, 0.0..1.0, true);
-
-            let visual = sensors.visual.range * visual_radar_efficacy;
-            let radar = sensors.radar.range * visual_radar_efficacy;
-
-            // Sonar works at full effective range as long as it is not airborne.
-            let sonar = if entity.altitude.is_airborne() {
-                0.0
-            } else {
-                sensors.sonar.range
-            };
-
-            if player.data.status.is_alive() {
-                Camera {
-                    active: entity.extension().is_active(),
-                    inner: data.radii().start,
-                    position: entity.transform.position,
-                    radar,
-                    sonar,
-                    speed: entity.transform.velocity.abs().to_mps(),
-                    view: data.camera_range(),
-                    visual,
-                }
+            if let Status::Alive { aim_target, .. } = player.data.status {
+                self.boat_camera(entity, aim_target)
             } else {
                 panic!("player not alive in outbound");
             }
@@ -88,6 +62,7 @@ impl World {
             let range = map_ranges(elapsed, 10.0..2.0, 0.0..visual_range, true).max(500.0);
             Camera {
                 active: true,
+                radar_active: true,
                 inner: 0.0,
                 position,
                 radar: range,
@@ -95,11 +70,14 @@ impl World {
                 speed: 0.0,
                 view: range,
                 visual: range,
+                revealed_visual: range,
+                searchlight: None,
             }
         } else {
             let range = 500.0;
             Camera {
                 active: true,
+                radar_active: true,
                 inner: 0.0,
                 position: Vec2::ZERO,
                 radar: range,
@@ -107,13 +85,12 @@ impl World {
                 speed: 0.0,
                 view: range,
                 visual: range,
+                revealed_visual: range,
+                searchlight: None,
             }
         };
 
-        let visual_range_inv = camera.visual.powi(-2);
-        let radar_range_inv = camera.radar.powi(-2);
-        let sonar_range_inv = camera.sonar.powi(-2);
-        let max_range = camera.visual.max(camera.radar.max(camera.sonar));
+        let max_range = camera.revealed_visual.max(camera.radar.max(camera.sonar));
         let close_proximity_squared = player_entity.map_or(0.0, |e| {
             (e.entity_type.data().radius + Entity::CLOSE_PROXIMITY).powi(2)
         });
@@ -147,111 +124,40 @@ impl World {
                 let altitude = entity.altitude;
 
                 if !known {
-                    let inv_size = data.inv_size;
-                    let default_ratio = distance_squared * inv_size;
-                    uncertainty = 1.0;
-                    let entity_abs_vel = entity.transform.velocity.abs().to_mps();
-
-                    if radar_range_inv.is_finite() && !altitude.is_submerged() {
-                        let radar_ratio = default_ratio * radar_range_inv;
-
-                        if camera.active {
-                            // Active radar can see moving targets easier.
-                            uncertainty =
-                                uncertainty.min(radar_ratio * 15.0 / (15.0 + entity_abs_vel));
-                        }
-
-                        // Always-on passive radar:
-                        // Inlined to allow constant propagation and replace div with mul.
-                        const BASE_FACTOR: f32 = 25.0;
-                        const BASE_EMISSION: f32 = 5.0f32;
-                        // let mut emission = BASE_EMISSION;
-                        let passive_radar_ratio = if data.kind == EntityKind::Boat {
-                            const BOAT_EMISSION: f32 = 5.0;
-                            // emission += BOAT_EMISSION;
-                            if entity.extension().is_active() && data.sensors.radar.range > 0.0 {
-                                // Active radar gives away entity's position.
-                                const ACTIVE_EMISSION: f32 = 20.0;
-                                // emission += ACTIVE_EMISSION;
-                                BASE_FACTOR / (BASE_EMISSION + BOAT_EMISSION + ACTIVE_EMISSION)
+                    let revealed = self.is_revealed(&camera, entity);
+                    let sense = || camera.sense(entity, distance_squared, revealed);
+                    (visible, uncertainty) = if let Some(cache) = cache.as_deref_mut() {
+                        let key = SensorKey {
+                            camera_position: camera.position,
+                            camera_ranges: [
+                                camera.visual,
+                                camera.revealed_visual,
+                                camera.radar,
+                                camera.sonar,
+                            ],
+                            camera_speed: camera.speed,
+                            camera_active: [camera.active, camera.radar_active],
+                            position: entity.transform.position,
+                            altitude,
+                            entity_type: entity.entity_type,
+                            speed: entity.transform.velocity.abs().to_mps(),
+                            emissions: if data.kind == EntityKind::Boat {
+                                let extension = entity.extension();
+                                [
+                                    extension.is_radar_active(),
+                                    extension.is_active(),
+                                    extension.is_horn(),
+                                    extension.reloads.iter().any(|&t| t > Ticks::ZERO),
+                                ]
                             } else {
-                                BASE_FACTOR / (BASE_EMISSION + BOAT_EMISSION)
-                            }
-                        } else if data.sub_kind == EntitySubKind::Missile {
-                            const MISSILE_EMISSION: f32 = 30.0;
-                            // emission += MISSILE_EMISSION;
-                            BASE_FACTOR / (BASE_EMISSION + MISSILE_EMISSION)
-                        } else {
-                            BASE_FACTOR / BASE_EMISSION
+                                [false; 4]
+                            },
+                            revealed,
                         };
-                        // let passive_radar_ratio = BASE_FACTOR / emission;
-
-                        uncertainty = uncertainty.min(passive_radar_ratio);
-                    }
-
-                    if sonar_range_inv.is_finite() && !altitude.is_airborne() {
-                        let mut sonar_ratio = default_ratio * sonar_range_inv;
-                        if camera.active {
-                            // Active sonar.
-                            uncertainty = uncertainty.min(sonar_ratio);
-                        }
-
-                        // Beyond this point, sonar_ratio means passive sonar ratio.
-
-                        // Always-on passive sonar:
-                        let mut noise = 2f32
-                            .max(entity_abs_vel - data.cavitation_speed(entity.altitude).to_mps());
-
-                        if data.kind == EntityKind::Boat
-                            || data.kind == EntityKind::Weapon
-                            || data.kind == EntityKind::Decoy
-                        {
-                            noise *= 2.0;
-
-                            if data.kind != EntityKind::Boat || data.sub_kind == EntitySubKind::Aeroplane || data.sub_kind == EntitySubKind::Helicopter {
-                                noise += 100.0;
-                            } else if entity.extension().is_horn() && matches!(data.sub_kind, EntitySubKind::Battleship | EntitySubKind::Carrier | EntitySubKind::Corvette | EntitySubKind::Cruiser | EntitySubKind::Destroyer | EntitySubKind::Dreadnought | EntitySubKind::Icebreaker | EntitySubKind::LandingShip | EntitySubKind::Lcs | EntitySubKind::Mtb | EntitySubKind::Passenger | EntitySubKind::Submarine | EntitySubKind::Tanker) {
-                                noise += 200.0;
-                            } else if entity.extension().is_active()
-                                && data.sensors.sonar.range > 0.0
-                            {
-                                // Active sonar gives away entity's position.
-                                noise += 20.0;
-                            }
-                        }
-
-                        sonar_ratio /= noise;
-
-                        // Making noise of your own reduces the performance of
-                        // passive sonar
-                        sonar_ratio *= 20.0 + camera.speed;
-                        uncertainty = uncertainty.min(sonar_ratio);
-                    }
-
-                    if visual_range_inv.is_finite() {
-                        let mut visual_ratio = default_ratio * visual_range_inv;
-                        if altitude.is_submerged() {
-                            let extra = if data.kind == EntityKind::Boat
-                                && entity.extension().reloads.iter().any(|&t| t > Ticks::ZERO)
-                            {
-                                // A submarine that has fired recently is visible, for practical reasons.
-                                0.05
-                            } else {
-                                0.0
-                            };
-                            // Don't clamp high because to_norm can't return above 1.0 (high).
-                            visual_ratio /= map_ranges_fast(
-                                altitude.to_norm(),
-                                -0.5..1.0,
-                                0.0..0.8,
-                                true,
-                                false,
-                            ) + extra;
-                        }
-                        visual_ratio /= (data.length / 100.0).max(1.0);
-                        visible = visual_ratio < 1.0;
-                        uncertainty = uncertainty.min(visual_ratio);
-                    }
+                        cache.get_or_insert_with(entity.id, key, sense)
+                    } else {
+                        sense()
+                    };
 
                     if player_entity.is_some()
                         && data.kind == EntityKind::Weapon
@@ -280,25 +186,383 @@ impl World {
                     }
                 }
 
-                let has_type = data.kind == EntityKind::Collectible
-                    || friendly
-                    || uncertainty < 0.5
-                    || distance_squared < inner_circle_squared;
-
-                Some(ContactRef::new(entity, visible, known, has_type))
+                let close = distance_squared < inner_circle_squared;
+                let classification = if data.kind == EntityKind::Collectible || friendly {
+                    Classification::Type
+                } else if let Some(classifier) =
+                    classifier.as_deref_mut().filter(|_| data.kind == EntityKind::Boat)
+                {
+                    classifier.classify(entity, uncertainty, close)
+                } else if close || uncertainty < Classifier::IDENTIFIED {
+                    Classification::Type
+                } else {
+                    Classification::Unknown
+                };
+
+                Some(ContactRef::new(entity, visible, known, classification))
             });
 
+        let camera_dims = Self::camera_dims(camera_view, player.data.hint.aspect);
+        CompleteRef::new(contacts, player, self, camera_pos, camera_dims)
+    }
+
+    /// get_caster_complete gets the complete update for a caster, corresponding to every boat,
+    /// wherever it is, and every entity near the camera, regardless of sensors.
+    pub fn get_caster_complete<'a>(
+        &'a self,
+        tuple: &'a PlayerTuple<Server>,
+        camera_pos: Vec2,
+    ) -> CompleteRef<'a, impl Iterator<Item = ContactRef>> {
+        let player = tuple.borrow_player();
+        let view_squared = Cast::VIEW.powi(2);
+
+        // Boats beyond the view, so casters know where to cut to.
+        let far_boats: Vec<&Entity> = self
+            .entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .filter_map(|(_, e)| {
+                (e.is_boat() && e.transform.position.distance_squared(camera_pos) > view_squared)
+                    .then_some(e)
+            })
+            .collect();
+
+        let contacts = self
+            .entities
+            .iter_radius(camera_pos, Cast::VIEW)
+            .map(|(_, e)| e)
+            .chain(far_boats)
+            .map(|e| ContactRef::new(e, true, true, Classification::Type));
+
+        let camera_dims = Self::camera_dims(Cast::VIEW, player.data.hint.aspect);
+        CompleteRef::new(contacts, player, self, camera_pos, camera_dims)
+    }
+
+    /// Returns what a boat's sensors can sense, given where its player aims.
+    fn boat_camera(&self, entity: &Entity, aim_target: Option<Vec2>) -> Camera {
+        let data = entity.data();
+        let sensors = &data.sensors;
+
+        // Visual range is reduced at night. Quantized, so dusk and dawn don't invalidate sensor
+        // caches every update.
+        let night = (self.time_of_day.visual_multiplier() * 20.0).round() * (1.0 / 20.0);
+
+        // Radar and visual work from periscope depth, where the mast is exposed, but not
+        // deeper.
+        let visual_radar_efficacy = match entity.altitude.depth_band() {
+            None | Some(DepthBand::Periscope) => 1.0,
+            Some(DepthBand::Deep) => 0.0,
+        };
+
+        // The horizon is further away from higher bands of airspace.
+        let horizon = entity
+            .altitude
+            .air_band()
+            .map_or(1.0, AirBand::sensor_multiplier);
+
+        let visual = sensors.visual.range * visual_radar_efficacy * horizon;
+        let radar = sensors.radar.range * visual_radar_efficacy * horizon;
+
+        // Sonar works at full effective range as long as it is not airborne.
+        let sonar = if entity.altitude.is_airborne() {
+            0.0
+        } else {
+            sensors.sonar.range
+        };
+
+        // Searchlights point where the turrets aim.
+        let searchlight = entity.extension().searchlight.then(|| {
+            if let Some(aim_target) = aim_target {
+                (aim_target - entity.transform.position).normalize_or_zero()
+            } else {
+                entity.transform.direction.to_vec()
+            }
+        });
+
+        Camera {
+            active: entity.extension().is_active(),
+            radar_active: entity.extension().is_radar_active(),
+            inner: data.radii().start,
+            position: entity.transform.position,
+            radar,
+            sonar,
+            speed: entity.transform.velocity.abs().to_mps(),
+            view: data.camera_range(),
+            visual: visual * night,
+            revealed_visual: visual,
+            searchlight,
+        }
+    }
+
+    /// Returns whether an entity gave itself away, or is lit up, such that it is visible from
+    /// further away.
+    fn is_revealed(&self, camera: &Camera, entity: &Entity) -> bool {
+        (entity.data().kind == EntityKind::Boat && entity.extension().is_revealed())
+            || self.is_lit(entity.transform.position)
+            || camera.in_searchlight(entity.transform.position)
+    }
+
+    /// Returns whether a boat's player identified another entity, as opposed to only detecting
+    /// it, as of their latest update (see [`Classifier`]). Target locks require it.
+    pub fn identifies(boat: &Entity, target: &Entity) -> bool {
+        boat.borrow_player().data.identified.contains(&target.id)
+    }
+
+    /// Returns the dimensions of the area a client sees, given the radius of its view and the
+    /// aspect ratio of its screen.
+    fn camera_dims(view: f32, aspect: f32) -> Vec2 {
         // How much more terrain can be sent.
         // 2.0 supports most computer monitors and phones.
         const MAX_ASPECT: f32 = 2.0;
 
-        let aspect = player.data.hint.aspect;
-        let camera_width = camera_view * 2.0;
-        let camera_dims = vec2(
+        let camera_width = view * 2.0;
+        vec2(
             camera_width * aspect.clamp(1.0, MAX_ASPECT),
             camera_width * (1.0 / aspect).clamp(1.0, MAX_ASPECT),
-        );
+        )
+    }
 
-        CompleteRef::new(contacts, player, self, camera_pos, camera_dims)
+    /// Returns the bearings of boats whose active radar illuminates `boat`, as detected by its
+    /// radar warning receiver. Empty if `boat` lacks radar or is submerged.
+    pub fn radar_warnings(&self, boat: &Entity) -> Vec<Angle> {
+        if boat.data().sensors.radar.range <= 0.0 || boat.altitude.is_submerged() {
+            return Vec::new();
+        }
+        let position = boat.transform.position;
+        self.entities
+            .iter_radius(position, *MAX_RADAR_RANGE)
+            .map(|(_, e)| e)
+            .filter(|e| {
+                let data = e.data();
+                e.is_boat()
+                    && *e != boat
+                    && !e.is_friendly(boat)
+                    && !e.altitude.is_submerged()
+                    && e.extension().is_radar_active()
+                    && e.transform.position.distance_squared(position)
+                        <= data.sensors.radar.range.powi(2)
+            })
+            .map(|e| Angle::from(e.transform.position - position))
+            .collect()
+    }
+
+    /// Returns whether the boat that left `ghost` behind was really lost by the sensors of the
+    /// player of `tuple`, as opposed to having sunk or become friendly.
+    pub fn is_ghost_lost(&self, tuple: &PlayerTuple<Server>, ghost: &Ghost) -> bool {
+        /// Meters, beyond how far the boat may have gone in a second, to look for it.
+        const LEEWAY: f32 = 100.0;
+
+        let range = LEEWAY + ghost.transform.velocity.abs().to_mps();
+        self.entities
+            .iter_radius(ghost.transform.position, range)
+            .find(|(_, e)| e.id == ghost.id)
+            .map_or(false, |(_, e)| !e.is_friendly_to_player(Some(tuple)))
+    }
+}
+
+lazy_static! {
+    /// Longest radar range of any boat, within which radar warning receivers look for emitters.
+    static ref MAX_RADAR_RANGE: f32 = EntityType::iter()
+        .filter(|t| t.data().kind == EntityKind::Boat)
+        .map(|t| t.data().sensors.radar.range)
+        .fold(0.0, f32::max);
+}
+
+/// What a player can sense, whether alive or dead.
+struct Camera {
+    active: bool,
+    /// Active radar, which may be silenced by emission control.
+    radar_active: bool,
+    inner: f32,
+    position: Vec2,
+    radar: f32,
+    sonar: f32,
+    speed: f32,
+    view: f32,
+    visual: f32,
+    /// Visual range for contacts that gave themselves away, e.g. by firing guns at night, or are
+    /// lit up.
+    revealed_visual: f32,
+    /// Direction of the searchlight, if shining one.
+    searchlight: Option<Vec2>,
+}
+
+impl Camera {
+    /// Cosine of half the angle of a searchlight's beam.
+    const SEARCHLIGHT_COS: f32 = 0.99;
+
+    /// Returns whether `position` is in the beam of the camera's searchlight, if any.
+    fn in_searchlight(&self, position: Vec2) -> bool {
+        self.searchlight.map_or(false, |direction| {
+            (position - self.position).normalize_or_zero().dot(direction) >= Self::SEARCHLIGHT_COS
+        })
+    }
+
+    /// Returns whether a contact that isn't otherwise known is visible, and the uncertainty of
+    /// detecting it (1.0 or more means not detected).
+    fn sense(&self, entity: &Entity, distance_squared: f32, revealed: bool) -> Sensed {
+        let data = entity.data();
+        let altitude = entity.altitude;
+        let visual = if revealed {
+            self.revealed_visual
+        } else {
+            self.visual
+        };
+        let visual_range_inv = visual.powi(-2);
+        let radar_range_inv = self.radar.powi(-2);
+        let sonar_range_inv = self.sonar.powi(-2);
+
+        let mut visible = false;
+        let inv_size = data.inv_size;
+        let default_ratio = distance_squared * inv_size;
+        let mut uncertainty = 1f32;
+        let entity_abs_vel = entity.transform.velocity.abs().to_mps();
+
+        if radar_range_inv.is_finite() && !altitude.is_submerged() {
+            let radar_ratio = default_ratio * radar_range_inv;
+
+            if self.radar_active {
+                // Active radar can see moving targets easier.
+                uncertainty =
+                    uncertainty.min(radar_ratio * 15.0 / (15.0 + entity_abs_vel));
+            }
+
+            // Always-on passive radar:
+            // Inlined to allow constant propagation and replace div with mul.
+            const BASE_FACTOR: f32 = 25.0;
+            const BASE_EMISSION: f32 = 5.0f32;
+            // let mut emission = BASE_EMISSION;
+            let passive_radar_ratio = if data.kind == EntityKind::Boat {
+                const BOAT_EMISSION: f32 = 5.0;
+                // emission += BOAT_EMISSION;
+                if entity.extension().is_radar_active() && data.sensors.radar.range > 0.0 {
+                    // Active radar gives away entity's position.
+                    const ACTIVE_EMISSION: f32 = 20.0;
+                    // emission += ACTIVE_EMISSION;
+                    BASE_FACTOR / (BASE_EMISSION + BOAT_EMISSION + ACTIVE_EMISSION)
+                } else {
+                    BASE_FACTOR / (BASE_EMISSION + BOAT_EMISSION)
+                }
+            } else if data.sub_kind == EntitySubKind::Missile {
+                const MISSILE_EMISSION: f32 = 30.0;
+                // emission += MISSILE_EMISSION;
+                BASE_FACTOR / (BASE_EMISSION + MISSILE_EMISSION)
+            } else {
+                BASE_FACTOR / BASE_EMISSION
+            };
+            // let passive_radar_ratio = BASE_FACTOR / emission;
+
+            uncertainty = uncertainty.min(passive_radar_ratio);
+        }
+
+        if sonar_range_inv.is_finite() && !altitude.is_airborne() {
+            let mut sonar_ratio = default_ratio * sonar_range_inv;
+            if self.active {
+                // Active sonar.
+                uncertainty = uncertainty.min(sonar_ratio);
+            }
+
+            // Beyond this point, sonar_ratio means passive sonar ratio.
+
+            // Always-on passive sonar:
+            let mut noise = 2f32
+                .max(entity_abs_vel - data.cavitation_speed(entity.altitude).to_mps());
+
+            if data.kind == EntityKind::Boat
+                || data.kind == EntityKind::Weapon
+                || data.kind == EntityKind::Decoy
+            {
+                noise *= 2.0;
+
+                if data.kind != EntityKind::Boat || data.sub_kind == EntitySubKind::Aeroplane || data.sub_kind == EntitySubKind::Helicopter {
+                    noise += 100.0;
+                } else if entity.extension().is_horn() && matches!(data.sub_kind, EntitySubKind::Battleship | EntitySubKind::Carrier | EntitySubKind::Corvette | EntitySubKind::Cruiser | EntitySubKind::Destroyer | EntitySubKind::Dreadnought | EntitySubKind::Icebreaker | EntitySubKind::LandingShip | EntitySubKind::Lcs | EntitySubKind::Mtb | EntitySubKind::Passenger | EntitySubKind::Submarine | EntitySubKind::Tanker) {
+                    noise += 200.0;
+                } else if entity.extension().is_active()
+                    && data.sensors.sonar.range > 0.0
+                {
+                    // Active sonar gives away entity's position.
+                    noise += 20.0;
+                }
+            }
+
+            sonar_ratio /= noise;
+
+            // Making noise of your own reduces the performance of
+            // passive sonar
+            sonar_ratio *= 20.0 + self.speed;
+            uncertainty = uncertainty.min(sonar_ratio);
+        }
+
+        if visual_range_inv.is_finite() {
+            let mut visual_ratio = default_ratio * visual_range_inv;
+            if let Some(depth_band) = altitude.depth_band() {
+                let extra = if data.kind == EntityKind::Boat
+                    && entity.extension().reloads.iter().any(|&t| t > Ticks::ZERO)
+                {
+                    // A submarine that has fired recently is visible, for practical reasons.
+                    0.05
+                } else {
+                    0.0
+                };
+                let exposure = match depth_band {
+                    // Only the mast is exposed, which is a tiny signature.
+                    DepthBand::Periscope if data.kind == EntityKind::Boat => {
+                        const PERISCOPE_SIGNATURE: f32 = 0.3;
+                        PERISCOPE_SIGNATURE * DepthBand::mast_exposure(altitude, data.mast)
+                    }
+                    DepthBand::Deep if data.kind == EntityKind::Boat => 0.0,
+                    // Other entities, such as torpedoes and mines, have no mast, and are harder
+                    // to see the deeper they are.
+                    _ => map_ranges_fast(altitude.to_norm(), -0.5..1.0, 0.0..0.8, true, false),
+                };
+                visual_ratio /= exposure + extra;
+            }
+            visual_ratio /= (data.length / 100.0).max(1.0);
+            visible = visual_ratio < 1.0;
+            uncertainty = uncertainty.min(visual_ratio);
+        }
+
+        (visible, uncertainty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::World;
+    use crate::world_outbound::MAX_RADAR_RANGE;
+    use common::angle::Angle;
+    use common::entity::{EntityKind, EntityType};
+    use glam::Vec2;
+
+    #[test]
+    fn radar_warnings() {
+        let longest = EntityType::iter()
+            .filter(|t| t.data().kind == EntityKind::Boat)
+            .max_by(|a, b| {
+                a.data()
+                    .sensors
+                    .radar
+                    .range
+                    .total_cmp(&b.data().sensors.radar.range)
+            })
+            .unwrap();
+        let range = longest.data().sensors.radar.range;
+        assert_eq!(*MAX_RADAR_RANGE, range);
+
+        let mut world = World::test_empty(4.0 * range);
+        let (_, receiver) = world.test_boat(longest, u32::MAX, Vec2::ZERO);
+        let (_, emitter) = world.test_boat(longest, u32::MAX - 1, Vec2::new(0.0, 0.99 * range));
+
+        // Sensors start active, and even the longest range radar is detected at the limit of its
+        // range.
+        assert!(world.entities[emitter].extension().is_radar_active());
+        let warnings = world.radar_warnings(&world.entities[receiver]);
+        assert_eq!(warnings.len(), 1);
+        assert!((warnings[0] - Angle::from(Vec2::Y)).abs() < Angle::from_degrees(1.0));
+
+        // Emission control silences it.
+        world.entities[emitter].extension_mut().emcon = true;
+        assert!(world.radar_warnings(&world.entities[receiver]).is_empty());
     }
 }
@@ -8,6 +8,7 @@ use common::altitude::Altitude;
 use common::angle::Angle;
 use common::death_reason::DeathReason;
 use common::entity::*;
+use common::shield::Shield;
 use common::terrain::TerrainMutation;
 use common::ticks::Ticks;
 use common::transform::Transform;
@@ -19,7 +20,9 @@ use common_util::range::map_ranges;
 use glam::Vec2;
 use maybe_parallel_iterator::{IntoMaybeParallelIterator, MaybeParallelSort};
 use rand::Rng;
+use std::mem;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 /// Fate terminates the physics for a particular entity with a single fate.
 enum Fate {
@@ -32,16 +35,22 @@ impl World {
     /// update_entities performs updates intrinsic to one entity (and updates the world radius based
     /// on the number of boats). This is currently the only safe location for entity positions to change, due
     /// to the implementation of `Entities`.
+    ///
+    /// Entities are updated independently (in parallel with the `rayon` feature), and then their
+    /// effects on the rest of the world are applied sequentially in order of `EntityIndex`.
     pub fn physics(&mut self, delta: Ticks) {
+        let start = Instant::now();
         let delta_seconds = delta.to_secs();
         let border_radius = self.radius; // Avoids double borrow.
         let border_radius_squared = self.radius.powi(2);
         let terrain = &self.terrain;
+        let sea_state = self.sea_state;
 
-        // Collected updates (order doesn't matter).
-        let terrain_mutations = Mutex::new(Vec::new());
-        let barrel_spawns = Mutex::new(Vec::new());
-        let reset_flags = Mutex::new(Vec::new());
+        // Collected updates, sorted before being applied so the result doesn't depend on the
+        // order entities were updated in.
+        let terrain_mutations = Mutex::new(mem::take(&mut self.scratch.terrain_mutations));
+        let barrel_spawns = Mutex::new(mem::take(&mut self.scratch.barrel_spawns));
+        let reset_flags = Mutex::new(mem::take(&mut self.scratch.reset_flags));
 
         let mut fates: Vec<_> = self
             .entities
@@ -199,7 +208,7 @@ impl World {
                                     barrel_spawns
                                         .lock()
                                         .unwrap()
-                                        .push(entity.transform.position)
+                                        .push((index, entity.transform.position))
                                 };
                                 entity.apply_altitude_target(
                                     terrain,
@@ -238,15 +247,53 @@ impl World {
                             barrel_spawns
                                 .lock()
                                 .unwrap()
-                                .push(entity.transform.position)
+                                .push((index, entity.transform.position))
                         }
                     }
                     _ => {}
                 }
 
-                entity
-                    .transform
-                    .apply_guidance(data, entity.guidance, max_speed, delta_seconds, entity.ticks);
+                // Flooding slows boats, and their turning.
+                let mut flooding_penalty = 1.0;
+                if data.kind == EntityKind::Boat {
+                    let position = entity.transform.position;
+                    let mut guidance = entity.guidance;
+                    entity.extension_mut().follow_route(position, &mut guidance);
+                    entity.guidance = guidance;
+                    flooding_penalty = entity.extension().status_effects.flooding_penalty();
+
+                    // High seas slow small boats on the surface.
+                    if !entity.altitude.is_submerged() && !entity.altitude.is_airborne() {
+                        max_speed *= sea_state.speed_multiplier(data.length);
+                    }
+                }
+
+                // Collision assist slows down and steers, rather than changing the player's
+                // guidance.
+                let mut guidance = entity.guidance;
+                if data.kind == EntityKind::Boat {
+                    if let Some(limit) = entity.extension().collision_limit {
+                        if let Some(speed) = limit.speed {
+                            guidance.velocity_target = guidance.velocity_target.min(speed);
+                        }
+                        if let Some(direction) = limit.direction {
+                            guidance.direction_target = direction;
+                        }
+                    }
+                }
+
+                let direction = entity.transform.direction;
+                entity.transform.apply_guidance(
+                    data,
+                    guidance,
+                    max_speed * flooding_penalty,
+                    delta_seconds,
+                    entity.ticks,
+                );
+                if flooding_penalty < 1.0 {
+                    let turn = entity.transform.direction - direction;
+                    entity.transform.direction = direction + turn * flooding_penalty;
+                }
                 entity.transform.do_kinematics(delta_seconds);
 
                 let arctic = entity.transform.position.y >= ARCTIC;
@@ -327,7 +374,7 @@ impl World {
                             terrain_mutations
                                 .lock()
                                 .unwrap()
-                                .push((terrain_mutation, is_icebreaker.then_some(index)));
+                                .push((index, terrain_mutation, is_icebreaker));
                         }
                     }
 
@@ -401,8 +448,27 @@ impl World {
 
                 if data.kind == EntityKind::Boat {
                     entity.update_turret_aim(delta_seconds);
-                    entity.reload(delta);
+                    let extension = entity.extension_mut();
+                    let reload = extension.reload_amount(delta);
+                    if mem::take(&mut extension.repair_crew) {
+                        extension.status_effects.call_repair_crew();
+                    }
+                    let burn = extension.status_effects.update(delta);
+                    entity.reload(reload);
                     entity.extension_mut().update_tickers(delta);
+                    if let Some(shield) = Shield::of(entity.entity_type) {
+                        entity
+                            .extension_mut()
+                            .recharge_shield(shield, data.max_health(), delta);
+                    }
+
+                    if burn > 0.0 {
+                        repair_eligible = false;
+                        let damage = data.max_health() * burn;
+                        if entity.damage(damage.max(Ticks::ONE)) {
+                            return Some((index, Fate::Remove(DeathReason::Fire)));
+                        }
+                    }
 
                     if repair_eligible {
                         let repair_amount = if data.length > 200.0 {
@@ -412,7 +478,9 @@ impl World {
                         } else {
                             1.0
                         };
-                        entity.repair(delta * repair_amount);
+                        let repair_multiplier =
+                            entity.extension().status_effects.repair_multiplier();
+                        entity.repair(delta * (repair_amount * repair_multiplier));
                     }
                     entity.transform.velocity = entity
                             .transform
@@ -422,8 +490,9 @@ impl World {
                     if data.sub_kind == EntitySubKind::Dredger {
                         // Dredgers excavate land they come into contact with.
                         terrain_mutations.lock().unwrap().push((
+                            index,
                             TerrainMutation::simple(entity.transform.position, -17.5),
-                            None,
+                            false,
                         ))
                     }
                 }
@@ -436,18 +505,27 @@ impl World {
             })
             .collect();
 
-        for (mutation, award_entity_index) in terrain_mutations.into_inner().unwrap() {
-            if self.terrain.modify(mutation).unwrap_or(false) {
-                if let Some(index) = award_entity_index {
-                    // Terrain actually changed, award some points.
-                    self.entities[index].borrow_player_mut().score += 1;
-                }
+        // Entities moved, so radius queries must see their new positions.
+        self.entities.sync_columns();
+
+        self.benchmark.record("physics", start);
+        let start = Instant::now();
+
+        let mut terrain_mutations = terrain_mutations.into_inner().unwrap();
+        terrain_mutations.sort_by_key(|(index, _, _)| *index);
+        for (index, mutation, award) in terrain_mutations.drain(..) {
+            if self.terrain.modify(mutation).unwrap_or(false) && award {
+                // Terrain actually changed, award some points.
+                self.entities[index].borrow_player_mut().score += 1;
             }
         }
+        self.scratch.terrain_mutations = terrain_mutations;
 
         // Spawn barrels around oil platforms.
+        let mut barrel_spawns = barrel_spawns.into_inner().unwrap();
+        barrel_spawns.sort_unstable_by_key(|(index, _)| *index);
         let mut rng = rand::thread_rng();
-        for mut position in barrel_spawns.into_inner().unwrap() {
+        for (_, mut position) in barrel_spawns.drain(..) {
             const BARREL_RADIUS: f32 = 120.0;
             position +=
                 rng.gen::<Angle>().to_vec() * rng.gen_range((BARREL_RADIUS / 2.0)..BARREL_RADIUS);
@@ -461,6 +539,7 @@ impl World {
                 Ticks::ZERO,
             );
         }
+        self.scratch.barrel_spawns = barrel_spawns;
 
         // Sorted in reverse to remove correctly.
         fates.maybe_par_sort_unstable_by(|a, b| b.0.cmp(&a.0));
@@ -490,9 +569,13 @@ impl World {
             });
 
         // Clear flags at end so they can be asserted in Mutation::reload_limited_armament.
-        for player in reset_flags.into_inner().unwrap() {
+        let mut reset_flags = reset_flags.into_inner().unwrap();
+        for player in reset_flags.drain(..) {
             player.borrow_player_mut().data.flags = Flags::default();
         }
+        self.scratch.reset_flags = reset_flags;
+
+        self.benchmark.record("physics_fates", start);
     }
 }
 
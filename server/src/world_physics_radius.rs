@@ -1,12 +1,14 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::bombing;
 use crate::entities::EntityIndex;
 use crate::entity::Entity;
 use crate::world::World;
 use crate::world_mutation::Mutation;
 use arrayvec::ArrayVec;
-use common::altitude::Altitude;
+use common::altitude::{AirBand, Altitude};
+use common::ammo::Ammo;
 use common::angle::Angle;
 use common::death_reason::DeathReason;
 use common::entity::*;
@@ -14,22 +16,25 @@ use common::ticks;
 use common::ticks::Ticks;
 use common::util::hash_u32_to_f32;
 use common::velocity::Velocity;
+use game_server::context_service::FriendlyFire;
 use maybe_parallel_iterator::{IntoMaybeParallelIterator, MaybeParallelSort};
 use rand::{thread_rng, Rng};
+use std::mem;
 use std::sync::Arc;
 use std::sync::Mutex;
 
 pub const MINE_SPEED: f32 = 8.0;
+/// Collectibles this close to a boat's hull are collected without touching it.
+pub const LOOT_AUTO_COLLECT_DISTANCE: f32 = 10.0;
 
 impl World {
     /// minimum_scan_radius returns the radius must be scanned to properly resolve all entity vs.
     /// entity interactions.
-    fn minimum_scan_radius(entity: &Entity, delta_seconds: f32) -> f32 {
-        let data = entity.data();
+    fn minimum_scan_radius(entity_type: EntityType, velocity: Velocity, delta_seconds: f32) -> f32 {
+        let data = entity_type.data();
 
         // Enough for collision only.
-        let mut radius =
-            data.radius * 2.0 + entity.transform.velocity.abs().to_mps() * delta_seconds;
+        let mut radius = data.radius * 2.0 + velocity.abs().to_mps() * delta_seconds;
 
         match data.kind {
             EntityKind::Aircraft | EntityKind::Weapon => {
@@ -37,7 +42,9 @@ impl World {
                 radius = radius.max(data.sensors.max_range());
             }
             EntityKind::Boat => {
-                radius = radius.max(data.anti_aircraft_range());
+                radius = radius
+                    .max(data.anti_aircraft_range())
+                    .max(Self::loot_magnetism(data).0);
             }
             _ => {}
         }
@@ -45,6 +52,23 @@ impl World {
         radius
     }
 
+    /// Returns the range and speed at which a boat attracts collectibles, both growing with its size.
+    fn loot_magnetism(data: &EntityData) -> (f32, Velocity) {
+        (
+            data.radius * 2.0 + 30.0,
+            Velocity::from_mps(15.0 + data.radius * 0.1),
+        )
+    }
+
+    /// Returns whether a collectible is close enough to a boat's hull to be collected.
+    fn within_auto_collect(boat: &Entity, collectible: &Entity) -> bool {
+        let position = collectible.transform.position;
+        let hull = boat.data().width * 0.5 + collectible.data().radius;
+        boat.closest_point_on_keel_to(position, 1.0)
+            .distance_squared(position)
+            <= (hull + LOOT_AUTO_COLLECT_DISTANCE).powi(2)
+    }
+
     /// parcel_entities adds zero, one, or both entities to an ArrayVec, based on whether they match
     /// a given entity kind.
     fn parcel_entities<'a>(
@@ -65,9 +89,12 @@ impl World {
     /// update_entities_and_others performs updates on each pair of entities, with some exceptions.
     pub fn physics_radius(&mut self, delta: Ticks) {
         let delta_seconds = delta.to_secs();
+        self.seeker_targets.clear();
 
         // TODO: look into lock free data structures.
-        let mutations = Mutex::new(Vec::new());
+        let mutations = Mutex::new(mem::take(&mut self.scratch.mutations));
+        // Hits by fast weapons, with their time of impact, of which only the first counts.
+        let swept_hits = Mutex::new(mem::take(&mut self.scratch.swept_hits));
 
         self.entities
             .par_iter()
@@ -83,17 +110,20 @@ impl World {
                     return; // continue
                 }
 
-                let radius = Self::minimum_scan_radius(entity, delta_seconds);
+                let radius = Self::minimum_scan_radius(
+                    entity.entity_type,
+                    entity.transform.velocity,
+                    delta_seconds,
+                );
 
-                for (other_index, other_entity) in self.entities.iter_radius(
-                    entity.transform.position,
-                    radius,
-                ) {
+                for (other_index, other_row) in self
+                    .entities
+                    .iter_radius_rows(entity.transform.position, radius)
+                {
                     if index == other_index {
                         // Entities do not interact with themselves.
                         continue;
                     }
-                    let _other_data = other_entity.data();
 
                     // Only want to process each pair of entities once, but without stopping early
                     // and assuming A.radius > B.radius, would process A -> B and, depending on
@@ -101,12 +131,20 @@ impl World {
                     // uniqueness guarantee. For the case of equal radii, the index is used to
                     // pick one permutation to block randomly. Must use scan radius, not entity
                     // radius, such that weapon guidance doesn't get ignored.
-                    let other_radius = Self::minimum_scan_radius(other_entity, delta_seconds);
+                    let other_radius = Self::minimum_scan_radius(
+                        other_row.entity_type,
+                        other_row.velocity,
+                        delta_seconds,
+                    );
                     #[allow(clippy::float_cmp)]
                     if other_radius > radius || (other_radius == radius && other_index > index) {
                         continue;
                     }
 
+                    // Only now that the pair is known to be processed, touch the rest of the
+                    // other entity.
+                    let other_entity = &self.entities[other_index];
+
                     let friendly = entity.is_friendly(other_entity);
                     let altitude_overlap = entity.altitude_overlapping(other_entity);
 
@@ -146,7 +184,14 @@ impl World {
                         }
                     }
 
-                    if !entity.collides_with(other_entity, delta_seconds) || !altitude_overlap {
+                    // Collectibles gravitate towards players (except if the player created them).
+                    let attracted = boats.len() == 1
+                        && collectibles.len() == 1
+                        && altitude_overlap
+                        && (!entity.has_same_player(other_entity) || collectibles[0].ticks > Ticks::from_secs(5.0));
+                    let auto_collected = attracted && Self::within_auto_collect(boats[0], collectibles[0]);
+
+                    if (!entity.collides_with(other_entity, delta_seconds) || !altitude_overlap) && !auto_collected {
                         if boats.len() == 2 && !friendly {
                             let boat = boats[0];
                             let other_boat = boats[1];
@@ -165,9 +210,9 @@ impl World {
                         }
 
                         if collectibles.len() == 1 && altitude_overlap {
-                            // Collectibles gravitate towards players (except if the player created them).
-                            if boats.len() == 1 && (!entity.has_same_player(other_entity) || collectibles[0].ticks > Ticks::from_secs(5.0)) {
-                                mutate(collectibles[0], Mutation::Attraction(boats[0].transform.position - collectibles[0].transform.position, Velocity::from_mps(20.0), boats[0].altitude - collectibles[0].altitude));
+                            if attracted {
+                                let (_, speed) = Self::loot_magnetism(boats[0].data());
+                                mutate(collectibles[0], Mutation::Attraction(boats[0].transform.position - collectibles[0].transform.position, speed, boats[0].altitude - collectibles[0].altitude));
                             }
 
                             // Payments gravitate towards oil rigs.
@@ -194,14 +239,13 @@ impl World {
                                 let target_data = target.data();
 
 
-                                let sub_kind = weapon_data.sub_kind;
                                 let is_rocket_torpedo = data.sub_kind == EntitySubKind::RocketTorpedo;
                                 let mut rocket_torpedo_sensed = false;
 
-                                if weapon_data.sensors.any() && sub_kind != EntitySubKind::Rocket {
+                                if weapon_data.has_seeker() {
                                     // Home towards target/decoy
-                                    // Sensor activates after 1 second.
-                                    if weapons[0].ticks > Ticks::from_secs(1.0) {
+                                    // Sensor activates after a delay.
+                                    if weapons[0].ticks > EntityData::SEEKER_DELAY {
                                         // Different targets are relevant to each weapon.
                                         let relevant = match weapon_data.sub_kind {
                                             EntitySubKind::Sam => {
@@ -216,6 +260,10 @@ impl World {
                                             EntitySubKind::GlideBomb => {
                                                 target_data.kind == EntityKind::Boat
                                             }
+                                            // Fighters intercept enemy aircraft, as well as attacking boats.
+                                            _ if weapon_data.is_fighter() && target_data.kind == EntityKind::Aircraft => {
+                                                target.altitude.is_airborne()
+                                            }
                                             _ => {
                                                 target_data.kind == EntityKind::Boat
                                             }
@@ -238,14 +286,7 @@ impl World {
                                             // Cannot sense beyond this angle.
                                             let angle_diff = (angle - weapon.transform.direction).abs();
 
-                                            let (max_angle_target_diff, max_angle_diff) = if weapon.data().sub_kind == EntitySubKind::Missile {
-                                                (Angle::from_degrees(30.0), Angle::from_degrees(40.0))
-                                            } else if weapon.data().sub_kind == EntitySubKind::GlideBomb {
-                                                (Angle::from_degrees(30.0), Angle::from_degrees(45.0))
-                                            } else {
-                                                (Angle::from_degrees(60.0), Angle::from_degrees(80.0))
-                                            };
-                                            if (is_rocket_torpedo || distance_squared <= remaining_range.powi(2)) && angle_target_diff <= max_angle_target_diff && angle_diff <= max_angle_diff {
+                                            if (is_rocket_torpedo || distance_squared <= remaining_range.powi(2)) && angle_target_diff <= weapon_data.seeker_course_angle() && angle_diff <= weapon_data.seeker_angle() {
                                                 if is_rocket_torpedo {
                                                     rocket_torpedo_sensed = true;
                                                 } else {
@@ -274,13 +315,17 @@ impl World {
                                                     // Altitude diff.
                                                     let altitude_diff = weapon.altitude.difference(target.altitude).to_norm();
 
+                                                    // Fighters prefer dogfighting to attacking boats.
+                                                    let interception = (weapon_data.is_fighter() && target_data.kind == EntityKind::Aircraft) as u8 as f32;
+
                                                     let randomness = hash_u32_to_f32(target.id.get() ^ weapon.id.get());
                                                     let strength = size / EntityData::MAX_RADIUS
+                                                        + interception
                                                         - distance_squared / radius.powi(2)
                                                         - angle_diff.to_radians() / Angle::MAX.to_radians()
                                                         - altitude_diff
                                                         + (1.0 / 3.0) * randomness;
-                                                    mutate(weapon, Mutation::Guidance {direction_target: angle, altitude_target: target.altitude, signal_strength: strength});
+                                                    mutate(weapon, Mutation::Guidance {direction_target: angle, altitude_target: target.altitude, signal_strength: strength, target: target.id});
                                                 }
                                             }
                                         }
@@ -294,7 +339,7 @@ impl World {
                                     match target_data.kind {
                                         EntityKind::Boat => {
                                             weapon_data.armaments.iter().map(|a| a.entity_type.data().sub_kind).find(|&s| {
-                                                if s == EntitySubKind::Sam && !target.altitude.is_airborne(){
+                                                if s == EntitySubKind::Sam && target.altitude.air_band().map_or(true, |band| band > AirBand::SAM_CEILING) {
                                                     return false;
                                                 }
                                                 if s == EntitySubKind::Missile && target.altitude.is_submerged() {
@@ -327,10 +372,19 @@ impl World {
                                         _ => 1.75
                                     };
 
+                                    // Bombs are released such that they fall onto where the target will be.
+                                    let is_bombing = weapon_data.kind == EntityKind::Aircraft && bombing::is_ballistic(sub_kind);
+                                    let bomb_aim = is_bombing.then(|| bombing::release(weapon, target, sub_kind)).flatten();
+                                    let in_position = if is_bombing {
+                                        bomb_aim.is_some()
+                                    } else {
+                                        weapon.collides_with(target, drop_time + weapon.hash() * 0.25)
+                                    };
+
                                     // Uses aircraft lifespan as weapon consumption.
                                     // Don't use future collision based firing for rocket torpedoes.
-                                    if rocket_torpedo_sensed || (!is_rocket_torpedo && weapon.ticks > Ticks::from_secs(3.0 * amount as f32) && weapon.collides_with(target, drop_time + weapon.hash() * 0.25)) {
-                                        mutate(weapon, Mutation::FireAll(sub_kind));
+                                    if rocket_torpedo_sensed || (!is_rocket_torpedo && weapon.ticks > Ticks::from_secs(3.0 * amount as f32) && in_position) {
+                                        mutate(weapon, Mutation::FireAll(sub_kind, bomb_aim));
 
                                         if weapon_data.sub_kind == EntitySubKind::RocketTorpedo {
                                             // ASROC expires when dropping torpedo.
@@ -387,6 +441,7 @@ impl World {
                             EntityType::Barrel => 6 + 6 * is_tanker as u32,
                             EntityType::Coin => 20,
                             EntityType::Crate => 6,
+                            EntityType::AmmoCrate => 2,
                             EntityType::Scrap => 6, 
                             _ => 0,
                         };
@@ -408,6 +463,9 @@ impl World {
                                 mutate(boats[0], Mutation::Repair(Ticks::from_secs(1.5)));
                             }
                             mutate(boats[0], Mutation::Reload(collectibles[0].data().reload));
+                            if collectibles[0].entity_type == EntityType::AmmoCrate {
+                                mutate(boats[0], Mutation::Resupply(Ammo::CRATE_RESUPPLY));
+                            }
                         }
                     } else if boats.len() == 2 {
                         /*
@@ -418,18 +476,10 @@ impl World {
                             - Low health boats still do damage, hence scale health percent
                         */
 
-                        let base_damage = if friendly {
+                        let base_damage = if friendly && !self.damage_rules.friendly_ram() {
                             Ticks::ZERO
                         } else {
-                            // Boats with more health have more structural integrity, and therefore
-                            // deal more damage during a collision.
-                            fn damage_contribution(boat: &Entity) -> Ticks {
-                                let damage = boat.ticks;
-                                let max_health = boat.data().max_health();
-                                max_health - damage * 0.5
-                            }
-
-                            damage_contribution(entity).min(damage_contribution(other_entity)) * delta / Ticks::FREQUENCY_HZ
+                            ram_damage_contribution(entity).min(ram_damage_contribution(other_entity)) * delta / Ticks::FREQUENCY_HZ * self.damage_rules.ram_damage
                         };
 
                         // Process both boats (relative to the other boat).
@@ -497,14 +547,22 @@ impl World {
 
                             mutate(boat, Mutation::CollidedWithBoat{other_player: Arc::clone(other_boat.player.as_ref().unwrap()), damage, ram: other_data.ram_damage > 1.0, impulse});
                         }
-                    } else if boats.len() == 1 && weapons.len() == 1 && !friendly {
+                    } else if boats.len() == 1 && weapons.len() == 1 && (!friendly || self.damage_rules.friendly_hit(weapons[0], boats[0]) != FriendlyFire::Off) {
                         let boat_data = boats[0].data();
                         let weapon_data = weapons[0].data();
 
+                        // Fast weapons hit where their path comes closest to the boat, if at all.
+                        let swept = weapons[0].swept_impact(boats[0], delta_seconds);
+                        let (time_of_impact, weapon_position) = match swept {
+                            Some(Some((t, closest))) => (Some(t), closest),
+                            Some(None) => continue,
+                            None => (None, weapons[0].transform.position),
+                        };
+
                         let d2 = boats[0]
                             .transform
                             .position
-                            .distance_squared(weapons[0].transform.position);
+                            .distance_squared(weapon_position);
                         let r2 = boat_data.radius.powi(2);
 
                         let damage_resistance = boat_data.resistance_to_subkind(weapon_data.sub_kind) * boats[0].extension().spawn_protection();
@@ -513,19 +571,40 @@ impl World {
                             weapon_data.damage * collision_multiplier(d2, r2, boat_data.sub_kind == EntitySubKind::Submarine) * damage_resistance,
                         );
 
-                        if weapon_data.sub_kind == EntitySubKind::Sam && !boats[0].altitude.is_airborne() {
+                        if weapon_data.sub_kind == EntitySubKind::Sam && boats[0].altitude.air_band().map_or(true, |band| band > AirBand::SAM_CEILING) {
                             damage = ticks::from_damage(0.0);
                         }
                         
-                        mutate(
-                            boats[0],
-                            Mutation::HitBy(
-                                Arc::clone(weapons[0].player.as_ref().unwrap()),
-                                weapons[0].entity_type,
-                                damage,
-                            ),
+                        let hit = Mutation::HitBy(
+                            Arc::clone(weapons[0].player.as_ref().unwrap()),
+                            weapons[0].entity_type,
+                            damage,
                         );
-                        debug_remove!(weapons[0], "hit");
+
+                        // Reflected friendly fire hits the attacker's boat instead, if any.
+                        let target_index = if friendly && self.damage_rules.friendly_hit(weapons[0], boats[0]) == FriendlyFire::Reflected {
+                            weapons[0].borrow_player().data.status.get_entity_index()
+                        } else {
+                            Some(get_index(boats[0]))
+                        };
+
+                        if let Some(time_of_impact) = time_of_impact {
+                            if let Some(target_index) = target_index {
+                                swept_hits.lock().unwrap().push((
+                                    get_index(weapons[0]),
+                                    time_of_impact,
+                                    target_index,
+                                    hit,
+                                ));
+                            } else {
+                                debug_remove!(weapons[0], "hit");
+                            }
+                        } else {
+                            if let Some(target_index) = target_index {
+                                mutations.lock().unwrap().push((target_index, hit));
+                            }
+                            debug_remove!(weapons[0], "hit");
+                        }
                     } else if boats.len() == 1 && obstacles.len() == 1 {
                         let pos_diff = (boats[0].transform.position - obstacles[0].transform.position).normalize_or_zero();
 
@@ -575,6 +654,16 @@ impl World {
 
         let mut mutations = mutations.into_inner().unwrap();
 
+        // Each fast weapon only hits the first boat along its path.
+        let mut swept_hits = swept_hits.into_inner().unwrap();
+        swept_hits.sort_unstable_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.total_cmp(&b.1)));
+        swept_hits.dedup_by_key(|(weapon_index, ..)| *weapon_index);
+        for (weapon_index, _, boat_index, hit) in swept_hits.drain(..) {
+            mutations.push((boat_index, hit));
+            mutations.push((weapon_index, Mutation::Remove(DeathReason::Unknown)));
+        }
+        self.scratch.swept_hits = swept_hits;
+
         // Sort by reverse EntityIndex while prioritizing Mutation ordering.
         mutations.maybe_par_sort_unstable_by(|a, b| {
             b.0.cmp(&a.0).then_with(|| {
@@ -588,7 +677,7 @@ impl World {
 
         // Apply mutations (already reversed).
         let mut skip = None;
-        let mut iter = mutations.into_iter().peekable();
+        let mut iter = mutations.drain(..).peekable();
         while let Some((index, mutation)) = iter.next() {
             let last_of_mutation_type = iter
                 .peek()
@@ -602,9 +691,19 @@ impl World {
                 skip = Some(index);
             }
         }
+        drop(iter);
+        self.scratch.mutations = mutations;
     }
 }
 
+/// Boats with more health have more structural integrity, and therefore deal more damage during a
+/// collision.
+fn ram_damage_contribution(boat: &Entity) -> Ticks {
+    let damage = boat.ticks;
+    let max_health = boat.data().max_health();
+    max_health - damage * 0.5
+}
+
 /// Computes multiplier for damage such that hits closer to center of boat do more damage.
 /// Graph comparing old system (red) to new system (sub yellow, boat red): https://www.desmos.com/calculator/crwtc3u4f3
 fn collision_multiplier(d2: f32, r2: f32, is_sub: bool) -> f32 {
@@ -619,15 +718,22 @@ fn collision_multiplier(d2: f32, r2: f32, is_sub: bool) -> f32 {
 mod tests {
     use crate::entity::Entity;
     use crate::world::World;
-    use common::entity::EntityType;
+    use crate::world_physics_radius::{
+        collision_multiplier, ram_damage_contribution, LOOT_AUTO_COLLECT_DISTANCE,
+    };
+    use common::entity::{EntityKind, EntityType};
+    use common::test_util;
     use common::ticks::Ticks;
+    use common::velocity::Velocity;
+    use glam::Vec2;
+    use proptest::prelude::*;
 
     #[test]
     fn test_minimum_scan_radius() {
         let mut minimum_scan_radii: Vec<_> = EntityType::iter()
             .map(|entity_type| {
-                let entity = Entity::new(entity_type, None);
-                let r = World::minimum_scan_radius(&entity, Ticks::ONE.to_secs());
+                let r =
+                    World::minimum_scan_radius(entity_type, Velocity::ZERO, Ticks::ONE.to_secs());
 
                 (entity_type, r)
             })
@@ -636,4 +742,61 @@ mod tests {
 
         println!("{:?}", minimum_scan_radii);
     }
+
+    #[test]
+    fn auto_collect() {
+        for boat_type in EntityType::iter().filter(|t| t.data().kind == EntityKind::Boat) {
+            let boat = Entity::new(boat_type, None);
+            let data = boat_type.data();
+            let mut barrel = Entity::new(EntityType::Barrel, None);
+
+            // Alongside the hull.
+            barrel.transform.position = Vec2::new(0.0, data.width * 0.5 + 5.0);
+            assert!(World::within_auto_collect(&boat, &barrel), "{:?}", boat_type);
+
+            // Out of reach, but still attracted.
+            barrel.transform.position = Vec2::new(0.0, data.width + LOOT_AUTO_COLLECT_DISTANCE + 5.0);
+            assert!(!World::within_auto_collect(&boat, &barrel), "{:?}", boat_type);
+            assert!(
+                barrel.transform.position.length() < World::loot_magnetism(data).0,
+                "{:?}",
+                boat_type
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn collision_multiplier_is_positive(
+            d2 in 0.0..1e6f32,
+            r2 in 1.0..1e6f32,
+            is_sub: bool,
+        ) {
+            let multiplier = collision_multiplier(d2, r2, is_sub);
+            prop_assert!(multiplier > 0.0 && multiplier <= 1.0, "{}", multiplier);
+        }
+
+        #[test]
+        fn weapon_damage_is_non_negative(
+            boat_type in test_util::entity_type_of_kind(EntityKind::Boat),
+            weapon_type in test_util::entity_type_of_kind(EntityKind::Weapon),
+        ) {
+            let resistance = boat_type.data().resistance_to_subkind(weapon_type.data().sub_kind);
+            prop_assert!((0.0..=1.0).contains(&resistance), "{}", resistance);
+            prop_assert!(weapon_type.data().damage >= 0.0);
+        }
+
+        #[test]
+        fn ram_damage_is_non_negative(
+            (boat_type, damage) in test_util::entity_type_of_kind(EntityKind::Boat)
+                .prop_flat_map(|t| (Just(t), test_util::damage(t))),
+        ) {
+            let mut boat = Entity::new(boat_type, None);
+            boat.ticks = damage;
+            let contribution = ram_damage_contribution(&boat);
+            // Even nearly sunk boats contribute damage.
+            prop_assert!(contribution * 2.0 >= boat_type.data().max_health());
+            prop_assert!(contribution <= boat_type.data().max_health());
+        }
+    }
 }
@@ -0,0 +1,143 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::world::World;
+use common::entity::{EntityKind, EntitySubKind, EntityType};
+use common::ticks::Ticks;
+use common_util::range::gen_radius;
+use glam::Vec2;
+use rand::seq::IteratorRandom;
+use rand::{thread_rng, Rng};
+
+/// A squadron of NPC pirates, led by an Indiaman, that is waiting for bots to crew it.
+#[derive(Debug)]
+pub struct PirateRaid {
+    /// Where the squadron spawns.
+    pub position: Vec2,
+    /// Whether the leader is yet to be crewed.
+    leader: bool,
+    /// Number of escorts yet to be crewed.
+    escorts: u8,
+}
+
+impl PirateRaid {
+    pub const LEADER: EntityType = EntityType::Indiaman;
+    const ESCORTS: u8 = 2;
+    /// Time between raids.
+    pub const INTERVAL: Ticks = Ticks::from_whole_secs(5 * 60);
+    /// Number of random positions considered for each raid.
+    const CANDIDATES: usize = 8;
+    /// Radius around a candidate in which boats make it less quiet.
+    const QUIET_RANGE: f32 = 1500.0;
+    /// Radius around a candidate in which there must be a real player to hunt.
+    const HUNT_RANGE: f32 = 3000.0;
+    /// Radius around the raid's position that its boats spawn within.
+    pub const SPREAD: f32 = 200.0;
+    /// Times over that a sunk pirate's loot is rolled.
+    pub const LOOT_ROLLS: usize = 3;
+
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+            leader: true,
+            escorts: Self::ESCORTS,
+        }
+    }
+
+    /// Takes the next vacant role in the squadron, returning the entity type to spawn as, or
+    /// [`None`] if the squadron is fully crewed.
+    pub fn crew(&mut self, rng: &mut impl Rng) -> Option<EntityType> {
+        if self.leader {
+            self.leader = false;
+            Some(Self::LEADER)
+        } else if self.escorts > 0 {
+            self.escorts -= 1;
+            Self::escort_types().choose(rng)
+        } else {
+            None
+        }
+    }
+
+    /// Whether every role in the squadron has been taken.
+    pub fn is_crewed(&self) -> bool {
+        !self.leader && self.escorts == 0
+    }
+
+    /// Entity types that may escort the leader.
+    fn escort_types() -> impl Iterator<Item = EntityType> + IteratorRandom {
+        EntityType::iter().filter(|t| {
+            let data = t.data();
+            data.kind == EntityKind::Boat
+                && (2..=Self::LEADER.data().level).contains(&data.level)
+                && matches!(
+                    data.sub_kind,
+                    EntitySubKind::Corvette | EntitySubKind::Destroyer | EntitySubKind::Mtb
+                )
+        })
+    }
+
+    /// Whether boats of the given sub kind carry cargo, and are therefore hunted by pirates.
+    pub fn is_cargo(sub_kind: EntitySubKind) -> bool {
+        matches!(
+            sub_kind,
+            EntitySubKind::Dredger | EntitySubKind::Passenger | EntitySubKind::Tanker
+        )
+    }
+}
+
+impl World {
+    /// Periodically plans a pirate raid in a quiet region of the world that a real player is
+    /// near enough to be hunted. Replaces any raid that bots didn't finish crewing.
+    pub fn update_pirates(&mut self, delta: Ticks) {
+        self.pirate_cooldown = self.pirate_cooldown.saturating_sub(delta);
+        if self.pirate_cooldown != Ticks::ZERO {
+            return;
+        }
+        self.pirate_cooldown = PirateRaid::INTERVAL;
+
+        let mut rng = thread_rng();
+        let position = (0..PirateRaid::CANDIDATES)
+            .map(|_| gen_radius(&mut rng, 0.8 * self.radius))
+            .filter(|&position| {
+                self.entities
+                    .iter_radius(position, PirateRaid::HUNT_RANGE)
+                    .any(|(_, entity)| {
+                        entity.is_boat() && !entity.borrow_player().player_id.is_bot()
+                    })
+            })
+            .min_by_key(|&position| {
+                self.entities
+                    .iter_radius(position, PirateRaid::QUIET_RANGE)
+                    .filter(|(_, entity)| entity.is_boat())
+                    .count()
+            });
+
+        self.pirate_raid = position.map(PirateRaid::new);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world_pirates::PirateRaid;
+    use common::entity::EntityKind;
+    use glam::Vec2;
+    use rand::thread_rng;
+
+    #[test]
+    fn crew() {
+        let mut rng = thread_rng();
+        let mut raid = PirateRaid::new(Vec2::ZERO);
+        assert!(!raid.is_crewed());
+        assert_eq!(raid.crew(&mut rng), Some(PirateRaid::LEADER));
+
+        for _ in 0..PirateRaid::ESCORTS {
+            let escort = raid.crew(&mut rng).unwrap();
+            let data = escort.data();
+            assert_eq!(data.kind, EntityKind::Boat);
+            assert!(data.level <= PirateRaid::LEADER.data().level);
+        }
+
+        assert!(raid.is_crewed());
+        assert_eq!(raid.crew(&mut rng), None);
+    }
+}
@@ -0,0 +1,152 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entities::EntityIndex;
+use crate::entity::Entity;
+use crate::world::World;
+use common::death_reason::DeathReason;
+use common::entity::{EntityData, EntityId, EntitySubKind, EntityType};
+use common::intercept::Intercept;
+use common::ticks::Ticks;
+use maybe_parallel_iterator::IntoMaybeParallelIterator;
+use rand::{thread_rng, Rng};
+
+/// Automatic defenses that shoot down inbound weapons.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PointDefense {
+    /// Close-in weapon systems, which shoot down missiles, rockets, and glide bombs.
+    Ciws,
+    /// Anti-torpedo decoys, which destroy torpedoes.
+    AntiTorpedo,
+}
+
+impl PointDefense {
+    /// Returns the point defense provided by an entity type, whether a turret or a decoy.
+    pub fn of(entity_type: EntityType) -> Option<Self> {
+        match entity_type {
+            EntityType::Type730 | EntityType::_2M3M | EntityType::Mark49 => Some(Self::Ciws),
+            EntityType::Brosok => Some(Self::AntiTorpedo),
+            _ => None,
+        }
+    }
+
+    /// Range within which weapons are engaged.
+    fn range(self) -> f32 {
+        match self {
+            Self::Ciws => 300.0,
+            Self::AntiTorpedo => 60.0,
+        }
+    }
+
+    /// Chance, per second, that one point defense shoots down the weapon it engages.
+    fn hit_chance(self) -> f32 {
+        match self {
+            Self::Ciws => 0.35,
+            Self::AntiTorpedo => 0.5,
+        }
+    }
+
+    /// Whether the point defense may engage a weapon.
+    fn engages(self, data: &EntityData) -> bool {
+        match self {
+            Self::Ciws => matches!(
+                data.sub_kind,
+                EntitySubKind::Missile | EntitySubKind::Rocket | EntitySubKind::GlideBomb
+            ),
+            Self::AntiTorpedo => data.sub_kind == EntitySubKind::Torpedo,
+        }
+    }
+
+    /// Returns the point defense of an entity, and how many of it there are, if any.
+    fn of_entity(entity: &Entity) -> Option<(Self, usize)> {
+        if entity.is_boat() {
+            // Turrets can't fire under water.
+            if entity.altitude.is_submerged() {
+                return None;
+            }
+            let ciws = entity
+                .data()
+                .turrets
+                .iter()
+                .filter(|t| t.entity_type.and_then(Self::of) == Some(Self::Ciws))
+                .count();
+            (ciws > 0).then_some((Self::Ciws, ciws))
+        } else {
+            Self::of(entity.entity_type).map(|defense| (defense, 1))
+        }
+    }
+}
+
+impl World {
+    /// Shoots down enemy weapons that come within range of point defenses, recording the
+    /// intercepts for clients.
+    pub fn update_point_defense(&mut self, delta: Ticks) {
+        self.intercepts.clear();
+        let secs = delta.to_secs();
+
+        let mut intercepts: Vec<(EntityIndex, EntityId, Intercept)> = self
+            .entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .filter_map(|(_, defender)| {
+                let (defense, count) = PointDefense::of_entity(defender)?;
+                let position = defender.transform.position;
+
+                // Engage the closest inbound weapon.
+                let (index, weapon) = self
+                    .entities
+                    .iter_radius(position, defense.range())
+                    .filter(|(_, weapon)| {
+                        defense.engages(weapon.data()) && !weapon.is_friendly(defender)
+                    })
+                    .min_by(|(_, a), (_, b)| {
+                        let a = a.transform.position.distance_squared(position);
+                        let b = b.transform.position.distance_squared(position);
+                        a.total_cmp(&b)
+                    })?;
+
+                let chance = defense.hit_chance() * count as f32 * secs;
+                thread_rng()
+                    .gen_bool((chance as f64).clamp(0.0, 1.0))
+                    .then(|| {
+                        (
+                            index,
+                            defender.id,
+                            Intercept {
+                                source: position,
+                                target: weapon.transform.position,
+                            },
+                        )
+                    })
+            })
+            .collect();
+
+        // Sorted in reverse to remove correctly.
+        intercepts.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+        intercepts.dedup_by_key(|(index, _, _)| *index);
+        for (index, defender_id, intercept) in intercepts {
+            self.remove(index, DeathReason::Unknown);
+            self.intercepts.push((defender_id, intercept));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world_point_defense::PointDefense;
+    use common::entity::EntityType;
+
+    #[test]
+    fn engages() {
+        let ciws = PointDefense::of(EntityType::Type730).unwrap();
+        assert_eq!(ciws, PointDefense::Ciws);
+        assert!(ciws.engages(EntityType::Exocet.data()));
+        assert!(!ciws.engages(EntityType::Mark18.data()));
+
+        let anti_torpedo = PointDefense::of(EntityType::Brosok).unwrap();
+        assert!(anti_torpedo.engages(EntityType::Mark18.data()));
+        assert!(!anti_torpedo.engages(EntityType::Exocet.data()));
+
+        assert_eq!(PointDefense::of(EntityType::Mark18), None);
+    }
+}
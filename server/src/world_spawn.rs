@@ -22,6 +22,8 @@ impl World {
     // pub const BOAT_VISUAL_OVERLAP: f32 = 0.32;
     /// Target density of crates (per square meter).
     const CRATE_DENSITY: f32 = 1.0 / 30000.0;
+    /// Fraction of crates that are ammo crates.
+    const AMMO_CRATE_CHANCE: f64 = 0.1;
     /// Target density of obstacles (per square meter).
     const OBSTACLE_DENSITY: f32 = 1.0 / 5000000.0;
 
@@ -265,12 +267,19 @@ impl World {
 
     /// Spawn basic entities (crates, oil platforms) to maintain their densities.
     pub fn spawn_statics(&mut self, ticks: Ticks) {
-        let crate_count = self.arena.count(EntityType::Crate);
+        let crate_count =
+            self.arena.count(EntityType::Crate) + self.arena.count(EntityType::AmmoCrate);
         let platform_count =
             self.arena.count(EntityType::OilPlatform) + self.arena.count(EntityType::Hq) + self.arena.count(EntityType::SuperOilPlatform);
 
         self.spawn_static_amount(
-            |_| Some(EntityType::Crate),
+            |_| {
+                Some(if thread_rng().gen_bool(Self::AMMO_CRATE_CHANCE) {
+                    EntityType::AmmoCrate
+                } else {
+                    EntityType::Crate
+                })
+            },
             crate_count,
             self.target_count(Self::CRATE_DENSITY),
             ticks.0 as usize * 150,
@@ -353,3 +362,81 @@ impl World {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::Entity;
+    use crate::world::World;
+    use crate::Server;
+    use common::death_reason::DeathReason;
+    use common::entity::{EntityKind, EntityType};
+    use common::test_util;
+    use common::ticks::Ticks;
+    use core_protocol::id::PlayerId;
+    use game_server::player::{PlayerData, PlayerTuple};
+    use maybe_parallel_iterator::IntoMaybeParallelIterator;
+    use proptest::prelude::*;
+    use std::sync::Arc;
+
+    /// Types that spawn_here_or_nearby is used for.
+    fn spawnable_type() -> impl Strategy<Value = EntityType> {
+        prop_oneof![
+            test_util::entity_type_of_kind(EntityKind::Boat),
+            test_util::entity_type_of_kind(EntityKind::Collectible),
+            test_util::entity_type_of_kind(EntityKind::Obstacle),
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn spawn_satisfies_can_spawn(
+            entity_types in prop::collection::vec(spawnable_type(), 1..20),
+            positions in prop::collection::vec(test_util::position(2000.0), 20),
+            initial_radius in 0.0..1000f32,
+        ) {
+            crate::noise::init();
+            let mut world = World::new(2000.0);
+            world.spawn_statics(Ticks::from_whole_secs(10));
+
+            for (i, (entity_type, position)) in entity_types.into_iter().zip(positions).enumerate() {
+                let player = (entity_type.data().kind == EntityKind::Boat).then(|| {
+                    Arc::new(PlayerTuple::<Server>::new(PlayerData::new(
+                        PlayerId::nth_bot(i).unwrap(),
+                        None,
+                    )))
+                });
+                let mut entity = Entity::new(entity_type, player);
+                entity.transform.position = position;
+
+                let ids: Vec<_> = world
+                    .entities
+                    .par_iter()
+                    .into_maybe_parallel_iter()
+                    .map(|(_, e)| e.id)
+                    .collect();
+                if !world.spawn_here_or_nearby(entity, initial_radius, None) {
+                    continue;
+                }
+                let spawned: Vec<_> = world
+                    .entities
+                    .par_iter()
+                    .into_maybe_parallel_iter()
+                    .filter_map(|(index, e)| (!ids.contains(&e.id)).then_some(index))
+                    .collect();
+                prop_assert_eq!(spawned.len(), 1);
+
+                // Check the spawned entity against everything else (it would collide with itself).
+                let entity = world.entities.remove_internal(spawned[0], DeathReason::Unknown);
+                prop_assert!(
+                    world.can_spawn(&entity, 1.0, world.radius),
+                    "{:?} spawned at {:?}",
+                    entity.entity_type,
+                    entity.transform.position
+                );
+                world.add(entity);
+            }
+        }
+    }
+}
@@ -0,0 +1,313 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entities::EntityIndex;
+use crate::world::World;
+use common::ammo::Ammo;
+use common::beam::Beam;
+use common::death_reason::DeathReason;
+use common::entity::{EntityId, EntityKind, EntitySubKind, EntityType};
+use common::ticks::Ticks;
+use glam::Vec2;
+use maybe_parallel_iterator::IntoMaybeParallelIterator;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Difficulty state of one HQ or oil platform, whose defenses scale with the levels of boats
+/// near it.
+#[derive(Debug, Default)]
+pub struct StructureDefense {
+    /// Smoothed average level of nearby boats, or zero if there are none.
+    pub difficulty: f32,
+    /// Time until the defenses may fire again.
+    reload: Ticks,
+    /// Time until another patrol may be recruited.
+    patrol_cooldown: Ticks,
+    /// Time until docked boats are resupplied again.
+    resupply: Ticks,
+}
+
+impl StructureDefense {
+    /// Range of the defenses, and in which boats contribute to difficulty.
+    const RANGE: f32 = 600.0;
+    /// Below this difficulty, structures are defenseless, so low-level areas are unaffected.
+    const MIN_DIFFICULTY: f32 = 4.0;
+    /// At or above this difficulty, structures recruit patrols.
+    const PATROL_DIFFICULTY: f32 = 8.0;
+    /// Seconds over which difficulty adjusts to the boats nearby.
+    const SMOOTHING_SECS: f32 = 10.0;
+    const RELOAD: Ticks = Ticks::from_whole_secs(3);
+    const PATROL_COOLDOWN: Ticks = Ticks::from_whole_secs(120);
+    /// Bots within this distance of a structure may be recruited to patrol it.
+    const PATROL_RECRUIT_RANGE: f32 = 5000.0;
+    /// How long a bot patrols a structure, once recruited.
+    const PATROL_DURATION: Duration = Duration::from_secs(240);
+    /// Boats within this distance of a structure's hull, and slower than `Self::DOCK_SPEED`,
+    /// are docked.
+    const DOCK_RANGE: f32 = 100.0;
+    /// Meters per second.
+    const DOCK_SPEED: f32 = 2.0;
+    const RESUPPLY_PERIOD: Ticks = Ticks::from_whole_secs(2);
+
+    /// Whether the defenses fire at all.
+    pub fn armed(&self) -> bool {
+        self.difficulty >= Self::MIN_DIFFICULTY
+    }
+
+    /// Fraction of a boat's health that each shot takes, growing with difficulty.
+    pub fn damage_fraction(&self) -> f32 {
+        if self.armed() {
+            0.01 * (self.difficulty - Self::MIN_DIFFICULTY + 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Moves difficulty towards the average of `levels` over time.
+    fn update_difficulty(&mut self, levels: &[u8], delta: Ticks) {
+        let target = if levels.is_empty() {
+            0.0
+        } else {
+            levels.iter().map(|&l| l as f32).sum::<f32>() / levels.len() as f32
+        };
+        let t = (delta.to_secs() / Self::SMOOTHING_SECS).min(1.0);
+        self.difficulty += (target - self.difficulty) * t;
+    }
+}
+
+impl World {
+    /// Updates the difficulty of HQs and oil platforms, fires their defenses at high-level boats,
+    /// recruits nearby bots to patrol them in high-level areas, and resupplies boats docked at
+    /// them.
+    pub fn update_structures(&mut self, delta: Ticks) {
+        let structures: Vec<(EntityId, EntityType, Vec2)> = self
+            .entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .filter_map(|(_, entity)| {
+                (entity.data().sub_kind == EntitySubKind::Structure).then_some((
+                    entity.id,
+                    entity.entity_type,
+                    entity.transform.position,
+                ))
+            })
+            .collect();
+
+        let mut hits: Vec<(EntityIndex, EntityType, f32)> = Vec::new();
+        let mut resupplies: Vec<EntityIndex> = Vec::new();
+        // Boats docked at each structure, and how difficult its defenses are.
+        let mut docks: Vec<(EntityIndex, Vec2, f32)> = Vec::new();
+
+        for &(id, entity_type, position) in &structures {
+            let dock_range = entity_type.data().radius + StructureDefense::DOCK_RANGE;
+            let boats: Vec<(EntityIndex, Vec2, u8, bool)> = self
+                .entities
+                .iter_radius(position, StructureDefense::RANGE)
+                .filter(|(_, entity)| entity.data().kind == EntityKind::Boat)
+                .map(|(index, entity)| {
+                    let docked = entity.transform.velocity.abs().to_mps()
+                        < StructureDefense::DOCK_SPEED
+                        && entity.transform.position.distance_squared(position)
+                            < dock_range.powi(2);
+                    (
+                        index,
+                        entity.transform.position,
+                        entity.data().level,
+                        docked,
+                    )
+                })
+                .collect();
+            let levels: Vec<u8> = boats.iter().map(|&(_, _, level, _)| level).collect();
+
+            let defense = self.structures.entry(id).or_default();
+            defense.update_difficulty(&levels, delta);
+            defense.reload = defense.reload.saturating_sub(delta);
+            defense.patrol_cooldown = defense.patrol_cooldown.saturating_sub(delta);
+            defense.resupply = defense.resupply.saturating_sub(delta);
+
+            if defense.armed() && defense.reload == Ticks::ZERO {
+                // Leave low-level boats alone.
+                let target = boats
+                    .iter()
+                    .filter(|&&(_, _, level, _)| level as f32 >= StructureDefense::MIN_DIFFICULTY)
+                    .max_by_key(|&&(_, _, level, _)| level);
+                if let Some(&(index, target_position, _, _)) = target {
+                    hits.push((index, entity_type, defense.damage_fraction()));
+                    // So that clients see where the damage came from.
+                    self.beams.push(Beam {
+                        source: position,
+                        target: target_position,
+                    });
+                    defense.reload = StructureDefense::RELOAD;
+                }
+            }
+
+            if defense.difficulty >= StructureDefense::PATROL_DIFFICULTY
+                && defense.patrol_cooldown == Ticks::ZERO
+            {
+                // The closest bot that isn't busy (with a raid or another patrol).
+                let now = Instant::now();
+                let recruit = self
+                    .entities
+                    .iter_radius(position, StructureDefense::PATROL_RECRUIT_RANGE)
+                    .filter(|(_, entity)| {
+                        entity.is_boat() && {
+                            let player = entity.borrow_player();
+                            player.is_bot()
+                                && !player.data.pirate
+                                && player.data.patrol.map_or(true, |(_, until)| until <= now)
+                        }
+                    })
+                    .min_by(|(_, a), (_, b)| {
+                        let a = a.transform.position.distance_squared(position);
+                        let b = b.transform.position.distance_squared(position);
+                        a.total_cmp(&b)
+                    })
+                    .map(|(index, _)| index);
+                if let Some(index) = recruit {
+                    self.entities[index].borrow_player_mut().data.patrol =
+                        Some((position, now + StructureDefense::PATROL_DURATION));
+                    defense.patrol_cooldown = StructureDefense::PATROL_COOLDOWN;
+                }
+            }
+
+            if defense.resupply == Ticks::ZERO {
+                // Only boats that the defenses leave alone may dock.
+                let start = resupplies.len();
+                resupplies.extend(
+                    boats
+                        .iter()
+                        .filter(|&&(_, _, level, docked)| {
+                            docked
+                                && (!defense.armed()
+                                    || (level as f32) < StructureDefense::MIN_DIFFICULTY)
+                        })
+                        .map(|&(index, _, _, _)| index),
+                );
+                docks.extend(
+                    resupplies[start..]
+                        .iter()
+                        .map(|&index| (index, position, defense.difficulty)),
+                );
+                defense.resupply = StructureDefense::RESUPPLY_PERIOD;
+            }
+        }
+
+        // Before removing any boats, which would invalidate indices.
+        resupplies.sort_unstable();
+        resupplies.dedup();
+        for index in resupplies {
+            self.entities[index].resupply(Ammo::DOCK_RESUPPLY);
+        }
+        let ports: Vec<Vec2> = structures
+            .iter()
+            .map(|&(_, _, position)| position)
+            .collect();
+        for (index, position, difficulty) in docks {
+            self.dock_cargo(index, position, difficulty, &ports);
+        }
+
+        // Forget structures that expired.
+        let ids: HashSet<EntityId> = structures.iter().map(|&(id, _, _)| id).collect();
+        self.structures.retain(|id, _| ids.contains(id));
+
+        // Sorted in reverse to remove correctly.
+        hits.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+        hits.dedup_by_key(|(index, _, _)| *index);
+        for (index, entity_type, fraction) in hits {
+            let boat = &mut self.entities[index];
+            let damage = boat.absorb((boat.data().max_health() * fraction).max(Ticks::ONE));
+            if boat.damage(damage) {
+                self.remove(index, DeathReason::Obstacle(entity_type));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::Entity;
+    use crate::world::World;
+    use crate::world_structures::StructureDefense;
+    use common::entity::EntityType;
+    use common::ticks::Ticks;
+    use glam::Vec2;
+
+    #[test]
+    fn difficulty() {
+        let mut defense = StructureDefense::default();
+        assert!(!defense.armed());
+
+        // Newbies don't arm structures.
+        for _ in 0..100 {
+            defense.update_difficulty(&[1, 2, 3], Ticks::from_whole_secs(1));
+        }
+        assert!(!defense.armed());
+
+        // High-level boats do, eventually.
+        defense.update_difficulty(&[10, 12], Ticks::from_whole_secs(1));
+        assert!(!defense.armed());
+        for _ in 0..100 {
+            defense.update_difficulty(&[10, 12], Ticks::from_whole_secs(1));
+        }
+        assert!(defense.armed());
+        let strong = defense.damage_fraction();
+
+        for _ in 0..100 {
+            defense.update_difficulty(&[6], Ticks::from_whole_secs(1));
+        }
+        assert!(defense.damage_fraction() < strong);
+
+        // Calms down once everyone leaves.
+        for _ in 0..100 {
+            defense.update_difficulty(&[], Ticks::from_whole_secs(1));
+        }
+        assert!(!defense.armed());
+    }
+
+    #[test]
+    fn defend_and_patrol() {
+        let mut world = World::test_empty(5000.0);
+        let position = Vec2::new(100.0, 0.0);
+        let mut platform = Entity::new(EntityType::OilPlatform, None);
+        platform.transform.position = position;
+        world.add(platform);
+        let id = world
+            .entities
+            .iter_radius(position, 1.0)
+            .find(|(_, entity)| entity.entity_type == EntityType::OilPlatform)
+            .unwrap()
+            .1
+            .id;
+        world.structures.entry(id).or_default().difficulty = 10.0;
+
+        let target_position = Vec2::new(400.0, 0.0);
+        let (player, _) = world.test_boat(EntityType::Yamato, u32::MAX, target_position);
+        let (pirate, _) = world.test_boat(EntityType::Zubr, 2, Vec2::new(1000.0, 0.0));
+        pirate.borrow_player_mut().data.pirate = true;
+        let (bot, _) = world.test_boat(EntityType::Zubr, 3, Vec2::new(2000.0, 0.0));
+        let (far_bot, _) = world.test_boat(EntityType::Zubr, 4, Vec2::new(3000.0, 0.0));
+
+        world.update_structures(Ticks::from_whole_secs(1));
+
+        // The shot is visible to clients.
+        assert_eq!(world.beams.len(), 1);
+        assert_eq!(world.beams[0].source, position);
+        assert_eq!(world.beams[0].target, target_position);
+
+        // The closest bot that isn't busy is recruited, and only it.
+        assert_eq!(
+            bot.borrow_player().data.patrol.map(|(post, _)| post),
+            Some(position)
+        );
+        assert!(player.borrow_player().data.patrol.is_none());
+        assert!(pirate.borrow_player().data.patrol.is_none());
+        assert!(far_bot.borrow_player().data.patrol.is_none());
+
+        // Not again until the cooldown expires.
+        bot.borrow_player_mut().data.patrol = None;
+        world.update_structures(Ticks::from_whole_secs(1));
+        assert!(bot.borrow_player().data.patrol.is_none());
+        assert!(far_bot.borrow_player().data.patrol.is_none());
+    }
+}
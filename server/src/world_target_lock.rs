@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entities::EntityIndex;
+use crate::entity::Entity;
+use crate::world::World;
+use common::entity::EntityId;
+use common::target_lock::TargetLock;
+use maybe_parallel_iterator::IntoMaybeParallelIterator;
+
+impl World {
+    /// Updates the target locks of boats (see [`LockTarget`][common::protocol::LockTarget]),
+    /// which are lost once the boat's sensors can no longer identify the target.
+    pub fn update_target_locks(&mut self) {
+        let locks: Vec<(EntityIndex, Option<TargetLock>)> = self
+            .entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .filter_map(|(index, boat)| {
+                if !boat.is_boat() {
+                    return None;
+                }
+                let id = boat.extension().target_lock?.id;
+                Some((index, self.track(boat, id)))
+            })
+            .collect();
+
+        for (index, lock) in locks {
+            self.entities[index].extension_mut().target_lock = lock;
+        }
+    }
+
+    /// Returns a lock on the entity with the given id, if it is an enemy boat that `boat`'s
+    /// sensors identify.
+    pub fn track(&self, boat: &Entity, id: EntityId) -> Option<TargetLock> {
+        let data = boat.data();
+        let range = data.sensors.max_range().max(data.radii().start);
+        let (_, target) = self
+            .entities
+            .iter_radius(boat.transform.position, range)
+            .find(|(_, entity)| entity.id == id)?;
+
+        (target.is_boat() && !target.is_friendly(boat) && Self::identifies(boat, target)).then(
+            || TargetLock {
+                id,
+                position: target.transform.position,
+                velocity: target.transform.direction.to_vec() * target.transform.velocity.to_mps(),
+            },
+        )
+    }
+}
@@ -1,17 +1,53 @@
 use crate::entities::EntityIndex;
 use crate::entity::Entity;
 use crate::world::World;
+use crate::Server;
 use common::altitude::Altitude;
-use common::entity::EntityKind;
+use common::entity::{EntityKind, EntityType};
+use common::terrain::Terrain;
 use common::world::ARCTIC;
+use core_protocol::id::PlayerId;
+use game_server::player::{PlayerData, PlayerTuple};
 use glam::Vec2;
 use image::{Rgba, RgbaImage};
 use imageproc::drawing::{draw_polygon_mut, Blend};
 use imageproc::point::Point;
 use maybe_parallel_iterator::IntoMaybeParallelIterator;
-use std::sync::Mutex;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
 
 impl World {
+    /// Creates a world without any terrain, for testing purposes.
+    pub fn test_empty(radius: f32) -> Self {
+        let mut world = Self::new(radius);
+        world.terrain = Terrain::new();
+        world
+    }
+
+    /// Adds a boat of type `entity_type` at `position`, for a new player with id `player_id`, for
+    /// testing purposes. Returns the player, and the index of the boat.
+    pub fn test_boat(
+        &mut self,
+        entity_type: EntityType,
+        player_id: u32,
+        position: Vec2,
+    ) -> (Arc<PlayerTuple<Server>>, EntityIndex) {
+        let player = Arc::new(PlayerTuple::new(PlayerData::new(
+            PlayerId(NonZeroU32::new(player_id).unwrap()),
+            None,
+        )));
+        let mut entity = Entity::new(entity_type, Some(Arc::clone(&player)));
+        entity.transform.position = position;
+        self.add(entity);
+        let index = player
+            .borrow_player()
+            .data
+            .status
+            .get_entity_index()
+            .unwrap();
+        (player, index)
+    }
+
     /// Creates a false-color CPU rendering of the world for testing purposes.
     pub fn test_render(&self, center: Vec2, radius: f32, resolution: u32) -> RgbaImage {
         let mut canvas = RgbaImage::new(resolution, resolution);
@@ -93,6 +129,8 @@ mod tests {
     use crate::protocol::AsCommandTrait;
     use crate::world::World;
     use crate::Server;
+    use common::cosmetic::Cosmetics;
+    use common::spawn_region::SpawnRegion;
     use common::entity::{EntityData, EntityType};
     use common::protocol::{Command, Spawn};
     use common::ticks::Ticks;
@@ -153,7 +191,11 @@ mod tests {
                 .filter(|t| t.can_spawn_as(score, bot) && t.data().level == level)
                 .choose(&mut rng)
                 .unwrap();
-            let spawn = Command::Spawn(Spawn { entity_type });
+            let spawn = Command::Spawn(Spawn {
+                entity_type,
+                cosmetics: Cosmetics::default(),
+                region: SpawnRegion::Fleet,
+            });
             const SPAWN_ATTEMPTS: usize = 25;
             for i in 0..=SPAWN_ATTEMPTS {
                 match spawn.as_command().apply(&mut world, player) {
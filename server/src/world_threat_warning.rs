@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entities::EntityIndex;
+use crate::entity::Entity;
+use crate::world::World;
+use common::angle::Angle;
+use common::entity::{EntityData, EntityId};
+use common::threat_warning::{ThreatKind, ThreatWarning};
+use maybe_parallel_iterator::IntoMaybeParallelIterator;
+use std::collections::HashMap;
+
+impl World {
+    /// Warns boats of weapons that newly threaten them (see [`ThreatWarning`]), once per weapon.
+    pub fn update_threat_warnings(&mut self) {
+        let threats: Vec<Vec<(EntityIndex, EntityId, ThreatWarning)>> = self
+            .entities
+            .par_iter()
+            .into_maybe_parallel_iter()
+            .filter_map(|(_, weapon)| {
+                let kind = ThreatKind::of(weapon.entity_type)?;
+                Some(self.threatened_boats(weapon, kind))
+            })
+            .collect();
+
+        let mut threats_by_boat: HashMap<EntityIndex, Vec<(EntityId, ThreatWarning)>> =
+            HashMap::new();
+        for (index, id, warning) in threats.into_iter().flatten() {
+            threats_by_boat
+                .entry(index)
+                .or_default()
+                .push((id, warning));
+        }
+
+        self.entities
+            .par_iter_mut()
+            .into_maybe_parallel_iter()
+            .for_each(|(index, boat)| {
+                if !boat.is_boat() {
+                    return;
+                }
+                let threats = threats_by_boat.get(&index).map_or(&[][..], Vec::as_slice);
+                let extension = boat.extension_mut();
+                extension.threat_warnings.clear();
+                extension
+                    .threats
+                    .retain(|id| threats.iter().any(|(threat_id, _)| threat_id == id));
+                for &(id, warning) in threats {
+                    // Threats beyond the limit are warned of in later updates.
+                    if !extension.threats.contains(&id)
+                        && extension.threat_warnings.len() < ThreatWarning::MAX_PER_UPDATE
+                    {
+                        extension.threat_warnings.push(warning);
+                        extension.threats.push(id);
+                    }
+                }
+            });
+    }
+
+    /// Returns the enemy boats that `weapon` threatens, along with warnings for them.
+    fn threatened_boats(
+        &self,
+        weapon: &Entity,
+        kind: ThreatKind,
+    ) -> Vec<(EntityIndex, EntityId, ThreatWarning)> {
+        let data = weapon.data();
+        let position = weapon.transform.position;
+        let speed = weapon.transform.velocity.to_mps();
+        let range = match kind {
+            ThreatKind::Missile => speed * ThreatWarning::MISSILE_SECONDS + EntityData::MAX_RADIUS,
+            ThreatKind::Torpedo => {
+                if !self.seeker_targets.contains_key(&weapon.id) {
+                    return Vec::new();
+                }
+                // Seekers home in on the nearest part of the keel.
+                data.sensors.max_range() + EntityData::MAX_RADIUS
+            }
+        };
+
+        self.entities
+            .iter_radius(position, range)
+            .filter(|(_, boat)| boat.is_boat() && !weapon.is_friendly(boat))
+            .filter(|(_, boat)| match kind {
+                ThreatKind::Missile => Self::missile_inbound(weapon, boat),
+                ThreatKind::Torpedo => self.seeker_targets.get(&weapon.id) == Some(&boat.id),
+            })
+            .map(|(index, boat)| {
+                let warning = ThreatWarning {
+                    kind,
+                    bearing: Angle::from(position - boat.transform.position),
+                };
+                (index, weapon.id, warning)
+            })
+            .collect()
+    }
+
+    /// Whether `missile` will hit `boat` within [`ThreatWarning::MISSILE_SECONDS`], if neither
+    /// changes course or speed.
+    fn missile_inbound(missile: &Entity, boat: &Entity) -> bool {
+        if !missile.altitude_overlapping(boat) {
+            return false;
+        }
+        let velocity = |entity: &Entity| {
+            entity.transform.direction.to_vec() * entity.transform.velocity.to_mps()
+        };
+        ThreatWarning::time_to_impact(
+            missile.transform.position - boat.transform.position,
+            velocity(missile) - velocity(boat),
+            boat.data().radius,
+        )
+        .map_or(false, |seconds| seconds <= ThreatWarning::MISSILE_SECONDS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::Entity;
+    use crate::world::World;
+    use common::entity::{EntityId, EntityType};
+    use common::threat_warning::{ThreatKind, ThreatWarning};
+    use glam::Vec2;
+    use std::sync::Arc;
+
+    #[test]
+    fn threat_warnings() {
+        let mut world = World::test_empty(2000.0);
+        let (_, index) = world.test_boat(EntityType::Zubr, u32::MAX, Vec2::ZERO);
+        let boat_id = world.entities[index].id;
+        let (attacker, _) = world.test_boat(EntityType::Zubr, 2, Vec2::new(1000.0, 0.0));
+
+        let torpedo_count = ThreatWarning::MAX_PER_UPDATE + 2;
+        for i in 0..torpedo_count {
+            let mut torpedo = Entity::new(EntityType::Mark48, Some(Arc::clone(&attacker)));
+            torpedo.transform.position = Vec2::new(500.0, i as f32 * 10.0);
+            world.add(torpedo);
+        }
+        let torpedoes: Vec<EntityId> = world
+            .entities
+            .iter_radius(Vec2::ZERO, 1000.0)
+            .filter(|(_, entity)| entity.entity_type == EntityType::Mark48)
+            .map(|(_, entity)| entity.id)
+            .collect();
+        assert_eq!(torpedoes.len(), torpedo_count);
+
+        let update = |world: &mut World, acquired: &[EntityId]| {
+            world.seeker_targets.clear();
+            for &id in acquired {
+                world.seeker_targets.insert(id, boat_id);
+            }
+            world.update_threat_warnings();
+            let warnings = world.entities[index].extension().threat_warnings.clone();
+            assert!(warnings.iter().all(|w| w.kind == ThreatKind::Torpedo));
+            warnings.len()
+        };
+
+        // Torpedoes that didn't acquire the boat aren't a threat.
+        assert_eq!(update(&mut world, &[]), 0);
+
+        // Warned of once.
+        assert_eq!(update(&mut world, &torpedoes[..1]), 1);
+        assert_eq!(update(&mut world, &torpedoes[..1]), 0);
+
+        // Warned of again, if reacquired.
+        assert_eq!(update(&mut world, &[]), 0);
+        assert_eq!(update(&mut world, &torpedoes[..1]), 1);
+
+        // Those beyond the limit are warned of later.
+        assert_eq!(
+            update(&mut world, &torpedoes),
+            ThreatWarning::MAX_PER_UPDATE
+        );
+        assert_eq!(update(&mut world, &torpedoes), 1);
+        assert_eq!(update(&mut world, &torpedoes), 0);
+    }
+}
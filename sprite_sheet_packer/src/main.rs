@@ -242,8 +242,12 @@ fn boat_pixels(boat: &EntityData) -> f32 {
 fn entity_sprite_params(entity_type: EntityType, width: u32) -> Image {
     // Other shells besides mark8 don't have their own sprites so they copy mark8.
     let mut file_name = entity_type.as_str();
-    if file_name.contains("MmR") {
+    if file_name.contains("MmR") || entity_type == EntityType::StarShell {
         file_name = "Mark8";
+    } else if entity_type == EntityType::BeamLaser {
+        file_name = "Blaster";
+    } else if entity_type == EntityType::AmmoCrate {
+        file_name = "Crate";
     }
 
     Image {